@@ -0,0 +1,30 @@
+-- Resumable uploads for legacy content
+
+CREATE TABLE IF NOT EXISTS legacy_content_upload_sessions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    owner_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    original_filename VARCHAR(255) NOT NULL,
+    content_type VARCHAR(100) NOT NULL,
+    description TEXT,
+    total_size BIGINT NOT NULL,
+    expected_hash VARCHAR(64) NOT NULL,
+    received_bytes BIGINT NOT NULL DEFAULT 0,
+    status VARCHAR(20) NOT NULL DEFAULT 'in_progress',
+    content_id UUID REFERENCES legacy_content(id) ON DELETE SET NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT check_upload_session_size
+        CHECK (total_size > 0 AND total_size <= 524288000),
+    CONSTRAINT check_upload_session_progress
+        CHECK (received_bytes >= 0 AND received_bytes <= total_size),
+    CONSTRAINT check_upload_session_status
+        CHECK (status IN ('in_progress', 'finalizing', 'completed', 'failed', 'aborted', 'expired'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_legacy_upload_sessions_owner
+    ON legacy_content_upload_sessions(owner_user_id, status);
+
+CREATE INDEX IF NOT EXISTS idx_legacy_upload_sessions_expiry
+    ON legacy_content_upload_sessions(expires_at)
+    WHERE status = 'in_progress';
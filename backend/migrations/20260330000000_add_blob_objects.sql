@@ -0,0 +1,26 @@
+-- Content-addressed blob registry for pluggable object storage
+
+CREATE TABLE IF NOT EXISTS blob_objects (
+    hash VARCHAR(64) PRIMARY KEY,
+    storage_key TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    ref_count INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT check_blob_ref_count CHECK (ref_count >= 0),
+    CONSTRAINT check_blob_size CHECK (size_bytes >= 0)
+);
+
+-- Encrypted document payloads move out of bytea into the blob store
+ALTER TABLE will_documents
+    ADD COLUMN encrypted_blob_hash VARCHAR(64) REFERENCES blob_objects(hash);
+
+ALTER TABLE document_backups
+    ADD COLUMN blob_hash VARCHAR(64) REFERENCES blob_objects(hash),
+    ALTER COLUMN encrypted_content DROP NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_will_documents_encrypted_blob
+    ON will_documents(encrypted_blob_hash);
+
+CREATE INDEX IF NOT EXISTS idx_document_backups_blob
+    ON document_backups(blob_hash);
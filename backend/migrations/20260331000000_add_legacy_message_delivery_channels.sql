@@ -0,0 +1,43 @@
+-- Multi-channel legacy message delivery with receipts and claim links
+
+ALTER TABLE legacy_messages
+    ADD COLUMN delivery_channel VARCHAR(20) NOT NULL DEFAULT 'email',
+    ADD COLUMN delivery_attempts INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN last_attempt_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN next_attempt_at TIMESTAMP WITH TIME ZONE,
+    ADD CONSTRAINT check_legacy_message_channel
+        CHECK (delivery_channel IN ('email', 'sms', 'claim_link'));
+
+-- Deliveries no longer carry plaintext; scrub what was stored before.
+ALTER TABLE legacy_message_deliveries
+    DROP COLUMN decrypted_payload,
+    ALTER COLUMN delivered_at DROP NOT NULL,
+    ALTER COLUMN delivered_at DROP DEFAULT,
+    ADD COLUMN channel VARCHAR(20) NOT NULL DEFAULT 'email',
+    ADD COLUMN status VARCHAR(20) NOT NULL DEFAULT 'delivered',
+    ADD COLUMN attempt INTEGER NOT NULL DEFAULT 1,
+    ADD COLUMN provider_message_id VARCHAR(255),
+    ADD COLUMN error TEXT,
+    ADD COLUMN claim_token_hash VARCHAR(64),
+    ADD COLUMN claim_expires_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN claimed_by_user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    ADD COLUMN claimed_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN receipt_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN attempted_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    ADD CONSTRAINT check_legacy_delivery_channel
+        CHECK (channel IN ('email', 'sms', 'claim_link')),
+    ADD CONSTRAINT check_legacy_delivery_status
+        CHECK (status IN ('sent', 'delivered', 'bounced', 'failed', 'claimed'));
+
+ALTER TABLE legacy_message_deliveries ALTER COLUMN status SET DEFAULT 'sent';
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_legacy_message_deliveries_provider_id
+    ON legacy_message_deliveries(provider_message_id)
+    WHERE provider_message_id IS NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_legacy_message_deliveries_claim_token
+    ON legacy_message_deliveries(claim_token_hash)
+    WHERE claim_token_hash IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_legacy_message_deliveries_message
+    ON legacy_message_deliveries(message_id, attempted_at DESC);
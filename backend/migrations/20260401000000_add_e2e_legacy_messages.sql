@@ -0,0 +1,42 @@
+-- End-to-end encrypted legacy messages and per-message content keys
+
+ALTER TABLE legacy_messages
+    ADD COLUMN encryption_mode VARCHAR(10) NOT NULL DEFAULT 'server',
+    ADD COLUMN wrapped_content_key BYTEA,
+    ADD COLUMN content_key_nonce BYTEA,
+    ADD COLUMN content_hash VARCHAR(64),
+    ADD COLUMN e2e_algorithm VARCHAR(64),
+    ALTER COLUMN key_version DROP NOT NULL,
+    ADD CONSTRAINT check_legacy_message_encryption_mode
+        CHECK (encryption_mode IN ('server', 'e2e')),
+    ADD CONSTRAINT check_legacy_message_key_version
+        CHECK ((encryption_mode = 'server') = (key_version IS NOT NULL));
+
+UPDATE legacy_messages SET content_hash = encode(sha256(encrypted_payload), 'hex');
+
+CREATE INDEX IF NOT EXISTS idx_legacy_messages_key_version
+    ON legacy_messages(key_version) WHERE key_version IS NOT NULL;
+
+ALTER TABLE message_encryption_keys
+    ADD CONSTRAINT check_message_key_status
+        CHECK (status IN ('active', 'retired', 'destroyed'));
+
+-- Content key of an end-to-end message, wrapped for one recipient via X25519
+CREATE TABLE IF NOT EXISTS legacy_message_recipient_keys (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    message_id UUID NOT NULL REFERENCES legacy_messages(id) ON DELETE CASCADE,
+    recipient_public_key BYTEA NOT NULL,
+    ephemeral_public_key BYTEA NOT NULL,
+    wrapped_key BYTEA NOT NULL,
+    wrap_nonce BYTEA NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (message_id, recipient_public_key)
+);
+
+-- X25519 public keys beneficiaries publish so owners can encrypt to them
+CREATE TABLE IF NOT EXISTS message_recipient_public_keys (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    x25519_public_key BYTEA NOT NULL CHECK (octet_length(x25519_public_key) = 32),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,20 @@
+-- Fund movements requested by emergency contacts during an access session
+
+CREATE TABLE IF NOT EXISTS emergency_withdrawal_requests (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    grant_id UUID NOT NULL REFERENCES emergency_access_grants(id) ON DELETE CASCADE,
+    session_id UUID NOT NULL REFERENCES emergency_access_sessions(id) ON DELETE CASCADE,
+    plan_id UUID NOT NULL REFERENCES plans(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    emergency_contact_id UUID NOT NULL REFERENCES emergency_contacts(id) ON DELETE CASCADE,
+    requested_by_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    amount NUMERIC(20, 8) NOT NULL CHECK (amount > 0),
+    limit_amount NUMERIC(20, 8) NOT NULL,
+    destination VARCHAR(255) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'executed', 'rejected')),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_emergency_withdrawal_requests_grant_plan
+    ON emergency_withdrawal_requests(grant_id, plan_id);
@@ -0,0 +1,6 @@
+-- Source text and testator name of generated wills, so the PDF can be
+-- re-rendered with the signatures collected after generation
+
+ALTER TABLE will_documents
+    ADD COLUMN IF NOT EXISTS rendered_content TEXT,
+    ADD COLUMN IF NOT EXISTS owner_name VARCHAR(255);
@@ -0,0 +1,24 @@
+-- Versioned, localized will templates managed by admins
+
+CREATE TABLE IF NOT EXISTS will_templates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    template_key VARCHAR(64) NOT NULL,
+    locale VARCHAR(10) NOT NULL,
+    version INTEGER NOT NULL CHECK (version > 0),
+    title VARCHAR(255) NOT NULL,
+    body TEXT NOT NULL,
+    required_fields TEXT[] NOT NULL DEFAULT '{}',
+    status VARCHAR(20) NOT NULL DEFAULT 'active'
+        CHECK (status IN ('active', 'retired')),
+    created_by UUID REFERENCES admins(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (template_key, locale, version)
+);
+
+CREATE INDEX IF NOT EXISTS idx_will_templates_key_locale
+    ON will_templates(template_key, locale, version DESC);
+
+-- The exact template version a document was rendered from
+ALTER TABLE will_documents
+    ADD COLUMN IF NOT EXISTS template_id UUID REFERENCES will_templates(id),
+    ADD COLUMN IF NOT EXISTS locale VARCHAR(10);
@@ -0,0 +1,3 @@
+-- Name printed as the testator on generated wills
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS legal_name VARCHAR(255);
@@ -0,0 +1,55 @@
+-- On-chain anchoring of will lifecycle steps (hashes, versions, signatures)
+
+CREATE TABLE IF NOT EXISTS will_anchor_transactions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- Submission order; a plan's calls are sent strictly in this order
+    seq BIGSERIAL NOT NULL UNIQUE,
+    plan_id UUID NOT NULL REFERENCES plans(id) ON DELETE CASCADE,
+    document_id UUID REFERENCES will_documents(id) ON DELETE CASCADE,
+    operation VARCHAR(32) NOT NULL,
+    contract_plan_id BIGINT,
+    source_account VARCHAR(56),
+    will_hash VARCHAR(64),
+    version INTEGER,
+    witness_account VARCHAR(56),
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    fee BIGINT,
+    tx_hash VARCHAR(64),
+    last_error TEXT,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    submitted_at TIMESTAMP WITH TIME ZONE,
+    confirmed_at TIMESTAMP WITH TIME ZONE,
+    ledger BIGINT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT check_will_anchor_operation CHECK (operation IN (
+        'store_will_hash', 'link_will_to_vault', 'create_will_version', 'sign_will',
+        'finalize_will', 'add_witness', 'sign_as_witness')),
+    CONSTRAINT check_will_anchor_status CHECK (status IN ('pending', 'submitted', 'confirmed', 'failed'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_will_anchor_transactions_due
+    ON will_anchor_transactions(next_attempt_at) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_will_anchor_transactions_submitted
+    ON will_anchor_transactions(submitted_at) WHERE status = 'submitted';
+CREATE INDEX IF NOT EXISTS idx_will_anchor_transactions_plan
+    ON will_anchor_transactions(plan_id, seq);
+CREATE INDEX IF NOT EXISTS idx_will_anchor_transactions_document
+    ON will_anchor_transactions(document_id);
+
+-- Differences between a document's database state and the chain
+CREATE TABLE IF NOT EXISTS will_anchor_drift (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    document_id UUID NOT NULL REFERENCES will_documents(id) ON DELETE CASCADE,
+    plan_id UUID NOT NULL REFERENCES plans(id) ON DELETE CASCADE,
+    check_name VARCHAR(50) NOT NULL,
+    expected TEXT NOT NULL,
+    actual TEXT NOT NULL,
+    detected_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    last_checked_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_will_anchor_drift_open
+    ON will_anchor_drift(document_id, check_name) WHERE resolved_at IS NULL;
@@ -0,0 +1,31 @@
+-- Will revocation, codicils and structured terms for version diffs
+
+CREATE TABLE IF NOT EXISTS will_revocations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    plan_id UUID NOT NULL REFERENCES plans(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    reason TEXT,
+    revoked_versions INTEGER[] NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_will_revocations_plan ON will_revocations(plan_id, created_at DESC);
+
+ALTER TABLE will_documents
+    -- 'will' for a complete will, 'codicil' for an amendment of base_version
+    ADD COLUMN IF NOT EXISTS kind VARCHAR(20) NOT NULL DEFAULT 'will',
+    ADD COLUMN IF NOT EXISTS base_version INTEGER,
+    -- Beneficiaries and clauses the document states, compared by version diffs
+    ADD COLUMN IF NOT EXISTS terms JSONB,
+    ADD COLUMN IF NOT EXISTS codicil_changes JSONB,
+    ADD COLUMN IF NOT EXISTS revoked_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN IF NOT EXISTS revocation_id UUID REFERENCES will_revocations(id),
+    ADD CONSTRAINT check_will_document_kind CHECK (
+        (kind = 'will' AND base_version IS NULL)
+        OR (kind = 'codicil' AND base_version IS NOT NULL));
+
+ALTER TABLE will_anchor_transactions
+    DROP CONSTRAINT IF EXISTS check_will_anchor_operation,
+    ADD CONSTRAINT check_will_anchor_operation CHECK (operation IN (
+        'store_will_hash', 'link_will_to_vault', 'create_will_version', 'sign_will',
+        'finalize_will', 'add_witness', 'sign_as_witness', 'revoke_will'));
@@ -0,0 +1,51 @@
+-- Notaries and remote online notarization of will documents
+
+CREATE TABLE IF NOT EXISTS notaries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL UNIQUE REFERENCES users(id) ON DELETE CASCADE,
+    full_name VARCHAR(255) NOT NULL,
+    commission_number VARCHAR(100) NOT NULL,
+    jurisdiction VARCHAR(20) NOT NULL,
+    -- Ed25519 key the notary seals with: a Stellar G-address or raw hex
+    public_key VARCHAR(64) NOT NULL,
+    commission_expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'active',
+    created_by UUID,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT check_notary_status CHECK (status IN ('active', 'suspended')),
+    UNIQUE (jurisdiction, commission_number)
+);
+
+CREATE TABLE IF NOT EXISTS notarization_requests (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    document_id UUID NOT NULL REFERENCES will_documents(id) ON DELETE CASCADE,
+    plan_id UUID NOT NULL REFERENCES plans(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    notary_id UUID REFERENCES notaries(id),
+    jurisdiction VARCHAR(20) NOT NULL,
+    -- Will hash of the document when notarization was requested
+    document_hash VARCHAR(64) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'requested',
+    notes TEXT,
+    scheduled_for TIMESTAMP WITH TIME ZONE,
+    session_url TEXT,
+    identity_method VARCHAR(50),
+    session_started_at TIMESTAMP WITH TIME ZONE,
+    seal_signature_hex TEXT,
+    sealed_at TIMESTAMP WITH TIME ZONE,
+    rejection_reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT check_notarization_status CHECK (status IN (
+        'requested', 'scheduled', 'in_session', 'notarized', 'rejected'))
+);
+
+-- One open request per document
+CREATE UNIQUE INDEX IF NOT EXISTS idx_notarization_requests_open
+    ON notarization_requests(document_id)
+    WHERE status IN ('requested', 'scheduled', 'in_session');
+CREATE INDEX IF NOT EXISTS idx_notarization_requests_notary
+    ON notarization_requests(notary_id, status);
+CREATE INDEX IF NOT EXISTS idx_notarization_requests_queue
+    ON notarization_requests(jurisdiction, created_at) WHERE status = 'requested';
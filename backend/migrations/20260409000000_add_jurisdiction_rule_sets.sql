@@ -0,0 +1,24 @@
+-- Versioned will compliance rules per jurisdiction, editable by admins
+
+CREATE TABLE IF NOT EXISTS jurisdiction_rule_sets (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- Country or region code, e.g. 'US' or 'US-CA'
+    code VARCHAR(20) NOT NULL,
+    version INTEGER NOT NULL CHECK (version > 0),
+    name VARCHAR(255) NOT NULL,
+    -- Jurisdiction whose rules this one inherits and overrides
+    extends VARCHAR(20),
+    -- Rule overrides applied on top of the parent's rules
+    rules JSONB NOT NULL DEFAULT '{}',
+    -- Citation for the rules, and per-rule citations keyed by rule name
+    source TEXT NOT NULL,
+    sources JSONB NOT NULL DEFAULT '{}',
+    status VARCHAR(20) NOT NULL DEFAULT 'active'
+        CHECK (status IN ('active', 'retired')),
+    created_by UUID REFERENCES admins(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (code, version)
+);
+
+CREATE INDEX IF NOT EXISTS idx_jurisdiction_rule_sets_code
+    ON jurisdiction_rule_sets(code, version DESC);
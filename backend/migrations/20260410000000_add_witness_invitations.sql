@@ -0,0 +1,19 @@
+-- Tokenized witness invitations: delivery, reminders, expiry and the key a
+-- witness signed with
+
+ALTER TABLE will_witnesses
+    -- SHA-256 of the current invitation token; cleared once it is used
+    ADD COLUMN IF NOT EXISTS invite_token_hash VARCHAR(64),
+    ADD COLUMN IF NOT EXISTS invite_expires_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN IF NOT EXISTS invite_sent_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN IF NOT EXISTS reminder_count INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS last_reminded_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN IF NOT EXISTS viewed_at TIMESTAMP WITH TIME ZONE,
+    -- 'stellar' for a wallet signature, 'device' for a browser-generated key
+    ADD COLUMN IF NOT EXISTS signing_key_type VARCHAR(20),
+    ADD COLUMN IF NOT EXISTS signer_public_key VARCHAR(64);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_will_witnesses_invite_token
+    ON will_witnesses(invite_token_hash) WHERE invite_token_hash IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_will_witnesses_pending_invites
+    ON will_witnesses(invite_expires_at) WHERE status = 'pending';
@@ -0,0 +1,19 @@
+-- Envelope encryption for will documents and their backups.
+--
+-- Content is encrypted under a per-document data key; only the data key,
+-- wrapped by key-encryption key (KEK) version kek_version, is stored. Rows
+-- that are encrypted but have no kek_version predate this and are encrypted
+-- under the key derived from DOCUMENT_ENCRYPTION_KEY until re-encrypted.
+
+ALTER TABLE will_documents
+    ADD COLUMN IF NOT EXISTS wrapped_data_key BYTEA,
+    ADD COLUMN IF NOT EXISTS kek_version      INTEGER;
+
+ALTER TABLE document_backups
+    ADD COLUMN IF NOT EXISTS wrapped_data_key BYTEA,
+    ADD COLUMN IF NOT EXISTS kek_version      INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_will_documents_kek_version
+    ON will_documents(kek_version) WHERE is_encrypted;
+CREATE INDEX IF NOT EXISTS idx_document_backups_kek_version
+    ON document_backups(kek_version);
@@ -0,0 +1,15 @@
+-- Admin alerts raised when external price sources disagree beyond the
+-- configured deviation, with every quote that was compared.
+CREATE TABLE IF NOT EXISTS price_source_alerts (
+    id              UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    asset_code      VARCHAR(20) NOT NULL,
+    -- Published median, or NULL when the source quorum was not met
+    median_price    DECIMAL(20, 8),
+    spread          DECIMAL(12, 6) NOT NULL,
+    accepted_sources INTEGER NOT NULL,
+    quotes          JSONB NOT NULL,
+    created_at      TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_price_source_alerts_asset
+    ON price_source_alerts(asset_code, created_at DESC);
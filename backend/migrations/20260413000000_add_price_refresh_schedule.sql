@@ -0,0 +1,19 @@
+-- Per-feed refresh schedule, moving-average windows and the stale-price
+-- circuit breaker that pauses new borrows against an asset.
+ALTER TABLE price_feeds
+    -- 0 disables scheduled refresh (price is only set by admins)
+    ADD COLUMN IF NOT EXISTS refresh_interval_secs INTEGER NOT NULL DEFAULT 60,
+    ADD COLUMN IF NOT EXISTS max_staleness_secs INTEGER NOT NULL DEFAULT 600,
+    ADD COLUMN IF NOT EXISTS twap_window_secs INTEGER NOT NULL DEFAULT 1800,
+    ADD COLUMN IF NOT EXISTS ema_half_life_secs INTEGER NOT NULL DEFAULT 600,
+    -- Set while the latest price is older than max_staleness_secs
+    ADD COLUMN IF NOT EXISTS borrows_paused_at TIMESTAMP WITH TIME ZONE,
+    ADD CONSTRAINT price_feeds_schedule_check CHECK (
+        refresh_interval_secs >= 0
+        AND max_staleness_secs > 0
+        AND twap_window_secs > 0
+        AND ema_half_life_secs > 0
+    );
+
+CREATE INDEX IF NOT EXISTS idx_asset_price_history_asset_timestamp
+    ON asset_price_history(asset_code, price_timestamp DESC);
@@ -0,0 +1,5 @@
+-- Price feeds identify Stellar assets as CODE:ISSUER (up to 12 + 1 + 56
+-- characters) instead of a bare asset code.
+ALTER TABLE price_feeds ALTER COLUMN asset_code TYPE VARCHAR(69);
+ALTER TABLE asset_price_history ALTER COLUMN asset_code TYPE VARCHAR(69);
+ALTER TABLE price_source_alerts ALTER COLUMN asset_code TYPE VARCHAR(69);
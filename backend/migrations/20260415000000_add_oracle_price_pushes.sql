@@ -0,0 +1,27 @@
+-- Aggregated prices mirrored to the on-chain price oracle contract.
+ALTER TABLE price_feeds
+    ADD COLUMN IF NOT EXISTS oracle_push_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE IF NOT EXISTS oracle_price_pushes (
+    id              UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    asset_code      VARCHAR(69) NOT NULL,
+    price           DECIMAL(20, 8) NOT NULL,
+    -- Price as sent, scaled by the oracle's decimals
+    scaled_price    NUMERIC(39, 0) NOT NULL,
+    -- Observation time passed to set_price
+    price_timestamp TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- deviation | heartbeat | initial
+    reason          VARCHAR(16) NOT NULL,
+    status          VARCHAR(16) NOT NULL DEFAULT 'submitted'
+                    CHECK (status IN ('submitted', 'confirmed', 'failed')),
+    tx_hash         VARCHAR(64),
+    ledger          INTEGER,
+    error           TEXT,
+    created_at      TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at      TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_oracle_price_pushes_asset
+    ON oracle_price_pushes(asset_code, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_oracle_price_pushes_submitted
+    ON oracle_price_pushes(status) WHERE status = 'submitted';
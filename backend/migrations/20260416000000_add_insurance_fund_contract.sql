@@ -0,0 +1,12 @@
+-- The primary insurance fund is held by the on-chain insurance-fund contract;
+-- its reserves are synced from the contract and claims are paid through it.
+ALTER TABLE insurance_fund
+    ADD COLUMN IF NOT EXISTS total_premiums NUMERIC(30, 8) NOT NULL DEFAULT 0;
+
+ALTER TABLE insurance_claims
+    -- file_claim transaction recording the approval on-chain
+    ADD COLUMN IF NOT EXISTS filing_tx_hash VARCHAR(64),
+    -- payout_claim transaction transferring the funds
+    ADD COLUMN IF NOT EXISTS payout_tx_hash VARCHAR(64);
+
+COMMENT ON COLUMN insurance_fund.total_premiums IS 'Lending protocol fees routed to the fund (on-chain funds only)';
@@ -0,0 +1,44 @@
+-- Claim adjudication: coverage policy per claim type, two-person review of
+-- large approvals, review SLAs and automatically filed claims.
+CREATE TABLE IF NOT EXISTS insurance_claim_policies (
+    claim_type              VARCHAR(50) PRIMARY KEY,
+    -- Most paid on one claim, after the deductible (NULL = no limit)
+    per_claim_limit         NUMERIC(30, 8) CHECK (per_claim_limit >= 0),
+    -- Most paid across all claims of this type on one plan (NULL = no limit)
+    per_plan_limit          NUMERIC(30, 8) CHECK (per_plan_limit >= 0),
+    -- Taken off every claim before limits apply
+    deductible              NUMERIC(30, 8) NOT NULL DEFAULT 0 CHECK (deductible >= 0),
+    -- Approvals above this need a second, different admin (NULL = never)
+    dual_review_threshold   NUMERIC(30, 8) CHECK (dual_review_threshold >= 0),
+    review_sla_hours        INTEGER NOT NULL DEFAULT 72 CHECK (review_sla_hours > 0),
+    -- Whether the fund monitor files claims of this type on its own
+    auto_create             BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_at              TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO insurance_claim_policies
+    (claim_type, dual_review_threshold, review_sla_hours, auto_create)
+VALUES
+    ('liquidation', 10000, 72, FALSE),
+    ('default', 10000, 72, FALSE),
+    ('emergency_payout', 10000, 24, FALSE),
+    ('liquidation_shortfall', 10000, 48, TRUE),
+    ('inheritance_recall', 10000, 48, TRUE)
+ON CONFLICT (claim_type) DO NOTHING;
+
+ALTER TABLE insurance_claims
+    -- Event an automatically filed claim covers, e.g. 'liquidation:<event id>'
+    ADD COLUMN IF NOT EXISTS source_key VARCHAR(100) UNIQUE,
+    -- First approval of a claim awaiting a second reviewer
+    ADD COLUMN IF NOT EXISTS first_reviewed_by UUID REFERENCES admins(id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS first_reviewed_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN IF NOT EXISTS review_due_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN IF NOT EXISTS sla_breached_at TIMESTAMP WITH TIME ZONE;
+
+UPDATE insurance_claims
+SET review_due_at = created_at + INTERVAL '72 hours'
+WHERE review_due_at IS NULL AND status = 'pending';
+
+CREATE INDEX IF NOT EXISTS idx_insurance_claims_review_due
+    ON insurance_claims(review_due_at)
+    WHERE status IN ('pending', 'awaiting_second_review');
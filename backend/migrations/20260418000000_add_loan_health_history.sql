@@ -0,0 +1,50 @@
+-- Portfolio risk engine: per-position health factor time series, current
+-- health on lifecycle loans and book-wide collateral concentration snapshots.
+--
+-- Health factors are now collateral value x the collateral's liquidation
+-- threshold / debt value; a position below 1.0 is liquidatable.
+
+-- One row per position per risk cycle. Lifecycle loans set loan_id; positions
+-- opened on-chain (derived from lending_events) only set plan_id.
+CREATE TABLE loan_health_history (
+    id                      BIGSERIAL PRIMARY KEY,
+    loan_id                 UUID REFERENCES loan_lifecycle(id) ON DELETE CASCADE,
+    plan_id                 UUID REFERENCES plans(id) ON DELETE CASCADE,
+    user_id                 UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    collateral_asset        VARCHAR(100) NOT NULL,
+    borrow_asset            VARCHAR(100) NOT NULL,
+    collateral_value        NUMERIC(30, 8) NOT NULL,
+    debt_value              NUMERIC(30, 8) NOT NULL,
+    liquidation_threshold   NUMERIC(5, 4) NOT NULL,
+    health_factor           NUMERIC(20, 8) NOT NULL,
+
+    recorded_at             TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CHECK (loan_id IS NOT NULL OR plan_id IS NOT NULL)
+);
+
+CREATE INDEX idx_loan_health_history_loan ON loan_health_history(loan_id, recorded_at DESC)
+    WHERE loan_id IS NOT NULL;
+CREATE INDEX idx_loan_health_history_plan ON loan_health_history(plan_id, recorded_at DESC)
+    WHERE loan_id IS NULL;
+CREATE INDEX idx_loan_health_history_recorded_at ON loan_health_history(recorded_at);
+
+ALTER TABLE loan_lifecycle
+    ADD COLUMN IF NOT EXISTS health_factor NUMERIC(20, 8),
+    ADD COLUMN IF NOT EXISTS is_risky BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS risk_flagged_at TIMESTAMP WITH TIME ZONE;
+
+-- Book-wide exposure per correlated collateral group, one row per risk cycle
+CREATE TABLE portfolio_risk_snapshots (
+    id                      BIGSERIAL PRIMARY KEY,
+    positions               INTEGER NOT NULL,
+    at_risk_positions       INTEGER NOT NULL,
+    total_collateral_value  NUMERIC(30, 8) NOT NULL,
+    total_debt_value        NUMERIC(30, 8) NOT NULL,
+    -- [{group, collateral_value, share, debt_value, stressed_debt_value, breached}]
+    concentrations          JSONB NOT NULL DEFAULT '[]',
+    recorded_at             TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_portfolio_risk_snapshots_recorded_at ON portfolio_risk_snapshots(recorded_at DESC);
@@ -0,0 +1,54 @@
+-- Dutch-auction liquidations: a risky plan's collateral is offered to keepers
+-- at a price that decays from a premium to a floor, filled by partial bids.
+-- Collateral left when the debt is covered stays with the borrower.
+
+CREATE TABLE liquidation_auctions (
+    id                      UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    plan_id                 UUID NOT NULL REFERENCES plans(id) ON DELETE CASCADE,
+    user_id                 UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    started_by              UUID REFERENCES users(id) ON DELETE SET NULL,
+
+    collateral_asset        VARCHAR(100) NOT NULL,
+    debt_asset              VARCHAR(100) NOT NULL,
+    -- TWAP prices when the auction started; bids are priced off these
+    collateral_price        NUMERIC(30, 8) NOT NULL,
+    debt_price              NUMERIC(30, 8) NOT NULL,
+
+    start_premium_bps       INTEGER NOT NULL,
+    floor_bps               INTEGER NOT NULL,
+    duration_seconds        BIGINT NOT NULL,
+
+    debt_total              NUMERIC(30, 8) NOT NULL,
+    debt_remaining          NUMERIC(30, 8) NOT NULL,
+    collateral_total        NUMERIC(30, 8) NOT NULL,
+    collateral_remaining    NUMERIC(30, 8) NOT NULL,
+
+    status                  VARCHAR(20) NOT NULL DEFAULT 'active'
+                                CHECK (status IN ('active', 'settled')),
+    collateral_returned     NUMERIC(30, 8),
+    bad_debt                NUMERIC(30, 8),
+
+    started_at              TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    settled_at              TIMESTAMP WITH TIME ZONE,
+
+    CHECK (floor_bps > 0 AND floor_bps <= start_premium_bps),
+    CHECK (duration_seconds > 0)
+);
+
+-- At most one running auction per plan
+CREATE UNIQUE INDEX idx_liquidation_auctions_active_plan ON liquidation_auctions(plan_id)
+    WHERE status = 'active';
+CREATE INDEX idx_liquidation_auctions_status ON liquidation_auctions(status, started_at DESC);
+
+CREATE TABLE liquidation_auction_bids (
+    id                      UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    auction_id              UUID NOT NULL REFERENCES liquidation_auctions(id) ON DELETE CASCADE,
+    keeper_id               UUID NOT NULL REFERENCES users(id),
+    debt_repaid             NUMERIC(30, 8) NOT NULL,
+    collateral_received     NUMERIC(30, 8) NOT NULL,
+    price_bps               INTEGER NOT NULL,
+    transaction_hash        VARCHAR(255),
+    created_at              TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_liquidation_auction_bids_auction ON liquidation_auction_bids(auction_id, created_at);
@@ -0,0 +1,11 @@
+-- Loan simulations keep their full payment schedule and, when the collateral
+-- has enough price history, the scenario and Monte Carlo projection, so
+-- saved simulations can be compared later.
+ALTER TABLE loan_simulations
+    ADD COLUMN IF NOT EXISTS repayment_schedule VARCHAR(30) NOT NULL DEFAULT 'bullet'
+        CHECK (repayment_schedule IN ('bullet', 'equal_installment', 'interest_only')),
+    ADD COLUMN IF NOT EXISTS installment_interval_days INTEGER,
+    ADD COLUMN IF NOT EXISTS schedule JSONB NOT NULL DEFAULT '[]',
+    ADD COLUMN IF NOT EXISTS projection JSONB,
+    -- Share of Monte Carlo runs liquidated before the due date
+    ADD COLUMN IF NOT EXISTS liquidation_probability NUMERIC(5, 4);
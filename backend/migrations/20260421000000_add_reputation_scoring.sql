@@ -0,0 +1,14 @@
+-- Borrower reputation moves to a 0-1000 scale starting at 500, with a tier
+-- that adjusts loan terms and the components behind the score. Old scores
+-- were on an unrelated scale, so they restart at the new base and are
+-- recomputed with each borrower's next lending event.
+ALTER TABLE borrower_reputation
+    ALTER COLUMN score SET DEFAULT 500,
+    ADD COLUMN tier VARCHAR(20) NOT NULL DEFAULT 'standard'
+        CHECK (tier IN ('excellent', 'good', 'standard', 'subprime')),
+    ADD COLUMN score_components JSONB NOT NULL DEFAULT '[]',
+    ADD COLUMN score_updated_at TIMESTAMP WITH TIME ZONE;
+
+UPDATE borrower_reputation SET score = 500;
+
+CREATE INDEX idx_borrower_reputation_tier ON borrower_reputation(tier);
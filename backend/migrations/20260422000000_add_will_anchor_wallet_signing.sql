@@ -0,0 +1,16 @@
+-- Wallet-signed anchoring and expiring transactions
+
+ALTER TABLE will_anchor_transactions
+    -- The transaction cannot land after this, so it is safe to rebuild
+    ADD COLUMN IF NOT EXISTS valid_until TIMESTAMP WITH TIME ZONE,
+    -- Prepared transaction waiting for the source account's wallet
+    ADD COLUMN IF NOT EXISTS unsigned_envelope TEXT,
+    ADD COLUMN IF NOT EXISTS signature_payload VARCHAR(64),
+    -- Exact envelope sent, so resends never produce a second transaction
+    ADD COLUMN IF NOT EXISTS signed_envelope TEXT,
+    DROP CONSTRAINT IF EXISTS check_will_anchor_status,
+    ADD CONSTRAINT check_will_anchor_status CHECK (status IN (
+        'pending', 'awaiting_signature', 'signed', 'submitted', 'confirmed', 'failed'));
+
+CREATE INDEX IF NOT EXISTS idx_will_anchor_awaiting_signature
+    ON will_anchor_transactions(source_account) WHERE status = 'awaiting_signature';
@@ -0,0 +1,16 @@
+-- The service reads and writes fund status as text, which Postgres will not
+-- coerce to or from the enum, so every fund lookup (including claim
+-- adjudication) failed to decode. Store it as checked text instead.
+ALTER TABLE insurance_fund ALTER COLUMN status DROP DEFAULT;
+ALTER TABLE insurance_fund
+    ALTER COLUMN status TYPE VARCHAR(20) USING status::text,
+    ALTER COLUMN status SET DEFAULT 'healthy',
+    ADD CONSTRAINT insurance_fund_status_check
+        CHECK (status IN ('healthy', 'warning', 'critical', 'insolvent'));
+
+ALTER TABLE insurance_fund_metrics_history
+    ALTER COLUMN status TYPE VARCHAR(20) USING status::text,
+    ADD CONSTRAINT insurance_fund_metrics_history_status_check
+        CHECK (status IN ('healthy', 'warning', 'critical', 'insolvent'));
+
+DROP TYPE IF EXISTS insurance_fund_status;
@@ -0,0 +1,3 @@
+-- Pushes whose price could not be scaled are recorded as failed without a
+-- scaled price
+ALTER TABLE oracle_price_pushes ALTER COLUMN scaled_price DROP NOT NULL;
@@ -0,0 +1,7 @@
+-- Emergency withdrawals are debited from the plan when accepted
+
+ALTER TABLE emergency_withdrawal_requests
+    ADD COLUMN IF NOT EXISTS executed_at TIMESTAMP WITH TIME ZONE;
+
+ALTER TABLE emergency_withdrawal_requests
+    ALTER COLUMN status SET DEFAULT 'executed';
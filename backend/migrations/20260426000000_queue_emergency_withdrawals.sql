@@ -0,0 +1,5 @@
+-- Emergency withdrawals are recorded as pending requests; the plan is only
+-- debited once the contract transfer has executed
+
+ALTER TABLE emergency_withdrawal_requests
+    ALTER COLUMN status SET DEFAULT 'pending';
@@ -0,0 +1,7 @@
+-- Delivery attempts are recorded before the provider is called, so a
+-- database failure after a successful send cannot cause a second send
+
+ALTER TABLE legacy_message_deliveries
+    DROP CONSTRAINT IF EXISTS check_legacy_delivery_status,
+    ADD CONSTRAINT check_legacy_delivery_status
+        CHECK (status IN ('sending', 'sent', 'delivered', 'bounced', 'failed', 'claimed'));
@@ -0,0 +1,7 @@
+-- On-chain legacy message whose message_hash anchors an end-to-end message's
+-- ciphertext. Messages stored before anchoring was required have none.
+ALTER TABLE legacy_messages
+    ADD COLUMN on_chain_message_id BIGINT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_legacy_messages_on_chain_message_id
+    ON legacy_messages(on_chain_message_id) WHERE on_chain_message_id IS NOT NULL;
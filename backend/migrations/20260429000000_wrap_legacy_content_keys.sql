@@ -0,0 +1,5 @@
+-- Legacy content sealed under its own data key, wrapped by the document key
+-- manager under KEK version encryption_key_version. Rows without one were
+-- sealed with the key derived from DOCUMENT_ENCRYPTION_KEY.
+ALTER TABLE legacy_content
+    ADD COLUMN wrapped_data_key BYTEA;
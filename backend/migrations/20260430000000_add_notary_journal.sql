@@ -0,0 +1,30 @@
+-- Notary journal, kept apart from will_event_log. Entries are written in the
+-- same transaction as the notarization state change they record and outlive
+-- the request and the notary, so neither id carries a foreign key.
+CREATE TABLE IF NOT EXISTS notary_journal (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    request_id UUID NOT NULL,
+    document_id UUID NOT NULL,
+    plan_id UUID NOT NULL,
+    notary_id UUID,
+    action VARCHAR(50) NOT NULL,
+    details JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_notary_journal_request
+    ON notary_journal(request_id, created_at);
+
+INSERT INTO notary_journal
+    (request_id, document_id, plan_id, notary_id, action, details, created_at)
+SELECT (event_data->>'request_id')::uuid,
+       document_id,
+       plan_id,
+       (event_data->>'notary_id')::uuid,
+       event_data->>'action',
+       COALESCE(event_data->'details', '{}'::jsonb),
+       created_at
+FROM will_event_log
+WHERE event_type = 'notary_journal';
+
+DELETE FROM will_event_log WHERE event_type = 'notary_journal';
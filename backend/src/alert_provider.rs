@@ -1,30 +1,536 @@
+use crate::api_error::ApiError;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
 use tracing::info;
 
+/// Provider acknowledgement for an accepted message, used to match later
+/// delivery receipts and bounces back to the attempt.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryReceipt {
+    pub provider_message_id: String,
+}
+
+/// Notification templates rendered per channel before hand-off to a provider.
+#[derive(Debug, Clone)]
+pub enum AlertTemplate<'a> {
+    /// A legacy message delivered in full to the beneficiary
+    LegacyMessage { message: &'a str },
+    /// A link the beneficiary must open (and sign in) to read a legacy message
+    LegacyMessageClaimLink {
+        claim_url: &'a str,
+        expires_at: DateTime<Utc>,
+    },
+    /// An invitation (or a reminder of one) to witness a will
+    WitnessInvitation {
+        owner_name: &'a str,
+        invite_url: &'a str,
+        expires_at: DateTime<Utc>,
+        reminder: bool,
+    },
+}
+
+impl AlertTemplate<'_> {
+    /// Template name, safe to log
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::LegacyMessage { .. } => "legacy_message",
+            Self::LegacyMessageClaimLink { .. } => "legacy_message_claim_link",
+            Self::WitnessInvitation { .. } => "witness_invitation",
+        }
+    }
+
+    /// Render as an email `(subject, body)`
+    pub fn render_email(&self) -> (String, String) {
+        match self {
+            Self::LegacyMessage { message } => (
+                "A message has been left for you on INHERITX".to_string(),
+                format!(
+                    "Someone who named you as a beneficiary left you the following message:\n\n{}\n\n\
+                     This message was delivered automatically by INHERITX.",
+                    message
+                ),
+            ),
+            Self::LegacyMessageClaimLink {
+                claim_url,
+                expires_at,
+            } => (
+                "A private message is waiting for you on INHERITX".to_string(),
+                format!(
+                    "Someone who named you as a beneficiary left you a private message.\n\n\
+                     Sign in and open the link below to read it:\n{}\n\n\
+                     The link expires on {}.",
+                    claim_url,
+                    expires_at.format("%Y-%m-%d %H:%M UTC")
+                ),
+            ),
+            Self::WitnessInvitation {
+                owner_name,
+                invite_url,
+                expires_at,
+                reminder,
+            } => (
+                if *reminder {
+                    format!("Reminder: {} asked you to witness their will", owner_name)
+                } else {
+                    format!("{} has asked you to witness their will", owner_name)
+                },
+                format!(
+                    "{} has asked you to witness their will on INHERITX.\n\n\
+                     Open the link below to read the will and sign as a witness. \
+                     You do not need an INHERITX account:\n{}\n\n\
+                     The link can be used once and expires on {}.",
+                    owner_name,
+                    invite_url,
+                    expires_at.format("%Y-%m-%d %H:%M UTC")
+                ),
+            ),
+        }
+    }
+
+    /// Render as an SMS body
+    pub fn render_sms(&self) -> String {
+        match self {
+            Self::LegacyMessage { message } => {
+                format!("INHERITX: A message was left for you: {}", message)
+            }
+            Self::LegacyMessageClaimLink {
+                claim_url,
+                expires_at,
+            } => format!(
+                "INHERITX: A private message is waiting for you. Sign in to read it before {}: {}",
+                expires_at.format("%Y-%m-%d"),
+                claim_url
+            ),
+            Self::WitnessInvitation {
+                owner_name,
+                invite_url,
+                expires_at,
+                ..
+            } => format!(
+                "INHERITX: {} asked you to witness their will. Open before {}: {}",
+                owner_name,
+                expires_at.format("%Y-%m-%d"),
+                invite_url
+            ),
+        }
+    }
+}
+
 #[async_trait]
 pub trait AlertProvider: Send + Sync {
-    async fn send_sms(&self, to: &str, message: &str) -> anyhow::Result<()>;
-    async fn send_email(&self, to: &str, subject: &str, body: &str) -> anyhow::Result<()>;
+    /// Send an SMS, returning the provider's id for the accepted message
+    async fn send_sms(&self, to: &str, message: &str) -> anyhow::Result<DeliveryReceipt>;
+    /// Send an email, returning the provider's id for the accepted message
+    async fn send_email(
+        &self,
+        to: &str,
+        subject: &str,
+        body: &str,
+    ) -> anyhow::Result<DeliveryReceipt>;
+
+    /// Render `template` and send it by email.
+    async fn send_templated_email(
+        &self,
+        to: &str,
+        template: &AlertTemplate<'_>,
+    ) -> anyhow::Result<DeliveryReceipt> {
+        let (subject, body) = template.render_email();
+        self.send_email(to, &subject, &body).await
+    }
+
+    /// Render `template` and send it by SMS.
+    async fn send_templated_sms(
+        &self,
+        to: &str,
+        template: &AlertTemplate<'_>,
+    ) -> anyhow::Result<DeliveryReceipt> {
+        self.send_sms(to, &template.render_sms()).await
+    }
+}
+
+/// Provider selected by `ALERT_PROVIDER`:
+///
+/// - `live` sends email through SendGrid (`SENDGRID_API_KEY`,
+///   `ALERT_EMAIL_FROM`) and SMS through Twilio (`TWILIO_ACCOUNT_SID`,
+///   `TWILIO_AUTH_TOKEN`, `TWILIO_FROM_NUMBER`, and optionally
+///   `TWILIO_STATUS_CALLBACK_URL` for delivery receipts); at least one
+///   channel must be configured and the other fails its sends.
+/// - `mock` only logs, and is refused unless `APP_ENV` is `development` or
+///   `test`, which is also when it is the default.
+pub fn alert_provider_from_env() -> Result<Arc<dyn AlertProvider>, ApiError> {
+    let dev = matches!(
+        std::env::var("APP_ENV").as_deref(),
+        Ok("development") | Ok("test")
+    );
+    let backend = match std::env::var("ALERT_PROVIDER") {
+        Ok(backend) => backend,
+        Err(_) if dev => "mock".to_string(),
+        Err(_) => {
+            return Err(ApiError::Internal(anyhow::anyhow!(
+                "ALERT_PROVIDER must be set outside development"
+            )))
+        }
+    };
+    match backend.as_str() {
+        "live" => Ok(Arc::new(LiveAlertProvider::from_env()?)),
+        "mock" if dev => Ok(Arc::new(MockAlertProvider)),
+        "mock" => Err(ApiError::Internal(anyhow::anyhow!(
+            "ALERT_PROVIDER=mock is only allowed when APP_ENV is development or test"
+        ))),
+        other => Err(ApiError::Internal(anyhow::anyhow!(
+            "Unknown ALERT_PROVIDER: {}",
+            other
+        ))),
+    }
 }
 
+/// Stand-in for development: logs who would have been contacted, never the
+/// content, which can hold legacy messages and invitation links
 pub struct MockAlertProvider;
 
 #[async_trait]
 impl AlertProvider for MockAlertProvider {
-    async fn send_sms(&self, to: &str, message: &str) -> anyhow::Result<()> {
-        info!("--- [MOCK SMS ALERT] ---");
-        info!("To: {}", to);
-        info!("Message: {}", message);
-        info!("-----------------------");
-        Ok(())
-    }
-
-    async fn send_email(&self, to: &str, subject: &str, body: &str) -> anyhow::Result<()> {
-        info!("--- [MOCK EMAIL ALERT] ---");
-        info!("To: {}", to);
-        info!("Subject: {}", subject);
-        info!("Body: {}", body);
-        info!("-------------------------");
-        Ok(())
+    async fn send_sms(&self, to: &str, _message: &str) -> anyhow::Result<DeliveryReceipt> {
+        let receipt = DeliveryReceipt {
+            provider_message_id: format!("mock-{}", uuid::Uuid::new_v4()),
+        };
+        info!(
+            recipient = %recipient_hash(to),
+            id = %receipt.provider_message_id,
+            "[MOCK SMS ALERT]"
+        );
+        Ok(receipt)
+    }
+
+    async fn send_email(
+        &self,
+        to: &str,
+        _subject: &str,
+        _body: &str,
+    ) -> anyhow::Result<DeliveryReceipt> {
+        let receipt = DeliveryReceipt {
+            provider_message_id: format!("mock-{}", uuid::Uuid::new_v4()),
+        };
+        info!(
+            recipient = %recipient_hash(to),
+            id = %receipt.provider_message_id,
+            "[MOCK EMAIL ALERT]"
+        );
+        Ok(receipt)
+    }
+
+    async fn send_templated_email(
+        &self,
+        to: &str,
+        template: &AlertTemplate<'_>,
+    ) -> anyhow::Result<DeliveryReceipt> {
+        info!(template = template.id(), "Rendering email template");
+        let (subject, body) = template.render_email();
+        self.send_email(to, &subject, &body).await
+    }
+
+    async fn send_templated_sms(
+        &self,
+        to: &str,
+        template: &AlertTemplate<'_>,
+    ) -> anyhow::Result<DeliveryReceipt> {
+        info!(template = template.id(), "Rendering SMS template");
+        self.send_sms(to, &template.render_sms()).await
+    }
+}
+
+/// Short hash identifying a recipient in logs without exposing the address
+pub fn recipient_hash(to: &str) -> String {
+    let digest = Sha256::digest(to.trim().to_lowercase().as_bytes());
+    hex::encode(&digest[..8])
+}
+
+// ─── Live provider ───────────────────────────────────────────────────────────
+
+const SENDGRID_API_URL: &str = "https://api.sendgrid.com";
+const TWILIO_API_URL: &str = "https://api.twilio.com";
+
+pub struct SendGridConfig {
+    pub api_url: String,
+    pub api_key: String,
+    pub from: String,
+}
+
+pub struct TwilioConfig {
+    pub api_url: String,
+    pub account_sid: String,
+    pub auth_token: String,
+    pub from: String,
+    /// Where Twilio posts status changes of each message
+    pub status_callback: Option<String>,
+}
+
+/// Email through SendGrid and SMS through Twilio. A send only succeeds once
+/// the provider has accepted the message, and the receipt carries the
+/// provider's own message id so delivery webhooks can be matched.
+pub struct LiveAlertProvider {
+    client: reqwest::Client,
+    email: Option<SendGridConfig>,
+    sms: Option<TwilioConfig>,
+}
+
+impl LiveAlertProvider {
+    pub fn new(email: Option<SendGridConfig>, sms: Option<TwilioConfig>) -> Result<Self, ApiError> {
+        if email.is_none() && sms.is_none() {
+            return Err(ApiError::Internal(anyhow::anyhow!(
+                "ALERT_PROVIDER=live needs SendGrid or Twilio credentials"
+            )));
+        }
+        Ok(Self {
+            client: reqwest::Client::new(),
+            email,
+            sms,
+        })
+    }
+
+    pub fn from_env() -> Result<Self, ApiError> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let email = match (var("SENDGRID_API_KEY"), var("ALERT_EMAIL_FROM")) {
+            (Some(api_key), Some(from)) => Some(SendGridConfig {
+                api_url: var("SENDGRID_API_URL").unwrap_or_else(|| SENDGRID_API_URL.to_string()),
+                api_key,
+                from,
+            }),
+            _ => None,
+        };
+        let sms = match (
+            var("TWILIO_ACCOUNT_SID"),
+            var("TWILIO_AUTH_TOKEN"),
+            var("TWILIO_FROM_NUMBER"),
+        ) {
+            (Some(account_sid), Some(auth_token), Some(from)) => Some(TwilioConfig {
+                api_url: var("TWILIO_API_URL").unwrap_or_else(|| TWILIO_API_URL.to_string()),
+                account_sid,
+                auth_token,
+                from,
+                status_callback: var("TWILIO_STATUS_CALLBACK_URL"),
+            }),
+            _ => None,
+        };
+        Self::new(email, sms)
+    }
+}
+
+#[async_trait]
+impl AlertProvider for LiveAlertProvider {
+    async fn send_sms(&self, to: &str, message: &str) -> anyhow::Result<DeliveryReceipt> {
+        let config = self
+            .sms
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("SMS delivery is not configured"))?;
+
+        #[derive(Deserialize)]
+        struct TwilioMessage {
+            sid: String,
+        }
+
+        let mut form = vec![
+            ("To", to),
+            ("From", config.from.as_str()),
+            ("Body", message),
+        ];
+        if let Some(callback) = &config.status_callback {
+            form.push(("StatusCallback", callback.as_str()));
+        }
+
+        let response = self
+            .client
+            .post(format!(
+                "{}/2010-04-01/Accounts/{}/Messages.json",
+                config.api_url.trim_end_matches('/'),
+                config.account_sid
+            ))
+            .basic_auth(&config.account_sid, Some(&config.auth_token))
+            .form(&form)
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("Twilio rejected the message with status {}", status);
+        }
+        let message: TwilioMessage = response.json().await?;
+        Ok(DeliveryReceipt {
+            provider_message_id: message.sid,
+        })
+    }
+
+    async fn send_email(
+        &self,
+        to: &str,
+        subject: &str,
+        body: &str,
+    ) -> anyhow::Result<DeliveryReceipt> {
+        let config = self
+            .email
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Email delivery is not configured"))?;
+
+        let response = self
+            .client
+            .post(format!(
+                "{}/v3/mail/send",
+                config.api_url.trim_end_matches('/')
+            ))
+            .bearer_auth(&config.api_key)
+            .json(&json!({
+                "personalizations": [{ "to": [{ "email": to }] }],
+                "from": { "email": config.from },
+                "subject": subject,
+                "content": [{ "type": "text/plain", "value": body }],
+            }))
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("SendGrid rejected the message with status {}", status);
+        }
+        let id = response
+            .headers()
+            .get("x-message-id")
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("SendGrid accepted the message without an id"))?;
+        Ok(DeliveryReceipt {
+            provider_message_id: id.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_message_templates_include_message() {
+        let template = AlertTemplate::LegacyMessage { message: "hello" };
+        let (subject, body) = template.render_email();
+        assert!(!subject.contains("hello"));
+        assert!(body.contains("hello"));
+        assert!(template.render_sms().contains("hello"));
+    }
+
+    #[test]
+    fn test_claim_link_templates_include_url() {
+        let template = AlertTemplate::LegacyMessageClaimLink {
+            claim_url: "https://example.com/claim/abc",
+            expires_at: Utc::now(),
+        };
+        assert!(template
+            .render_email()
+            .1
+            .contains("https://example.com/claim/abc"));
+        assert!(template
+            .render_sms()
+            .contains("https://example.com/claim/abc"));
+    }
+
+    #[test]
+    fn test_witness_invitation_templates() {
+        let invite = AlertTemplate::WitnessInvitation {
+            owner_name: "Ada Obi",
+            invite_url: "https://example.com/api/will/witness-invitations/t",
+            expires_at: Utc::now(),
+            reminder: false,
+        };
+        let (subject, body) = invite.render_email();
+        assert!(subject.starts_with("Ada Obi"));
+        assert!(body.contains("https://example.com/api/will/witness-invitations/t"));
+        assert!(invite.render_sms().contains("Ada Obi"));
+
+        let reminder = AlertTemplate::WitnessInvitation {
+            owner_name: "Ada Obi",
+            invite_url: "https://example.com/api/will/witness-invitations/t",
+            expires_at: Utc::now(),
+            reminder: true,
+        };
+        assert!(reminder.render_email().0.starts_with("Reminder"));
+    }
+
+    fn live_provider(server: &httpmock::MockServer) -> LiveAlertProvider {
+        LiveAlertProvider::new(
+            Some(SendGridConfig {
+                api_url: server.base_url(),
+                api_key: "sg-key".to_string(),
+                from: "noreply@inheritx.test".to_string(),
+            }),
+            Some(TwilioConfig {
+                api_url: server.base_url(),
+                account_sid: "AC123".to_string(),
+                auth_token: "token".to_string(),
+                from: "+15550000000".to_string(),
+                status_callback: Some("https://api.inheritx.test/twilio".to_string()),
+            }),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_live_email_returns_provider_message_id() {
+        let server = httpmock::MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/v3/mail/send")
+                .header("authorization", "Bearer sg-key");
+            then.status(202).header("X-Message-Id", "sg-msg-1");
+        });
+
+        let receipt = live_provider(&server)
+            .send_templated_email(
+                "heir@example.com",
+                &AlertTemplate::LegacyMessage { message: "hi" },
+            )
+            .await
+            .unwrap();
+        mock.assert();
+        assert_eq!(receipt.provider_message_id, "sg-msg-1");
+    }
+
+    #[tokio::test]
+    async fn test_live_sms_returns_twilio_sid_and_fails_when_rejected() {
+        let server = httpmock::MockServer::start();
+        server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/2010-04-01/Accounts/AC123/Messages.json")
+                .body_contains("To=%2B15551234567")
+                .body_contains("StatusCallback=https%3A%2F%2Fapi.inheritx.test%2Ftwilio");
+            then.status(201).json_body(json!({ "sid": "SM42" }));
+        });
+        server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/2010-04-01/Accounts/AC123/Messages.json")
+                .body_contains("To=%2B15559999999");
+            then.status(400).json_body(json!({ "code": 21211 }));
+        });
+
+        let provider = live_provider(&server);
+        let receipt = provider.send_sms("+15551234567", "hello").await.unwrap();
+        assert_eq!(receipt.provider_message_id, "SM42");
+        assert!(provider.send_sms("+15559999999", "hello").await.is_err());
+    }
+
+    #[test]
+    fn test_live_provider_needs_a_channel() {
+        assert!(LiveAlertProvider::new(None, None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_default_templated_send_issues_receipt() {
+        let receipt = MockAlertProvider
+            .send_templated_email(
+                "heir@example.com",
+                &AlertTemplate::LegacyMessage { message: "hi" },
+            )
+            .await
+            .unwrap();
+        assert!(!receipt.provider_message_id.is_empty());
     }
 }
@@ -1,7 +1,7 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{DefaultBodyLimit, Multipart, Path, Query, State},
     routing::{get, post, put},
-    Json, Router,
+    Form, Json, Router,
 };
 use serde_json::{json, Value};
 use sqlx::PgPool;
@@ -11,24 +11,54 @@ use tower_governor::{governor::GovernorConfigBuilder, GovernorLayer};
 use tower_http::trace::TraceLayer;
 use uuid::Uuid;
 
+use crate::alert_provider::{alert_provider_from_env, AlertProvider};
 use crate::analytics::analytics_router;
 use crate::api_error::ApiError;
 use crate::auth::{AuthenticatedAdmin, AuthenticatedUser};
 use crate::beneficiary_sync::{BeneficiarySyncService, DocumentBeneficiary};
+use crate::blob_store::{
+    blob_store_from_env, BlobService, BlobStore, DownloadUrlSigner, SignedUrlParams,
+};
 use crate::config::Config;
-use crate::document_storage::DocumentStorageService;
+use crate::contract_reader::{contract_reader_from_env, InheritanceContractReader};
+use crate::document_storage::{DocumentKeyRotationService, DocumentStorageService};
+use crate::emergency_principal::{
+    EmergencyPermission, EmergencyPrincipal, EmergencyWithdrawalRequest,
+    EmergencyWithdrawalService, Principal,
+};
 use crate::governance::{
     CreateProposalRequest, GovernanceService, ParameterUpdateRequest, Proposal, VoteRequest,
 };
 use crate::insurance_fund::{CreateInsuranceClaimRequest, ProcessInsuranceClaimRequest};
-use crate::legacy_content::{ContentListFilters, LegacyContentService};
+use crate::jurisdiction_rules::{
+    CreateRuleSetRequest, JurisdictionRuleService, RuleSetQuery, UpdateRuleSetRequest,
+};
+use crate::key_manager::{key_manager_from_env, KeyManager};
+use crate::legacy_content::{
+    ContentListFilters, FileStorageService, InitUploadRequest, LegacyContentService,
+};
+use crate::liquidation_auction::{
+    AuctionParams, LiquidationAuctionService, PlaceBidRequest, StartAuctionRequest,
+};
 use crate::loan_lifecycle::{CreateLoanRequest, LoanLifecycleService, LoanListFilters};
-use crate::message_access_audit::{
-    MessageAccessAuditService, MessageAuditFilters,
+use crate::message_access_audit::{MessageAccessAuditService, MessageAuditFilters};
+use crate::message_e2e::{
+    CreateE2eMessageRequest, E2eMessageService, RecipientKeyQuery, RecipientKeyService,
+    RegisterRecipientKeyRequest,
+};
+use crate::notarization::{
+    self, AuthenticatedNotary, CreateNotaryRequest, NotarizationService, NotaryQueueQuery,
+    RejectNotarizationRequest, RequestNotarizationRequest, ScheduleSessionRequest, SealRequest,
+    UpdateNotaryStatusRequest,
 };
+use crate::receipt_webhooks::{
+    sendgrid_receipts, twilio_receipt, verify_sendgrid_signature, verify_twilio_signature,
+    SENDGRID_SIGNATURE_HEADER, SENDGRID_TIMESTAMP_HEADER, TWILIO_SIGNATURE_HEADER,
+};
+use crate::reputation::{ReputationAttester, ReputationService};
 use crate::secure_messages::{
-    CreateLegacyMessageRequest, LegacyMessageDeliveryService, MessageEncryptionService,
-    MessageKeyService,
+    verify_receipt_signature, CreateLegacyMessageRequest, DeliveryReceiptEvent,
+    LegacyMessageDeliveryService, MessageEncryptionService, MessageKeyService,
 };
 use crate::service::{
     ClaimPlanRequest, CreateEmergencyAccessGrantRequest, CreateEmergencyContactRequest,
@@ -39,13 +69,23 @@ use crate::service::{
     UnpausePlanRequest, UpdateEmergencyContactRequest,
 };
 use crate::stress_testing::StressTestingEngine;
-use crate::will_compliance::{ValidationResult, WillComplianceService};
-use crate::will_pdf::{WillDocumentInput, WillPdfService, WillTemplate};
+use crate::will_anchor::{AnchorTransactionQuery, SubmitWalletSignatureRequest, WillAnchorService};
+use crate::will_assembly::{WillGenerationOptions, WillInputAssembler};
+use crate::will_bundle::WillBundleService;
+use crate::will_compliance::{ExecutionFacts, ValidationResult, WillComplianceService};
+use crate::will_pdf::{WillDocumentInput, WillPdfService};
+use crate::will_revision::{
+    CreateCodicilRequest, RevokeWillRequest, VersionDiffQuery, WillRevisionService,
+};
 use crate::will_signature::{
     SigningChallengeRequest, SubmitSignatureRequest, WillSignatureService,
 };
+use crate::will_template::{
+    CreateWillTemplateRequest, UpdateWillTemplateRequest, WillTemplateQuery, WillTemplateService,
+};
 use crate::will_version::{PaginatedVersions, PaginationParams, WillVersionService};
 use crate::witness::{InviteWitnessRequest, WitnessService, WitnessSignRequest};
+use crate::witness_invitation::{InvitationSignRequest, WitnessInvitationService};
 use crate::yield_service::{DefaultOnChainYieldService, OnChainYieldService};
 use base64::Engine as _;
 
@@ -55,9 +95,19 @@ pub struct AppState {
     pub yield_service: Arc<dyn OnChainYieldService>,
     pub stress_testing_engine: Arc<StressTestingEngine>,
     pub insurance_fund_service: Arc<crate::insurance_fund::InsuranceFundService>,
-}
-
-pub async fn create_app(db: PgPool, config: Config) -> Result<Router, ApiError> {
+    pub content_storage: Arc<FileStorageService>,
+    pub blob_store: Arc<dyn BlobStore>,
+    pub url_signer: Arc<DownloadUrlSigner>,
+    pub key_manager: Arc<dyn KeyManager>,
+    pub alert_provider: Arc<dyn AlertProvider>,
+    pub witness_invitations: Arc<WitnessInvitationService>,
+    pub contract_reader: Arc<dyn InheritanceContractReader>,
+    pub liquidation_auctions: Arc<LiquidationAuctionService>,
+    pub reputation_attester: Option<Arc<ReputationAttester>>,
+}
+
+/// Price feed with the default feeds registered
+pub async fn default_price_feed(db: &PgPool) -> Arc<crate::price_feed::DefaultPriceFeedService> {
     let price_feed = Arc::new(crate::price_feed::DefaultPriceFeedService::new(
         db.clone(),
         3600,
@@ -65,14 +115,61 @@ pub async fn create_app(db: PgPool, config: Config) -> Result<Router, ApiError>
     if let Err(e) = price_feed.initialize_defaults().await {
         tracing::warn!("Failed to initialize default price feeds: {}", e);
     }
+    price_feed
+}
 
-    let risk_engine = Arc::new(crate::risk_engine::RiskEngine::new(
+pub async fn create_app(db: PgPool, config: Config) -> Result<Router, ApiError> {
+    let price_feed = default_price_feed(&db).await;
+    create_app_with_price_feed(db, config, price_feed).await
+}
+
+/// Build the router around `price_feed`, which background price workers
+/// started by the caller share so the API sees their refreshed cache
+pub async fn create_app_with_price_feed(
+    db: PgPool,
+    config: Config,
+    price_feed: Arc<crate::price_feed::DefaultPriceFeedService>,
+) -> Result<Router, ApiError> {
+    build_app(db, config, price_feed, contract_reader_from_env()?).await
+}
+
+/// Build the router reading the inheritance contract through `contract_reader`
+/// instead of the one configured in the environment
+pub async fn create_app_with_contract_reader(
+    db: PgPool,
+    config: Config,
+    contract_reader: Arc<dyn InheritanceContractReader>,
+) -> Result<Router, ApiError> {
+    let price_feed = default_price_feed(&db).await;
+    build_app(db, config, price_feed, contract_reader).await
+}
+
+async fn build_app(
+    db: PgPool,
+    config: Config,
+    price_feed: Arc<crate::price_feed::DefaultPriceFeedService>,
+    contract_reader: Arc<dyn InheritanceContractReader>,
+) -> Result<Router, ApiError> {
+    let risk_engine = Arc::new(crate::risk_engine::RiskEngine::from_env(
         db.clone(),
         price_feed.clone(),
-        rust_decimal::Decimal::new(12, 1),
     ));
     risk_engine.clone().start();
 
+    let liquidation_auctions = Arc::new(LiquidationAuctionService::new(
+        db.clone(),
+        price_feed.clone(),
+        AuctionParams::from_env(),
+    ));
+
+    let reputation_attester = match ReputationAttester::from_env() {
+        Ok(attester) => attester.map(Arc::new),
+        Err(e) => {
+            tracing::warn!("Reputation attestations disabled: {}", e);
+            None
+        }
+    };
+
     let yield_service = Arc::new(DefaultOnChainYieldService::new());
 
     let stress_testing_engine = Arc::new(StressTestingEngine::new(
@@ -81,16 +178,36 @@ pub async fn create_app(db: PgPool, config: Config) -> Result<Router, ApiError>
         risk_engine,
     ));
 
-    let insurance_fund_service =
-        Arc::new(crate::insurance_fund::InsuranceFundService::new(db.clone()));
+    let insurance_fund_service = Arc::new(crate::insurance_fund::InsuranceFundService::new(
+        db.clone(),
+        crate::insurance_fund_contract::insurance_fund_contract_from_env()?,
+        contract_reader.clone(),
+    ));
     insurance_fund_service.clone().start();
 
+    let url_signer = Arc::new(DownloadUrlSigner::from_env()?);
+
+    let alert_provider = alert_provider_from_env()?;
+    let witness_invitations = Arc::new(WitnessInvitationService::from_env(
+        db.clone(),
+        alert_provider.clone(),
+    )?);
+
     let state = Arc::new(AppState {
         db: db.clone(),
         config,
         yield_service,
         stress_testing_engine,
         insurance_fund_service,
+        content_storage: Arc::new(FileStorageService::from_env()),
+        blob_store: blob_store_from_env()?,
+        url_signer,
+        key_manager: key_manager_from_env()?,
+        alert_provider,
+        witness_invitations,
+        contract_reader,
+        liquidation_auctions,
+        reputation_attester,
     });
 
     // Rate limiting configuration
@@ -136,20 +253,40 @@ pub async fn create_app(db: PgPool, config: Config) -> Result<Router, ApiError>
             "/api/messages/legacy",
             post(create_legacy_message).get(list_legacy_messages),
         )
+        .route("/api/messages/legacy/e2e", post(create_e2e_legacy_message))
+        .route("/api/messages/recipient-key", put(register_recipient_key))
+        .route("/api/messages/recipient-keys", get(lookup_recipient_key))
         .route(
             "/api/messages/legacy/vault/:vault_id",
             get(list_vault_legacy_messages),
         )
+        .route(
+            "/api/messages/legacy/:message_id/deliveries",
+            get(list_legacy_message_deliveries),
+        )
+        .route(
+            "/api/messages/legacy/claim/:token",
+            post(claim_legacy_message),
+        )
+        .route(
+            "/api/messages/legacy/deliveries/receipts",
+            post(receive_legacy_delivery_receipt),
+        )
+        .route(
+            "/api/messages/legacy/deliveries/receipts/sendgrid",
+            post(receive_sendgrid_delivery_events),
+        )
+        .route(
+            "/api/messages/legacy/deliveries/receipts/twilio",
+            post(receive_twilio_delivery_status),
+        )
         .route("/api/admin/messages/keys", get(list_message_keys))
         .route("/api/admin/messages/keys/rotate", post(rotate_message_key))
         .route(
             "/api/admin/messages/delivery/process",
             post(process_legacy_message_delivery),
         )
-        .route(
-            "/api/admin/messages/audit",
-            get(get_message_audit_logs),
-        )
+        .route("/api/admin/messages/audit", get(get_message_audit_logs))
         .route(
             "/api/admin/messages/audit/summary",
             get(get_message_audit_summary),
@@ -203,6 +340,14 @@ pub async fn create_app(db: PgPool, config: Config) -> Result<Router, ApiError>
             "/api/emergency/access/sessions",
             post(start_emergency_session).get(list_active_emergency_sessions),
         )
+        .route(
+            "/api/emergency/access/sessions/contact",
+            post(start_contact_emergency_session),
+        )
+        .route(
+            "/api/plans/:plan_id/emergency-withdrawals",
+            post(request_emergency_withdrawal),
+        )
         .route(
             "/api/emergency/access/sessions/:session_id/heartbeat",
             put(heartbeat_emergency_session),
@@ -216,12 +361,29 @@ pub async fn create_app(db: PgPool, config: Config) -> Result<Router, ApiError>
         .route("/api/loans/simulations", get(get_user_simulations))
         .route("/api/loans/simulations/:simulation_id", get(get_simulation))
         .route("/api/reputation", get(get_user_reputation))
+        .route("/api/reputation/explain", get(explain_user_reputation))
+        .route(
+            "/api/reputation/attestation",
+            post(issue_reputation_attestation),
+        )
         // ── Loan Lifecycle Tracker ─────────────────────────────────────────────
         .route("/api/loans/lifecycle", post(create_lifecycle_loan))
         .route("/api/loans/lifecycle", get(list_lifecycle_loans))
         .route("/api/loans/lifecycle/summary", get(get_lifecycle_summary))
         .route("/api/loans/lifecycle/:id", get(get_lifecycle_loan))
         .route("/api/loans/lifecycle/:id/repay", post(repay_lifecycle_loan))
+        .route("/api/loans/:id/health", get(get_loan_health_history))
+        .route("/api/admin/risk/portfolio", get(get_portfolio_risk))
+        .route("/api/keeper/liquidatable", get(list_liquidatable_positions))
+        .route(
+            "/api/keeper/auctions",
+            get(list_liquidation_auctions).post(start_liquidation_auction),
+        )
+        .route("/api/keeper/auctions/:id", get(get_liquidation_auction))
+        .route(
+            "/api/keeper/auctions/:id/bids",
+            post(bid_liquidation_auction),
+        )
         .route(
             "/api/admin/loans/lifecycle/:id/liquidate",
             post(liquidate_lifecycle_loan),
@@ -303,6 +465,14 @@ pub async fn create_app(db: PgPool, config: Config) -> Result<Router, ApiError>
             get(get_insurance_fund_dashboard),
         )
         .route("/api/admin/insurance-funds", get(get_all_insurance_funds))
+        .route(
+            "/api/admin/insurance-fund/policies",
+            get(get_insurance_claim_policies),
+        )
+        .route(
+            "/api/admin/insurance-fund/policies/:claim_type",
+            put(update_insurance_claim_policy),
+        )
         .route(
             "/api/admin/insurance-fund/:fund_id",
             get(get_insurance_fund),
@@ -323,6 +493,10 @@ pub async fn create_app(db: PgPool, config: Config) -> Result<Router, ApiError>
             "/api/admin/insurance-fund/claims/:claim_id",
             get(get_insurance_claim),
         )
+        .route(
+            "/api/admin/insurance-fund/claims/:claim_id/adjudication",
+            get(get_insurance_claim_adjudication),
+        )
         .route(
             "/api/admin/insurance-fund/claims/:claim_id/process",
             post(process_insurance_claim),
@@ -337,7 +511,52 @@ pub async fn create_app(db: PgPool, config: Config) -> Result<Router, ApiError>
             "/api/plans/:plan_id/will/generate",
             post(generate_will_document),
         )
+        .route("/api/users/me/legal-name", put(update_legal_name))
         .route("/api/will/documents/:document_id", get(get_will_document))
+        .route("/api/will/templates", get(list_will_templates))
+        .route(
+            "/api/admin/will/templates",
+            get(admin_list_will_templates).post(admin_create_will_template),
+        )
+        .route(
+            "/api/admin/will/templates/:template_id",
+            get(admin_get_will_template)
+                .put(admin_update_will_template)
+                .delete(admin_retire_will_template),
+        )
+        .route(
+            "/api/will/documents/:document_id/attested",
+            get(get_attested_will_document),
+        )
+        .route(
+            "/api/will/documents/:document_id/anchoring",
+            get(get_will_anchor_status),
+        )
+        .route(
+            "/api/will/documents/:document_id/bundle",
+            get(download_will_bundle),
+        )
+        .route(
+            "/api/will/anchoring/signatures",
+            get(list_will_anchor_signatures),
+        )
+        .route(
+            "/api/will/anchoring/:anchor_id/signature",
+            post(submit_will_anchor_signature),
+        )
+        .route("/api/admin/will/anchoring", get(admin_list_will_anchors))
+        .route(
+            "/api/admin/will/anchoring/drift",
+            get(admin_list_will_anchor_drift),
+        )
+        .route(
+            "/api/admin/will/anchoring/reconcile",
+            post(admin_reconcile_will_anchors),
+        )
+        .route(
+            "/api/admin/will/anchoring/:anchor_id/retry",
+            post(admin_retry_will_anchor),
+        )
         .route(
             "/api/plans/:plan_id/will/documents",
             get(list_will_documents),
@@ -356,6 +575,19 @@ pub async fn create_app(db: PgPool, config: Config) -> Result<Router, ApiError>
             "/api/plans/:plan_id/will/versions/:version_number/finalize",
             put(finalize_will_version),
         )
+        .route(
+            "/api/plans/:plan_id/will/versions/diff",
+            get(diff_will_versions),
+        )
+        .route("/api/plans/:plan_id/will/revoke", post(revoke_will))
+        .route(
+            "/api/plans/:plan_id/will/revocations",
+            get(list_will_revocations),
+        )
+        .route(
+            "/api/plans/:plan_id/will/codicils",
+            post(create_will_codicil),
+        )
         // ── Beneficiary Sync (Task 3) ─────────────────────────────────────────
         .route(
             "/api/plans/:plan_id/beneficiaries/sync",
@@ -388,6 +620,11 @@ pub async fn create_app(db: PgPool, config: Config) -> Result<Router, ApiError>
             "/api/will/documents/:document_id/backups",
             get(list_document_backups),
         )
+        .route("/api/admin/documents/keys", get(get_document_key_status))
+        .route(
+            "/api/admin/documents/keys/reencrypt",
+            post(reencrypt_document_keys),
+        )
         // -- Will Compliance Validation (Issue #330) --
         .route("/api/will/validate", post(validate_will_compliance))
         .route("/api/will/jurisdictions", get(list_jurisdictions))
@@ -395,6 +632,16 @@ pub async fn create_app(db: PgPool, config: Config) -> Result<Router, ApiError>
             "/api/will/jurisdictions/:jurisdiction",
             get(get_jurisdiction_rules),
         )
+        .route(
+            "/api/admin/will/jurisdictions",
+            get(admin_list_jurisdiction_rules).post(admin_create_jurisdiction_rules),
+        )
+        .route(
+            "/api/admin/will/jurisdictions/:rule_set_id",
+            get(admin_get_jurisdiction_rules)
+                .put(admin_update_jurisdiction_rules)
+                .delete(admin_retire_jurisdiction_rules),
+        )
         // -- Witness Verification (Issue #331) --------------------------------
         .route(
             "/api/will/documents/:document_id/witnesses",
@@ -412,6 +659,49 @@ pub async fn create_app(db: PgPool, config: Config) -> Result<Router, ApiError>
             "/api/will/witnesses/:witness_id/decline",
             post(decline_witness),
         )
+        .route(
+            "/api/will/witness-invitations/:token",
+            get(view_witness_invitation),
+        )
+        .route(
+            "/api/will/witness-invitations/:token/sign",
+            post(sign_witness_invitation),
+        )
+        .route(
+            "/api/will/witness-invitations/:token/decline",
+            post(decline_witness_invitation),
+        )
+        // -- Notarization ------------------------------------------------------
+        .route(
+            "/api/will/documents/:document_id/notarizations",
+            post(request_notarization).get(list_document_notarizations),
+        )
+        .route("/api/notary/requests", get(list_notary_queue))
+        .route(
+            "/api/notary/requests/:request_id/schedule",
+            post(schedule_notarization),
+        )
+        .route(
+            "/api/notary/requests/:request_id/start",
+            post(start_notarization_session),
+        )
+        .route(
+            "/api/notary/requests/:request_id/seal",
+            post(seal_notarization),
+        )
+        .route(
+            "/api/notary/requests/:request_id/reject",
+            post(reject_notarization),
+        )
+        .route("/api/notary/journal", get(get_notary_journal))
+        .route(
+            "/api/admin/notaries",
+            post(create_notary).get(list_notaries),
+        )
+        .route(
+            "/api/admin/notaries/:notary_id/status",
+            put(update_notary_status),
+        )
         // -- Legal Document Integrity Check (Issue #332) ----------------------
         .route(
             "/api/will/documents/:document_id/verify",
@@ -445,6 +735,10 @@ pub async fn create_app(db: PgPool, config: Config) -> Result<Router, ApiError>
             "/api/will/documents/:document_id/download",
             get(download_will_document),
         )
+        .route(
+            "/api/will/documents/:document_id/download-url",
+            post(create_will_document_download_url),
+        )
         .route(
             "/api/plans/:plan_id/will/documents/:version/download",
             get(download_will_document_by_version),
@@ -472,24 +766,39 @@ pub async fn create_app(db: PgPool, config: Config) -> Result<Router, ApiError>
         // -- Legacy Content Upload (Issue #XXX) -------------------------------
         .route(
             "/api/content/upload",
-            post(upload_legacy_content),
+            post(upload_legacy_content).layer(DefaultBodyLimit::max(
+                crate::legacy_content::MAX_FILE_SIZE + MULTIPART_OVERHEAD_BYTES,
+            )),
+        )
+        .route("/api/content/uploads", post(init_content_upload))
+        .route(
+            "/api/content/uploads/:session_id",
+            get(get_content_upload).delete(abort_content_upload),
+        )
+        .route(
+            "/api/content/uploads/:session_id/chunks",
+            put(upload_content_chunk)
+                .layer(DefaultBodyLimit::max(crate::legacy_content::MAX_CHUNK_SIZE)),
         )
         .route(
-            "/api/content",
-            get(list_user_content),
+            "/api/content/uploads/:session_id/complete",
+            post(complete_content_upload),
         )
+        .route("/api/content", get(list_user_content))
         .route(
             "/api/content/:content_id",
             get(get_content_by_id).delete(delete_content),
         )
+        .route("/api/content/:content_id/download", get(download_content))
         .route(
-            "/api/content/:content_id/download",
-            get(download_content),
+            "/api/content/:content_id/download-url",
+            post(create_content_download_url),
         )
         .route(
-            "/api/content/stats",
-            get(get_storage_stats),
+            "/api/admin/storage/migrate-blobs",
+            post(migrate_database_blobs),
         )
+        .route("/api/content/stats", get(get_storage_stats))
         .with_state(state);
 
     // Add price feed routes with separate state
@@ -506,6 +815,10 @@ pub async fn create_app(db: PgPool, config: Config) -> Result<Router, ApiError>
             "/api/prices/:asset_code/history",
             get(crate::price_feed_handlers::get_price_history),
         )
+        .route(
+            "/api/prices/:asset_code/averages",
+            get(crate::price_feed_handlers::get_price_averages),
+        )
         .route(
             "/api/prices/:asset_code/valuation/:amount",
             get(crate::price_feed_handlers::calculate_valuation),
@@ -530,6 +843,22 @@ pub async fn create_app(db: PgPool, config: Config) -> Result<Router, ApiError>
             "/api/admin/prices/feeds",
             get(crate::price_feed_handlers::get_active_feeds),
         )
+        .route(
+            "/api/admin/prices/:asset_code/schedule",
+            put(crate::price_feed_handlers::update_feed_schedule),
+        )
+        .route(
+            "/api/admin/prices/sources",
+            get(crate::price_feed_handlers::get_price_source_stats),
+        )
+        .route(
+            "/api/admin/prices/alerts",
+            get(crate::price_feed_handlers::list_price_source_alerts),
+        )
+        .route(
+            "/api/admin/prices/oracle-pushes",
+            get(crate::price_feed_handlers::list_oracle_price_pushes),
+        )
         .with_state(price_feed_state);
 
     Ok(app.merge(price_routes))
@@ -563,9 +892,17 @@ async fn create_plan(
 async fn get_plan(
     State(state): State<Arc<AppState>>,
     Path(plan_id): Path<Uuid>,
-    AuthenticatedUser(user): AuthenticatedUser,
+    principal: Principal,
 ) -> Result<Json<Value>, ApiError> {
-    let plan = PlanService::get_plan_by_id(&state.db, plan_id, user.user_id).await?;
+    let owner_id = principal
+        .owner_id(
+            &state.db,
+            EmergencyPermission::ViewPlan,
+            "plan_viewed",
+            json!({ "plan_id": plan_id }),
+        )
+        .await?;
+    let plan = PlanService::get_plan_by_id(&state.db, plan_id, owner_id).await?;
     match plan {
         Some(p) => Ok(Json(json!({
             "status": "success",
@@ -620,9 +957,17 @@ async fn create_legacy_message(
 
 async fn list_legacy_messages(
     State(state): State<Arc<AppState>>,
-    AuthenticatedUser(user): AuthenticatedUser,
+    principal: Principal,
 ) -> Result<Json<Value>, ApiError> {
-    let messages = MessageEncryptionService::list_owner_messages(&state.db, user.user_id).await?;
+    let owner_id = principal
+        .owner_id(
+            &state.db,
+            EmergencyPermission::ViewMessages,
+            "legacy_messages_listed",
+            json!({}),
+        )
+        .await?;
+    let messages = MessageEncryptionService::list_owner_messages(&state.db, owner_id).await?;
     Ok(Json(
         json!({ "status": "success", "data": messages, "count": messages.len() }),
     ))
@@ -631,10 +976,18 @@ async fn list_legacy_messages(
 async fn list_vault_legacy_messages(
     State(state): State<Arc<AppState>>,
     Path(vault_id): Path<i64>,
-    AuthenticatedUser(user): AuthenticatedUser,
+    principal: Principal,
 ) -> Result<Json<Value>, ApiError> {
+    let owner_id = principal
+        .owner_id(
+            &state.db,
+            EmergencyPermission::ViewMessages,
+            "legacy_messages_listed",
+            json!({ "vault_id": vault_id }),
+        )
+        .await?;
     let messages =
-        MessageEncryptionService::list_vault_messages(&state.db, user.user_id, vault_id).await?;
+        MessageEncryptionService::list_vault_messages(&state.db, owner_id, vault_id).await?;
     Ok(Json(
         json!({ "status": "success", "data": messages, "count": messages.len() }),
     ))
@@ -654,23 +1007,176 @@ async fn rotate_message_key(
     State(state): State<Arc<AppState>>,
     AuthenticatedAdmin(admin): AuthenticatedAdmin,
 ) -> Result<Json<Value>, ApiError> {
-    let key = MessageKeyService::rotate_active_key(&state.db, admin.admin_id).await?;
+    let rotation = MessageKeyService::rotate_active_key(&state.db, admin.admin_id).await?;
     Ok(Json(json!({
         "status": "success",
         "message": "Message encryption key rotated",
-        "data": key
+        "data": rotation
     })))
 }
 
+/// Store a legacy message encrypted client-side for its recipients
+///
+/// `POST /api/messages/legacy/e2e`
+async fn create_e2e_legacy_message(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    Json(req): Json<CreateE2eMessageRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let message = E2eMessageService::create(
+        &state.db,
+        state.contract_reader.as_ref(),
+        user.user_id,
+        &req,
+    )
+    .await?;
+    Ok(Json(json!({ "status": "success", "data": message })))
+}
+
+/// Publish the caller's X25519 key for receiving end-to-end messages
+///
+/// `PUT /api/messages/recipient-key`
+async fn register_recipient_key(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    Json(req): Json<RegisterRecipientKeyRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let key = RecipientKeyService::register(&state.db, user.user_id, &req).await?;
+    Ok(Json(json!({ "status": "success", "data": key })))
+}
+
+/// Look up the X25519 key of a beneficiary on one of the caller's plans, by
+/// contact email or Stellar address
+///
+/// `GET /api/messages/recipient-keys?contact=…|stellar_address=…`
+async fn lookup_recipient_key(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    Query(query): Query<RecipientKeyQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let key = RecipientKeyService::lookup(&state.db, user.user_id, &query).await?;
+    Ok(Json(json!({ "status": "success", "data": key })))
+}
+
 async fn process_legacy_message_delivery(
     State(state): State<Arc<AppState>>,
     AuthenticatedAdmin(_admin): AuthenticatedAdmin,
 ) -> Result<Json<Value>, ApiError> {
-    let delivery_service = LegacyMessageDeliveryService::new(state.db.clone());
+    let delivery_service =
+        LegacyMessageDeliveryService::new(state.db.clone(), state.alert_provider.clone());
     let result = delivery_service.process_due_messages().await?;
     Ok(Json(json!({ "status": "success", "data": result })))
 }
 
+/// Delivery attempts and receipts for one of the caller's legacy messages
+///
+/// `GET /api/messages/legacy/:message_id/deliveries`
+async fn list_legacy_message_deliveries(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    Path(message_id): Path<Uuid>,
+) -> Result<Json<Value>, ApiError> {
+    let deliveries =
+        LegacyMessageDeliveryService::list_deliveries(&state.db, user.user_id, message_id).await?;
+    Ok(Json(
+        json!({ "status": "success", "data": deliveries, "count": deliveries.len() }),
+    ))
+}
+
+/// Beneficiary: redeem a claim link and read the message
+///
+/// `POST /api/messages/legacy/claim/:token`
+async fn claim_legacy_message(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    Path(token): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let claimed = LegacyMessageDeliveryService::claim_message(
+        &state.db,
+        state.contract_reader.as_ref(),
+        &token,
+        user.user_id,
+        &user.email,
+    )
+    .await?;
+    Ok(Json(json!({ "status": "success", "data": claimed })))
+}
+
+/// Provider webhook for delivery receipts and bounces, signed with
+/// `ALERT_WEBHOOK_SECRET` in the `X-Webhook-Signature` header
+///
+/// `POST /api/messages/legacy/deliveries/receipts`
+async fn receive_legacy_delivery_receipt(
+    State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+    body: axum::body::Bytes,
+) -> Result<Json<Value>, ApiError> {
+    let signature = headers
+        .get("x-webhook-signature")
+        .and_then(|v| v.to_str().ok())
+        .ok_or(ApiError::Unauthorized)?;
+    let secret = std::env::var("ALERT_WEBHOOK_SECRET").unwrap_or_default();
+    verify_receipt_signature(secret.as_bytes(), &body, signature)?;
+
+    let event: DeliveryReceiptEvent = serde_json::from_slice(&body)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid receipt payload: {}", e)))?;
+    let delivery = LegacyMessageDeliveryService::record_receipt(&state.db, &event).await?;
+    Ok(Json(json!({ "status": "success", "data": delivery })))
+}
+
+/// SendGrid Event Webhook, verified with `SENDGRID_WEBHOOK_PUBLIC_KEY`
+///
+/// `POST /api/messages/legacy/deliveries/receipts/sendgrid`
+async fn receive_sendgrid_delivery_events(
+    State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+    body: axum::body::Bytes,
+) -> Result<Json<Value>, ApiError> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .ok_or(ApiError::Unauthorized)
+    };
+    let public_key = std::env::var("SENDGRID_WEBHOOK_PUBLIC_KEY").unwrap_or_default();
+    verify_sendgrid_signature(
+        &public_key,
+        header(SENDGRID_TIMESTAMP_HEADER)?,
+        &body,
+        header(SENDGRID_SIGNATURE_HEADER)?,
+    )?;
+
+    let events = sendgrid_receipts(&body)?;
+    let applied = LegacyMessageDeliveryService::record_receipts(&state.db, &events).await?;
+    Ok(Json(
+        json!({ "status": "success", "data": { "received": events.len(), "applied": applied } }),
+    ))
+}
+
+/// Twilio message status callback, verified against `TWILIO_AUTH_TOKEN` and
+/// the `TWILIO_STATUS_CALLBACK_URL` Twilio was given
+///
+/// `POST /api/messages/legacy/deliveries/receipts/twilio`
+async fn receive_twilio_delivery_status(
+    State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+    Form(params): Form<Vec<(String, String)>>,
+) -> Result<Json<Value>, ApiError> {
+    let signature = headers
+        .get(TWILIO_SIGNATURE_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .ok_or(ApiError::Unauthorized)?;
+    let auth_token = std::env::var("TWILIO_AUTH_TOKEN").unwrap_or_default();
+    let callback_url = std::env::var("TWILIO_STATUS_CALLBACK_URL").unwrap_or_default();
+    verify_twilio_signature(&auth_token, &callback_url, &params, signature)?;
+
+    let events: Vec<DeliveryReceiptEvent> = twilio_receipt(&params).into_iter().collect();
+    let applied = LegacyMessageDeliveryService::record_receipts(&state.db, &events).await?;
+    Ok(Json(
+        json!({ "status": "success", "data": { "applied": applied } }),
+    ))
+}
+
 // ─── Message Access Audit Handlers ───────────────────────────────────────────
 
 async fn get_message_audit_logs(
@@ -679,7 +1185,9 @@ async fn get_message_audit_logs(
     Query(filters): Query<MessageAuditFilters>,
 ) -> Result<Json<Value>, ApiError> {
     let logs = MessageAccessAuditService::get_logs(&state.db, &filters).await?;
-    Ok(Json(json!({ "status": "success", "data": logs, "count": logs.len() })))
+    Ok(Json(
+        json!({ "status": "success", "data": logs, "count": logs.len() }),
+    ))
 }
 
 async fn get_message_audit_summary(
@@ -696,9 +1204,10 @@ async fn search_message_audit_logs(
     Query(params): Query<SearchAuditParams>,
 ) -> Result<Json<Value>, ApiError> {
     let limit = params.limit.unwrap_or(100);
-    let logs =
-        MessageAccessAuditService::search_logs(&state.db, &params.q, limit).await?;
-    Ok(Json(json!({ "status": "success", "data": logs, "count": logs.len() })))
+    let logs = MessageAccessAuditService::search_logs(&state.db, &params.q, limit).await?;
+    Ok(Json(
+        json!({ "status": "success", "data": logs, "count": logs.len() }),
+    ))
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -712,17 +1221,17 @@ async fn get_message_access_history(
     AuthenticatedUser(_user): AuthenticatedUser,
     Path(message_id): Path<Uuid>,
 ) -> Result<Json<Value>, ApiError> {
-    let logs =
-        MessageAccessAuditService::get_message_logs(&state.db, message_id, None).await?;
-    Ok(Json(json!({ "status": "success", "data": logs, "count": logs.len() })))
+    let logs = MessageAccessAuditService::get_message_logs(&state.db, message_id, None).await?;
+    Ok(Json(
+        json!({ "status": "success", "data": logs, "count": logs.len() }),
+    ))
 }
 
 async fn get_my_message_activity(
     State(state): State<Arc<AppState>>,
     AuthenticatedUser(user): AuthenticatedUser,
 ) -> Result<Json<Value>, ApiError> {
-    let activity =
-        MessageAccessAuditService::get_user_activity(&state.db, user.user_id).await?;
+    let activity = MessageAccessAuditService::get_user_activity(&state.db, user.user_id).await?;
     Ok(Json(json!({ "status": "success", "data": activity })))
 }
 
@@ -854,6 +1363,43 @@ async fn start_emergency_session(
     ))
 }
 
+/// Emergency contact: start a session on a grant naming the caller
+///
+/// `POST /api/emergency/access/sessions/contact`
+async fn start_contact_emergency_session(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    Json(req): Json<StartSessionRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let session =
+        EmergencySessionService::start_contact_session(&state.db, &user.email, &req).await?;
+    Ok(Json(
+        json!({ "status": "success", "data": session, "message": "Session started" }),
+    ))
+}
+
+/// Emergency contact: request a withdrawal from the owner's plan, capped per
+/// grant at the contract's emergency transfer limit. The request is recorded
+/// as pending; funds move only when the contract transfer executes.
+///
+/// `POST /api/plans/:plan_id/emergency-withdrawals`
+async fn request_emergency_withdrawal(
+    State(state): State<Arc<AppState>>,
+    Path(plan_id): Path<Uuid>,
+    principal: EmergencyPrincipal,
+    Json(req): Json<EmergencyWithdrawalRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let withdrawal = EmergencyWithdrawalService::request(
+        &state.db,
+        state.contract_reader.as_ref(),
+        &principal,
+        plan_id,
+        &req,
+    )
+    .await?;
+    Ok(Json(json!({ "status": "success", "data": withdrawal })))
+}
+
 async fn heartbeat_emergency_session(
     State(state): State<Arc<AppState>>,
     AuthenticatedUser(user): AuthenticatedUser,
@@ -987,14 +1533,56 @@ async fn get_user_reputation(
     State(state): State<Arc<AppState>>,
     AuthenticatedUser(user): AuthenticatedUser,
 ) -> Result<Json<Value>, ApiError> {
-    let reputation =
-        crate::reputation::ReputationService::get_reputation(&state.db, user.user_id).await?;
+    let reputation = ReputationService::get_reputation(&state.db, user.user_id).await?;
     Ok(Json(json!({
         "status": "success",
         "data": reputation
     })))
 }
 
+/// Break the current user's score down by factor, with the loan terms it
+/// earns and the points needed for the next tier
+async fn explain_user_reputation(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedUser(user): AuthenticatedUser,
+) -> Result<Json<Value>, ApiError> {
+    let explanation = ReputationService::explain(&state.db, user.user_id).await?;
+    Ok(Json(json!({
+        "status": "success",
+        "data": explanation
+    })))
+}
+
+/// Sign the current user's tier for the lending contract. The borrower
+/// submits it with `submit_reputation` to borrow at their tier's
+/// collateral ratio.
+async fn issue_reputation_attestation(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedUser(user): AuthenticatedUser,
+) -> Result<Json<Value>, ApiError> {
+    let attester = state.reputation_attester.as_ref().ok_or_else(|| {
+        ApiError::BadRequest("Reputation attestations are not configured".to_string())
+    })?;
+    let wallet = ReputationService::wallet_address(&state.db, user.user_id).await?;
+
+    let mut conn = state.db.acquire().await?;
+    let breakdown = ReputationService::assess(&mut conn, user.user_id).await?;
+    let attestation = attester.attest(&wallet, &breakdown).await?;
+    crate::notifications::AuditLogService::log(
+        &mut *conn,
+        Some(user.user_id),
+        crate::notifications::audit_action::REPUTATION_ATTESTED,
+        Some(user.user_id),
+        Some(crate::notifications::entity_type::USER),
+    )
+    .await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": attestation
+    })))
+}
+
 // Loan Lifecycle Endpoints
 
 /// Open a new loan in the `active` state.
@@ -1053,25 +1641,160 @@ async fn get_lifecycle_loan(
     Ok(Json(json!({ "status": "success", "data": record })))
 }
 
-/// Apply a repayment to a loan.  When cumulative repayments reach or exceed
-/// the principal the loan transitions to `repaid`.
+/// Health factor history of a lifecycle loan, or of the on-chain position
+/// against a plan, newest first.
 ///
-/// `POST /api/loans/lifecycle/:id/repay`
-#[derive(serde::Deserialize)]
-struct RepayRequest {
-    amount: rust_decimal::Decimal,
-}
-
-async fn repay_lifecycle_loan(
+/// `GET /api/loans/:id/health?days=30`
+async fn get_loan_health_history(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
+    Query(query): Query<MetricsHistoryQuery>,
     AuthenticatedUser(user): AuthenticatedUser,
-    Json(req): Json<RepayRequest>,
 ) -> Result<Json<Value>, ApiError> {
-    let record = LoanLifecycleService::repay_loan(&state.db, id, user.user_id, req.amount).await?;
-    Ok(Json(json!({ "status": "success", "data": record })))
-}
-
+    let days = query.days.unwrap_or(30).clamp(1, 90) as i32;
+    let history =
+        crate::risk_engine::RiskEngine::get_health_history(&state.db, id, user.user_id, days)
+            .await?;
+    Ok(Json(json!({
+        "status": "success",
+        "data": {
+            "current": history.first(),
+            "history": history,
+        },
+        "count": history.len()
+    })))
+}
+
+/// Admin: Book-wide collateral exposure from the latest risk cycle.
+///
+/// `GET /api/admin/risk/portfolio`
+async fn get_portfolio_risk(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedAdmin(_admin): AuthenticatedAdmin,
+) -> Result<Json<Value>, ApiError> {
+    let snapshot = crate::risk_engine::RiskEngine::latest_portfolio_snapshot(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("No portfolio risk snapshot yet".to_string()))?;
+    Ok(Json(json!({ "status": "success", "data": snapshot })))
+}
+
+/// Keeper: Risky plan positions that can be put up for auction.
+///
+/// `GET /api/keeper/liquidatable`
+async fn list_liquidatable_positions(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedUser(_user): AuthenticatedUser,
+) -> Result<Json<Value>, ApiError> {
+    let positions = state.liquidation_auctions.list_liquidatable(None).await?;
+    Ok(Json(json!({
+        "status": "success",
+        "data": positions,
+        "count": positions.len()
+    })))
+}
+
+/// Keeper: Start a Dutch auction of a risky plan's collateral.
+///
+/// `POST /api/keeper/auctions`
+async fn start_liquidation_auction(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    Json(req): Json<StartAuctionRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let auction = state
+        .liquidation_auctions
+        .start_auction(req.plan_id, Some(user.user_id))
+        .await?;
+    Ok(Json(json!({ "status": "success", "data": auction })))
+}
+
+#[derive(serde::Deserialize)]
+struct AuctionListQuery {
+    status: Option<String>,
+}
+
+/// Keeper: Liquidation auctions, newest first, optionally by status.
+///
+/// `GET /api/keeper/auctions?status=active`
+async fn list_liquidation_auctions(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AuctionListQuery>,
+    AuthenticatedUser(_user): AuthenticatedUser,
+) -> Result<Json<Value>, ApiError> {
+    let auctions = state
+        .liquidation_auctions
+        .list_auctions(query.status.as_deref())
+        .await?;
+    let now = chrono::Utc::now();
+    let data: Vec<Value> = auctions
+        .iter()
+        .map(|a| json!({ "auction": a, "current_price_bps": a.price_bps_at(now) }))
+        .collect();
+    Ok(Json(json!({
+        "status": "success",
+        "data": data,
+        "count": data.len()
+    })))
+}
+
+/// Keeper: An auction with its current price and bids.
+///
+/// `GET /api/keeper/auctions/:id`
+async fn get_liquidation_auction(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    AuthenticatedUser(_user): AuthenticatedUser,
+) -> Result<Json<Value>, ApiError> {
+    let (auction, bids) = state.liquidation_auctions.get_auction(id).await?;
+    Ok(Json(json!({
+        "status": "success",
+        "data": {
+            "current_price_bps": auction.price_bps_at(chrono::Utc::now()),
+            "auction": auction,
+            "bids": bids,
+        }
+    })))
+}
+
+/// Keeper: Repay up to `max_debt` of an auctioned loan for collateral at the
+/// current price.
+///
+/// `POST /api/keeper/auctions/:id/bids`
+async fn bid_liquidation_auction(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    Json(req): Json<PlaceBidRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let (auction, bid) = state
+        .liquidation_auctions
+        .place_bid(id, user.user_id, &req)
+        .await?;
+    Ok(Json(json!({
+        "status": "success",
+        "data": { "auction": auction, "bid": bid }
+    })))
+}
+
+/// Apply a repayment to a loan.  When cumulative repayments reach or exceed
+/// the principal the loan transitions to `repaid`.
+///
+/// `POST /api/loans/lifecycle/:id/repay`
+#[derive(serde::Deserialize)]
+struct RepayRequest {
+    amount: rust_decimal::Decimal,
+}
+
+async fn repay_lifecycle_loan(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    Json(req): Json<RepayRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let record = LoanLifecycleService::repay_loan(&state.db, id, user.user_id, req.amount).await?;
+    Ok(Json(json!({ "status": "success", "data": record })))
+}
+
 /// Admin: forcefully liquidate a loan.
 ///
 /// `POST /api/admin/loans/lifecycle/:id/liquidate`
@@ -1329,63 +2052,308 @@ async fn update_protocol_parameter(
 
 // ─── Will PDF & Template Engine Handlers (Tasks 1 & 2) ───────────────────────
 
-#[derive(serde::Deserialize)]
-struct GenerateWillRequest {
-    owner_name: String,
-    owner_wallet: String,
-    vault_id: String,
-    beneficiaries: Vec<crate::will_pdf::BeneficiaryEntry>,
-    execution_rules: Option<String>,
-    template: Option<String>,
-    jurisdiction: Option<String>,
-    will_hash_reference: Option<String>,
-}
-
+/// Generate a will from the plan's stored and on-chain state. The client
+/// only chooses the template, locale, jurisdiction and execution rules.
+///
+/// `POST /api/plans/:plan_id/will/generate`
 async fn generate_will_document(
     State(state): State<Arc<AppState>>,
     Path(plan_id): Path<Uuid>,
     AuthenticatedUser(user): AuthenticatedUser,
-    Json(req): Json<GenerateWillRequest>,
+    Json(options): Json<WillGenerationOptions>,
 ) -> Result<Json<Value>, ApiError> {
-    use std::str::FromStr;
-    let template = req
-        .template
-        .as_deref()
-        .map(WillTemplate::from_str)
-        .transpose()?
-        .unwrap_or(WillTemplate::Formal);
-
-    let input = WillDocumentInput {
+    let input = WillInputAssembler::assemble(
+        &state.db,
+        state.contract_reader.as_ref(),
+        user.user_id,
         plan_id,
-        owner_name: req.owner_name,
-        owner_wallet: req.owner_wallet,
-        vault_id: req.vault_id,
-        beneficiaries: req.beneficiaries,
-        execution_rules: req.execution_rules,
-        template,
-        jurisdiction: req.jurisdiction,
-        will_hash_reference: req.will_hash_reference,
-    };
+        options,
+    )
+    .await?;
 
     let doc = WillPdfService::generate(&state.db, user.user_id, &input).await?;
     Ok(Json(json!({ "status": "success", "data": doc })))
 }
 
+#[derive(serde::Deserialize)]
+struct UpdateLegalNameRequest {
+    legal_name: String,
+}
+
+/// Set the name printed on the caller's wills
+///
+/// `PUT /api/users/me/legal-name`
+async fn update_legal_name(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    Json(req): Json<UpdateLegalNameRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let legal_name =
+        WillInputAssembler::set_legal_name(&state.db, user.user_id, &req.legal_name).await?;
+    Ok(Json(
+        json!({ "status": "success", "data": { "legal_name": legal_name } }),
+    ))
+}
+
+/// Active will templates with their locales and required fields
+///
+/// `GET /api/will/templates`
+async fn list_will_templates(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedUser(_user): AuthenticatedUser,
+    Query(query): Query<WillTemplateQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let query = WillTemplateQuery {
+        all_versions: false,
+        ..query
+    };
+    let templates = WillTemplateService::list(&state.db, &query).await?;
+    Ok(Json(
+        json!({ "status": "success", "data": templates, "count": templates.len() }),
+    ))
+}
+
+/// `GET /api/admin/will/templates`
+async fn admin_list_will_templates(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedAdmin(_admin): AuthenticatedAdmin,
+    Query(query): Query<WillTemplateQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let templates = WillTemplateService::list(&state.db, &query).await?;
+    Ok(Json(
+        json!({ "status": "success", "data": templates, "count": templates.len() }),
+    ))
+}
+
+/// `GET /api/admin/will/templates/:template_id`
+async fn admin_get_will_template(
+    State(state): State<Arc<AppState>>,
+    Path(template_id): Path<Uuid>,
+    AuthenticatedAdmin(_admin): AuthenticatedAdmin,
+) -> Result<Json<Value>, ApiError> {
+    let template = WillTemplateService::get(&state.db, template_id).await?;
+    Ok(Json(json!({ "status": "success", "data": template })))
+}
+
+/// Add a template, or a new version of an existing key and locale
+///
+/// `POST /api/admin/will/templates`
+async fn admin_create_will_template(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedAdmin(admin): AuthenticatedAdmin,
+    Json(req): Json<CreateWillTemplateRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let template = WillTemplateService::create(&state.db, admin.admin_id, &req).await?;
+    Ok(Json(json!({ "status": "success", "data": template })))
+}
+
+/// Publish an edited copy of a template as its next version
+///
+/// `PUT /api/admin/will/templates/:template_id`
+async fn admin_update_will_template(
+    State(state): State<Arc<AppState>>,
+    Path(template_id): Path<Uuid>,
+    AuthenticatedAdmin(admin): AuthenticatedAdmin,
+    Json(req): Json<UpdateWillTemplateRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let template =
+        WillTemplateService::update(&state.db, admin.admin_id, template_id, &req).await?;
+    Ok(Json(json!({ "status": "success", "data": template })))
+}
+
+/// Retire a template version; existing documents keep referencing it
+///
+/// `DELETE /api/admin/will/templates/:template_id`
+async fn admin_retire_will_template(
+    State(state): State<Arc<AppState>>,
+    Path(template_id): Path<Uuid>,
+    AuthenticatedAdmin(_admin): AuthenticatedAdmin,
+) -> Result<Json<Value>, ApiError> {
+    let template = WillTemplateService::retire(&state.db, template_id).await?;
+    Ok(Json(json!({ "status": "success", "data": template })))
+}
+
 async fn get_will_document(
+    State(state): State<Arc<AppState>>,
+    Path(document_id): Path<Uuid>,
+    principal: Principal,
+) -> Result<Json<Value>, ApiError> {
+    let owner_id = principal
+        .owner_id(
+            &state.db,
+            EmergencyPermission::DownloadDocuments,
+            "will_document_viewed",
+            json!({ "document_id": document_id }),
+        )
+        .await?;
+    let doc = WillPdfService::get_document(&state.db, document_id, owner_id).await?;
+    Ok(Json(json!({ "status": "success", "data": doc })))
+}
+
+/// On-chain anchoring status of a will document
+///
+/// `GET /api/will/documents/:document_id/anchoring`
+async fn get_will_anchor_status(
     State(state): State<Arc<AppState>>,
     Path(document_id): Path<Uuid>,
     AuthenticatedUser(user): AuthenticatedUser,
 ) -> Result<Json<Value>, ApiError> {
-    let doc = WillPdfService::get_document(&state.db, document_id, user.user_id).await?;
+    if WillPdfService::get_document_owner(&state.db, document_id).await? != user.user_id {
+        return Err(ApiError::NotFound(format!(
+            "Will document {document_id} not found"
+        )));
+    }
+    let status = WillAnchorService::document_status(&state.db, document_id).await?;
+    Ok(Json(json!({ "status": "success", "data": status })))
+}
+
+/// Anchoring transactions waiting for the caller's wallet to sign them
+///
+/// `GET /api/will/anchoring/signatures`
+async fn list_will_anchor_signatures(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedUser(user): AuthenticatedUser,
+) -> Result<Json<Value>, ApiError> {
+    let pending = WillAnchorService::pending_wallet_signatures(&state.db, user.user_id).await?;
+    Ok(Json(
+        json!({ "status": "success", "data": pending, "count": pending.len() }),
+    ))
+}
+
+/// Hand back a wallet-signed anchoring transaction for the worker to send
+///
+/// `POST /api/will/anchoring/:anchor_id/signature`
+async fn submit_will_anchor_signature(
+    State(state): State<Arc<AppState>>,
+    Path(anchor_id): Path<Uuid>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    Json(req): Json<SubmitWalletSignatureRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let anchor = WillAnchorService::submit_wallet_signature(
+        &state.db,
+        user.user_id,
+        anchor_id,
+        &req.signed_envelope,
+    )
+    .await?;
+    Ok(Json(json!({ "status": "success", "data": anchor })))
+}
+
+/// `GET /api/admin/will/anchoring?status=`
+async fn admin_list_will_anchors(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedAdmin(_admin): AuthenticatedAdmin,
+    Query(query): Query<AnchorTransactionQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let anchors = WillAnchorService::list(&state.db, &query).await?;
+    Ok(Json(
+        json!({ "status": "success", "data": anchors, "count": anchors.len() }),
+    ))
+}
+
+/// `GET /api/admin/will/anchoring/drift`
+async fn admin_list_will_anchor_drift(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedAdmin(_admin): AuthenticatedAdmin,
+) -> Result<Json<Value>, ApiError> {
+    let drift = WillAnchorService::list_drift(&state.db).await?;
+    Ok(Json(
+        json!({ "status": "success", "data": drift, "count": drift.len() }),
+    ))
+}
+
+/// Compare settled documents with the chain now instead of on the next pass
+///
+/// `POST /api/admin/will/anchoring/reconcile`
+async fn admin_reconcile_will_anchors(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedAdmin(_admin): AuthenticatedAdmin,
+) -> Result<Json<Value>, ApiError> {
+    let report = WillAnchorService::reconcile(&state.db, state.contract_reader.as_ref()).await?;
+    Ok(Json(json!({ "status": "success", "data": report })))
+}
+
+/// `POST /api/admin/will/anchoring/:anchor_id/retry`
+async fn admin_retry_will_anchor(
+    State(state): State<Arc<AppState>>,
+    Path(anchor_id): Path<Uuid>,
+    AuthenticatedAdmin(_admin): AuthenticatedAdmin,
+) -> Result<Json<Value>, ApiError> {
+    let anchor = WillAnchorService::retry(&state.db, anchor_id).await?;
+    Ok(Json(json!({ "status": "success", "data": anchor })))
+}
+
+/// Re-render a will with its current testator signatures and witnesses
+///
+/// `GET /api/will/documents/:document_id/attested`
+async fn get_attested_will_document(
+    State(state): State<Arc<AppState>>,
+    Path(document_id): Path<Uuid>,
+    principal: Principal,
+) -> Result<Json<Value>, ApiError> {
+    let owner_id = principal
+        .owner_id(
+            &state.db,
+            EmergencyPermission::DownloadDocuments,
+            "will_document_viewed",
+            json!({ "document_id": document_id, "attested": true }),
+        )
+        .await?;
+    let doc = WillPdfService::render_attested(&state.db, document_id, owner_id).await?;
     Ok(Json(json!({ "status": "success", "data": doc })))
 }
 
+/// Download a self-contained bundle for verifying a will offline
+///
+/// `GET /api/will/documents/:document_id/bundle`
+async fn download_will_bundle(
+    State(state): State<Arc<AppState>>,
+    Path(document_id): Path<Uuid>,
+    principal: Principal,
+) -> Result<axum::response::Response, ApiError> {
+    let owner_id = principal
+        .owner_id(
+            &state.db,
+            EmergencyPermission::DownloadDocuments,
+            "will_bundle_exported",
+            json!({ "document_id": document_id }),
+        )
+        .await?;
+    let bundle = WillBundleService::export(&state.db, document_id, owner_id).await?;
+    let body = serde_json::to_vec_pretty(&bundle)
+        .map_err(|e| ApiError::Internal(anyhow::anyhow!("Failed to encode bundle: {}", e)))?;
+
+    use axum::body::Body;
+    use axum::http::{header, Response, StatusCode};
+
+    let content_disposition = format!(
+        "attachment; filename=\"will_{}_v{}.bundle.json\"",
+        document_id, bundle.document.version
+    );
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::CONTENT_DISPOSITION, content_disposition)
+        .header(header::CACHE_CONTROL, "no-cache, no-store, must-revalidate")
+        .body(Body::from(body))
+        .map_err(|e| ApiError::Internal(anyhow::anyhow!("Failed to build response: {}", e)))
+}
+
 async fn list_will_documents(
     State(state): State<Arc<AppState>>,
     Path(plan_id): Path<Uuid>,
-    AuthenticatedUser(user): AuthenticatedUser,
+    principal: Principal,
 ) -> Result<Json<Value>, ApiError> {
-    let docs = WillPdfService::list_for_plan(&state.db, plan_id, user.user_id).await?;
+    let owner_id = principal
+        .owner_id(
+            &state.db,
+            EmergencyPermission::DownloadDocuments,
+            "will_documents_listed",
+            json!({ "plan_id": plan_id }),
+        )
+        .await?;
+    let docs = WillPdfService::list_for_plan(&state.db, plan_id, owner_id).await?;
     Ok(Json(
         json!({ "status": "success", "data": docs, "count": docs.len() }),
     ))
@@ -1396,14 +2364,21 @@ async fn list_will_documents(
 async fn list_will_versions(
     State(state): State<Arc<AppState>>,
     Path(plan_id): Path<Uuid>,
-    AuthenticatedUser(user): AuthenticatedUser,
+    principal: Principal,
     Query(params): Query<PaginationParams>,
 ) -> Result<Json<Value>, ApiError> {
+    let owner_id = principal
+        .owner_id(
+            &state.db,
+            EmergencyPermission::DownloadDocuments,
+            "will_versions_listed",
+            json!({ "plan_id": plan_id }),
+        )
+        .await?;
     let page = params.page.unwrap_or(1).max(1);
     let per_page = params.per_page.unwrap_or(10).clamp(1, 100);
     let (versions, total) =
-        WillVersionService::get_all_versions(&state.db, plan_id, user.user_id, page, per_page)
-            .await?;
+        WillVersionService::get_all_versions(&state.db, plan_id, owner_id, page, per_page).await?;
     Ok(Json(json!({
         "status": "success",
         "data": PaginatedVersions { versions, total, page, per_page }
@@ -1440,6 +2415,68 @@ async fn finalize_will_version(
     Ok(Json(json!({ "status": "success", "data": version })))
 }
 
+/// Beneficiary, allocation, clause and text changes between two versions
+///
+/// `GET /api/plans/:plan_id/will/versions/diff?from=&to=`
+async fn diff_will_versions(
+    State(state): State<Arc<AppState>>,
+    Path(plan_id): Path<Uuid>,
+    Query(query): Query<VersionDiffQuery>,
+    principal: Principal,
+) -> Result<Json<Value>, ApiError> {
+    let owner_id = principal
+        .owner_id(
+            &state.db,
+            EmergencyPermission::DownloadDocuments,
+            "will_versions_compared",
+            json!({ "plan_id": plan_id, "from": query.from, "to": query.to }),
+        )
+        .await?;
+    let diff =
+        WillRevisionService::diff(&state.db, plan_id, owner_id, query.from, query.to).await?;
+    Ok(Json(json!({ "status": "success", "data": diff })))
+}
+
+/// Revoke every finalized version of the plan's will
+///
+/// `POST /api/plans/:plan_id/will/revoke`
+async fn revoke_will(
+    State(state): State<Arc<AppState>>,
+    Path(plan_id): Path<Uuid>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    Json(req): Json<RevokeWillRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let revocation =
+        WillRevisionService::revoke(&state.db, plan_id, user.user_id, req.reason).await?;
+    Ok(Json(json!({ "status": "success", "data": revocation })))
+}
+
+/// `GET /api/plans/:plan_id/will/revocations`
+async fn list_will_revocations(
+    State(state): State<Arc<AppState>>,
+    Path(plan_id): Path<Uuid>,
+    AuthenticatedUser(user): AuthenticatedUser,
+) -> Result<Json<Value>, ApiError> {
+    let revocations =
+        WillRevisionService::list_revocations(&state.db, plan_id, user.user_id).await?;
+    Ok(Json(json!({ "status": "success", "data": revocations })))
+}
+
+/// Draft a codicil amending clauses of a finalized will. Codicils are kept
+/// off-chain: the contract records wills only, and their anchor status
+/// reads `off_chain`.
+///
+/// `POST /api/plans/:plan_id/will/codicils`
+async fn create_will_codicil(
+    State(state): State<Arc<AppState>>,
+    Path(plan_id): Path<Uuid>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    Json(req): Json<CreateCodicilRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let doc = WillRevisionService::create_codicil(&state.db, plan_id, user.user_id, &req).await?;
+    Ok(Json(json!({ "status": "success", "data": doc })))
+}
+
 // ─── Beneficiary Sync Handler (Task 3) ───────────────────────────────────────
 
 #[derive(serde::Deserialize)]
@@ -1450,9 +2487,26 @@ struct SyncBeneficiariesRequest {
 async fn sync_beneficiaries(
     State(state): State<Arc<AppState>>,
     Path(plan_id): Path<Uuid>,
-    AuthenticatedUser(_user): AuthenticatedUser,
+    principal: Principal,
     Json(req): Json<SyncBeneficiariesRequest>,
 ) -> Result<Json<Value>, ApiError> {
+    let owner_id = principal
+        .owner_id(
+            &state.db,
+            EmergencyPermission::ManageBeneficiaries,
+            "beneficiaries_synced",
+            json!({
+                "plan_id": plan_id,
+                "beneficiary_count": req.document_beneficiaries.len(),
+            }),
+        )
+        .await?;
+    if PlanService::get_plan_by_id(&state.db, plan_id, owner_id)
+        .await?
+        .is_none()
+    {
+        return Err(ApiError::NotFound(format!("Plan {} not found", plan_id)));
+    }
     let result =
         BeneficiarySyncService::sync_and_validate(&state.db, plan_id, &req.document_beneficiaries)
             .await?;
@@ -1511,8 +2565,15 @@ async fn encrypt_document(
         .decode(&doc.pdf_base64)
         .map_err(|e| ApiError::Internal(anyhow::anyhow!("Base64 decode error: {e}")))?;
 
-    DocumentStorageService::store_encrypted(&state.db, user.user_id, document_id, &content_bytes)
-        .await?;
+    DocumentStorageService::store_encrypted(
+        &state.db,
+        state.blob_store.as_ref(),
+        state.key_manager.as_ref(),
+        user.user_id,
+        document_id,
+        &content_bytes,
+    )
+    .await?;
 
     Ok(Json(json!({
         "status": "success",
@@ -1521,76 +2582,357 @@ async fn encrypt_document(
     })))
 }
 
-async fn decrypt_document(
+async fn decrypt_document(
+    State(state): State<Arc<AppState>>,
+    Path(document_id): Path<Uuid>,
+    AuthenticatedUser(user): AuthenticatedUser,
+) -> Result<Json<Value>, ApiError> {
+    let plaintext = DocumentStorageService::retrieve_decrypted(
+        &state.db,
+        state.blob_store.as_ref(),
+        state.key_manager.as_ref(),
+        user.user_id,
+        document_id,
+    )
+    .await?;
+    let encoded = base64::engine::general_purpose::STANDARD.encode(&plaintext);
+
+    Ok(Json(json!({
+        "status": "success",
+        "document_id": document_id,
+        "pdf_base64": encoded
+    })))
+}
+
+async fn create_document_backup(
+    State(state): State<Arc<AppState>>,
+    Path(document_id): Path<Uuid>,
+    AuthenticatedUser(user): AuthenticatedUser,
+) -> Result<Json<Value>, ApiError> {
+    let backup = DocumentStorageService::create_backup(
+        &state.db,
+        state.blob_store.as_ref(),
+        user.user_id,
+        document_id,
+    )
+    .await?;
+    Ok(Json(json!({ "status": "success", "data": backup })))
+}
+
+async fn list_document_backups(
+    State(state): State<Arc<AppState>>,
+    Path(document_id): Path<Uuid>,
+    AuthenticatedUser(user): AuthenticatedUser,
+) -> Result<Json<Value>, ApiError> {
+    let backups =
+        DocumentStorageService::list_backups(&state.db, user.user_id, document_id).await?;
+    Ok(Json(
+        json!({ "status": "success", "data": backups, "count": backups.len() }),
+    ))
+}
+
+/// Encrypted documents and backups per key-encryption key version
+///
+/// `GET /api/admin/documents/keys`
+async fn get_document_key_status(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedAdmin(_admin): AuthenticatedAdmin,
+) -> Result<Json<Value>, ApiError> {
+    let status = DocumentKeyRotationService::status(&state.db, state.key_manager.as_ref()).await?;
+    Ok(Json(json!({ "status": "success", "data": status })))
+}
+
+/// Move a batch of documents onto the active key-encryption key now
+///
+/// `POST /api/admin/documents/keys/reencrypt`
+async fn reencrypt_document_keys(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedAdmin(_admin): AuthenticatedAdmin,
+) -> Result<Json<Value>, ApiError> {
+    let rotation = DocumentKeyRotationService::new(
+        state.db.clone(),
+        state.blob_store.clone(),
+        state.key_manager.clone(),
+    );
+    let progress = rotation.run_batch().await?;
+    Ok(Json(json!({ "status": "success", "data": progress })))
+}
+
+// -- Will Compliance Validation Handlers (Issue #330) --
+
+#[derive(serde::Deserialize)]
+struct ValidateWillRequest {
+    #[serde(flatten)]
+    input: WillDocumentInput,
+    witness_count: u32,
+    /// Stored document whose notary seal and signed witnesses should count
+    /// towards compliance
+    document_id: Option<Uuid>,
+    /// `attested` (default) or `holographic`
+    execution_form: Option<String>,
+    /// Wallet addresses, emails or names of the attesting witnesses
+    #[serde(default)]
+    witnesses: Vec<String>,
+}
+
+async fn validate_will_compliance(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    Json(req): Json<ValidateWillRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let holographic = match req.execution_form.as_deref() {
+        None | Some("attested") => false,
+        Some("holographic") => true,
+        Some(other) => {
+            return Err(ApiError::BadRequest(format!(
+                "Invalid execution_form '{other}'; expected 'attested' or 'holographic'"
+            )))
+        }
+    };
+    let mut facts = ExecutionFacts {
+        witness_count: req.witness_count,
+        notarized: None,
+        holographic,
+        witnesses: req.witnesses,
+    };
+    if let Some(document_id) = req.document_id {
+        let owned: Option<Uuid> =
+            sqlx::query_scalar("SELECT id FROM will_documents WHERE id = $1 AND user_id = $2")
+                .bind(document_id)
+                .bind(user.user_id)
+                .fetch_optional(&state.db)
+                .await?;
+        if owned.is_none() {
+            return Err(ApiError::NotFound(format!(
+                "Will document {document_id} not found"
+            )));
+        }
+        facts.notarized = Some(NotarizationService::has_valid_seal(&state.db, document_id).await?);
+        let signed: Vec<String> = sqlx::query_scalar(
+            "SELECT COALESCE(wallet_address, email) FROM will_witnesses \
+             WHERE document_id = $1 AND status = 'signed'",
+        )
+        .bind(document_id)
+        .fetch_all(&state.db)
+        .await?;
+        facts.witnesses.extend(signed);
+    }
+
+    let rules = JurisdictionRuleService::resolve(
+        &state.db,
+        req.input.jurisdiction.as_deref().unwrap_or("GLOBAL"),
+    )
+    .await?;
+    let result: ValidationResult =
+        WillComplianceService::validate_with_rules(&req.input, &rules, &facts);
+    Ok(Json(json!({ "status": "success", "data": result })))
+}
+
+async fn list_jurisdictions(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedUser(_user): AuthenticatedUser,
+) -> Result<Json<Value>, ApiError> {
+    let jurisdictions = JurisdictionRuleService::catalog(&state.db).await?.codes();
+    Ok(Json(
+        json!({ "status": "success", "data": jurisdictions, "count": jurisdictions.len() }),
+    ))
+}
+
+async fn get_jurisdiction_rules(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedUser(_user): AuthenticatedUser,
+    Path(jurisdiction): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let rules = JurisdictionRuleService::resolve(&state.db, &jurisdiction).await?;
+    Ok(Json(json!({ "status": "success", "data": rules })))
+}
+
+/// `GET /api/admin/will/jurisdictions`
+async fn admin_list_jurisdiction_rules(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedAdmin(_admin): AuthenticatedAdmin,
+    Query(query): Query<RuleSetQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let rule_sets = JurisdictionRuleService::list(&state.db, &query).await?;
+    Ok(Json(
+        json!({ "status": "success", "data": rule_sets, "count": rule_sets.len() }),
+    ))
+}
+
+/// `GET /api/admin/will/jurisdictions/:rule_set_id`
+async fn admin_get_jurisdiction_rules(
+    State(state): State<Arc<AppState>>,
+    Path(rule_set_id): Path<Uuid>,
+    AuthenticatedAdmin(_admin): AuthenticatedAdmin,
+) -> Result<Json<Value>, ApiError> {
+    let rule_set = JurisdictionRuleService::get(&state.db, rule_set_id).await?;
+    Ok(Json(json!({ "status": "success", "data": rule_set })))
+}
+
+/// Add a jurisdiction, or a new version of an existing one
+///
+/// `POST /api/admin/will/jurisdictions`
+async fn admin_create_jurisdiction_rules(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedAdmin(admin): AuthenticatedAdmin,
+    Json(req): Json<CreateRuleSetRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let rule_set = JurisdictionRuleService::create(&state.db, admin.admin_id, &req).await?;
+    Ok(Json(json!({ "status": "success", "data": rule_set })))
+}
+
+/// Publish an edited copy of a rule set as its next version
+///
+/// `PUT /api/admin/will/jurisdictions/:rule_set_id`
+async fn admin_update_jurisdiction_rules(
+    State(state): State<Arc<AppState>>,
+    Path(rule_set_id): Path<Uuid>,
+    AuthenticatedAdmin(admin): AuthenticatedAdmin,
+    Json(req): Json<UpdateRuleSetRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let rule_set =
+        JurisdictionRuleService::update(&state.db, admin.admin_id, rule_set_id, &req).await?;
+    Ok(Json(json!({ "status": "success", "data": rule_set })))
+}
+
+/// Retire a rule set version; the previous active version takes over
+///
+/// `DELETE /api/admin/will/jurisdictions/:rule_set_id`
+async fn admin_retire_jurisdiction_rules(
+    State(state): State<Arc<AppState>>,
+    Path(rule_set_id): Path<Uuid>,
+    AuthenticatedAdmin(_admin): AuthenticatedAdmin,
+) -> Result<Json<Value>, ApiError> {
+    let rule_set = JurisdictionRuleService::retire(&state.db, rule_set_id).await?;
+    Ok(Json(json!({ "status": "success", "data": rule_set })))
+}
+
+// -- Notarization --------------------------------------------------------------
+
+/// `POST /api/will/documents/:document_id/notarizations`
+async fn request_notarization(
     State(state): State<Arc<AppState>>,
     Path(document_id): Path<Uuid>,
     AuthenticatedUser(user): AuthenticatedUser,
+    Json(req): Json<RequestNotarizationRequest>,
 ) -> Result<Json<Value>, ApiError> {
-    let plaintext =
-        DocumentStorageService::retrieve_decrypted(&state.db, user.user_id, document_id).await?;
-    let encoded = base64::engine::general_purpose::STANDARD.encode(&plaintext);
-
-    Ok(Json(json!({
-        "status": "success",
-        "document_id": document_id,
-        "pdf_base64": encoded
-    })))
+    let request =
+        NotarizationService::request_notarization(&state.db, user.user_id, document_id, &req)
+            .await?;
+    Ok(Json(json!({ "status": "success", "data": request })))
 }
 
-async fn create_document_backup(
+/// `GET /api/will/documents/:document_id/notarizations`
+async fn list_document_notarizations(
     State(state): State<Arc<AppState>>,
     Path(document_id): Path<Uuid>,
     AuthenticatedUser(user): AuthenticatedUser,
 ) -> Result<Json<Value>, ApiError> {
-    let backup =
-        DocumentStorageService::create_backup(&state.db, user.user_id, document_id).await?;
-    Ok(Json(json!({ "status": "success", "data": backup })))
+    let requests =
+        NotarizationService::list_for_document(&state.db, user.user_id, document_id).await?;
+    Ok(Json(
+        json!({ "status": "success", "data": requests, "count": requests.len() }),
+    ))
 }
 
-async fn list_document_backups(
+/// `GET /api/notary/requests`
+async fn list_notary_queue(
     State(state): State<Arc<AppState>>,
-    Path(document_id): Path<Uuid>,
-    AuthenticatedUser(user): AuthenticatedUser,
+    AuthenticatedNotary(notary): AuthenticatedNotary,
+    Query(query): Query<NotaryQueueQuery>,
 ) -> Result<Json<Value>, ApiError> {
-    let backups =
-        DocumentStorageService::list_backups(&state.db, user.user_id, document_id).await?;
+    let requests =
+        NotarizationService::list_queue(&state.db, &notary, query.status.as_deref()).await?;
     Ok(Json(
-        json!({ "status": "success", "data": backups, "count": backups.len() }),
+        json!({ "status": "success", "data": requests, "count": requests.len() }),
     ))
 }
 
-// -- Will Compliance Validation Handlers (Issue #330) --
+/// `POST /api/notary/requests/:request_id/schedule`
+async fn schedule_notarization(
+    State(state): State<Arc<AppState>>,
+    Path(request_id): Path<Uuid>,
+    AuthenticatedNotary(notary): AuthenticatedNotary,
+    Json(req): Json<ScheduleSessionRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let request = NotarizationService::schedule(&state.db, &notary, request_id, &req).await?;
+    Ok(Json(json!({ "status": "success", "data": request })))
+}
 
-#[derive(serde::Deserialize)]
-struct ValidateWillRequest {
-    #[serde(flatten)]
-    input: WillDocumentInput,
-    witness_count: u32,
+/// `POST /api/notary/requests/:request_id/start`
+async fn start_notarization_session(
+    State(state): State<Arc<AppState>>,
+    Path(request_id): Path<Uuid>,
+    AuthenticatedNotary(notary): AuthenticatedNotary,
+    Json(req): Json<notarization::StartSessionRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let request = NotarizationService::start_session(&state.db, &notary, request_id, &req).await?;
+    Ok(Json(json!({ "status": "success", "data": request })))
 }
 
-async fn validate_will_compliance(
-    AuthenticatedUser(_user): AuthenticatedUser,
-    Json(req): Json<ValidateWillRequest>,
+/// `POST /api/notary/requests/:request_id/seal`
+async fn seal_notarization(
+    State(state): State<Arc<AppState>>,
+    Path(request_id): Path<Uuid>,
+    AuthenticatedNotary(notary): AuthenticatedNotary,
+    Json(req): Json<SealRequest>,
 ) -> Result<Json<Value>, ApiError> {
-    let result: ValidationResult = WillComplianceService::validate(&req.input, req.witness_count);
-    Ok(Json(json!({ "status": "success", "data": result })))
+    let request = NotarizationService::seal(&state.db, &notary, request_id, &req).await?;
+    Ok(Json(json!({ "status": "success", "data": request })))
 }
 
-async fn list_jurisdictions(
-    AuthenticatedUser(_user): AuthenticatedUser,
+/// `POST /api/notary/requests/:request_id/reject`
+async fn reject_notarization(
+    State(state): State<Arc<AppState>>,
+    Path(request_id): Path<Uuid>,
+    AuthenticatedNotary(notary): AuthenticatedNotary,
+    Json(req): Json<RejectNotarizationRequest>,
 ) -> Result<Json<Value>, ApiError> {
-    let jurisdictions = WillComplianceService::list_supported_jurisdictions();
+    let request = NotarizationService::reject(&state.db, &notary, request_id, &req).await?;
+    Ok(Json(json!({ "status": "success", "data": request })))
+}
+
+/// `GET /api/notary/journal`
+async fn get_notary_journal(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedNotary(notary): AuthenticatedNotary,
+) -> Result<Json<Value>, ApiError> {
+    let entries = NotarizationService::journal_for_notary(&state.db, notary.id).await?;
     Ok(Json(
-        json!({ "status": "success", "data": jurisdictions, "count": jurisdictions.len() }),
+        json!({ "status": "success", "data": entries, "count": entries.len() }),
     ))
 }
 
-async fn get_jurisdiction_rules(
-    AuthenticatedUser(_user): AuthenticatedUser,
-    Path(jurisdiction): Path<String>,
+/// `POST /api/admin/notaries`
+async fn create_notary(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedAdmin(admin): AuthenticatedAdmin,
+    Json(req): Json<CreateNotaryRequest>,
 ) -> Result<Json<Value>, ApiError> {
-    let rules = WillComplianceService::get_jurisdiction_rules(&jurisdiction);
-    Ok(Json(json!({ "status": "success", "data": rules })))
+    let notary = NotarizationService::create_notary(&state.db, admin.admin_id, &req).await?;
+    Ok(Json(json!({ "status": "success", "data": notary })))
+}
+
+/// `GET /api/admin/notaries`
+async fn list_notaries(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedAdmin(_admin): AuthenticatedAdmin,
+) -> Result<Json<Value>, ApiError> {
+    let notaries = NotarizationService::list_notaries(&state.db).await?;
+    Ok(Json(
+        json!({ "status": "success", "data": notaries, "count": notaries.len() }),
+    ))
+}
+
+/// `PUT /api/admin/notaries/:notary_id/status`
+async fn update_notary_status(
+    State(state): State<Arc<AppState>>,
+    Path(notary_id): Path<Uuid>,
+    AuthenticatedAdmin(_admin): AuthenticatedAdmin,
+    Json(req): Json<UpdateNotaryStatusRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let notary = NotarizationService::set_notary_status(&state.db, notary_id, &req.status).await?;
+    Ok(Json(json!({ "status": "success", "data": notary })))
 }
 
 // -- Witness Verification (Issue #331) ----------------------------------------
@@ -1609,6 +2951,12 @@ async fn invite_witness(
         req.email,
     )
     .await?;
+    if record.email.is_some() {
+        if let Err(e) = state.witness_invitations.send_invitation(record.id).await {
+            // The invitation worker retries unsent invitations.
+            tracing::warn!("Failed to send witness invitation {}: {}", record.id, e);
+        }
+    }
     Ok(Json(json!({ "status": "success", "data": record })))
 }
 
@@ -1655,6 +3003,36 @@ async fn decline_witness(
     Ok(Json(json!({ "status": "success", "data": record })))
 }
 
+/// Read-only view of the will for an invited witness; needs no account
+///
+/// `GET /api/will/witness-invitations/:token`
+async fn view_witness_invitation(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let view = state.witness_invitations.view(&token).await?;
+    Ok(Json(json!({ "status": "success", "data": view })))
+}
+
+/// `POST /api/will/witness-invitations/:token/sign`
+async fn sign_witness_invitation(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+    Json(req): Json<InvitationSignRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let record = state.witness_invitations.sign(&token, &req).await?;
+    Ok(Json(json!({ "status": "success", "data": record })))
+}
+
+/// `POST /api/will/witness-invitations/:token/decline`
+async fn decline_witness_invitation(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let record = state.witness_invitations.decline(&token).await?;
+    Ok(Json(json!({ "status": "success", "data": record })))
+}
+
 // -- Legal Document Integrity Check (Issue #332) -------------------------------
 
 async fn verify_document_integrity(
@@ -1777,16 +3155,96 @@ async fn get_plan_event_stats(
 
 // -- Legal Document Download API (Issue #334) ----------------------------------
 
+#[derive(Debug, serde::Deserialize)]
+struct DownloadUrlQuery {
+    ttl_seconds: Option<i64>,
+}
+
+/// Resolve whose resource is downloaded: a bearer token, or a valid signed
+/// URL for `path` in which case the owner is looked up by `owner_of`.
+///
+/// Emergency contacts need the `download_documents` permission; their
+/// access is audited as `action`.
+async fn resolve_download_user<F, Fut>(
+    state: &AppState,
+    principal: Option<Principal>,
+    path: &str,
+    signed: &SignedUrlParams,
+    action: &str,
+    metadata: Value,
+    owner_of: F,
+) -> Result<Uuid, ApiError>
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Result<Uuid, ApiError>>,
+{
+    if signed.signature.is_some() {
+        state.url_signer.verify(path, signed)?;
+        return owner_of().await;
+    }
+    match principal {
+        Some(principal) => {
+            principal
+                .owner_id(
+                    &state.db,
+                    EmergencyPermission::DownloadDocuments,
+                    action,
+                    metadata,
+                )
+                .await
+        }
+        None => Err(ApiError::Unauthorized),
+    }
+}
+
+/// Issue a time-limited signed URL for downloading a will document
+///
+/// `POST /api/will/documents/:document_id/download-url?ttl_seconds=300`
+async fn create_will_document_download_url(
+    State(state): State<Arc<AppState>>,
+    Path(document_id): Path<Uuid>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    Query(query): Query<DownloadUrlQuery>,
+) -> Result<Json<Value>, ApiError> {
+    if WillPdfService::get_document_owner(&state.db, document_id).await? != user.user_id {
+        return Err(ApiError::NotFound(format!(
+            "Will document {document_id} not found"
+        )));
+    }
+    let signed = state.url_signer.signed_url(
+        &format!("/api/will/documents/{}/download", document_id),
+        query.ttl_seconds,
+    );
+    Ok(Json(json!({ "status": "success", "data": signed })))
+}
+
 /// Download a will document as a PDF file by document ID
 ///
+/// Accepts either a bearer token or a signed URL from
+/// `POST /api/will/documents/:document_id/download-url`.
+///
 /// `GET /api/will/documents/:document_id/download`
 async fn download_will_document(
     State(state): State<Arc<AppState>>,
     Path(document_id): Path<Uuid>,
-    AuthenticatedUser(user): AuthenticatedUser,
+    Query(signed): Query<SignedUrlParams>,
+    principal: Option<Principal>,
 ) -> Result<axum::response::Response, ApiError> {
+    let path = format!("/api/will/documents/{}/download", document_id);
+    let accessed_by = principal.as_ref().map(Principal::actor_user_id);
+    let user_id = resolve_download_user(
+        &state,
+        principal,
+        &path,
+        &signed,
+        "will_document_downloaded",
+        json!({ "document_id": document_id }),
+        || WillPdfService::get_document_owner(&state.db, document_id),
+    )
+    .await?;
+
     // Retrieve the document with authentication check
-    let doc = WillPdfService::get_document(&state.db, document_id, user.user_id).await?;
+    let doc = WillPdfService::get_document(&state.db, document_id, user_id).await?;
 
     // Decode the base64 PDF content
     let pdf_bytes = base64::engine::general_purpose::STANDARD
@@ -1798,7 +3256,7 @@ async fn download_will_document(
         vault_id: format!("plan_{}", doc.plan_id),
         document_id,
         plan_id: doc.plan_id,
-        accessed_by: user.user_id,
+        accessed_by: accessed_by.unwrap_or(user_id),
         timestamp: chrono::Utc::now(),
     };
     if let Err(e) = crate::will_events::WillEventService::emit(&state.db, event).await {
@@ -1828,10 +3286,18 @@ async fn download_will_document(
 async fn download_will_document_by_version(
     State(state): State<Arc<AppState>>,
     Path((plan_id, version)): Path<(Uuid, u32)>,
-    AuthenticatedUser(user): AuthenticatedUser,
+    principal: Principal,
 ) -> Result<axum::response::Response, ApiError> {
+    let owner_id = principal
+        .owner_id(
+            &state.db,
+            EmergencyPermission::DownloadDocuments,
+            "will_document_downloaded",
+            json!({ "plan_id": plan_id, "version": version }),
+        )
+        .await?;
     // Retrieve the specific version
-    let doc = WillVersionService::get_version(&state.db, plan_id, user.user_id, version).await?;
+    let doc = WillVersionService::get_version(&state.db, plan_id, owner_id, version).await?;
 
     // Decode the base64 PDF content
     let pdf_bytes = base64::engine::general_purpose::STANDARD
@@ -1843,7 +3309,7 @@ async fn download_will_document_by_version(
         vault_id: format!("plan_{}", doc.plan_id),
         document_id: doc.document_id,
         plan_id: doc.plan_id,
-        accessed_by: user.user_id,
+        accessed_by: principal.actor_user_id(),
         timestamp: chrono::Utc::now(),
     };
     if let Err(e) = crate::will_events::WillEventService::emit(&state.db, event).await {
@@ -2161,6 +3627,71 @@ async fn get_insurance_claim(
     })))
 }
 
+/// Admin: How much of an insurance claim its policy and the fund's
+/// reserves allow paying
+///
+/// `GET /api/admin/insurance-fund/claims/:claim_id/adjudication`
+async fn get_insurance_claim_adjudication(
+    State(state): State<Arc<AppState>>,
+    Path(claim_id): Path<Uuid>,
+    AuthenticatedAdmin(_admin): AuthenticatedAdmin,
+) -> Result<Json<Value>, ApiError> {
+    let claim = state.insurance_fund_service.get_claim(claim_id).await?;
+    let (policy, adjudication) = state
+        .insurance_fund_service
+        .adjudicate_claim(&claim)
+        .await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": {
+            "claim_id": claim.id,
+            "policy": policy,
+            "adjudication": adjudication,
+        }
+    })))
+}
+
+/// Admin: List insurance claim policies
+///
+/// `GET /api/admin/insurance-fund/policies`
+async fn get_insurance_claim_policies(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedAdmin(_admin): AuthenticatedAdmin,
+) -> Result<Json<Value>, ApiError> {
+    let policies = crate::claim_adjudication::list_policies(&state.db).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": policies
+    })))
+}
+
+/// Admin: Set the coverage policy for a claim type
+///
+/// `PUT /api/admin/insurance-fund/policies/:claim_type`
+async fn update_insurance_claim_policy(
+    State(state): State<Arc<AppState>>,
+    Path(claim_type): Path<String>,
+    AuthenticatedAdmin(admin): AuthenticatedAdmin,
+    Json(req): Json<crate::claim_adjudication::ClaimPolicyRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let policy = crate::claim_adjudication::upsert_policy(&state.db, &claim_type, &req).await?;
+    crate::notifications::AuditLogService::log(
+        &state.db,
+        Some(admin.admin_id),
+        crate::notifications::audit_action::INSURANCE_CLAIM_POLICY_UPDATED,
+        None,
+        None,
+    )
+    .await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": policy
+    })))
+}
+
 /// Admin: Create insurance claim
 ///
 /// `POST /api/admin/insurance-fund/:fund_id/claims`
@@ -2221,50 +3752,258 @@ async fn payout_insurance_claim(
 // Legacy Content Handlers (Issue #XXX)
 // ─────────────────────────────────────────────────────────────────────────────
 
-#[derive(serde::Deserialize)]
-pub struct UploadContentRequest {
-    pub original_filename: String,
-    pub content_type: String,
-    pub description: Option<String>,
-}
+/// Headroom for multipart boundaries and text fields on top of the file itself
+const MULTIPART_OVERHEAD_BYTES: usize = 1_048_576;
 
 /// User: Upload legacy content
 ///
-/// `POST /api/content/upload`
+/// `POST /api/content/upload` (multipart: `file`, optional `description` and `sha256`)
 async fn upload_legacy_content(
     State(state): State<Arc<AppState>>,
     AuthenticatedUser(user): AuthenticatedUser,
-    Json(req): Json<UploadContentRequest>,
+    multipart: Multipart,
 ) -> Result<Json<Value>, ApiError> {
-    // Validate the content type
-    LegacyContentService::validate_content_type(&req.content_type)?;
-    
-    // For now, we'll create a metadata record. Full implementation would handle file upload.
+    let staging_path = LegacyContentService::staging_path(Uuid::new_v4());
+    let result = receive_legacy_upload(&state, user.user_id, multipart, &staging_path).await;
+    if result.is_err() {
+        // Whatever was staged before the failure is never finalized
+        let _ = state.content_storage.delete_file(&staging_path).await;
+    }
+    let content = result?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": content
+    })))
+}
+
+/// Stage the multipart file at `staging_path` and finalize it
+async fn receive_legacy_upload(
+    state: &AppState,
+    user_id: Uuid,
+    mut multipart: Multipart,
+    staging_path: &str,
+) -> Result<crate::legacy_content::LegacyContent, ApiError> {
+    let limit = LegacyContentService::remaining_quota(&mut *state.db.acquire().await?, user_id)
+        .await?
+        .min(crate::legacy_content::MAX_FILE_SIZE as i64) as u64;
+
+    let mut description = None;
+    let mut expected_hash = None;
+    let mut file = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Invalid multipart body: {}", e)))?
+    {
+        match field.name() {
+            Some("description") => {
+                description = Some(field.text().await.map_err(|e| {
+                    ApiError::BadRequest(format!("Invalid description field: {}", e))
+                })?);
+            }
+            Some("sha256") => {
+                let hash = field
+                    .text()
+                    .await
+                    .map_err(|e| ApiError::BadRequest(format!("Invalid sha256 field: {}", e)))?;
+                LegacyContentService::validate_sha256(hash.trim())?;
+                expected_hash = Some(hash.trim().to_string());
+            }
+            Some("file") => {
+                let original_filename = field
+                    .file_name()
+                    .map(str::to_string)
+                    .ok_or_else(|| ApiError::BadRequest("File name is required".to_string()))?;
+                let content_type = field
+                    .content_type()
+                    .map(str::to_string)
+                    .ok_or_else(|| ApiError::BadRequest("Content type is required".to_string()))?;
+                LegacyContentService::validate_content_type(&content_type)?;
+
+                let size =
+                    stream_field_to_staging(&state.content_storage, field, staging_path, limit)
+                        .await?;
+                file = Some((original_filename, content_type, size));
+            }
+            _ => {}
+        }
+    }
+
+    let (original_filename, content_type, file_size) =
+        file.ok_or_else(|| ApiError::BadRequest("Missing file field".to_string()))?;
     let metadata = crate::legacy_content::UploadMetadata {
-        original_filename: req.original_filename,
-        content_type: req.content_type.clone(),
-        file_size: 0, // Would be set from actual file upload
-        description: req.description,
+        original_filename,
+        content_type,
+        file_size: file_size as usize,
+        description,
     };
-    
-    let storage_path = LegacyContentService::generate_storage_path(user.user_id, &metadata.original_filename);
-    let file_hash = "pending".to_string(); // Would be calculated from file content
-    
-    let content = LegacyContentService::create_content_record(
+
+    LegacyContentService::finalize_staged_upload(
         &state.db,
-        user.user_id,
+        &state.content_storage,
+        state.blob_store.as_ref(),
+        state.key_manager.as_ref(),
+        user_id,
         &metadata,
-        storage_path,
-        file_hash,
+        staging_path,
+        expected_hash.as_deref(),
+    )
+    .await
+}
+
+/// Stream a multipart file field into a staging file, enforcing `limit` bytes.
+async fn stream_field_to_staging(
+    storage: &FileStorageService,
+    mut field: axum::extract::multipart::Field<'_>,
+    staging_path: &str,
+    limit: u64,
+) -> Result<u64, ApiError> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut writer = storage.create_writer(staging_path).await?;
+    let mut size: u64 = 0;
+
+    let result: Result<(), ApiError> = async {
+        while let Some(chunk) = field
+            .chunk()
+            .await
+            .map_err(|e| ApiError::BadRequest(format!("Upload interrupted: {}", e)))?
+        {
+            size += chunk.len() as u64;
+            if size > limit {
+                return Err(ApiError::BadRequest(format!(
+                    "File exceeds the {} bytes available for this upload",
+                    limit
+                )));
+            }
+            writer
+                .write_all(&chunk)
+                .await
+                .map_err(|e| ApiError::Internal(anyhow::anyhow!("Failed to stage file: {}", e)))?;
+        }
+        writer
+            .flush()
+            .await
+            .map_err(|e| ApiError::Internal(anyhow::anyhow!("Failed to stage file: {}", e)))
+    }
+    .await;
+
+    if let Err(e) = result {
+        let _ = storage.delete_file(staging_path).await;
+        return Err(e);
+    }
+
+    Ok(size)
+}
+
+#[derive(serde::Deserialize)]
+pub struct ChunkOffsetQuery {
+    pub offset: i64,
+}
+
+/// User: Start a resumable legacy content upload
+///
+/// `POST /api/content/uploads`
+async fn init_content_upload(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    Json(req): Json<InitUploadRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let session = LegacyContentService::init_upload_session(&state.db, user.user_id, &req).await?;
+    Ok(Json(json!({
+        "status": "success",
+        "data": session,
+        "max_chunk_size": crate::legacy_content::MAX_CHUNK_SIZE
+    })))
+}
+
+/// User: Get resumable upload progress
+///
+/// `GET /api/content/uploads/:session_id`
+async fn get_content_upload(
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<Uuid>,
+    AuthenticatedUser(user): AuthenticatedUser,
+) -> Result<Json<Value>, ApiError> {
+    let session =
+        LegacyContentService::get_upload_session(&state.db, session_id, user.user_id).await?;
+    Ok(Json(json!({
+        "status": "success",
+        "data": session
+    })))
+}
+
+/// User: Upload the next chunk of a resumable upload
+///
+/// `PUT /api/content/uploads/:session_id/chunks?offset=0`
+async fn upload_content_chunk(
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<Uuid>,
+    Query(query): Query<ChunkOffsetQuery>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    body: axum::body::Bytes,
+) -> Result<Json<Value>, ApiError> {
+    let session = LegacyContentService::append_upload_chunk(
+        &state.db,
+        &state.content_storage,
+        session_id,
+        user.user_id,
+        query.offset,
+        &body,
+    )
+    .await?;
+    Ok(Json(json!({
+        "status": "success",
+        "data": session
+    })))
+}
+
+/// User: Verify and finish a resumable upload
+///
+/// `POST /api/content/uploads/:session_id/complete`
+async fn complete_content_upload(
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<Uuid>,
+    AuthenticatedUser(user): AuthenticatedUser,
+) -> Result<Json<Value>, ApiError> {
+    let content = LegacyContentService::complete_upload_session(
+        &state.db,
+        &state.content_storage,
+        state.blob_store.as_ref(),
+        state.key_manager.as_ref(),
+        session_id,
+        user.user_id,
     )
     .await?;
-    
     Ok(Json(json!({
         "status": "success",
         "data": content
     })))
 }
 
+/// User: Abort a resumable upload
+///
+/// `DELETE /api/content/uploads/:session_id`
+async fn abort_content_upload(
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<Uuid>,
+    AuthenticatedUser(user): AuthenticatedUser,
+) -> Result<Json<Value>, ApiError> {
+    LegacyContentService::abort_upload_session(
+        &state.db,
+        &state.content_storage,
+        session_id,
+        user.user_id,
+    )
+    .await?;
+    Ok(Json(json!({
+        "status": "success",
+        "message": "Upload aborted"
+    })))
+}
+
 /// User: List legacy content
 ///
 /// `GET /api/content?content_type_prefix=video&limit=50&offset=0`
@@ -2273,7 +4012,8 @@ async fn list_user_content(
     AuthenticatedUser(user): AuthenticatedUser,
     Query(filters): Query<ContentListFilters>,
 ) -> Result<Json<Value>, ApiError> {
-    let contents = LegacyContentService::list_user_content(&state.db, user.user_id, &filters).await?;
+    let contents =
+        LegacyContentService::list_user_content(&state.db, user.user_id, &filters).await?;
     Ok(Json(json!({
         "status": "success",
         "data": contents,
@@ -2289,7 +4029,8 @@ async fn get_content_by_id(
     Path(content_id): Path<Uuid>,
     AuthenticatedUser(user): AuthenticatedUser,
 ) -> Result<Json<Value>, ApiError> {
-    let content = LegacyContentService::get_content_by_id(&state.db, content_id, user.user_id).await?;
+    let content =
+        LegacyContentService::get_content_by_id(&state.db, content_id, user.user_id).await?;
     Ok(Json(json!({
         "status": "success",
         "data": content
@@ -2311,32 +4052,89 @@ async fn delete_content(
     })))
 }
 
-/// User: Download content
+/// User: Issue a time-limited signed download URL
+///
+/// `POST /api/content/:content_id/download-url?ttl_seconds=300`
+async fn create_content_download_url(
+    State(state): State<Arc<AppState>>,
+    Path(content_id): Path<Uuid>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    Query(query): Query<DownloadUrlQuery>,
+) -> Result<Json<Value>, ApiError> {
+    LegacyContentService::get_content_by_id(&state.db, content_id, user.user_id).await?;
+    let signed = state.url_signer.signed_url(
+        &format!("/api/content/{}/download", content_id),
+        query.ttl_seconds,
+    );
+    Ok(Json(json!({
+        "status": "success",
+        "data": signed
+    })))
+}
+
+/// User: Download content (bearer token or signed URL)
 ///
 /// `GET /api/content/:content_id/download`
 async fn download_content(
     State(state): State<Arc<AppState>>,
     Path(content_id): Path<Uuid>,
-    AuthenticatedUser(user): AuthenticatedUser,
+    Query(signed): Query<SignedUrlParams>,
+    user: Option<AuthenticatedUser>,
 ) -> Result<axum::response::Response, ApiError> {
-    let content = LegacyContentService::get_content_by_id(&state.db, content_id, user.user_id).await?;
-    
-    // In a full implementation, this would read from the FileStorageService
-    // For now, return a placeholder response
+    let path = format!("/api/content/{}/download", content_id);
+    let owner = user.map(|AuthenticatedUser(user)| Principal::Owner(user));
+    let user_id = resolve_download_user(
+        &state,
+        owner,
+        &path,
+        &signed,
+        "legacy_content_downloaded",
+        json!({ "content_id": content_id }),
+        || LegacyContentService::get_content_owner(&state.db, content_id),
+    )
+    .await?;
+
+    let content = LegacyContentService::get_content_by_id(&state.db, content_id, user_id).await?;
+    let stream = LegacyContentService::read_content(
+        &state.db,
+        &state.content_storage,
+        state.blob_store.as_ref(),
+        state.key_manager.as_ref(),
+        &content,
+    )
+    .await?;
+
     use axum::body::Body;
     use axum::http::{header, Response, StatusCode};
-    
-    let content_disposition = format!("attachment; filename=\"{}\"", content.original_filename);
-    
+
     Response::builder()
         .status(StatusCode::OK)
         .header(header::CONTENT_TYPE, &content.content_type)
-        .header(header::CONTENT_DISPOSITION, content_disposition)
+        .header(
+            header::CONTENT_DISPOSITION,
+            LegacyContentService::content_disposition(&content.original_filename),
+        )
         .header(header::CACHE_CONTROL, "no-cache, no-store, must-revalidate")
-        .body(Body::empty())
+        .header(header::CONTENT_LENGTH, content.file_size)
+        .header("X-Content-SHA256", &content.file_hash)
+        .body(Body::from_stream(stream))
         .map_err(|e| ApiError::Internal(anyhow::anyhow!("Failed to build response: {}", e)))
 }
 
+/// Admin: Move encrypted document payloads from the database into the blob store
+///
+/// `POST /api/admin/storage/migrate-blobs`
+async fn migrate_database_blobs(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedAdmin(_admin): AuthenticatedAdmin,
+) -> Result<Json<Value>, ApiError> {
+    let report = BlobService::migrate_database_blobs(&state.db, state.blob_store.as_ref()).await?;
+    Ok(Json(json!({
+        "status": "success",
+        "data": report
+    })))
+}
+
 /// User: Get storage statistics
 ///
 /// `GET /api/content/stats`
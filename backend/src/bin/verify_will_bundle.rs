@@ -0,0 +1,97 @@
+//! Verify an exported will bundle against the inheritance contract.
+//!
+//! Usage: `verify_will_bundle <bundle.json> [--rpc-url URL] [--contract C...]
+//! [--offline] [--json]`
+//!
+//! The RPC URL and contract default to `SOROBAN_RPC_URL` and
+//! `INHERITANCE_CONTRACT_ID`. The contract is never taken from the bundle,
+//! which could name any contract. `--offline` checks hashes and signatures
+//! without consulting the chain and reports "VALID OFFLINE" when they pass.
+//!
+//! Exits 0 when every check passes (every offline check under `--offline`), 1
+//! when any check fails or was skipped and 2 when the bundle cannot be read.
+
+use inheritx_backend::contract_reader::SorobanContractReader;
+use inheritx_backend::will_bundle::{
+    verify_bundle, verify_bundle_on_chain, BundleVerdict, WillBundle,
+};
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: verify_will_bundle <bundle.json> [--rpc-url URL] [--contract C...] \
+                     [--offline] [--json]";
+
+fn option(args: &[String], name: &str, env: &str) -> Option<String> {
+    args.iter()
+        .position(|a| a == name)
+        .and_then(|i| args.get(i + 1).cloned())
+        .or_else(|| std::env::var(env).ok())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let json = args.iter().any(|a| a == "--json");
+    let offline = args.iter().any(|a| a == "--offline");
+    let rpc_url = option(&args, "--rpc-url", "SOROBAN_RPC_URL");
+    let contract = option(&args, "--contract", "INHERITANCE_CONTRACT_ID");
+    let Some(path) = args.iter().enumerate().find_map(|(i, a)| {
+        let is_value = i > 0 && matches!(args[i - 1].as_str(), "--rpc-url" | "--contract");
+        (!a.starts_with("--") && !is_value).then_some(a)
+    }) else {
+        eprintln!("{USAGE}");
+        return ExitCode::from(2);
+    };
+
+    let bundle: WillBundle = match std::fs::read(path)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| serde_json::from_slice(&bytes).map_err(|e| e.to_string()))
+    {
+        Ok(bundle) => bundle,
+        Err(e) => {
+            eprintln!("failed to read bundle {path}: {e}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let report = if offline {
+        verify_bundle(&bundle)
+    } else {
+        let (Some(rpc_url), Some(contract)) = (rpc_url, contract) else {
+            eprintln!("--rpc-url and --contract are required unless --offline is given");
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        };
+        let reader = match SorobanContractReader::new(&rpc_url, &contract) {
+            Ok(reader) => reader,
+            Err(e) => {
+                eprintln!("invalid contract {contract}: {e}");
+                return ExitCode::from(2);
+            }
+        };
+        let runtime = match tokio::runtime::Runtime::new() {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                eprintln!("failed to start runtime: {e}");
+                return ExitCode::from(2);
+            }
+        };
+        runtime.block_on(verify_bundle_on_chain(&bundle, &reader))
+    };
+
+    if json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(out) => println!("{out}"),
+            Err(e) => {
+                eprintln!("failed to encode report: {e}");
+                return ExitCode::from(2);
+            }
+        }
+    } else {
+        println!("{report}");
+    }
+
+    match report.verdict {
+        BundleVerdict::Valid => ExitCode::SUCCESS,
+        BundleVerdict::ValidOffline if offline => ExitCode::SUCCESS,
+        _ => ExitCode::from(1),
+    }
+}
@@ -0,0 +1,286 @@
+//! # Insurance Claim Adjudication
+//!
+//! Works out how much of a claim the insurance fund may pay. Each claim type
+//! has a policy with a deductible, a per-claim limit and a per-plan limit;
+//! on top of those, a payout may never push the fund's coverage ratio below
+//! its critical threshold (the `Insolvent` boundary). Large approvals need a
+//! second administrator, and every claim carries a review deadline.
+
+use crate::api_error::ApiError;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+/// Claim filed for the part of a liquidated debt the collateral did not cover
+pub const LIQUIDATION_SHORTFALL: &str = "liquidation_shortfall";
+/// Claim filed when loaned plan funds were written off at inheritance
+pub const INHERITANCE_RECALL: &str = "inheritance_recall";
+
+/// Decimals of amounts stored by the inheritance contract (Stellar assets)
+pub const CONTRACT_AMOUNT_DECIMALS: u32 = 7;
+
+/// Review deadline for claim types without a policy
+const DEFAULT_REVIEW_SLA_HOURS: i32 = 72;
+
+// ─── Types ────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ClaimPolicy {
+    pub claim_type: String,
+    pub per_claim_limit: Option<Decimal>,
+    pub per_plan_limit: Option<Decimal>,
+    pub deductible: Decimal,
+    pub dual_review_threshold: Option<Decimal>,
+    pub review_sla_hours: i32,
+    pub auto_create: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ClaimPolicy {
+    /// Policy for claim types without a row: no deductible or limits
+    pub fn unrestricted(claim_type: &str) -> Self {
+        Self {
+            claim_type: claim_type.to_string(),
+            per_claim_limit: None,
+            per_plan_limit: None,
+            deductible: Decimal::ZERO,
+            dual_review_threshold: None,
+            review_sla_hours: DEFAULT_REVIEW_SLA_HOURS,
+            auto_create: false,
+            updated_at: Utc::now(),
+        }
+    }
+
+    pub fn requires_second_review(&self, amount: Decimal) -> bool {
+        self.dual_review_threshold.is_some_and(|t| amount > t)
+    }
+}
+
+/// Replacement policy for a claim type
+#[derive(Debug, Deserialize)]
+pub struct ClaimPolicyRequest {
+    pub per_claim_limit: Option<Decimal>,
+    pub per_plan_limit: Option<Decimal>,
+    #[serde(default)]
+    pub deductible: Decimal,
+    pub dual_review_threshold: Option<Decimal>,
+    #[serde(default = "default_review_sla_hours")]
+    pub review_sla_hours: i32,
+    #[serde(default)]
+    pub auto_create: bool,
+}
+
+fn default_review_sla_hours() -> i32 {
+    DEFAULT_REVIEW_SLA_HOURS
+}
+
+impl ClaimPolicyRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        let amounts = [
+            self.per_claim_limit,
+            self.per_plan_limit,
+            Some(self.deductible),
+            self.dual_review_threshold,
+        ];
+        if amounts.iter().flatten().any(|a| *a < Decimal::ZERO) {
+            return Err(ApiError::BadRequest(
+                "Policy amounts cannot be negative".to_string(),
+            ));
+        }
+        if self.review_sla_hours <= 0 {
+            return Err(ApiError::BadRequest(
+                "review_sla_hours must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The rule that set a claim's payable amount
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutCap {
+    Deductible,
+    ClaimLimit,
+    PlanLimit,
+    Reserves,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Adjudication {
+    pub claimed_amount: Decimal,
+    /// Most that may be approved for the claim
+    pub payable_amount: Decimal,
+    /// The tightest rule, if anything reduced the claim
+    pub limited_by: Option<PayoutCap>,
+    /// Payouts the fund can make without its coverage going insolvent
+    pub reserve_headroom: Decimal,
+    pub requires_second_review: bool,
+}
+
+// ─── Rules ────────────────────────────────────────────────────────────────────
+
+/// Largest payout that keeps reserves, net of `committed` approvals not yet
+/// paid, at or above `critical_ratio` times `liabilities`
+pub fn reserve_headroom(
+    total_reserves: Decimal,
+    committed: Decimal,
+    liabilities: Decimal,
+    critical_ratio: Decimal,
+) -> Decimal {
+    (total_reserves - committed - critical_ratio * liabilities).max(Decimal::ZERO)
+}
+
+/// Payable amount of a claim for `claimed`, given what the policy has already
+/// covered on the same plan and the fund's reserve headroom
+pub fn adjudicate(
+    policy: &ClaimPolicy,
+    claimed: Decimal,
+    plan_covered: Decimal,
+    headroom: Decimal,
+) -> Adjudication {
+    let mut payable = (claimed - policy.deductible).max(Decimal::ZERO);
+    let mut limited_by = (payable < claimed).then_some(PayoutCap::Deductible);
+
+    let caps = [
+        (policy.per_claim_limit, PayoutCap::ClaimLimit),
+        (
+            policy
+                .per_plan_limit
+                .map(|limit| (limit - plan_covered).max(Decimal::ZERO)),
+            PayoutCap::PlanLimit,
+        ),
+        (Some(headroom), PayoutCap::Reserves),
+    ];
+    for (cap, reason) in caps {
+        if let Some(cap) = cap.filter(|cap| *cap < payable) {
+            payable = cap;
+            limited_by = Some(reason);
+        }
+    }
+
+    Adjudication {
+        claimed_amount: claimed,
+        payable_amount: payable,
+        limited_by,
+        reserve_headroom: headroom,
+        requires_second_review: policy.requires_second_review(payable),
+    }
+}
+
+// ─── Storage ──────────────────────────────────────────────────────────────────
+
+/// The policy for `claim_type`, or an unrestricted one if none is set
+pub async fn get_policy(db: &PgPool, claim_type: &str) -> Result<ClaimPolicy, ApiError> {
+    Ok(sqlx::query_as::<_, ClaimPolicy>(
+        "SELECT * FROM insurance_claim_policies WHERE claim_type = $1",
+    )
+    .bind(claim_type)
+    .fetch_optional(db)
+    .await?
+    .unwrap_or_else(|| ClaimPolicy::unrestricted(claim_type)))
+}
+
+pub async fn list_policies(db: &PgPool) -> Result<Vec<ClaimPolicy>, ApiError> {
+    Ok(sqlx::query_as::<_, ClaimPolicy>(
+        "SELECT * FROM insurance_claim_policies ORDER BY claim_type",
+    )
+    .fetch_all(db)
+    .await?)
+}
+
+pub async fn upsert_policy(
+    db: &PgPool,
+    claim_type: &str,
+    req: &ClaimPolicyRequest,
+) -> Result<ClaimPolicy, ApiError> {
+    req.validate()?;
+    Ok(sqlx::query_as::<_, ClaimPolicy>(
+        r#"
+        INSERT INTO insurance_claim_policies (
+            claim_type, per_claim_limit, per_plan_limit, deductible,
+            dual_review_threshold, review_sla_hours, auto_create
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (claim_type) DO UPDATE SET
+            per_claim_limit = EXCLUDED.per_claim_limit,
+            per_plan_limit = EXCLUDED.per_plan_limit,
+            deductible = EXCLUDED.deductible,
+            dual_review_threshold = EXCLUDED.dual_review_threshold,
+            review_sla_hours = EXCLUDED.review_sla_hours,
+            auto_create = EXCLUDED.auto_create,
+            updated_at = NOW()
+        RETURNING *
+        "#,
+    )
+    .bind(claim_type)
+    .bind(req.per_claim_limit)
+    .bind(req.per_plan_limit)
+    .bind(req.deductible)
+    .bind(req.dual_review_threshold)
+    .bind(req.review_sla_hours)
+    .bind(req.auto_create)
+    .fetch_one(db)
+    .await?)
+}
+
+// ─── Unit Tests ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn policy() -> ClaimPolicy {
+        ClaimPolicy {
+            per_claim_limit: Some(dec!(5000)),
+            per_plan_limit: Some(dec!(8000)),
+            deductible: dec!(100),
+            dual_review_threshold: Some(dec!(1000)),
+            ..ClaimPolicy::unrestricted(LIQUIDATION_SHORTFALL)
+        }
+    }
+
+    #[test]
+    fn applies_deductible_and_limits() {
+        let small = adjudicate(&policy(), dec!(600), dec!(0), dec!(1_000_000));
+        assert_eq!(small.payable_amount, dec!(500));
+        assert_eq!(small.limited_by, Some(PayoutCap::Deductible));
+        assert!(!small.requires_second_review);
+
+        let large = adjudicate(&policy(), dec!(9000), dec!(0), dec!(1_000_000));
+        assert_eq!(large.payable_amount, dec!(5000));
+        assert_eq!(large.limited_by, Some(PayoutCap::ClaimLimit));
+        assert!(large.requires_second_review);
+
+        // 6000 already covered on the plan leaves 2000
+        let repeat = adjudicate(&policy(), dec!(9000), dec!(6000), dec!(1_000_000));
+        assert_eq!(repeat.payable_amount, dec!(2000));
+        assert_eq!(repeat.limited_by, Some(PayoutCap::PlanLimit));
+    }
+
+    #[test]
+    fn caps_payouts_at_reserve_headroom() {
+        // 10k reserves, 2k committed, 5k liabilities at a 1.0 critical ratio
+        let headroom = reserve_headroom(dec!(10000), dec!(2000), dec!(5000), dec!(1.0));
+        assert_eq!(headroom, dec!(3000));
+
+        let adjudication = adjudicate(&policy(), dec!(4100), dec!(0), headroom);
+        assert_eq!(adjudication.payable_amount, dec!(3000));
+        assert_eq!(adjudication.limited_by, Some(PayoutCap::Reserves));
+
+        assert_eq!(
+            reserve_headroom(dec!(1000), dec!(0), dec!(5000), dec!(1.0)),
+            Decimal::ZERO
+        );
+    }
+
+    #[test]
+    fn unrestricted_policy_pays_in_full() {
+        let policy = ClaimPolicy::unrestricted("default");
+        let adjudication = adjudicate(&policy, dec!(250), dec!(0), dec!(1000));
+        assert_eq!(adjudication.payable_amount, dec!(250));
+        assert_eq!(adjudication.limited_by, None);
+        assert!(!adjudication.requires_second_review);
+    }
+}
@@ -0,0 +1,651 @@
+//! # Inheritance Contract Reader
+//!
+//! Read-only access to plan state held by the inheritance contract. The
+//! Soroban implementation fetches the contract's persistent storage entries
+//! through the RPC `getLedgerEntries` method, so no transaction or source
+//! account is needed. Values the contract only exposes through a function
+//! are read by simulating the call.
+
+use crate::api_error::ApiError;
+use crate::tx_submitter::{ContractCall, SorobanInvoker, TESTNET_PASSPHRASE};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use soroban_sdk::xdr::{
+    ContractDataDurability, Hash, LedgerEntryData, LedgerKey, LedgerKeyContractData, Limits,
+    PublicKey, ReadXdr, ScAddress, ScMap, ScSymbol, ScVal, ScVec, WriteXdr,
+};
+use std::sync::Arc;
+
+// ─── Types ────────────────────────────────────────────────────────────────────
+
+/// A beneficiary as stored on-chain. Identities are hashed in the contract,
+/// so only the allocation can be compared with off-chain records.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OnChainBeneficiary {
+    pub allocation_bp: u32,
+}
+
+/// The subset of the contract's `InheritancePlan` the backend relies on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OnChainPlan {
+    pub plan_id: u64,
+    pub plan_name: String,
+    /// Owner as a Stellar strkey (`G…` account or `C…` contract)
+    pub owner: String,
+    pub total_amount: u64,
+    pub total_allocation_bp: u32,
+    pub beneficiaries: Vec<OnChainBeneficiary>,
+    pub is_active: bool,
+}
+
+/// The contract's `InheritanceTriggerInfo`: how loaned plan funds were
+/// recovered once inheritance was triggered
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OnChainInheritanceTrigger {
+    pub triggered_at: u64,
+    pub original_loaned: u64,
+    pub recalled_amount: u64,
+    /// Set once unrecoverable loans were written off by `liquidation_fallback`
+    pub liquidation_triggered: bool,
+    /// Amount written off
+    pub settled_amount: u64,
+}
+
+/// The contract's `LegacyMessageMetadata` for one message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OnChainLegacyMessage {
+    pub message_id: u64,
+    pub vault_id: u64,
+    /// Hex `message_hash`
+    pub message_hash: String,
+    /// Creator as a Stellar strkey
+    pub creator: String,
+}
+
+#[async_trait]
+pub trait InheritanceContractReader: Send + Sync {
+    /// The plan stored under `DataKey::Plan(plan_id)`, if any
+    async fn get_plan(&self, plan_id: u64) -> Result<Option<OnChainPlan>, ApiError>;
+
+    /// The hex will hash stored under `DataKey::WillHash(plan_id)`, if any
+    async fn get_will_hash(&self, plan_id: u64) -> Result<Option<String>, ApiError>;
+
+    /// The hex will hash of `DataKey::WillVersion(plan_id, version)`, if any
+    async fn get_will_version_hash(
+        &self,
+        plan_id: u64,
+        version: u32,
+    ) -> Result<Option<String>, ApiError>;
+
+    /// Whether `DataKey::WillFinalized(plan_id, version)` is set
+    async fn is_will_finalized(&self, plan_id: u64, version: u32) -> Result<bool, ApiError>;
+
+    /// Whether `DataKey::WillRevokedAt(plan_id, version)` is set
+    async fn is_will_revoked(&self, plan_id: u64, version: u32) -> Result<bool, ApiError>;
+
+    /// The trigger info stored under `DataKey::InheritanceTrigger(plan_id)`,
+    /// if inheritance was triggered
+    async fn get_inheritance_trigger(
+        &self,
+        plan_id: u64,
+    ) -> Result<Option<OnChainInheritanceTrigger>, ApiError>;
+
+    /// Share of a plan, in basis points, an emergency transfer may move
+    async fn emergency_transfer_limit_bp(&self) -> Result<u32, ApiError>;
+
+    /// The metadata stored under `DataKey::LegacyMessage(message_id)`, if any
+    async fn get_legacy_message(
+        &self,
+        message_id: u64,
+    ) -> Result<Option<OnChainLegacyMessage>, ApiError>;
+}
+
+/// Build the configured reader from `SOROBAN_RPC_URL` and
+/// `INHERITANCE_CONTRACT_ID`. Without them every read fails, so features
+/// that must agree with the chain refuse to proceed rather than guess.
+pub fn contract_reader_from_env() -> Result<Arc<dyn InheritanceContractReader>, ApiError> {
+    match (
+        std::env::var("SOROBAN_RPC_URL"),
+        std::env::var("INHERITANCE_CONTRACT_ID"),
+    ) {
+        (Ok(rpc_url), Ok(contract_id)) => Ok(Arc::new(SorobanContractReader::new(
+            &rpc_url,
+            &contract_id,
+        )?)),
+        _ => Ok(Arc::new(UnconfiguredContractReader)),
+    }
+}
+
+// ─── Unconfigured Reader ──────────────────────────────────────────────────────
+
+pub struct UnconfiguredContractReader;
+
+impl UnconfiguredContractReader {
+    fn error() -> ApiError {
+        ApiError::Internal(anyhow::anyhow!(
+            "Contract reader is not configured; set SOROBAN_RPC_URL and INHERITANCE_CONTRACT_ID"
+        ))
+    }
+}
+
+#[async_trait]
+impl InheritanceContractReader for UnconfiguredContractReader {
+    async fn get_plan(&self, _plan_id: u64) -> Result<Option<OnChainPlan>, ApiError> {
+        Err(Self::error())
+    }
+
+    async fn get_will_hash(&self, _plan_id: u64) -> Result<Option<String>, ApiError> {
+        Err(Self::error())
+    }
+
+    async fn get_will_version_hash(
+        &self,
+        _plan_id: u64,
+        _version: u32,
+    ) -> Result<Option<String>, ApiError> {
+        Err(Self::error())
+    }
+
+    async fn is_will_finalized(&self, _plan_id: u64, _version: u32) -> Result<bool, ApiError> {
+        Err(Self::error())
+    }
+
+    async fn is_will_revoked(&self, _plan_id: u64, _version: u32) -> Result<bool, ApiError> {
+        Err(Self::error())
+    }
+
+    async fn get_inheritance_trigger(
+        &self,
+        _plan_id: u64,
+    ) -> Result<Option<OnChainInheritanceTrigger>, ApiError> {
+        Err(Self::error())
+    }
+
+    async fn emergency_transfer_limit_bp(&self) -> Result<u32, ApiError> {
+        Err(Self::error())
+    }
+
+    async fn get_legacy_message(
+        &self,
+        _message_id: u64,
+    ) -> Result<Option<OnChainLegacyMessage>, ApiError> {
+        Err(Self::error())
+    }
+}
+
+// ─── Soroban RPC Reader ───────────────────────────────────────────────────────
+
+pub struct SorobanContractReader {
+    client: reqwest::Client,
+    rpc_url: String,
+    contract: [u8; 32],
+    /// Simulates view functions; simulation neither signs nor checks the
+    /// network, so the passphrase is irrelevant
+    invoker: SorobanInvoker,
+}
+
+pub(crate) fn xdr_error(e: impl std::fmt::Display) -> ApiError {
+    ApiError::Internal(anyhow::anyhow!("Malformed contract data: {}", e))
+}
+
+fn symbol(name: &str) -> Result<ScVal, ApiError> {
+    Ok(ScVal::Symbol(ScSymbol(name.try_into().map_err(xdr_error)?)))
+}
+
+/// Storage key of a `DataKey` variant such as `Plan(u64)` or
+/// `WillVersion(u64, u32)`
+fn data_key(variant: &str, fields: &[ScVal]) -> Result<ScVal, ApiError> {
+    let mut items = vec![symbol(variant)?];
+    items.extend_from_slice(fields);
+    Ok(ScVal::Vec(Some(ScVec(
+        items.try_into().map_err(xdr_error)?,
+    ))))
+}
+
+fn as_hex_bytes(val: &ScVal) -> Result<String, ApiError> {
+    match val {
+        ScVal::Bytes(bytes) => Ok(hex::encode(bytes.0.as_slice())),
+        other => Err(xdr_error(format!("expected bytes, got {other:?}"))),
+    }
+}
+
+pub(crate) fn field<'a>(map: &'a ScMap, name: &str) -> Result<&'a ScVal, ApiError> {
+    map.0
+        .iter()
+        .find(|e| matches!(&e.key, ScVal::Symbol(s) if s.0.as_slice() == name.as_bytes()))
+        .map(|e| &e.val)
+        .ok_or_else(|| xdr_error(format!("missing field '{name}'")))
+}
+
+pub(crate) fn as_map(val: &ScVal) -> Result<&ScMap, ApiError> {
+    match val {
+        ScVal::Map(Some(map)) => Ok(map),
+        other => Err(xdr_error(format!("expected map, got {other:?}"))),
+    }
+}
+
+pub(crate) fn as_u64(val: &ScVal) -> Result<u64, ApiError> {
+    match val {
+        ScVal::U64(v) => Ok(*v),
+        other => Err(xdr_error(format!("expected u64, got {other:?}"))),
+    }
+}
+
+pub(crate) fn as_i128(val: &ScVal) -> Result<i128, ApiError> {
+    match val {
+        ScVal::I128(parts) => Ok(((parts.hi as i128) << 64) | parts.lo as i128),
+        other => Err(xdr_error(format!("expected i128, got {other:?}"))),
+    }
+}
+
+pub(crate) fn as_u32(val: &ScVal) -> Result<u32, ApiError> {
+    match val {
+        ScVal::U32(v) => Ok(*v),
+        other => Err(xdr_error(format!("expected u32, got {other:?}"))),
+    }
+}
+
+fn as_string(val: &ScVal) -> Result<String, ApiError> {
+    match val {
+        ScVal::String(s) => Ok(String::from_utf8_lossy(s.0.as_slice()).into_owned()),
+        other => Err(xdr_error(format!("expected string, got {other:?}"))),
+    }
+}
+
+pub(crate) fn as_address(val: &ScVal) -> Result<String, ApiError> {
+    match val {
+        ScVal::Address(ScAddress::Account(account)) => {
+            let PublicKey::PublicKeyTypeEd25519(key) = &account.0;
+            Ok(stellar_strkey::ed25519::PublicKey(key.0)
+                .to_string()
+                .as_str()
+                .to_owned())
+        }
+        ScVal::Address(ScAddress::Contract(hash)) => Ok(stellar_strkey::Contract(hash.0)
+            .to_string()
+            .as_str()
+            .to_owned()),
+        other => Err(xdr_error(format!("expected address, got {other:?}"))),
+    }
+}
+
+/// Decode an `InheritancePlan` contract value
+pub fn parse_plan(plan_id: u64, val: &ScVal) -> Result<OnChainPlan, ApiError> {
+    let map = as_map(val)?;
+    let beneficiaries = match field(map, "beneficiaries")? {
+        ScVal::Vec(Some(items)) => items
+            .0
+            .iter()
+            .map(|b| {
+                Ok(OnChainBeneficiary {
+                    allocation_bp: as_u32(field(as_map(b)?, "allocation_bp")?)?,
+                })
+            })
+            .collect::<Result<Vec<_>, ApiError>>()?,
+        other => return Err(xdr_error(format!("expected vec, got {other:?}"))),
+    };
+    Ok(OnChainPlan {
+        plan_id,
+        plan_name: as_string(field(map, "plan_name")?)?,
+        owner: as_address(field(map, "owner")?)?,
+        total_amount: as_u64(field(map, "total_amount")?)?,
+        total_allocation_bp: as_u32(field(map, "total_allocation_bp")?)?,
+        beneficiaries,
+        is_active: matches!(field(map, "is_active")?, ScVal::Bool(true)),
+    })
+}
+
+/// Decode an `InheritanceTriggerInfo` contract value
+pub fn parse_inheritance_trigger(val: &ScVal) -> Result<OnChainInheritanceTrigger, ApiError> {
+    let map = as_map(val)?;
+    Ok(OnChainInheritanceTrigger {
+        triggered_at: as_u64(field(map, "triggered_at")?)?,
+        original_loaned: as_u64(field(map, "original_loaned")?)?,
+        recalled_amount: as_u64(field(map, "recalled_amount")?)?,
+        liquidation_triggered: matches!(field(map, "liquidation_triggered")?, ScVal::Bool(true)),
+        settled_amount: as_u64(field(map, "settled_amount")?)?,
+    })
+}
+
+/// Decode a `LegacyMessageMetadata` contract value
+pub fn parse_legacy_message(val: &ScVal) -> Result<OnChainLegacyMessage, ApiError> {
+    let map = as_map(val)?;
+    Ok(OnChainLegacyMessage {
+        message_id: as_u64(field(map, "message_id")?)?,
+        vault_id: as_u64(field(map, "vault_id")?)?,
+        message_hash: as_hex_bytes(field(map, "message_hash")?)?,
+        creator: as_address(field(map, "creator")?)?,
+    })
+}
+
+impl SorobanContractReader {
+    pub fn new(rpc_url: &str, contract_id: &str) -> Result<Self, ApiError> {
+        let contract = stellar_strkey::Contract::from_string(contract_id)
+            .map_err(|e| {
+                ApiError::Internal(anyhow::anyhow!(
+                    "Invalid INHERITANCE_CONTRACT_ID {}: {}",
+                    contract_id,
+                    e
+                ))
+            })?
+            .0;
+        Ok(Self {
+            client: reqwest::Client::new(),
+            rpc_url: rpc_url.to_string(),
+            contract,
+            invoker: SorobanInvoker::new(rpc_url, contract_id, TESTNET_PASSPHRASE)?,
+        })
+    }
+
+    /// Result of a view function, from simulating it
+    async fn call(&self, function: &str, args: Vec<ScVal>) -> Result<ScVal, ApiError> {
+        self.invoker
+            .read(&ContractCall {
+                // Any account works; simulation neither signs nor charges it
+                source_account: stellar_strkey::ed25519::PublicKey([0; 32])
+                    .to_string()
+                    .as_str()
+                    .to_owned(),
+                function: function.to_string(),
+                args,
+            })
+            .await
+            .map_err(|e| {
+                ApiError::Internal(anyhow::anyhow!("Contract call {function} failed: {e}"))
+            })
+    }
+
+    /// Fetch one persistent contract storage value
+    async fn read_persistent(&self, key: ScVal) -> Result<Option<ScVal>, ApiError> {
+        let ledger_key = LedgerKey::ContractData(LedgerKeyContractData {
+            contract: ScAddress::Contract(Hash(self.contract)),
+            key,
+            durability: ContractDataDurability::Persistent,
+        });
+        let encoded = BASE64.encode(ledger_key.to_xdr(Limits::none()).map_err(xdr_error)?);
+
+        let response: JsonValue = self
+            .client
+            .post(&self.rpc_url)
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "getLedgerEntries",
+                "params": { "keys": [encoded] }
+            }))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| ApiError::Internal(anyhow::anyhow!("Soroban RPC request failed: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| {
+                ApiError::Internal(anyhow::anyhow!("Invalid Soroban RPC response: {}", e))
+            })?;
+
+        if let Some(error) = response.get("error") {
+            return Err(ApiError::Internal(anyhow::anyhow!(
+                "Soroban RPC error: {}",
+                error
+            )));
+        }
+        let Some(entry) = response["result"]["entries"]
+            .as_array()
+            .and_then(|entries| entries.first())
+        else {
+            return Ok(None);
+        };
+        let xdr = entry["xdr"]
+            .as_str()
+            .ok_or_else(|| xdr_error("ledger entry without xdr"))?;
+        let bytes = BASE64.decode(xdr).map_err(xdr_error)?;
+        match LedgerEntryData::from_xdr(bytes, Limits::none()).map_err(xdr_error)? {
+            LedgerEntryData::ContractData(data) => Ok(Some(data.val)),
+            _ => Err(xdr_error("ledger entry is not contract data")),
+        }
+    }
+}
+
+#[async_trait]
+impl InheritanceContractReader for SorobanContractReader {
+    async fn get_plan(&self, plan_id: u64) -> Result<Option<OnChainPlan>, ApiError> {
+        self.read_persistent(data_key("Plan", &[ScVal::U64(plan_id)])?)
+            .await?
+            .map(|val| parse_plan(plan_id, &val))
+            .transpose()
+    }
+
+    async fn get_will_hash(&self, plan_id: u64) -> Result<Option<String>, ApiError> {
+        self.read_persistent(data_key("WillHash", &[ScVal::U64(plan_id)])?)
+            .await?
+            .map(|val| as_hex_bytes(&val))
+            .transpose()
+    }
+
+    async fn get_will_version_hash(
+        &self,
+        plan_id: u64,
+        version: u32,
+    ) -> Result<Option<String>, ApiError> {
+        let key = data_key("WillVersion", &[ScVal::U64(plan_id), ScVal::U32(version)])?;
+        self.read_persistent(key)
+            .await?
+            .map(|val| as_hex_bytes(field(as_map(&val)?, "will_hash")?))
+            .transpose()
+    }
+
+    async fn is_will_finalized(&self, plan_id: u64, version: u32) -> Result<bool, ApiError> {
+        let key = data_key("WillFinalized", &[ScVal::U64(plan_id), ScVal::U32(version)])?;
+        Ok(matches!(
+            self.read_persistent(key).await?,
+            Some(ScVal::Bool(true))
+        ))
+    }
+
+    async fn is_will_revoked(&self, plan_id: u64, version: u32) -> Result<bool, ApiError> {
+        let key = data_key("WillRevokedAt", &[ScVal::U64(plan_id), ScVal::U32(version)])?;
+        Ok(self.read_persistent(key).await?.is_some())
+    }
+
+    async fn get_inheritance_trigger(
+        &self,
+        plan_id: u64,
+    ) -> Result<Option<OnChainInheritanceTrigger>, ApiError> {
+        self.read_persistent(data_key("InheritanceTrigger", &[ScVal::U64(plan_id)])?)
+            .await?
+            .map(|val| parse_inheritance_trigger(&val))
+            .transpose()
+    }
+
+    async fn emergency_transfer_limit_bp(&self) -> Result<u32, ApiError> {
+        as_u32(
+            &self
+                .call("get_emergency_transfer_limit_bp", Vec::new())
+                .await?,
+        )
+    }
+
+    async fn get_legacy_message(
+        &self,
+        message_id: u64,
+    ) -> Result<Option<OnChainLegacyMessage>, ApiError> {
+        self.read_persistent(data_key("LegacyMessage", &[ScVal::U64(message_id)])?)
+            .await?
+            .map(|val| parse_legacy_message(&val))
+            .transpose()
+    }
+}
+
+// ─── Unit Tests ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+    use soroban_sdk::xdr::{
+        AccountId, ContractDataEntry, ExtensionPoint, ScBytes, ScMapEntry, ScString, Uint256,
+    };
+
+    fn entry(name: &str, val: ScVal) -> ScMapEntry {
+        ScMapEntry {
+            key: symbol(name).unwrap(),
+            val,
+        }
+    }
+
+    fn map(entries: Vec<ScMapEntry>) -> ScVal {
+        ScVal::Map(Some(ScMap(entries.try_into().unwrap())))
+    }
+
+    fn sample_plan_val() -> ScVal {
+        let beneficiary = |bp: u32| map(vec![entry("allocation_bp", ScVal::U32(bp))]);
+        let beneficiaries = vec![beneficiary(6000), beneficiary(4000)];
+        map(vec![
+            entry(
+                "beneficiaries",
+                ScVal::Vec(Some(ScVec(beneficiaries.try_into().unwrap()))),
+            ),
+            entry("is_active", ScVal::Bool(true)),
+            entry(
+                "owner",
+                ScVal::Address(ScAddress::Account(AccountId(
+                    PublicKey::PublicKeyTypeEd25519(Uint256([7; 32])),
+                ))),
+            ),
+            entry(
+                "plan_name",
+                ScVal::String(ScString("Family".try_into().unwrap())),
+            ),
+            entry("total_allocation_bp", ScVal::U32(10000)),
+            entry("total_amount", ScVal::U64(1_000_000)),
+        ])
+    }
+
+    #[test]
+    fn parses_inheritance_plan_value() {
+        let plan = parse_plan(9, &sample_plan_val()).unwrap();
+        assert_eq!(plan.plan_id, 9);
+        assert_eq!(plan.plan_name, "Family");
+        assert_eq!(
+            plan.owner,
+            stellar_strkey::ed25519::PublicKey([7; 32])
+                .to_string()
+                .as_str()
+        );
+        assert_eq!(plan.total_allocation_bp, 10000);
+        assert_eq!(
+            plan.beneficiaries,
+            vec![
+                OnChainBeneficiary {
+                    allocation_bp: 6000
+                },
+                OnChainBeneficiary {
+                    allocation_bp: 4000
+                }
+            ]
+        );
+        assert!(parse_plan(9, &ScVal::U32(1)).is_err());
+    }
+
+    #[test]
+    fn parses_inheritance_trigger_value() {
+        let val = map(vec![
+            entry("liquidation_triggered", ScVal::Bool(true)),
+            entry("loan_freeze_active", ScVal::Bool(true)),
+            entry("original_loaned", ScVal::U64(50_000)),
+            entry("recall_attempted", ScVal::Bool(true)),
+            entry("recalled_amount", ScVal::U64(30_000)),
+            entry("settled_amount", ScVal::U64(20_000)),
+            entry("triggered_at", ScVal::U64(1_700_000_000)),
+        ]);
+        let trigger = parse_inheritance_trigger(&val).unwrap();
+        assert!(trigger.liquidation_triggered);
+        assert_eq!(trigger.recalled_amount, 30_000);
+        assert_eq!(trigger.settled_amount, 20_000);
+    }
+
+    #[test]
+    fn parses_legacy_message_value() {
+        let val = map(vec![
+            entry("created_at", ScVal::U64(1_700_000_000)),
+            entry(
+                "creator",
+                ScVal::Address(ScAddress::Account(AccountId(
+                    PublicKey::PublicKeyTypeEd25519(Uint256([7; 32])),
+                ))),
+            ),
+            entry("is_finalized", ScVal::Bool(false)),
+            entry("is_unlocked", ScVal::Bool(false)),
+            entry(
+                "key_reference",
+                ScVal::String(ScString("e2e".try_into().unwrap())),
+            ),
+            entry(
+                "message_hash",
+                ScVal::Bytes(ScBytes(vec![0xab; 32].try_into().unwrap())),
+            ),
+            entry("message_id", ScVal::U64(4)),
+            entry("unlock_timestamp", ScVal::U64(1_800_000_000)),
+            entry("vault_id", ScVal::U64(9)),
+        ]);
+        let message = parse_legacy_message(&val).unwrap();
+        assert_eq!(message.message_id, 4);
+        assert_eq!(message.vault_id, 9);
+        assert_eq!(message.message_hash, "ab".repeat(32));
+        assert_eq!(
+            message.creator,
+            stellar_strkey::ed25519::PublicKey([7; 32])
+                .to_string()
+                .as_str()
+        );
+    }
+
+    #[tokio::test]
+    async fn reads_plan_through_get_ledger_entries() {
+        let server = MockServer::start();
+        let contract_id = stellar_strkey::Contract([1; 32]).to_string();
+        let reader = SorobanContractReader::new(&server.url("/"), contract_id.as_str()).unwrap();
+
+        let key = LedgerKey::ContractData(LedgerKeyContractData {
+            contract: ScAddress::Contract(Hash(reader.contract)),
+            key: data_key("Plan", &[ScVal::U64(9)]).unwrap(),
+            durability: ContractDataDurability::Persistent,
+        });
+        let data = LedgerEntryData::ContractData(ContractDataEntry {
+            ext: ExtensionPoint::V0,
+            contract: ScAddress::Contract(Hash(reader.contract)),
+            key: data_key("Plan", &[ScVal::U64(9)]).unwrap(),
+            durability: ContractDataDurability::Persistent,
+            val: sample_plan_val(),
+        });
+        let key_b64 = BASE64.encode(key.to_xdr(Limits::none()).unwrap());
+        let data_b64 = BASE64.encode(data.to_xdr(Limits::none()).unwrap());
+
+        let found = server.mock(|when, then| {
+            when.method(POST)
+                .body_contains("getLedgerEntries")
+                .body_contains(&key_b64);
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": { "entries": [{ "key": key_b64, "xdr": data_b64 }], "latestLedger": 10 }
+            }));
+        });
+        let plan = reader.get_plan(9).await.unwrap().unwrap();
+        assert_eq!(plan.total_amount, 1_000_000);
+        found.assert();
+
+        let missing = server.mock(|when, then| {
+            when.method(POST).body_contains("getLedgerEntries");
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": { "entries": [], "latestLedger": 10 }
+            }));
+        });
+        assert!(reader.get_plan(10).await.unwrap().is_none());
+        missing.assert();
+    }
+}
@@ -1,26 +1,41 @@
 //! Encrypted document storage with backup support.
 //!
-//! Provides AES-256-GCM encryption for will documents at rest,
-//! per-user access control, and a backup mechanism.
+//! Will documents are encrypted at rest with AES-256-GCM under a per-document
+//! data key, stored wrapped by a [`KeyManager`] key-encryption key (KEK).
+//! Ciphertext lives in the configured [`BlobStore`]; rows written before the
+//! blob store existed still carry it inline and are read from there until
+//! migrated. Rows encrypted before envelope encryption use a key derived from
+//! `DOCUMENT_ENCRYPTION_KEY` until [`DocumentKeyRotationService`] moves them.
+//! Legacy content uploads use the same key manager for their data keys.
 
 use crate::api_error::ApiError;
+use crate::blob_store::{BlobService, BlobStore};
+use crate::key_manager::{self, KeyManager};
 use chrono::{DateTime, Utc};
-use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM};
-use ring::digest::{digest, SHA256};
+use ring::aead::{LessSafeKey, AES_256_GCM};
 use ring::hkdf::{Salt, HKDF_SHA256};
-use ring::rand::{SecureRandom, SystemRandom};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
-const NONCE_LEN: usize = 12;
-const KEY_LEN: usize = 32;
+use crate::key_manager::KEY_LEN;
+pub(crate) use crate::key_manager::NONCE_LEN;
+
+/// Rows the re-encryption job moves per run
+const ROTATION_BATCH_SIZE: i64 = 50;
+
+/// Legacy content not yet wrapped under the active KEK (`$1`)
+const LEGACY_CONTENT_TO_ROTATE: &str = "encrypted \
+     AND (wrapped_data_key IS NULL OR encryption_key_version IS DISTINCT FROM $1)";
 
 // ---------------------------------------------------------------------------
 // Encryption helpers
 // ---------------------------------------------------------------------------
 
-fn derive_key(secret: &[u8]) -> Result<LessSafeKey, ApiError> {
+fn derive_key_bytes(secret: &[u8]) -> Result<[u8; KEY_LEN], ApiError> {
     let salt = Salt::new(HKDF_SHA256, b"inheritx-document-encryption");
     let prk = salt.extract(secret);
     let okm = prk
@@ -29,50 +44,88 @@ fn derive_key(secret: &[u8]) -> Result<LessSafeKey, ApiError> {
     let mut key_bytes = [0u8; KEY_LEN];
     okm.fill(&mut key_bytes)
         .map_err(|_| ApiError::Internal(anyhow::anyhow!("Key material extraction failed")))?;
-    let unbound = UnboundKey::new(&AES_256_GCM, &key_bytes)
-        .map_err(|_| ApiError::Internal(anyhow::anyhow!("Failed to create encryption key")))?;
-    Ok(LessSafeKey::new(unbound))
+    Ok(key_bytes)
 }
 
-fn load_encryption_secret() -> Vec<u8> {
+pub(crate) fn load_encryption_secret() -> Vec<u8> {
     std::env::var("DOCUMENT_ENCRYPTION_KEY")
         .unwrap_or_default()
         .into_bytes()
 }
 
-fn encrypt_bytes(plaintext: &[u8], secret: &[u8]) -> Result<(Vec<u8>, Vec<u8>), ApiError> {
-    let key = derive_key(secret)?;
-    let rng = SystemRandom::new();
-    let mut nonce_bytes = [0u8; NONCE_LEN];
-    rng.fill(&mut nonce_bytes)
-        .map_err(|_| ApiError::Internal(anyhow::anyhow!("Failed to generate nonce")))?;
-    let nonce = Nonce::assume_unique_for_key(nonce_bytes);
+/// Key bytes for rows encrypted before envelope encryption
+pub(crate) fn legacy_key_bytes() -> Result<[u8; KEY_LEN], ApiError> {
+    let secret = load_encryption_secret();
+    if secret.is_empty() {
+        return Err(ApiError::Internal(anyhow::anyhow!(
+            "DOCUMENT_ENCRYPTION_KEY is needed to read documents encrypted before envelope \
+             encryption"
+        )));
+    }
+    derive_key_bytes(&secret)
+}
+
+fn legacy_key() -> Result<LessSafeKey, ApiError> {
+    key_manager::aes_key(&legacy_key_bytes()?)
+}
 
-    let mut in_out = plaintext.to_vec();
-    key.seal_in_place_append_tag(nonce, Aad::empty(), &mut in_out)
-        .map_err(|_| ApiError::Internal(anyhow::anyhow!("Encryption failed")))?;
+/// Content encrypted under a fresh data key, with the data key wrapped
+struct SealedDocument {
+    ciphertext: Vec<u8>,
+    nonce: Vec<u8>,
+    wrapped_data_key: Vec<u8>,
+    kek_version: i32,
+}
 
-    Ok((in_out, nonce_bytes.to_vec()))
+async fn seal_document(
+    keys: &dyn KeyManager,
+    plaintext: &[u8],
+) -> Result<SealedDocument, ApiError> {
+    let data_key = key_manager::generate_data_key()?;
+    let (ciphertext, nonce) = key_manager::seal(&key_manager::aes_key(&data_key)?, plaintext)?;
+    let wrapped = keys.wrap(&data_key).await?;
+    Ok(SealedDocument {
+        ciphertext,
+        nonce,
+        wrapped_data_key: wrapped.ciphertext,
+        kek_version: wrapped.kek_version,
+    })
 }
 
-fn decrypt_bytes(
+/// Decrypt content under its wrapped data key, or the legacy key when it has none.
+async fn open_document(
+    keys: &dyn KeyManager,
     ciphertext: &[u8],
-    nonce_bytes: &[u8],
-    secret: &[u8],
+    nonce: &[u8],
+    wrapped_data_key: Option<&[u8]>,
+    kek_version: Option<i32>,
+) -> Result<Vec<u8>, ApiError> {
+    let key = match (wrapped_data_key, kek_version) {
+        (Some(wrapped), Some(version)) => {
+            key_manager::aes_key(&keys.unwrap(version, wrapped).await?)?
+        }
+        (None, None) => legacy_key()?,
+        _ => {
+            return Err(ApiError::Internal(anyhow::anyhow!(
+                "Document data key is incomplete"
+            )))
+        }
+    };
+    key_manager::open(&key, ciphertext, nonce)
+}
+
+async fn load_ciphertext(
+    store: &dyn BlobStore,
+    blob_hash: Option<&str>,
+    inline: Option<Vec<u8>>,
 ) -> Result<Vec<u8>, ApiError> {
-    let key = derive_key(secret)?;
-    let mut nonce_arr = [0u8; NONCE_LEN];
-    if nonce_bytes.len() != NONCE_LEN {
-        return Err(ApiError::Internal(anyhow::anyhow!("Invalid nonce length")));
+    match (blob_hash, inline) {
+        (Some(hash), _) => BlobService::load(store, hash).await,
+        (None, Some(inline)) => Ok(inline),
+        (None, None) => Err(ApiError::Internal(anyhow::anyhow!(
+            "Missing encrypted content"
+        ))),
     }
-    nonce_arr.copy_from_slice(nonce_bytes);
-    let nonce = Nonce::assume_unique_for_key(nonce_arr);
-
-    let mut in_out = ciphertext.to_vec();
-    let plaintext = key
-        .open_in_place(nonce, Aad::empty(), &mut in_out)
-        .map_err(|_| ApiError::Internal(anyhow::anyhow!("Decryption failed")))?;
-    Ok(plaintext.to_vec())
 }
 
 // ---------------------------------------------------------------------------
@@ -88,6 +141,33 @@ pub struct BackupRecord {
     pub created_at: DateTime<Utc>,
 }
 
+/// Encrypted rows per KEK version; `None` is the pre-envelope key
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct KekVersionCount {
+    pub kek_version: Option<i32>,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentKeyStatus {
+    pub key_manager: String,
+    pub active_version: i32,
+    pub documents: Vec<KekVersionCount>,
+    pub backups: Vec<KekVersionCount>,
+    pub legacy_content: Vec<KekVersionCount>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KeyRotationProgress {
+    /// Data keys re-wrapped under the active KEK
+    pub rewrapped: usize,
+    /// Pre-envelope rows re-encrypted under a new data key
+    pub reencrypted: usize,
+    pub failed: usize,
+    /// Rows still on another KEK version after this run
+    pub remaining: i64,
+}
+
 // ---------------------------------------------------------------------------
 // Service
 // ---------------------------------------------------------------------------
@@ -95,9 +175,12 @@ pub struct BackupRecord {
 pub struct DocumentStorageService;
 
 impl DocumentStorageService {
-    /// Encrypt an existing document's content and store the ciphertext.
+    /// Encrypt an existing document's content under a new data key and store
+    /// the ciphertext.
     pub async fn store_encrypted(
         db: &PgPool,
+        store: &dyn BlobStore,
+        keys: &dyn KeyManager,
         user_id: Uuid,
         document_id: Uuid,
         content_bytes: &[u8],
@@ -117,45 +200,91 @@ impl DocumentStorageService {
             ));
         }
 
-        let secret = load_encryption_secret();
-        if secret.is_empty() {
-            return Err(ApiError::Internal(anyhow::anyhow!(
-                "DOCUMENT_ENCRYPTION_KEY is not configured"
-            )));
+        let mut sealed = seal_document(keys, content_bytes).await?;
+        let ciphertext = std::mem::take(&mut sealed.ciphertext);
+        let blob = BlobService::store_bytes(db, store, ciphertext).await?;
+
+        // Swap the reference under the document's row lock. The new blob's
+        // reference is already committed, so release it if the swap fails.
+        let previous = match Self::swap_blob(db, user_id, document_id, &blob.hash, &sealed).await {
+            Ok(previous) => previous,
+            Err(e) => {
+                if let Err(release_err) = BlobService::release(db, store, &blob.hash).await {
+                    error!(
+                        "Failed to release blob {} after a failed store: {}",
+                        blob.hash, release_err
+                    );
+                }
+                return Err(e);
+            }
+        };
+
+        if let Some(previous) = previous {
+            BlobService::release(db, store, &previous).await?;
         }
 
-        let (ciphertext, nonce) = encrypt_bytes(content_bytes, &secret)?;
+        Ok(())
+    }
+
+    /// Point a document at `hash`, returning the blob it pointed at before
+    async fn swap_blob(
+        db: &PgPool,
+        user_id: Uuid,
+        document_id: Uuid,
+        hash: &str,
+        sealed: &SealedDocument,
+    ) -> Result<Option<String>, ApiError> {
+        let mut tx = db.begin().await?;
+        let previous: Option<String> = sqlx::query_scalar(
+            "SELECT encrypted_blob_hash FROM will_documents \
+             WHERE id = $1 AND user_id = $2 FOR UPDATE",
+        )
+        .bind(document_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| ApiError::Forbidden("Not authorized to access this document".to_string()))?;
 
         sqlx::query(
             "UPDATE will_documents \
-             SET encrypted_content = $1, encryption_nonce = $2, is_encrypted = TRUE \
-             WHERE id = $3 AND user_id = $4",
+             SET encrypted_blob_hash = $1, encrypted_content = NULL, encryption_nonce = $2, \
+                 wrapped_data_key = $3, kek_version = $4, is_encrypted = TRUE \
+             WHERE id = $5 AND user_id = $6",
         )
-        .bind(&ciphertext)
-        .bind(&nonce)
+        .bind(hash)
+        .bind(&sealed.nonce)
+        .bind(&sealed.wrapped_data_key)
+        .bind(sealed.kek_version)
         .bind(document_id)
         .bind(user_id)
-        .execute(db)
+        .execute(&mut *tx)
         .await?;
+        tx.commit().await?;
 
-        Ok(())
+        Ok(previous)
     }
 
     /// Retrieve and decrypt a document's encrypted content.
     pub async fn retrieve_decrypted(
         db: &PgPool,
+        store: &dyn BlobStore,
+        keys: &dyn KeyManager,
         user_id: Uuid,
         document_id: Uuid,
     ) -> Result<Vec<u8>, ApiError> {
         #[derive(sqlx::FromRow)]
         struct Row {
             encrypted_content: Option<Vec<u8>>,
+            encrypted_blob_hash: Option<String>,
             encryption_nonce: Option<Vec<u8>>,
+            wrapped_data_key: Option<Vec<u8>>,
+            kek_version: Option<i32>,
             is_encrypted: bool,
         }
 
         let row = sqlx::query_as::<_, Row>(
-            "SELECT encrypted_content, encryption_nonce, is_encrypted \
+            "SELECT encrypted_content, encrypted_blob_hash, encryption_nonce, wrapped_data_key, \
+                    kek_version, is_encrypted \
              FROM will_documents WHERE id = $1 AND user_id = $2",
         )
         .bind(document_id)
@@ -170,38 +299,50 @@ impl DocumentStorageService {
             ));
         }
 
-        let ciphertext = row
-            .encrypted_content
-            .ok_or_else(|| ApiError::Internal(anyhow::anyhow!("Missing encrypted content")))?;
+        let ciphertext = load_ciphertext(
+            store,
+            row.encrypted_blob_hash.as_deref(),
+            row.encrypted_content,
+        )
+        .await?;
         let nonce = row
             .encryption_nonce
             .ok_or_else(|| ApiError::Internal(anyhow::anyhow!("Missing encryption nonce")))?;
 
-        let secret = load_encryption_secret();
-        if secret.is_empty() {
-            return Err(ApiError::Internal(anyhow::anyhow!(
-                "DOCUMENT_ENCRYPTION_KEY is not configured"
-            )));
-        }
-
-        decrypt_bytes(&ciphertext, &nonce, &secret)
+        open_document(
+            keys,
+            &ciphertext,
+            &nonce,
+            row.wrapped_data_key.as_deref(),
+            row.kek_version,
+        )
+        .await
     }
 
     /// Create an encrypted backup of a document.
+    ///
+    /// Backups reference the same content-addressed blob as the document, so
+    /// repeated backups of unchanged ciphertext take no extra space. They keep
+    /// their own copy of the wrapped data key.
     pub async fn create_backup(
         db: &PgPool,
+        store: &dyn BlobStore,
         user_id: Uuid,
         document_id: Uuid,
     ) -> Result<BackupRecord, ApiError> {
         #[derive(sqlx::FromRow)]
         struct DocRow {
             encrypted_content: Option<Vec<u8>>,
+            encrypted_blob_hash: Option<String>,
             encryption_nonce: Option<Vec<u8>>,
+            wrapped_data_key: Option<Vec<u8>>,
+            kek_version: Option<i32>,
             is_encrypted: bool,
         }
 
         let doc = sqlx::query_as::<_, DocRow>(
-            "SELECT encrypted_content, encryption_nonce, is_encrypted \
+            "SELECT encrypted_content, encrypted_blob_hash, encryption_nonce, wrapped_data_key, \
+                    kek_version, is_encrypted \
              FROM will_documents WHERE id = $1 AND user_id = $2",
         )
         .bind(document_id)
@@ -216,33 +357,47 @@ impl DocumentStorageService {
             ));
         }
 
-        let ciphertext = doc
-            .encrypted_content
-            .ok_or_else(|| ApiError::Internal(anyhow::anyhow!("Missing encrypted content")))?;
         let nonce = doc
             .encryption_nonce
             .ok_or_else(|| ApiError::Internal(anyhow::anyhow!("Missing encryption nonce")))?;
 
-        let hash_bytes = digest(&SHA256, &ciphertext);
-        let backup_hash = hex::encode(hash_bytes.as_ref());
+        // The blob hash is the SHA-256 of the ciphertext, i.e. the backup hash.
+        let backup_hash = match (doc.encrypted_blob_hash, doc.encrypted_content) {
+            (Some(hash), _) => {
+                BlobService::retain(db, &hash).await?;
+                hash
+            }
+            (None, Some(inline)) => BlobService::store_bytes(db, store, inline).await?.hash,
+            (None, None) => {
+                return Err(ApiError::Internal(anyhow::anyhow!(
+                    "Missing encrypted content"
+                )))
+            }
+        };
 
         let backup_id = Uuid::new_v4();
         let created_at = Utc::now();
 
-        sqlx::query(
+        let inserted = sqlx::query(
             "INSERT INTO document_backups \
-             (id, document_id, user_id, backup_hash, encrypted_content, encryption_nonce, created_at) \
-             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+             (id, document_id, user_id, backup_hash, blob_hash, encryption_nonce, \
+              wrapped_data_key, kek_version, created_at) \
+             VALUES ($1, $2, $3, $4, $4, $5, $6, $7, $8)",
         )
         .bind(backup_id)
         .bind(document_id)
         .bind(user_id)
         .bind(&backup_hash)
-        .bind(&ciphertext)
         .bind(&nonce)
+        .bind(&doc.wrapped_data_key)
+        .bind(doc.kek_version)
         .bind(created_at)
         .execute(db)
-        .await?;
+        .await;
+        if let Err(e) = inserted {
+            let _ = BlobService::release(db, store, &backup_hash).await;
+            return Err(e.into());
+        }
 
         Ok(BackupRecord {
             id: backup_id,
@@ -307,6 +462,334 @@ impl DocumentStorageService {
     }
 }
 
+// ---------------------------------------------------------------------------
+// KEK rotation
+// ---------------------------------------------------------------------------
+
+/// Encrypted rows the re-encryption job moves onto the active KEK
+#[derive(Debug, Clone, Copy)]
+enum RotationTarget {
+    Document,
+    Backup,
+}
+
+impl RotationTarget {
+    fn table(&self) -> &'static str {
+        match self {
+            Self::Document => "will_documents",
+            Self::Backup => "document_backups",
+        }
+    }
+
+    fn blob_column(&self) -> &'static str {
+        match self {
+            Self::Document => "encrypted_blob_hash",
+            Self::Backup => "blob_hash",
+        }
+    }
+
+    fn filter(&self) -> &'static str {
+        match self {
+            Self::Document => "is_encrypted",
+            Self::Backup => "TRUE",
+        }
+    }
+}
+
+/// Moves encrypted documents, backups and legacy content keys onto the
+/// active KEK while the server keeps serving them.
+///
+/// Rows already under a wrapped data key only have that key re-wrapped.
+/// Rows from before envelope encryption are re-encrypted under a new data
+/// key. Each row is locked and updated in its own transaction, so readers
+/// see either the old or the new key, never a mix.
+pub struct DocumentKeyRotationService {
+    db: PgPool,
+    store: Arc<dyn BlobStore>,
+    keys: Arc<dyn KeyManager>,
+}
+
+impl DocumentKeyRotationService {
+    pub fn new(db: PgPool, store: Arc<dyn BlobStore>, keys: Arc<dyn KeyManager>) -> Self {
+        Self { db, store, keys }
+    }
+
+    pub fn start(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(300));
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.run_batch().await {
+                    error!("Document Key Rotation Service error: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Encrypted documents and backups per KEK version.
+    pub async fn status(db: &PgPool, keys: &dyn KeyManager) -> Result<DocumentKeyStatus, ApiError> {
+        let documents = sqlx::query_as::<_, KekVersionCount>(
+            "SELECT kek_version, COUNT(*) AS count FROM will_documents \
+             WHERE is_encrypted GROUP BY kek_version ORDER BY kek_version NULLS FIRST",
+        )
+        .fetch_all(db)
+        .await?;
+        let backups = sqlx::query_as::<_, KekVersionCount>(
+            "SELECT kek_version, COUNT(*) AS count FROM document_backups \
+             GROUP BY kek_version ORDER BY kek_version NULLS FIRST",
+        )
+        .fetch_all(db)
+        .await?;
+        let legacy_content = sqlx::query_as::<_, KekVersionCount>(
+            "SELECT CASE WHEN wrapped_data_key IS NULL THEN NULL ELSE encryption_key_version END \
+                    AS kek_version, COUNT(*) AS count \
+             FROM legacy_content WHERE encrypted GROUP BY 1 ORDER BY 1 NULLS FIRST",
+        )
+        .fetch_all(db)
+        .await?;
+
+        Ok(DocumentKeyStatus {
+            key_manager: keys.name().to_string(),
+            active_version: keys.active_version(),
+            documents,
+            backups,
+            legacy_content,
+        })
+    }
+
+    /// Move up to one batch of documents and of backups onto the active KEK.
+    pub async fn run_batch(&self) -> Result<KeyRotationProgress, ApiError> {
+        let active = self.keys.active_version();
+        let mut progress = KeyRotationProgress::default();
+
+        for target in [RotationTarget::Document, RotationTarget::Backup] {
+            let ids: Vec<Uuid> = sqlx::query_scalar(&format!(
+                "SELECT id FROM {} WHERE {} AND kek_version IS DISTINCT FROM $1 \
+                 ORDER BY created_at ASC LIMIT $2",
+                target.table(),
+                target.filter()
+            ))
+            .bind(active)
+            .bind(ROTATION_BATCH_SIZE)
+            .fetch_all(&self.db)
+            .await?;
+
+            for id in ids {
+                match self.rotate_row(target, id).await {
+                    Ok(Some(true)) => progress.reencrypted += 1,
+                    Ok(Some(false)) => progress.rewrapped += 1,
+                    Ok(None) => {}
+                    Err(e) => {
+                        progress.failed += 1;
+                        warn!(
+                            "Failed moving {} {} to KEK v{}: {}",
+                            target.table(),
+                            id,
+                            active,
+                            e
+                        );
+                    }
+                }
+            }
+
+            let remaining: i64 = sqlx::query_scalar(&format!(
+                "SELECT COUNT(*) FROM {} WHERE {} AND kek_version IS DISTINCT FROM $1",
+                target.table(),
+                target.filter()
+            ))
+            .bind(active)
+            .fetch_one(&self.db)
+            .await?;
+            progress.remaining += remaining;
+        }
+
+        let ids: Vec<Uuid> = sqlx::query_scalar(&format!(
+            "SELECT id FROM legacy_content WHERE {LEGACY_CONTENT_TO_ROTATE} \
+             ORDER BY created_at ASC LIMIT $2"
+        ))
+        .bind(active)
+        .bind(ROTATION_BATCH_SIZE)
+        .fetch_all(&self.db)
+        .await?;
+        for id in ids {
+            match self.rewrap_legacy_content(id).await {
+                Ok(true) => progress.rewrapped += 1,
+                Ok(false) => {}
+                Err(e) => {
+                    progress.failed += 1;
+                    warn!(
+                        "Failed moving legacy_content {} to KEK v{}: {}",
+                        id, active, e
+                    );
+                }
+            }
+        }
+        let remaining: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM legacy_content WHERE {LEGACY_CONTENT_TO_ROTATE}"
+        ))
+        .bind(active)
+        .fetch_one(&self.db)
+        .await?;
+        progress.remaining += remaining;
+
+        if progress.rewrapped + progress.reencrypted > 0 {
+            info!(
+                "Moved {} document keys to KEK v{} ({} re-encrypted), {} remaining",
+                progress.rewrapped + progress.reencrypted,
+                active,
+                progress.reencrypted,
+                progress.remaining
+            );
+        }
+        Ok(progress)
+    }
+
+    /// Wrap a legacy content record's data key under the active KEK. Content
+    /// sealed before its key was wrapped used the key derived from
+    /// `DOCUMENT_ENCRYPTION_KEY`; once that key is wrapped the secret is no
+    /// longer needed to read it. Returns false when the row is locked
+    /// elsewhere or already on the active KEK.
+    async fn rewrap_legacy_content(&self, id: Uuid) -> Result<bool, ApiError> {
+        let keys = self.keys.as_ref();
+        let mut tx = self.db.begin().await?;
+
+        let row: Option<(Option<Vec<u8>>, Option<i32>)> = sqlx::query_as(&format!(
+            "SELECT wrapped_data_key, encryption_key_version FROM legacy_content \
+             WHERE id = $2 AND {LEGACY_CONTENT_TO_ROTATE} FOR UPDATE SKIP LOCKED"
+        ))
+        .bind(keys.active_version())
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some((wrapped, version)) = row else {
+            return Ok(false);
+        };
+
+        let data_key = match (wrapped, version) {
+            (Some(wrapped), Some(version)) => keys.unwrap(version, &wrapped).await?,
+            (Some(_), None) => {
+                return Err(ApiError::Internal(anyhow::anyhow!(
+                    "Content data key is incomplete"
+                )))
+            }
+            (None, _) => legacy_key_bytes()?.to_vec(),
+        };
+        let rewrapped = keys.wrap(&data_key).await?;
+        sqlx::query(
+            "UPDATE legacy_content SET wrapped_data_key = $2, encryption_key_version = $3 \
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(&rewrapped.ciphertext)
+        .bind(rewrapped.kek_version)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Returns whether the row was re-encrypted (rather than re-wrapped), or
+    /// `None` when it is locked elsewhere or already on the active KEK.
+    async fn rotate_row(&self, target: RotationTarget, id: Uuid) -> Result<Option<bool>, ApiError> {
+        #[derive(sqlx::FromRow)]
+        struct Row {
+            encrypted_content: Option<Vec<u8>>,
+            blob_hash: Option<String>,
+            encryption_nonce: Option<Vec<u8>>,
+            wrapped_data_key: Option<Vec<u8>>,
+            kek_version: Option<i32>,
+        }
+
+        let table = target.table();
+        let blob_column = target.blob_column();
+        let keys = self.keys.as_ref();
+        let mut tx = self.db.begin().await?;
+
+        let row = sqlx::query_as::<_, Row>(&format!(
+            "SELECT encrypted_content, {blob_column} AS blob_hash, encryption_nonce, \
+                    wrapped_data_key, kek_version \
+             FROM {table} WHERE id = $1 AND {} AND kek_version IS DISTINCT FROM $2 \
+             FOR UPDATE SKIP LOCKED",
+            target.filter()
+        ))
+        .bind(id)
+        .bind(keys.active_version())
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        if let (Some(wrapped), Some(version)) = (&row.wrapped_data_key, row.kek_version) {
+            let data_key = keys.unwrap(version, wrapped).await?;
+            let rewrapped = keys.wrap(&data_key).await?;
+            sqlx::query(&format!(
+                "UPDATE {table} SET wrapped_data_key = $2, kek_version = $3 WHERE id = $1"
+            ))
+            .bind(id)
+            .bind(&rewrapped.ciphertext)
+            .bind(rewrapped.kek_version)
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+            return Ok(Some(false));
+        }
+
+        let nonce = row
+            .encryption_nonce
+            .ok_or_else(|| ApiError::Internal(anyhow::anyhow!("Missing encryption nonce")))?;
+        let ciphertext = load_ciphertext(
+            self.store.as_ref(),
+            row.blob_hash.as_deref(),
+            row.encrypted_content,
+        )
+        .await?;
+        let plaintext = open_document(
+            keys,
+            &ciphertext,
+            &nonce,
+            row.wrapped_data_key.as_deref(),
+            row.kek_version,
+        )
+        .await?;
+        let sealed = seal_document(keys, &plaintext).await?;
+        let blob =
+            BlobService::store_bytes(&self.db, self.store.as_ref(), sealed.ciphertext).await?;
+
+        // A backup's hash is the hash of its ciphertext, which has changed.
+        let backup_hash = match target {
+            RotationTarget::Backup => ", backup_hash = $2",
+            RotationTarget::Document => "",
+        };
+        let updated = sqlx::query(&format!(
+            "UPDATE {table} \
+             SET {blob_column} = $2, encrypted_content = NULL, encryption_nonce = $3, \
+                 wrapped_data_key = $4, kek_version = $5{backup_hash} \
+             WHERE id = $1"
+        ))
+        .bind(id)
+        .bind(&blob.hash)
+        .bind(&sealed.nonce)
+        .bind(&sealed.wrapped_data_key)
+        .bind(sealed.kek_version)
+        .execute(&mut *tx)
+        .await;
+        let committed = match updated {
+            Ok(_) => tx.commit().await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = committed {
+            let _ = BlobService::release(&self.db, self.store.as_ref(), &blob.hash).await;
+            return Err(e.into());
+        }
+
+        if let Some(previous) = row.blob_hash {
+            BlobService::release(&self.db, self.store.as_ref(), &previous).await?;
+        }
+        Ok(Some(true))
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Unit tests
 // ---------------------------------------------------------------------------
@@ -314,11 +797,68 @@ impl DocumentStorageService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::key_manager::KmsKeyManager;
+    use ring::aead::{Aad, Nonce};
+
+    fn derive_key(secret: &[u8]) -> Result<LessSafeKey, ApiError> {
+        key_manager::aes_key(&derive_key_bytes(secret)?)
+    }
 
     fn test_secret() -> Vec<u8> {
         b"test-encryption-key-for-unit-tests".to_vec()
     }
 
+    fn encrypt_bytes(plaintext: &[u8], secret: &[u8]) -> Result<(Vec<u8>, Vec<u8>), ApiError> {
+        key_manager::seal(&derive_key(secret)?, plaintext)
+    }
+
+    fn decrypt_bytes(ciphertext: &[u8], nonce: &[u8], secret: &[u8]) -> Result<Vec<u8>, ApiError> {
+        key_manager::open(&derive_key(secret)?, ciphertext, nonce)
+    }
+
+    #[tokio::test]
+    async fn test_envelope_round_trip_survives_kek_rotation() {
+        let root = [3u8; 32];
+        let v1 = KmsKeyManager::new(&root, 1).unwrap();
+        let plaintext = b"Will content";
+        let sealed = seal_document(&v1, plaintext).await.unwrap();
+        assert_eq!(sealed.kek_version, 1);
+
+        // Documents stay readable after rotation until they are re-wrapped.
+        let v2 = KmsKeyManager::new(&root, 2).unwrap();
+        let opened = open_document(
+            &v2,
+            &sealed.ciphertext,
+            &sealed.nonce,
+            Some(&sealed.wrapped_data_key),
+            Some(sealed.kek_version),
+        )
+        .await
+        .unwrap();
+        assert_eq!(opened, plaintext);
+
+        // A wrapped key without its version is rejected rather than treated
+        // as a pre-envelope row.
+        assert!(open_document(
+            &v2,
+            &sealed.ciphertext,
+            &sealed.nonce,
+            Some(&sealed.wrapped_data_key),
+            None,
+        )
+        .await
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_documents_get_distinct_data_keys() {
+        let keys = KmsKeyManager::new(&[3u8; 32], 1).unwrap();
+        let a = seal_document(&keys, b"same").await.unwrap();
+        let b = seal_document(&keys, b"same").await.unwrap();
+        assert_ne!(a.wrapped_data_key, b.wrapped_data_key);
+        assert_ne!(a.ciphertext, b.ciphertext);
+    }
+
     #[test]
     fn test_encrypt_decrypt_roundtrip() {
         let plaintext = b"Sensitive will document content for testing.";
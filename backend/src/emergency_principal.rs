@@ -0,0 +1,578 @@
+//! Acting on an owner's behalf through an emergency access session.
+//!
+//! An emergency contact signs in with their own account and sends the ID of
+//! an active session in the `X-Emergency-Session` header. Every operation is
+//! checked against the grant's permissions and recorded in
+//! `emergency_access_audit_logs`, including denials.
+
+use crate::api_error::ApiError;
+use crate::app::AppState;
+use crate::auth::{AuthenticatedUser, UserClaims};
+use crate::contract_reader::InheritanceContractReader;
+use crate::service::EmergencyAccessService;
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub const EMERGENCY_SESSION_HEADER: &str = "x-emergency-session";
+
+/// A session unused for this long is ended on its next request
+pub const EMERGENCY_SESSION_IDLE_TIMEOUT_MINUTES: i64 = 15;
+
+/// No session outlives this, however active it is
+pub const EMERGENCY_SESSION_MAX_HOURS: i64 = 8;
+
+/// Capabilities an emergency access grant can confer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmergencyPermission {
+    ViewPlan,
+    DownloadDocuments,
+    ViewMessages,
+    ManageBeneficiaries,
+    TransferFunds,
+}
+
+impl EmergencyPermission {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ViewPlan => "view_plan",
+            Self::DownloadDocuments => "download_documents",
+            Self::ViewMessages => "view_messages",
+            Self::ManageBeneficiaries => "manage_beneficiaries",
+            Self::TransferFunds => "transfer_funds",
+        }
+    }
+}
+
+/// An emergency contact acting through an active session
+#[derive(Debug, Clone, Serialize)]
+pub struct EmergencyPrincipal {
+    pub session_id: Uuid,
+    pub grant_id: Uuid,
+    /// The account owner whose resources are accessed
+    pub owner_user_id: Uuid,
+    pub emergency_contact_id: Uuid,
+    /// The signed-in account of the emergency contact
+    pub actor_user_id: Uuid,
+    pub permissions: Vec<String>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl EmergencyPrincipal {
+    /// Resolve an active session for the signed-in contact.
+    ///
+    /// Sessions idle for [`EMERGENCY_SESSION_IDLE_TIMEOUT_MINUTES`] or older
+    /// than [`EMERGENCY_SESSION_MAX_HOURS`] are ended and refused.
+    pub async fn resolve(
+        db: &PgPool,
+        session_id: Uuid,
+        actor: &UserClaims,
+    ) -> Result<Self, ApiError> {
+        #[derive(sqlx::FromRow)]
+        struct Row {
+            grant_id: Uuid,
+            owner_user_id: Uuid,
+            emergency_contact_id: Uuid,
+            contact_email: Option<String>,
+            permissions: Vec<String>,
+            expires_at: DateTime<Utc>,
+            is_active: bool,
+            started_at: DateTime<Utc>,
+            last_active_at: DateTime<Utc>,
+        }
+
+        let row = sqlx::query_as::<_, Row>(
+            r#"
+            SELECT s.grant_id, s.user_id AS owner_user_id, s.emergency_contact_id,
+                   c.email AS contact_email, g.permissions, g.expires_at, g.is_active,
+                   s.started_at, s.last_active_at
+            FROM emergency_access_sessions s
+            JOIN emergency_access_grants g ON g.id = s.grant_id
+            JOIN emergency_contacts c ON c.id = s.emergency_contact_id
+            WHERE s.id = $1 AND s.ended_at IS NULL
+            "#,
+        )
+        .bind(session_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or(ApiError::Unauthorized)?;
+
+        if !row.is_active || row.expires_at <= Utc::now() {
+            return Err(ApiError::Forbidden(
+                "Emergency access grant is no longer active".to_string(),
+            ));
+        }
+        let is_contact = row
+            .contact_email
+            .as_deref()
+            .is_some_and(|email| email.eq_ignore_ascii_case(&actor.email));
+        if !is_contact {
+            return Err(ApiError::Forbidden(
+                "Session does not belong to this emergency contact".to_string(),
+            ));
+        }
+
+        if session_timed_out(row.started_at, row.last_active_at, Utc::now()) {
+            sqlx::query(
+                "UPDATE emergency_access_sessions SET ended_at = NOW() \
+                 WHERE id = $1 AND ended_at IS NULL",
+            )
+            .bind(session_id)
+            .execute(db)
+            .await?;
+            EmergencyAccessService::log_action(
+                db,
+                row.grant_id,
+                row.owner_user_id,
+                row.emergency_contact_id,
+                "session_timed_out",
+                serde_json::json!({
+                    "session_id": session_id,
+                    "actor_user_id": actor.user_id,
+                    "started_at": row.started_at,
+                    "last_active_at": row.last_active_at,
+                }),
+            )
+            .await?;
+            return Err(ApiError::Unauthorized);
+        }
+
+        sqlx::query("UPDATE emergency_access_sessions SET last_active_at = NOW() WHERE id = $1")
+            .bind(session_id)
+            .execute(db)
+            .await?;
+
+        Ok(Self {
+            session_id,
+            grant_id: row.grant_id,
+            owner_user_id: row.owner_user_id,
+            emergency_contact_id: row.emergency_contact_id,
+            actor_user_id: actor.user_id,
+            permissions: row.permissions,
+            expires_at: row.expires_at,
+        })
+    }
+
+    pub fn has_permission(&self, permission: EmergencyPermission) -> bool {
+        self.permissions.iter().any(|p| p == permission.as_str())
+    }
+
+    /// Record an action taken in this session
+    pub async fn audit(
+        &self,
+        db: &PgPool,
+        action: &str,
+        metadata: serde_json::Value,
+    ) -> Result<(), ApiError> {
+        EmergencyAccessService::log_action(
+            db,
+            self.grant_id,
+            self.owner_user_id,
+            self.emergency_contact_id,
+            action,
+            serde_json::json!({
+                "session_id": self.session_id,
+                "actor_user_id": self.actor_user_id,
+                "details": metadata,
+            }),
+        )
+        .await
+    }
+
+    /// Check `permission` and audit the outcome under `action`
+    pub async fn authorize(
+        &self,
+        db: &PgPool,
+        permission: EmergencyPermission,
+        action: &str,
+        metadata: serde_json::Value,
+    ) -> Result<(), ApiError> {
+        self.require(db, permission, action, metadata.clone())
+            .await?;
+        self.audit(db, action, metadata).await
+    }
+
+    /// Check `permission`, auditing only a denial. Callers audit the action
+    /// themselves once it has succeeded.
+    pub async fn require(
+        &self,
+        db: &PgPool,
+        permission: EmergencyPermission,
+        action: &str,
+        metadata: serde_json::Value,
+    ) -> Result<(), ApiError> {
+        if !self.has_permission(permission) {
+            self.audit(
+                db,
+                "permission_denied",
+                serde_json::json!({
+                    "attempted_action": action,
+                    "required_permission": permission.as_str(),
+                    "details": metadata,
+                }),
+            )
+            .await?;
+            return Err(ApiError::Forbidden(format!(
+                "Emergency access grant does not include {}",
+                permission.as_str()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Whether a session has sat idle or run for too long as of `now`
+fn session_timed_out(
+    started_at: DateTime<Utc>,
+    last_active_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> bool {
+    now - last_active_at > Duration::minutes(EMERGENCY_SESSION_IDLE_TIMEOUT_MINUTES)
+        || now - started_at > Duration::hours(EMERGENCY_SESSION_MAX_HOURS)
+}
+
+fn session_header(parts: &Parts) -> Result<Option<Uuid>, ApiError> {
+    parts
+        .headers
+        .get(EMERGENCY_SESSION_HEADER)
+        .map(|value| {
+            value
+                .to_str()
+                .ok()
+                .and_then(|v| Uuid::parse_str(v.trim()).ok())
+                .ok_or_else(|| {
+                    ApiError::BadRequest("X-Emergency-Session must be a session ID".to_string())
+                })
+        })
+        .transpose()
+}
+
+#[async_trait::async_trait]
+impl FromRequestParts<Arc<AppState>> for EmergencyPrincipal {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let AuthenticatedUser(claims) = AuthenticatedUser::from_request_parts(parts, state).await?;
+        let session_id = session_header(parts)?.ok_or(ApiError::Unauthorized)?;
+        Self::resolve(&state.db, session_id, &claims).await
+    }
+}
+
+/// Either the account owner or an emergency contact acting for them
+pub enum Principal {
+    Owner(UserClaims),
+    Emergency(EmergencyPrincipal),
+}
+
+impl Principal {
+    /// Return the owner whose resources this request may touch.
+    ///
+    /// Owners always pass; emergency principals must hold `permission`, and
+    /// the access is audited as `action`.
+    pub async fn owner_id(
+        &self,
+        db: &PgPool,
+        permission: EmergencyPermission,
+        action: &str,
+        metadata: serde_json::Value,
+    ) -> Result<Uuid, ApiError> {
+        match self {
+            Self::Owner(user) => Ok(user.user_id),
+            Self::Emergency(principal) => {
+                principal
+                    .authorize(db, permission, action, metadata)
+                    .await?;
+                Ok(principal.owner_user_id)
+            }
+        }
+    }
+
+    /// The signed-in account making the request
+    pub fn actor_user_id(&self) -> Uuid {
+        match self {
+            Self::Owner(user) => user.user_id,
+            Self::Emergency(principal) => principal.actor_user_id,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl FromRequestParts<Arc<AppState>> for Principal {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let AuthenticatedUser(claims) = AuthenticatedUser::from_request_parts(parts, state).await?;
+        match session_header(parts)? {
+            Some(session_id) => Ok(Self::Emergency(
+                EmergencyPrincipal::resolve(&state.db, session_id, &claims).await?,
+            )),
+            None => Ok(Self::Owner(claims)),
+        }
+    }
+}
+
+/// Largest total an emergency contact may move out of a plan worth
+/// `plan_amount`, given the contract's limit in basis points
+pub fn emergency_transfer_limit(plan_amount: Decimal, limit_bp: u32) -> Decimal {
+    (plan_amount * Decimal::from(limit_bp) / Decimal::from(10_000u32)).max(Decimal::ZERO)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EmergencyWithdrawalRequest {
+    pub amount: Decimal,
+    pub destination: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct EmergencyWithdrawal {
+    pub id: Uuid,
+    pub grant_id: Uuid,
+    pub session_id: Uuid,
+    pub plan_id: Uuid,
+    pub amount: Decimal,
+    pub limit_amount: Decimal,
+    pub destination: String,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub executed_at: Option<DateTime<Utc>>,
+}
+
+pub struct EmergencyWithdrawalService;
+
+impl EmergencyWithdrawalService {
+    /// Record a withdrawal from one of the owner's plans as `pending`.
+    ///
+    /// No funds move here; the plan is only debited once the contract's
+    /// emergency transfer has executed. The total requested under a single
+    /// grant, pending or executed, may not exceed the contract's emergency
+    /// transfer limit of the plan as it stood before the grant's first
+    /// executed withdrawal. The limit is read from the contract on every
+    /// request, and nothing is recorded if it cannot be read.
+    pub async fn request(
+        db: &PgPool,
+        reader: &dyn InheritanceContractReader,
+        principal: &EmergencyPrincipal,
+        plan_id: Uuid,
+        req: &EmergencyWithdrawalRequest,
+    ) -> Result<EmergencyWithdrawal, ApiError> {
+        let metadata = serde_json::json!({
+            "plan_id": plan_id,
+            "amount": req.amount,
+            "destination": req.destination,
+        });
+        principal
+            .require(
+                db,
+                EmergencyPermission::TransferFunds,
+                "withdrawal_requested",
+                metadata.clone(),
+            )
+            .await?;
+
+        if req.amount <= Decimal::ZERO {
+            return Err(ApiError::BadRequest("amount must be positive".to_string()));
+        }
+        let destination = req.destination.trim();
+        if stellar_strkey::ed25519::PublicKey::from_string(destination).is_err() {
+            return Err(ApiError::BadRequest(
+                "destination must be a Stellar account address".to_string(),
+            ));
+        }
+
+        let limit_bp = reader.emergency_transfer_limit_bp().await?;
+
+        let mut tx = db.begin().await?;
+
+        let net_amount: Decimal = sqlx::query_scalar(
+            "SELECT net_amount FROM plans WHERE id = $1 AND user_id = $2 FOR UPDATE",
+        )
+        .bind(plan_id)
+        .bind(principal.owner_user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Plan {} not found", plan_id)))?;
+
+        let (already_executed, already_requested): (Decimal, Decimal) = sqlx::query_as(
+            "SELECT COALESCE(SUM(amount) FILTER (WHERE status = 'executed'), 0), \
+                    COALESCE(SUM(amount), 0) \
+             FROM emergency_withdrawal_requests \
+             WHERE grant_id = $1 AND plan_id = $2 AND status IN ('pending', 'executed')",
+        )
+        .bind(principal.grant_id)
+        .bind(plan_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let limit = emergency_transfer_limit(net_amount + already_executed, limit_bp);
+        if already_requested + req.amount > limit {
+            drop(tx);
+            principal
+                .audit(
+                    db,
+                    "withdrawal_limit_exceeded",
+                    serde_json::json!({
+                        "request": metadata,
+                        "limit": limit,
+                        "limit_bp": limit_bp,
+                        "already_requested": already_requested,
+                    }),
+                )
+                .await?;
+            return Err(ApiError::Forbidden(format!(
+                "Emergency withdrawals are limited to {} for this plan ({} already requested)",
+                limit, already_requested
+            )));
+        }
+
+        let withdrawal = sqlx::query_as::<_, EmergencyWithdrawal>(
+            r#"
+            INSERT INTO emergency_withdrawal_requests (
+                grant_id, session_id, plan_id, user_id, emergency_contact_id,
+                requested_by_user_id, amount, limit_amount, destination, status
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, 'pending')
+            RETURNING id, grant_id, session_id, plan_id, amount, limit_amount, destination,
+                      status, created_at, executed_at
+            "#,
+        )
+        .bind(principal.grant_id)
+        .bind(principal.session_id)
+        .bind(plan_id)
+        .bind(principal.owner_user_id)
+        .bind(principal.emergency_contact_id)
+        .bind(principal.actor_user_id)
+        .bind(req.amount)
+        .bind(limit)
+        .bind(destination)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        principal
+            .audit(
+                db,
+                "withdrawal_requested",
+                serde_json::json!({ "request": metadata, "withdrawal_id": withdrawal.id }),
+            )
+            .await?;
+        Ok(withdrawal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract_reader::UnconfiguredContractReader;
+    use rust_decimal_macros::dec;
+
+    fn principal(permissions: &[&str]) -> EmergencyPrincipal {
+        EmergencyPrincipal {
+            session_id: Uuid::new_v4(),
+            grant_id: Uuid::new_v4(),
+            owner_user_id: Uuid::new_v4(),
+            emergency_contact_id: Uuid::new_v4(),
+            actor_user_id: Uuid::new_v4(),
+            permissions: permissions.iter().map(|p| p.to_string()).collect(),
+            expires_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn permissions_match_grant_capabilities() {
+        let p = principal(&["view_plan", "download_documents"]);
+        assert!(p.has_permission(EmergencyPermission::ViewPlan));
+        assert!(p.has_permission(EmergencyPermission::DownloadDocuments));
+        assert!(!p.has_permission(EmergencyPermission::TransferFunds));
+        assert!(!p.has_permission(EmergencyPermission::ManageBeneficiaries));
+    }
+
+    #[test]
+    fn transfer_limit_scales_with_basis_points() {
+        assert_eq!(emergency_transfer_limit(dec!(1000), 1000), dec!(100));
+        assert_eq!(emergency_transfer_limit(dec!(12.5), 1000), dec!(1.25));
+        assert_eq!(emergency_transfer_limit(dec!(1000), 250), dec!(25));
+        assert_eq!(emergency_transfer_limit(dec!(0), 1000), dec!(0));
+        assert_eq!(emergency_transfer_limit(dec!(-5), 1000), dec!(0));
+    }
+
+    #[test]
+    fn sessions_time_out_when_idle_or_too_old() {
+        let now = Utc::now();
+        assert!(!session_timed_out(
+            now - Duration::hours(1),
+            now - Duration::minutes(5),
+            now
+        ));
+        assert!(session_timed_out(
+            now - Duration::hours(1),
+            now - Duration::minutes(EMERGENCY_SESSION_IDLE_TIMEOUT_MINUTES + 1),
+            now
+        ));
+        assert!(session_timed_out(
+            now - Duration::hours(EMERGENCY_SESSION_MAX_HOURS) - Duration::minutes(1),
+            now - Duration::minutes(1),
+            now
+        ));
+    }
+
+    #[tokio::test]
+    async fn withdrawal_is_refused_when_the_limit_cannot_be_read() {
+        let db = PgPool::connect_lazy("postgres://localhost/test").unwrap();
+        let req = EmergencyWithdrawalRequest {
+            amount: dec!(10),
+            destination: stellar_strkey::ed25519::PublicKey([3; 32])
+                .to_string()
+                .as_str()
+                .to_owned(),
+        };
+        let err = EmergencyWithdrawalService::request(
+            &db,
+            &UnconfiguredContractReader,
+            &principal(&["transfer_funds"]),
+            Uuid::new_v4(),
+            &req,
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            matches!(&err, ApiError::Internal(e) if e.to_string().contains("not configured")),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn withdrawal_requires_a_stellar_destination() {
+        let db = PgPool::connect_lazy("postgres://localhost/test").unwrap();
+        for destination in ["", "GDEST", "not-an-address"] {
+            let req = EmergencyWithdrawalRequest {
+                amount: dec!(10),
+                destination: destination.to_string(),
+            };
+            let err = EmergencyWithdrawalService::request(
+                &db,
+                &UnconfiguredContractReader,
+                &principal(&["transfer_funds"]),
+                Uuid::new_v4(),
+                &req,
+            )
+            .await
+            .unwrap_err();
+            assert!(
+                matches!(&err, ApiError::BadRequest(m) if m.contains("Stellar account")),
+                "unexpected error for {destination:?}: {err:?}"
+            );
+        }
+    }
+}
@@ -104,6 +104,9 @@ pub struct LiquidationMetadata {
     pub collateral_seized: rust_decimal::Decimal,
     pub debt_covered: rust_decimal::Decimal,
     pub liquidation_penalty: rust_decimal::Decimal,
+    /// Set on bids and shortfalls of a liquidation auction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auction_id: Option<Uuid>,
 }
 
 /// Metadata for interest accrual events
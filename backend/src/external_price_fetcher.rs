@@ -5,6 +5,7 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tracing::{error, info, warn};
 
@@ -52,8 +53,12 @@ impl CoinGeckoProvider {
         }
     }
 
-    /// Map asset codes to CoinGecko IDs
+    /// Map asset codes to CoinGecko IDs. Issued Stellar assets (`CODE:ISSUER`)
+    /// are not the exchange-listed token of the same code, so never match.
     fn get_coingecko_id(asset_code: &str) -> Option<&'static str> {
+        if asset_code.contains(':') {
+            return None;
+        }
         match asset_code.to_uppercase().as_str() {
             "ETH" => Some("ethereum"),
             "BTC" => Some("bitcoin"),
@@ -64,6 +69,7 @@ impl CoinGeckoProvider {
             "ADA" => Some("cardano"),
             "MATIC" => Some("matic-network"),
             "DAI" => Some("dai"),
+            "XLM" => Some("stellar"),
             _ => None,
         }
     }
@@ -150,8 +156,11 @@ impl BinanceProvider {
         }
     }
 
-    /// Map asset codes to Binance symbols
+    /// Map asset codes to Binance symbols; issued Stellar assets never match
     fn get_binance_symbol(asset_code: &str) -> Option<&'static str> {
+        if asset_code.contains(':') {
+            return None;
+        }
         match asset_code.to_uppercase().as_str() {
             "ETH" => Some("ETHUSDT"),
             "BTC" => Some("BTCUSDT"),
@@ -162,6 +171,7 @@ impl BinanceProvider {
             "ADA" => Some("ADAUSDT"),
             "MATIC" => Some("MATICUSDT"),
             "DAI" => Some("DAIUSDT"),
+            "XLM" => Some("XLMUSDT"),
             _ => None,
         }
     }
@@ -223,9 +233,226 @@ impl ExternalPriceProvider for BinanceProvider {
     }
 }
 
-/// Redundant price fetcher with fallback logic
+/// Guards applied when combining quotes from several providers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceAggregationConfig {
+    /// Per-provider request timeout
+    pub source_timeout_ms: u64,
+    /// Largest fraction a quote may sit from the median before it is
+    /// discarded as an outlier (0.02 = 2%)
+    pub max_deviation: Decimal,
+    /// Accepted quotes needed before a price is published
+    pub min_sources: usize,
+    /// Quotes older than this are discarded as stale
+    pub max_age_secs: i64,
+}
+
+impl Default for PriceAggregationConfig {
+    fn default() -> Self {
+        Self {
+            source_timeout_ms: 5_000,
+            max_deviation: Decimal::new(2, 2),
+            min_sources: 2,
+            max_age_secs: 120,
+        }
+    }
+}
+
+impl PriceAggregationConfig {
+    /// Defaults overridden by `PRICE_SOURCE_TIMEOUT_MS`,
+    /// `PRICE_MAX_DEVIATION_PERCENT`, `PRICE_MIN_SOURCES` and
+    /// `PRICE_MAX_AGE_SECS`.
+    pub fn from_env() -> Self {
+        fn var<T: FromStr>(name: &str) -> Option<T> {
+            std::env::var(name).ok().and_then(|v| v.trim().parse().ok())
+        }
+        let defaults = Self::default();
+        Self {
+            source_timeout_ms: var("PRICE_SOURCE_TIMEOUT_MS").unwrap_or(defaults.source_timeout_ms),
+            max_deviation: var::<Decimal>("PRICE_MAX_DEVIATION_PERCENT")
+                .map(|pct| pct / Decimal::ONE_HUNDRED)
+                .unwrap_or(defaults.max_deviation),
+            min_sources: var("PRICE_MIN_SOURCES").unwrap_or(defaults.min_sources),
+            max_age_secs: var("PRICE_MAX_AGE_SECS").unwrap_or(defaults.max_age_secs),
+        }
+    }
+}
+
+/// What happened to one provider's quote
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteStatus {
+    Accepted,
+    Outlier,
+    Stale,
+    Failed,
+    TimedOut,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceQuote {
+    pub source: String,
+    pub status: QuoteStatus,
+    pub price: Option<Decimal>,
+    /// Fractional distance from the median of fresh quotes
+    pub deviation: Option<Decimal>,
+    pub error: Option<String>,
+}
+
+/// Median of the accepted quotes, with every quote and why it was (not) used
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregatedPrice {
+    pub asset_code: String,
+    /// `None` when fewer than `min_sources` quotes were accepted
+    pub price: Option<Decimal>,
+    pub accepted_sources: usize,
+    /// `(max - min) / median` over fresh quotes
+    pub spread: Decimal,
+    /// Set when any fresh quote was rejected as an outlier or the spread
+    /// exceeds the allowed deviation
+    pub disagreement: bool,
+    pub quotes: Vec<SourceQuote>,
+    pub timestamp_seconds: i64,
+}
+
+/// Running quality counters for one provider
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceStats {
+    pub source: String,
+    pub requests: u64,
+    pub accepted: u64,
+    pub outliers: u64,
+    pub stale: u64,
+    pub failures: u64,
+    pub timeouts: u64,
+    pub last_deviation: Option<Decimal>,
+    pub last_error: Option<String>,
+}
+
+impl SourceStats {
+    /// Share of requests whose quote was used
+    pub fn acceptance_rate(&self) -> Decimal {
+        if self.requests == 0 {
+            return Decimal::ZERO;
+        }
+        Decimal::from(self.accepted) / Decimal::from(self.requests)
+    }
+}
+
+fn median(sorted: &[Decimal]) -> Option<Decimal> {
+    let n = sorted.len();
+    match n {
+        0 => None,
+        _ if n % 2 == 1 => Some(sorted[n / 2]),
+        _ => Some((sorted[n / 2 - 1] + sorted[n / 2]) / Decimal::TWO),
+    }
+}
+
+/// A provider's answer before aggregation
+pub(crate) enum RawQuote {
+    Price(ExternalPrice),
+    Failed(String),
+    TimedOut,
+}
+
+/// Combine provider answers into a median price.
+pub(crate) fn aggregate(
+    asset_code: &str,
+    raw: Vec<(String, RawQuote)>,
+    config: &PriceAggregationConfig,
+    now_seconds: i64,
+) -> AggregatedPrice {
+    let mut quotes: Vec<SourceQuote> = raw
+        .into_iter()
+        .map(|(source, quote)| match quote {
+            RawQuote::Price(p) if p.price <= Decimal::ZERO => SourceQuote {
+                source,
+                status: QuoteStatus::Failed,
+                price: Some(p.price),
+                deviation: None,
+                error: Some("Non-positive price".to_string()),
+            },
+            RawQuote::Price(p) if now_seconds - p.timestamp_seconds > config.max_age_secs => {
+                SourceQuote {
+                    source,
+                    status: QuoteStatus::Stale,
+                    price: Some(p.price),
+                    deviation: None,
+                    error: None,
+                }
+            }
+            RawQuote::Price(p) => SourceQuote {
+                source,
+                status: QuoteStatus::Accepted,
+                price: Some(p.price),
+                deviation: None,
+                error: None,
+            },
+            RawQuote::Failed(error) => SourceQuote {
+                source,
+                status: QuoteStatus::Failed,
+                price: None,
+                deviation: None,
+                error: Some(error),
+            },
+            RawQuote::TimedOut => SourceQuote {
+                source,
+                status: QuoteStatus::TimedOut,
+                price: None,
+                deviation: None,
+                error: None,
+            },
+        })
+        .collect();
+
+    let mut fresh: Vec<Decimal> = quotes
+        .iter()
+        .filter(|q| q.status == QuoteStatus::Accepted)
+        .filter_map(|q| q.price)
+        .collect();
+    fresh.sort();
+
+    let mut spread = Decimal::ZERO;
+    if let Some(reference) = median(&fresh) {
+        spread = (fresh[fresh.len() - 1] - fresh[0]) / reference;
+        for quote in quotes
+            .iter_mut()
+            .filter(|q| q.status == QuoteStatus::Accepted)
+        {
+            let price = quote.price.unwrap_or_default();
+            let deviation = (price - reference).abs() / reference;
+            quote.deviation = Some(deviation.round_dp(6));
+            if deviation > config.max_deviation {
+                quote.status = QuoteStatus::Outlier;
+            }
+        }
+    }
+
+    let mut accepted: Vec<Decimal> = quotes
+        .iter()
+        .filter(|q| q.status == QuoteStatus::Accepted)
+        .filter_map(|q| q.price)
+        .collect();
+    accepted.sort();
+    let has_outliers = quotes.iter().any(|q| q.status == QuoteStatus::Outlier);
+
+    AggregatedPrice {
+        asset_code: asset_code.to_uppercase(),
+        price: median(&accepted).filter(|_| accepted.len() >= config.min_sources.max(1)),
+        accepted_sources: accepted.len(),
+        spread: spread.round_dp(6),
+        disagreement: has_outliers || spread > config.max_deviation,
+        quotes,
+        timestamp_seconds: now_seconds,
+    }
+}
+
+/// Queries every provider concurrently and publishes the median of the
+/// quotes that agree, once enough of them do.
 pub struct RedundantPriceFetcher {
-    providers: Vec<Box<dyn ExternalPriceProvider>>,
+    providers: Vec<Arc<dyn ExternalPriceProvider>>,
+    config: PriceAggregationConfig,
+    stats: Mutex<HashMap<String, SourceStats>>,
 }
 
 impl Default for RedundantPriceFetcher {
@@ -236,57 +463,154 @@ impl Default for RedundantPriceFetcher {
 
 impl RedundantPriceFetcher {
     pub fn new() -> Self {
+        let mut fetcher = Self::empty(PriceAggregationConfig::from_env());
+        fetcher.add_provider(Box::new(BinanceProvider::new()));
+        fetcher.add_provider(Box::new(CoinGeckoProvider::new()));
+        for provider in crate::stellar_price_sources::stellar_providers_from_env() {
+            fetcher.add_provider(provider);
+        }
+        fetcher
+    }
+
+    /// A fetcher with no providers; add them with [`Self::add_provider`].
+    pub fn empty(config: PriceAggregationConfig) -> Self {
         Self {
-            providers: vec![
-                Box::new(BinanceProvider::new()),
-                Box::new(CoinGeckoProvider::new()),
-            ],
+            providers: Vec::new(),
+            config,
+            stats: Mutex::new(HashMap::new()),
         }
     }
 
-    /// Fetch price with fallback to next provider on failure
-    pub async fn fetch_price(&self, asset_code: &str) -> Result<ExternalPrice, ApiError> {
-        let mut last_error = None;
+    pub fn config(&self) -> &PriceAggregationConfig {
+        &self.config
+    }
 
+    /// Query all providers and aggregate their quotes.
+    pub async fn fetch_aggregated(&self, asset_code: &str) -> AggregatedPrice {
+        let timeout = Duration::from_millis(self.config.source_timeout_ms);
+        let mut tasks = tokio::task::JoinSet::new();
         for (idx, provider) in self.providers.iter().enumerate() {
-            info!(
-                "Attempting to fetch {} from {} ({}/{})",
+            let provider = provider.clone();
+            let asset_code = asset_code.to_string();
+            tasks.spawn(async move {
+                let quote =
+                    match tokio::time::timeout(timeout, provider.fetch_price(&asset_code)).await {
+                        Ok(Ok(price)) => RawQuote::Price(price),
+                        Ok(Err(e)) => RawQuote::Failed(e.to_string()),
+                        Err(_) => RawQuote::TimedOut,
+                    };
+                (idx, quote)
+            });
+        }
+
+        let mut raw: Vec<Option<RawQuote>> = self.providers.iter().map(|_| None).collect();
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok((idx, quote)) => raw[idx] = Some(quote),
+                Err(e) => error!("Price provider task failed: {}", e),
+            }
+        }
+        let raw = self
+            .providers
+            .iter()
+            .zip(raw)
+            .map(|(provider, quote)| {
+                (
+                    provider.name().to_string(),
+                    quote.unwrap_or_else(|| RawQuote::Failed("Provider task failed".to_string())),
+                )
+            })
+            .collect();
+
+        let aggregated = aggregate(
+            asset_code,
+            raw,
+            &self.config,
+            chrono::Utc::now().timestamp(),
+        );
+        self.record_stats(&aggregated);
+
+        match aggregated.price {
+            Some(price) => info!(
+                "Aggregated {} price {} from {}/{} sources (spread {})",
                 asset_code,
-                provider.name(),
-                idx + 1,
-                self.providers.len()
-            );
-
-            match provider.fetch_price(asset_code).await {
-                Ok(price) => {
-                    info!(
-                        "Successfully fetched {} price {} from {}",
-                        asset_code,
-                        price.price,
-                        provider.name()
-                    );
-                    return Ok(price);
-                }
-                Err(e) => {
-                    warn!(
-                        "Failed to fetch {} from {}: {}. Trying next provider...",
-                        asset_code,
-                        provider.name(),
-                        e
-                    );
-                    last_error = Some(e);
-                }
+                price,
+                aggregated.accepted_sources,
+                self.providers.len(),
+                aggregated.spread
+            ),
+            None => warn!(
+                "No {} price: {}/{} sources accepted, {} required",
+                asset_code,
+                aggregated.accepted_sources,
+                self.providers.len(),
+                self.config.min_sources
+            ),
+        }
+        aggregated
+    }
+
+    /// Fetch the median price, failing when the source quorum is not met.
+    pub async fn fetch_price(&self, asset_code: &str) -> Result<ExternalPrice, ApiError> {
+        let aggregated = self.fetch_aggregated(asset_code).await;
+        self.published_price(&aggregated)
+    }
+
+    /// The price to publish from `aggregated`, if the source quorum was met.
+    pub fn published_price(&self, aggregated: &AggregatedPrice) -> Result<ExternalPrice, ApiError> {
+        let price = aggregated.price.ok_or_else(|| {
+            ApiError::Internal(anyhow::anyhow!(
+                "Price quorum not met for {}: {} of {} required sources agree",
+                aggregated.asset_code,
+                aggregated.accepted_sources,
+                self.config.min_sources
+            ))
+        })?;
+        Ok(ExternalPrice {
+            asset_code: aggregated.asset_code.clone(),
+            price,
+            source: "median".to_string(),
+            timestamp_seconds: aggregated.timestamp_seconds,
+        })
+    }
+
+    fn record_stats(&self, aggregated: &AggregatedPrice) {
+        let mut stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        for quote in &aggregated.quotes {
+            let entry = stats
+                .entry(quote.source.clone())
+                .or_insert_with(|| SourceStats {
+                    source: quote.source.clone(),
+                    ..SourceStats::default()
+                });
+            entry.requests += 1;
+            match quote.status {
+                QuoteStatus::Accepted => entry.accepted += 1,
+                QuoteStatus::Outlier => entry.outliers += 1,
+                QuoteStatus::Stale => entry.stale += 1,
+                QuoteStatus::Failed => entry.failures += 1,
+                QuoteStatus::TimedOut => entry.timeouts += 1,
+            }
+            if quote.deviation.is_some() {
+                entry.last_deviation = quote.deviation;
+            }
+            if quote.error.is_some() {
+                entry.last_error = quote.error.clone();
             }
         }
+    }
 
-        error!("All providers failed to fetch price for {}", asset_code);
-        Err(last_error
-            .unwrap_or_else(|| ApiError::Internal(anyhow::anyhow!("No price providers available"))))
+    /// Quality counters per provider since start-up
+    pub fn source_stats(&self) -> Vec<SourceStats> {
+        let stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stats: Vec<SourceStats> = stats.values().cloned().collect();
+        stats.sort_by(|a, b| a.source.cmp(&b.source));
+        stats
     }
 
     /// Add a custom provider (useful for testing or custom sources)
     pub fn add_provider(&mut self, provider: Box<dyn ExternalPriceProvider>) {
-        self.providers.push(provider);
+        self.providers.push(Arc::from(provider));
     }
 }
 
@@ -306,5 +630,166 @@ mod tests {
         assert_eq!(BinanceProvider::get_binance_symbol("ETH"), Some("ETHUSDT"));
         assert_eq!(BinanceProvider::get_binance_symbol("BTC"), Some("BTCUSDT"));
         assert_eq!(BinanceProvider::get_binance_symbol("UNKNOWN"), None);
+        assert_eq!(
+            BinanceProvider::get_binance_symbol(
+                "USDC:GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN"
+            ),
+            None
+        );
+    }
+
+    struct MockProvider {
+        name: &'static str,
+        price: Option<Decimal>,
+        delay_ms: u64,
+        age_secs: i64,
+    }
+
+    impl MockProvider {
+        fn quoting(name: &'static str, price: i64) -> Self {
+            Self {
+                name,
+                price: Some(Decimal::from(price)),
+                delay_ms: 0,
+                age_secs: 0,
+            }
+        }
+
+        fn failing(name: &'static str) -> Self {
+            Self {
+                name,
+                price: None,
+                delay_ms: 0,
+                age_secs: 0,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ExternalPriceProvider for MockProvider {
+        async fn fetch_price(&self, asset_code: &str) -> Result<ExternalPrice, ApiError> {
+            tokio::time::sleep(Duration::from_millis(self.delay_ms)).await;
+            let price = self
+                .price
+                .ok_or_else(|| ApiError::Internal(anyhow::anyhow!("{} is down", self.name)))?;
+            Ok(ExternalPrice {
+                asset_code: asset_code.to_string(),
+                price,
+                source: self.name.to_string(),
+                timestamp_seconds: chrono::Utc::now().timestamp() - self.age_secs,
+            })
+        }
+
+        fn name(&self) -> &'static str {
+            self.name
+        }
+    }
+
+    fn fetcher(providers: Vec<MockProvider>) -> RedundantPriceFetcher {
+        let mut fetcher = RedundantPriceFetcher::empty(PriceAggregationConfig {
+            source_timeout_ms: 200,
+            ..PriceAggregationConfig::default()
+        });
+        for provider in providers {
+            fetcher.add_provider(Box::new(provider));
+        }
+        fetcher
+    }
+
+    #[tokio::test]
+    async fn test_median_of_agreeing_sources() {
+        let odd = fetcher(vec![
+            MockProvider::quoting("a", 1000),
+            MockProvider::quoting("b", 1010),
+            MockProvider::quoting("c", 1005),
+        ]);
+        let price = odd.fetch_price("ETH").await.unwrap();
+        assert_eq!(price.price, Decimal::from(1005));
+        assert_eq!(price.source, "median");
+
+        let even = fetcher(vec![
+            MockProvider::quoting("a", 1000),
+            MockProvider::quoting("b", 1010),
+        ]);
+        let aggregated = even.fetch_aggregated("ETH").await;
+        assert_eq!(aggregated.price, Some(Decimal::from(1005)));
+        assert!(!aggregated.disagreement);
+    }
+
+    #[tokio::test]
+    async fn test_outlier_is_rejected_and_flagged() {
+        let fetcher = fetcher(vec![
+            MockProvider::quoting("a", 1000),
+            MockProvider::quoting("b", 1002),
+            MockProvider::quoting("bad", 1500),
+        ]);
+        let aggregated = fetcher.fetch_aggregated("ETH").await;
+        assert_eq!(aggregated.price, Some(Decimal::from(1001)));
+        assert_eq!(aggregated.accepted_sources, 2);
+        assert!(aggregated.disagreement);
+        let bad = aggregated
+            .quotes
+            .iter()
+            .find(|q| q.source == "bad")
+            .unwrap();
+        assert_eq!(bad.status, QuoteStatus::Outlier);
+
+        let stats = fetcher.source_stats();
+        let bad = stats.iter().find(|s| s.source == "bad").unwrap();
+        assert_eq!((bad.requests, bad.outliers, bad.accepted), (1, 1, 0));
+    }
+
+    #[tokio::test]
+    async fn test_quorum_is_enforced() {
+        let fetcher = fetcher(vec![
+            MockProvider::quoting("a", 1000),
+            MockProvider::failing("b"),
+        ]);
+        assert!(fetcher.fetch_price("ETH").await.is_err());
+
+        let stats = fetcher.source_stats();
+        assert_eq!(stats.iter().find(|s| s.source == "b").unwrap().failures, 1);
+        assert_eq!(
+            stats
+                .iter()
+                .find(|s| s.source == "a")
+                .unwrap()
+                .acceptance_rate(),
+            Decimal::ONE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_slow_and_stale_sources_are_excluded() {
+        let fetcher = fetcher(vec![
+            MockProvider::quoting("a", 1000),
+            MockProvider::quoting("b", 1001),
+            MockProvider {
+                name: "slow",
+                price: Some(Decimal::from(1000)),
+                delay_ms: 2_000,
+                age_secs: 0,
+            },
+            MockProvider {
+                name: "old",
+                price: Some(Decimal::from(900)),
+                delay_ms: 0,
+                age_secs: 3_600,
+            },
+        ]);
+        let aggregated = fetcher.fetch_aggregated("ETH").await;
+        let status = |name: &str| {
+            aggregated
+                .quotes
+                .iter()
+                .find(|q| q.source == name)
+                .unwrap()
+                .status
+        };
+        assert_eq!(status("slow"), QuoteStatus::TimedOut);
+        assert_eq!(status("old"), QuoteStatus::Stale);
+        assert_eq!(aggregated.accepted_sources, 2);
+        // A stale price far from the others is not a disagreement.
+        assert!(!aggregated.disagreement);
     }
 }
@@ -1,15 +1,18 @@
 use crate::api_error::ApiError;
+use crate::claim_adjudication::{self, Adjudication, ClaimPolicy};
+use crate::contract_reader::InheritanceContractReader;
+use crate::insurance_fund_contract::{InsuranceFundContract, OnChainClaimStatus};
 use crate::notifications::{
     audit_action, entity_type, notif_type, AuditLogService, NotificationService,
 };
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
 /// Insurance fund status
@@ -68,6 +71,7 @@ pub struct InsuranceFund {
     pub total_contributions: Decimal,
     pub total_payouts: Decimal,
     pub yield_earned: Decimal,
+    pub total_premiums: Decimal,
     pub metadata: serde_json::Value,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -128,6 +132,13 @@ pub struct InsuranceClaim {
     pub metadata: serde_json::Value,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub filing_tx_hash: Option<String>,
+    pub payout_tx_hash: Option<String>,
+    pub source_key: Option<String>,
+    pub first_reviewed_by: Option<Uuid>,
+    pub first_reviewed_at: Option<DateTime<Utc>>,
+    pub review_due_at: Option<DateTime<Utc>>,
+    pub sla_breached_at: Option<DateTime<Utc>>,
 }
 
 /// Request to create insurance claim
@@ -155,6 +166,8 @@ pub struct InsuranceFundDashboard {
     pub fund: InsuranceFundMetrics,
     pub recent_transactions: Vec<InsuranceFundTransaction>,
     pub pending_claims: Vec<InsuranceClaim>,
+    /// Pending claims past their review deadline
+    pub overdue_claims_count: usize,
     pub total_claims_count: usize,
     pub total_claims_amount: Decimal,
     pub trends: FundTrends,
@@ -169,14 +182,101 @@ pub struct FundTrends {
     pub payouts_last_7_days: Decimal,
 }
 
+/// Claim row to insert
+struct NewClaim<'a> {
+    fund_id: Uuid,
+    user_id: Uuid,
+    plan_id: Option<Uuid>,
+    loan_id: Option<Uuid>,
+    claim_type: &'a str,
+    claimed_amount: Decimal,
+    metadata: serde_json::Value,
+    source_key: Option<String>,
+}
+
+/// Claim the fund monitor files on its own
+struct AutoClaim {
+    claim_type: &'static str,
+    user_id: Uuid,
+    plan_id: Option<Uuid>,
+    amount: Decimal,
+    metadata: serde_json::Value,
+    source_key: String,
+}
+
 /// Insurance Fund Service
 pub struct InsuranceFundService {
     db: PgPool,
+    /// Contract holding the primary fund's reserves, when configured
+    contract: Option<Arc<dyn InsuranceFundContract>>,
+    /// Inheritance contract, read for loan write-offs at plan claim time
+    reader: Arc<dyn InheritanceContractReader>,
 }
 
 impl InsuranceFundService {
-    pub fn new(db: PgPool) -> Self {
-        Self { db }
+    pub fn new(
+        db: PgPool,
+        contract: Option<Arc<dyn InsuranceFundContract>>,
+        reader: Arc<dyn InheritanceContractReader>,
+    ) -> Self {
+        Self {
+            db,
+            contract,
+            reader,
+        }
+    }
+
+    /// The contract, if it holds `fund_id`'s reserves (only the primary fund
+    /// is on-chain)
+    async fn contract_for(
+        &self,
+        fund_id: Uuid,
+    ) -> Result<Option<&dyn InsuranceFundContract>, ApiError> {
+        let Some(contract) = &self.contract else {
+            return Ok(None);
+        };
+        Ok((self.get_primary_fund().await?.id == fund_id).then_some(contract.as_ref()))
+    }
+
+    /// Overwrite the primary fund's reserves with the contract's figures.
+    /// On a read failure the last synced figures stay in place.
+    async fn sync_on_chain_reserves(&self) -> Result<(), ApiError> {
+        let Some(contract) = &self.contract else {
+            return Ok(());
+        };
+        let metrics = match contract.metrics().await {
+            Ok(metrics) => metrics,
+            Err(e) => {
+                warn!("Could not read insurance fund contract metrics: {}", e);
+                return Ok(());
+            }
+        };
+        let fund = self.get_primary_fund().await?;
+        sqlx::query(
+            r#"
+            UPDATE insurance_fund
+            SET total_reserves = $1,
+                available_reserves = $2,
+                locked_reserves = $3,
+                total_premiums = $4,
+                total_contributions = $5,
+                total_payouts = $6
+            WHERE id = $7
+            "#,
+        )
+        .bind(metrics.total_reserves)
+        .bind(metrics.available_reserves)
+        .bind(metrics.locked_reserves)
+        .bind(metrics.total_premiums)
+        .bind(metrics.total_contributions)
+        .bind(metrics.total_payouts)
+        .bind(fund.id)
+        .execute(&self.db)
+        .await
+        .map_err(|e| {
+            ApiError::Internal(anyhow::anyhow!("DB error syncing fund reserves: {}", e))
+        })?;
+        Ok(())
     }
 
     /// Start background monitoring job
@@ -188,6 +288,18 @@ impl InsuranceFundService {
                 if let Err(e) = self.update_fund_metrics().await {
                     error!("Insurance Fund Service error updating metrics: {}", e);
                 }
+                if let Err(e) = self.generate_automatic_claims().await {
+                    error!(
+                        "Insurance Fund Service error filing automatic claims: {}",
+                        e
+                    );
+                }
+                if let Err(e) = self.flag_overdue_claims().await {
+                    error!("Insurance Fund Service error checking claim SLAs: {}", e);
+                }
+                if let Err(e) = self.retry_claim_filings(5).await {
+                    error!("Insurance Fund Service error filing claims: {}", e);
+                }
             }
         });
     }
@@ -310,6 +422,7 @@ impl InsuranceFundService {
 
     /// Update fund metrics based on current state
     pub async fn update_fund_metrics(&self) -> Result<(), ApiError> {
+        self.sync_on_chain_reserves().await?;
         let funds = self.get_all_funds().await?;
 
         for fund in funds {
@@ -527,26 +640,22 @@ impl InsuranceFundService {
             .await
             .map_err(|e| ApiError::Internal(anyhow::anyhow!("Tx start error: {}", e)))?;
 
-        let claim = sqlx::query_as::<_, InsuranceClaim>(
-            r#"
-            INSERT INTO insurance_claims (
-                fund_id, user_id, plan_id, loan_id, claim_type, claimed_amount, metadata
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7)
-            RETURNING *
-            "#,
-        )
-        .bind(fund_id)
-        .bind(user_id)
-        .bind(req.plan_id)
-        .bind(req.loan_id)
-        .bind(&req.claim_type)
-        .bind(req.claimed_amount)
-        .bind(req.metadata.clone().unwrap_or_default())
-        .fetch_one(&mut *tx)
-        .await
-        .map_err(|e| {
-            ApiError::Internal(anyhow::anyhow!("DB error creating insurance claim: {}", e))
-        })?;
+        let claim = self
+            .insert_claim(
+                &mut tx,
+                NewClaim {
+                    fund_id,
+                    user_id,
+                    plan_id: req.plan_id,
+                    loan_id: req.loan_id,
+                    claim_type: &req.claim_type,
+                    claimed_amount: req.claimed_amount,
+                    metadata: req.metadata.clone().unwrap_or_default(),
+                    source_key: None,
+                },
+            )
+            .await?
+            .ok_or_else(|| ApiError::Internal(anyhow::anyhow!("Insurance claim not inserted")))?;
 
         AuditLogService::log(
             &mut *tx,
@@ -564,7 +673,136 @@ impl InsuranceFundService {
         Ok(claim)
     }
 
-    /// Process insurance claim (approve/reject)
+    /// Insert a pending claim due for review within its policy's SLA.
+    /// Returns `None` if a claim with the same source key already exists.
+    async fn insert_claim(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        new: NewClaim<'_>,
+    ) -> Result<Option<InsuranceClaim>, ApiError> {
+        let policy = claim_adjudication::get_policy(&self.db, new.claim_type).await?;
+        sqlx::query_as::<_, InsuranceClaim>(
+            r#"
+            INSERT INTO insurance_claims (
+                fund_id, user_id, plan_id, loan_id, claim_type, claimed_amount, metadata,
+                source_key, review_due_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW() + make_interval(hours => $9))
+            ON CONFLICT (source_key) DO NOTHING
+            RETURNING *
+            "#,
+        )
+        .bind(new.fund_id)
+        .bind(new.user_id)
+        .bind(new.plan_id)
+        .bind(new.loan_id)
+        .bind(new.claim_type)
+        .bind(new.claimed_amount)
+        .bind(new.metadata)
+        .bind(new.source_key)
+        .bind(policy.review_sla_hours)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| {
+            ApiError::Internal(anyhow::anyhow!("DB error creating insurance claim: {}", e))
+        })
+    }
+
+    /// Get insurance claim
+    pub async fn get_claim(&self, claim_id: Uuid) -> Result<InsuranceClaim, ApiError> {
+        sqlx::query_as::<_, InsuranceClaim>("SELECT * FROM insurance_claims WHERE id = $1")
+            .bind(claim_id)
+            .fetch_optional(&self.db)
+            .await
+            .map_err(|e| ApiError::Internal(anyhow::anyhow!("DB error fetching claim: {}", e)))?
+            .ok_or_else(|| ApiError::NotFound(format!("Insurance claim {} not found", claim_id)))
+    }
+
+    /// How much of `claim` its policy and the fund's reserves allow paying
+    pub async fn adjudicate_claim(
+        &self,
+        claim: &InsuranceClaim,
+    ) -> Result<(ClaimPolicy, Adjudication), ApiError> {
+        let mut tx = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| ApiError::Internal(anyhow::anyhow!("Tx start error: {}", e)))?;
+        let adjudication = self.adjudicate_in(&mut tx, claim).await?;
+        tx.commit()
+            .await
+            .map_err(|e| ApiError::Internal(anyhow::anyhow!("Tx commit error: {}", e)))?;
+        Ok(adjudication)
+    }
+
+    /// [`Self::adjudicate_claim`] on `tx`, holding the fund row so concurrent
+    /// approvals cannot both count the same reserve headroom
+    async fn adjudicate_in(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        claim: &InsuranceClaim,
+    ) -> Result<(ClaimPolicy, Adjudication), ApiError> {
+        let policy = claim_adjudication::get_policy(&self.db, &claim.claim_type).await?;
+        let fund = sqlx::query_as::<_, InsuranceFund>(
+            "SELECT * FROM insurance_fund WHERE id = $1 FOR UPDATE",
+        )
+        .bind(claim.fund_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| {
+            ApiError::Internal(anyhow::anyhow!("DB error fetching insurance fund: {}", e))
+        })?
+        .ok_or_else(|| ApiError::NotFound(format!("Insurance fund {} not found", claim.fund_id)))?;
+
+        // Approvals not yet paid out still draw on the reserves
+        let committed: Decimal = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(SUM(approved_amount), 0)
+            FROM insurance_claims
+            WHERE fund_id = $1 AND id <> $2
+              AND status IN ('approved', 'awaiting_second_review', 'filing')
+            "#,
+        )
+        .bind(claim.fund_id)
+        .bind(claim.id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| {
+            ApiError::Internal(anyhow::anyhow!("DB error summing committed claims: {}", e))
+        })?;
+
+        let plan_covered: Decimal = match claim.plan_id {
+            Some(plan_id) => sqlx::query_scalar(
+                r#"
+                SELECT COALESCE(SUM(approved_amount), 0)
+                FROM insurance_claims
+                WHERE plan_id = $1 AND claim_type = $2 AND id <> $3
+                  AND status IN ('approved', 'awaiting_second_review', 'filing', 'paid')
+                "#,
+            )
+            .bind(plan_id)
+            .bind(&claim.claim_type)
+            .bind(claim.id)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(|e| {
+                ApiError::Internal(anyhow::anyhow!("DB error summing plan coverage: {}", e))
+            })?,
+            None => Decimal::ZERO,
+        };
+
+        let headroom = claim_adjudication::reserve_headroom(
+            fund.total_reserves,
+            committed,
+            fund.total_covered_liabilities,
+            fund.critical_coverage_ratio,
+        );
+        let adjudication =
+            claim_adjudication::adjudicate(&policy, claim.claimed_amount, plan_covered, headroom);
+        Ok((policy, adjudication))
+    }
+
+    /// Process insurance claim (approve/reject). Approvals above the policy's
+    /// dual-review threshold take effect once a second admin confirms them.
     pub async fn process_claim(
         &self,
         claim_id: Uuid,
@@ -586,19 +824,67 @@ impl InsuranceFundService {
         .map_err(|e| ApiError::Internal(anyhow::anyhow!("DB error fetching claim: {}", e)))?
         .ok_or_else(|| ApiError::NotFound(format!("Insurance claim {} not found", claim_id)))?;
 
-        if claim.status != "pending" {
-            return Err(ApiError::BadRequest(
-                "Claim has already been processed".to_string(),
-            ));
-        }
+        let awaiting_second_review = match claim.status.as_str() {
+            "pending" => false,
+            "awaiting_second_review" => true,
+            _ => {
+                return Err(ApiError::BadRequest(
+                    "Claim has already been processed".to_string(),
+                ))
+            }
+        };
 
         let (new_status, payout_amount) = if req.approved {
-            let amount = req.approved_amount.unwrap_or(claim.claimed_amount);
+            let (policy, adjudication) = self.adjudicate_in(&mut tx, &claim).await?;
+            let amount = if awaiting_second_review {
+                if claim.first_reviewed_by == Some(admin_id) {
+                    return Err(ApiError::Forbidden(
+                        "A different admin must give the second approval".to_string(),
+                    ));
+                }
+                let first_amount = claim.approved_amount.unwrap_or(claim.claimed_amount);
+                if req.approved_amount.is_some_and(|a| a != first_amount) {
+                    return Err(ApiError::BadRequest(format!(
+                        "Second approval must confirm the first reviewer's amount of {}",
+                        first_amount
+                    )));
+                }
+                first_amount
+            } else {
+                req.approved_amount.unwrap_or(adjudication.payable_amount)
+            };
+            if amount <= Decimal::ZERO {
+                return Err(ApiError::BadRequest(
+                    "Nothing is payable on this claim".to_string(),
+                ));
+            }
+            if amount > adjudication.payable_amount {
+                return Err(ApiError::BadRequest(format!(
+                    "Approved amount {} exceeds the payable amount of {}",
+                    amount, adjudication.payable_amount
+                )));
+            }
+            if !awaiting_second_review && policy.requires_second_review(amount) {
+                return self
+                    .record_first_review(tx, claim_id, admin_id, amount)
+                    .await;
+            }
             ("approved".to_string(), Some(amount))
         } else {
             ("rejected".to_string(), None)
         };
 
+        // Approvals of on-chain claims are filed with the contract, which
+        // pays out once the admin or enough reviewers have signed off. They
+        // are committed as 'filing' first so the submission holds no locks.
+        let filing = payout_amount.is_some() && self.contract_for(claim.fund_id).await?.is_some();
+        let new_status = if filing {
+            Self::claimant_wallet(&mut tx, claim.user_id).await?;
+            "filing".to_string()
+        } else {
+            new_status
+        };
+
         claim = sqlx::query_as::<_, InsuranceClaim>(
             r#"
             UPDATE insurance_claims
@@ -615,6 +901,7 @@ impl InsuranceFundService {
         )
         .bind(&new_status)
         .bind(payout_amount)
+        .bind(payout_amount)
         .bind(admin_id)
         .bind(&req.rejection_reason)
         .bind(claim_id)
@@ -631,6 +918,151 @@ impl InsuranceFundService {
         )
         .await?;
 
+        tx.commit()
+            .await
+            .map_err(|e| ApiError::Internal(anyhow::anyhow!("Tx commit error: {}", e)))?;
+
+        if filing {
+            claim = self.file_on_chain(&claim).await?;
+        }
+        Ok(claim)
+    }
+
+    /// The wallet an on-chain payout to `user_id` goes to
+    async fn claimant_wallet(conn: &mut PgConnection, user_id: Uuid) -> Result<String, ApiError> {
+        let wallet: Option<String> =
+            sqlx::query_scalar("SELECT wallet_address FROM users WHERE id = $1")
+                .bind(user_id)
+                .fetch_optional(conn)
+                .await
+                .map_err(|e| {
+                    ApiError::Internal(anyhow::anyhow!("DB error fetching wallet: {}", e))
+                })?
+                .flatten();
+        wallet.ok_or_else(|| {
+            ApiError::BadRequest(
+                "Claimant has no wallet address to receive an on-chain payout".to_string(),
+            )
+        })
+    }
+
+    /// File a claim approved as 'filing' with the contract and mark it
+    /// approved. A filing that already landed is not sent again, so a claim
+    /// left in 'filing' by a failed submission can simply be retried.
+    async fn file_on_chain(&self, claim: &InsuranceClaim) -> Result<InsuranceClaim, ApiError> {
+        let contract = self.contract_for(claim.fund_id).await?.ok_or_else(|| {
+            ApiError::Internal(anyhow::anyhow!(
+                "Insurance claim {} is filing but its fund is not on-chain",
+                claim.id
+            ))
+        })?;
+        let amount = claim.payout_amount.ok_or_else(|| {
+            ApiError::Internal(anyhow::anyhow!(
+                "Insurance claim {} is filing without a payout amount",
+                claim.id
+            ))
+        })?;
+
+        let filing_tx_hash = match contract.claim(claim.id).await? {
+            Some(_) => None,
+            None => {
+                let mut conn = self.db.acquire().await.map_err(|e| {
+                    ApiError::Internal(anyhow::anyhow!("DB connection error: {}", e))
+                })?;
+                let wallet = Self::claimant_wallet(&mut conn, claim.user_id).await?;
+                Some(contract.file_claim(claim.id, &wallet, amount).await?)
+            }
+        };
+
+        let filed = sqlx::query_as::<_, InsuranceClaim>(
+            r#"
+            UPDATE insurance_claims
+            SET status = 'approved',
+                filing_tx_hash = COALESCE($1, filing_tx_hash),
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = $2 AND status = 'filing'
+            RETURNING *
+            "#,
+        )
+        .bind(&filing_tx_hash)
+        .bind(claim.id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| ApiError::Internal(anyhow::anyhow!("DB error updating claim: {}", e)))?;
+        match filed {
+            Some(claim) => Ok(claim),
+            // Another pass recorded the filing first
+            None => self.get_claim(claim.id).await,
+        }
+    }
+
+    /// Retry filings left in 'filing' by a failed or interrupted
+    /// submission. Ones updated within `settle_minutes` are skipped as their
+    /// approval may still be submitting them.
+    pub async fn retry_claim_filings(&self, settle_minutes: i32) -> Result<usize, ApiError> {
+        let stuck = sqlx::query_as::<_, InsuranceClaim>(
+            r#"
+            SELECT * FROM insurance_claims
+            WHERE status = 'filing' AND updated_at < NOW() - (INTERVAL '1 minute' * $1)
+            ORDER BY updated_at
+            "#,
+        )
+        .bind(settle_minutes)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            ApiError::Internal(anyhow::anyhow!("DB error fetching claim filings: {}", e))
+        })?;
+
+        let mut filed = 0;
+        for claim in &stuck {
+            match self.file_on_chain(claim).await {
+                Ok(_) => filed += 1,
+                Err(e) => warn!(
+                    "Could not file insurance claim {} on-chain: {}",
+                    claim.id, e
+                ),
+            }
+        }
+        Ok(filed)
+    }
+
+    /// Hold an approval for a second admin, reserving `amount` meanwhile
+    async fn record_first_review(
+        &self,
+        mut tx: sqlx::Transaction<'_, sqlx::Postgres>,
+        claim_id: Uuid,
+        admin_id: Uuid,
+        amount: Decimal,
+    ) -> Result<InsuranceClaim, ApiError> {
+        let claim = sqlx::query_as::<_, InsuranceClaim>(
+            r#"
+            UPDATE insurance_claims
+            SET status = 'awaiting_second_review',
+                approved_amount = $1,
+                first_reviewed_by = $2,
+                first_reviewed_at = CURRENT_TIMESTAMP,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = $3
+            RETURNING *
+            "#,
+        )
+        .bind(amount)
+        .bind(admin_id)
+        .bind(claim_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Internal(anyhow::anyhow!("DB error updating claim: {}", e)))?;
+
+        AuditLogService::log(
+            &mut *tx,
+            Some(admin_id),
+            audit_action::INSURANCE_CLAIM_FIRST_REVIEW,
+            Some(claim.id),
+            Some(entity_type::INSURANCE_CLAIM),
+        )
+        .await?;
+
         tx.commit()
             .await
             .map_err(|e| ApiError::Internal(anyhow::anyhow!("Tx commit error: {}", e)))?;
@@ -638,6 +1070,240 @@ impl InsuranceFundService {
         Ok(claim)
     }
 
+    /// File claims for liquidation shortfalls and loan write-offs at
+    /// inheritance, for claim types whose policy enables it. Each event is
+    /// claimed once, keyed by its source.
+    pub async fn generate_automatic_claims(&self) -> Result<usize, ApiError> {
+        let auto_types: Vec<String> = claim_adjudication::list_policies(&self.db)
+            .await?
+            .into_iter()
+            .filter(|p| p.auto_create)
+            .map(|p| p.claim_type)
+            .collect();
+        if auto_types.is_empty() {
+            return Ok(0);
+        }
+        let fund = self.get_primary_fund().await?;
+
+        let mut candidates = Vec::new();
+        if auto_types
+            .iter()
+            .any(|t| t == claim_adjudication::LIQUIDATION_SHORTFALL)
+        {
+            candidates.extend(self.liquidation_shortfalls().await?);
+        }
+        if auto_types
+            .iter()
+            .any(|t| t == claim_adjudication::INHERITANCE_RECALL)
+        {
+            candidates.extend(self.inheritance_write_offs().await?);
+        }
+
+        let mut created = 0;
+        for candidate in candidates {
+            let mut tx = self
+                .db
+                .begin()
+                .await
+                .map_err(|e| ApiError::Internal(anyhow::anyhow!("Tx start error: {}", e)))?;
+            let inserted = self
+                .insert_claim(
+                    &mut tx,
+                    NewClaim {
+                        fund_id: fund.id,
+                        user_id: candidate.user_id,
+                        plan_id: candidate.plan_id,
+                        loan_id: None,
+                        claim_type: candidate.claim_type,
+                        claimed_amount: candidate.amount,
+                        metadata: candidate.metadata,
+                        source_key: Some(candidate.source_key),
+                    },
+                )
+                .await?;
+            let Some(claim) = inserted else {
+                continue;
+            };
+            AuditLogService::log(
+                &mut *tx,
+                None,
+                audit_action::INSURANCE_CLAIM_AUTO_CREATED,
+                Some(claim.id),
+                Some(entity_type::INSURANCE_CLAIM),
+            )
+            .await?;
+            tx.commit()
+                .await
+                .map_err(|e| ApiError::Internal(anyhow::anyhow!("Tx commit error: {}", e)))?;
+            info!(
+                "Filed {} insurance claim {} for {}",
+                claim.claim_type, claim.id, claim.claimed_amount
+            );
+            created += 1;
+        }
+        Ok(created)
+    }
+
+    /// Liquidations whose seized collateral fell short of the debt covered
+    /// (valued 1:1, as the liquidation bot does)
+    async fn liquidation_shortfalls(&self) -> Result<Vec<AutoClaim>, ApiError> {
+        let rows: Vec<(Uuid, Uuid, Option<Uuid>, Decimal, serde_json::Value)> = sqlx::query_as(
+            r#"
+            SELECT e.id, e.user_id, e.plan_id,
+                   (e.metadata->>'debt_covered')::numeric
+                       - (e.metadata->>'collateral_seized')::numeric AS shortfall,
+                   e.metadata
+            FROM lending_events e
+            WHERE e.event_type = 'liquidation'
+              AND e.metadata ? 'debt_covered'
+              AND e.metadata ? 'collateral_seized'
+              AND (e.metadata->>'debt_covered')::numeric
+                    > (e.metadata->>'collateral_seized')::numeric
+              -- Auction bids are priced by the auction; only the unpaid
+              -- remainder booked at settlement is a shortfall
+              AND (NOT e.metadata ? 'auction_id'
+                   OR (e.metadata->>'collateral_seized')::numeric = 0)
+              AND NOT EXISTS (
+                  SELECT 1 FROM insurance_claims c
+                  WHERE c.source_key = 'liquidation:' || e.id::text
+              )
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            ApiError::Internal(anyhow::anyhow!(
+                "DB error finding liquidation shortfalls: {}",
+                e
+            ))
+        })?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(event_id, user_id, plan_id, shortfall, metadata)| AutoClaim {
+                    claim_type: claim_adjudication::LIQUIDATION_SHORTFALL,
+                    user_id,
+                    plan_id,
+                    amount: shortfall,
+                    metadata: serde_json::json!({
+                        "lending_event_id": event_id,
+                        "liquidation": metadata,
+                    }),
+                    source_key: format!("liquidation:{}", event_id),
+                },
+            )
+            .collect())
+    }
+
+    /// Recently claimed plans whose loaned funds the inheritance contract
+    /// could not recall and wrote off
+    async fn inheritance_write_offs(&self) -> Result<Vec<AutoClaim>, ApiError> {
+        let plans: Vec<(Uuid, Uuid, i64)> = sqlx::query_as(
+            r#"
+            SELECT p.id, p.user_id, p.contract_plan_id
+            FROM plans p
+            WHERE p.contract_plan_id IS NOT NULL
+              AND p.status IN ('due-for-claim', 'claimed')
+              AND p.updated_at >= NOW() - INTERVAL '30 days'
+              AND NOT EXISTS (
+                  SELECT 1 FROM insurance_claims c
+                  WHERE c.source_key = 'recall:' || p.id::text
+              )
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            ApiError::Internal(anyhow::anyhow!("DB error finding claimed plans: {}", e))
+        })?;
+
+        let mut claims = Vec::new();
+        for (plan_id, user_id, contract_plan_id) in plans {
+            let trigger = match self
+                .reader
+                .get_inheritance_trigger(contract_plan_id as u64)
+                .await
+            {
+                Ok(trigger) => trigger,
+                Err(e) => {
+                    warn!(
+                        "Could not read inheritance triggers, skipping recall claims: {}",
+                        e
+                    );
+                    break;
+                }
+            };
+            let Some(trigger) = trigger.filter(|t| t.liquidation_triggered && t.settled_amount > 0)
+            else {
+                continue;
+            };
+            claims.push(AutoClaim {
+                claim_type: claim_adjudication::INHERITANCE_RECALL,
+                user_id,
+                plan_id: Some(plan_id),
+                amount: Decimal::from_i128_with_scale(
+                    trigger.settled_amount as i128,
+                    claim_adjudication::CONTRACT_AMOUNT_DECIMALS,
+                ),
+                metadata: serde_json::json!({
+                    "contract_plan_id": contract_plan_id,
+                    "inheritance_trigger": trigger,
+                }),
+                source_key: format!("recall:{}", plan_id),
+            });
+        }
+        Ok(claims)
+    }
+
+    /// Mark pending claims past their review deadline, once per claim
+    pub async fn flag_overdue_claims(&self) -> Result<usize, ApiError> {
+        let mut tx = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| ApiError::Internal(anyhow::anyhow!("Tx start error: {}", e)))?;
+
+        let overdue = sqlx::query_as::<_, InsuranceClaim>(
+            r#"
+            UPDATE insurance_claims
+            SET sla_breached_at = NOW()
+            WHERE status IN ('pending', 'awaiting_second_review')
+              AND review_due_at < NOW()
+              AND sla_breached_at IS NULL
+            RETURNING *
+            "#,
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| {
+            ApiError::Internal(anyhow::anyhow!("DB error flagging overdue claims: {}", e))
+        })?;
+
+        // Notifications belong to users, so admins see breaches through the
+        // dashboard's overdue count and the audit log instead
+        for claim in &overdue {
+            warn!(
+                "Insurance claim {} ({}, {}) missed its review deadline",
+                claim.id, claim.claim_type, claim.claimed_amount
+            );
+            AuditLogService::log(
+                &mut *tx,
+                None,
+                audit_action::INSURANCE_CLAIM_SLA_BREACHED,
+                Some(claim.id),
+                Some(entity_type::INSURANCE_CLAIM),
+            )
+            .await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| ApiError::Internal(anyhow::anyhow!("Tx commit error: {}", e)))?;
+
+        Ok(overdue.len())
+    }
+
     /// Pay out approved claim
     pub async fn payout_claim(&self, claim_id: Uuid) -> Result<(), ApiError> {
         let mut tx = self
@@ -669,25 +1335,68 @@ impl InsuranceFundService {
 
         let payout_amount = claim.payout_amount.unwrap();
 
+        // On-chain claims move funds only once the contract has approved them
+        let payout_tx_hash = match self.contract_for(claim.fund_id).await? {
+            Some(contract) => match contract.claim(claim_id).await? {
+                None => {
+                    return Err(ApiError::BadRequest(
+                        "Claim has not been recorded on-chain yet".to_string(),
+                    ))
+                }
+                Some(on_chain) => match on_chain.status {
+                    OnChainClaimStatus::Approved => Some(contract.payout_claim(claim_id).await?),
+                    // Payouts are permissionless, so someone may have beaten us to it
+                    OnChainClaimStatus::Paid => claim.payout_tx_hash.clone(),
+                    OnChainClaimStatus::Pending => {
+                        return Err(ApiError::BadRequest(format!(
+                            "Claim awaits reviewer approval on-chain ({} sign-offs so far)",
+                            on_chain.approvals
+                        )))
+                    }
+                    OnChainClaimStatus::Rejected => {
+                        return Err(ApiError::BadRequest(
+                            "Claim was rejected on-chain".to_string(),
+                        ))
+                    }
+                },
+            },
+            None => None,
+        };
+        let fund = self.get_fund_by_id(claim.fund_id).await?;
+
         // Record payout transaction
-        self.record_transaction(
-            claim.fund_id,
-            "payout",
-            payout_amount,
-            "USDC",
-            Some(claim.user_id),
-            claim.plan_id,
-            claim.loan_id,
-            Some(format!("Insurance claim payout for claim {}", claim_id)),
-            Some(serde_json::json!({"claim_id": claim_id.to_string()})),
-        )
-        .await?;
+        let transaction = self
+            .record_transaction(
+                claim.fund_id,
+                "payout",
+                payout_amount,
+                &fund.asset_code,
+                Some(claim.user_id),
+                claim.plan_id,
+                claim.loan_id,
+                Some(format!("Insurance claim payout for claim {}", claim_id)),
+                Some(serde_json::json!({"claim_id": claim_id.to_string()})),
+            )
+            .await?;
+        if payout_tx_hash.is_some() {
+            sqlx::query(
+                "UPDATE insurance_fund_transactions SET transaction_hash = $1 WHERE id = $2",
+            )
+            .bind(&payout_tx_hash)
+            .bind(transaction.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                ApiError::Internal(anyhow::anyhow!("DB error recording payout hash: {}", e))
+            })?;
+        }
 
         // Update claim status
         sqlx::query(
-            "UPDATE insurance_claims SET status = 'paid', paid_at = CURRENT_TIMESTAMP WHERE id = $1",
+            "UPDATE insurance_claims SET status = 'paid', paid_at = CURRENT_TIMESTAMP, payout_tx_hash = $2 WHERE id = $1",
         )
         .bind(claim_id)
+        .bind(&payout_tx_hash)
         .execute(&mut *tx)
         .await
         .map_err(|e| ApiError::Internal(anyhow::anyhow!("DB error updating claim status: {}", e)))?;
@@ -723,7 +1432,7 @@ impl InsuranceFundService {
 
         // Get pending claims
         let pending_claims = sqlx::query_as::<_, InsuranceClaim>(
-            "SELECT * FROM insurance_claims WHERE fund_id = $1 AND status = 'pending' ORDER BY created_at DESC LIMIT 10",
+            "SELECT * FROM insurance_claims WHERE fund_id = $1 AND status IN ('pending', 'awaiting_second_review') ORDER BY created_at DESC LIMIT 10",
         )
         .bind(fund_id)
         .fetch_all(&self.db)
@@ -745,6 +1454,16 @@ impl InsuranceFundService {
             ApiError::Internal(anyhow::anyhow!("DB error fetching claims stats: {}", e))
         })?;
 
+        let overdue_claims_count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM insurance_claims WHERE fund_id = $1 AND sla_breached_at IS NOT NULL AND status IN ('pending', 'awaiting_second_review')",
+        )
+        .bind(fund_id)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| {
+            ApiError::Internal(anyhow::anyhow!("DB error counting overdue claims: {}", e))
+        })?;
+
         let total_claims_count = claims_stats.0;
         let total_claims_amount = claims_stats.1;
 
@@ -783,6 +1502,7 @@ impl InsuranceFundService {
             },
             recent_transactions,
             pending_claims,
+            overdue_claims_count: overdue_claims_count as usize,
             total_claims_count: total_claims_count as usize,
             total_claims_amount,
             trends,
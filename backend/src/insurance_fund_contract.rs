@@ -0,0 +1,358 @@
+//! # Insurance Fund Contract
+//!
+//! Access to the on-chain `insurance-fund` contract backing the primary
+//! insurance fund. Reserves, premiums and payouts are read from the contract
+//! rather than tallied in Postgres, and approved claims are filed and paid
+//! there. Claims are keyed on-chain by the SHA-256 of their database id.
+
+use crate::api_error::ApiError;
+use crate::contract_reader::{as_i128, as_map, as_u32, field, xdr_error};
+use crate::tx_submitter::{
+    account_address, i128_val, ContractCall, ContractInvoker, SorobanInvoker, SubmitError,
+    TransactionSigner, TESTNET_PASSPHRASE,
+};
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use soroban_sdk::xdr::{ScBytes, ScVal};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Decimals of the reserve token when `INSURANCE_FUND_TOKEN_DECIMALS` is unset
+/// (Stellar asset contracts use 7)
+const DEFAULT_TOKEN_DECIMALS: u32 = 7;
+
+/// Inclusion fee per call, in stroops
+const DEFAULT_TX_FEE: u32 = 1_000;
+
+// ─── Types ────────────────────────────────────────────────────────────────────
+
+/// The contract's `FundMetrics`, in token units
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OnChainFundMetrics {
+    pub total_reserves: Decimal,
+    pub locked_reserves: Decimal,
+    pub available_reserves: Decimal,
+    pub total_premiums: Decimal,
+    pub total_contributions: Decimal,
+    pub total_payouts: Decimal,
+    pub pending_claims: u32,
+    pub paid_claims: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnChainClaimStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Paid,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OnChainClaim {
+    pub status: OnChainClaimStatus,
+    pub amount: Decimal,
+    /// Reviewer (or admin) sign-offs recorded so far
+    pub approvals: usize,
+}
+
+#[async_trait]
+pub trait InsuranceFundContract: Send + Sync {
+    async fn metrics(&self) -> Result<OnChainFundMetrics, ApiError>;
+
+    /// The on-chain claim for `claim_id`, or `None` if it was never filed
+    /// (or its filing has not landed yet)
+    async fn claim(&self, claim_id: Uuid) -> Result<Option<OnChainClaim>, ApiError>;
+
+    /// File `claim_id` for `beneficiary`, counting the operator's sign-off.
+    /// Returns the transaction hash.
+    async fn file_claim(
+        &self,
+        claim_id: Uuid,
+        beneficiary: &str,
+        amount: Decimal,
+    ) -> Result<String, ApiError>;
+
+    /// Pay an approved claim. Returns the transaction hash.
+    async fn payout_claim(&self, claim_id: Uuid) -> Result<String, ApiError>;
+}
+
+/// Contract for `INSURANCE_FUND_CONTRACT_ID`, acting as the reviewer account
+/// `INSURANCE_FUND_OPERATOR_ACCOUNT`, or `None` when either (or
+/// `SOROBAN_RPC_URL`) is unset and the fund is tracked off-chain only.
+pub fn insurance_fund_contract_from_env() -> Result<Option<Arc<dyn InsuranceFundContract>>, ApiError>
+{
+    let (Ok(rpc_url), Ok(contract_id), Ok(operator)) = (
+        std::env::var("SOROBAN_RPC_URL"),
+        std::env::var("INSURANCE_FUND_CONTRACT_ID"),
+        std::env::var("INSURANCE_FUND_OPERATOR_ACCOUNT"),
+    ) else {
+        return Ok(None);
+    };
+    let passphrase = std::env::var("STELLAR_NETWORK_PASSPHRASE")
+        .unwrap_or_else(|_| TESTNET_PASSPHRASE.to_string());
+    let decimals = std::env::var("INSURANCE_FUND_TOKEN_DECIMALS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_TOKEN_DECIMALS);
+    Ok(Some(Arc::new(SorobanInsuranceFund {
+        invoker: SorobanInvoker::new(&rpc_url, &contract_id, &passphrase)?,
+        signer: crate::tx_submitter::signer_from_env()?,
+        operator,
+        decimals,
+        fee: std::env::var("INSURANCE_FUND_TX_FEE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_TX_FEE),
+    })))
+}
+
+// ─── Encoding ─────────────────────────────────────────────────────────────────
+
+/// On-chain key of a claim: SHA-256 of its database id
+pub fn claim_reference(claim_id: Uuid) -> [u8; 32] {
+    Sha256::digest(claim_id.as_bytes()).into()
+}
+
+fn claim_reference_val(claim_id: Uuid) -> Result<ScVal, ApiError> {
+    Ok(ScVal::Bytes(ScBytes(
+        claim_reference(claim_id)
+            .to_vec()
+            .try_into()
+            .map_err(xdr_error)?,
+    )))
+}
+
+/// `amount` in the token's smallest unit, truncated
+pub fn to_token_units(amount: Decimal, decimals: u32) -> Option<i128> {
+    if amount <= Decimal::ZERO {
+        return None;
+    }
+    let truncated = amount.trunc_with_scale(decimals);
+    truncated
+        .mantissa()
+        .checked_mul(10i128.checked_pow(decimals - truncated.scale())?)
+}
+
+fn from_token_units(val: &ScVal, decimals: u32) -> Result<Decimal, ApiError> {
+    Decimal::try_from_i128_with_scale(as_i128(val)?, decimals)
+        .map(|d| d.normalize())
+        .map_err(xdr_error)
+}
+
+/// Decode a `FundMetrics` contract value
+pub fn parse_metrics(val: &ScVal, decimals: u32) -> Result<OnChainFundMetrics, ApiError> {
+    let map = as_map(val)?;
+    let amount = |name: &str| from_token_units(field(map, name)?, decimals);
+    Ok(OnChainFundMetrics {
+        total_reserves: amount("total_reserves")?,
+        locked_reserves: amount("locked_reserves")?,
+        available_reserves: amount("available_reserves")?,
+        total_premiums: amount("total_premiums")?,
+        total_contributions: amount("total_contributions")?,
+        total_payouts: amount("total_payouts")?,
+        pending_claims: as_u32(field(map, "pending_claims")?)?,
+        paid_claims: as_u32(field(map, "paid_claims")?)?,
+    })
+}
+
+/// Decode an `Option<Claim>` contract value
+pub fn parse_claim(val: &ScVal, decimals: u32) -> Result<Option<OnChainClaim>, ApiError> {
+    if matches!(val, ScVal::Void) {
+        return Ok(None);
+    }
+    let map = as_map(val)?;
+    // Unit enum variants encode as a one-element vec holding the name
+    let status = match field(map, "status")? {
+        ScVal::Vec(Some(items)) => match items.0.first() {
+            Some(ScVal::Symbol(s)) => match s.0.as_slice() {
+                b"Pending" => OnChainClaimStatus::Pending,
+                b"Approved" => OnChainClaimStatus::Approved,
+                b"Rejected" => OnChainClaimStatus::Rejected,
+                b"Paid" => OnChainClaimStatus::Paid,
+                other => {
+                    return Err(xdr_error(format!(
+                        "unknown claim status {}",
+                        String::from_utf8_lossy(other)
+                    )))
+                }
+            },
+            other => return Err(xdr_error(format!("expected status symbol, got {other:?}"))),
+        },
+        other => return Err(xdr_error(format!("expected status, got {other:?}"))),
+    };
+    let approvals = match field(map, "approvals")? {
+        ScVal::Vec(Some(items)) => items.0.len(),
+        other => return Err(xdr_error(format!("expected vec, got {other:?}"))),
+    };
+    Ok(Some(OnChainClaim {
+        status,
+        amount: from_token_units(field(map, "amount")?, decimals)?,
+        approvals,
+    }))
+}
+
+// ─── Soroban Implementation ───────────────────────────────────────────────────
+
+pub struct SorobanInsuranceFund {
+    invoker: SorobanInvoker,
+    signer: Arc<dyn TransactionSigner>,
+    /// `G…` account registered as a reviewer on the contract
+    operator: String,
+    decimals: u32,
+    fee: u32,
+}
+
+fn chain_error(action: &str, e: SubmitError) -> ApiError {
+    ApiError::Internal(anyhow::anyhow!("Insurance fund {action} failed: {e}"))
+}
+
+impl SorobanInsuranceFund {
+    fn call(&self, function: &str, args: Vec<ScVal>) -> ContractCall {
+        ContractCall {
+            source_account: self.operator.clone(),
+            function: function.to_string(),
+            args,
+        }
+    }
+
+    async fn read(&self, function: &str, args: Vec<ScVal>) -> Result<ScVal, ApiError> {
+        self.invoker
+            .read(&self.call(function, args))
+            .await
+            .map_err(|e| chain_error(function, e))
+    }
+
+    async fn submit(&self, function: &str, args: Vec<ScVal>) -> Result<String, ApiError> {
+        self.invoker
+            .submit(&self.call(function, args), self.fee, self.signer.as_ref())
+            .await
+            .map_err(|e| chain_error(function, e))
+    }
+}
+
+#[async_trait]
+impl InsuranceFundContract for SorobanInsuranceFund {
+    async fn metrics(&self) -> Result<OnChainFundMetrics, ApiError> {
+        parse_metrics(&self.read("get_metrics", Vec::new()).await?, self.decimals)
+    }
+
+    async fn claim(&self, claim_id: Uuid) -> Result<Option<OnChainClaim>, ApiError> {
+        let val = self
+            .read("get_claim", vec![claim_reference_val(claim_id)?])
+            .await?;
+        parse_claim(&val, self.decimals)
+    }
+
+    async fn file_claim(
+        &self,
+        claim_id: Uuid,
+        beneficiary: &str,
+        amount: Decimal,
+    ) -> Result<String, ApiError> {
+        let units = to_token_units(amount, self.decimals).ok_or_else(|| {
+            ApiError::BadRequest(format!("Claim amount {} cannot be paid on-chain", amount))
+        })?;
+        self.submit(
+            "file_claim",
+            vec![
+                account_address(&self.operator)?,
+                claim_reference_val(claim_id)?,
+                account_address(beneficiary)?,
+                i128_val(units),
+            ],
+        )
+        .await
+    }
+
+    async fn payout_claim(&self, claim_id: Uuid) -> Result<String, ApiError> {
+        self.submit("payout_claim", vec![claim_reference_val(claim_id)?])
+            .await
+    }
+}
+
+// ─── Unit Tests ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use soroban_sdk::xdr::{ScMap, ScMapEntry, ScSymbol, ScVec};
+
+    fn sym(name: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(name.try_into().unwrap()))
+    }
+
+    fn map(entries: Vec<(&str, ScVal)>) -> ScVal {
+        let entries: Vec<ScMapEntry> = entries
+            .into_iter()
+            .map(|(key, val)| ScMapEntry { key: sym(key), val })
+            .collect();
+        ScVal::Map(Some(ScMap(entries.try_into().unwrap())))
+    }
+
+    #[test]
+    fn converts_amounts_to_token_units() {
+        assert_eq!(to_token_units(dec!(12.5), 7), Some(125_000_000));
+        assert_eq!(to_token_units(dec!(0.123456789), 7), Some(1_234_567));
+        assert_eq!(to_token_units(dec!(0), 7), None);
+    }
+
+    #[test]
+    fn parses_fund_metrics() {
+        let val = map(vec![
+            ("available_reserves", i128_val(6_000_000_000)),
+            ("locked_reserves", i128_val(4_000_000_000)),
+            ("paid_claims", ScVal::U32(3)),
+            ("pending_claims", ScVal::U32(1)),
+            ("total_contributions", i128_val(7_500_000_000)),
+            ("total_payouts", i128_val(500_000_000)),
+            ("total_premiums", i128_val(3_000_000_000)),
+            ("total_reserves", i128_val(10_000_000_000)),
+        ]);
+        let metrics = parse_metrics(&val, 7).unwrap();
+        assert_eq!(metrics.total_reserves, dec!(1000));
+        assert_eq!(metrics.available_reserves, dec!(600));
+        assert_eq!(metrics.total_payouts, dec!(50));
+        assert_eq!(metrics.pending_claims, 1);
+    }
+
+    #[test]
+    fn parses_claims() {
+        assert_eq!(parse_claim(&ScVal::Void, 7).unwrap(), None);
+
+        let approvals = ScVal::Vec(Some(ScVec(
+            vec![
+                account_address("GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7")
+                    .unwrap(),
+            ]
+            .try_into()
+            .unwrap(),
+        )));
+        let val = map(vec![
+            ("amount", i128_val(2_500_000)),
+            ("approvals", approvals),
+            (
+                "status",
+                ScVal::Vec(Some(ScVec(vec![sym("Pending")].try_into().unwrap()))),
+            ),
+        ]);
+        assert_eq!(
+            parse_claim(&val, 7).unwrap(),
+            Some(OnChainClaim {
+                status: OnChainClaimStatus::Pending,
+                amount: dec!(0.25),
+                approvals: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn claim_reference_is_stable() {
+        let id = Uuid::nil();
+        assert_eq!(claim_reference(id), claim_reference(id));
+        assert_ne!(claim_reference(id), claim_reference(Uuid::new_v4()));
+    }
+}
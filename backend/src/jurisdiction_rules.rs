@@ -0,0 +1,806 @@
+//! # Jurisdiction Rules
+//!
+//! Will compliance rules are data: each jurisdiction is a rule set stored in
+//! `jurisdiction_rule_sets`, one row per code and version. A rule set only
+//! lists the rules it changes and names the jurisdiction it `extends`, so
+//! `US-CA` inherits everything from `US`, which inherits from `GLOBAL`.
+//! Versions are immutable; admins edit a jurisdiction by creating its next
+//! version. The sets in `data/jurisdiction_rules.json` are bundled with the
+//! backend and seeded as version 1.
+//!
+//! Every rule carries a citation, so validation errors can point at the
+//! statute or policy they enforce.
+
+use crate::api_error::ApiError;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize};
+use sqlx::types::Json;
+use sqlx::PgPool;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::OnceLock;
+use uuid::Uuid;
+
+/// Root of every inheritance chain, and the fallback for unknown codes
+pub const GLOBAL_JURISDICTION: &str = "GLOBAL";
+
+/// Names of the rules a rule set can override or cite
+pub const RULE_KEYS: [&str; 9] = [
+    "min_witnesses",
+    "require_notarization",
+    "min_beneficiaries",
+    "require_execution_rules",
+    "min_testator_age",
+    "require_relationship",
+    "holographic_wills_accepted",
+    "witness_beneficiary_conflict",
+    "forced_heirship",
+];
+
+const BUNDLED_RULES: &str = include_str!("../data/jurisdiction_rules.json");
+
+// ─── Rule Types ───────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleSeverity {
+    Error,
+    Warning,
+}
+
+impl RuleSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+        }
+    }
+}
+
+/// What happens to a gift left to someone who witnessed the will
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WitnessConflictRule {
+    Allowed,
+    /// The gift stands but may be challenged
+    Warning,
+    /// The gift to the witness is void
+    Void,
+}
+
+/// Share of the estate reserved by law for certain heirs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForcedHeirship {
+    /// Beneficiary relationships that count as forced heirs, lowercase
+    pub heirs: Vec<String>,
+    pub reserved_share_percent: Decimal,
+    pub severity: RuleSeverity,
+}
+
+impl ForcedHeirship {
+    pub fn covers(&self, relationship: &str) -> bool {
+        let relationship = relationship.trim().to_lowercase();
+        self.heirs.contains(&relationship)
+    }
+}
+
+/// Accept an explicit `null` as "clear the inherited value"
+fn explicit_null<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Rules a rule set sets on top of those it inherits
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_witnesses: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_notarization: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_beneficiaries: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_execution_rules: Option<bool>,
+    #[serde(
+        default,
+        deserialize_with = "explicit_null",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub min_testator_age: Option<Option<u32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_relationship: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub holographic_wills_accepted: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub witness_beneficiary_conflict: Option<WitnessConflictRule>,
+    #[serde(
+        default,
+        deserialize_with = "explicit_null",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub forced_heirship: Option<Option<ForcedHeirship>>,
+}
+
+impl RuleOverrides {
+    /// Names of the rules this set overrides
+    pub fn keys(&self) -> Vec<&'static str> {
+        let set = [
+            self.min_witnesses.is_some(),
+            self.require_notarization.is_some(),
+            self.min_beneficiaries.is_some(),
+            self.require_execution_rules.is_some(),
+            self.min_testator_age.is_some(),
+            self.require_relationship.is_some(),
+            self.holographic_wills_accepted.is_some(),
+            self.witness_beneficiary_conflict.is_some(),
+            self.forced_heirship.is_some(),
+        ];
+        RULE_KEYS
+            .iter()
+            .zip(set)
+            .filter(|(_, set)| *set)
+            .map(|(k, _)| *k)
+            .collect()
+    }
+}
+
+fn first_version() -> i32 {
+    1
+}
+
+/// One version of a jurisdiction's rule set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleSetDefinition {
+    pub code: String,
+    #[serde(default = "first_version")]
+    pub version: i32,
+    pub name: String,
+    pub extends: Option<String>,
+    /// Citation for rules without a more specific one in `sources`
+    pub source: String,
+    #[serde(default)]
+    pub rules: RuleOverrides,
+    #[serde(default)]
+    pub sources: BTreeMap<String, String>,
+}
+
+/// Rules in force for a jurisdiction once inheritance is applied
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JurisdictionRules {
+    pub jurisdiction: String,
+    pub name: String,
+    pub min_witnesses: u32,
+    pub require_notarization: bool,
+    pub min_beneficiaries: u32,
+    pub require_execution_rules: bool,
+    pub min_testator_age: Option<u32>,
+    pub require_relationship: bool,
+    pub holographic_wills_accepted: bool,
+    pub witness_beneficiary_conflict: WitnessConflictRule,
+    pub forced_heirship: Option<ForcedHeirship>,
+    /// Rule sets applied, root first, e.g. `["GLOBAL v1", "US v1", "US-CA v2"]`
+    pub inherits: Vec<String>,
+    /// Citation for each rule, keyed by rule name
+    pub sources: BTreeMap<String, String>,
+}
+
+impl JurisdictionRules {
+    fn baseline() -> Self {
+        Self {
+            jurisdiction: GLOBAL_JURISDICTION.to_string(),
+            name: String::new(),
+            min_witnesses: 1,
+            require_notarization: false,
+            min_beneficiaries: 1,
+            require_execution_rules: false,
+            min_testator_age: None,
+            require_relationship: false,
+            holographic_wills_accepted: false,
+            witness_beneficiary_conflict: WitnessConflictRule::Allowed,
+            forced_heirship: None,
+            inherits: Vec::new(),
+            sources: BTreeMap::new(),
+        }
+    }
+
+    fn apply(&mut self, set: &RuleSetDefinition) {
+        let r = &set.rules;
+        if let Some(v) = r.min_witnesses {
+            self.min_witnesses = v;
+        }
+        if let Some(v) = r.require_notarization {
+            self.require_notarization = v;
+        }
+        if let Some(v) = r.min_beneficiaries {
+            self.min_beneficiaries = v;
+        }
+        if let Some(v) = r.require_execution_rules {
+            self.require_execution_rules = v;
+        }
+        if let Some(v) = r.min_testator_age {
+            self.min_testator_age = v;
+        }
+        if let Some(v) = r.require_relationship {
+            self.require_relationship = v;
+        }
+        if let Some(v) = r.holographic_wills_accepted {
+            self.holographic_wills_accepted = v;
+        }
+        if let Some(v) = r.witness_beneficiary_conflict {
+            self.witness_beneficiary_conflict = v;
+        }
+        if let Some(v) = &r.forced_heirship {
+            self.forced_heirship = v.clone();
+        }
+
+        let label = format!("{} v{}", set.code, set.version);
+        for key in r.keys() {
+            self.sources
+                .insert(key.to_string(), format!("{label}: {}", set.source));
+        }
+        for (key, citation) in &set.sources {
+            self.sources
+                .insert(key.clone(), format!("{label}: {citation}"));
+        }
+        self.jurisdiction = set.code.clone();
+        self.name = set.name.clone();
+        self.inherits.push(label);
+    }
+
+    /// Citation for a rule, if any rule set in the chain provides one
+    pub fn source(&self, rule: &str) -> Option<String> {
+        self.sources.get(rule).cloned()
+    }
+}
+
+/// Normalize a jurisdiction code for lookup, e.g. `us-ca` to `US-CA`
+pub fn normalize_code(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+/// Codes are a 2-6 letter country or bloc code followed by up to two
+/// region segments, e.g. `BR`, `US-CA` or `GLOBAL`.
+pub fn valid_code(code: &str) -> bool {
+    let mut segments = code.split('-');
+    let head = segments.next().unwrap_or("");
+    let rest: Vec<&str> = segments.collect();
+    (2..=6).contains(&head.len())
+        && head.chars().all(|c| c.is_ascii_uppercase())
+        && rest.len() <= 2
+        && rest.iter().all(|s| {
+            (1..=3).contains(&s.len())
+                && s.chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        })
+}
+
+/// The jurisdiction a code inherits from when none is given: its parent
+/// region (`US-CA` extends `US`), or `GLOBAL` for a top-level code.
+pub fn default_parent(code: &str) -> Option<String> {
+    if code == GLOBAL_JURISDICTION {
+        return None;
+    }
+    Some(match code.rsplit_once('-') {
+        Some((parent, _)) => parent.to_string(),
+        None => GLOBAL_JURISDICTION.to_string(),
+    })
+}
+
+/// Check a rule set before it is stored
+pub fn validate_definition(set: &RuleSetDefinition) -> Result<(), ApiError> {
+    if !valid_code(&set.code) {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid jurisdiction code '{}'; expected e.g. 'BR' or 'US-CA'",
+            set.code
+        )));
+    }
+    if set.name.trim().is_empty() || set.name.len() > 255 {
+        return Err(ApiError::BadRequest(
+            "name must be 1-255 characters".to_string(),
+        ));
+    }
+    if set.source.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "source must cite where the rules come from".to_string(),
+        ));
+    }
+    match (&set.extends, set.code.as_str()) {
+        (Some(_), GLOBAL_JURISDICTION) => {
+            return Err(ApiError::BadRequest(
+                "GLOBAL cannot extend another jurisdiction".to_string(),
+            ))
+        }
+        (None, code) if code != GLOBAL_JURISDICTION => {
+            return Err(ApiError::BadRequest(format!(
+                "{code} must extend another jurisdiction"
+            )))
+        }
+        (Some(parent), _) if !valid_code(parent) => {
+            return Err(ApiError::BadRequest(format!(
+                "Invalid parent jurisdiction '{parent}'"
+            )))
+        }
+        _ => {}
+    }
+    if let Some(key) = set
+        .sources
+        .keys()
+        .find(|k| !RULE_KEYS.contains(&k.as_str()))
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Unknown rule '{key}' in sources"
+        )));
+    }
+    if let Some(Some(heirship)) = &set.rules.forced_heirship {
+        if heirship.heirs.iter().all(|h| h.trim().is_empty()) {
+            return Err(ApiError::BadRequest(
+                "forced_heirship.heirs must list at least one relationship".to_string(),
+            ));
+        }
+        if heirship.reserved_share_percent <= Decimal::ZERO
+            || heirship.reserved_share_percent > Decimal::ONE_HUNDRED
+        {
+            return Err(ApiError::BadRequest(
+                "forced_heirship.reserved_share_percent must be in (0, 100]".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+// ─── Catalog ──────────────────────────────────────────────────────────────────
+
+/// The rule set in force for each jurisdiction code
+#[derive(Debug, Clone, Default)]
+pub struct RuleCatalog {
+    sets: BTreeMap<String, RuleSetDefinition>,
+}
+
+impl RuleCatalog {
+    pub fn new(sets: impl IntoIterator<Item = RuleSetDefinition>) -> Self {
+        Self {
+            sets: sets.into_iter().map(|s| (s.code.clone(), s)).collect(),
+        }
+    }
+
+    /// The rule sets bundled with the backend
+    pub fn bundled() -> &'static RuleCatalog {
+        static CATALOG: OnceLock<RuleCatalog> = OnceLock::new();
+        CATALOG.get_or_init(|| {
+            let sets: Vec<RuleSetDefinition> =
+                serde_json::from_str(BUNDLED_RULES).expect("data/jurisdiction_rules.json is valid");
+            RuleCatalog::new(sets)
+        })
+    }
+
+    pub fn codes(&self) -> Vec<String> {
+        self.sets.keys().cloned().collect()
+    }
+
+    pub fn sets(&self) -> impl Iterator<Item = &RuleSetDefinition> {
+        self.sets.values()
+    }
+
+    pub fn insert(&mut self, set: RuleSetDefinition) {
+        self.sets.insert(set.code.clone(), set);
+    }
+
+    pub fn remove(&mut self, code: &str) {
+        self.sets.remove(code);
+    }
+
+    /// The code whose rules apply: the code itself, else its nearest parent
+    /// region (`US-XX` falls back to `US`), else `GLOBAL`.
+    pub fn applicable_code(&self, code: &str) -> String {
+        let mut code = normalize_code(code);
+        loop {
+            if self.sets.contains_key(&code) {
+                return code;
+            }
+            match code.rsplit_once('-') {
+                Some((parent, _)) => code = parent.to_string(),
+                None => return GLOBAL_JURISDICTION.to_string(),
+            }
+        }
+    }
+
+    /// Resolve the rules for a jurisdiction by applying its inheritance chain
+    pub fn resolve(&self, code: &str) -> Result<JurisdictionRules, ApiError> {
+        let mut chain: Vec<&RuleSetDefinition> = Vec::new();
+        let mut seen = BTreeSet::new();
+        let mut next = Some(self.applicable_code(code));
+        while let Some(code) = next {
+            if !seen.insert(code.clone()) {
+                return Err(ApiError::BadRequest(format!(
+                    "Jurisdiction {code} inherits from itself"
+                )));
+            }
+            let set = self.sets.get(&code).ok_or_else(|| {
+                ApiError::BadRequest(format!("Jurisdiction {code} has no active rule set"))
+            })?;
+            chain.push(set);
+            next = set.extends.clone();
+        }
+
+        let mut rules = JurisdictionRules::baseline();
+        for set in chain.into_iter().rev() {
+            rules.apply(set);
+        }
+        Ok(rules)
+    }
+
+    /// Check that every jurisdiction in the catalog resolves
+    pub fn check(&self) -> Result<(), ApiError> {
+        if !self.sets.contains_key(GLOBAL_JURISDICTION) {
+            return Err(ApiError::BadRequest(
+                "The GLOBAL rule set must remain active".to_string(),
+            ));
+        }
+        for code in self.sets.keys() {
+            self.resolve(code)?;
+        }
+        Ok(())
+    }
+}
+
+// ─── Data Structures ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct JurisdictionRuleSetRecord {
+    pub id: Uuid,
+    pub code: String,
+    pub version: i32,
+    pub name: String,
+    pub extends: Option<String>,
+    pub rules: Json<RuleOverrides>,
+    pub source: String,
+    pub sources: Json<BTreeMap<String, String>>,
+    pub status: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl JurisdictionRuleSetRecord {
+    fn into_definition(self) -> RuleSetDefinition {
+        RuleSetDefinition {
+            code: self.code,
+            version: self.version,
+            name: self.name,
+            extends: self.extends,
+            source: self.source,
+            rules: self.rules.0,
+            sources: self.sources.0,
+        }
+    }
+}
+
+const RULE_SET_COLUMNS: &str =
+    "id, code, version, name, extends, rules, source, sources, status, created_by, created_at";
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRuleSetRequest {
+    pub code: String,
+    pub name: String,
+    /// Defaults to the parent region, or `GLOBAL`
+    pub extends: Option<String>,
+    pub source: String,
+    #[serde(default)]
+    pub rules: RuleOverrides,
+    #[serde(default)]
+    pub sources: BTreeMap<String, String>,
+}
+
+/// Changes applied on top of an existing version to form the next one
+#[derive(Debug, Deserialize)]
+pub struct UpdateRuleSetRequest {
+    pub name: Option<String>,
+    pub extends: Option<String>,
+    pub source: Option<String>,
+    pub rules: Option<RuleOverrides>,
+    pub sources: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RuleSetQuery {
+    pub code: Option<String>,
+    /// Include retired versions and superseded versions
+    #[serde(default)]
+    pub all_versions: bool,
+}
+
+// ─── Service ──────────────────────────────────────────────────────────────────
+
+pub struct JurisdictionRuleService;
+
+impl JurisdictionRuleService {
+    /// Seed the bundled rule sets as version 1 for codes with no versions yet.
+    /// Existing versions are never modified.
+    pub async fn ensure_builtin_rules(db: &PgPool) -> Result<(), ApiError> {
+        for set in RuleCatalog::bundled().sets() {
+            sqlx::query(
+                r#"
+                INSERT INTO jurisdiction_rule_sets
+                    (code, version, name, extends, rules, source, sources)
+                SELECT $1, 1, $2, $3, $4, $5, $6
+                WHERE NOT EXISTS (SELECT 1 FROM jurisdiction_rule_sets WHERE code = $1)
+                "#,
+            )
+            .bind(&set.code)
+            .bind(&set.name)
+            .bind(&set.extends)
+            .bind(Json(&set.rules))
+            .bind(&set.source)
+            .bind(Json(&set.sources))
+            .execute(db)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// The latest active version of every jurisdiction, optionally leaving
+    /// one version out. Falls back to the bundled rules before seeding.
+    async fn load_catalog(db: &PgPool, exclude: Option<Uuid>) -> Result<RuleCatalog, ApiError> {
+        let rows = sqlx::query_as::<_, JurisdictionRuleSetRecord>(&format!(
+            "SELECT DISTINCT ON (code) {RULE_SET_COLUMNS} FROM jurisdiction_rule_sets \
+             WHERE status = 'active' AND ($1::UUID IS NULL OR id <> $1) \
+             ORDER BY code, version DESC"
+        ))
+        .bind(exclude)
+        .fetch_all(db)
+        .await?;
+        if rows.is_empty() && exclude.is_none() {
+            return Ok(RuleCatalog::bundled().clone());
+        }
+        Ok(RuleCatalog::new(
+            rows.into_iter()
+                .map(JurisdictionRuleSetRecord::into_definition),
+        ))
+    }
+
+    pub async fn catalog(db: &PgPool) -> Result<RuleCatalog, ApiError> {
+        Self::load_catalog(db, None).await
+    }
+
+    /// Rules in force for a jurisdiction
+    pub async fn resolve(db: &PgPool, code: &str) -> Result<JurisdictionRules, ApiError> {
+        Self::catalog(db).await?.resolve(code)
+    }
+
+    /// List rule sets; by default only the latest active version of each.
+    pub async fn list(
+        db: &PgPool,
+        query: &RuleSetQuery,
+    ) -> Result<Vec<JurisdictionRuleSetRecord>, ApiError> {
+        let sql = if query.all_versions {
+            format!(
+                "SELECT {RULE_SET_COLUMNS} FROM jurisdiction_rule_sets \
+                 WHERE ($1::TEXT IS NULL OR code = $1) \
+                 ORDER BY code, version DESC"
+            )
+        } else {
+            format!(
+                "SELECT DISTINCT ON (code) {RULE_SET_COLUMNS} FROM jurisdiction_rule_sets \
+                 WHERE status = 'active' AND ($1::TEXT IS NULL OR code = $1) \
+                 ORDER BY code, version DESC"
+            )
+        };
+        let rows = sqlx::query_as::<_, JurisdictionRuleSetRecord>(&sql)
+            .bind(query.code.as_deref().map(normalize_code))
+            .fetch_all(db)
+            .await?;
+        Ok(rows)
+    }
+
+    pub async fn get(db: &PgPool, id: Uuid) -> Result<JurisdictionRuleSetRecord, ApiError> {
+        sqlx::query_as::<_, JurisdictionRuleSetRecord>(&format!(
+            "SELECT {RULE_SET_COLUMNS} FROM jurisdiction_rule_sets WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Jurisdiction rule set {id} not found")))
+    }
+
+    /// Store a rule set as the next version of its jurisdiction.
+    pub async fn create(
+        db: &PgPool,
+        admin_id: Uuid,
+        req: &CreateRuleSetRequest,
+    ) -> Result<JurisdictionRuleSetRecord, ApiError> {
+        let code = normalize_code(&req.code);
+        let extends = match &req.extends {
+            Some(parent) => Some(normalize_code(parent)),
+            None => default_parent(&code),
+        };
+        let set = RuleSetDefinition {
+            code,
+            version: 0,
+            name: req.name.trim().to_string(),
+            extends,
+            source: req.source.trim().to_string(),
+            rules: req.rules.clone(),
+            sources: req.sources.clone(),
+        };
+        validate_definition(&set)?;
+
+        // The new version must leave every jurisdiction resolvable
+        let mut catalog = Self::catalog(db).await?;
+        catalog.insert(set.clone());
+        catalog.check()?;
+
+        let record = sqlx::query_as::<_, JurisdictionRuleSetRecord>(&format!(
+            r#"
+            INSERT INTO jurisdiction_rule_sets
+                (code, version, name, extends, rules, source, sources, created_by)
+            SELECT $1, COALESCE(MAX(version), 0) + 1, $2, $3, $4, $5, $6, $7
+            FROM jurisdiction_rule_sets WHERE code = $1
+            RETURNING {RULE_SET_COLUMNS}
+            "#
+        ))
+        .bind(&set.code)
+        .bind(&set.name)
+        .bind(&set.extends)
+        .bind(Json(&set.rules))
+        .bind(&set.source)
+        .bind(Json(&set.sources))
+        .bind(admin_id)
+        .fetch_one(db)
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => ApiError::BadRequest(
+                "Another version of this jurisdiction was created concurrently; retry".to_string(),
+            ),
+            _ => ApiError::from(e),
+        })?;
+        Ok(record)
+    }
+
+    /// Create the next version of a rule set from an existing one.
+    pub async fn update(
+        db: &PgPool,
+        admin_id: Uuid,
+        id: Uuid,
+        req: &UpdateRuleSetRequest,
+    ) -> Result<JurisdictionRuleSetRecord, ApiError> {
+        let current = Self::get(db, id).await?;
+        let next = CreateRuleSetRequest {
+            code: current.code,
+            name: req.name.clone().unwrap_or(current.name),
+            extends: req.extends.clone().or(current.extends),
+            source: req.source.clone().unwrap_or(current.source),
+            rules: req.rules.clone().unwrap_or(current.rules.0),
+            sources: req.sources.clone().unwrap_or(current.sources.0),
+        };
+        Self::create(db, admin_id, &next).await
+    }
+
+    /// Retire a version. The previous active version of the jurisdiction, if
+    /// any, takes over; retiring is refused if it would leave another
+    /// jurisdiction without a parent.
+    pub async fn retire(db: &PgPool, id: Uuid) -> Result<JurisdictionRuleSetRecord, ApiError> {
+        Self::get(db, id).await?;
+        Self::load_catalog(db, Some(id)).await?.check()?;
+
+        sqlx::query_as::<_, JurisdictionRuleSetRecord>(&format!(
+            "UPDATE jurisdiction_rule_sets SET status = 'retired' WHERE id = $1 \
+             RETURNING {RULE_SET_COLUMNS}"
+        ))
+        .bind(id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Jurisdiction rule set {id} not found")))
+    }
+}
+
+// ─── Unit Tests ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(code: &str, extends: Option<&str>, rules: RuleOverrides) -> RuleSetDefinition {
+        RuleSetDefinition {
+            code: code.to_string(),
+            version: 1,
+            name: code.to_string(),
+            extends: extends.map(str::to_string),
+            source: format!("{code} statute"),
+            rules,
+            sources: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn bundled_rules_resolve_for_every_jurisdiction() {
+        let catalog = RuleCatalog::bundled();
+        catalog.check().unwrap();
+        for set in catalog.sets() {
+            validate_definition(set).unwrap();
+        }
+    }
+
+    #[test]
+    fn sub_national_rules_inherit_and_override() {
+        let catalog = RuleCatalog::bundled();
+        let ca = catalog.resolve("us-ca").unwrap();
+        assert_eq!(ca.jurisdiction, "US-CA");
+        assert_eq!(ca.inherits, vec!["GLOBAL v1", "US v1", "US-CA v1"]);
+        // Inherited from US, overridden by California
+        assert_eq!(ca.min_witnesses, 2);
+        assert!(ca.holographic_wills_accepted);
+        assert_eq!(
+            ca.source("min_witnesses").as_deref(),
+            Some("US-CA v1: Cal. Prob. Code § 6110")
+        );
+        assert_eq!(
+            ca.source("min_testator_age").as_deref(),
+            Some("US v1: Uniform Probate Code § 2-501")
+        );
+
+        // Scotland overrides the UK witness rules
+        let sct = catalog.resolve("UK-SCT").unwrap();
+        assert_eq!(sct.min_witnesses, 1);
+        assert!(sct.require_relationship);
+        assert_eq!(
+            sct.witness_beneficiary_conflict,
+            WitnessConflictRule::Allowed
+        );
+    }
+
+    #[test]
+    fn unknown_codes_fall_back_to_nearest_parent() {
+        let catalog = RuleCatalog::bundled();
+        assert_eq!(catalog.applicable_code("US-WY"), "US");
+        assert_eq!(catalog.applicable_code("ZZ"), "GLOBAL");
+        assert_eq!(catalog.resolve("").unwrap().jurisdiction, "GLOBAL");
+    }
+
+    #[test]
+    fn explicit_null_clears_inherited_rule() {
+        let overrides: RuleOverrides =
+            serde_json::from_str(r#"{"forced_heirship": null, "min_witnesses": 3}"#).unwrap();
+        assert_eq!(overrides.forced_heirship, Some(None));
+        assert_eq!(overrides.keys(), vec!["min_witnesses", "forced_heirship"]);
+
+        let mut catalog = RuleCatalog::bundled().clone();
+        catalog.insert(set("BR-SP", Some("BR"), overrides));
+        let sp = catalog.resolve("BR-SP").unwrap();
+        assert!(sp.forced_heirship.is_none());
+        assert_eq!(sp.min_witnesses, 3);
+
+        assert!(serde_json::from_str::<RuleOverrides>(r#"{"min_witneses": 2}"#).is_err());
+    }
+
+    #[test]
+    fn catalog_check_rejects_cycles_and_orphans() {
+        let mut catalog = RuleCatalog::new([
+            set("GLOBAL", None, RuleOverrides::default()),
+            set("AA", Some("BB"), RuleOverrides::default()),
+            set("BB", Some("AA"), RuleOverrides::default()),
+        ]);
+        assert!(catalog.check().is_err());
+
+        catalog.insert(set("BB", Some("GLOBAL"), RuleOverrides::default()));
+        assert!(catalog.check().is_ok());
+
+        catalog.remove("BB");
+        assert!(catalog.check().is_err());
+    }
+
+    #[test]
+    fn codes_are_validated() {
+        assert!(valid_code("GLOBAL"));
+        assert!(valid_code("US-CA"));
+        assert!(valid_code("UK-SCT"));
+        assert!(!valid_code("us"));
+        assert!(!valid_code("U"));
+        assert!(!valid_code("US-CALIF"));
+        assert_eq!(default_parent("US-CA").as_deref(), Some("US"));
+        assert_eq!(default_parent("BR").as_deref(), Some("GLOBAL"));
+        assert_eq!(default_parent("GLOBAL"), None);
+    }
+}
@@ -0,0 +1,423 @@
+//! Key-encryption keys (KEKs) for envelope-encrypted will documents.
+//!
+//! Every document is encrypted under its own random data key. Only the data
+//! key wrapped by a versioned KEK is stored, so rotating the KEK re-wraps a
+//! few dozen bytes per document instead of re-encrypting the content; see
+//! [`crate::document_storage::DocumentKeyRotationService`].
+//!
+//! Two [`KeyManager`]s are provided, selected by `DOCUMENT_KEY_MANAGER`:
+//! - `local` (default): a keyring file at `DOCUMENT_KEYRING_PATH`.
+//! - `kms`: a stand-in for a cloud KMS that derives versioned KEKs from
+//!   `DOCUMENT_KMS_ROOT_KEY` and never exposes them to callers.
+//!
+//! The server refuses to start when neither is configured.
+
+use crate::api_error::ApiError;
+use async_trait::async_trait;
+use base64::Engine as _;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM};
+use ring::hkdf::{Salt, HKDF_SHA256};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+pub(crate) const NONCE_LEN: usize = 12;
+pub(crate) const KEY_LEN: usize = 32;
+
+/// Header on ciphertext blobs returned by [`KmsKeyManager`]
+const KMS_BLOB_MAGIC: &[u8; 4] = b"IXK1";
+
+// ---------------------------------------------------------------------------
+// AES-256-GCM helpers
+// ---------------------------------------------------------------------------
+
+pub(crate) fn aes_key(raw: &[u8]) -> Result<LessSafeKey, ApiError> {
+    let unbound = UnboundKey::new(&AES_256_GCM, raw)
+        .map_err(|_| ApiError::Internal(anyhow::anyhow!("Failed to create encryption key")))?;
+    Ok(LessSafeKey::new(unbound))
+}
+
+/// Encrypt `plaintext`, returning `(ciphertext, nonce)`.
+pub(crate) fn seal(key: &LessSafeKey, plaintext: &[u8]) -> Result<(Vec<u8>, Vec<u8>), ApiError> {
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce_bytes)
+        .map_err(|_| ApiError::Internal(anyhow::anyhow!("Failed to generate nonce")))?;
+    let nonce = Nonce::assume_unique_for_key(nonce_bytes);
+
+    let mut in_out = plaintext.to_vec();
+    key.seal_in_place_append_tag(nonce, Aad::empty(), &mut in_out)
+        .map_err(|_| ApiError::Internal(anyhow::anyhow!("Encryption failed")))?;
+    Ok((in_out, nonce_bytes.to_vec()))
+}
+
+pub(crate) fn open(
+    key: &LessSafeKey,
+    ciphertext: &[u8],
+    nonce: &[u8],
+) -> Result<Vec<u8>, ApiError> {
+    if nonce.len() != NONCE_LEN {
+        return Err(ApiError::Internal(anyhow::anyhow!("Invalid nonce length")));
+    }
+    let mut nonce_arr = [0u8; NONCE_LEN];
+    nonce_arr.copy_from_slice(nonce);
+    let nonce = Nonce::assume_unique_for_key(nonce_arr);
+
+    let mut in_out = ciphertext.to_vec();
+    let plaintext = key
+        .open_in_place(nonce, Aad::empty(), &mut in_out)
+        .map_err(|_| ApiError::Internal(anyhow::anyhow!("Decryption failed")))?;
+    Ok(plaintext.to_vec())
+}
+
+/// A fresh random data key for one document
+pub(crate) fn generate_data_key() -> Result<Vec<u8>, ApiError> {
+    let mut key = [0u8; KEY_LEN];
+    SystemRandom::new()
+        .fill(&mut key)
+        .map_err(|_| ApiError::Internal(anyhow::anyhow!("Failed to generate data key")))?;
+    Ok(key.to_vec())
+}
+
+/// Wrap with a raw KEK as `nonce || ciphertext`
+fn wrap_with(kek: &[u8], data_key: &[u8]) -> Result<Vec<u8>, ApiError> {
+    let (ciphertext, nonce) = seal(&aes_key(kek)?, data_key)?;
+    Ok([nonce, ciphertext].concat())
+}
+
+fn unwrap_with(kek: &[u8], wrapped: &[u8]) -> Result<Vec<u8>, ApiError> {
+    if wrapped.len() <= NONCE_LEN {
+        return Err(ApiError::Internal(anyhow::anyhow!(
+            "Wrapped key is truncated"
+        )));
+    }
+    let (nonce, ciphertext) = wrapped.split_at(NONCE_LEN);
+    open(&aes_key(kek)?, ciphertext, nonce)
+}
+
+// ---------------------------------------------------------------------------
+// Key managers
+// ---------------------------------------------------------------------------
+
+/// A data key wrapped under a KEK version
+#[derive(Debug, Clone)]
+pub struct WrappedKey {
+    pub kek_version: i32,
+    pub ciphertext: Vec<u8>,
+}
+
+#[async_trait]
+pub trait KeyManager: Send + Sync {
+    /// Short name for logs and the admin key status
+    fn name(&self) -> &'static str;
+
+    /// KEK version new data keys are wrapped under
+    fn active_version(&self) -> i32;
+
+    /// Wrap `data_key` under the active KEK.
+    async fn wrap(&self, data_key: &[u8]) -> Result<WrappedKey, ApiError>;
+
+    /// Unwrap a data key wrapped under KEK `kek_version`.
+    async fn unwrap(&self, kek_version: i32, wrapped: &[u8]) -> Result<Vec<u8>, ApiError>;
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyringFile {
+    active_version: i32,
+    keys: Vec<KeyringEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyringEntry {
+    version: i32,
+    /// Base64-encoded 32-byte key
+    key: String,
+}
+
+/// KEKs read from a JSON keyring file:
+///
+/// ```json
+/// { "active_version": 2,
+///   "keys": [{ "version": 1, "key": "<base64>" }, { "version": 2, "key": "<base64>" }] }
+/// ```
+///
+/// To rotate, append a key, point `active_version` at it and restart; old
+/// versions must stay in the file until re-encryption has moved every
+/// document off them.
+pub struct LocalKeyManager {
+    active_version: i32,
+    keys: BTreeMap<i32, Vec<u8>>,
+}
+
+impl LocalKeyManager {
+    pub fn new(active_version: i32, keys: BTreeMap<i32, Vec<u8>>) -> Result<Self, ApiError> {
+        if let Some((version, _)) = keys.iter().find(|(_, key)| key.len() != KEY_LEN) {
+            return Err(ApiError::Internal(anyhow::anyhow!(
+                "Keyring key version {} must be {} bytes",
+                version,
+                KEY_LEN
+            )));
+        }
+        if !keys.contains_key(&active_version) {
+            return Err(ApiError::Internal(anyhow::anyhow!(
+                "Keyring has no key for active version {}",
+                active_version
+            )));
+        }
+        Ok(Self {
+            active_version,
+            keys,
+        })
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, ApiError> {
+        let raw = std::fs::read_to_string(path).map_err(|e| {
+            ApiError::Internal(anyhow::anyhow!(
+                "Failed to read keyring {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let file: KeyringFile = serde_json::from_str(&raw).map_err(|e| {
+            ApiError::Internal(anyhow::anyhow!("Invalid keyring {}: {}", path.display(), e))
+        })?;
+
+        let mut keys = BTreeMap::new();
+        for entry in file.keys {
+            let key = base64::engine::general_purpose::STANDARD
+                .decode(entry.key.trim())
+                .map_err(|_| {
+                    ApiError::Internal(anyhow::anyhow!(
+                        "Keyring key version {} is not valid base64",
+                        entry.version
+                    ))
+                })?;
+            if keys.insert(entry.version, key).is_some() {
+                return Err(ApiError::Internal(anyhow::anyhow!(
+                    "Keyring lists version {} twice",
+                    entry.version
+                )));
+            }
+        }
+        Self::new(file.active_version, keys)
+    }
+}
+
+#[async_trait]
+impl KeyManager for LocalKeyManager {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    fn active_version(&self) -> i32 {
+        self.active_version
+    }
+
+    async fn wrap(&self, data_key: &[u8]) -> Result<WrappedKey, ApiError> {
+        Ok(WrappedKey {
+            kek_version: self.active_version,
+            ciphertext: wrap_with(&self.keys[&self.active_version], data_key)?,
+        })
+    }
+
+    async fn unwrap(&self, kek_version: i32, wrapped: &[u8]) -> Result<Vec<u8>, ApiError> {
+        let kek = self.keys.get(&kek_version).ok_or_else(|| {
+            ApiError::Internal(anyhow::anyhow!(
+                "KEK version {} is not in the keyring",
+                kek_version
+            ))
+        })?;
+        unwrap_with(kek, wrapped)
+    }
+}
+
+/// Stand-in for a cloud KMS key. Versions `1..=active_version` are derived
+/// from a root secret, as a KMS keeps every version of a rotated key, and
+/// wrapped keys are opaque blobs that name the version they were made with.
+pub struct KmsKeyManager {
+    root: Vec<u8>,
+    active_version: i32,
+}
+
+impl KmsKeyManager {
+    pub fn new(root: &[u8], active_version: i32) -> Result<Self, ApiError> {
+        if root.len() < KEY_LEN {
+            return Err(ApiError::Internal(anyhow::anyhow!(
+                "KMS root key must be at least {} bytes",
+                KEY_LEN
+            )));
+        }
+        if active_version < 1 {
+            return Err(ApiError::Internal(anyhow::anyhow!(
+                "KMS key version must be at least 1"
+            )));
+        }
+        Ok(Self {
+            root: root.to_vec(),
+            active_version,
+        })
+    }
+
+    fn kek(&self, version: i32) -> Result<Vec<u8>, ApiError> {
+        if !(1..=self.active_version).contains(&version) {
+            return Err(ApiError::Internal(anyhow::anyhow!(
+                "KMS key version {} does not exist",
+                version
+            )));
+        }
+        let info = format!("inheritx-kms-kek-v{}", version);
+        let info = [info.as_bytes()];
+        let prk = Salt::new(HKDF_SHA256, b"inheritx-kms").extract(&self.root);
+        let okm = prk
+            .expand(&info, &AES_256_GCM)
+            .map_err(|_| ApiError::Internal(anyhow::anyhow!("Key derivation failed")))?;
+        let mut kek = [0u8; KEY_LEN];
+        okm.fill(&mut kek)
+            .map_err(|_| ApiError::Internal(anyhow::anyhow!("Key derivation failed")))?;
+        Ok(kek.to_vec())
+    }
+}
+
+#[async_trait]
+impl KeyManager for KmsKeyManager {
+    fn name(&self) -> &'static str {
+        "kms"
+    }
+
+    fn active_version(&self) -> i32 {
+        self.active_version
+    }
+
+    async fn wrap(&self, data_key: &[u8]) -> Result<WrappedKey, ApiError> {
+        let version = self.active_version;
+        let wrapped = wrap_with(&self.kek(version)?, data_key)?;
+        Ok(WrappedKey {
+            kek_version: version,
+            ciphertext: [&KMS_BLOB_MAGIC[..], &version.to_be_bytes(), &wrapped].concat(),
+        })
+    }
+
+    async fn unwrap(&self, kek_version: i32, wrapped: &[u8]) -> Result<Vec<u8>, ApiError> {
+        let blob = wrapped
+            .strip_prefix(&KMS_BLOB_MAGIC[..])
+            .filter(|rest| rest.len() > 4)
+            .ok_or_else(|| ApiError::Internal(anyhow::anyhow!("Not a KMS ciphertext blob")))?;
+        let (version, wrapped) = blob.split_at(4);
+        let version = i32::from_be_bytes([version[0], version[1], version[2], version[3]]);
+        if version != kek_version {
+            return Err(ApiError::Internal(anyhow::anyhow!(
+                "KMS blob was made with key version {}, expected {}",
+                version,
+                kek_version
+            )));
+        }
+        unwrap_with(&self.kek(version)?, wrapped)
+    }
+}
+
+/// Build the configured key manager, failing when no key is configured.
+pub fn key_manager_from_env() -> Result<Arc<dyn KeyManager>, ApiError> {
+    let backend = std::env::var("DOCUMENT_KEY_MANAGER").unwrap_or_else(|_| "local".to_string());
+    match backend.as_str() {
+        "local" => {
+            let path = std::env::var("DOCUMENT_KEYRING_PATH")
+                .ok()
+                .filter(|p| !p.trim().is_empty())
+                .ok_or_else(|| {
+                    ApiError::Internal(anyhow::anyhow!(
+                        "DOCUMENT_KEYRING_PATH must be set to encrypt will documents"
+                    ))
+                })?;
+            Ok(Arc::new(LocalKeyManager::from_file(Path::new(&path))?))
+        }
+        "kms" => {
+            let root = std::env::var("DOCUMENT_KMS_ROOT_KEY").unwrap_or_default();
+            let version = match std::env::var("DOCUMENT_KMS_KEY_VERSION") {
+                Ok(v) => v.parse().map_err(|_| {
+                    ApiError::Internal(anyhow::anyhow!(
+                        "DOCUMENT_KMS_KEY_VERSION must be an integer"
+                    ))
+                })?,
+                Err(_) => 1,
+            };
+            Ok(Arc::new(KmsKeyManager::new(root.as_bytes(), version)?))
+        }
+        other => Err(ApiError::Internal(anyhow::anyhow!(
+            "Unknown DOCUMENT_KEY_MANAGER: {}",
+            other
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyring(active: i32, versions: &[i32]) -> LocalKeyManager {
+        let keys = versions
+            .iter()
+            .map(|v| (*v, vec![*v as u8; KEY_LEN]))
+            .collect();
+        LocalKeyManager::new(active, keys).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_local_wrap_round_trip_across_rotation() {
+        let data_key = generate_data_key().unwrap();
+        let wrapped = keyring(1, &[1]).wrap(&data_key).await.unwrap();
+        assert_eq!(wrapped.kek_version, 1);
+
+        // After rotation the old version still unwraps, new wraps use v2.
+        let rotated = keyring(2, &[1, 2]);
+        let unwrapped = rotated.unwrap(1, &wrapped.ciphertext).await.unwrap();
+        assert_eq!(unwrapped, data_key);
+        assert_eq!(rotated.wrap(&data_key).await.unwrap().kek_version, 2);
+
+        assert!(rotated.unwrap(2, &wrapped.ciphertext).await.is_err());
+        assert!(keyring(2, &[2])
+            .unwrap(1, &wrapped.ciphertext)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_local_keyring_validation() {
+        assert!(LocalKeyManager::new(2, BTreeMap::from([(1, vec![0u8; KEY_LEN])])).is_err());
+        assert!(LocalKeyManager::new(1, BTreeMap::from([(1, vec![0u8; 16])])).is_err());
+    }
+
+    #[test]
+    fn test_local_keyring_file() {
+        let path = std::env::temp_dir().join(format!("keyring-{}.json", uuid::Uuid::new_v4()));
+        let key = base64::engine::general_purpose::STANDARD.encode([7u8; KEY_LEN]);
+        std::fs::write(
+            &path,
+            format!(r#"{{"active_version": 1, "keys": [{{"version": 1, "key": "{key}"}}]}}"#),
+        )
+        .unwrap();
+        let manager = LocalKeyManager::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(manager.active_version(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_kms_blobs_are_bound_to_their_version() {
+        let root = [9u8; KEY_LEN];
+        let data_key = generate_data_key().unwrap();
+        let v1 = KmsKeyManager::new(&root, 1).unwrap();
+        let wrapped = v1.wrap(&data_key).await.unwrap();
+
+        let v2 = KmsKeyManager::new(&root, 2).unwrap();
+        assert_eq!(v2.unwrap(1, &wrapped.ciphertext).await.unwrap(), data_key);
+        assert!(v2.unwrap(2, &wrapped.ciphertext).await.is_err());
+        assert!(v1.unwrap(1, &wrapped.ciphertext[4..]).await.is_err());
+        assert!(KmsKeyManager::new(&[9u8; KEY_LEN - 1], 1).is_err());
+        assert!(KmsKeyManager::new(&[0u8; KEY_LEN], 1)
+            .unwrap()
+            .unwrap(1, &wrapped.ciphertext)
+            .await
+            .is_err());
+    }
+}
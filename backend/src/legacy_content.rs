@@ -1,13 +1,50 @@
 use crate::api_error::ApiError;
-use chrono::{DateTime, Utc};
+use crate::blob_store::{BlobReader, BlobService, BlobStore};
+use crate::document_storage::{legacy_key_bytes, NONCE_LEN};
+use crate::key_manager::{self, KeyManager, WrappedKey};
+use chrono::{DateTime, Duration, Utc};
+use futures::stream::BoxStream;
+use ring::aead::{Aad, LessSafeKey, Nonce};
+use ring::rand::{SecureRandom, SystemRandom};
 use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
+use sha2::{Digest, Sha256};
+use sqlx::{PgConnection, PgPool};
 use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 use uuid::Uuid;
 
-const MAX_FILE_SIZE: usize = 524_288_000; // 500MB
-const ALLOWED_VIDEO_TYPES: &[&str] = &["video/mp4", "video/mpeg", "video/quicktime", "video/x-msvideo", "video/webm"];
-const ALLOWED_AUDIO_TYPES: &[&str] = &["audio/mpeg", "audio/wav", "audio/ogg", "audio/mp4", "audio/webm"];
+pub const MAX_FILE_SIZE: usize = 524_288_000; // 500MB
+pub const MAX_CHUNK_SIZE: usize = 8_388_608; // 8MB
+const DEFAULT_USER_QUOTA: i64 = 2_147_483_648; // 2GB
+const UPLOAD_SESSION_TTL_HOURS: i64 = 24;
+const DEFAULT_STORAGE_PATH: &str = "storage";
+
+// Sealed files are a header (magic + nonce prefix) followed by AES-256-GCM
+// segments. Each segment nonce is the prefix plus a big-endian counter, and the
+// AAD marks the final segment so truncation at a segment boundary is detected.
+const SEALED_MAGIC: &[u8; 4] = b"IXC1";
+const NONCE_PREFIX_LEN: usize = 8;
+const SEGMENT_SIZE: usize = 65_536;
+const TAG_LEN: usize = 16;
+
+/// Decrypted content, yielded one segment at a time
+pub type ContentStream = BoxStream<'static, Result<Vec<u8>, ApiError>>;
+
+const ALLOWED_VIDEO_TYPES: &[&str] = &[
+    "video/mp4",
+    "video/mpeg",
+    "video/quicktime",
+    "video/x-msvideo",
+    "video/webm",
+];
+const ALLOWED_AUDIO_TYPES: &[&str] = &[
+    "audio/mpeg",
+    "audio/wav",
+    "audio/ogg",
+    "audio/mp4",
+    "audio/webm",
+];
 const ALLOWED_TEXT_TYPES: &[&str] = &["text/plain", "text/markdown", "text/html"];
 const ALLOWED_DOCUMENT_TYPES: &[&str] = &[
     "application/pdf",
@@ -50,6 +87,36 @@ pub struct ContentListFilters {
     pub offset: Option<i64>,
 }
 
+/// A resumable upload. Chunks are appended to a staging file in order and the
+/// upload is sealed into `legacy_content` once the declared size and SHA-256
+/// hash have been verified.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct UploadSession {
+    pub id: Uuid,
+    pub owner_user_id: Uuid,
+    pub original_filename: String,
+    pub content_type: String,
+    pub description: Option<String>,
+    pub total_size: i64,
+    pub expected_hash: String,
+    pub received_bytes: i64,
+    pub status: String,
+    pub content_id: Option<Uuid>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InitUploadRequest {
+    pub original_filename: String,
+    pub content_type: String,
+    pub total_size: i64,
+    /// Hex-encoded SHA-256 of the complete file, verified on completion.
+    pub sha256: String,
+    pub description: Option<String>,
+}
+
 pub struct LegacyContentService;
 
 impl LegacyContentService {
@@ -89,20 +156,124 @@ impl LegacyContentService {
         Ok(())
     }
 
+    /// Validate a client-declared SHA-256 hex digest
+    pub fn validate_sha256(hash: &str) -> Result<(), ApiError> {
+        if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ApiError::BadRequest(
+                "sha256 must be a 64-character hex digest".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
     /// Generate storage path
     pub fn generate_storage_path(user_id: Uuid, filename: &str) -> String {
         let date = Utc::now().format("%Y/%m/%d");
         format!("legacy_content/{}/{}/{}", user_id, date, filename)
     }
 
+    /// Staging path for an in-flight upload
+    pub fn staging_path(upload_id: Uuid) -> String {
+        format!("uploads/{}.part", upload_id)
+    }
+
     /// Calculate SHA-256 hash of file content
     pub fn calculate_file_hash(content: &[u8]) -> String {
-        use sha2::{Digest, Sha256};
         let mut hasher = Sha256::new();
         hasher.update(content);
         format!("{:x}", hasher.finalize())
     }
 
+    /// `Content-Disposition` for downloading a file under its client-supplied
+    /// name: an ASCII `filename` with anything unsafe in a quoted string
+    /// replaced, and the exact name as an RFC 5987 `filename*`
+    pub fn content_disposition(original_filename: &str) -> String {
+        let fallback: String = original_filename
+            .chars()
+            .map(|c| match c {
+                ' ' => ' ',
+                '"' | '\\' => '_',
+                c if c.is_ascii_graphic() => c,
+                _ => '_',
+            })
+            .collect();
+        let encoded: String = original_filename
+            .bytes()
+            .map(|b| match b {
+                b'A'..=b'Z'
+                | b'a'..=b'z'
+                | b'0'..=b'9'
+                | b'!'
+                | b'#'
+                | b'$'
+                | b'&'
+                | b'+'
+                | b'-'
+                | b'.'
+                | b'^'
+                | b'_'
+                | b'`'
+                | b'|'
+                | b'~' => (b as char).to_string(),
+                _ => format!("%{:02X}", b),
+            })
+            .collect();
+        format!(
+            "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+            fallback, encoded
+        )
+    }
+
+    /// Per-user storage quota in bytes (`LEGACY_CONTENT_QUOTA_BYTES`, default 2GB)
+    pub fn user_quota_bytes() -> i64 {
+        std::env::var("LEGACY_CONTENT_QUOTA_BYTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_USER_QUOTA)
+    }
+
+    /// Reject an upload that would take the user past their quota
+    pub fn check_quota(
+        used: i64,
+        reserved: i64,
+        incoming: i64,
+        quota: i64,
+    ) -> Result<(), ApiError> {
+        let remaining = quota.saturating_sub(used).saturating_sub(reserved);
+        if incoming > remaining {
+            return Err(ApiError::Forbidden(format!(
+                "Storage quota exceeded: {} bytes requested, {} bytes available",
+                incoming,
+                remaining.max(0)
+            )));
+        }
+        Ok(())
+    }
+
+    /// Bytes the user can still upload, accounting for stored content and the
+    /// declared sizes of open upload sessions.
+    pub async fn remaining_quota(
+        conn: &mut PgConnection,
+        owner_user_id: Uuid,
+    ) -> Result<i64, ApiError> {
+        let (used, reserved): (i64, i64) = sqlx::query_as(
+            "SELECT \
+                (SELECT COALESCE(SUM(file_size), 0)::bigint FROM legacy_content \
+                 WHERE owner_user_id = $1 AND status = 'active'), \
+                (SELECT COALESCE(SUM(total_size), 0)::bigint FROM legacy_content_upload_sessions \
+                 WHERE owner_user_id = $1 AND status IN ('in_progress', 'finalizing') \
+                   AND expires_at > NOW())",
+        )
+        .bind(owner_user_id)
+        .fetch_one(conn)
+        .await?;
+
+        Ok(Self::user_quota_bytes()
+            .saturating_sub(used)
+            .saturating_sub(reserved)
+            .max(0))
+    }
+
     /// Store file metadata in database
     pub async fn create_content_record(
         db: &PgPool,
@@ -110,9 +281,23 @@ impl LegacyContentService {
         metadata: &UploadMetadata,
         storage_path: String,
         file_hash: String,
+    ) -> Result<LegacyContent, ApiError> {
+        Self::insert_content_record(db, owner_user_id, metadata, storage_path, file_hash, None)
+            .await
+    }
+
+    /// `content_key` is the record's data key wrapped by the key manager;
+    /// its KEK version is stored as `encryption_key_version`.
+    async fn insert_content_record(
+        db: &PgPool,
+        owner_user_id: Uuid,
+        metadata: &UploadMetadata,
+        storage_path: String,
+        file_hash: String,
+        content_key: Option<&WrappedKey>,
     ) -> Result<LegacyContent, ApiError> {
         let filename = Uuid::new_v4().to_string();
-        
+
         let meta_json = serde_json::json!({
             "description": metadata.description,
             "uploaded_at": Utc::now()
@@ -121,14 +306,15 @@ impl LegacyContentService {
         let record = sqlx::query_as!(
             LegacyContent,
             r#"
-            INSERT INTO legacy_content 
-            (owner_user_id, filename, original_filename, content_type, file_size, 
-             storage_path, file_hash, encrypted, status, metadata)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, false, 'active', $8)
-            RETURNING 
-                id, owner_user_id, filename, original_filename, content_type, 
-                file_size, storage_path, file_hash, encrypted, 
-                encryption_key_version, status, 
+            INSERT INTO legacy_content
+            (owner_user_id, filename, original_filename, content_type, file_size,
+             storage_path, file_hash, encrypted, encryption_key_version, wrapped_data_key,
+             status, metadata)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, 'active', $11)
+            RETURNING
+                id, owner_user_id, filename, original_filename, content_type,
+                file_size, storage_path, file_hash, encrypted,
+                encryption_key_version, status,
                 metadata as "metadata: serde_json::Value",
                 created_at, updated_at
             "#,
@@ -139,6 +325,9 @@ impl LegacyContentService {
             metadata.file_size as i64,
             storage_path,
             file_hash,
+            content_key.is_some(),
+            content_key.map(|key| key.kek_version),
+            content_key.map(|key| key.ciphertext.as_slice()),
             meta_json
         )
         .fetch_one(db)
@@ -147,6 +336,389 @@ impl LegacyContentService {
         Ok(record)
     }
 
+    /// Encrypt a fully staged upload into the blob store and record it.
+    ///
+    /// Each upload is sealed under a fresh data key wrapped by the key
+    /// manager, so KEK rotation covers legacy content like documents. The
+    /// SHA-256 hash is computed server-side while sealing; if the client
+    /// declared a hash and it does not match, nothing is recorded. Blobs are
+    /// addressed by the hash of the sealed file, which reveals nothing about
+    /// whether anyone stored the same plaintext before.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn finalize_staged_upload(
+        db: &PgPool,
+        storage: &FileStorageService,
+        blobs: &dyn BlobStore,
+        keys: &dyn KeyManager,
+        owner_user_id: Uuid,
+        metadata: &UploadMetadata,
+        staging_path: &str,
+        expected_hash: Option<&str>,
+    ) -> Result<LegacyContent, ApiError> {
+        let (data_key, wrapped) = match new_content_key(keys).await {
+            Ok(key) => key,
+            Err(e) => {
+                let _ = storage.delete_file(staging_path).await;
+                return Err(e);
+            }
+        };
+
+        let sealed_path = format!("{}.sealed", staging_path.trim_end_matches(".part"));
+        let sealed = storage
+            .seal_file(staging_path, &sealed_path, &data_key)
+            .await;
+        let _ = storage.delete_file(staging_path).await;
+        let SealedFile {
+            size: file_size,
+            plaintext_hash: file_hash,
+            sealed_hash,
+        } = match sealed {
+            Ok(sealed) => sealed,
+            Err(e) => {
+                let _ = storage.delete_file(&sealed_path).await;
+                return Err(e);
+            }
+        };
+
+        let verified =
+            Self::validate_file_size(file_size as usize).and_then(|_| match expected_hash {
+                Some(expected) if !expected.eq_ignore_ascii_case(&file_hash) => {
+                    Err(ApiError::BadRequest(format!(
+                        "SHA-256 mismatch: expected {}, received {}",
+                        expected, file_hash
+                    )))
+                }
+                _ => Ok(()),
+            });
+        if let Err(e) = verified {
+            let _ = storage.delete_file(&sealed_path).await;
+            return Err(e);
+        }
+
+        let blob =
+            BlobService::store_file_as(db, blobs, &sealed_hash, &storage.full_path(&sealed_path))
+                .await;
+        let _ = storage.delete_file(&sealed_path).await;
+        let blob = blob?;
+
+        let metadata = UploadMetadata {
+            file_size: file_size as usize,
+            ..metadata.clone()
+        };
+        let result = Self::insert_content_record(
+            db,
+            owner_user_id,
+            &metadata,
+            blob.storage_key,
+            file_hash,
+            Some(&wrapped),
+        )
+        .await;
+        if result.is_err() {
+            let _ = BlobService::release(db, blobs, &blob.hash).await;
+        }
+        result
+    }
+
+    /// Start a resumable upload, reserving its declared size against the quota
+    pub async fn init_upload_session(
+        db: &PgPool,
+        owner_user_id: Uuid,
+        req: &InitUploadRequest,
+    ) -> Result<UploadSession, ApiError> {
+        Self::validate_content_type(&req.content_type)?;
+        Self::validate_file_size(req.total_size.max(0) as usize)?;
+        Self::validate_sha256(&req.sha256)?;
+
+        // Checks for one user run one at a time under their user row lock, so
+        // concurrent inits cannot both reserve the same remaining bytes
+        let mut tx = db.begin().await?;
+        sqlx::query("SELECT id FROM users WHERE id = $1 FOR UPDATE")
+            .bind(owner_user_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        let remaining = Self::remaining_quota(&mut tx, owner_user_id).await?;
+        Self::check_quota(0, 0, req.total_size, remaining)?;
+
+        let session = sqlx::query_as::<_, UploadSession>(
+            "INSERT INTO legacy_content_upload_sessions \
+             (owner_user_id, original_filename, content_type, description, total_size, \
+              expected_hash, expires_at) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7) \
+             RETURNING id, owner_user_id, original_filename, content_type, description, \
+                       total_size, expected_hash, received_bytes, status, content_id, \
+                       expires_at, created_at, updated_at",
+        )
+        .bind(owner_user_id)
+        .bind(&req.original_filename)
+        .bind(&req.content_type)
+        .bind(&req.description)
+        .bind(req.total_size)
+        .bind(req.sha256.to_ascii_lowercase())
+        .bind(Utc::now() + Duration::hours(UPLOAD_SESSION_TTL_HOURS))
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(session)
+    }
+
+    /// Get an upload session owned by the user
+    pub async fn get_upload_session(
+        db: &PgPool,
+        session_id: Uuid,
+        owner_user_id: Uuid,
+    ) -> Result<UploadSession, ApiError> {
+        sqlx::query_as::<_, UploadSession>(
+            "SELECT id, owner_user_id, original_filename, content_type, description, \
+                    total_size, expected_hash, received_bytes, status, content_id, \
+                    expires_at, created_at, updated_at \
+             FROM legacy_content_upload_sessions WHERE id = $1 AND owner_user_id = $2",
+        )
+        .bind(session_id)
+        .bind(owner_user_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Upload session not found".to_string()))
+    }
+
+    /// Write a chunk at `offset`, which must equal the bytes received so far.
+    ///
+    /// Anything past `offset` in the staging file (a partially written chunk
+    /// from an interrupted request) is discarded before the write.
+    pub async fn append_upload_chunk(
+        db: &PgPool,
+        storage: &FileStorageService,
+        session_id: Uuid,
+        owner_user_id: Uuid,
+        offset: i64,
+        chunk: &[u8],
+    ) -> Result<UploadSession, ApiError> {
+        if chunk.is_empty() {
+            return Err(ApiError::BadRequest("Chunk is empty".to_string()));
+        }
+        if chunk.len() > MAX_CHUNK_SIZE {
+            return Err(ApiError::BadRequest(format!(
+                "Chunk exceeds maximum size of {} bytes",
+                MAX_CHUNK_SIZE
+            )));
+        }
+
+        let mut tx = db.begin().await?;
+        let session = sqlx::query_as::<_, UploadSession>(
+            "SELECT id, owner_user_id, original_filename, content_type, description, \
+                    total_size, expected_hash, received_bytes, status, content_id, \
+                    expires_at, created_at, updated_at \
+             FROM legacy_content_upload_sessions WHERE id = $1 AND owner_user_id = $2 \
+             FOR UPDATE",
+        )
+        .bind(session_id)
+        .bind(owner_user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Upload session not found".to_string()))?;
+
+        Self::ensure_session_open(&session)?;
+        if offset != session.received_bytes {
+            return Err(ApiError::BadRequest(format!(
+                "Unexpected offset {}; resume from offset {}",
+                offset, session.received_bytes
+            )));
+        }
+        let new_received = offset + chunk.len() as i64;
+        if new_received > session.total_size {
+            return Err(ApiError::BadRequest(format!(
+                "Chunk exceeds declared size of {} bytes",
+                session.total_size
+            )));
+        }
+
+        storage
+            .write_at(&Self::staging_path(session_id), offset as u64, chunk)
+            .await?;
+
+        let session = sqlx::query_as::<_, UploadSession>(
+            "UPDATE legacy_content_upload_sessions \
+             SET received_bytes = $2, updated_at = NOW() WHERE id = $1 \
+             RETURNING id, owner_user_id, original_filename, content_type, description, \
+                       total_size, expected_hash, received_bytes, status, content_id, \
+                       expires_at, created_at, updated_at",
+        )
+        .bind(session_id)
+        .bind(new_received)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(session)
+    }
+
+    /// Verify and seal a fully received upload
+    pub async fn complete_upload_session(
+        db: &PgPool,
+        storage: &FileStorageService,
+        blobs: &dyn BlobStore,
+        keys: &dyn KeyManager,
+        session_id: Uuid,
+        owner_user_id: Uuid,
+    ) -> Result<LegacyContent, ApiError> {
+        let session = Self::get_upload_session(db, session_id, owner_user_id).await?;
+        Self::ensure_session_open(&session)?;
+        if session.received_bytes != session.total_size {
+            return Err(ApiError::BadRequest(format!(
+                "Upload incomplete: received {} of {} bytes",
+                session.received_bytes, session.total_size
+            )));
+        }
+
+        // Claim the session so concurrent completions cannot both seal it.
+        let claimed = sqlx::query(
+            "UPDATE legacy_content_upload_sessions SET status = 'finalizing', updated_at = NOW() \
+             WHERE id = $1 AND status = 'in_progress'",
+        )
+        .bind(session_id)
+        .execute(db)
+        .await?;
+        if claimed.rows_affected() == 0 {
+            return Err(ApiError::BadRequest(
+                "Upload session is already being finalized".to_string(),
+            ));
+        }
+
+        let metadata = UploadMetadata {
+            original_filename: session.original_filename.clone(),
+            content_type: session.content_type.clone(),
+            file_size: session.total_size as usize,
+            description: session.description.clone(),
+        };
+        let result = Self::finalize_staged_upload(
+            db,
+            storage,
+            blobs,
+            keys,
+            owner_user_id,
+            &metadata,
+            &Self::staging_path(session_id),
+            Some(&session.expected_hash),
+        )
+        .await;
+
+        let (status, content_id) = match &result {
+            Ok(content) => ("completed", Some(content.id)),
+            Err(_) => {
+                // A failed session cannot be resumed, so its staged bytes go
+                let _ = storage.delete_file(&Self::staging_path(session_id)).await;
+                ("failed", None)
+            }
+        };
+        sqlx::query(
+            "UPDATE legacy_content_upload_sessions \
+             SET status = $2, content_id = $3, updated_at = NOW() WHERE id = $1",
+        )
+        .bind(session_id)
+        .bind(status)
+        .bind(content_id)
+        .execute(db)
+        .await?;
+
+        result
+    }
+
+    /// Abort an upload and discard its staged bytes
+    pub async fn abort_upload_session(
+        db: &PgPool,
+        storage: &FileStorageService,
+        session_id: Uuid,
+        owner_user_id: Uuid,
+    ) -> Result<(), ApiError> {
+        let result = sqlx::query(
+            "UPDATE legacy_content_upload_sessions SET status = 'aborted', updated_at = NOW() \
+             WHERE id = $1 AND owner_user_id = $2 AND status = 'in_progress'",
+        )
+        .bind(session_id)
+        .bind(owner_user_id)
+        .execute(db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ApiError::NotFound(
+                "Active upload session not found".to_string(),
+            ));
+        }
+
+        let _ = storage.delete_file(&Self::staging_path(session_id)).await;
+        Ok(())
+    }
+
+    /// Expire abandoned upload sessions and remove their staging files
+    pub async fn expire_stale_upload_sessions(
+        db: &PgPool,
+        storage: &FileStorageService,
+    ) -> Result<u64, ApiError> {
+        let expired: Vec<Uuid> = sqlx::query_scalar(
+            "UPDATE legacy_content_upload_sessions SET status = 'expired', updated_at = NOW() \
+             WHERE status = 'in_progress' AND expires_at <= NOW() RETURNING id",
+        )
+        .fetch_all(db)
+        .await?;
+
+        for session_id in &expired {
+            let _ = storage.delete_file(&Self::staging_path(*session_id)).await;
+        }
+
+        Ok(expired.len() as u64)
+    }
+
+    fn ensure_session_open(session: &UploadSession) -> Result<(), ApiError> {
+        if session.status != "in_progress" {
+            return Err(ApiError::BadRequest(format!(
+                "Upload session is {}",
+                session.status
+            )));
+        }
+        if session.expires_at <= Utc::now() {
+            return Err(ApiError::BadRequest(
+                "Upload session has expired".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Stream the decrypted bytes of a content record
+    ///
+    /// Records sealed before the blob store was introduced are still read
+    /// from local storage.
+    pub async fn read_content(
+        db: &PgPool,
+        storage: &FileStorageService,
+        blobs: &dyn BlobStore,
+        keys: &dyn KeyManager,
+        content: &LegacyContent,
+    ) -> Result<ContentStream, ApiError> {
+        if !content.encrypted {
+            return Err(ApiError::NotFound("Content has no stored file".to_string()));
+        }
+
+        let wrapped_data_key: Option<Vec<u8>> =
+            sqlx::query_scalar("SELECT wrapped_data_key FROM legacy_content WHERE id = $1")
+                .bind(content.id)
+                .fetch_one(db)
+                .await?;
+        let key = content_key(
+            keys,
+            wrapped_data_key.as_deref(),
+            content.encryption_key_version,
+        )
+        .await?;
+
+        if content.storage_path.starts_with(BlobService::KEY_PREFIX) {
+            let sealed = blobs.open(&content.storage_path).await?;
+            open_sealed(sealed, key).await
+        } else {
+            storage.read_sealed(&content.storage_path, key).await
+        }
+    }
+
     /// List user's content
     pub async fn list_user_content(
         db: &PgPool,
@@ -160,14 +732,14 @@ impl LegacyContentService {
             sqlx::query_as!(
                 LegacyContent,
                 r#"
-                SELECT 
-                    id, owner_user_id, filename, original_filename, content_type, 
-                    file_size, storage_path, file_hash, encrypted, 
+                SELECT
+                    id, owner_user_id, filename, original_filename, content_type,
+                    file_size, storage_path, file_hash, encrypted,
                     encryption_key_version, status,
                     metadata as "metadata: serde_json::Value",
                     created_at, updated_at
                 FROM legacy_content
-                WHERE owner_user_id = $1 
+                WHERE owner_user_id = $1
                   AND status = 'active'
                   AND content_type LIKE $2
                 ORDER BY created_at DESC
@@ -184,9 +756,9 @@ impl LegacyContentService {
             sqlx::query_as!(
                 LegacyContent,
                 r#"
-                SELECT 
-                    id, owner_user_id, filename, original_filename, content_type, 
-                    file_size, storage_path, file_hash, encrypted, 
+                SELECT
+                    id, owner_user_id, filename, original_filename, content_type,
+                    file_size, storage_path, file_hash, encrypted,
                     encryption_key_version, status,
                     metadata as "metadata: serde_json::Value",
                     created_at, updated_at
@@ -215,9 +787,9 @@ impl LegacyContentService {
         let record = sqlx::query_as!(
             LegacyContent,
             r#"
-            SELECT 
-                id, owner_user_id, filename, original_filename, content_type, 
-                file_size, storage_path, file_hash, encrypted, 
+            SELECT
+                id, owner_user_id, filename, original_filename, content_type,
+                file_size, storage_path, file_hash, encrypted,
                 encryption_key_version, status,
                 metadata as "metadata: serde_json::Value",
                 created_at, updated_at
@@ -234,6 +806,17 @@ impl LegacyContentService {
         Ok(record)
     }
 
+    /// Resolve the owner of active content, for access via signed URLs
+    pub async fn get_content_owner(db: &PgPool, content_id: Uuid) -> Result<Uuid, ApiError> {
+        sqlx::query_scalar(
+            "SELECT owner_user_id FROM legacy_content WHERE id = $1 AND status = 'active'",
+        )
+        .bind(content_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Content not found".to_string()))
+    }
+
     /// Delete content (soft delete)
     pub async fn delete_content(
         db: &PgPool,
@@ -267,7 +850,7 @@ impl LegacyContentService {
         let stats = sqlx::query_as!(
             StorageStats,
             r#"
-            SELECT 
+            SELECT
                 COUNT(*)::bigint as "total_files!",
                 COALESCE(SUM(file_size), 0)::bigint as "total_size!",
                 COUNT(CASE WHEN content_type LIKE 'video/%' THEN 1 END)::bigint as "video_count!",
@@ -296,6 +879,162 @@ pub struct StorageStats {
     pub document_count: i64,
 }
 
+/// Background job that expires abandoned resumable uploads
+pub struct UploadSessionJanitor;
+
+impl UploadSessionJanitor {
+    pub fn start(db: PgPool, storage: Arc<FileStorageService>) {
+        tokio::spawn(async move {
+            // Run every hour
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(3600));
+
+            loop {
+                interval.tick().await;
+
+                match LegacyContentService::expire_stale_upload_sessions(&db, &storage).await {
+                    Ok(count) => {
+                        if count > 0 {
+                            tracing::info!("Expired {} stale legacy content uploads", count);
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!("Error expiring legacy content uploads: {}", e);
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// A fresh data key for one upload, with its wrapped form for storage
+async fn new_content_key(keys: &dyn KeyManager) -> Result<(LessSafeKey, WrappedKey), ApiError> {
+    let data_key = key_manager::generate_data_key()?;
+    let wrapped = keys.wrap(&data_key).await?;
+    Ok((key_manager::aes_key(&data_key)?, wrapped))
+}
+
+/// Key of a sealed record: its data key unwrapped by the key manager, or
+/// the key derived from `DOCUMENT_ENCRYPTION_KEY` for records sealed before
+/// their data key was wrapped
+async fn content_key(
+    keys: &dyn KeyManager,
+    wrapped_data_key: Option<&[u8]>,
+    kek_version: Option<i32>,
+) -> Result<LessSafeKey, ApiError> {
+    match (wrapped_data_key, kek_version) {
+        (Some(wrapped), Some(version)) => {
+            key_manager::aes_key(&keys.unwrap(version, wrapped).await?)
+        }
+        (Some(_), None) => Err(ApiError::Internal(anyhow::anyhow!(
+            "Content data key is incomplete"
+        ))),
+        (None, _) => key_manager::aes_key(&legacy_key_bytes()?),
+    }
+}
+
+fn segment_nonce(prefix: &[u8; NONCE_PREFIX_LEN], counter: u32) -> Nonce {
+    let mut nonce = [0u8; NONCE_LEN];
+    nonce[..NONCE_PREFIX_LEN].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_LEN..].copy_from_slice(&counter.to_be_bytes());
+    Nonce::assume_unique_for_key(nonce)
+}
+
+fn seal_segment(
+    key: &LessSafeKey,
+    prefix: &[u8; NONCE_PREFIX_LEN],
+    counter: u32,
+    last: bool,
+    in_out: &mut Vec<u8>,
+) -> Result<(), ApiError> {
+    key.seal_in_place_append_tag(
+        segment_nonce(prefix, counter),
+        Aad::from([last as u8]),
+        in_out,
+    )
+    .map_err(|_| ApiError::Internal(anyhow::anyhow!("Encryption failed")))
+}
+
+fn open_segment(
+    key: &LessSafeKey,
+    prefix: &[u8; NONCE_PREFIX_LEN],
+    counter: u32,
+    last: bool,
+    segment: &[u8],
+) -> Result<Vec<u8>, ApiError> {
+    let mut in_out = segment.to_vec();
+    let plaintext = key
+        .open_in_place(
+            segment_nonce(prefix, counter),
+            Aad::from([last as u8]),
+            &mut in_out,
+        )
+        .map_err(|_| ApiError::Internal(anyhow::anyhow!("Decryption failed")))?;
+    Ok(plaintext.to_vec())
+}
+
+/// Fill `buf` from `reader`, stopping early only at end of input
+async fn read_segment(reader: &mut BlobReader, buf: &mut Vec<u8>) -> Result<(), ApiError> {
+    buf.clear();
+    (&mut *reader)
+        .take((SEGMENT_SIZE + TAG_LEN) as u64)
+        .read_to_end(buf)
+        .await
+        .map_err(|e| ApiError::Internal(anyhow::anyhow!("Failed to read sealed file: {}", e)))?;
+    Ok(())
+}
+
+/// Decrypt a sealed file produced by [`FileStorageService::seal_file`]
+///
+/// Segments are read and authenticated one at a time, so memory stays
+/// bounded by the segment size. A segment is only known to be the last
+/// once the next read comes back empty, hence the one-segment lookahead.
+async fn open_sealed(mut sealed: BlobReader, key: LessSafeKey) -> Result<ContentStream, ApiError> {
+    let mut header = [0u8; SEALED_MAGIC.len() + NONCE_PREFIX_LEN];
+    let mut first = Vec::new();
+    let header_ok = sealed.read_exact(&mut header).await.is_ok()
+        && &header[..SEALED_MAGIC.len()] == SEALED_MAGIC;
+    if header_ok {
+        read_segment(&mut sealed, &mut first).await?;
+    }
+    if !header_ok || first.len() < TAG_LEN {
+        return Err(ApiError::Internal(anyhow::anyhow!(
+            "Stored file is not a sealed content file"
+        )));
+    }
+
+    let mut prefix = [0u8; NONCE_PREFIX_LEN];
+    prefix.copy_from_slice(&header[SEALED_MAGIC.len()..]);
+
+    let state = (sealed, key, Some(first), 0u32);
+    let stream = futures::stream::try_unfold(
+        state,
+        move |(mut sealed, key, current, counter)| async move {
+            let Some(segment) = current else {
+                return Ok(None);
+            };
+            let mut next = Vec::new();
+            read_segment(&mut sealed, &mut next).await?;
+            let last = next.is_empty();
+            let plaintext = open_segment(&key, &prefix, counter, last, &segment)?;
+            let next = (!last).then_some(next);
+            Ok(Some((
+                plaintext,
+                (sealed, key, next, counter.wrapping_add(1)),
+            )))
+        },
+    );
+    Ok(Box::pin(stream))
+}
+
+/// Result of [`FileStorageService::seal_file`]
+pub struct SealedFile {
+    pub size: u64,
+    /// Hex SHA-256 of the plaintext
+    pub plaintext_hash: String,
+    /// Hex SHA-256 of the sealed file, which addresses its blob
+    pub sealed_hash: String,
+}
+
 /// File storage handler (filesystem-based)
 pub struct FileStorageService {
     base_path: PathBuf,
@@ -306,39 +1045,166 @@ impl FileStorageService {
         Self { base_path }
     }
 
-    /// Save file to disk
-    pub async fn save_file(&self, storage_path: &str, content: &[u8]) -> Result<(), ApiError> {
+    /// Root at `LEGACY_CONTENT_STORAGE_PATH`, defaulting to `./storage`
+    pub fn from_env() -> Self {
+        let base_path = std::env::var("LEGACY_CONTENT_STORAGE_PATH")
+            .unwrap_or_else(|_| DEFAULT_STORAGE_PATH.to_string());
+        Self::new(PathBuf::from(base_path))
+    }
+
+    async fn prepare_path(&self, storage_path: &str) -> Result<PathBuf, ApiError> {
         let full_path = self.base_path.join(storage_path);
-        
+
         if let Some(parent) = full_path.parent() {
             tokio::fs::create_dir_all(parent).await.map_err(|e| {
                 ApiError::Internal(anyhow::anyhow!("Failed to create directory: {}", e))
             })?;
         }
 
-        tokio::fs::write(&full_path, content).await.map_err(|e| {
-            ApiError::Internal(anyhow::anyhow!("Failed to write file: {}", e))
-        })?;
+        Ok(full_path)
+    }
+
+    /// Save file to disk
+    pub async fn save_file(&self, storage_path: &str, content: &[u8]) -> Result<(), ApiError> {
+        let full_path = self.prepare_path(storage_path).await?;
+
+        tokio::fs::write(&full_path, content)
+            .await
+            .map_err(|e| ApiError::Internal(anyhow::anyhow!("Failed to write file: {}", e)))?;
 
         Ok(())
     }
 
+    /// Create (or truncate) a file for streaming writes
+    pub async fn create_writer(&self, storage_path: &str) -> Result<tokio::fs::File, ApiError> {
+        let full_path = self.prepare_path(storage_path).await?;
+
+        tokio::fs::File::create(&full_path)
+            .await
+            .map_err(|e| ApiError::Internal(anyhow::anyhow!("Failed to create file: {}", e)))
+    }
+
+    /// Write `content` at `offset`, discarding anything already past it
+    pub async fn write_at(
+        &self,
+        storage_path: &str,
+        offset: u64,
+        content: &[u8],
+    ) -> Result<(), ApiError> {
+        let full_path = self.prepare_path(storage_path).await?;
+        let io_err =
+            |e: std::io::Error| ApiError::Internal(anyhow::anyhow!("Failed to write file: {}", e));
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&full_path)
+            .await
+            .map_err(io_err)?;
+        file.set_len(offset).await.map_err(io_err)?;
+        file.seek(std::io::SeekFrom::Start(offset))
+            .await
+            .map_err(io_err)?;
+        file.write_all(content).await.map_err(io_err)?;
+        file.sync_data().await.map_err(io_err)?;
+
+        Ok(())
+    }
+
+    /// Encrypt `source_path` into `storage_path` segment by segment.
+    ///
+    /// The plaintext and sealed hashes are computed in the same pass.
+    pub async fn seal_file(
+        &self,
+        source_path: &str,
+        storage_path: &str,
+        key: &LessSafeKey,
+    ) -> Result<SealedFile, ApiError> {
+        let io_err =
+            |e: std::io::Error| ApiError::Internal(anyhow::anyhow!("Failed to seal file: {}", e));
+
+        let mut source = tokio::fs::File::open(self.base_path.join(source_path))
+            .await
+            .map_err(io_err)?;
+        let total = source.metadata().await.map_err(io_err)?.len();
+
+        let mut prefix = [0u8; NONCE_PREFIX_LEN];
+        SystemRandom::new()
+            .fill(&mut prefix)
+            .map_err(|_| ApiError::Internal(anyhow::anyhow!("Failed to generate nonce")))?;
+
+        let mut dest = self.create_writer(storage_path).await?;
+        dest.write_all(SEALED_MAGIC).await.map_err(io_err)?;
+        dest.write_all(&prefix).await.map_err(io_err)?;
+        let mut sealed_hasher = Sha256::new();
+        sealed_hasher.update(SEALED_MAGIC);
+        sealed_hasher.update(prefix);
+
+        let mut hasher = Sha256::new();
+        let mut remaining = total;
+        let mut counter: u32 = 0;
+        loop {
+            let len = remaining.min(SEGMENT_SIZE as u64) as usize;
+            let mut segment = vec![0u8; len];
+            source.read_exact(&mut segment).await.map_err(io_err)?;
+            hasher.update(&segment);
+            remaining -= len as u64;
+
+            let last = remaining == 0;
+            seal_segment(key, &prefix, counter, last, &mut segment)?;
+            sealed_hasher.update(&segment);
+            dest.write_all(&segment).await.map_err(io_err)?;
+
+            if last {
+                break;
+            }
+            counter = counter
+                .checked_add(1)
+                .ok_or_else(|| ApiError::Internal(anyhow::anyhow!("File too large to seal")))?;
+        }
+        dest.sync_all().await.map_err(io_err)?;
+
+        Ok(SealedFile {
+            size: total,
+            plaintext_hash: format!("{:x}", hasher.finalize()),
+            sealed_hash: format!("{:x}", sealed_hasher.finalize()),
+        })
+    }
+
+    /// Absolute location of a stored file
+    pub fn full_path(&self, storage_path: &str) -> PathBuf {
+        self.base_path.join(storage_path)
+    }
+
     /// Read file from disk
     pub async fn read_file(&self, storage_path: &str) -> Result<Vec<u8>, ApiError> {
         let full_path = self.base_path.join(storage_path);
-        
-        tokio::fs::read(&full_path).await.map_err(|e| {
-            ApiError::Internal(anyhow::anyhow!("Failed to read file: {}", e))
-        })
+
+        tokio::fs::read(&full_path)
+            .await
+            .map_err(|e| ApiError::Internal(anyhow::anyhow!("Failed to read file: {}", e)))
+    }
+
+    /// Stream the decrypted contents of a file written by [`Self::seal_file`]
+    pub async fn read_sealed(
+        &self,
+        storage_path: &str,
+        key: LessSafeKey,
+    ) -> Result<ContentStream, ApiError> {
+        let file = tokio::fs::File::open(self.base_path.join(storage_path))
+            .await
+            .map_err(|e| ApiError::Internal(anyhow::anyhow!("Failed to read file: {}", e)))?;
+        open_sealed(Box::new(file), key).await
     }
 
     /// Delete file from disk
     pub async fn delete_file(&self, storage_path: &str) -> Result<(), ApiError> {
         let full_path = self.base_path.join(storage_path);
-        
-        tokio::fs::remove_file(&full_path).await.map_err(|e| {
-            ApiError::Internal(anyhow::anyhow!("Failed to delete file: {}", e))
-        })?;
+
+        tokio::fs::remove_file(&full_path)
+            .await
+            .map_err(|e| ApiError::Internal(anyhow::anyhow!("Failed to delete file: {}", e)))?;
 
         Ok(())
     }
@@ -348,6 +1214,19 @@ impl FileStorageService {
 mod tests {
     use super::*;
 
+    fn test_storage() -> FileStorageService {
+        FileStorageService::new(std::env::temp_dir().join(format!("inheritx-{}", Uuid::new_v4())))
+    }
+
+    fn test_key(seed: u8) -> LessSafeKey {
+        key_manager::aes_key(&[seed; 32]).unwrap()
+    }
+
+    async fn collect(stream: ContentStream) -> Result<Vec<u8>, ApiError> {
+        use futures::TryStreamExt;
+        stream.try_concat().await
+    }
+
     #[test]
     fn test_validate_video_types() {
         assert!(LegacyContentService::validate_content_type("video/mp4").is_ok());
@@ -386,4 +1265,131 @@ mod tests {
         let hash = LegacyContentService::calculate_file_hash(content);
         assert_eq!(hash.len(), 64); // SHA-256 produces 64 hex characters
     }
+
+    #[test]
+    fn test_validate_sha256() {
+        let hash = LegacyContentService::calculate_file_hash(b"abc");
+        assert!(LegacyContentService::validate_sha256(&hash).is_ok());
+        assert!(LegacyContentService::validate_sha256(&hash.to_uppercase()).is_ok());
+        assert!(LegacyContentService::validate_sha256("pending").is_err());
+        assert!(LegacyContentService::validate_sha256(&"z".repeat(64)).is_err());
+    }
+
+    #[test]
+    fn test_content_disposition_escapes_filename() {
+        assert_eq!(
+            LegacyContentService::content_disposition("will.pdf"),
+            "attachment; filename=\"will.pdf\"; filename*=UTF-8''will.pdf"
+        );
+        assert_eq!(
+            LegacyContentService::content_disposition("a\"b\r\nSet-Cookie: x.txt"),
+            "attachment; filename=\"a_b__Set-Cookie: x.txt\"; \
+             filename*=UTF-8''a%22b%0D%0ASet-Cookie%3A%20x.txt"
+        );
+        assert_eq!(
+            LegacyContentService::content_disposition("résumé.pdf"),
+            "attachment; filename=\"r_sum_.pdf\"; filename*=UTF-8''r%C3%A9sum%C3%A9.pdf"
+        );
+    }
+
+    #[test]
+    fn test_check_quota() {
+        assert!(LegacyContentService::check_quota(100, 0, 900, 1000).is_ok());
+        assert!(LegacyContentService::check_quota(100, 0, 901, 1000).is_err());
+        assert!(LegacyContentService::check_quota(100, 400, 501, 1000).is_err());
+        assert!(LegacyContentService::check_quota(2000, 0, 1, 1000).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_seal_and_read_roundtrip_across_segments() {
+        let storage = test_storage();
+        let key = test_key(1);
+        let content: Vec<u8> = (0..SEGMENT_SIZE * 2 + 123).map(|i| i as u8).collect();
+
+        storage.save_file("uploads/a.part", &content).await.unwrap();
+        let sealed = storage
+            .seal_file("uploads/a.part", "sealed/a", &key)
+            .await
+            .unwrap();
+        assert_eq!(sealed.size, content.len() as u64);
+        assert_eq!(
+            sealed.plaintext_hash,
+            LegacyContentService::calculate_file_hash(&content)
+        );
+
+        let on_disk = storage.read_file("sealed/a").await.unwrap();
+        assert_eq!(
+            sealed.sealed_hash,
+            LegacyContentService::calculate_file_hash(&on_disk)
+        );
+        assert_ne!(
+            &on_disk[SEALED_MAGIC.len() + NONCE_PREFIX_LEN..][..64],
+            &content[..64]
+        );
+
+        let stream = storage.read_sealed("sealed/a", key).await.unwrap();
+        assert_eq!(collect(stream).await.unwrap(), content);
+        let stream = storage.read_sealed("sealed/a", test_key(2)).await.unwrap();
+        assert!(collect(stream).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_truncated_sealed_file_is_rejected() {
+        let storage = test_storage();
+        let key = test_key(1);
+        let content = vec![7u8; SEGMENT_SIZE * 2];
+
+        storage.save_file("uploads/b.part", &content).await.unwrap();
+        storage
+            .seal_file("uploads/b.part", "sealed/b", &key)
+            .await
+            .unwrap();
+
+        // Drop the final segment: the remaining one was not sealed as last.
+        let sealed = storage.read_file("sealed/b").await.unwrap();
+        let truncated =
+            sealed[..SEALED_MAGIC.len() + NONCE_PREFIX_LEN + SEGMENT_SIZE + TAG_LEN].to_vec();
+        let stream = open_sealed(Box::new(std::io::Cursor::new(truncated)), key)
+            .await
+            .unwrap();
+        assert!(collect(stream).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_empty_file_roundtrips() {
+        let storage = test_storage();
+        let key = test_key(1);
+
+        storage.save_file("uploads/e.part", b"").await.unwrap();
+        storage
+            .seal_file("uploads/e.part", "sealed/e", &key)
+            .await
+            .unwrap();
+        let stream = storage.read_sealed("sealed/e", key).await.unwrap();
+        assert!(collect(stream).await.unwrap().is_empty());
+        let garbage = Box::new(std::io::Cursor::new(b"not sealed".to_vec()));
+        assert!(open_sealed(garbage, test_key(1)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_write_at_discards_partial_tail() {
+        let storage = test_storage();
+
+        storage
+            .write_at("uploads/c.part", 0, b"hello")
+            .await
+            .unwrap();
+        storage
+            .write_at("uploads/c.part", 5, b" wor")
+            .await
+            .unwrap();
+        // Resume from offset 5 after an interrupted chunk.
+        storage
+            .write_at("uploads/c.part", 5, b" world")
+            .await
+            .unwrap();
+
+        let staged = storage.read_file("uploads/c.part").await.unwrap();
+        assert_eq!(staged, b"hello world");
+    }
 }
@@ -4,50 +4,76 @@ pub mod api_error;
 pub mod app;
 pub mod auth;
 pub mod beneficiary_sync;
+pub mod blob_store;
+pub mod claim_adjudication;
 pub mod compliance;
 pub mod config;
+pub mod contract_reader;
 pub mod db;
 pub mod document_storage;
 pub mod document_verification;
 pub mod emergency_access;
 pub mod emergency_access_jobs;
+pub mod emergency_principal;
 pub mod event_handlers;
 pub mod events;
 pub mod external_price_fetcher;
 pub mod governance;
 pub mod insurance_fund;
+pub mod insurance_fund_contract;
 pub mod interest_reconciliation;
+pub mod jurisdiction_rules;
+pub mod key_manager;
 pub mod legacy_content;
 pub mod lending_notification_service;
+pub mod liquidation_auction;
 pub mod loan_lifecycle;
+pub mod loan_projection;
 pub mod message_access_audit;
+pub mod message_e2e;
 pub mod middleware;
+pub mod notarization;
 pub mod notifications;
+pub mod oracle_pusher;
+pub mod pdf_layout;
 pub mod price_feed;
 pub mod price_feed_handlers;
+pub mod receipt_webhooks;
 pub mod reputation;
 pub mod risk_engine;
 pub mod safe_math;
 pub mod secure_messages;
 pub mod service;
+pub mod stellar_asset;
+pub mod stellar_price_sources;
 pub mod stress_testing;
 pub mod telemetry;
+pub mod tx_submitter;
+pub mod will_anchor;
+pub mod will_assembly;
 pub mod will_audit;
+pub mod will_bundle;
 pub mod will_compliance;
 pub mod will_events;
 pub mod will_pdf;
+pub mod will_revision;
 pub mod will_signature;
+pub mod will_template;
 pub mod will_version;
 pub mod witness;
+pub mod witness_invitation;
 pub mod yield_service;
 
 pub use api_error::ApiError;
-pub use app::create_app;
+pub use app::{
+    create_app, create_app_with_contract_reader, create_app_with_price_feed, default_price_feed,
+};
 pub use compliance::ComplianceEngine;
 pub use config::Config;
 pub use events::{EventService, EventType, LendingEvent};
 pub use governance::GovernanceService;
 pub use interest_reconciliation::InterestReconciliationService;
+pub use jurisdiction_rules::JurisdictionRuleService;
 pub use lending_notification_service::LendingNotificationService;
 pub use loan_lifecycle::{LoanLifecycleService, LoanStatus};
 pub use price_feed::{DefaultPriceFeedService, PriceFeedService, PriceFeedSource};
@@ -57,4 +83,6 @@ pub use secure_messages::{
     LegacyMessageDeliveryService, MessageEncryptionService, MessageKeyService,
 };
 pub use stress_testing::StressTestingEngine;
+pub use will_anchor::WillAnchorService;
+pub use will_template::WillTemplateService;
 pub use yield_service::{DefaultOnChainYieldService, OnChainYieldService};
@@ -0,0 +1,705 @@
+//! # Liquidation Auctions
+//!
+//! Risky plan positions are liquidated by Dutch auction rather than by seizing
+//! collateral at a fixed penalty. The collateral is offered to keepers at
+//! `start_premium_bps` of its current TWAP value, falling linearly to
+//! `floor_bps` over the auction window; each bid is priced at the TWAP of the
+//! moment, so a price move after the start cannot strand the auction. Keepers repay part or all of the
+//! debt at the current price and receive collateral in return; once the debt is
+//! covered whatever collateral is left stays with the borrower. If the
+//! collateral sells out first, the unpaid debt is recorded as a shortfall
+//! liquidation for the insurance fund to pick up.
+
+use crate::api_error::ApiError;
+use crate::events::{EventService, LiquidationMetadata};
+use crate::notifications::{
+    audit_action, entity_type, notif_type, AuditLogService, NotificationService,
+};
+use crate::price_feed::PriceFeedService;
+use chrono::{DateTime, Utc};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+const BPS: Decimal = Decimal::from_parts(10_000, 0, 0, false, 0);
+/// Scale of the NUMERIC(30, 8) amount columns
+const AMOUNT_DP: u32 = 8;
+
+// ─── Types ────────────────────────────────────────────────────────────────────
+
+/// Price curve for new auctions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuctionParams {
+    pub duration_seconds: i64,
+    pub start_premium_bps: i32,
+    pub floor_bps: i32,
+}
+
+impl Default for AuctionParams {
+    fn default() -> Self {
+        Self {
+            duration_seconds: 3600,
+            start_premium_bps: 11_000,
+            floor_bps: 8_000,
+        }
+    }
+}
+
+impl AuctionParams {
+    /// Reads `LIQUIDATION_AUCTION_DURATION_SECS`, `LIQUIDATION_AUCTION_START_BPS`
+    /// and `LIQUIDATION_AUCTION_FLOOR_BPS`, falling back to the defaults when
+    /// unset or inconsistent
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+            std::env::var(name).ok().and_then(|v| v.parse().ok())
+        }
+        let defaults = Self::default();
+        let params = Self {
+            duration_seconds: var("LIQUIDATION_AUCTION_DURATION_SECS")
+                .unwrap_or(defaults.duration_seconds),
+            start_premium_bps: var("LIQUIDATION_AUCTION_START_BPS")
+                .unwrap_or(defaults.start_premium_bps),
+            floor_bps: var("LIQUIDATION_AUCTION_FLOOR_BPS").unwrap_or(defaults.floor_bps),
+        };
+        if params.duration_seconds <= 0
+            || params.floor_bps <= 0
+            || params.floor_bps > params.start_premium_bps
+        {
+            tracing::warn!(
+                "Invalid liquidation auction settings {:?}, using defaults",
+                params
+            );
+            return defaults;
+        }
+        params
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct LiquidationAuction {
+    pub id: Uuid,
+    pub plan_id: Uuid,
+    pub user_id: Uuid,
+    pub started_by: Option<Uuid>,
+    pub collateral_asset: String,
+    pub debt_asset: String,
+    /// TWAP prices at the start or the latest bid
+    pub collateral_price: Decimal,
+    pub debt_price: Decimal,
+    pub start_premium_bps: i32,
+    pub floor_bps: i32,
+    pub duration_seconds: i64,
+    pub debt_total: Decimal,
+    pub debt_remaining: Decimal,
+    pub collateral_total: Decimal,
+    pub collateral_remaining: Decimal,
+    pub status: String,
+    pub collateral_returned: Option<Decimal>,
+    pub bad_debt: Option<Decimal>,
+    pub started_at: DateTime<Utc>,
+    pub settled_at: Option<DateTime<Utc>>,
+}
+
+impl LiquidationAuction {
+    /// Price of the collateral as bps of its current value at `now`
+    pub fn price_bps_at(&self, now: DateTime<Utc>) -> i32 {
+        price_factor_bps(
+            self.start_premium_bps,
+            self.floor_bps,
+            self.duration_seconds,
+            (now - self.started_at).num_seconds(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AuctionBid {
+    pub id: Uuid,
+    pub auction_id: Uuid,
+    pub keeper_id: Uuid,
+    pub debt_repaid: Decimal,
+    pub collateral_received: Decimal,
+    pub price_bps: i32,
+    pub transaction_hash: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A risky plan position with no auction running
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct LiquidatablePosition {
+    pub plan_id: Uuid,
+    pub user_id: Uuid,
+    pub debt_asset: String,
+    pub debt: Decimal,
+    pub collateral_asset: String,
+    pub collateral_amount: Decimal,
+    pub health_factor: Option<Decimal>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StartAuctionRequest {
+    pub plan_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PlaceBidRequest {
+    /// Most debt the keeper is willing to repay
+    pub max_debt: Decimal,
+    /// On-chain bid this records, if any
+    pub transaction_hash: Option<String>,
+}
+
+/// Debt repaid and collateral bought by one bid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fill {
+    pub debt_repaid: Decimal,
+    pub collateral_sold: Decimal,
+}
+
+// ─── Pricing ──────────────────────────────────────────────────────────────────
+
+/// Linear decay from `start_bps` to `floor_bps` over `duration_secs`, then flat
+pub fn price_factor_bps(start_bps: i32, floor_bps: i32, duration_secs: i64, elapsed: i64) -> i32 {
+    if elapsed >= duration_secs {
+        return floor_bps;
+    }
+    let elapsed = elapsed.max(0);
+    start_bps - ((start_bps - floor_bps) as i64 * elapsed / duration_secs) as i32
+}
+
+/// What a bid of up to `max_debt` buys at `price_bps`. When the collateral left
+/// is worth less than the bid, all of it is sold for what it fetches.
+pub fn fill(auction: &LiquidationAuction, max_debt: Decimal, price_bps: i32) -> Option<Fill> {
+    let factor = Decimal::from(price_bps) / BPS;
+    if max_debt <= Decimal::ZERO || factor <= Decimal::ZERO || auction.collateral_price.is_zero() {
+        return None;
+    }
+
+    let mut debt_repaid = max_debt.min(auction.debt_remaining);
+    let mut collateral_sold =
+        (debt_repaid * auction.debt_price / auction.collateral_price / factor)
+            .round_dp_with_strategy(AMOUNT_DP, RoundingStrategy::ToZero);
+    if collateral_sold > auction.collateral_remaining {
+        collateral_sold = auction.collateral_remaining;
+        debt_repaid = (collateral_sold * auction.collateral_price * factor / auction.debt_price)
+            .round_dp_with_strategy(AMOUNT_DP, RoundingStrategy::AwayFromZero)
+            .min(auction.debt_remaining);
+    }
+
+    (debt_repaid > Decimal::ZERO && collateral_sold > Decimal::ZERO).then_some(Fill {
+        debt_repaid,
+        collateral_sold,
+    })
+}
+
+// ─── Service ──────────────────────────────────────────────────────────────────
+
+pub struct LiquidationAuctionService {
+    db: PgPool,
+    price_feed: Arc<dyn PriceFeedService>,
+    params: AuctionParams,
+}
+
+impl LiquidationAuctionService {
+    pub fn new(db: PgPool, price_feed: Arc<dyn PriceFeedService>, params: AuctionParams) -> Self {
+        Self {
+            db,
+            price_feed,
+            params,
+        }
+    }
+
+    /// Risky plan positions with outstanding debt and no running auction.
+    /// Plan collateral is the plan's USDC vault, as in the risk engine.
+    pub async fn list_liquidatable(
+        &self,
+        plan_id: Option<Uuid>,
+    ) -> Result<Vec<LiquidatablePosition>, ApiError> {
+        sqlx::query_as::<_, LiquidatablePosition>(
+            r#"
+            WITH loan_balances AS (
+                SELECT plan_id, user_id, asset_code AS debt_asset,
+                       SUM(CASE WHEN event_type = 'borrow' THEN CAST(amount AS numeric) ELSE 0 END) -
+                       SUM(CASE WHEN event_type = 'repay' THEN CAST(amount AS numeric) ELSE 0 END) -
+                       SUM(CASE WHEN event_type = 'liquidation' THEN CAST(amount AS numeric) ELSE 0 END) AS debt
+                FROM lending_events
+                WHERE plan_id IS NOT NULL
+                GROUP BY plan_id, user_id, asset_code
+            )
+            SELECT lb.plan_id, lb.user_id, lb.debt_asset, lb.debt,
+                   'USDC' AS collateral_asset,
+                   COALESCE(CAST(p.net_amount AS numeric), 0) AS collateral_amount,
+                   p.health_factor
+            FROM loan_balances lb
+            JOIN plans p ON p.id = lb.plan_id
+            WHERE p.is_risky = true
+              AND p.status != 'liquidated'
+              AND lb.debt > 0
+              AND ($1::uuid IS NULL OR lb.plan_id = $1)
+              AND NOT EXISTS (
+                  SELECT 1 FROM liquidation_auctions a
+                  WHERE a.plan_id = lb.plan_id AND a.status = 'active'
+              )
+            ORDER BY p.health_factor ASC NULLS LAST
+            "#,
+        )
+        .bind(plan_id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            ApiError::Internal(anyhow::anyhow!(
+                "DB error loading liquidatable positions: {}",
+                e
+            ))
+        })
+    }
+
+    /// Open an auction for a risky plan, pricing both sides at TWAP
+    pub async fn start_auction(
+        &self,
+        plan_id: Uuid,
+        keeper_id: Option<Uuid>,
+    ) -> Result<LiquidationAuction, ApiError> {
+        let position = self
+            .list_liquidatable(Some(plan_id))
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                ApiError::BadRequest(format!(
+                    "Plan {} is not liquidatable or already has an auction",
+                    plan_id
+                ))
+            })?;
+        if position.collateral_amount <= Decimal::ZERO {
+            return Err(ApiError::BadRequest(format!(
+                "Plan {} has no collateral to auction",
+                plan_id
+            )));
+        }
+
+        let collateral_price = self
+            .price_feed
+            .get_twap_price(&position.collateral_asset)
+            .await?
+            .price;
+        let debt_price = self
+            .price_feed
+            .get_twap_price(&position.debt_asset)
+            .await?
+            .price;
+
+        let mut tx = self.db.begin().await?;
+        let auction = sqlx::query_as::<_, LiquidationAuction>(
+            r#"
+            INSERT INTO liquidation_auctions (
+                plan_id, user_id, started_by, collateral_asset, debt_asset,
+                collateral_price, debt_price, start_premium_bps, floor_bps, duration_seconds,
+                debt_total, debt_remaining, collateral_total, collateral_remaining
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $11, $12, $12)
+            ON CONFLICT (plan_id) WHERE status = 'active' DO NOTHING
+            RETURNING *
+            "#,
+        )
+        .bind(plan_id)
+        .bind(position.user_id)
+        .bind(keeper_id)
+        .bind(&position.collateral_asset)
+        .bind(&position.debt_asset)
+        .bind(collateral_price)
+        .bind(debt_price)
+        .bind(self.params.start_premium_bps)
+        .bind(self.params.floor_bps)
+        .bind(self.params.duration_seconds)
+        .bind(position.debt)
+        .bind(position.collateral_amount)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!("Plan {} already has an active auction", plan_id))
+        })?;
+
+        NotificationService::create(
+            &mut tx,
+            auction.user_id,
+            notif_type::LIQUIDATION_AUCTION_STARTED,
+            format!(
+                "Your loan against plan {} is being liquidated. {} {} of collateral is being auctioned to cover {} {} of debt; collateral left once the debt is covered stays in your plan.",
+                plan_id,
+                auction.collateral_total,
+                auction.collateral_asset,
+                auction.debt_total,
+                auction.debt_asset
+            ),
+        )
+        .await?;
+        AuditLogService::log(
+            &mut *tx,
+            keeper_id,
+            audit_action::LIQUIDATION_AUCTION_STARTED,
+            Some(auction.id),
+            Some(entity_type::LIQUIDATION_AUCTION),
+        )
+        .await?;
+        tx.commit().await?;
+
+        info!(
+            "Liquidation auction {} started for plan {}: {} {} against {} {}",
+            auction.id,
+            plan_id,
+            auction.collateral_total,
+            auction.collateral_asset,
+            auction.debt_total,
+            auction.debt_asset
+        );
+        Ok(auction)
+    }
+
+    pub async fn list_auctions(
+        &self,
+        status: Option<&str>,
+    ) -> Result<Vec<LiquidationAuction>, ApiError> {
+        Ok(sqlx::query_as::<_, LiquidationAuction>(
+            r#"
+            SELECT * FROM liquidation_auctions
+            WHERE $1::text IS NULL OR status = $1
+            ORDER BY started_at DESC
+            LIMIT 200
+            "#,
+        )
+        .bind(status)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    pub async fn get_auction(
+        &self,
+        auction_id: Uuid,
+    ) -> Result<(LiquidationAuction, Vec<AuctionBid>), ApiError> {
+        let auction = sqlx::query_as::<_, LiquidationAuction>(
+            "SELECT * FROM liquidation_auctions WHERE id = $1",
+        )
+        .bind(auction_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Auction {} not found", auction_id)))?;
+        let bids = sqlx::query_as::<_, AuctionBid>(
+            "SELECT * FROM liquidation_auction_bids WHERE auction_id = $1 ORDER BY created_at",
+        )
+        .bind(auction_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok((auction, bids))
+    }
+
+    /// Fill a bid at the current auction price, settling the auction when the
+    /// debt is covered or the collateral runs out
+    pub async fn place_bid(
+        &self,
+        auction_id: Uuid,
+        keeper_id: Uuid,
+        req: &PlaceBidRequest,
+    ) -> Result<(LiquidationAuction, AuctionBid), ApiError> {
+        let (collateral_asset, debt_asset): (String, String) = sqlx::query_as(
+            "SELECT collateral_asset, debt_asset FROM liquidation_auctions WHERE id = $1",
+        )
+        .bind(auction_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Auction {} not found", auction_id)))?;
+        let collateral_price = self
+            .price_feed
+            .get_twap_price(&collateral_asset)
+            .await?
+            .price;
+        let debt_price = self.price_feed.get_twap_price(&debt_asset).await?.price;
+
+        let mut tx = self.db.begin().await?;
+        let mut auction = sqlx::query_as::<_, LiquidationAuction>(
+            "SELECT * FROM liquidation_auctions WHERE id = $1 FOR UPDATE",
+        )
+        .bind(auction_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Auction {} not found", auction_id)))?;
+        if auction.status != "active" {
+            return Err(ApiError::BadRequest(format!(
+                "Auction {} is already settled",
+                auction_id
+            )));
+        }
+
+        auction.collateral_price = collateral_price;
+        auction.debt_price = debt_price;
+        let price_bps = auction.price_bps_at(Utc::now());
+        let Fill {
+            debt_repaid,
+            collateral_sold,
+        } = fill(&auction, req.max_debt, price_bps).ok_or_else(|| {
+            ApiError::BadRequest("Bid is too small to buy any collateral".to_string())
+        })?;
+
+        let bid = sqlx::query_as::<_, AuctionBid>(
+            r#"
+            INSERT INTO liquidation_auction_bids (
+                auction_id, keeper_id, debt_repaid, collateral_received, price_bps, transaction_hash
+            ) VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(auction_id)
+        .bind(keeper_id)
+        .bind(debt_repaid)
+        .bind(collateral_sold)
+        .bind(price_bps)
+        .bind(&req.transaction_hash)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // The keeper's gain over the debt it repaid, valued at the bid prices
+        let collateral_value = collateral_sold * auction.collateral_price / auction.debt_price;
+        EventService::emit_liquidation(
+            &mut tx,
+            auction.user_id,
+            Some(auction.plan_id),
+            &auction.debt_asset,
+            debt_repaid,
+            LiquidationMetadata {
+                liquidator_id: keeper_id,
+                collateral_asset: auction.collateral_asset.clone(),
+                collateral_seized: collateral_sold,
+                debt_covered: debt_repaid,
+                liquidation_penalty: (collateral_value - debt_repaid)
+                    .max(Decimal::ZERO)
+                    .round_dp(AMOUNT_DP),
+                auction_id: Some(auction_id),
+            },
+            req.transaction_hash.clone(),
+            None,
+        )
+        .await?;
+
+        sqlx::query("UPDATE plans SET net_amount = GREATEST(net_amount - $1, 0) WHERE id = $2")
+            .bind(collateral_sold)
+            .bind(auction.plan_id)
+            .execute(&mut *tx)
+            .await?;
+
+        auction.debt_remaining -= debt_repaid;
+        auction.collateral_remaining -= collateral_sold;
+        let settles = auction.debt_remaining.is_zero() || auction.collateral_remaining.is_zero();
+        auction = sqlx::query_as::<_, LiquidationAuction>(
+            r#"
+            UPDATE liquidation_auctions
+            SET debt_remaining = $1, collateral_remaining = $2,
+                collateral_price = $3, debt_price = $4
+            WHERE id = $5
+            RETURNING *
+            "#,
+        )
+        .bind(auction.debt_remaining)
+        .bind(auction.collateral_remaining)
+        .bind(auction.collateral_price)
+        .bind(auction.debt_price)
+        .bind(auction_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        AuditLogService::log(
+            &mut *tx,
+            Some(keeper_id),
+            audit_action::LIQUIDATION_AUCTION_BID,
+            Some(auction_id),
+            Some(entity_type::LIQUIDATION_AUCTION),
+        )
+        .await?;
+
+        if settles {
+            auction = Self::settle(&mut tx, &auction).await?;
+        }
+        tx.commit().await?;
+
+        info!(
+            "Auction {} bid by {}: {} {} repaid for {} {} at {} bps",
+            auction_id,
+            keeper_id,
+            debt_repaid,
+            auction.debt_asset,
+            collateral_sold,
+            auction.collateral_asset,
+            price_bps
+        );
+        Ok((auction, bid))
+    }
+
+    /// Close a finished auction. Leftover collateral stays in the plan; unpaid
+    /// debt is booked as a liquidation with nothing seized, which the insurance
+    /// fund files as a shortfall claim.
+    async fn settle(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        auction: &LiquidationAuction,
+    ) -> Result<LiquidationAuction, ApiError> {
+        let bad_debt = auction.debt_remaining;
+        if bad_debt > Decimal::ZERO {
+            EventService::emit_liquidation(
+                tx,
+                auction.user_id,
+                Some(auction.plan_id),
+                &auction.debt_asset,
+                bad_debt,
+                LiquidationMetadata {
+                    liquidator_id: Uuid::nil(),
+                    collateral_asset: auction.collateral_asset.clone(),
+                    collateral_seized: Decimal::ZERO,
+                    debt_covered: bad_debt,
+                    liquidation_penalty: Decimal::ZERO,
+                    auction_id: Some(auction.id),
+                },
+                None,
+                None,
+            )
+            .await?;
+        }
+
+        // The plan is only liquidated once its collateral is gone
+        sqlx::query(
+            r#"
+            UPDATE plans
+            SET is_risky = false,
+                risk_flagged_at = NULL,
+                status = CASE WHEN $1 THEN 'liquidated' ELSE status END
+            WHERE id = $2
+            "#,
+        )
+        .bind(auction.collateral_remaining.is_zero())
+        .bind(auction.plan_id)
+        .execute(&mut **tx)
+        .await?;
+
+        let settled = sqlx::query_as::<_, LiquidationAuction>(
+            r#"
+            UPDATE liquidation_auctions
+            SET status = 'settled', collateral_returned = collateral_remaining,
+                bad_debt = $1, settled_at = NOW()
+            WHERE id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(bad_debt)
+        .bind(auction.id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        NotificationService::create(
+            tx,
+            auction.user_id,
+            notif_type::LIQUIDATION_AUCTION_SETTLED,
+            format!(
+                "The liquidation auction for plan {} has ended. {} {} of debt was repaid from {} {} of collateral; {} {} remains in your plan.",
+                auction.plan_id,
+                auction.debt_total - bad_debt,
+                auction.debt_asset,
+                auction.collateral_total - auction.collateral_remaining,
+                auction.collateral_asset,
+                auction.collateral_remaining,
+                auction.collateral_asset
+            ),
+        )
+        .await?;
+        AuditLogService::log(
+            &mut **tx,
+            Some(auction.user_id),
+            audit_action::LIQUIDATION_AUCTION_SETTLED,
+            Some(auction.id),
+            Some(entity_type::LIQUIDATION_AUCTION),
+        )
+        .await?;
+
+        info!(
+            "Auction {} settled: {} {} returned, {} {} bad debt",
+            auction.id,
+            auction.collateral_remaining,
+            auction.collateral_asset,
+            bad_debt,
+            auction.debt_asset
+        );
+        Ok(settled)
+    }
+}
+
+// ─── Unit Tests ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn auction(collateral_price: Decimal) -> LiquidationAuction {
+        LiquidationAuction {
+            id: Uuid::new_v4(),
+            plan_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            started_by: None,
+            collateral_asset: "USDC".to_string(),
+            debt_asset: "XLM".to_string(),
+            collateral_price,
+            debt_price: dec!(1),
+            start_premium_bps: 10_000,
+            floor_bps: 5_000,
+            duration_seconds: 1_000,
+            debt_total: dec!(1000),
+            debt_remaining: dec!(1000),
+            collateral_total: dec!(1000),
+            collateral_remaining: dec!(1000),
+            status: "active".to_string(),
+            collateral_returned: None,
+            bad_debt: None,
+            started_at: Utc::now(),
+            settled_at: None,
+        }
+    }
+
+    #[test]
+    fn price_decays_linearly_to_floor() {
+        assert_eq!(price_factor_bps(11_000, 8_000, 3_600, 0), 11_000);
+        assert_eq!(price_factor_bps(11_000, 8_000, 3_600, 1_800), 9_500);
+        assert_eq!(price_factor_bps(11_000, 8_000, 3_600, 3_600), 8_000);
+        assert_eq!(price_factor_bps(11_000, 8_000, 3_600, 86_400), 8_000);
+        assert_eq!(price_factor_bps(11_000, 8_000, 3_600, -5), 11_000);
+    }
+
+    #[test]
+    fn partial_fill_leaves_collateral_for_the_borrower() {
+        // Collateral at 1.2: 300 of debt buys 250 at full price, 500 at half
+        let auction = auction(dec!(1.2));
+        assert_eq!(
+            fill(&auction, dec!(300), 10_000),
+            Some(Fill {
+                debt_repaid: dec!(300),
+                collateral_sold: dec!(250),
+            })
+        );
+        assert_eq!(
+            fill(&auction, dec!(300), 5_000).unwrap().collateral_sold,
+            dec!(500)
+        );
+
+        // Bids above the debt only repay what is owed
+        let all = fill(&auction, dec!(5000), 10_000).unwrap();
+        assert_eq!(all.debt_repaid, dec!(1000));
+        assert_eq!(all.collateral_sold, dec!(833.33333333));
+        assert!(fill(&auction, Decimal::ZERO, 10_000).is_none());
+    }
+
+    #[test]
+    fn underwater_collateral_sells_out_short_of_the_debt() {
+        // 1000 collateral at 0.5 and a 5000 bps floor fetches 250
+        let auction = auction(dec!(0.5));
+        let fill = fill(&auction, dec!(1000), 5_000).unwrap();
+        assert_eq!(fill.collateral_sold, dec!(1000));
+        assert_eq!(fill.debt_repaid, dec!(250));
+    }
+}
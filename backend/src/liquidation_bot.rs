@@ -59,7 +59,7 @@ impl LiquidationBotService {
                 GROUP BY plan_id, user_id, asset_code
             )
             SELECT lb.plan_id, lb.user_id, lb.borrow_asset, lb.total_debt,
-                   p.asset_code as collateral_asset, CAST(p.net_amount AS numeric) as collateral_amount
+                   'USDC' as collateral_asset, CAST(p.net_amount AS numeric) as collateral_amount
             FROM loan_balances lb
             JOIN plans p ON p.id = lb.plan_id
             WHERE p.is_risky = true AND p.status != 'liquidated' AND lb.total_debt > 0
@@ -105,6 +105,7 @@ impl LiquidationBotService {
                 collateral_seized,
                 debt_covered: debt_to_cover,
                 liquidation_penalty: penalty_amount,
+                auction_id: None,
             };
 
             EventService::emit_liquidation(
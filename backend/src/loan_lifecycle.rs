@@ -23,6 +23,7 @@
 
 use crate::api_error::ApiError;
 use crate::notifications::{audit_action, entity_type, AuditLogService};
+use crate::reputation::ReputationService;
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
@@ -162,7 +163,8 @@ pub struct CreateLoanRequest {
     pub collateral_asset: String,
     /// Loan principal in the borrow asset's native units.
     pub principal: Decimal,
-    /// Annual interest rate expressed in basis-points (e.g. 800 = 8 %).
+    /// Requested annual interest rate in basis-points (e.g. 800 = 8 %), before
+    /// the borrower's reputation adjustment.
     pub interest_rate_bps: i32,
     pub collateral_amount: Decimal,
     /// ISO-8601 datetime when the loan is due.
@@ -334,6 +336,9 @@ impl LoanLifecycleService {
     // ── Write operations ──────────────────────────────────────────────────────
 
     /// Open a new loan in the `active` state.
+    ///
+    /// The borrower's reputation tier adjusts the requested interest rate and
+    /// caps the loan-to-value ratio (see [`crate::reputation`]).
     pub async fn create_loan(
         pool: &PgPool,
         req: &CreateLoanRequest,
@@ -379,6 +384,69 @@ impl LoanLifecycleService {
             }
         }
 
+        // Stale-price circuit breaker: no new borrows against an asset whose
+        // price has not refreshed within its feed's staleness limit
+        let paused_assets: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT asset_code FROM price_feeds
+            WHERE asset_code IN ($1, $2) AND borrows_paused_at IS NOT NULL
+            ORDER BY asset_code
+            "#,
+        )
+        .bind(&req.borrow_asset)
+        .bind(&req.collateral_asset)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        if !paused_assets.is_empty() {
+            return Err(ApiError::BadRequest(format!(
+                "New borrows are paused while the price of {} is stale",
+                paused_assets.join(", ")
+            )));
+        }
+
+        // Borrower reputation sets the rate adjustment and the LTV ceiling
+        let reputation = ReputationService::assess(&mut tx, req.user_id).await?;
+        let terms = reputation.tier.terms();
+        let interest_rate_bps = (req.interest_rate_bps + terms.rate_adjustment_bps).max(0);
+
+        // LTV is checked once both assets have a recorded price
+        let prices: Vec<(String, Decimal)> = sqlx::query_as(
+            r#"
+            SELECT DISTINCT ON (asset_code) asset_code, price
+            FROM asset_price_history
+            WHERE asset_code IN ($1, $2)
+            ORDER BY asset_code, price_timestamp DESC
+            "#,
+        )
+        .bind(&req.borrow_asset)
+        .bind(&req.collateral_asset)
+        .fetch_all(&mut *tx)
+        .await?;
+        let price_of = |asset: &str| {
+            prices
+                .iter()
+                .find(|(code, _)| code == asset)
+                .map(|(_, price)| *price)
+        };
+        if let (Some(borrow_price), Some(collateral_price)) =
+            (price_of(&req.borrow_asset), price_of(&req.collateral_asset))
+        {
+            let collateral_value = req.collateral_amount * collateral_price;
+            let max_ltv = crate::reputation::max_ltv(&req.collateral_asset, reputation.tier);
+            if collateral_value <= Decimal::ZERO
+                || req.principal * borrow_price / collateral_value > max_ltv
+            {
+                return Err(ApiError::BadRequest(format!(
+                    "Loan exceeds the {} LTV allowed against {} for a {} borrower (score {})",
+                    max_ltv,
+                    req.collateral_asset,
+                    reputation.tier.as_str(),
+                    reputation.score
+                )));
+            }
+        }
+
         let row = sqlx::query_as::<_, LoanLifecycleRow>(
             r#"
             INSERT INTO loan_lifecycle (
@@ -398,7 +466,7 @@ impl LoanLifecycleService {
         .bind(&req.borrow_asset)
         .bind(&req.collateral_asset)
         .bind(req.principal)
-        .bind(req.interest_rate_bps)
+        .bind(interest_rate_bps)
         .bind(req.collateral_amount)
         .bind(req.due_date)
         .bind(&req.transaction_hash)
@@ -0,0 +1,518 @@
+//! # Loan Projections
+//!
+//! Amortization schedules and collateral risk projections for loan
+//! simulations. A schedule lists every payment of a bullet, equal-installment
+//! or interest-only loan; a projection replays collateral price paths over the
+//! loan term — deterministic volatility shocks, the worst stretch of recorded
+//! history and Monte Carlo runs — against the debt outstanding each day to
+//! estimate how likely the loan is to be liquidated before it is due.
+
+use crate::api_error::ApiError;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::str::FromStr;
+
+/// Days of price history used to estimate volatility
+const HISTORY_LOOKBACK_DAYS: i32 = 365;
+/// Fewer daily closes than this are not enough to estimate volatility
+const MIN_DAILY_CLOSES: usize = 10;
+/// Deterministic shocks, in standard deviations over the loan term
+const SIGMA_SHOCKS: [u32; 3] = [1, 2, 3];
+
+pub const DEFAULT_INSTALLMENT_INTERVAL_DAYS: u32 = 30;
+pub const DEFAULT_MONTE_CARLO_RUNS: u32 = 1_000;
+pub const MAX_MONTE_CARLO_RUNS: u32 = 10_000;
+
+// ─── Amortization ─────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepaymentSchedule {
+    /// Principal and all interest at maturity
+    Bullet,
+    /// Equal payments of principal and interest every interval
+    EqualInstallment,
+    /// Interest every interval, principal at maturity
+    InterestOnly,
+}
+
+impl RepaymentSchedule {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RepaymentSchedule::Bullet => "bullet",
+            RepaymentSchedule::EqualInstallment => "equal_installment",
+            RepaymentSchedule::InterestOnly => "interest_only",
+        }
+    }
+}
+
+impl FromStr for RepaymentSchedule {
+    type Err = ApiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "bullet" => Ok(RepaymentSchedule::Bullet),
+            "equal_installment" | "amortizing" => Ok(RepaymentSchedule::EqualInstallment),
+            "interest_only" => Ok(RepaymentSchedule::InterestOnly),
+            _ => Err(ApiError::BadRequest(
+                "repayment_schedule must be bullet, equal_installment or interest_only".to_string(),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledPayment {
+    pub number: u32,
+    /// Days after the loan starts
+    pub day: u32,
+    pub payment: Decimal,
+    pub principal: Decimal,
+    pub interest: Decimal,
+    /// Principal still owed after this payment
+    pub remaining_balance: Decimal,
+    /// Collateral price below which the remaining balance is liquidatable
+    pub liquidation_price: Option<Decimal>,
+}
+
+/// Payment plan for `principal` over `duration_days`, paying every
+/// `interval_days` (bullet loans ignore the interval). Interest accrues
+/// simply on the outstanding principal at `annual_rate` / 365 per day.
+pub fn amortization_schedule(
+    kind: RepaymentSchedule,
+    principal: Decimal,
+    annual_rate: Decimal,
+    duration_days: u32,
+    interval_days: u32,
+) -> Vec<ScheduledPayment> {
+    let days_per_year = Decimal::from(365);
+    let interval_days = match kind {
+        RepaymentSchedule::Bullet => duration_days,
+        _ => interval_days.clamp(1, duration_days),
+    };
+    let periods = duration_days.div_ceil(interval_days);
+
+    // Level payment over full-length periods; the last payment clears the rest
+    let level_payment = (kind == RepaymentSchedule::EqualInstallment).then(|| {
+        let rate = annual_rate * Decimal::from(interval_days) / days_per_year;
+        if rate.is_zero() {
+            return principal / Decimal::from(periods);
+        }
+        let growth = (0..periods).fold(Decimal::ONE, |acc, _| acc * (Decimal::ONE + rate));
+        principal * rate * growth / (growth - Decimal::ONE)
+    });
+
+    let mut balance = principal;
+    let mut previous_day = 0;
+    let mut schedule = Vec::with_capacity(periods as usize);
+    for number in 1..=periods {
+        let day = (number * interval_days).min(duration_days);
+        let interest =
+            (balance * annual_rate * Decimal::from(day - previous_day) / days_per_year).round_dp(8);
+        let principal_paid = if number == periods {
+            balance
+        } else {
+            match level_payment {
+                Some(payment) => (payment - interest)
+                    .round_dp(8)
+                    .clamp(Decimal::ZERO, balance),
+                None => Decimal::ZERO,
+            }
+        };
+        balance -= principal_paid;
+        schedule.push(ScheduledPayment {
+            number,
+            day,
+            payment: principal_paid + interest,
+            principal: principal_paid,
+            interest,
+            remaining_balance: balance,
+            liquidation_price: None,
+        });
+        previous_day = day;
+    }
+    schedule
+}
+
+/// Amount owed on each day `0..=duration_days`, just before that day's
+/// payment: outstanding principal plus interest accrued since the last payment
+pub fn debt_by_day(
+    schedule: &[ScheduledPayment],
+    principal: Decimal,
+    annual_rate: Decimal,
+    duration_days: u32,
+) -> Vec<f64> {
+    let principal = principal.to_f64().unwrap_or(0.0);
+    let daily_rate = annual_rate.to_f64().unwrap_or(0.0) / 365.0;
+    let mut payments = schedule.iter().peekable();
+    let mut balance = principal;
+    let mut last_payment_day = 0;
+    (0..=duration_days)
+        .map(|day| {
+            let owed = balance * (1.0 + daily_rate * (day - last_payment_day) as f64);
+            if let Some(payment) = payments.next_if(|p| p.day == day) {
+                balance = payment.remaining_balance.to_f64().unwrap_or(0.0);
+                last_payment_day = day;
+            }
+            owed
+        })
+        .collect()
+}
+
+// ─── Price History ────────────────────────────────────────────────────────────
+
+/// Volatility and worst run of an asset's recent daily closes
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceHistoryStats {
+    pub daily_closes: usize,
+    pub daily_volatility: f64,
+    /// Daily log returns, oldest first
+    pub returns: Vec<f64>,
+}
+
+impl PriceHistoryStats {
+    pub fn from_closes(closes: &[Decimal]) -> Option<Self> {
+        if closes.len() < MIN_DAILY_CLOSES {
+            return None;
+        }
+        let returns: Vec<f64> = closes
+            .windows(2)
+            .filter_map(|w| {
+                let (from, to) = (w[0].to_f64()?, w[1].to_f64()?);
+                (from > 0.0 && to > 0.0).then(|| (to / from).ln())
+            })
+            .collect();
+        if returns.len() < 2 {
+            return None;
+        }
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        let variance =
+            returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+        Some(Self {
+            daily_closes: closes.len(),
+            daily_volatility: variance.sqrt(),
+            returns,
+        })
+    }
+
+    /// The consecutive daily returns of length `days` (or all of history, if
+    /// shorter) with the lowest cumulative return
+    pub fn worst_window(&self, days: usize) -> &[f64] {
+        let len = days.clamp(1, self.returns.len());
+        self.returns
+            .windows(len)
+            .min_by(|a, b| a.iter().sum::<f64>().total_cmp(&b.iter().sum::<f64>()))
+            .unwrap_or(&[])
+    }
+}
+
+/// Last recorded price of each day over the lookback window, oldest first
+pub async fn load_daily_closes(db: &PgPool, asset_code: &str) -> Result<Vec<Decimal>, ApiError> {
+    let rows: Vec<(Decimal,)> = sqlx::query_as(
+        r#"
+        SELECT price FROM (
+            SELECT DISTINCT ON (date_trunc('day', price_timestamp))
+                   date_trunc('day', price_timestamp) AS day, price
+            FROM asset_price_history
+            WHERE asset_code = $1
+              AND price_timestamp > NOW() - make_interval(days => $2)
+            ORDER BY date_trunc('day', price_timestamp), price_timestamp DESC
+        ) closes
+        ORDER BY day
+        "#,
+    )
+    .bind(asset_code)
+    .bind(HISTORY_LOOKBACK_DAYS)
+    .fetch_all(db)
+    .await?;
+    Ok(rows.into_iter().map(|(price,)| price).collect())
+}
+
+// ─── Projection ───────────────────────────────────────────────────────────────
+
+/// What the collateral and debt look like for a projection
+pub struct ProjectionInputs<'a> {
+    pub collateral_quantity: Decimal,
+    pub start_price: Decimal,
+    pub liquidation_threshold: Decimal,
+    /// From [`debt_by_day`]
+    pub debt_by_day: &'a [f64],
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioOutcome {
+    pub scenario: String,
+    pub terminal_price: Decimal,
+    pub min_health_factor: Decimal,
+    /// First day the health factor fell below 1, if it did
+    pub liquidation_day: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonteCarloSummary {
+    pub runs: u32,
+    pub seed: u64,
+    /// Share of runs liquidated before the loan was repaid
+    pub liquidation_probability: Decimal,
+    pub terminal_price_p5: Decimal,
+    pub terminal_price_median: Decimal,
+    pub terminal_price_p95: Decimal,
+    /// Fifth percentile of each run's lowest health factor
+    pub min_health_factor_p5: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskProjection {
+    pub asset_code: String,
+    pub daily_closes: usize,
+    pub daily_volatility: Decimal,
+    pub scenarios: Vec<ScenarioOutcome>,
+    pub monte_carlo: MonteCarloSummary,
+}
+
+/// Lowest health factor along a daily price path (`path[d]` is the price on
+/// day `d`) and the first day it fell below 1
+fn walk_path(inputs: &ProjectionInputs, path: impl Iterator<Item = f64>) -> (f64, Option<u32>) {
+    let collateral = inputs.collateral_quantity.to_f64().unwrap_or(0.0)
+        * inputs.liquidation_threshold.to_f64().unwrap_or(0.0);
+    let mut min_health = f64::INFINITY;
+    let mut liquidation_day = None;
+    for (day, (price, debt)) in path.zip(inputs.debt_by_day).enumerate() {
+        if *debt <= 0.0 {
+            continue;
+        }
+        let health = collateral * price / debt;
+        min_health = min_health.min(health);
+        if health < 1.0 && liquidation_day.is_none() {
+            liquidation_day = Some(day as u32);
+        }
+    }
+    (min_health, liquidation_day)
+}
+
+fn to_decimal(value: f64) -> Decimal {
+    Decimal::from_f64(value)
+        .unwrap_or(Decimal::ZERO)
+        .round_dp(8)
+}
+
+/// Replay `returns` (one per day, holding flat once they run out) from the
+/// start price
+fn scenario(inputs: &ProjectionInputs, name: String, returns: &[f64]) -> ScenarioOutcome {
+    let start = inputs.start_price.to_f64().unwrap_or(0.0);
+    let days = inputs.debt_by_day.len();
+    let path: Vec<f64> = std::iter::once(start)
+        .chain(
+            returns
+                .iter()
+                .chain(std::iter::repeat(&0.0))
+                .scan(start, |price, r| {
+                    *price *= r.exp();
+                    Some(*price)
+                }),
+        )
+        .take(days)
+        .collect();
+    let (min_health, liquidation_day) = walk_path(inputs, path.iter().copied());
+    ScenarioOutcome {
+        scenario: name,
+        terminal_price: to_decimal(*path.last().unwrap_or(&start)),
+        min_health_factor: to_decimal(min_health),
+        liquidation_day,
+    }
+}
+
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    sorted[((sorted.len() - 1) as f64 * p).round() as usize]
+}
+
+/// Shock scenarios and `runs` zero-drift geometric Brownian motion paths at
+/// the historical daily volatility
+pub fn project(
+    inputs: &ProjectionInputs,
+    asset_code: &str,
+    history: &PriceHistoryStats,
+    runs: u32,
+    seed: u64,
+) -> RiskProjection {
+    let sigma = history.daily_volatility;
+    let days = inputs.debt_by_day.len().saturating_sub(1);
+
+    // k-sigma moves over the whole term, spread evenly across its days
+    let mut scenarios: Vec<ScenarioOutcome> = SIGMA_SHOCKS
+        .iter()
+        .map(|k| {
+            let daily = -(*k as f64) * sigma / (days.max(1) as f64).sqrt();
+            scenario(inputs, format!("{}_sigma_drop", k), &vec![daily; days])
+        })
+        .collect();
+    scenarios.push(scenario(
+        inputs,
+        "historical_worst_window".to_string(),
+        history.worst_window(days),
+    ));
+
+    let start = inputs.start_price.to_f64().unwrap_or(0.0);
+    let drift = -sigma * sigma / 2.0;
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut terminal_prices = Vec::with_capacity(runs as usize);
+    let mut min_healths = Vec::with_capacity(runs as usize);
+    let mut liquidated = 0u32;
+    for _ in 0..runs {
+        let mut price = start;
+        let path = std::iter::once(start).chain((0..days).map(|_| {
+            price *= (drift + sigma * standard_normal(&mut rng)).exp();
+            price
+        }));
+        let mut last = start;
+        let (min_health, liquidation_day) = walk_path(inputs, path.inspect(|p| last = *p));
+        terminal_prices.push(last);
+        min_healths.push(min_health);
+        if liquidation_day.is_some() {
+            liquidated += 1;
+        }
+    }
+    terminal_prices.sort_by(f64::total_cmp);
+    min_healths.sort_by(f64::total_cmp);
+
+    RiskProjection {
+        asset_code: asset_code.to_string(),
+        daily_closes: history.daily_closes,
+        daily_volatility: to_decimal(sigma),
+        scenarios,
+        monte_carlo: MonteCarloSummary {
+            runs,
+            seed,
+            liquidation_probability: (Decimal::from(liquidated) / Decimal::from(runs.max(1)))
+                .round_dp(4),
+            terminal_price_p5: to_decimal(percentile(&terminal_prices, 0.05)),
+            terminal_price_median: to_decimal(percentile(&terminal_prices, 0.5)),
+            terminal_price_p95: to_decimal(percentile(&terminal_prices, 0.95)),
+            min_health_factor_p5: to_decimal(percentile(&min_healths, 0.05)),
+        },
+    }
+}
+
+/// Box-Muller transform
+fn standard_normal(rng: &mut StdRng) -> f64 {
+    let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+// ─── Unit Tests ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn total(schedule: &[ScheduledPayment], f: fn(&ScheduledPayment) -> Decimal) -> Decimal {
+        schedule.iter().map(f).sum()
+    }
+
+    #[test]
+    fn bullet_pays_everything_at_maturity() {
+        let schedule =
+            amortization_schedule(RepaymentSchedule::Bullet, dec!(1000), dec!(0.073), 100, 30);
+        assert_eq!(schedule.len(), 1);
+        assert_eq!(schedule[0].day, 100);
+        assert_eq!(schedule[0].interest, dec!(20));
+        assert_eq!(schedule[0].payment, dec!(1020));
+        assert_eq!(schedule[0].remaining_balance, Decimal::ZERO);
+    }
+
+    #[test]
+    fn interest_only_defers_principal() {
+        let schedule = amortization_schedule(
+            RepaymentSchedule::InterestOnly,
+            dec!(1000),
+            dec!(0.073),
+            90,
+            30,
+        );
+        assert_eq!(schedule.len(), 3);
+        assert!(schedule[..2].iter().all(|p| p.principal.is_zero()));
+        assert!(schedule.iter().all(|p| p.interest == dec!(6)));
+        assert_eq!(schedule[2].principal, dec!(1000));
+    }
+
+    #[test]
+    fn equal_installments_amortize_to_zero() {
+        let schedule = amortization_schedule(
+            RepaymentSchedule::EqualInstallment,
+            dec!(12000),
+            dec!(0.12),
+            365,
+            30,
+        );
+        // 12 full periods and a 5-day stub
+        assert_eq!(schedule.len(), 13);
+        assert_eq!(schedule.last().unwrap().remaining_balance, Decimal::ZERO);
+        assert_eq!(total(&schedule, |p| p.principal), dec!(12000));
+        let first = schedule[0].payment;
+        assert!(schedule[1..12]
+            .iter()
+            .all(|p| (p.payment - first).abs() < dec!(0.0001)));
+        // Amortizing costs less interest than holding the full balance
+        let bullet =
+            amortization_schedule(RepaymentSchedule::Bullet, dec!(12000), dec!(0.12), 365, 30);
+        assert!(total(&schedule, |p| p.interest) < bullet[0].interest);
+    }
+
+    #[test]
+    fn debt_accrues_between_payments() {
+        let schedule = amortization_schedule(
+            RepaymentSchedule::InterestOnly,
+            dec!(1000),
+            dec!(0.365),
+            20,
+            10,
+        );
+        let debt = debt_by_day(&schedule, dec!(1000), dec!(0.365), 20);
+        assert_eq!(debt.len(), 21);
+        assert!((debt[10] - 1010.0).abs() < 1e-9);
+        assert!((debt[11] - 1001.0).abs() < 1e-9);
+        assert!((debt[20] - 1010.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn projection_flags_liquidations_under_shocks() {
+        // Collateral of 2000 at a 0.8 threshold backs 1000 of debt: health 1.6
+        let debt = vec![1000.0; 31];
+        let inputs = ProjectionInputs {
+            collateral_quantity: dec!(2000),
+            start_price: dec!(1),
+            liquidation_threshold: dec!(0.8),
+            debt_by_day: &debt,
+        };
+        let closes: Vec<Decimal> = (0..40)
+            .map(|i| if i % 2 == 0 { dec!(1.0) } else { dec!(0.9) })
+            .collect();
+        let history = PriceHistoryStats::from_closes(&closes).unwrap();
+        assert!(history.daily_volatility > 0.1);
+
+        let projection = project(&inputs, "XLM", &history, 500, 7);
+        assert_eq!(projection.scenarios[0].scenario, "1_sigma_drop");
+        assert!(projection.scenarios[0].min_health_factor < dec!(1.6));
+        // A 3-sigma drop halves the price and liquidates the loan
+        assert!(projection.scenarios[2].liquidation_day.is_some());
+        let probability = projection.monte_carlo.liquidation_probability;
+        assert!(probability > Decimal::ZERO && probability < Decimal::ONE);
+
+        // The same seed reproduces the same runs
+        assert_eq!(project(&inputs, "XLM", &history, 500, 7), projection);
+    }
+
+    #[test]
+    fn short_history_gives_no_stats() {
+        assert!(PriceHistoryStats::from_closes(&[dec!(1); 5]).is_none());
+    }
+}
@@ -1,5 +1,6 @@
 use inheritx_backend::{
-    create_app, db, telemetry, Config, LegacyMessageDeliveryService, MessageKeyService,
+    create_app_with_price_feed, db, default_price_feed, telemetry, Config, JurisdictionRuleService,
+    LegacyMessageDeliveryService, MessageKeyService, WillTemplateService,
 };
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -22,8 +23,30 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Ensure there is always one active message encryption key.
     MessageKeyService::ensure_active_key(&db_pool).await?;
 
-    // Create application
-    let app = create_app(db_pool.clone(), config.clone()).await?;
+    // Seed the bundled will templates for any key and locale not yet stored.
+    WillTemplateService::ensure_builtin_templates(&db_pool).await?;
+
+    // Seed the bundled jurisdiction rules for any code not yet stored.
+    JurisdictionRuleService::ensure_builtin_rules(&db_pool).await?;
+
+    // Create application around a price feed shared with the price workers
+    let price_feed = default_price_feed(&db_pool).await;
+    let app =
+        create_app_with_price_feed(db_pool.clone(), config.clone(), price_feed.clone()).await?;
+
+    // Refresh feeds as they fall due.
+    Arc::new(inheritx_backend::price_feed::PriceRefreshScheduler::new(
+        price_feed.clone(),
+    ))
+    .start();
+
+    // Push aggregated prices to the on-chain oracle when a target is configured.
+    match inheritx_backend::oracle_pusher::OraclePusher::from_env(db_pool.clone(), price_feed).await
+    {
+        Ok(Some(pusher)) => Arc::new(pusher).start(),
+        Ok(None) => info!("Price oracle push target not configured"),
+        Err(e) => tracing::warn!("Oracle price pusher disabled: {}", e),
+    }
 
     let compliance_engine = std::sync::Arc::new(inheritx_backend::ComplianceEngine::new(
         db_pool.clone(),
@@ -48,11 +71,55 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
     lending_notification_service.start();
 
+    // Email and SMS go through the provider configured by ALERT_PROVIDER.
+    let alert_provider = inheritx_backend::alert_provider::alert_provider_from_env()?;
+
     // Start legacy message delivery worker.
-    let legacy_message_delivery_service =
-        Arc::new(LegacyMessageDeliveryService::new(db_pool.clone()));
+    let legacy_message_delivery_service = Arc::new(LegacyMessageDeliveryService::new(
+        db_pool.clone(),
+        alert_provider.clone(),
+    ));
     legacy_message_delivery_service.start();
 
+    // Send witness invitations and reminders, and expire unanswered ones.
+    let witness_invitation_service = Arc::new(
+        inheritx_backend::witness_invitation::WitnessInvitationService::from_env(
+            db_pool.clone(),
+            alert_provider,
+        )?,
+    );
+    witness_invitation_service.start();
+
+    // Move encrypted will documents onto the active key-encryption key.
+    let document_key_rotation = Arc::new(
+        inheritx_backend::document_storage::DocumentKeyRotationService::new(
+            db_pool.clone(),
+            inheritx_backend::blob_store::blob_store_from_env()?,
+            inheritx_backend::key_manager::key_manager_from_env()?,
+        ),
+    );
+    document_key_rotation.start();
+
+    // Expire abandoned resumable legacy content uploads.
+    inheritx_backend::legacy_content::UploadSessionJanitor::start(
+        db_pool.clone(),
+        Arc::new(inheritx_backend::legacy_content::FileStorageService::from_env()),
+    );
+
+    // Mirror will lifecycle steps on-chain once the contract is configured.
+    match inheritx_backend::tx_submitter::SorobanInvoker::from_env()? {
+        Some(invoker) => {
+            let will_anchor_service = Arc::new(inheritx_backend::WillAnchorService::new(
+                db_pool.clone(),
+                Arc::new(invoker),
+                inheritx_backend::tx_submitter::signer_from_env()?,
+                inheritx_backend::contract_reader::contract_reader_from_env()?,
+            ));
+            will_anchor_service.start();
+        }
+        None => info!("Soroban RPC not configured; will anchoring calls stay queued"),
+    }
+
     // Start server
     let addr = SocketAddr::from(([0, 0, 0, 0], config.port));
     info!("Starting INHERITX backend server on {}", addr);
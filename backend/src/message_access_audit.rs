@@ -19,6 +19,7 @@ pub enum MessageAccessAction {
     Decrypted,
     Delivered,
     DeliveryFailed,
+    DeliveryBounced,
     KeyRotated,
     KeyListed,
     Deleted,
@@ -32,6 +33,7 @@ impl MessageAccessAction {
             Self::Decrypted => "decrypted",
             Self::Delivered => "delivered",
             Self::DeliveryFailed => "delivery_failed",
+            Self::DeliveryBounced => "delivery_bounced",
             Self::KeyRotated => "key_rotated",
             Self::KeyListed => "key_listed",
             Self::Deleted => "deleted",
@@ -370,6 +372,7 @@ mod tests {
             (MessageAccessAction::Decrypted, "decrypted"),
             (MessageAccessAction::Delivered, "delivered"),
             (MessageAccessAction::DeliveryFailed, "delivery_failed"),
+            (MessageAccessAction::DeliveryBounced, "delivery_bounced"),
             (MessageAccessAction::KeyRotated, "key_rotated"),
             (MessageAccessAction::KeyListed, "key_listed"),
             (MessageAccessAction::Deleted, "deleted"),
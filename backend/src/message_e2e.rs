@@ -0,0 +1,598 @@
+//! End-to-end encrypted legacy messages.
+//!
+//! The owner's client encrypts the message with a random content key and
+//! wraps that key for each recipient: an ephemeral X25519 key agreement with
+//! the recipient's public key, HKDF-SHA256 over the shared secret (salt =
+//! ephemeral public key || recipient public key, info = [`E2E_ALGORITHM`]),
+//! then AES-256-GCM. The server only stores ciphertext and wrapped keys and
+//! can never read these messages.
+//!
+//! The owner's wallet anchors the ciphertext hash on-chain with
+//! `create_legacy_message` before the message is stored here. The anchor is
+//! checked when the message is created and again whenever it is handed to a
+//! recipient, so the server cannot swap the ciphertext unnoticed.
+//!
+//! Recipient keys come either from a key the beneficiary registered, or from
+//! their Stellar account: the Ed25519 account key maps to an X25519 key whose
+//! secret the wallet can derive from its seed.
+
+use crate::api_error::ApiError;
+use crate::contract_reader::{InheritanceContractReader, OnChainLegacyMessage};
+use crate::secure_messages::{
+    DeliveryChannel, LegacyMessage, MessageEncryptionService, MESSAGE_COLUMNS,
+};
+use base64::Engine as _;
+use chrono::{DateTime, Utc};
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use stellar_strkey::Strkey;
+use uuid::Uuid;
+
+/// Algorithm label stored with each message and used as the HKDF info
+pub const E2E_ALGORITHM: &str = "x25519-hkdf-sha256-aes-256-gcm";
+
+const PUBLIC_KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+/// A 32-byte content key plus the 16-byte GCM tag
+const WRAPPED_KEY_LEN: usize = 48;
+const MAX_RECIPIENTS: usize = 16;
+
+/// Derive the X25519 public key matching a Stellar `G…` account address
+pub fn x25519_from_stellar(address: &str) -> Result<[u8; 32], ApiError> {
+    let ed25519 = match Strkey::from_string(address) {
+        Ok(Strkey::PublicKeyEd25519(pk)) => pk.0,
+        _ => {
+            return Err(ApiError::BadRequest(
+                "Invalid Stellar account address".to_string(),
+            ))
+        }
+    };
+    let point = CompressedEdwardsY(ed25519)
+        .decompress()
+        .ok_or_else(|| ApiError::BadRequest("Invalid Stellar account key".to_string()))?;
+    Ok(point.to_montgomery().to_bytes())
+}
+
+fn b64() -> base64::engine::GeneralPurpose {
+    base64::engine::general_purpose::STANDARD
+}
+
+fn decode_b64(field: &str, value: &str, expected_len: Option<usize>) -> Result<Vec<u8>, ApiError> {
+    let bytes = b64()
+        .decode(value)
+        .map_err(|_| ApiError::BadRequest(format!("{} must be valid base64", field)))?;
+    if let Some(len) = expected_len {
+        if bytes.len() != len {
+            return Err(ApiError::BadRequest(format!(
+                "{} must be {} bytes",
+                field, len
+            )));
+        }
+    }
+    Ok(bytes)
+}
+
+/// A recipient's published X25519 public key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecipientPublicKey {
+    /// Base64 X25519 public key
+    pub x25519_public_key: String,
+    /// `registered` or `stellar_account`
+    pub source: String,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RegisterRecipientKeyRequest {
+    pub x25519_public_key: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RecipientKeyQuery {
+    pub contact: Option<String>,
+    pub stellar_address: Option<String>,
+}
+
+pub struct RecipientKeyService;
+
+impl RecipientKeyService {
+    /// Publish (or replace) the caller's X25519 public key
+    pub async fn register(
+        db: &PgPool,
+        user_id: Uuid,
+        req: &RegisterRecipientKeyRequest,
+    ) -> Result<RecipientPublicKey, ApiError> {
+        let key = decode_b64(
+            "x25519_public_key",
+            &req.x25519_public_key,
+            Some(PUBLIC_KEY_LEN),
+        )?;
+        if key.iter().all(|b| *b == 0) {
+            return Err(ApiError::BadRequest(
+                "x25519_public_key must not be all zeroes".to_string(),
+            ));
+        }
+
+        let updated_at: DateTime<Utc> = sqlx::query_scalar(
+            "INSERT INTO message_recipient_public_keys (user_id, x25519_public_key) \
+             VALUES ($1, $2) \
+             ON CONFLICT (user_id) DO UPDATE \
+             SET x25519_public_key = EXCLUDED.x25519_public_key, updated_at = NOW() \
+             RETURNING updated_at",
+        )
+        .bind(user_id)
+        .bind(&key)
+        .fetch_one(db)
+        .await?;
+
+        Ok(RecipientPublicKey {
+            x25519_public_key: b64().encode(&key),
+            source: "registered".to_string(),
+            updated_at: Some(updated_at),
+        })
+    }
+
+    /// Find the key to encrypt to, by account email or Stellar address.
+    ///
+    /// Only beneficiaries on the owner's own plans can be looked up, so the
+    /// endpoint cannot be used to map emails to wallets. A registered key
+    /// takes precedence over one derived from a Stellar account, since the
+    /// beneficiary chose it explicitly.
+    pub async fn lookup(
+        db: &PgPool,
+        owner_user_id: Uuid,
+        query: &RecipientKeyQuery,
+    ) -> Result<RecipientPublicKey, ApiError> {
+        #[derive(sqlx::FromRow)]
+        struct Row {
+            x25519_public_key: Vec<u8>,
+            updated_at: DateTime<Utc>,
+        }
+
+        match (&query.contact, &query.stellar_address) {
+            (Some(contact), None) => {
+                let row = sqlx::query_as::<_, Row>(
+                    "SELECT k.x25519_public_key, k.updated_at \
+                     FROM message_recipient_public_keys k \
+                     JOIN users u ON u.id = k.user_id \
+                     WHERE LOWER(u.email) = LOWER($1) \
+                       AND EXISTS (SELECT 1 FROM plan_beneficiaries b \
+                                   JOIN plans p ON p.id = b.plan_id \
+                                   WHERE p.user_id = $2 AND b.wallet_address = u.wallet_address)",
+                )
+                .bind(contact.trim())
+                .bind(owner_user_id)
+                .fetch_optional(db)
+                .await?
+                .ok_or_else(|| {
+                    ApiError::NotFound(
+                        "No public key registered for a beneficiary with this contact".to_string(),
+                    )
+                })?;
+                Ok(RecipientPublicKey {
+                    x25519_public_key: b64().encode(&row.x25519_public_key),
+                    source: "registered".to_string(),
+                    updated_at: Some(row.updated_at),
+                })
+            }
+            (None, Some(address)) => {
+                let is_beneficiary: bool = sqlx::query_scalar(
+                    "SELECT EXISTS (SELECT 1 FROM plan_beneficiaries b \
+                                    JOIN plans p ON p.id = b.plan_id \
+                                    WHERE p.user_id = $1 AND b.wallet_address = $2)",
+                )
+                .bind(owner_user_id)
+                .bind(address.trim())
+                .fetch_one(db)
+                .await?;
+                if !is_beneficiary {
+                    return Err(ApiError::NotFound(
+                        "No beneficiary with this Stellar address on your plans".to_string(),
+                    ));
+                }
+
+                let registered = sqlx::query_as::<_, Row>(
+                    "SELECT k.x25519_public_key, k.updated_at \
+                     FROM message_recipient_public_keys k \
+                     JOIN users u ON u.id = k.user_id \
+                     WHERE u.wallet_address = $1",
+                )
+                .bind(address.trim())
+                .fetch_optional(db)
+                .await?;
+                if let Some(row) = registered {
+                    return Ok(RecipientPublicKey {
+                        x25519_public_key: b64().encode(&row.x25519_public_key),
+                        source: "registered".to_string(),
+                        updated_at: Some(row.updated_at),
+                    });
+                }
+                Ok(RecipientPublicKey {
+                    x25519_public_key: b64().encode(x25519_from_stellar(address.trim())?),
+                    source: "stellar_account".to_string(),
+                    updated_at: None,
+                })
+            }
+            _ => Err(ApiError::BadRequest(
+                "Provide exactly one of contact or stellar_address".to_string(),
+            )),
+        }
+    }
+}
+
+/// The content key wrapped for one recipient (all fields base64)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WrappedRecipientKey {
+    pub recipient_public_key: String,
+    pub ephemeral_public_key: String,
+    pub wrapped_key: String,
+    pub wrap_nonce: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateE2eMessageRequest {
+    pub vault_id: Option<i64>,
+    pub beneficiary_contact: String,
+    pub unlock_at: DateTime<Utc>,
+    /// Base64 AES-256-GCM ciphertext of the message
+    pub ciphertext: String,
+    /// Base64 12-byte nonce for `ciphertext`
+    pub nonce: String,
+    /// Hex SHA-256 of the raw ciphertext, as anchored on-chain
+    pub content_hash: String,
+    /// Id `create_legacy_message` returned when the owner's wallet anchored
+    /// `content_hash` under `vault_id`
+    pub on_chain_message_id: u64,
+    pub recipients: Vec<WrappedRecipientKey>,
+}
+
+struct DecodedRecipient {
+    recipient_public_key: Vec<u8>,
+    ephemeral_public_key: Vec<u8>,
+    wrapped_key: Vec<u8>,
+    wrap_nonce: Vec<u8>,
+}
+
+struct DecodedE2eMessage {
+    vault_id: u64,
+    ciphertext: Vec<u8>,
+    nonce: Vec<u8>,
+    content_hash: String,
+    recipients: Vec<DecodedRecipient>,
+}
+
+impl CreateE2eMessageRequest {
+    fn decode(&self) -> Result<DecodedE2eMessage, ApiError> {
+        let vault_id = self
+            .vault_id
+            .and_then(|id| u64::try_from(id).ok())
+            .ok_or_else(|| {
+                ApiError::BadRequest(
+                    "vault_id of the plan the message is anchored under is required".to_string(),
+                )
+            })?;
+        let ciphertext = decode_b64("ciphertext", &self.ciphertext, None)?;
+        if ciphertext.len() <= 16 {
+            return Err(ApiError::BadRequest("ciphertext is too short".to_string()));
+        }
+        let nonce = decode_b64("nonce", &self.nonce, Some(NONCE_LEN))?;
+
+        let content_hash = self.content_hash.trim().to_ascii_lowercase();
+        if content_hash != hex::encode(Sha256::digest(&ciphertext)) {
+            return Err(ApiError::BadRequest(
+                "content_hash does not match ciphertext".to_string(),
+            ));
+        }
+
+        if self.recipients.is_empty() || self.recipients.len() > MAX_RECIPIENTS {
+            return Err(ApiError::BadRequest(format!(
+                "Between 1 and {} recipients are required",
+                MAX_RECIPIENTS
+            )));
+        }
+        let mut recipients = Vec::with_capacity(self.recipients.len());
+        for recipient in &self.recipients {
+            let decoded = DecodedRecipient {
+                recipient_public_key: decode_b64(
+                    "recipient_public_key",
+                    &recipient.recipient_public_key,
+                    Some(PUBLIC_KEY_LEN),
+                )?,
+                ephemeral_public_key: decode_b64(
+                    "ephemeral_public_key",
+                    &recipient.ephemeral_public_key,
+                    Some(PUBLIC_KEY_LEN),
+                )?,
+                wrapped_key: decode_b64(
+                    "wrapped_key",
+                    &recipient.wrapped_key,
+                    Some(WRAPPED_KEY_LEN),
+                )?,
+                wrap_nonce: decode_b64("wrap_nonce", &recipient.wrap_nonce, Some(NONCE_LEN))?,
+            };
+            if recipients
+                .iter()
+                .any(|r: &DecodedRecipient| r.recipient_public_key == decoded.recipient_public_key)
+            {
+                return Err(ApiError::BadRequest(
+                    "Duplicate recipient_public_key".to_string(),
+                ));
+            }
+            recipients.push(decoded);
+        }
+
+        Ok(DecodedE2eMessage {
+            vault_id,
+            ciphertext,
+            nonce,
+            content_hash,
+            recipients,
+        })
+    }
+}
+
+/// Check that on-chain message metadata anchors `content_hash` under
+/// `vault_id`, returning why not otherwise
+fn check_anchor(
+    anchor: Option<&OnChainLegacyMessage>,
+    vault_id: u64,
+    content_hash: &str,
+) -> Result<(), String> {
+    let anchor = anchor.ok_or("the message is not anchored on-chain")?;
+    if anchor.vault_id != vault_id {
+        return Err(format!(
+            "the on-chain message belongs to vault {}",
+            anchor.vault_id
+        ));
+    }
+    if !anchor.message_hash.eq_ignore_ascii_case(content_hash) {
+        return Err("the on-chain message_hash does not match content_hash".to_string());
+    }
+    Ok(())
+}
+
+/// What a beneficiary's client needs to open an end-to-end message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct E2eEnvelope {
+    pub algorithm: String,
+    pub ciphertext: String,
+    pub nonce: String,
+    pub content_hash: String,
+    /// On-chain message whose `message_hash` was checked against
+    /// `content_hash`; `None` for messages stored before anchoring was
+    /// required, whose hash has not been checked
+    pub on_chain_message_id: Option<u64>,
+    pub recipients: Vec<WrappedRecipientKey>,
+}
+
+impl E2eEnvelope {
+    pub(crate) async fn load(
+        db: &PgPool,
+        reader: &dyn InheritanceContractReader,
+        message_id: Uuid,
+        ciphertext: Vec<u8>,
+        nonce: Vec<u8>,
+    ) -> Result<Self, ApiError> {
+        #[derive(sqlx::FromRow)]
+        struct Row {
+            recipient_public_key: Vec<u8>,
+            ephemeral_public_key: Vec<u8>,
+            wrapped_key: Vec<u8>,
+            wrap_nonce: Vec<u8>,
+        }
+
+        let (algorithm, vault_id, on_chain_message_id): (Option<String>, Option<i64>, Option<i64>) =
+            sqlx::query_as(
+                "SELECT e2e_algorithm, vault_id, on_chain_message_id \
+                 FROM legacy_messages WHERE id = $1",
+            )
+            .bind(message_id)
+            .fetch_one(db)
+            .await?;
+
+        let content_hash = hex::encode(Sha256::digest(&ciphertext));
+        let on_chain_message_id = on_chain_message_id.map(|id| id as u64);
+        if let Some(id) = on_chain_message_id {
+            let anchor = reader.get_legacy_message(id).await?;
+            let vault_id = vault_id.unwrap_or_default() as u64;
+            check_anchor(anchor.as_ref(), vault_id, &content_hash).map_err(|reason| {
+                ApiError::Internal(anyhow::anyhow!(
+                    "Legacy message {} failed its on-chain check: {}",
+                    message_id,
+                    reason
+                ))
+            })?;
+        }
+
+        let rows = sqlx::query_as::<_, Row>(
+            "SELECT recipient_public_key, ephemeral_public_key, wrapped_key, wrap_nonce \
+             FROM legacy_message_recipient_keys WHERE message_id = $1 ORDER BY created_at",
+        )
+        .bind(message_id)
+        .fetch_all(db)
+        .await?;
+
+        Ok(Self {
+            algorithm: algorithm.unwrap_or_else(|| E2E_ALGORITHM.to_string()),
+            content_hash,
+            on_chain_message_id,
+            ciphertext: b64().encode(&ciphertext),
+            nonce: b64().encode(&nonce),
+            recipients: rows
+                .into_iter()
+                .map(|row| WrappedRecipientKey {
+                    recipient_public_key: b64().encode(row.recipient_public_key),
+                    ephemeral_public_key: b64().encode(row.ephemeral_public_key),
+                    wrapped_key: b64().encode(row.wrapped_key),
+                    wrap_nonce: b64().encode(row.wrap_nonce),
+                })
+                .collect(),
+        })
+    }
+}
+
+pub struct E2eMessageService;
+
+impl E2eMessageService {
+    /// Store a client-encrypted message once its hash is anchored on-chain
+    /// by the owner's wallet. It is always delivered as a claim link, since
+    /// the server cannot produce the plaintext for email or SMS.
+    pub async fn create(
+        db: &PgPool,
+        reader: &dyn InheritanceContractReader,
+        owner_user_id: Uuid,
+        req: &CreateE2eMessageRequest,
+    ) -> Result<LegacyMessage, ApiError> {
+        MessageEncryptionService::validate_new_message(req.unlock_at, &req.beneficiary_contact)?;
+        let decoded = req.decode()?;
+
+        let on_chain_message_id = i64::try_from(req.on_chain_message_id)
+            .map_err(|_| ApiError::BadRequest("Invalid on_chain_message_id".to_string()))?;
+        let anchor = reader.get_legacy_message(req.on_chain_message_id).await?;
+        check_anchor(anchor.as_ref(), decoded.vault_id, &decoded.content_hash)
+            .map_err(|reason| ApiError::BadRequest(format!("Cannot store message: {}", reason)))?;
+        let owner_wallet: Option<String> =
+            sqlx::query_scalar("SELECT wallet_address FROM users WHERE id = $1")
+                .bind(owner_user_id)
+                .fetch_optional(db)
+                .await?
+                .flatten();
+        if anchor.map(|a| a.creator) != owner_wallet {
+            return Err(ApiError::Forbidden(
+                "The on-chain message was not created by your wallet".to_string(),
+            ));
+        }
+
+        let mut tx = db.begin().await?;
+
+        let message = sqlx::query_as::<_, LegacyMessage>(&format!(
+            "INSERT INTO legacy_messages \
+             (owner_user_id, vault_id, beneficiary_contact, encrypted_payload, payload_nonce, \
+              key_version, unlock_at, status, delivery_channel, encryption_mode, content_hash, \
+              e2e_algorithm, on_chain_message_id) \
+             VALUES ($1, $2, $3, $4, $5, NULL, $6, 'pending', $7, 'e2e', $8, $9, $10) \
+             RETURNING {}",
+            MESSAGE_COLUMNS
+        ))
+        .bind(owner_user_id)
+        .bind(req.vault_id)
+        .bind(req.beneficiary_contact.trim())
+        .bind(&decoded.ciphertext)
+        .bind(&decoded.nonce)
+        .bind(req.unlock_at)
+        .bind(DeliveryChannel::ClaimLink.as_str())
+        .bind(&decoded.content_hash)
+        .bind(E2E_ALGORITHM)
+        .bind(on_chain_message_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        for recipient in &decoded.recipients {
+            sqlx::query(
+                "INSERT INTO legacy_message_recipient_keys \
+                 (message_id, recipient_public_key, ephemeral_public_key, wrapped_key, wrap_nonce) \
+                 VALUES ($1, $2, $3, $4, $5)",
+            )
+            .bind(message.id)
+            .bind(&recipient.recipient_public_key)
+            .bind(&recipient.ephemeral_public_key)
+            .bind(&recipient.wrapped_key)
+            .bind(&recipient.wrap_nonce)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use curve25519_dalek::constants::ED25519_BASEPOINT_POINT;
+    use curve25519_dalek::montgomery::MontgomeryPoint;
+    use curve25519_dalek::scalar::Scalar;
+
+    fn scalar(seed: u8) -> Scalar {
+        Scalar::from_bytes_mod_order([seed; 32])
+    }
+
+    #[test]
+    fn stellar_address_maps_to_matching_x25519_key() {
+        let secret = scalar(7);
+        let ed25519 = (secret * ED25519_BASEPOINT_POINT).compress().to_bytes();
+        let address = stellar_strkey::ed25519::PublicKey(ed25519).to_string();
+
+        let derived = x25519_from_stellar(&address).unwrap();
+        assert_eq!(derived, MontgomeryPoint::mul_base(&secret).to_bytes());
+    }
+
+    #[test]
+    fn non_account_strkeys_are_rejected() {
+        assert!(x25519_from_stellar("not-an-address").is_err());
+        let contract = stellar_strkey::Contract([1u8; 32]).to_string();
+        assert!(x25519_from_stellar(&contract).is_err());
+    }
+
+    fn request(ciphertext: &[u8]) -> CreateE2eMessageRequest {
+        CreateE2eMessageRequest {
+            vault_id: Some(9),
+            beneficiary_contact: "heir@example.com".to_string(),
+            unlock_at: Utc::now() + chrono::Duration::days(1),
+            ciphertext: b64().encode(ciphertext),
+            nonce: b64().encode([0u8; 12]),
+            content_hash: hex::encode(Sha256::digest(ciphertext)),
+            on_chain_message_id: 4,
+            recipients: vec![WrappedRecipientKey {
+                recipient_public_key: b64().encode([1u8; 32]),
+                ephemeral_public_key: b64().encode([2u8; 32]),
+                wrapped_key: b64().encode([3u8; 48]),
+                wrap_nonce: b64().encode([4u8; 12]),
+            }],
+        }
+    }
+
+    #[test]
+    fn e2e_request_validation() {
+        let ciphertext = [9u8; 40];
+        assert!(request(&ciphertext).decode().is_ok());
+
+        let mut bad_hash = request(&ciphertext);
+        bad_hash.content_hash = hex::encode([0u8; 32]);
+        assert!(bad_hash.decode().is_err());
+
+        let mut no_vault = request(&ciphertext);
+        no_vault.vault_id = None;
+        assert!(no_vault.decode().is_err());
+
+        let mut no_recipients = request(&ciphertext);
+        no_recipients.recipients.clear();
+        assert!(no_recipients.decode().is_err());
+
+        let mut short_key = request(&ciphertext);
+        short_key.recipients[0].wrapped_key = b64().encode([3u8; 32]);
+        assert!(short_key.decode().is_err());
+
+        let mut duplicate = request(&ciphertext);
+        duplicate.recipients.push(duplicate.recipients[0].clone());
+        assert!(duplicate.decode().is_err());
+    }
+
+    #[test]
+    fn anchor_must_hold_the_content_hash_under_the_vault() {
+        let hash = hex::encode(Sha256::digest(b"ciphertext"));
+        let anchor = OnChainLegacyMessage {
+            message_id: 4,
+            vault_id: 9,
+            message_hash: hash.to_ascii_uppercase(),
+            creator: "GOWNER".to_string(),
+        };
+        assert!(check_anchor(Some(&anchor), 9, &hash).is_ok());
+        assert!(check_anchor(None, 9, &hash).is_err());
+        assert!(check_anchor(Some(&anchor), 10, &hash).is_err());
+        assert!(check_anchor(Some(&anchor), 9, &hex::encode([0u8; 32])).is_err());
+    }
+}
@@ -0,0 +1,863 @@
+//! Notaries and remote online notarization of will documents.
+//!
+//! Jurisdictions with `require_notarization` need a commissioned notary to
+//! seal the will. An owner requests notarization of a document; a notary in
+//! that jurisdiction claims and schedules the request, runs an online session
+//! in which the signer's identity is checked, and then seals the document by
+//! signing its hash with their Ed25519 key. Each step is recorded in the
+//! notary journal in the same transaction as the state change. Notaries
+//! cannot handle requests for their own wills.
+
+use crate::api_error::ApiError;
+use crate::app::AppState;
+use crate::auth::AuthenticatedUser;
+use crate::will_signature::WillSignatureService;
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::{PgConnection, PgPool};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// How early a notary may open a session before its scheduled time
+pub const SESSION_EARLY_START_MINUTES: i64 = 15;
+
+// ─── Types ────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Notary {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub full_name: String,
+    pub commission_number: String,
+    pub jurisdiction: String,
+    pub public_key: String,
+    pub commission_expires_at: DateTime<Utc>,
+    pub status: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct NotarizationRequest {
+    pub id: Uuid,
+    pub document_id: Uuid,
+    pub plan_id: Uuid,
+    pub user_id: Uuid,
+    pub notary_id: Option<Uuid>,
+    pub jurisdiction: String,
+    pub document_hash: String,
+    pub status: String,
+    pub notes: Option<String>,
+    pub scheduled_for: Option<DateTime<Utc>>,
+    pub session_url: Option<String>,
+    pub identity_method: Option<String>,
+    pub session_started_at: Option<DateTime<Utc>>,
+    pub seal_signature_hex: Option<String>,
+    pub sealed_at: Option<DateTime<Utc>>,
+    pub rejection_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct NotaryJournalEntry {
+    pub id: Uuid,
+    pub request_id: Uuid,
+    pub document_id: Uuid,
+    pub plan_id: Uuid,
+    pub notary_id: Option<Uuid>,
+    pub action: String,
+    pub details: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotarizationStatus {
+    Requested,
+    Scheduled,
+    InSession,
+    Notarized,
+    Rejected,
+}
+
+impl NotarizationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Requested => "requested",
+            Self::Scheduled => "scheduled",
+            Self::InSession => "in_session",
+            Self::Notarized => "notarized",
+            Self::Rejected => "rejected",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, ApiError> {
+        match s {
+            "requested" => Ok(Self::Requested),
+            "scheduled" => Ok(Self::Scheduled),
+            "in_session" => Ok(Self::InSession),
+            "notarized" => Ok(Self::Notarized),
+            "rejected" => Ok(Self::Rejected),
+            other => Err(ApiError::BadRequest(format!(
+                "Invalid notarization status: {other}"
+            ))),
+        }
+    }
+
+    /// Whether a request may move from `self` to `next`. Requests can be
+    /// rescheduled until the session starts, and rejected at any open stage.
+    pub fn can_transition_to(&self, next: Self) -> bool {
+        use NotarizationStatus::*;
+        matches!(
+            (self, next),
+            (Requested, Scheduled)
+                | (Scheduled, Scheduled)
+                | (Scheduled, InSession)
+                | (InSession, Notarized)
+                | (Requested | Scheduled | InSession, Rejected)
+        )
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateNotaryRequest {
+    pub user_id: Uuid,
+    pub full_name: String,
+    pub commission_number: String,
+    pub jurisdiction: String,
+    pub public_key: String,
+    pub commission_expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateNotaryStatusRequest {
+    pub status: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RequestNotarizationRequest {
+    /// Defaults to the jurisdiction stated in the will
+    pub jurisdiction: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NotaryQueueQuery {
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScheduleSessionRequest {
+    pub scheduled_for: DateTime<Utc>,
+    pub session_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StartSessionRequest {
+    /// How the signer's identity was checked, e.g. `kba` or `credential_analysis`
+    pub identity_method: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SealRequest {
+    pub signature_hex: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RejectNotarizationRequest {
+    pub reason: String,
+}
+
+/// Message a notary signs to seal a document
+pub fn seal_message(document_hash: &str, request_id: Uuid, commission_number: &str) -> String {
+    format!("INHERITX_NOTARY_SEAL:{document_hash}:{request_id}:{commission_number}")
+}
+
+/// Whether a notary may open the session for a request scheduled at `scheduled_for`
+pub fn can_start_session(scheduled_for: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+    now >= scheduled_for - Duration::minutes(SESSION_EARLY_START_MINUTES)
+}
+
+// ─── Notary principal ─────────────────────────────────────────────────────────
+
+/// A signed-in user holding an active, unexpired notary commission
+pub struct AuthenticatedNotary(pub Notary);
+
+#[async_trait::async_trait]
+impl FromRequestParts<Arc<AppState>> for AuthenticatedNotary {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let AuthenticatedUser(claims) = AuthenticatedUser::from_request_parts(parts, state).await?;
+        let notary = sqlx::query_as::<_, Notary>("SELECT * FROM notaries WHERE user_id = $1")
+            .bind(claims.user_id)
+            .fetch_optional(&state.db)
+            .await?
+            .ok_or_else(|| ApiError::Forbidden("Not a registered notary".to_string()))?;
+
+        if notary.status != "active" {
+            return Err(ApiError::Forbidden(
+                "Notary commission is suspended".to_string(),
+            ));
+        }
+        if notary.commission_expires_at <= Utc::now() {
+            return Err(ApiError::Forbidden(
+                "Notary commission has expired".to_string(),
+            ));
+        }
+        Ok(Self(notary))
+    }
+}
+
+// ─── Service ──────────────────────────────────────────────────────────────────
+
+pub struct NotarizationService;
+
+impl NotarizationService {
+    // ── Notary registry (admin) ──
+
+    pub async fn create_notary(
+        db: &PgPool,
+        admin_id: Uuid,
+        req: &CreateNotaryRequest,
+    ) -> Result<Notary, ApiError> {
+        if req.full_name.trim().is_empty() || req.commission_number.trim().is_empty() {
+            return Err(ApiError::BadRequest(
+                "full_name and commission_number are required".to_string(),
+            ));
+        }
+        if req.commission_expires_at <= Utc::now() {
+            return Err(ApiError::BadRequest(
+                "Commission has already expired".to_string(),
+            ));
+        }
+        WillSignatureService::decode_public_key(&req.public_key)?;
+
+        let notary = sqlx::query_as::<_, Notary>(
+            r#"
+            INSERT INTO notaries
+                (user_id, full_name, commission_number, jurisdiction, public_key,
+                 commission_expires_at, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(req.user_id)
+        .bind(req.full_name.trim())
+        .bind(req.commission_number.trim())
+        .bind(req.jurisdiction.trim().to_uppercase())
+        .bind(req.public_key.trim())
+        .bind(req.commission_expires_at)
+        .bind(admin_id)
+        .fetch_one(db)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref d) if d.is_unique_violation() => ApiError::BadRequest(
+                "User is already a notary, or the commission number is taken".to_string(),
+            ),
+            other => other.into(),
+        })?;
+
+        Ok(notary)
+    }
+
+    pub async fn list_notaries(db: &PgPool) -> Result<Vec<Notary>, ApiError> {
+        let notaries =
+            sqlx::query_as::<_, Notary>("SELECT * FROM notaries ORDER BY created_at DESC")
+                .fetch_all(db)
+                .await?;
+        Ok(notaries)
+    }
+
+    pub async fn set_notary_status(
+        db: &PgPool,
+        notary_id: Uuid,
+        status: &str,
+    ) -> Result<Notary, ApiError> {
+        if !matches!(status, "active" | "suspended") {
+            return Err(ApiError::BadRequest(
+                "status must be 'active' or 'suspended'".to_string(),
+            ));
+        }
+        sqlx::query_as::<_, Notary>(
+            "UPDATE notaries SET status = $2, updated_at = NOW() WHERE id = $1 RETURNING *",
+        )
+        .bind(notary_id)
+        .bind(status)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Notary {notary_id} not found")))
+    }
+
+    // ── Owner side ──
+
+    /// Open a notarization request for one of the owner's will documents
+    pub async fn request_notarization(
+        db: &PgPool,
+        user_id: Uuid,
+        document_id: Uuid,
+        req: &RequestNotarizationRequest,
+    ) -> Result<NotarizationRequest, ApiError> {
+        #[derive(sqlx::FromRow)]
+        struct Doc {
+            plan_id: Uuid,
+            status: String,
+            will_hash: String,
+            jurisdiction: Option<String>,
+        }
+
+        let doc = sqlx::query_as::<_, Doc>(
+            "SELECT plan_id, status, will_hash, terms->'clauses'->>'jurisdiction' AS jurisdiction \
+             FROM will_documents WHERE id = $1 AND user_id = $2",
+        )
+        .bind(document_id)
+        .bind(user_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Will document {document_id} not found")))?;
+
+        if doc.status == "revoked" {
+            return Err(ApiError::BadRequest(
+                "Will document has been revoked".to_string(),
+            ));
+        }
+
+        let jurisdiction = req
+            .jurisdiction
+            .as_deref()
+            .or(doc.jurisdiction.as_deref())
+            .map(|j| j.trim().to_uppercase())
+            .filter(|j| !j.is_empty())
+            .ok_or_else(|| {
+                ApiError::BadRequest(
+                    "jurisdiction is required when the will does not state one".to_string(),
+                )
+            })?;
+
+        let mut tx = db.begin().await?;
+        let request = sqlx::query_as::<_, NotarizationRequest>(
+            r#"
+            INSERT INTO notarization_requests
+                (document_id, plan_id, user_id, jurisdiction, document_hash, notes)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(document_id)
+        .bind(doc.plan_id)
+        .bind(user_id)
+        .bind(&jurisdiction)
+        .bind(&doc.will_hash)
+        .bind(&req.notes)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref d) if d.is_unique_violation() => ApiError::BadRequest(
+                "A notarization request is already open for this document".to_string(),
+            ),
+            other => other.into(),
+        })?;
+
+        Self::journal(
+            &mut tx,
+            &request,
+            None,
+            "requested",
+            json!({ "jurisdiction": jurisdiction, "document_hash": doc.will_hash }),
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(request)
+    }
+
+    pub async fn list_for_document(
+        db: &PgPool,
+        user_id: Uuid,
+        document_id: Uuid,
+    ) -> Result<Vec<NotarizationRequest>, ApiError> {
+        let requests = sqlx::query_as::<_, NotarizationRequest>(
+            "SELECT * FROM notarization_requests \
+             WHERE document_id = $1 AND user_id = $2 ORDER BY created_at DESC",
+        )
+        .bind(document_id)
+        .bind(user_id)
+        .fetch_all(db)
+        .await?;
+        Ok(requests)
+    }
+
+    // ── Notary side ──
+
+    /// Unclaimed requests in the notary's jurisdiction and requests they hold
+    pub async fn list_queue(
+        db: &PgPool,
+        notary: &Notary,
+        status: Option<&str>,
+    ) -> Result<Vec<NotarizationRequest>, ApiError> {
+        if let Some(status) = status {
+            NotarizationStatus::parse(status)?;
+        }
+        let requests = sqlx::query_as::<_, NotarizationRequest>(
+            "SELECT * FROM notarization_requests \
+             WHERE (notary_id = $1 OR (notary_id IS NULL AND jurisdiction = $2)) \
+               AND ($3::text IS NULL OR status = $3) \
+               AND user_id <> $4 \
+             ORDER BY created_at",
+        )
+        .bind(notary.id)
+        .bind(&notary.jurisdiction)
+        .bind(status)
+        .bind(notary.user_id)
+        .fetch_all(db)
+        .await?;
+        Ok(requests)
+    }
+
+    /// Claim (or reschedule) a request and set the session time
+    pub async fn schedule(
+        db: &PgPool,
+        notary: &Notary,
+        request_id: Uuid,
+        req: &ScheduleSessionRequest,
+    ) -> Result<NotarizationRequest, ApiError> {
+        if req.scheduled_for <= Utc::now() {
+            return Err(ApiError::BadRequest(
+                "scheduled_for must be in the future".to_string(),
+            ));
+        }
+        if req.session_url.trim().is_empty() {
+            return Err(ApiError::BadRequest("session_url is required".to_string()));
+        }
+        let current = Self::load_for_notary(db, notary, request_id).await?;
+        Self::check_transition(&current, NotarizationStatus::Scheduled)?;
+
+        let mut tx = db.begin().await?;
+        let updated = sqlx::query_as::<_, NotarizationRequest>(
+            "UPDATE notarization_requests \
+             SET notary_id = $2, status = 'scheduled', scheduled_for = $3, session_url = $4, \
+                 updated_at = NOW() \
+             WHERE id = $1 AND status = $5 RETURNING *",
+        )
+        .bind(request_id)
+        .bind(notary.id)
+        .bind(req.scheduled_for)
+        .bind(req.session_url.trim())
+        .bind(&current.status)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(Self::concurrent_update)?;
+
+        Self::journal(
+            &mut tx,
+            &updated,
+            Some(notary.id),
+            "scheduled",
+            json!({ "scheduled_for": req.scheduled_for, "session_url": updated.session_url }),
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(updated)
+    }
+
+    /// Open the online session once the signer's identity has been checked
+    pub async fn start_session(
+        db: &PgPool,
+        notary: &Notary,
+        request_id: Uuid,
+        req: &StartSessionRequest,
+    ) -> Result<NotarizationRequest, ApiError> {
+        if req.identity_method.trim().is_empty() {
+            return Err(ApiError::BadRequest(
+                "identity_method is required".to_string(),
+            ));
+        }
+        let current = Self::load_for_notary(db, notary, request_id).await?;
+        Self::check_transition(&current, NotarizationStatus::InSession)?;
+        if let Some(scheduled_for) = current.scheduled_for {
+            if !can_start_session(scheduled_for, Utc::now()) {
+                return Err(ApiError::BadRequest(format!(
+                    "Session cannot start more than {SESSION_EARLY_START_MINUTES} minutes before it is scheduled"
+                )));
+            }
+        }
+
+        let mut tx = db.begin().await?;
+        let updated = sqlx::query_as::<_, NotarizationRequest>(
+            "UPDATE notarization_requests \
+             SET status = 'in_session', identity_method = $2, session_started_at = NOW(), \
+                 updated_at = NOW() \
+             WHERE id = $1 AND status = 'scheduled' RETURNING *",
+        )
+        .bind(request_id)
+        .bind(req.identity_method.trim())
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(Self::concurrent_update)?;
+
+        Self::journal(
+            &mut tx,
+            &updated,
+            Some(notary.id),
+            "session_started",
+            json!({ "identity_method": updated.identity_method }),
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(updated)
+    }
+
+    /// Seal the document with the notary's Ed25519 signature over its hash
+    pub async fn seal(
+        db: &PgPool,
+        notary: &Notary,
+        request_id: Uuid,
+        req: &SealRequest,
+    ) -> Result<NotarizationRequest, ApiError> {
+        let current = Self::load_for_notary(db, notary, request_id).await?;
+        Self::check_transition(&current, NotarizationStatus::Notarized)?;
+
+        let (status, will_hash): (String, String) =
+            sqlx::query_as("SELECT status, will_hash FROM will_documents WHERE id = $1")
+                .bind(current.document_id)
+                .fetch_one(db)
+                .await?;
+        if status == "revoked" {
+            return Err(ApiError::BadRequest(
+                "Will document has been revoked".to_string(),
+            ));
+        }
+        if will_hash != current.document_hash {
+            return Err(ApiError::BadRequest(
+                "Will document has changed since notarization was requested".to_string(),
+            ));
+        }
+
+        let message = seal_message(
+            &current.document_hash,
+            current.id,
+            &notary.commission_number,
+        );
+        WillSignatureService::verify_signature(&notary.public_key, &message, &req.signature_hex)?;
+
+        let mut tx = db.begin().await?;
+        let updated = sqlx::query_as::<_, NotarizationRequest>(
+            "UPDATE notarization_requests \
+             SET status = 'notarized', seal_signature_hex = $2, sealed_at = NOW(), \
+                 updated_at = NOW() \
+             WHERE id = $1 AND status = 'in_session' RETURNING *",
+        )
+        .bind(request_id)
+        .bind(req.signature_hex.trim())
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(Self::concurrent_update)?;
+
+        Self::journal(
+            &mut tx,
+            &updated,
+            Some(notary.id),
+            "notarized",
+            json!({
+                "document_hash": updated.document_hash,
+                "commission_number": notary.commission_number,
+                "public_key": notary.public_key,
+                "signature_hex": updated.seal_signature_hex,
+            }),
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(updated)
+    }
+
+    pub async fn reject(
+        db: &PgPool,
+        notary: &Notary,
+        request_id: Uuid,
+        req: &RejectNotarizationRequest,
+    ) -> Result<NotarizationRequest, ApiError> {
+        if req.reason.trim().is_empty() {
+            return Err(ApiError::BadRequest("reason is required".to_string()));
+        }
+        let current = Self::load_for_notary(db, notary, request_id).await?;
+        Self::check_transition(&current, NotarizationStatus::Rejected)?;
+
+        let mut tx = db.begin().await?;
+        let updated = sqlx::query_as::<_, NotarizationRequest>(
+            "UPDATE notarization_requests \
+             SET notary_id = $2, status = 'rejected', rejection_reason = $3, updated_at = NOW() \
+             WHERE id = $1 AND status = $4 RETURNING *",
+        )
+        .bind(request_id)
+        .bind(notary.id)
+        .bind(req.reason.trim())
+        .bind(&current.status)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(Self::concurrent_update)?;
+
+        Self::journal(
+            &mut tx,
+            &updated,
+            Some(notary.id),
+            "rejected",
+            json!({ "reason": updated.rejection_reason }),
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(updated)
+    }
+
+    /// The notary's journal: every entry recorded for requests they handled
+    pub async fn journal_for_notary(
+        db: &PgPool,
+        notary_id: Uuid,
+    ) -> Result<Vec<NotaryJournalEntry>, ApiError> {
+        let entries = sqlx::query_as::<_, NotaryJournalEntry>(
+            "SELECT * FROM notary_journal \
+             WHERE request_id IN (SELECT id FROM notarization_requests WHERE notary_id = $1) \
+             ORDER BY created_at",
+        )
+        .bind(notary_id)
+        .fetch_all(db)
+        .await?;
+        Ok(entries)
+    }
+
+    // ── Compliance ──
+
+    /// Whether the document carries a valid notary seal for its current
+    /// content: the sealed hash matches the stored will hash, the document is
+    /// not revoked, and the signature verifies against the notary's key.
+    pub async fn has_valid_seal(db: &PgPool, document_id: Uuid) -> Result<bool, ApiError> {
+        #[derive(sqlx::FromRow)]
+        struct Row {
+            id: Uuid,
+            document_hash: String,
+            seal_signature_hex: Option<String>,
+            commission_number: String,
+            public_key: String,
+            will_hash: String,
+            document_status: String,
+        }
+
+        let rows = sqlx::query_as::<_, Row>(
+            "SELECT r.id, r.document_hash, r.seal_signature_hex, n.commission_number, \
+                    n.public_key, d.will_hash, d.status AS document_status \
+             FROM notarization_requests r \
+             JOIN notaries n ON n.id = r.notary_id \
+             JOIN will_documents d ON d.id = r.document_id \
+             WHERE r.document_id = $1 AND r.status = 'notarized'",
+        )
+        .bind(document_id)
+        .fetch_all(db)
+        .await?;
+
+        Ok(rows.iter().any(|r| {
+            r.document_status != "revoked"
+                && r.document_hash == r.will_hash
+                && r.seal_signature_hex.as_deref().is_some_and(|sig| {
+                    let message = seal_message(&r.document_hash, r.id, &r.commission_number);
+                    WillSignatureService::verify_signature(&r.public_key, &message, sig).is_ok()
+                })
+        }))
+    }
+
+    // ── Helpers ──
+
+    async fn load_for_notary(
+        db: &PgPool,
+        notary: &Notary,
+        request_id: Uuid,
+    ) -> Result<NotarizationRequest, ApiError> {
+        let request = sqlx::query_as::<_, NotarizationRequest>(
+            "SELECT * FROM notarization_requests WHERE id = $1",
+        )
+        .bind(request_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| {
+            ApiError::NotFound(format!("Notarization request {request_id} not found"))
+        })?;
+
+        let visible = match request.notary_id {
+            Some(id) => id == notary.id,
+            None => request.jurisdiction == notary.jurisdiction,
+        };
+        if !visible {
+            return Err(ApiError::NotFound(format!(
+                "Notarization request {request_id} not found"
+            )));
+        }
+        Self::check_not_signer(notary, &request)?;
+        Ok(request)
+    }
+
+    /// A notary may not notarize their own will
+    fn check_not_signer(notary: &Notary, request: &NotarizationRequest) -> Result<(), ApiError> {
+        if notary.user_id == request.user_id {
+            return Err(ApiError::Forbidden(
+                "Notaries cannot notarize their own will".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_transition(
+        request: &NotarizationRequest,
+        next: NotarizationStatus,
+    ) -> Result<(), ApiError> {
+        let current = NotarizationStatus::parse(&request.status)?;
+        if !current.can_transition_to(next) {
+            return Err(ApiError::BadRequest(format!(
+                "Cannot move a {} request to {}",
+                current.as_str(),
+                next.as_str()
+            )));
+        }
+        Ok(())
+    }
+
+    fn concurrent_update() -> ApiError {
+        ApiError::BadRequest("Notarization request was updated concurrently".to_string())
+    }
+
+    async fn journal(
+        conn: &mut PgConnection,
+        request: &NotarizationRequest,
+        notary_id: Option<Uuid>,
+        action: &str,
+        details: serde_json::Value,
+    ) -> Result<(), ApiError> {
+        sqlx::query(
+            "INSERT INTO notary_journal \
+                 (request_id, document_id, plan_id, notary_id, action, details) \
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(request.id)
+        .bind(request.document_id)
+        .bind(request.plan_id)
+        .bind(notary_id)
+        .bind(action)
+        .bind(details)
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    #[test]
+    fn test_lifecycle_transitions() {
+        use NotarizationStatus::*;
+        assert!(Requested.can_transition_to(Scheduled));
+        assert!(Scheduled.can_transition_to(Scheduled));
+        assert!(Scheduled.can_transition_to(InSession));
+        assert!(InSession.can_transition_to(Notarized));
+        assert!(Requested.can_transition_to(Rejected));
+        assert!(InSession.can_transition_to(Rejected));
+
+        assert!(!Requested.can_transition_to(InSession));
+        assert!(!Requested.can_transition_to(Notarized));
+        assert!(!Scheduled.can_transition_to(Notarized));
+        assert!(!Notarized.can_transition_to(Rejected));
+        assert!(!Rejected.can_transition_to(Scheduled));
+    }
+
+    #[test]
+    fn test_session_start_window() {
+        let scheduled = Utc::now();
+        assert!(can_start_session(scheduled, scheduled));
+        assert!(can_start_session(
+            scheduled,
+            scheduled - Duration::minutes(10)
+        ));
+        assert!(!can_start_session(
+            scheduled,
+            scheduled - Duration::minutes(30)
+        ));
+    }
+
+    #[test]
+    fn test_seal_signature_verifies_against_notary_key() {
+        let rng = SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let public_key = hex::encode(key_pair.public_key().as_ref());
+
+        let request_id = Uuid::new_v4();
+        let hash = "ab".repeat(32);
+        let message = seal_message(&hash, request_id, "NP-1234");
+        let signature = hex::encode(key_pair.sign(message.as_bytes()).as_ref());
+
+        assert!(WillSignatureService::verify_signature(&public_key, &message, &signature).is_ok());
+
+        // A seal does not carry over to a different version of the document
+        let other = seal_message(&"cd".repeat(32), request_id, "NP-1234");
+        assert!(WillSignatureService::verify_signature(&public_key, &other, &signature).is_err());
+    }
+
+    #[test]
+    fn test_notary_cannot_notarize_own_will() {
+        let now = Utc::now();
+        let user_id = Uuid::new_v4();
+        let notary = Notary {
+            id: Uuid::new_v4(),
+            user_id,
+            full_name: "Ada Notary".to_string(),
+            commission_number: "NP-1234".to_string(),
+            jurisdiction: "US-CA".to_string(),
+            public_key: "ab".repeat(32),
+            commission_expires_at: now + Duration::days(365),
+            status: "active".to_string(),
+            created_by: None,
+            created_at: now,
+            updated_at: now,
+        };
+        let mut request = NotarizationRequest {
+            id: Uuid::new_v4(),
+            document_id: Uuid::new_v4(),
+            plan_id: Uuid::new_v4(),
+            user_id,
+            notary_id: Some(notary.id),
+            jurisdiction: "US-CA".to_string(),
+            document_hash: "cd".repeat(32),
+            status: "in_session".to_string(),
+            notes: None,
+            scheduled_for: None,
+            session_url: None,
+            identity_method: None,
+            session_started_at: None,
+            seal_signature_hex: None,
+            sealed_at: None,
+            rejection_reason: None,
+            created_at: now,
+            updated_at: now,
+        };
+        assert!(matches!(
+            NotarizationService::check_not_signer(&notary, &request),
+            Err(ApiError::Forbidden(_))
+        ));
+
+        request.user_id = Uuid::new_v4();
+        assert!(NotarizationService::check_not_signer(&notary, &request).is_ok());
+    }
+}
@@ -30,6 +30,9 @@ pub mod notif_type {
     // Insurance fund monitoring (Issue #249)
     pub const ADMIN_ALERT: &str = "admin_alert";
     pub const FUND_STATUS_CHANGE: &str = "fund_status_change";
+    // Liquidation auctions
+    pub const LIQUIDATION_AUCTION_STARTED: &str = "liquidation_auction_started";
+    pub const LIQUIDATION_AUCTION_SETTLED: &str = "liquidation_auction_settled";
 }
 
 // ─── Notification ────────────────────────────────────────────────────────────
@@ -256,6 +259,15 @@ pub mod audit_action {
     pub const INSURANCE_CLAIM_CREATED: &str = "insurance_claim_created";
     pub const INSURANCE_CLAIM_PROCESSED: &str = "insurance_claim_processed";
     pub const INSURANCE_CLAIM_PAID: &str = "insurance_claim_paid";
+    pub const INSURANCE_CLAIM_FIRST_REVIEW: &str = "insurance_claim_first_review";
+    pub const INSURANCE_CLAIM_AUTO_CREATED: &str = "insurance_claim_auto_created";
+    pub const INSURANCE_CLAIM_SLA_BREACHED: &str = "insurance_claim_sla_breached";
+    pub const INSURANCE_CLAIM_POLICY_UPDATED: &str = "insurance_claim_policy_updated";
+    // Liquidation auctions
+    pub const LIQUIDATION_AUCTION_STARTED: &str = "liquidation_auction_started";
+    pub const LIQUIDATION_AUCTION_BID: &str = "liquidation_auction_bid";
+    pub const LIQUIDATION_AUCTION_SETTLED: &str = "liquidation_auction_settled";
+    pub const REPUTATION_ATTESTED: &str = "reputation_attested";
 }
 
 /// Entity type constants — stored in `entity_type` column of `action_logs`.
@@ -266,6 +278,7 @@ pub mod entity_type {
     // Insurance fund monitoring (Issue #249)
     pub const INSURANCE_FUND: &str = "insurance_fund";
     pub const INSURANCE_CLAIM: &str = "insurance_claim";
+    pub const LIQUIDATION_AUCTION: &str = "liquidation_auction";
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -0,0 +1,557 @@
+//! # Oracle Price Pusher
+//!
+//! Mirrors aggregated spot prices onto the `price-oracle` contract so the
+//! lending and borrowing contracts can value collateral. A feed opts in with
+//! `oracle_push_enabled`; its price is pushed when it moves past
+//! `PRICE_ORACLE_PUSH_DEVIATION` from the last push, or when the last push is
+//! older than `PRICE_ORACLE_PUSH_HEARTBEAT_SECS`. Stale and paused feeds are
+//! never pushed, leaving the contract to report the price as stale.
+
+use crate::api_error::ApiError;
+use crate::price_feed::PriceFeedService;
+use crate::stellar_asset::AssetId;
+use crate::tx_submitter::{
+    account_address, i128_val, ContractCall, ContractInvoker, SorobanInvoker, SubmitError,
+    TransactionSigner, TxStatus, TESTNET_PASSPHRASE,
+};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use soroban_sdk::xdr::ScVal;
+use sqlx::PgPool;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Relative move that triggers a push by default (0.5%)
+const DEFAULT_PUSH_DEVIATION: Decimal = Decimal::from_parts(5, 0, 0, false, 3);
+
+/// Age of the last push that triggers a refresh by default. Must stay under
+/// the contract's heartbeat or borrowers see stale prices between pushes.
+const DEFAULT_PUSH_HEARTBEAT_SECS: i64 = 300;
+
+/// How long a push may stay unconfirmed before the next one replaces it
+const CONFIRMATION_TIMEOUT_SECS: i64 = 120;
+
+/// Inclusion fee per push, in stroops
+const DEFAULT_PUSH_FEE: u32 = 1_000;
+
+// ─── Types ────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PushReason {
+    /// Nothing has been pushed for the asset yet
+    Initial,
+    Deviation,
+    Heartbeat,
+}
+
+impl PushReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PushReason::Initial => "initial",
+            PushReason::Deviation => "deviation",
+            PushReason::Heartbeat => "heartbeat",
+        }
+    }
+}
+
+/// When a feed's price is due on-chain
+#[derive(Debug, Clone, Copy)]
+pub struct PushPolicy {
+    /// Relative move from the last pushed price, e.g. 0.005 for 0.5%
+    pub deviation: Decimal,
+    pub heartbeat_secs: i64,
+}
+
+impl PushPolicy {
+    pub fn from_env() -> Self {
+        Self {
+            deviation: std::env::var("PRICE_ORACLE_PUSH_DEVIATION")
+                .ok()
+                .and_then(|v| Decimal::from_str(&v).ok())
+                .filter(|v| *v > Decimal::ZERO)
+                .unwrap_or(DEFAULT_PUSH_DEVIATION),
+            heartbeat_secs: std::env::var("PRICE_ORACLE_PUSH_HEARTBEAT_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(DEFAULT_PUSH_HEARTBEAT_SECS),
+        }
+    }
+
+    /// Why `price` observed at `price_timestamp` should be pushed, given the
+    /// last accepted push, or `None` if it need not be
+    pub fn push_reason(
+        &self,
+        last: Option<&OraclePricePush>,
+        price: Decimal,
+        price_timestamp: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Option<PushReason> {
+        let Some(last) = last else {
+            return Some(PushReason::Initial);
+        };
+        // The contract only accepts newer observations
+        if price_timestamp <= last.price_timestamp {
+            return None;
+        }
+        if !last.price.is_zero() && ((price - last.price) / last.price).abs() >= self.deviation {
+            return Some(PushReason::Deviation);
+        }
+        if now
+            .signed_duration_since(last.price_timestamp)
+            .num_seconds()
+            >= self.heartbeat_secs
+        {
+            return Some(PushReason::Heartbeat);
+        }
+        None
+    }
+}
+
+/// One `set_price` call sent to the oracle contract
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct OraclePricePush {
+    pub id: Uuid,
+    pub asset_code: String,
+    pub price: Decimal,
+    /// `None` when the price could not be scaled and nothing was sent
+    pub scaled_price: Option<Decimal>,
+    pub price_timestamp: DateTime<Utc>,
+    pub reason: String,
+    /// submitted | confirmed | failed
+    pub status: String,
+    pub tx_hash: Option<String>,
+    pub ledger: Option<i32>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Outcome of one pusher pass
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct OraclePushRun {
+    pub pushed: Vec<String>,
+    pub skipped_stale: Vec<String>,
+    pub failed: Vec<String>,
+    pub confirmed: usize,
+}
+
+/// `price` as the integer the oracle stores, scaled by `10^decimals` and
+/// truncated
+pub fn scale_price(price: Decimal, decimals: u32) -> Option<i128> {
+    if price <= Decimal::ZERO {
+        return None;
+    }
+    let truncated = price.trunc_with_scale(decimals);
+    let mantissa = truncated.mantissa();
+    mantissa.checked_mul(10i128.checked_pow(decimals - truncated.scale())?)
+}
+
+// ─── Service ──────────────────────────────────────────────────────────────────
+
+pub struct OraclePusher {
+    db: PgPool,
+    price_feed: Arc<dyn PriceFeedService>,
+    invoker: Arc<dyn ContractInvoker>,
+    signer: Arc<dyn TransactionSigner>,
+    /// `G…` account whitelisted as an oracle signer
+    source_account: String,
+    network_passphrase: String,
+    decimals: u32,
+    policy: PushPolicy,
+    fee: u32,
+}
+
+impl OraclePusher {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        db: PgPool,
+        price_feed: Arc<dyn PriceFeedService>,
+        invoker: Arc<dyn ContractInvoker>,
+        signer: Arc<dyn TransactionSigner>,
+        source_account: String,
+        network_passphrase: String,
+        decimals: u32,
+        policy: PushPolicy,
+    ) -> Self {
+        Self {
+            db,
+            price_feed,
+            invoker,
+            signer,
+            source_account,
+            network_passphrase,
+            decimals,
+            policy,
+            fee: std::env::var("PRICE_ORACLE_PUSH_FEE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_PUSH_FEE),
+        }
+    }
+
+    /// Pusher for `PRICE_ORACLE_PUSH_CONTRACT_ID` signing as
+    /// `PRICE_ORACLE_PUSHER_ACCOUNT`, or `None` when either (or
+    /// `SOROBAN_RPC_URL`) is unset. This is deliberately not the oracle read
+    /// as a price source, which would feed pushed prices back into the
+    /// median. Reads the contract's `decimals()` once at startup.
+    pub async fn from_env(
+        db: PgPool,
+        price_feed: Arc<dyn PriceFeedService>,
+    ) -> Result<Option<Self>, ApiError> {
+        let (Ok(rpc_url), Ok(contract_id), Ok(account)) = (
+            std::env::var("SOROBAN_RPC_URL"),
+            std::env::var("PRICE_ORACLE_PUSH_CONTRACT_ID"),
+            std::env::var("PRICE_ORACLE_PUSHER_ACCOUNT"),
+        ) else {
+            return Ok(None);
+        };
+        let passphrase = std::env::var("STELLAR_NETWORK_PASSPHRASE")
+            .unwrap_or_else(|_| TESTNET_PASSPHRASE.to_string());
+        let invoker = SorobanInvoker::new(&rpc_url, &contract_id, &passphrase)?;
+
+        let call = ContractCall {
+            source_account: account.clone(),
+            function: "decimals".to_string(),
+            args: Vec::new(),
+        };
+        let decimals = match invoker.read(&call).await {
+            Ok(ScVal::U32(decimals)) => decimals,
+            Ok(other) => {
+                return Err(ApiError::Internal(anyhow::anyhow!(
+                    "Price oracle returned {other:?} for decimals"
+                )))
+            }
+            Err(e) => {
+                return Err(ApiError::Internal(anyhow::anyhow!(
+                    "Could not read price oracle decimals: {e}"
+                )))
+            }
+        };
+
+        Ok(Some(Self::new(
+            db,
+            price_feed,
+            Arc::new(invoker),
+            crate::tx_submitter::signer_from_env()?,
+            account,
+            passphrase,
+            decimals,
+            PushPolicy::from_env(),
+        )))
+    }
+
+    pub fn start(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(15));
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.run_once().await {
+                    error!("Oracle price pusher error: {}", e);
+                }
+            }
+        });
+    }
+
+    pub async fn run_once(&self) -> Result<OraclePushRun, ApiError> {
+        let mut run = OraclePushRun {
+            confirmed: self.poll_confirmations().await?,
+            ..Default::default()
+        };
+
+        let now = Utc::now();
+        for feed in self.price_feed.get_active_feeds().await? {
+            if !feed.oracle_push_enabled {
+                continue;
+            }
+            let asset_code = feed.asset_code;
+            if self.has_outstanding_push(&asset_code).await? {
+                continue;
+            }
+            let averages = match self.price_feed.get_price_averages(&asset_code).await {
+                Ok(averages) => averages,
+                Err(e) => {
+                    warn!("No price to push for {}: {}", asset_code, e);
+                    continue;
+                }
+            };
+            if averages.is_stale || averages.borrows_paused {
+                run.skipped_stale.push(asset_code);
+                continue;
+            }
+
+            let last = self.last_accepted_push(&asset_code).await?;
+            let Some(reason) =
+                self.policy
+                    .push_reason(last.as_ref(), averages.spot, averages.spot_timestamp, now)
+            else {
+                continue;
+            };
+
+            match self
+                .push(&asset_code, averages.spot, averages.spot_timestamp, reason)
+                .await?
+            {
+                Some(_) => run.pushed.push(asset_code),
+                None => run.failed.push(asset_code),
+            }
+        }
+
+        if !run.pushed.is_empty() {
+            info!("Pushed oracle prices for {}", run.pushed.join(", "));
+        }
+        Ok(run)
+    }
+
+    /// The `set_price` call for a scaled price
+    fn set_price_call(
+        &self,
+        asset_code: &str,
+        scaled: i128,
+        price_timestamp: DateTime<Utc>,
+    ) -> Result<ContractCall, ApiError> {
+        let asset: AssetId = asset_code.parse()?;
+        Ok(ContractCall {
+            source_account: self.source_account.clone(),
+            function: "set_price".to_string(),
+            args: vec![
+                account_address(&self.source_account)?,
+                asset.oracle_asset(&self.network_passphrase)?,
+                i128_val(scaled),
+                ScVal::U64(price_timestamp.timestamp().max(0) as u64),
+            ],
+        })
+    }
+
+    /// Send one price and record the attempt. Returns the transaction hash,
+    /// or `None` if the call could not be built or was rejected before
+    /// reaching a ledger.
+    async fn push(
+        &self,
+        asset_code: &str,
+        price: Decimal,
+        price_timestamp: DateTime<Utc>,
+        reason: PushReason,
+    ) -> Result<Option<String>, ApiError> {
+        let scaled = scale_price(price, self.decimals);
+        let call = match scaled {
+            Some(scaled) => self
+                .set_price_call(asset_code, scaled, price_timestamp)
+                .map_err(|e| e.to_string()),
+            None => Err(format!("Price {} cannot be scaled for the oracle", price)),
+        };
+
+        let (status, tx_hash, push_error) = match call {
+            Ok(call) => match self
+                .invoker
+                .submit(&call, self.fee, self.signer.as_ref())
+                .await
+            {
+                Ok(hash) => ("submitted", Some(hash), None),
+                Err(SubmitError::Retryable(e)) | Err(SubmitError::Fatal(e)) => {
+                    warn!("Oracle price push for {} failed: {}", asset_code, e);
+                    ("failed", None, Some(e))
+                }
+            },
+            Err(e) => {
+                warn!("Oracle price push for {} not sent: {}", asset_code, e);
+                ("failed", None, Some(e))
+            }
+        };
+
+        sqlx::query(
+            "INSERT INTO oracle_price_pushes \
+             (asset_code, price, scaled_price, price_timestamp, reason, status, tx_hash, error) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(asset_code)
+        .bind(price)
+        .bind(scaled.map(|s| Decimal::from_i128_with_scale(s, 0)))
+        .bind(price_timestamp)
+        .bind(reason.as_str())
+        .bind(status)
+        .bind(&tx_hash)
+        .bind(&push_error)
+        .execute(&self.db)
+        .await?;
+
+        Ok(tx_hash)
+    }
+
+    /// Settle sent pushes; those unconfirmed past the timeout are failed so
+    /// the next pass can push a fresh price in their place
+    async fn poll_confirmations(&self) -> Result<usize, ApiError> {
+        let sent = sqlx::query_as::<_, OraclePricePush>(
+            "SELECT * FROM oracle_price_pushes WHERE status = 'submitted' ORDER BY created_at",
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut confirmed = 0;
+        for push in &sent {
+            let Some(hash) = &push.tx_hash else { continue };
+            let status = match self.invoker.status(hash).await {
+                Ok(status) => status,
+                Err(e) => {
+                    warn!("Could not check oracle push {}: {}", hash, e);
+                    continue;
+                }
+            };
+            let timed_out = Utc::now()
+                .signed_duration_since(push.created_at)
+                .num_seconds()
+                > CONFIRMATION_TIMEOUT_SECS;
+
+            let (new_status, ledger, push_error) = match status {
+                TxStatus::Confirmed { ledger } => ("confirmed", Some(ledger as i32), None),
+                TxStatus::Failed(e) => ("failed", None, Some(e)),
+                TxStatus::Pending | TxStatus::NotFound if timed_out => {
+                    ("failed", None, Some("Not confirmed in time".to_string()))
+                }
+                TxStatus::Pending | TxStatus::NotFound => continue,
+            };
+            sqlx::query(
+                "UPDATE oracle_price_pushes \
+                 SET status = $2, ledger = $3, error = $4, updated_at = NOW() \
+                 WHERE id = $1",
+            )
+            .bind(push.id)
+            .bind(new_status)
+            .bind(ledger)
+            .bind(&push_error)
+            .execute(&self.db)
+            .await?;
+            if new_status == "confirmed" {
+                confirmed += 1;
+            }
+        }
+        Ok(confirmed)
+    }
+
+    async fn has_outstanding_push(&self, asset_code: &str) -> Result<bool, ApiError> {
+        let outstanding: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM oracle_price_pushes \
+             WHERE asset_code = $1 AND status = 'submitted')",
+        )
+        .bind(asset_code)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(outstanding)
+    }
+
+    /// Latest push the contract accepted or may still accept
+    async fn last_accepted_push(
+        &self,
+        asset_code: &str,
+    ) -> Result<Option<OraclePricePush>, ApiError> {
+        Ok(sqlx::query_as::<_, OraclePricePush>(
+            "SELECT * FROM oracle_price_pushes \
+             WHERE asset_code = $1 AND status <> 'failed' \
+             ORDER BY price_timestamp DESC LIMIT 1",
+        )
+        .bind(asset_code)
+        .fetch_optional(&self.db)
+        .await?)
+    }
+
+    /// Recent pushes, newest first
+    pub async fn list_pushes(
+        db: &PgPool,
+        asset_code: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<OraclePricePush>, ApiError> {
+        Ok(sqlx::query_as::<_, OraclePricePush>(
+            "SELECT * FROM oracle_price_pushes \
+             WHERE ($1::text IS NULL OR asset_code = $1) \
+             ORDER BY created_at DESC LIMIT $2",
+        )
+        .bind(asset_code)
+        .bind(limit)
+        .fetch_all(db)
+        .await?)
+    }
+}
+
+// ─── Unit Tests ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_760_000_000 + secs, 0).unwrap()
+    }
+
+    fn pushed(price: Decimal, secs: i64) -> OraclePricePush {
+        OraclePricePush {
+            id: Uuid::new_v4(),
+            asset_code: "XLM".to_string(),
+            price,
+            scaled_price: None,
+            price_timestamp: at(secs),
+            reason: "initial".to_string(),
+            status: "confirmed".to_string(),
+            tx_hash: None,
+            ledger: None,
+            error: None,
+            created_at: at(secs),
+            updated_at: at(secs),
+        }
+    }
+
+    #[test]
+    fn scales_prices_to_oracle_decimals() {
+        assert_eq!(scale_price(dec!(0.1184), 14), Some(11_840_000_000_000));
+        assert_eq!(scale_price(dec!(2), 7), Some(20_000_000));
+        // Digits beyond the oracle's precision are truncated
+        assert_eq!(scale_price(dec!(1.23456789), 4), Some(12_345));
+        assert_eq!(scale_price(dec!(0), 7), None);
+        assert_eq!(scale_price(dec!(-1), 7), None);
+    }
+
+    #[test]
+    fn pushes_on_deviation_or_heartbeat() {
+        let policy = PushPolicy {
+            deviation: dec!(0.005),
+            heartbeat_secs: 300,
+        };
+        assert_eq!(
+            policy.push_reason(None, dec!(1), at(0), at(0)),
+            Some(PushReason::Initial)
+        );
+
+        let last = pushed(dec!(1.000), 0);
+        assert_eq!(
+            policy.push_reason(Some(&last), dec!(1.004), at(10), at(10)),
+            None
+        );
+        assert_eq!(
+            policy.push_reason(Some(&last), dec!(0.995), at(10), at(10)),
+            Some(PushReason::Deviation)
+        );
+        assert_eq!(
+            policy.push_reason(Some(&last), dec!(1.001), at(290), at(300)),
+            Some(PushReason::Heartbeat)
+        );
+    }
+
+    #[test]
+    fn never_pushes_an_older_observation() {
+        let policy = PushPolicy {
+            deviation: dec!(0.005),
+            heartbeat_secs: 300,
+        };
+        let last = pushed(dec!(1), 100);
+        assert_eq!(
+            policy.push_reason(Some(&last), dec!(2), at(100), at(1_000)),
+            None
+        );
+    }
+}
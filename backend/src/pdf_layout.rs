@@ -0,0 +1,1145 @@
+//! # PDF Layout Engine
+//!
+//! Lays out a flow of text blocks onto fixed-size pages and serialises them
+//! as a PDF: wrapped paragraphs, automatic page breaks, a running header,
+//! "Page X of Y" footers and XMP metadata.
+//!
+//! Without assets the standard Courier fonts are referenced, which every
+//! viewer ships. PDF/A requires embedded fonts and an output intent, so when
+//! regular and bold TrueType fonts and an ICC profile are supplied they are
+//! embedded and the document declares PDF/A-1b conformance in its metadata.
+//! Wills are always rendered with all three, loaded from
+//! `WILL_PDF_FONT_PATH`, `WILL_PDF_BOLD_FONT_PATH` and
+//! `WILL_PDF_ICC_PROFILE_PATH`.
+//!
+//! Output is a pure function of its inputs, so identical documents produce
+//! identical bytes.
+
+use crate::api_error::ApiError;
+use chrono::{DateTime, Utc};
+use std::sync::OnceLock;
+
+// ─── Layout Types ─────────────────────────────────────────────────────────────
+
+/// A unit of flowing content
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    /// Bold line, kept on the same page as the block that follows it
+    Heading(String),
+    /// Text wrapped to the content width
+    Paragraph(String),
+    /// A horizontal rule
+    Rule,
+    /// Vertical space in points
+    Spacer(f32),
+    /// Blocks that move to the next page together rather than split
+    KeepTogether(Vec<Block>),
+    /// Continue on a new page
+    PageBreak,
+    /// A signature line with a caption and supporting detail lines
+    SignatureLine { label: String, details: Vec<String> },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PageSize {
+    Letter,
+    A4,
+}
+
+impl PageSize {
+    fn dimensions(self) -> (f32, f32) {
+        match self {
+            PageSize::Letter => (612.0, 792.0),
+            PageSize::A4 => (595.0, 842.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PageLayout {
+    pub size: PageSize,
+    pub margin: f32,
+    pub body_size: f32,
+    pub heading_size: f32,
+    pub footer_size: f32,
+    /// Line height as a multiple of the font size
+    pub leading: f32,
+}
+
+impl Default for PageLayout {
+    fn default() -> Self {
+        Self {
+            size: PageSize::Letter,
+            margin: 54.0,
+            body_size: 9.5,
+            heading_size: 11.0,
+            footer_size: 7.5,
+            leading: 1.35,
+        }
+    }
+}
+
+/// Document-level information written to the Info dictionary and XMP
+#[derive(Debug, Clone)]
+pub struct DocumentMeta {
+    pub title: String,
+    pub author: String,
+    pub subject: String,
+    pub keywords: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    /// Shown at the top of every page
+    pub running_header: String,
+    /// Shown at the bottom left of every page
+    pub footer_note: String,
+    /// Shown under the footer note in a smaller size
+    pub footer_detail: Option<String>,
+    /// Extra XMP properties in the document's own namespace
+    pub custom_properties: Vec<(String, String)>,
+    /// Seed for the trailer `/ID`, e.g. a content hash
+    pub document_id: Vec<u8>,
+}
+
+// ─── Fonts ────────────────────────────────────────────────────────────────────
+
+/// A TrueType font embedded in the document
+#[derive(Debug, Clone)]
+pub struct EmbeddedFont {
+    pub name: String,
+    program: Vec<u8>,
+    /// Advance widths in 1/1000 em for WinAnsi codes 32..=255
+    widths: Vec<u16>,
+    bbox: [i32; 4],
+    ascent: i32,
+    descent: i32,
+}
+
+fn be_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+}
+
+fn be_i16(data: &[u8], offset: usize) -> Option<i16> {
+    be_u16(data, offset).map(|v| v as i16)
+}
+
+fn be_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+impl EmbeddedFont {
+    /// Parse the metrics needed for embedding from a TrueType font program
+    pub fn from_truetype(name: &str, program: Vec<u8>) -> Result<Self, String> {
+        let table = |tag: &[u8; 4]| -> Option<usize> {
+            let count = be_u16(&program, 4)? as usize;
+            (0..count).find_map(|i| {
+                let entry = 12 + i * 16;
+                (program.get(entry..entry + 4)? == tag)
+                    .then(|| be_u32(&program, entry + 8).map(|o| o as usize))
+                    .flatten()
+            })
+        };
+        let missing = |t: &str| format!("font has no {} table", t);
+
+        let head = table(b"head").ok_or_else(|| missing("head"))?;
+        let hhea = table(b"hhea").ok_or_else(|| missing("hhea"))?;
+        let hmtx = table(b"hmtx").ok_or_else(|| missing("hmtx"))?;
+        let cmap = table(b"cmap").ok_or_else(|| missing("cmap"))?;
+        let malformed = || "malformed TrueType font".to_string();
+
+        let units_per_em = be_u16(&program, head + 18).ok_or_else(malformed)? as i32;
+        if units_per_em == 0 {
+            return Err(malformed());
+        }
+        let scale = |v: i32| v * 1000 / units_per_em;
+        let bbox = [
+            scale(be_i16(&program, head + 36).ok_or_else(malformed)? as i32),
+            scale(be_i16(&program, head + 38).ok_or_else(malformed)? as i32),
+            scale(be_i16(&program, head + 40).ok_or_else(malformed)? as i32),
+            scale(be_i16(&program, head + 42).ok_or_else(malformed)? as i32),
+        ];
+        let ascent = scale(be_i16(&program, hhea + 4).ok_or_else(malformed)? as i32);
+        let descent = scale(be_i16(&program, hhea + 6).ok_or_else(malformed)? as i32);
+        let metric_count = be_u16(&program, hhea + 34).ok_or_else(malformed)? as usize;
+        if metric_count == 0 {
+            return Err(malformed());
+        }
+
+        // Locate a Unicode BMP (format 4) subtable
+        let subtables = be_u16(&program, cmap + 2).ok_or_else(malformed)? as usize;
+        let format4 = (0..subtables)
+            .filter_map(|i| {
+                let rec = cmap + 4 + i * 8;
+                let platform = be_u16(&program, rec)?;
+                let encoding = be_u16(&program, rec + 2)?;
+                let offset = cmap + be_u32(&program, rec + 4)? as usize;
+                (((platform == 3 && encoding == 1) || platform == 0)
+                    && be_u16(&program, offset)? == 4)
+                    .then_some(offset)
+            })
+            .next()
+            .ok_or_else(|| "font has no Unicode cmap".to_string())?;
+
+        let glyph_for = |c: u32| -> Option<u16> {
+            let seg_x2 = be_u16(&program, format4 + 6)? as usize;
+            let ends = format4 + 14;
+            let starts = ends + seg_x2 + 2;
+            let deltas = starts + seg_x2;
+            let range_offsets = deltas + seg_x2;
+            for seg in 0..seg_x2 / 2 {
+                let end = be_u16(&program, ends + seg * 2)? as u32;
+                if c > end {
+                    continue;
+                }
+                let start = be_u16(&program, starts + seg * 2)? as u32;
+                if c < start {
+                    return None;
+                }
+                let delta = be_u16(&program, deltas + seg * 2)?;
+                let range_offset = be_u16(&program, range_offsets + seg * 2)? as usize;
+                if range_offset == 0 {
+                    return Some((c as u16).wrapping_add(delta));
+                }
+                let addr = range_offsets + seg * 2 + range_offset + (c - start) as usize * 2;
+                let glyph = be_u16(&program, addr)?;
+                return Some(if glyph == 0 {
+                    0
+                } else {
+                    glyph.wrapping_add(delta)
+                });
+            }
+            None
+        };
+
+        let advance = |glyph: u16| -> Option<u16> {
+            let index = (glyph as usize).min(metric_count - 1);
+            be_u16(&program, hmtx + index * 4)
+        };
+
+        let widths = (32u8..=255)
+            .map(|code| {
+                let glyph = win_ansi_to_unicode(code)
+                    .and_then(|c| glyph_for(c as u32))
+                    .unwrap_or(0);
+                advance(glyph)
+                    .map(|w| (w as i32 * 1000 / units_per_em) as u16)
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        Ok(Self {
+            name: name
+                .chars()
+                .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+                .collect(),
+            program,
+            widths,
+            bbox,
+            ascent,
+            descent,
+        })
+    }
+}
+
+/// Fonts and colour profile shared by every document
+#[derive(Debug, Clone, Default)]
+pub struct PdfAssets {
+    pub font: Option<EmbeddedFont>,
+    /// Face used for headings and labels
+    pub bold_font: Option<EmbeddedFont>,
+    pub icc_profile: Option<Vec<u8>>,
+}
+
+impl PdfAssets {
+    /// Load the PDF/A assets configured by `WILL_PDF_FONT_PATH`,
+    /// `WILL_PDF_BOLD_FONT_PATH` and `WILL_PDF_ICC_PROFILE_PATH`, once per
+    /// process. All three are required.
+    pub fn from_env() -> Result<&'static PdfAssets, ApiError> {
+        static ASSETS: OnceLock<Result<PdfAssets, String>> = OnceLock::new();
+        ASSETS
+            .get_or_init(|| {
+                let font = load_font("WILL_PDF_FONT_PATH", "a TrueType font")?;
+                let bold_font = load_font("WILL_PDF_BOLD_FONT_PATH", "a bold TrueType font")?;
+                let icc_path = required_path("WILL_PDF_ICC_PROFILE_PATH", "an ICC profile")?;
+                let icc_profile = std::fs::read(&icc_path).map_err(|e| {
+                    format!("Invalid WILL_PDF_ICC_PROFILE_PATH {}: {}", icc_path, e)
+                })?;
+                Ok(PdfAssets {
+                    font: Some(font),
+                    bold_font: Some(bold_font),
+                    icc_profile: Some(icc_profile),
+                })
+            })
+            .as_ref()
+            .map_err(|e| ApiError::Internal(anyhow::anyhow!("{}", e)))
+    }
+
+    /// Regular and bold faces, when both are supplied. Otherwise the
+    /// standard Courier pair is used.
+    fn embedded_fonts(&self) -> Option<(&EmbeddedFont, &EmbeddedFont)> {
+        self.font.as_ref().zip(self.bold_font.as_ref())
+    }
+
+    /// Whether the output can claim PDF/A-1b conformance
+    pub fn pdfa_capable(&self) -> bool {
+        self.embedded_fonts().is_some() && self.icc_profile.is_some()
+    }
+
+    /// Width of `text` set in the regular face, in points
+    fn text_width(&self, text: &str, size: f32) -> f32 {
+        let units: u32 = match self.embedded_fonts().map(|(regular, _)| regular) {
+            Some(font) => encode_win_ansi(text)
+                .iter()
+                .map(|&b| {
+                    b.checked_sub(32)
+                        .and_then(|i| font.widths.get(i as usize))
+                        .copied()
+                        .unwrap_or(0) as u32
+                })
+                .sum(),
+            // Courier: every glyph is 600 units wide
+            None => text.chars().count() as u32 * 600,
+        };
+        units as f32 * size / 1000.0
+    }
+}
+
+fn required_path(key: &str, what: &str) -> Result<String, String> {
+    std::env::var(key)
+        .ok()
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| format!("{key} must be set to {what} for PDF/A will output"))
+}
+
+fn load_font(key: &str, what: &str) -> Result<EmbeddedFont, String> {
+    let path = required_path(key, what)?;
+    let name = std::path::Path::new(&path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("EmbeddedFont")
+        .to_string();
+    std::fs::read(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| EmbeddedFont::from_truetype(&name, bytes))
+        .map_err(|e| format!("Invalid {} {}: {}", key, path, e))
+}
+
+// ─── Text Encoding ────────────────────────────────────────────────────────────
+
+const WIN_ANSI_HIGH: [(u8, char); 27] = [
+    (0x80, '€'),
+    (0x82, '‚'),
+    (0x83, 'ƒ'),
+    (0x84, '„'),
+    (0x85, '…'),
+    (0x86, '†'),
+    (0x87, '‡'),
+    (0x88, 'ˆ'),
+    (0x89, '‰'),
+    (0x8A, 'Š'),
+    (0x8B, '‹'),
+    (0x8C, 'Œ'),
+    (0x8E, 'Ž'),
+    (0x91, '‘'),
+    (0x92, '’'),
+    (0x93, '“'),
+    (0x94, '”'),
+    (0x95, '•'),
+    (0x96, '–'),
+    (0x97, '—'),
+    (0x98, '˜'),
+    (0x99, '™'),
+    (0x9A, 'š'),
+    (0x9B, '›'),
+    (0x9C, 'œ'),
+    (0x9E, 'ž'),
+    (0x9F, 'Ÿ'),
+];
+
+fn win_ansi_to_unicode(code: u8) -> Option<char> {
+    match code {
+        0x20..=0x7E | 0xA0..=0xFF => Some(code as char),
+        _ => WIN_ANSI_HIGH
+            .iter()
+            .find(|(c, _)| *c == code)
+            .map(|(_, ch)| *ch),
+    }
+}
+
+/// Encode text as WinAnsi bytes; unsupported characters become `?`
+pub fn encode_win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|ch| match ch as u32 {
+            0x20..=0x7E | 0xA0..=0xFF => ch as u8,
+            _ => WIN_ANSI_HIGH
+                .iter()
+                .find(|(_, c)| *c == ch)
+                .map(|(code, _)| *code)
+                .unwrap_or(b'?'),
+        })
+        .collect()
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// PDF text string: UTF-16BE with BOM, hex-encoded
+fn pdf_text_string(text: &str) -> String {
+    let mut hex = String::from("<FEFF");
+    for unit in text.encode_utf16() {
+        hex.push_str(&format!("{unit:04X}"));
+    }
+    hex.push('>');
+    hex
+}
+
+// ─── Layout ───────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FontStyle {
+    Regular,
+    Bold,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum DrawOp {
+    Text {
+        x: f32,
+        y: f32,
+        style: FontStyle,
+        size: f32,
+        text: String,
+    },
+    Line {
+        x1: f32,
+        x2: f32,
+        y: f32,
+        width: f32,
+    },
+}
+
+struct Layouter<'a> {
+    layout: &'a PageLayout,
+    assets: &'a PdfAssets,
+    pages: Vec<Vec<DrawOp>>,
+    cursor: f32,
+    top: f32,
+    bottom: f32,
+    width: f32,
+}
+
+impl<'a> Layouter<'a> {
+    fn new(layout: &'a PageLayout, assets: &'a PdfAssets) -> Self {
+        let (page_width, page_height) = layout.size.dimensions();
+        // Reserve room for the running header and the footer
+        let top = page_height - layout.margin - layout.body_size * 2.5;
+        let bottom = layout.margin + layout.footer_size * 4.0;
+        Self {
+            layout,
+            assets,
+            pages: vec![Vec::new()],
+            cursor: top,
+            top,
+            bottom,
+            width: page_width - 2.0 * layout.margin,
+        }
+    }
+
+    fn line_height(&self, size: f32) -> f32 {
+        size * self.layout.leading
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(Vec::new());
+        self.cursor = self.top;
+    }
+
+    fn page_is_empty(&self) -> bool {
+        self.pages.last().is_none_or(|ops| ops.is_empty())
+    }
+
+    fn ensure_space(&mut self, height: f32) {
+        if self.cursor - height < self.bottom && !self.page_is_empty() {
+            self.new_page();
+        }
+    }
+
+    /// Break `text` into lines no wider than the content width
+    fn wrap(&self, text: &str, size: f32) -> Vec<String> {
+        let indent: String = text.chars().take_while(|c| *c == ' ').collect();
+        let mut lines = Vec::new();
+        let mut current = indent.clone();
+        for word in text.split_whitespace() {
+            let candidate = if current.trim().is_empty() {
+                format!("{indent}{word}")
+            } else {
+                format!("{current} {word}")
+            };
+            if self.assets.text_width(&candidate, size) <= self.width {
+                current = candidate;
+                continue;
+            }
+            if !current.trim().is_empty() {
+                lines.push(std::mem::replace(&mut current, indent.clone()));
+            }
+            // Hard-break words longer than a line, such as addresses
+            let mut piece = indent.clone();
+            for ch in word.chars() {
+                let mut next = piece.clone();
+                next.push(ch);
+                if self.assets.text_width(&next, size) > self.width && piece.len() > indent.len() {
+                    lines.push(std::mem::replace(&mut piece, format!("{indent}{ch}")));
+                } else {
+                    piece = next;
+                }
+            }
+            current = piece;
+        }
+        if !current.trim().is_empty() || lines.is_empty() {
+            lines.push(current);
+        }
+        lines
+    }
+
+    fn height_of(&self, block: &Block) -> f32 {
+        let body = self.line_height(self.layout.body_size);
+        match block {
+            Block::Heading(_) => self.line_height(self.layout.heading_size) + body * 0.4,
+            Block::Paragraph(text) => self.wrap(text, self.layout.body_size).len() as f32 * body,
+            Block::Rule => body * 0.8,
+            Block::Spacer(h) => *h,
+            Block::KeepTogether(blocks) => blocks.iter().map(|b| self.height_of(b)).sum(),
+            Block::PageBreak => 0.0,
+            Block::SignatureLine { details, .. } => body * (3.0 + details.len() as f32),
+        }
+    }
+
+    fn push(&mut self, op: DrawOp) {
+        if let Some(page) = self.pages.last_mut() {
+            page.push(op);
+        }
+    }
+
+    fn text_line(&mut self, text: String, style: FontStyle, size: f32) {
+        let height = self.line_height(size);
+        self.ensure_space(height);
+        self.cursor -= height;
+        let x = self.layout.margin;
+        let y = self.cursor + (height - size);
+        self.push(DrawOp::Text {
+            x,
+            y,
+            style,
+            size,
+            text,
+        });
+    }
+
+    fn place(&mut self, blocks: &[Block]) {
+        for (i, block) in blocks.iter().enumerate() {
+            match block {
+                Block::Heading(text) => {
+                    // Keep headings with at least the start of what follows
+                    let next = blocks
+                        .get(i + 1)
+                        .map(|b| {
+                            self.height_of(b)
+                                .min(self.line_height(self.layout.body_size) * 3.0)
+                        })
+                        .unwrap_or(0.0);
+                    self.ensure_space(self.height_of(block) + next);
+                    self.text_line(text.clone(), FontStyle::Bold, self.layout.heading_size);
+                    self.cursor -= self.line_height(self.layout.body_size) * 0.4;
+                }
+                Block::Paragraph(text) => {
+                    for line in self.wrap(text, self.layout.body_size) {
+                        self.text_line(line, FontStyle::Regular, self.layout.body_size);
+                    }
+                }
+                Block::Rule => {
+                    let height = self.height_of(block);
+                    self.ensure_space(height);
+                    self.cursor -= height;
+                    let y = self.cursor + height / 2.0;
+                    self.push(DrawOp::Line {
+                        x1: self.layout.margin,
+                        x2: self.layout.margin + self.width,
+                        y,
+                        width: 0.5,
+                    });
+                }
+                Block::Spacer(h) => {
+                    if self.cursor - h < self.bottom {
+                        self.new_page();
+                    } else {
+                        self.cursor -= h;
+                    }
+                }
+                Block::KeepTogether(inner) => {
+                    let height = self.height_of(block);
+                    if height <= self.top - self.bottom {
+                        self.ensure_space(height);
+                    }
+                    self.place(inner);
+                }
+                Block::PageBreak => {
+                    if !self.page_is_empty() {
+                        self.new_page();
+                    }
+                }
+                Block::SignatureLine { label, details } => {
+                    self.ensure_space(self.height_of(block));
+                    let body = self.line_height(self.layout.body_size);
+                    self.cursor -= body * 2.0;
+                    let y = self.cursor;
+                    self.push(DrawOp::Line {
+                        x1: self.layout.margin,
+                        x2: self.layout.margin + self.width * 0.6,
+                        y,
+                        width: 0.75,
+                    });
+                    self.text_line(label.clone(), FontStyle::Bold, self.layout.body_size);
+                    for detail in details {
+                        for line in self.wrap(detail, self.layout.body_size) {
+                            self.text_line(line, FontStyle::Regular, self.layout.body_size);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Add the running header and footers once the page count is known
+    fn finish(mut self, meta: &DocumentMeta) -> Vec<Vec<DrawOp>> {
+        let total = self.pages.len();
+        let (page_width, page_height) = self.layout.size.dimensions();
+        let margin = self.layout.margin;
+        let footer = self.layout.footer_size;
+        for (index, page) in self.pages.iter_mut().enumerate() {
+            let header_y = page_height - margin - self.layout.body_size;
+            page.push(DrawOp::Text {
+                x: margin,
+                y: header_y,
+                style: FontStyle::Bold,
+                size: footer,
+                text: meta.running_header.clone(),
+            });
+            page.push(DrawOp::Line {
+                x1: margin,
+                x2: page_width - margin,
+                y: header_y - footer * 0.8,
+                width: 0.5,
+            });
+
+            let footer_y = margin + footer * 1.5;
+            page.push(DrawOp::Line {
+                x1: margin,
+                x2: page_width - margin,
+                y: footer_y + footer * 1.4,
+                width: 0.5,
+            });
+            page.push(DrawOp::Text {
+                x: margin,
+                y: footer_y,
+                style: FontStyle::Regular,
+                size: footer,
+                text: meta.footer_note.clone(),
+            });
+            let page_label = format!("Page {} of {}", index + 1, total);
+            let label_width = self.assets.text_width(&page_label, footer);
+            page.push(DrawOp::Text {
+                x: page_width - margin - label_width,
+                y: footer_y,
+                style: FontStyle::Regular,
+                size: footer,
+                text: page_label,
+            });
+            if let Some(detail) = &meta.footer_detail {
+                page.push(DrawOp::Text {
+                    x: margin,
+                    y: footer_y - footer * 1.3,
+                    style: FontStyle::Regular,
+                    size: footer * 0.85,
+                    text: detail.clone(),
+                });
+            }
+        }
+        self.pages
+    }
+}
+
+// ─── Serialisation ────────────────────────────────────────────────────────────
+
+fn content_stream(ops: &[DrawOp]) -> Vec<u8> {
+    let mut out = String::new();
+    for op in ops {
+        match op {
+            DrawOp::Text {
+                x,
+                y,
+                style,
+                size,
+                text,
+            } => {
+                let font = match style {
+                    FontStyle::Regular => "F1",
+                    FontStyle::Bold => "F2",
+                };
+                out.push_str(&format!(
+                    "BT /{font} {size:.2} Tf 1 0 0 1 {x:.2} {y:.2} Tm <{}> Tj ET\n",
+                    hex::encode_upper(encode_win_ansi(text))
+                ));
+            }
+            DrawOp::Line { x1, x2, y, width } => {
+                out.push_str(&format!(
+                    "{width:.2} w {x1:.2} {y:.2} m {x2:.2} {y:.2} l S\n"
+                ));
+            }
+        }
+    }
+    out.into_bytes()
+}
+
+fn xmp_packet(meta: &DocumentMeta, pdfa: bool) -> String {
+    let created = meta.created_at.format("%Y-%m-%dT%H:%M:%SZ");
+    let keywords = xml_escape(&meta.keywords.join(", "));
+    let custom: String = meta
+        .custom_properties
+        .iter()
+        .map(|(k, v)| format!("      <inheritx:{k}>{}</inheritx:{k}>\n", xml_escape(v)))
+        .collect();
+    let pdfa_parts = if pdfa {
+        let properties: String = meta
+            .custom_properties
+            .iter()
+            .map(|(k, _)| {
+                format!(
+                    "              <rdf:li rdf:parseType=\"Resource\">\n\
+                     \x20               <pdfaProperty:name>{k}</pdfaProperty:name>\n\
+                     \x20               <pdfaProperty:valueType>Text</pdfaProperty:valueType>\n\
+                     \x20               <pdfaProperty:category>external</pdfaProperty:category>\n\
+                     \x20               <pdfaProperty:description>{k}</pdfaProperty:description>\n\
+                     \x20             </rdf:li>\n"
+                )
+            })
+            .collect();
+        format!(
+            "      <pdfaid:part>1</pdfaid:part>\n\
+             \x20     <pdfaid:conformance>B</pdfaid:conformance>\n\
+             \x20     <pdfaExtension:schemas>\n\
+             \x20       <rdf:Bag>\n\
+             \x20         <rdf:li rdf:parseType=\"Resource\">\n\
+             \x20           <pdfaSchema:schema>INHERITX will metadata</pdfaSchema:schema>\n\
+             \x20           <pdfaSchema:namespaceURI>https://inheritx.io/ns/will/1.0/</pdfaSchema:namespaceURI>\n\
+             \x20           <pdfaSchema:prefix>inheritx</pdfaSchema:prefix>\n\
+             \x20           <pdfaSchema:property>\n\
+             \x20             <rdf:Seq>\n{properties}\
+             \x20             </rdf:Seq>\n\
+             \x20           </pdfaSchema:property>\n\
+             \x20         </rdf:li>\n\
+             \x20       </rdf:Bag>\n\
+             \x20     </pdfaExtension:schemas>\n"
+        )
+    } else {
+        String::new()
+    };
+    format!(
+        "<?xpacket begin=\"\u{FEFF}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\n\
+         <x:xmpmeta xmlns:x=\"adobe:ns:meta/\">\n\
+         \x20 <rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\n\
+         \x20   <rdf:Description rdf:about=\"\"\n\
+         \x20       xmlns:dc=\"http://purl.org/dc/elements/1.1/\"\n\
+         \x20       xmlns:xmp=\"http://ns.adobe.com/xap/1.0/\"\n\
+         \x20       xmlns:pdf=\"http://ns.adobe.com/pdf/1.3/\"\n\
+         \x20       xmlns:pdfaid=\"http://www.aiim.org/pdfa/ns/id/\"\n\
+         \x20       xmlns:pdfaExtension=\"http://www.aiim.org/pdfa/ns/extension/\"\n\
+         \x20       xmlns:pdfaSchema=\"http://www.aiim.org/pdfa/ns/schema#\"\n\
+         \x20       xmlns:pdfaProperty=\"http://www.aiim.org/pdfa/ns/property#\"\n\
+         \x20       xmlns:inheritx=\"https://inheritx.io/ns/will/1.0/\">\n\
+         \x20     <dc:format>application/pdf</dc:format>\n\
+         \x20     <dc:title><rdf:Alt><rdf:li xml:lang=\"x-default\">{title}</rdf:li></rdf:Alt></dc:title>\n\
+         \x20     <dc:creator><rdf:Seq><rdf:li>{author}</rdf:li></rdf:Seq></dc:creator>\n\
+         \x20     <dc:description><rdf:Alt><rdf:li xml:lang=\"x-default\">{subject}</rdf:li></rdf:Alt></dc:description>\n\
+         \x20     <xmp:CreateDate>{created}</xmp:CreateDate>\n\
+         \x20     <xmp:ModifyDate>{created}</xmp:ModifyDate>\n\
+         \x20     <xmp:CreatorTool>INHERITX</xmp:CreatorTool>\n\
+         \x20     <pdf:Producer>INHERITX</pdf:Producer>\n\
+         \x20     <pdf:Keywords>{keywords}</pdf:Keywords>\n\
+         {custom}{pdfa_parts}\
+         \x20   </rdf:Description>\n\
+         \x20 </rdf:RDF>\n\
+         </x:xmpmeta>\n\
+         <?xpacket end=\"w\"?>",
+        title = xml_escape(&meta.title),
+        author = xml_escape(&meta.author),
+        subject = xml_escape(&meta.subject),
+    )
+}
+
+struct PdfWriter {
+    buf: Vec<u8>,
+    offsets: Vec<usize>,
+}
+
+impl PdfWriter {
+    fn object(&mut self, id: usize, body: &[u8]) {
+        self.offsets[id] = self.buf.len();
+        self.buf
+            .extend_from_slice(format!("{id} 0 obj\n").as_bytes());
+        self.buf.extend_from_slice(body);
+        self.buf.extend_from_slice(b"\nendobj\n");
+    }
+
+    fn stream(&mut self, id: usize, dict: &str, data: &[u8]) {
+        let mut body = format!("<< {dict} /Length {} >>\nstream\n", data.len()).into_bytes();
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\nendstream");
+        self.object(id, &body);
+    }
+}
+
+/// Lay out `blocks` and serialise the result as PDF bytes
+pub fn render(
+    meta: &DocumentMeta,
+    blocks: &[Block],
+    layout: &PageLayout,
+    assets: &PdfAssets,
+) -> Vec<u8> {
+    let mut layouter = Layouter::new(layout, assets);
+    layouter.place(blocks);
+    let pages = layouter.finish(meta);
+    let (page_width, page_height) = layout.size.dimensions();
+    let pdfa = assets.pdfa_capable();
+
+    // Fixed objects, then one page + content stream pair per page
+    const CATALOG: usize = 1;
+    const PAGES: usize = 2;
+    const INFO: usize = 3;
+    const METADATA: usize = 4;
+    const FONT_REGULAR: usize = 5;
+    const FONT_BOLD: usize = 6;
+    const FONT_DESCRIPTOR: usize = 7;
+    const FONT_FILE: usize = 8;
+    const BOLD_DESCRIPTOR: usize = 9;
+    const BOLD_FILE: usize = 10;
+    const OUTPUT_PROFILE: usize = 11;
+    const FIRST_PAGE: usize = 12;
+    let object_count = FIRST_PAGE + pages.len() * 2;
+
+    let mut w = PdfWriter {
+        buf: b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec(),
+        offsets: vec![0; object_count],
+    };
+
+    let output_intent = if pdfa {
+        format!(
+            " /OutputIntents [<< /Type /OutputIntent /S /GTS_PDFA1 \
+             /OutputConditionIdentifier (sRGB) /DestOutputProfile {OUTPUT_PROFILE} 0 R >>]"
+        )
+    } else {
+        String::new()
+    };
+    w.object(
+        CATALOG,
+        format!("<< /Type /Catalog /Pages {PAGES} 0 R /Metadata {METADATA} 0 R{output_intent} >>")
+            .as_bytes(),
+    );
+
+    let kids: Vec<String> = (0..pages.len())
+        .map(|i| format!("{} 0 R", FIRST_PAGE + i * 2))
+        .collect();
+    w.object(
+        PAGES,
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        )
+        .as_bytes(),
+    );
+
+    let pdf_date = meta.created_at.format("D:%Y%m%d%H%M%SZ").to_string();
+    w.object(
+        INFO,
+        format!(
+            "<< /Title {} /Author {} /Subject {} /Keywords {} /Creator (INHERITX) \
+             /Producer (INHERITX) /CreationDate ({pdf_date}) /ModDate ({pdf_date}) >>",
+            pdf_text_string(&meta.title),
+            pdf_text_string(&meta.author),
+            pdf_text_string(&meta.subject),
+            pdf_text_string(&meta.keywords.join(", ")),
+        )
+        .as_bytes(),
+    );
+
+    w.stream(
+        METADATA,
+        "/Type /Metadata /Subtype /XML",
+        xmp_packet(meta, pdfa).as_bytes(),
+    );
+
+    match assets.embedded_fonts() {
+        Some((regular, bold)) => {
+            // Bold sets ForceBold (bit 19) and a heavier stem on top of Nonsymbolic
+            for (font, id, descriptor, file, flags, stem_v) in [
+                (regular, FONT_REGULAR, FONT_DESCRIPTOR, FONT_FILE, 32, 80),
+                (
+                    bold,
+                    FONT_BOLD,
+                    BOLD_DESCRIPTOR,
+                    BOLD_FILE,
+                    32 | 1 << 18,
+                    140,
+                ),
+            ] {
+                let widths: Vec<String> = font.widths.iter().map(|w| w.to_string()).collect();
+                w.object(
+                    id,
+                    format!(
+                        "<< /Type /Font /Subtype /TrueType /BaseFont /{} \
+                         /FirstChar 32 /LastChar 255 /Widths [{}] \
+                         /Encoding /WinAnsiEncoding /FontDescriptor {descriptor} 0 R >>",
+                        font.name,
+                        widths.join(" ")
+                    )
+                    .as_bytes(),
+                );
+                let [x_min, y_min, x_max, y_max] = font.bbox;
+                w.object(
+                    descriptor,
+                    format!(
+                        "<< /Type /FontDescriptor /FontName /{} /Flags {flags} \
+                         /FontBBox [{x_min} {y_min} {x_max} {y_max}] /ItalicAngle 0 \
+                         /Ascent {} /Descent {} /CapHeight {} /StemV {stem_v} \
+                         /FontFile2 {file} 0 R >>",
+                        font.name, font.ascent, font.descent, font.ascent
+                    )
+                    .as_bytes(),
+                );
+                w.stream(
+                    file,
+                    &format!("/Length1 {}", font.program.len()),
+                    &font.program,
+                );
+            }
+        }
+        None => {
+            w.object(
+                FONT_REGULAR,
+                b"<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>",
+            );
+            w.object(
+                FONT_BOLD,
+                b"<< /Type /Font /Subtype /Type1 /BaseFont /Courier-Bold /Encoding /WinAnsiEncoding >>",
+            );
+            for id in [FONT_DESCRIPTOR, FONT_FILE, BOLD_DESCRIPTOR, BOLD_FILE] {
+                w.object(id, b"null");
+            }
+        }
+    }
+
+    match (&assets.icc_profile, pdfa) {
+        (Some(profile), true) => w.stream(OUTPUT_PROFILE, "/N 3", profile),
+        _ => w.object(OUTPUT_PROFILE, b"null"),
+    }
+
+    for (i, ops) in pages.iter().enumerate() {
+        let page_id = FIRST_PAGE + i * 2;
+        let content_id = page_id + 1;
+        w.object(
+            page_id,
+            format!(
+                "<< /Type /Page /Parent {PAGES} 0 R /MediaBox [0 0 {page_width} {page_height}] \
+                 /Contents {content_id} 0 R \
+                 /Resources << /Font << /F1 {FONT_REGULAR} 0 R /F2 {FONT_BOLD} 0 R >> >> >>"
+            )
+            .as_bytes(),
+        );
+        w.stream(content_id, "", &content_stream(ops));
+    }
+
+    let xref_offset = w.buf.len();
+    let mut xref = format!("xref\n0 {object_count}\n0000000000 65535 f \n");
+    for offset in &w.offsets[1..] {
+        xref.push_str(&format!("{offset:010} 00000 n \n"));
+    }
+    w.buf.extend_from_slice(xref.as_bytes());
+
+    let id = hex::encode_upper(&meta.document_id);
+    w.buf.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {object_count} /Root {CATALOG} 0 R /Info {INFO} 0 R \
+             /ID [<{id}> <{id}>] >>\nstartxref\n{xref_offset}\n%%EOF\n"
+        )
+        .as_bytes(),
+    );
+    w.buf
+}
+
+/// Number of pages `blocks` occupy
+pub fn page_count(blocks: &[Block], layout: &PageLayout, assets: &PdfAssets) -> usize {
+    let mut layouter = Layouter::new(layout, assets);
+    layouter.place(blocks);
+    layouter.pages.len()
+}
+
+// ─── Unit Tests ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta() -> DocumentMeta {
+        DocumentMeta {
+            title: "Test Will".to_string(),
+            author: "Alice".to_string(),
+            subject: "Last will".to_string(),
+            keywords: vec!["will".to_string()],
+            created_at: DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            running_header: "TEST WILL".to_string(),
+            footer_note: "Plan ID: 42".to_string(),
+            footer_detail: Some("Will hash: abc".to_string()),
+            custom_properties: vec![("PlanId".to_string(), "42".to_string())],
+            document_id: vec![1, 2, 3, 4],
+        }
+    }
+
+    fn count(haystack: &[u8], needle: &[u8]) -> usize {
+        haystack
+            .windows(needle.len())
+            .filter(|w| *w == needle)
+            .count()
+    }
+
+    #[test]
+    fn long_content_paginates_with_page_numbers() {
+        let blocks: Vec<Block> = (0..200)
+            .map(|i| Block::Paragraph(format!("Line {i} of a long beneficiary list")))
+            .collect();
+        let assets = PdfAssets::default();
+        let layout = PageLayout::default();
+        let pages = page_count(&blocks, &layout, &assets);
+        assert!(pages > 1);
+
+        let pdf = render(&meta(), &blocks, &layout, &assets);
+        assert_eq!(count(&pdf, b"/Type /Page "), pages);
+        let last = format!("Page {pages} of {pages}");
+        assert_eq!(
+            count(&pdf, hex::encode_upper(encode_win_ansi(&last)).as_bytes()),
+            1
+        );
+    }
+
+    #[test]
+    fn paragraphs_wrap_to_content_width() {
+        let assets = PdfAssets::default();
+        let layout = PageLayout::default();
+        let layouter = Layouter::new(&layout, &assets);
+        let text = "word ".repeat(100);
+        let lines = layouter.wrap(&text, layout.body_size);
+        assert!(lines.len() > 1);
+        for line in &lines {
+            assert!(assets.text_width(line, layout.body_size) <= layouter.width);
+        }
+
+        let address = "G".repeat(200);
+        let lines = layouter.wrap(&address, layout.body_size);
+        assert_eq!(lines.concat(), address);
+    }
+
+    #[test]
+    fn page_break_and_keep_together() {
+        let assets = PdfAssets::default();
+        let layout = PageLayout::default();
+        let blocks = vec![
+            Block::Paragraph("first".to_string()),
+            Block::PageBreak,
+            Block::Paragraph("second".to_string()),
+        ];
+        assert_eq!(page_count(&blocks, &layout, &assets), 2);
+
+        // Leave room for two lines at the bottom of the first page
+        let layouter = Layouter::new(&layout, &assets);
+        let per_page =
+            ((layouter.top - layouter.bottom) / layouter.line_height(layout.body_size)) as usize;
+        let mut blocks: Vec<Block> = (0..per_page - 2)
+            .map(|_| Block::Paragraph("filler".to_string()))
+            .collect();
+        blocks.push(Block::KeepTogether(vec![
+            Block::Paragraph("a".to_string()),
+            Block::Paragraph("b".to_string()),
+            Block::Paragraph("c".to_string()),
+            Block::Paragraph("d".to_string()),
+        ]));
+        let mut layouter = Layouter::new(&layout, &assets);
+        layouter.place(&blocks);
+        assert_eq!(layouter.pages.len(), 2);
+        assert_eq!(layouter.pages[1].len(), 4);
+    }
+
+    #[test]
+    fn metadata_carries_custom_properties_and_is_deterministic() {
+        let blocks = vec![Block::Heading("Title".to_string())];
+        let assets = PdfAssets::default();
+        let a = render(&meta(), &blocks, &PageLayout::default(), &assets);
+        let b = render(&meta(), &blocks, &PageLayout::default(), &assets);
+        assert_eq!(a, b);
+        assert_eq!(count(&a, b"<inheritx:PlanId>42</inheritx:PlanId>"), 1);
+        assert_eq!(count(&a, b"/ID [<01020304> <01020304>]"), 1);
+        // Without an embedded font and ICC profile, PDF/A is not claimed
+        assert_eq!(count(&a, b"pdfaid:part"), 0);
+    }
+
+    #[test]
+    fn win_ansi_encoding_covers_latin_accents() {
+        assert_eq!(encode_win_ansi("é"), vec![0xE9]);
+        assert_eq!(encode_win_ansi("€"), vec![0x80]);
+        assert_eq!(encode_win_ansi("漢"), vec![b'?']);
+        assert_eq!(win_ansi_to_unicode(0x93), Some('“'));
+    }
+
+    #[test]
+    fn truetype_parser_rejects_garbage() {
+        assert!(EmbeddedFont::from_truetype("x", vec![0; 32]).is_err());
+    }
+
+    #[test]
+    fn embedded_assets_claim_pdfa() {
+        let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+        let font = |name: &str| {
+            let program = std::fs::read(format!("{fixtures}/{name}.ttf")).unwrap();
+            EmbeddedFont::from_truetype(name, program).unwrap()
+        };
+        let mut assets = PdfAssets {
+            font: Some(font("DejaVuSansMono")),
+            bold_font: None,
+            icc_profile: Some(std::fs::read(format!("{fixtures}/srgb_test.icc")).unwrap()),
+        };
+        // A regular face alone would have to stand in for bold
+        assert!(!assets.pdfa_capable());
+        assets.bold_font = Some(font("DejaVuSansMono-Bold"));
+        assert!(assets.pdfa_capable());
+        let pdf = render(
+            &meta(),
+            &[Block::Paragraph("Testament".to_string())],
+            &PageLayout::default(),
+            &assets,
+        );
+        assert_eq!(count(&pdf, b"<pdfaid:part>1</pdfaid:part>"), 1);
+        assert_eq!(count(&pdf, b"/OutputIntents"), 1);
+        assert_eq!(count(&pdf, b"/FontFile2"), 2);
+        assert_eq!(count(&pdf, b"/BaseFont /DejaVuSansMono-Bold "), 1);
+    }
+
+    #[test]
+    fn missing_asset_paths_are_reported() {
+        let err = required_path("WILL_PDF_UNSET_FOR_TEST", "a TrueType font").unwrap_err();
+        assert!(err.contains("WILL_PDF_UNSET_FOR_TEST must be set"));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::api_error::ApiError;
-use crate::external_price_fetcher::RedundantPriceFetcher;
+use crate::external_price_fetcher::{AggregatedPrice, RedundantPriceFetcher, SourceStats};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
@@ -14,11 +14,15 @@ use uuid::Uuid;
 
 /// Price feed source types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "snake_case")]
 pub enum PriceFeedSource {
     Pyth,
     Chainlink,
     Custom,
+    /// Stellar DEX order book and trades via Horizon
+    Sdex,
+    /// SEP-40 oracle contract via Soroban RPC
+    SorobanOracle,
 }
 
 impl PriceFeedSource {
@@ -27,6 +31,8 @@ impl PriceFeedSource {
             PriceFeedSource::Pyth => "pyth",
             PriceFeedSource::Chainlink => "chainlink",
             PriceFeedSource::Custom => "custom",
+            PriceFeedSource::Sdex => "sdex",
+            PriceFeedSource::SorobanOracle => "soroban_oracle",
         }
     }
 }
@@ -41,7 +47,7 @@ pub struct AssetPrice {
 }
 
 /// Price feed configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct PriceFeedConfig {
     pub id: Uuid,
     pub asset_code: String,
@@ -49,6 +55,54 @@ pub struct PriceFeedConfig {
     pub feed_id: String,
     pub is_active: bool,
     pub last_updated: Option<DateTime<Utc>>,
+    /// Seconds between scheduled refreshes; 0 leaves the price to admins
+    pub refresh_interval_secs: i32,
+    /// Age after which the price is stale and new borrows are paused
+    pub max_staleness_secs: i32,
+    pub twap_window_secs: i32,
+    pub ema_half_life_secs: i32,
+    /// Set while the stale-price circuit breaker is tripped
+    pub borrows_paused_at: Option<DateTime<Utc>>,
+    /// Whether the oracle pusher mirrors this feed on-chain
+    pub oracle_push_enabled: bool,
+}
+
+/// Partial update of a feed's refresh schedule and averaging windows
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FeedScheduleUpdate {
+    pub refresh_interval_secs: Option<i32>,
+    pub max_staleness_secs: Option<i32>,
+    pub twap_window_secs: Option<i32>,
+    pub ema_half_life_secs: Option<i32>,
+    pub oracle_push_enabled: Option<bool>,
+}
+
+/// Spot price alongside its time-weighted and exponential moving averages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceAverages {
+    pub asset_code: String,
+    pub spot: Decimal,
+    pub spot_timestamp: DateTime<Utc>,
+    pub twap: Decimal,
+    pub ema: Decimal,
+    pub twap_window_secs: i32,
+    pub ema_half_life_secs: i32,
+    /// History points that fed the averages
+    pub samples: usize,
+    pub is_stale: bool,
+    pub borrows_paused: bool,
+}
+
+/// Raised when external price sources disagree
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PriceSourceAlert {
+    pub id: Uuid,
+    pub asset_code: String,
+    pub median_price: Option<Decimal>,
+    pub spread: Decimal,
+    pub accepted_sources: i32,
+    pub quotes: serde_json::Value,
+    pub created_at: DateTime<Utc>,
 }
 
 /// Collateral valuation data
@@ -99,6 +153,103 @@ pub trait PriceFeedService: Send + Sync {
 
     /// Get all active price feeds
     async fn get_active_feeds(&self) -> Result<Vec<PriceFeedConfig>, ApiError>;
+
+    /// Quality counters for each external price source
+    fn source_stats(&self) -> Vec<SourceStats>;
+
+    /// Spot, TWAP and EMA for an asset over its feed's configured windows
+    async fn get_price_averages(&self, asset_code: &str) -> Result<PriceAverages, ApiError>;
+
+    /// Time-weighted average price, used for risk decisions instead of spot
+    async fn get_twap_price(&self, asset_code: &str) -> Result<AssetPrice, ApiError> {
+        let averages = self.get_price_averages(asset_code).await?;
+        Ok(AssetPrice {
+            asset_code: averages.asset_code,
+            price: averages.twap,
+            timestamp: averages.spot_timestamp,
+            source: "twap".to_string(),
+        })
+    }
+
+    /// Change a feed's refresh schedule or averaging windows
+    async fn update_feed_schedule(
+        &self,
+        asset_code: &str,
+        update: &FeedScheduleUpdate,
+    ) -> Result<PriceFeedConfig, ApiError>;
+}
+
+const FEED_COLUMNS: &str = "id, asset_code, source, feed_id, is_active, last_updated, \
+     refresh_interval_secs, max_staleness_secs, twap_window_secs, ema_half_life_secs, \
+     borrows_paused_at, oracle_push_enabled";
+
+/// Number of EMA half-lives of history loaded; older prices weigh under 0.4%
+const EMA_LOOKBACK_HALF_LIVES: i64 = 8;
+
+/// Time-weighted average of a step price series over `[start, end]`.
+///
+/// `points` must be in ascending time order. Each price holds until the next
+/// point, so a point before `start` sets the price the window opens at.
+pub fn time_weighted_average(
+    points: &[(DateTime<Utc>, Decimal)],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Option<Decimal> {
+    let mut weighted = Decimal::ZERO;
+    let mut total_ms = 0i64;
+    for (i, (at, price)) in points.iter().enumerate() {
+        let seg_start = (*at).max(start);
+        let seg_end = points.get(i + 1).map_or(end, |(next, _)| (*next).min(end));
+        let ms = seg_end.signed_duration_since(seg_start).num_milliseconds();
+        if ms > 0 {
+            weighted += *price * Decimal::from(ms);
+            total_ms += ms;
+        }
+    }
+
+    if total_ms == 0 {
+        return points
+            .iter()
+            .rev()
+            .find(|(at, _)| *at <= end)
+            .map(|(_, price)| *price);
+    }
+    Some((weighted / Decimal::from(total_ms)).round_dp(8))
+}
+
+/// Continuous-time exponential moving average of a step price series.
+///
+/// Each price is weighted by how long it held, decaying by half every
+/// `half_life_secs`, up to `end`.
+pub fn exponential_moving_average(
+    points: &[(DateTime<Utc>, Decimal)],
+    half_life_secs: i64,
+    end: DateTime<Utc>,
+) -> Option<Decimal> {
+    let (first_at, first_price) = points.first()?;
+    let half_life_ms = (half_life_secs.max(1) * 1000) as f64;
+
+    let mut ema = *first_price;
+    let mut held = (*first_at, *first_price);
+    for (at, price) in points
+        .iter()
+        .skip(1)
+        .chain(std::iter::once(&(end, Decimal::ZERO)))
+    {
+        let ms = at
+            .min(&end)
+            .signed_duration_since(held.0)
+            .num_milliseconds();
+        if ms > 0 {
+            let alpha = 1.0 - 0.5f64.powf(ms as f64 / half_life_ms);
+            let alpha = Decimal::from_f64_retain(alpha)
+                .unwrap_or(Decimal::ONE)
+                .round_dp(12);
+            ema += (held.1 - ema) * alpha;
+        }
+        held = (*at, *price);
+    }
+    Some(ema.round_dp(8))
 }
 
 /// In-memory price cache with database persistence and external price fetching
@@ -157,6 +308,140 @@ impl DefaultPriceFeedService {
 
         Ok(())
     }
+
+    /// Record an admin alert for sources that disagree.
+    async fn record_source_alert(&self, aggregated: &AggregatedPrice) -> Result<(), ApiError> {
+        sqlx::query(
+            r#"
+            INSERT INTO price_source_alerts (asset_code, median_price, spread, accepted_sources, quotes)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(&aggregated.asset_code)
+        .bind(aggregated.price)
+        .bind(aggregated.spread)
+        .bind(aggregated.accepted_sources as i32)
+        .bind(serde_json::to_value(&aggregated.quotes).unwrap_or_default())
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn feed_config(&self, asset_code: &str) -> Result<PriceFeedConfig, ApiError> {
+        sqlx::query_as::<_, PriceFeedConfig>(&format!(
+            "SELECT {} FROM price_feeds WHERE asset_code = $1",
+            FEED_COLUMNS
+        ))
+        .bind(asset_code)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| {
+            ApiError::NotFound(format!("Price feed not found for asset: {}", asset_code))
+        })
+    }
+
+    /// Ascending price history from `since`, plus the last point before it
+    async fn load_series(
+        &self,
+        asset_code: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<(DateTime<Utc>, Decimal)>, ApiError> {
+        let rows = sqlx::query_as::<_, (Decimal, DateTime<Utc>)>(
+            r#"
+            SELECT price, price_timestamp FROM (
+                (SELECT price, price_timestamp FROM asset_price_history
+                 WHERE asset_code = $1 AND price_timestamp < $2
+                 ORDER BY price_timestamp DESC LIMIT 1)
+                UNION ALL
+                (SELECT price, price_timestamp FROM asset_price_history
+                 WHERE asset_code = $1 AND price_timestamp >= $2)
+            ) series
+            ORDER BY price_timestamp
+            "#,
+        )
+        .bind(asset_code)
+        .bind(since)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(rows.into_iter().map(|(price, at)| (at, price)).collect())
+    }
+
+    /// Active feeds whose refresh interval has elapsed
+    pub async fn feeds_due_for_refresh(&self) -> Result<Vec<String>, ApiError> {
+        let assets = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT asset_code FROM price_feeds
+            WHERE is_active = true
+              AND refresh_interval_secs > 0
+              AND (last_updated IS NULL
+                   OR last_updated <= NOW() - make_interval(secs => refresh_interval_secs))
+            ORDER BY last_updated NULLS FIRST
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await?;
+        Ok(assets)
+    }
+
+    /// Trip the stale-price circuit breaker for feeds whose latest price is
+    /// older than their staleness limit, and reset it for feeds that have
+    /// recovered. Returns `(paused, resumed)` asset codes.
+    pub async fn enforce_staleness(&self) -> Result<(Vec<String>, Vec<String>), ApiError> {
+        let paused = sqlx::query_scalar::<_, String>(
+            r#"
+            UPDATE price_feeds SET borrows_paused_at = NOW()
+            WHERE is_active = true
+              AND borrows_paused_at IS NULL
+              AND (last_updated IS NULL
+                   OR last_updated < NOW() - make_interval(secs => max_staleness_secs))
+            RETURNING asset_code
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        let resumed = sqlx::query_scalar::<_, String>(
+            r#"
+            UPDATE price_feeds SET borrows_paused_at = NULL
+            WHERE borrows_paused_at IS NOT NULL
+              AND (is_active = false
+                   OR last_updated >= NOW() - make_interval(secs => max_staleness_secs))
+            RETURNING asset_code
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        for asset in &paused {
+            warn!("Price for {} is stale; pausing new borrows", asset);
+        }
+        for asset in &resumed {
+            info!("Price for {} is fresh again; resuming new borrows", asset);
+        }
+        Ok((paused, resumed))
+    }
+
+    /// Most recent source disagreement alerts, optionally for one asset
+    pub async fn list_source_alerts(
+        db: &PgPool,
+        asset_code: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<PriceSourceAlert>, ApiError> {
+        let alerts = sqlx::query_as::<_, PriceSourceAlert>(
+            r#"
+            SELECT id, asset_code, median_price, spread, accepted_sources, quotes, created_at
+            FROM price_source_alerts
+            WHERE $1::text IS NULL OR asset_code = $1
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(asset_code)
+        .bind(limit)
+        .fetch_all(db)
+        .await?;
+        Ok(alerts)
+    }
 }
 
 #[async_trait]
@@ -276,21 +561,21 @@ impl PriceFeedService for DefaultPriceFeedService {
         source: PriceFeedSource,
         feed_id: &str,
     ) -> Result<PriceFeedConfig, ApiError> {
-        let id = Uuid::new_v4();
-
-        sqlx::query(
+        let config = sqlx::query_as::<_, PriceFeedConfig>(&format!(
             r#"
             INSERT INTO price_feeds (id, asset_code, source, feed_id, is_active)
             VALUES ($1, $2, $3, $4, true)
             ON CONFLICT (asset_code) DO UPDATE
             SET source = $3, feed_id = $4, is_active = true, updated_at = CURRENT_TIMESTAMP
+            RETURNING {}
             "#,
-        )
-        .bind(id)
+            FEED_COLUMNS
+        ))
+        .bind(Uuid::new_v4())
         .bind(asset_code)
         .bind(source.as_str())
         .bind(feed_id)
-        .execute(&self.db)
+        .fetch_one(&self.db)
         .await
         .map_err(|e| {
             error!("Failed to register price feed: {}", e);
@@ -303,14 +588,7 @@ impl PriceFeedService for DefaultPriceFeedService {
             source.as_str()
         );
 
-        Ok(PriceFeedConfig {
-            id,
-            asset_code: asset_code.to_string(),
-            source: source.as_str().to_string(),
-            feed_id: feed_id.to_string(),
-            is_active: true,
-            last_updated: None,
-        })
+        Ok(config)
     }
 
     async fn update_price(&self, asset_code: &str, price: Decimal) -> Result<AssetPrice, ApiError> {
@@ -378,8 +656,18 @@ impl PriceFeedService for DefaultPriceFeedService {
     }
 
     async fn fetch_and_update_price(&self, asset_code: &str) -> Result<AssetPrice, ApiError> {
-        // Fetch price from external sources
-        let external_price = self.external_fetcher.fetch_price(asset_code).await?;
+        // Take the median of all external sources that agree
+        let aggregated = self.external_fetcher.fetch_aggregated(asset_code).await;
+        if aggregated.disagreement {
+            warn!(
+                "Price sources disagree on {}: spread {}, {} accepted",
+                asset_code, aggregated.spread, aggregated.accepted_sources
+            );
+            if let Err(e) = self.record_source_alert(&aggregated).await {
+                error!("Failed to record price source alert: {}", e);
+            }
+        }
+        let external_price = self.external_fetcher.published_price(&aggregated)?;
 
         // Store in database
         let now = Utc::now();
@@ -454,39 +742,214 @@ impl PriceFeedService for DefaultPriceFeedService {
     }
 
     async fn get_active_feeds(&self) -> Result<Vec<PriceFeedConfig>, ApiError> {
-        let feeds = sqlx::query_as::<_, (Uuid, String, String, String, Option<String>)>(
-            r#"
-            SELECT id, asset_code, source, feed_id, last_updated::text
-            FROM price_feeds
-            WHERE is_active = true
-            ORDER BY asset_code
-            "#,
-        )
+        sqlx::query_as::<_, PriceFeedConfig>(&format!(
+            "SELECT {} FROM price_feeds WHERE is_active = true ORDER BY asset_code",
+            FEED_COLUMNS
+        ))
         .fetch_all(&self.db)
         .await
         .map_err(|e| {
             error!("Failed to fetch active feeds: {}", e);
             ApiError::Internal(anyhow::anyhow!("Database error"))
+        })
+    }
+
+    fn source_stats(&self) -> Vec<SourceStats> {
+        self.external_fetcher.source_stats()
+    }
+
+    async fn get_price_averages(&self, asset_code: &str) -> Result<PriceAverages, ApiError> {
+        let feed = self.feed_config(asset_code).await?;
+        let now = Utc::now();
+        let twap_start = now - chrono::Duration::seconds(feed.twap_window_secs as i64);
+        let ema_start = now
+            - chrono::Duration::seconds(feed.ema_half_life_secs as i64 * EMA_LOOKBACK_HALF_LIVES);
+        let series = self
+            .load_series(asset_code, twap_start.min(ema_start))
+            .await?;
+
+        let (spot_timestamp, spot) = *series.last().ok_or_else(|| {
+            ApiError::NotFound(format!("Price not found for asset: {}", asset_code))
         })?;
+        let twap_from = series
+            .partition_point(|(at, _)| *at < twap_start)
+            .saturating_sub(1);
+        let twap = time_weighted_average(&series[twap_from..], twap_start, now).unwrap_or(spot);
+        let ema = exponential_moving_average(&series, feed.ema_half_life_secs as i64, now)
+            .unwrap_or(spot);
+
+        Ok(PriceAverages {
+            asset_code: asset_code.to_string(),
+            spot,
+            spot_timestamp,
+            twap,
+            ema,
+            twap_window_secs: feed.twap_window_secs,
+            ema_half_life_secs: feed.ema_half_life_secs,
+            samples: series.len(),
+            is_stale: now.signed_duration_since(spot_timestamp).num_seconds()
+                > feed.max_staleness_secs as i64,
+            borrows_paused: feed.borrows_paused_at.is_some(),
+        })
+    }
 
-        let mut result = Vec::new();
-        for (id, asset_code, source, feed_id, last_updated_str) in feeds {
-            let last_updated = last_updated_str.and_then(|ts| {
-                chrono::DateTime::parse_from_rfc3339(&ts)
-                    .map(|dt| dt.with_timezone(&chrono::Utc))
-                    .ok()
-            });
+    async fn update_feed_schedule(
+        &self,
+        asset_code: &str,
+        update: &FeedScheduleUpdate,
+    ) -> Result<PriceFeedConfig, ApiError> {
+        if update.refresh_interval_secs.is_some_and(|v| v < 0) {
+            return Err(ApiError::BadRequest(
+                "refresh_interval_secs must not be negative".to_string(),
+            ));
+        }
+        for (name, value) in [
+            ("max_staleness_secs", update.max_staleness_secs),
+            ("twap_window_secs", update.twap_window_secs),
+            ("ema_half_life_secs", update.ema_half_life_secs),
+        ] {
+            if value.is_some_and(|v| v <= 0) {
+                return Err(ApiError::BadRequest(format!(
+                    "{} must be greater than zero",
+                    name
+                )));
+            }
+        }
 
-            result.push(PriceFeedConfig {
-                id,
-                asset_code,
-                source,
-                feed_id,
-                is_active: true,
-                last_updated,
-            });
+        sqlx::query_as::<_, PriceFeedConfig>(&format!(
+            r#"
+            UPDATE price_feeds
+            SET refresh_interval_secs = COALESCE($2, refresh_interval_secs),
+                max_staleness_secs = COALESCE($3, max_staleness_secs),
+                twap_window_secs = COALESCE($4, twap_window_secs),
+                ema_half_life_secs = COALESCE($5, ema_half_life_secs),
+                oracle_push_enabled = COALESCE($6, oracle_push_enabled),
+                updated_at = CURRENT_TIMESTAMP
+            WHERE asset_code = $1
+            RETURNING {}
+            "#,
+            FEED_COLUMNS
+        ))
+        .bind(asset_code)
+        .bind(update.refresh_interval_secs)
+        .bind(update.max_staleness_secs)
+        .bind(update.twap_window_secs)
+        .bind(update.ema_half_life_secs)
+        .bind(update.oracle_push_enabled)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| {
+            ApiError::NotFound(format!("Price feed not found for asset: {}", asset_code))
+        })
+    }
+}
+
+/// Outcome of one refresh scheduler pass
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct PriceRefreshRun {
+    pub refreshed: Vec<String>,
+    pub failed: Vec<String>,
+    pub borrows_paused: Vec<String>,
+    pub borrows_resumed: Vec<String>,
+}
+
+/// Refreshes each active feed on its own interval and trips the stale-price
+/// circuit breaker for feeds that fall behind.
+pub struct PriceRefreshScheduler {
+    price_feed: Arc<DefaultPriceFeedService>,
+    tick: std::time::Duration,
+}
+
+impl PriceRefreshScheduler {
+    /// Checks for due feeds every `PRICE_REFRESH_TICK_SECS` (default 5).
+    pub fn new(price_feed: Arc<DefaultPriceFeedService>) -> Self {
+        let tick_secs = std::env::var("PRICE_REFRESH_TICK_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(5);
+        Self {
+            price_feed,
+            tick: std::time::Duration::from_secs(tick_secs),
+        }
+    }
+
+    pub fn start(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.tick);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.run_once().await {
+                    error!("Price refresh scheduler error: {}", e);
+                }
+            }
+        });
+    }
+
+    pub async fn run_once(&self) -> Result<PriceRefreshRun, ApiError> {
+        let mut run = PriceRefreshRun::default();
+        for asset_code in self.price_feed.feeds_due_for_refresh().await? {
+            match self.price_feed.fetch_and_update_price(&asset_code).await {
+                Ok(_) => run.refreshed.push(asset_code),
+                Err(e) => {
+                    warn!("Scheduled price refresh failed for {}: {}", asset_code, e);
+                    run.failed.push(asset_code);
+                }
+            }
         }
 
-        Ok(result)
+        let (paused, resumed) = self.price_feed.enforce_staleness().await?;
+        run.borrows_paused = paused;
+        run.borrows_resumed = resumed;
+        Ok(run)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    #[test]
+    fn test_twap_weights_prices_by_duration() {
+        // 100 for 30s, then 200 for 10s
+        let points = vec![(at(0), dec!(100)), (at(30), dec!(200))];
+        let twap = time_weighted_average(&points, at(0), at(40)).unwrap();
+        assert_eq!(twap, dec!(125));
+    }
+
+    #[test]
+    fn test_twap_carries_price_from_before_window() {
+        let points = vec![(at(-100), dec!(50)), (at(10), dec!(150))];
+        let twap = time_weighted_average(&points, at(0), at(20)).unwrap();
+        assert_eq!(twap, dec!(100));
+
+        assert_eq!(
+            time_weighted_average(&[(at(0), dec!(7))], at(0), at(0)),
+            Some(dec!(7))
+        );
+        assert_eq!(time_weighted_average(&[], at(0), at(10)), None);
+    }
+
+    #[test]
+    fn test_ema_decays_towards_latest_price() {
+        // After one half-life at 200 the EMA is halfway from 100
+        let points = vec![(at(0), dec!(100)), (at(10), dec!(200))];
+        let ema = exponential_moving_average(&points, 60, at(70)).unwrap();
+        assert_eq!(ema, dec!(150));
+
+        // A brief spike barely moves a long EMA
+        let spike = vec![
+            (at(0), dec!(100)),
+            (at(599), dec!(1000)),
+            (at(600), dec!(100)),
+        ];
+        let ema = exponential_moving_average(&spike, 3600, at(600)).unwrap();
+        assert!(ema < dec!(101));
     }
 }
@@ -1,7 +1,11 @@
 use crate::api_error::ApiError;
 use crate::auth::AuthenticatedAdmin;
-use crate::price_feed::{PriceFeedService, PriceFeedSource};
-use axum::extract::{Path, State};
+use crate::oracle_pusher::OraclePusher;
+use crate::price_feed::{
+    DefaultPriceFeedService, FeedScheduleUpdate, PriceFeedService, PriceFeedSource,
+};
+use crate::stellar_asset::AssetId;
+use axum::extract::{Path, Query, State};
 use axum::Json;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
@@ -109,30 +113,58 @@ pub async fn get_price_history(
     })))
 }
 
+/// Spot price with its TWAP and EMA over the feed's configured windows
+pub async fn get_price_averages(
+    State((_db, price_service)): State<(PgPool, Arc<dyn PriceFeedService>)>,
+    Path(asset_code): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let averages = price_service.get_price_averages(&asset_code).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": averages
+    })))
+}
+
 /// Register a new price feed (admin only)
 pub async fn register_price_feed(
     State((_db, price_service)): State<(PgPool, Arc<dyn PriceFeedService>)>,
     AuthenticatedAdmin(_admin): AuthenticatedAdmin,
     Json(req): Json<RegisterFeedRequest>,
 ) -> Result<Json<Value>, ApiError> {
-    let source = match req.source.to_lowercase().as_str() {
-        "pyth" => PriceFeedSource::Pyth,
-        "chainlink" => PriceFeedSource::Chainlink,
-        "custom" => PriceFeedSource::Custom,
-        _ => {
-            return Err(ApiError::BadRequest(
-                "Invalid source. Must be 'pyth', 'chainlink', or 'custom'".to_string(),
-            ))
-        }
-    };
+    let source =
+        match req.source.to_lowercase().as_str() {
+            "pyth" => PriceFeedSource::Pyth,
+            "chainlink" => PriceFeedSource::Chainlink,
+            "custom" => PriceFeedSource::Custom,
+            "sdex" => PriceFeedSource::Sdex,
+            "soroban_oracle" => PriceFeedSource::SorobanOracle,
+            _ => return Err(ApiError::BadRequest(
+                "Invalid source. Must be 'pyth', 'chainlink', 'custom', 'sdex' or 'soroban_oracle'"
+                    .to_string(),
+            )),
+        };
+
+    // Feeds are keyed by canonical `CODE:ISSUER` (or a bare exchange ticker)
+    let asset: AssetId = req.asset_code.parse()?;
+    if matches!(
+        source,
+        PriceFeedSource::Sdex | PriceFeedSource::SorobanOracle
+    ) && !asset.is_stellar()
+    {
+        return Err(ApiError::BadRequest(format!(
+            "{} feeds need a Stellar asset as CODE:ISSUER",
+            source.as_str()
+        )));
+    }
 
     let config = price_service
-        .register_feed(&req.asset_code, source, &req.feed_id)
+        .register_feed(&asset.to_string(), source, &req.feed_id)
         .await?;
 
     Ok(Json(json!({
         "status": "success",
-        "message": format!("Price feed registered for {}", req.asset_code),
+        "message": format!("Price feed registered for {}", config.asset_code),
         "data": {
             "id": config.id,
             "asset_code": config.asset_code,
@@ -283,3 +315,81 @@ pub async fn fetch_and_update_price(
         }
     })))
 }
+
+/// Quality counters for each external price source (admin only)
+pub async fn get_price_source_stats(
+    State((_db, price_service)): State<(PgPool, Arc<dyn PriceFeedService>)>,
+    AuthenticatedAdmin(_admin): AuthenticatedAdmin,
+) -> Result<Json<Value>, ApiError> {
+    let stats = price_service.source_stats();
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": stats,
+        "count": stats.len()
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PriceSourceAlertQuery {
+    pub asset_code: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Alerts raised when external price sources disagree (admin only)
+pub async fn list_price_source_alerts(
+    State((db, _price_service)): State<(PgPool, Arc<dyn PriceFeedService>)>,
+    AuthenticatedAdmin(_admin): AuthenticatedAdmin,
+    Query(query): Query<PriceSourceAlertQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    let alerts =
+        DefaultPriceFeedService::list_source_alerts(&db, query.asset_code.as_deref(), limit)
+            .await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": alerts,
+        "count": alerts.len()
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OraclePushQuery {
+    pub asset_code: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Prices pushed to the on-chain oracle, newest first (admin only)
+pub async fn list_oracle_price_pushes(
+    State((db, _price_service)): State<(PgPool, Arc<dyn PriceFeedService>)>,
+    AuthenticatedAdmin(_admin): AuthenticatedAdmin,
+    Query(query): Query<OraclePushQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    let pushes = OraclePusher::list_pushes(&db, query.asset_code.as_deref(), limit).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": pushes,
+        "count": pushes.len()
+    })))
+}
+
+/// Change a feed's refresh interval, staleness limit or averaging windows (admin only)
+pub async fn update_feed_schedule(
+    State((_db, price_service)): State<(PgPool, Arc<dyn PriceFeedService>)>,
+    AuthenticatedAdmin(_admin): AuthenticatedAdmin,
+    Path(asset_code): Path<String>,
+    Json(req): Json<FeedScheduleUpdate>,
+) -> Result<Json<Value>, ApiError> {
+    let config = price_service
+        .update_feed_schedule(&asset_code, &req)
+        .await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "message": format!("Price feed schedule updated for {}", asset_code),
+        "data": config
+    })))
+}
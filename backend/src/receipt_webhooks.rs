@@ -0,0 +1,245 @@
+//! Delivery receipt webhooks from the live alert providers.
+//!
+//! SendGrid posts batches of email events signed with ECDSA P-256 over the
+//! timestamp and raw body; the verification key is the base64 public key
+//! from the Event Webhook settings (`SENDGRID_WEBHOOK_PUBLIC_KEY`). Twilio
+//! posts one form-encoded status per message to the `StatusCallback` set on
+//! the send, signed with HMAC-SHA1 of the callback URL and parameters under
+//! the account's auth token. Events are mapped onto [`DeliveryReceiptEvent`]
+//! and applied like any other receipt.
+
+use crate::api_error::ApiError;
+use crate::secure_messages::{DeliveryReceiptEvent, ReceiptStatus};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use chrono::Utc;
+use ring::{hmac, signature};
+use serde::Deserialize;
+
+pub const SENDGRID_SIGNATURE_HEADER: &str = "x-twilio-email-event-webhook-signature";
+pub const SENDGRID_TIMESTAMP_HEADER: &str = "x-twilio-email-event-webhook-timestamp";
+pub const TWILIO_SIGNATURE_HEADER: &str = "x-twilio-signature";
+
+/// Signed SendGrid batches older or newer than this are refused as replays
+const SENDGRID_MAX_SKEW_SECS: i64 = 600;
+
+/// DER SubjectPublicKeyInfo header of an uncompressed P-256 public key
+const P256_SPKI_PREFIX: [u8; 26] = [
+    0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08, 0x2a,
+    0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00,
+];
+
+/// Check a SendGrid Event Webhook signature: base64 DER ECDSA over
+/// `timestamp` followed by the raw body.
+pub fn verify_sendgrid_signature(
+    public_key_b64: &str,
+    timestamp: &str,
+    body: &[u8],
+    signature_b64: &str,
+) -> Result<(), ApiError> {
+    let spki = BASE64
+        .decode(public_key_b64.trim())
+        .map_err(|_| ApiError::Unauthorized)?;
+    let point = spki
+        .strip_prefix(P256_SPKI_PREFIX.as_slice())
+        .filter(|point| point.len() == 65)
+        .ok_or(ApiError::Unauthorized)?;
+    let signed_at: i64 = timestamp
+        .trim()
+        .parse()
+        .map_err(|_| ApiError::Unauthorized)?;
+    if (Utc::now().timestamp() - signed_at).abs() > SENDGRID_MAX_SKEW_SECS {
+        return Err(ApiError::Unauthorized);
+    }
+    let sig = BASE64
+        .decode(signature_b64.trim())
+        .map_err(|_| ApiError::Unauthorized)?;
+
+    let mut message = timestamp.trim().as_bytes().to_vec();
+    message.extend_from_slice(body);
+    signature::UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_ASN1, point)
+        .verify(&message, &sig)
+        .map_err(|_| ApiError::Unauthorized)
+}
+
+#[derive(Debug, Deserialize)]
+struct SendGridEvent {
+    event: String,
+    sg_message_id: Option<String>,
+    reason: Option<String>,
+    #[serde(rename = "type")]
+    bounce_type: Option<String>,
+}
+
+/// Receipts in a SendGrid event batch. Opens, clicks and deferrals (which
+/// SendGrid retries itself) carry no outcome and are left out.
+pub fn sendgrid_receipts(body: &[u8]) -> Result<Vec<DeliveryReceiptEvent>, ApiError> {
+    let events: Vec<SendGridEvent> = serde_json::from_slice(body)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid SendGrid events: {}", e)))?;
+    Ok(events
+        .into_iter()
+        .filter_map(|event| {
+            let status = match (event.event.as_str(), event.bounce_type.as_deref()) {
+                ("delivered", _) => ReceiptStatus::Delivered,
+                // A block is the receiving server refusing for now
+                ("bounce", Some("blocked")) => ReceiptStatus::Failed,
+                ("bounce", _) | ("dropped", _) => ReceiptStatus::Bounced,
+                _ => return None,
+            };
+            // `X-Message-Id` from the send is the part before the first dot
+            let id = event.sg_message_id?.split('.').next()?.to_string();
+            Some(DeliveryReceiptEvent {
+                provider_message_id: id,
+                status,
+                reason: event.reason,
+            })
+        })
+        .collect())
+}
+
+/// Check `X-Twilio-Signature`: base64 HMAC-SHA1, keyed with the auth token,
+/// of the callback URL followed by every parameter name and value, sorted
+/// by name.
+pub fn verify_twilio_signature(
+    auth_token: &str,
+    url: &str,
+    params: &[(String, String)],
+    signature_b64: &str,
+) -> Result<(), ApiError> {
+    if auth_token.is_empty() {
+        return Err(ApiError::Unauthorized);
+    }
+    let mut sorted: Vec<&(String, String)> = params.iter().collect();
+    sorted.sort();
+    let mut data = url.to_string();
+    for (name, value) in sorted {
+        data.push_str(name);
+        data.push_str(value);
+    }
+    let sig = BASE64
+        .decode(signature_b64.trim())
+        .map_err(|_| ApiError::Unauthorized)?;
+    let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, auth_token.as_bytes());
+    hmac::verify(&key, data.as_bytes(), &sig).map_err(|_| ApiError::Unauthorized)
+}
+
+/// The receipt in a Twilio status callback, if the status is final
+pub fn twilio_receipt(params: &[(String, String)]) -> Option<DeliveryReceiptEvent> {
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
+    let status = match param("MessageStatus")? {
+        "delivered" => ReceiptStatus::Delivered,
+        // Twilio could not send it at all, e.g. an invalid number
+        "failed" => ReceiptStatus::Bounced,
+        // The carrier could not deliver it this time
+        "undelivered" => ReceiptStatus::Failed,
+        _ => return None,
+    };
+    Some(DeliveryReceiptEvent {
+        provider_message_id: param("MessageSid")?.to_string(),
+        status,
+        reason: param("ErrorCode").map(|code| format!("Twilio error {code}")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_ASN1_SIGNING};
+
+    #[test]
+    fn sendgrid_signature_covers_timestamp_and_body() {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng).unwrap();
+        let pair = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng)
+            .unwrap();
+        let public_key =
+            BASE64.encode([&P256_SPKI_PREFIX[..], pair.public_key().as_ref()].concat());
+
+        let body = br#"[{"event":"delivered","sg_message_id":"abc.filter0001"}]"#;
+        let timestamp = Utc::now().timestamp().to_string();
+        let signed = [timestamp.as_bytes(), body.as_slice()].concat();
+        let sig = BASE64.encode(pair.sign(&rng, &signed).unwrap());
+
+        assert!(verify_sendgrid_signature(&public_key, &timestamp, body, &sig).is_ok());
+        assert!(verify_sendgrid_signature(&public_key, &timestamp, b"[]", &sig).is_err());
+        let old = (Utc::now().timestamp() - SENDGRID_MAX_SKEW_SECS - 60).to_string();
+        assert!(verify_sendgrid_signature(&public_key, &old, body, &sig).is_err());
+        assert!(verify_sendgrid_signature("not-a-key", &timestamp, body, &sig).is_err());
+    }
+
+    #[test]
+    fn sendgrid_events_map_to_receipts() {
+        let body = br#"[
+            {"event":"processed","sg_message_id":"m1.filter0001"},
+            {"event":"delivered","sg_message_id":"m1.filter0001"},
+            {"event":"bounce","type":"bounce","reason":"550 no such user","sg_message_id":"m2.f"},
+            {"event":"bounce","type":"blocked","sg_message_id":"m3.f"},
+            {"event":"dropped","sg_message_id":"m4.f"},
+            {"event":"open","sg_message_id":"m1.filter0001"},
+            {"event":"delivered"}
+        ]"#;
+        let receipts = sendgrid_receipts(body).unwrap();
+        let summary: Vec<(&str, ReceiptStatus)> = receipts
+            .iter()
+            .map(|r| (r.provider_message_id.as_str(), r.status))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("m1", ReceiptStatus::Delivered),
+                ("m2", ReceiptStatus::Bounced),
+                ("m3", ReceiptStatus::Failed),
+                ("m4", ReceiptStatus::Bounced),
+            ]
+        );
+        assert_eq!(receipts[1].reason.as_deref(), Some("550 no such user"));
+        assert!(sendgrid_receipts(b"{}").is_err());
+    }
+
+    fn params(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn twilio_signature_covers_url_and_sorted_params() {
+        let url = "https://api.inheritx.test/api/messages/legacy/deliveries/receipts/twilio";
+        let form = params(&[("MessageStatus", "delivered"), ("MessageSid", "SM1")]);
+        let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, b"token");
+        let sig = BASE64.encode(hmac::sign(
+            &key,
+            format!("{url}MessageSidSM1MessageStatusdelivered").as_bytes(),
+        ));
+
+        assert!(verify_twilio_signature("token", url, &form, &sig).is_ok());
+        assert!(verify_twilio_signature("other", url, &form, &sig).is_err());
+        assert!(verify_twilio_signature("", url, &form, &sig).is_err());
+        let tampered = params(&[("MessageStatus", "failed"), ("MessageSid", "SM1")]);
+        assert!(verify_twilio_signature("token", url, &tampered, &sig).is_err());
+    }
+
+    #[test]
+    fn twilio_statuses_map_to_receipts() {
+        let receipt = |status: &str| {
+            twilio_receipt(&params(&[
+                ("MessageSid", "SM1"),
+                ("MessageStatus", status),
+                ("ErrorCode", "30003"),
+            ]))
+            .map(|r| r.status)
+        };
+        assert_eq!(receipt("delivered"), Some(ReceiptStatus::Delivered));
+        assert_eq!(receipt("failed"), Some(ReceiptStatus::Bounced));
+        assert_eq!(receipt("undelivered"), Some(ReceiptStatus::Failed));
+        assert_eq!(receipt("sent"), None);
+        assert_eq!(receipt("queued"), None);
+        assert!(twilio_receipt(&params(&[("MessageStatus", "delivered")])).is_none());
+    }
+}
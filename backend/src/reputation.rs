@@ -1,15 +1,305 @@
+//! # Borrower Reputation
+//!
+//! Scores each borrower from their loan history on a 0–1000 scale. A new
+//! borrower starts at 500 and the score moves with:
+//!
+//! | Factor | Points |
+//! |---|---|
+//! | On-time repayment (`repaid_at <= due_date`) | +25 each, at most +250 |
+//! | Late repayment | −60 each |
+//! | Loan currently [`LoanStatus::Overdue`] | −100 each |
+//! | Tenure since the first loan | +10 per 30 days, at most +120 |
+//! | Utilization of open loans (average of 1 / health factor) | +50 at or below 0.5, 0 up to 0.8, −100 above |
+//! | Liquidation | −200 each, halving every 180 days |
+//!
+//! The score maps to a [`ReputationTier`] whose [`LoanTerms`] adjust the
+//! maximum LTV and the interest rate in
+//! [`LoanLifecycleService::create_loan`](crate::loan_lifecycle::LoanLifecycleService::create_loan),
+//! and the collateral ratio the lending contract asks of the borrower once
+//! they submit a signed attestation from [`ReputationAttester`].
+//!
+//! Scores are computed from the loan records on every read, so overdue
+//! sweeps and the decay of old liquidations show up without a new event;
+//! the stored score is refreshed whenever a lending event lands.
+
 use crate::api_error::ApiError;
 use crate::events::EventType;
-use chrono::{DateTime, Utc};
+use crate::loan_lifecycle::LoanStatus;
+use crate::risk_engine::collateral_params;
+use crate::service::CollateralType;
+use crate::tx_submitter::{account_address, TransactionSigner};
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use sqlx::{PgPool, Postgres, Transaction};
+use sha2::{Digest, Sha256};
+use soroban_sdk::xdr::{Hash, Limits, ScAddress, ScMap, ScMapEntry, ScSymbol, ScVal, WriteXdr};
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
+use std::str::FromStr;
+use std::sync::Arc;
 use uuid::Uuid;
 
+pub const BASE_SCORE: i32 = 500;
+pub const MAX_SCORE: i32 = 1000;
+
+const ON_TIME_POINTS: i64 = 25;
+const ON_TIME_MAX_POINTS: i64 = 250;
+const LATE_POINTS: i64 = -60;
+const OVERDUE_POINTS: i64 = -100;
+const TENURE_POINTS_PER_30_DAYS: i64 = 10;
+const TENURE_MAX_POINTS: i64 = 120;
+const LOW_UTILIZATION_POINTS: i64 = 50;
+const HIGH_UTILIZATION_POINTS: i64 = -100;
+const LIQUIDATION_POINTS: f64 = -200.0;
+const LIQUIDATION_HALF_LIFE_DAYS: f64 = 180.0;
+
+/// Base LTV for collateral outside [`CollateralType`]
+const UNLISTED_BASE_LTV: Decimal = Decimal::from_parts(50, 0, 0, false, 2);
+/// Headroom kept between a new loan's LTV and the liquidation threshold
+const LTV_LIQUIDATION_BUFFER: Decimal = Decimal::from_parts(5, 0, 0, false, 2);
+
+// ─── Scoring model ───────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReputationTier {
+    Excellent,
+    Good,
+    Standard,
+    Subprime,
+}
+
+impl ReputationTier {
+    /// Tiers from best to worst
+    pub const ALL: [ReputationTier; 4] = [
+        ReputationTier::Excellent,
+        ReputationTier::Good,
+        ReputationTier::Standard,
+        ReputationTier::Subprime,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReputationTier::Excellent => "excellent",
+            ReputationTier::Good => "good",
+            ReputationTier::Standard => "standard",
+            ReputationTier::Subprime => "subprime",
+        }
+    }
+
+    /// Lowest score that reaches this tier
+    pub fn min_score(&self) -> i32 {
+        match self {
+            ReputationTier::Excellent => 800,
+            ReputationTier::Good => 650,
+            ReputationTier::Standard => 450,
+            ReputationTier::Subprime => 0,
+        }
+    }
+
+    pub fn from_score(score: i32) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|tier| score >= tier.min_score())
+            .unwrap_or(ReputationTier::Subprime)
+    }
+
+    pub fn terms(&self) -> LoanTerms {
+        let (ltv_adjustment, rate_adjustment_bps, collateral_ratio_bps) = match self {
+            ReputationTier::Excellent => (Decimal::new(10, 2), -150, 12500),
+            ReputationTier::Good => (Decimal::new(5, 2), -75, 13500),
+            ReputationTier::Standard => (Decimal::ZERO, 0, 15000),
+            ReputationTier::Subprime => (Decimal::new(-10, 2), 200, 17500),
+        };
+        LoanTerms {
+            tier: *self,
+            min_score: self.min_score(),
+            ltv_adjustment,
+            rate_adjustment_bps,
+            collateral_ratio_bps,
+        }
+    }
+}
+
+/// Loan terms granted to a tier
+#[derive(Debug, Clone, Serialize)]
+pub struct LoanTerms {
+    pub tier: ReputationTier,
+    pub min_score: i32,
+    /// Added to the collateral's base LTV
+    #[serde(with = "rust_decimal::serde::str")]
+    pub ltv_adjustment: Decimal,
+    /// Added to the requested interest rate
+    pub rate_adjustment_bps: i32,
+    /// Collateral ratio attested to the lending contract
+    pub collateral_ratio_bps: u32,
+}
+
+/// Largest LTV a new loan against `collateral_asset` may open at for `tier`:
+/// the collateral's base LTV plus the tier adjustment, kept
+/// [`LTV_LIQUIDATION_BUFFER`] below its liquidation threshold
+pub fn max_ltv(collateral_asset: &str, tier: ReputationTier) -> Decimal {
+    let code = collateral_asset
+        .split(':')
+        .next()
+        .unwrap_or(collateral_asset);
+    let base = CollateralType::from_str(code)
+        .map(|collateral| collateral.get_ltv_ratio())
+        .unwrap_or(UNLISTED_BASE_LTV);
+    let ceiling =
+        collateral_params(collateral_asset).liquidation_threshold - LTV_LIQUIDATION_BUFFER;
+    (base + tier.terms().ltv_adjustment)
+        .min(ceiling)
+        .max(Decimal::ZERO)
+}
+
+/// The loan history a score is computed from
+#[derive(Debug, Clone, Default)]
+pub struct ReputationFacts {
+    pub on_time_repayments: i64,
+    pub late_repayments: i64,
+    pub overdue_loans: i64,
+    pub first_loan_at: Option<DateTime<Utc>>,
+    /// Health factors of open loans the risk engine has priced
+    pub open_health_factors: Vec<Decimal>,
+    pub liquidated_at: Vec<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreComponent {
+    pub factor: String,
+    pub points: i32,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoreBreakdown {
+    pub score: i32,
+    pub tier: ReputationTier,
+    pub components: Vec<ScoreComponent>,
+}
+
+fn component(factor: &str, points: i64, detail: String) -> ScoreComponent {
+    ScoreComponent {
+        factor: factor.to_string(),
+        points: points.clamp(i32::MIN as i64, i32::MAX as i64) as i32,
+        detail,
+    }
+}
+
+/// Score `facts` as of `now`
+pub fn score(facts: &ReputationFacts, now: DateTime<Utc>) -> ScoreBreakdown {
+    let mut components = Vec::with_capacity(6);
+
+    components.push(component(
+        "on_time_repayments",
+        facts
+            .on_time_repayments
+            .saturating_mul(ON_TIME_POINTS)
+            .min(ON_TIME_MAX_POINTS),
+        format!(
+            "{} loans repaid by their due date",
+            facts.on_time_repayments
+        ),
+    ));
+    components.push(component(
+        "late_repayments",
+        facts.late_repayments.saturating_mul(LATE_POINTS),
+        format!(
+            "{} loans repaid after their due date",
+            facts.late_repayments
+        ),
+    ));
+    components.push(component(
+        "overdue_loans",
+        facts.overdue_loans.saturating_mul(OVERDUE_POINTS),
+        format!("{} loans currently overdue", facts.overdue_loans),
+    ));
+
+    let tenure_days = facts
+        .first_loan_at
+        .map(|first| (now - first).num_days().max(0))
+        .unwrap_or(0);
+    components.push(component(
+        "tenure",
+        (tenure_days / 30 * TENURE_POINTS_PER_30_DAYS).min(TENURE_MAX_POINTS),
+        match facts.first_loan_at {
+            Some(_) => format!("first loan {tenure_days} days ago"),
+            None => "no loans yet".to_string(),
+        },
+    ));
+
+    components.push(if facts.open_health_factors.is_empty() {
+        component("utilization", 0, "no priced open loans".to_string())
+    } else {
+        // A health factor of 1 is the liquidation line, so its inverse is
+        // how much of the borrowing limit a loan uses
+        let total: Decimal = facts
+            .open_health_factors
+            .iter()
+            .map(|hf| {
+                if *hf > Decimal::ZERO {
+                    Decimal::ONE / *hf
+                } else {
+                    Decimal::ONE
+                }
+            })
+            .sum();
+        let utilization = total / Decimal::from(facts.open_health_factors.len());
+        let points = if utilization <= Decimal::new(5, 1) {
+            LOW_UTILIZATION_POINTS
+        } else if utilization <= Decimal::new(8, 1) {
+            0
+        } else {
+            HIGH_UTILIZATION_POINTS
+        };
+        component(
+            "utilization",
+            points,
+            format!(
+                "open loans use {:.2} of their liquidation limit on average",
+                utilization
+            ),
+        )
+    });
+
+    let liquidation_points: f64 = facts
+        .liquidated_at
+        .iter()
+        .map(|at| {
+            let age_days = ((now - *at).num_seconds().max(0) as f64) / 86_400.0;
+            LIQUIDATION_POINTS * 0.5f64.powf(age_days / LIQUIDATION_HALF_LIFE_DAYS)
+        })
+        .sum();
+    components.push(component(
+        "liquidations",
+        liquidation_points.round() as i64,
+        match facts.liquidated_at.iter().max() {
+            Some(latest) => format!(
+                "{} liquidations, the latest {} days ago",
+                facts.liquidated_at.len(),
+                (now - *latest).num_days().max(0)
+            ),
+            None => "no liquidations".to_string(),
+        },
+    ));
+
+    let total: i64 = components.iter().map(|c| c.points as i64).sum();
+    let score = (BASE_SCORE as i64 + total).clamp(0, MAX_SCORE as i64) as i32;
+    ScoreBreakdown {
+        score,
+        tier: ReputationTier::from_score(score),
+        components,
+    }
+}
+
+// ─── Service ─────────────────────────────────────────────────────────────────
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BorrowerReputation {
     pub user_id: Uuid,
     pub score: i32,
+    pub tier: ReputationTier,
     #[serde(with = "rust_decimal::serde::str")]
     pub total_borrowed: Decimal,
     #[serde(with = "rust_decimal::serde::str")]
@@ -19,6 +309,54 @@ pub struct BorrowerReputation {
     pub updated_at: DateTime<Utc>,
 }
 
+/// A score with the terms it earns and what the next tier takes
+#[derive(Debug, Clone, Serialize)]
+pub struct ReputationExplanation {
+    pub base_score: i32,
+    #[serde(flatten)]
+    pub breakdown: ScoreBreakdown,
+    pub terms: LoanTerms,
+    pub next_tier: Option<NextTier>,
+    /// Every tier's terms, best first
+    pub tiers: Vec<LoanTerms>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NextTier {
+    pub tier: ReputationTier,
+    pub points_needed: i32,
+}
+
+impl ReputationExplanation {
+    pub fn new(breakdown: ScoreBreakdown) -> Self {
+        let next_tier = ReputationTier::ALL
+            .into_iter()
+            .rev()
+            .find(|tier| tier.min_score() > breakdown.score)
+            .map(|tier| NextTier {
+                tier,
+                points_needed: tier.min_score() - breakdown.score,
+            });
+        Self {
+            base_score: BASE_SCORE,
+            terms: breakdown.tier.terms(),
+            next_tier,
+            tiers: ReputationTier::ALL.iter().map(|t| t.terms()).collect(),
+            breakdown,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct ReputationRow {
+    user_id: Uuid,
+    total_borrowed: Decimal,
+    total_repaid: Decimal,
+    liquidation_count: i32,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
 pub struct ReputationService;
 
 impl ReputationService {
@@ -27,53 +365,128 @@ impl ReputationService {
         pool: &PgPool,
         user_id: Uuid,
     ) -> Result<BorrowerReputation, ApiError> {
-        #[derive(sqlx::FromRow)]
-        struct ReputationRow {
-            user_id: Uuid,
-            score: i32,
-            total_borrowed: rust_decimal::Decimal,
-            total_repaid: rust_decimal::Decimal,
-            liquidation_count: i32,
-            created_at: DateTime<Utc>,
-            updated_at: DateTime<Utc>,
-        }
-
+        let mut conn = pool.acquire().await?;
         let rep_row = sqlx::query_as::<_, ReputationRow>(
             r#"
-            SELECT user_id, score, total_borrowed, total_repaid, liquidation_count, created_at, updated_at
+            SELECT user_id, total_borrowed, total_repaid, liquidation_count, created_at, updated_at
             FROM borrower_reputation
             WHERE user_id = $1
             "#,
         )
         .bind(user_id)
-        .fetch_optional(pool)
+        .fetch_optional(&mut *conn)
         .await
         .map_err(|e| ApiError::Internal(anyhow::anyhow!("DB Error: {}", e)))?;
+        let breakdown = Self::assess(&mut conn, user_id).await?;
 
-        if let Some(row) = rep_row {
-            Ok(BorrowerReputation {
+        Ok(match rep_row {
+            Some(row) => BorrowerReputation {
                 user_id: row.user_id,
-                score: row.score,
+                score: breakdown.score,
+                tier: breakdown.tier,
                 total_borrowed: row.total_borrowed,
                 total_repaid: row.total_repaid,
                 liquidation_count: row.liquidation_count,
                 created_at: row.created_at,
                 updated_at: row.updated_at,
-            })
-        } else {
-            Ok(BorrowerReputation {
+            },
+            None => BorrowerReputation {
                 user_id,
-                score: 100, // Default base score
+                score: breakdown.score,
+                tier: breakdown.tier,
                 total_borrowed: Decimal::ZERO,
                 total_repaid: Decimal::ZERO,
                 liquidation_count: 0,
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
-            })
-        }
+            },
+        })
+    }
+
+    /// The borrower's score, component by component, with the terms it earns
+    pub async fn explain(pool: &PgPool, user_id: Uuid) -> Result<ReputationExplanation, ApiError> {
+        let mut conn = pool.acquire().await?;
+        Ok(ReputationExplanation::new(
+            Self::assess(&mut conn, user_id).await?,
+        ))
     }
 
-    /// Update reputation score based on an event type and amount.
+    /// Score the borrower's history as it stands
+    pub async fn assess(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+    ) -> Result<ScoreBreakdown, ApiError> {
+        let facts = Self::load_facts(conn, user_id).await?;
+        Ok(score(&facts, Utc::now()))
+    }
+
+    async fn load_facts(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+    ) -> Result<ReputationFacts, ApiError> {
+        let (on_time_repayments, late_repayments, overdue_loans, first_loan_at): (
+            i64,
+            i64,
+            i64,
+            Option<DateTime<Utc>>,
+        ) = sqlx::query_as(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE status = 'repaid' AND repaid_at <= due_date),
+                COUNT(*) FILTER (WHERE status = 'repaid' AND repaid_at > due_date),
+                COUNT(*) FILTER (WHERE status = $2::loan_lifecycle_status),
+                LEAST(
+                    MIN(created_at),
+                    (SELECT MIN(event_timestamp) FROM lending_events
+                     WHERE user_id = $1 AND event_type = 'borrow')
+                )
+            FROM loan_lifecycle
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .bind(LoanStatus::Overdue.as_str())
+        .fetch_one(&mut *conn)
+        .await?;
+
+        let open_health_factors: Vec<Decimal> = sqlx::query_scalar(
+            r#"
+            SELECT health_factor FROM loan_lifecycle
+            WHERE user_id = $1 AND status IN ('active', 'overdue')
+              AND health_factor IS NOT NULL
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        // Auction bids share their auction's id, so each auction counts once
+        let liquidated_at: Vec<DateTime<Utc>> = sqlx::query_scalar(
+            r#"
+            SELECT liquidated_at FROM loan_lifecycle
+            WHERE user_id = $1 AND liquidated_at IS NOT NULL
+            UNION ALL
+            SELECT MIN(event_timestamp) FROM lending_events
+            WHERE user_id = $1 AND event_type = 'liquidation'
+            GROUP BY COALESCE(metadata->>'auction_id', id::text)
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(ReputationFacts {
+            on_time_repayments,
+            late_repayments,
+            overdue_loans,
+            first_loan_at,
+            open_health_factors,
+            liquidated_at,
+        })
+    }
+
+    /// Add an event's amount to the borrower's totals and store their
+    /// recomputed score.
     /// This should be called within the same transaction as the event insertion.
     pub async fn update_reputation<'a>(
         tx: &mut Transaction<'a, Postgres>,
@@ -97,37 +510,30 @@ impl ReputationService {
             0
         };
 
-        let score_adjustment = match event_type {
-            EventType::Repay => 2,
-            EventType::Liquidation => -50,
-            _ => 0,
-        };
-
-        #[derive(sqlx::FromRow)]
-        struct ReputationRow {
-            user_id: Uuid,
-            score: i32,
-            total_borrowed: rust_decimal::Decimal,
-            total_repaid: rust_decimal::Decimal,
-            liquidation_count: i32,
-            created_at: DateTime<Utc>,
-            updated_at: DateTime<Utc>,
-        }
+        let breakdown = Self::assess(tx, user_id).await?;
 
         let row = sqlx::query_as::<_, ReputationRow>(
             r#"
-            INSERT INTO borrower_reputation (user_id, score, total_borrowed, total_repaid, liquidation_count)
-            VALUES ($1, 100 + $2, $3, $4, $5)
+            INSERT INTO borrower_reputation (
+                user_id, score, tier, score_components, score_updated_at,
+                total_borrowed, total_repaid, liquidation_count
+            )
+            VALUES ($1, $2, $3, $4, NOW(), $5, $6, $7)
             ON CONFLICT (user_id) DO UPDATE SET
-                score = borrower_reputation.score + $2,
-                total_borrowed = borrower_reputation.total_borrowed + $3,
-                total_repaid = borrower_reputation.total_repaid + $4,
-                liquidation_count = borrower_reputation.liquidation_count + $5
-            RETURNING user_id, score, total_borrowed, total_repaid, liquidation_count, created_at, updated_at
+                score = $2,
+                tier = $3,
+                score_components = $4,
+                score_updated_at = NOW(),
+                total_borrowed = borrower_reputation.total_borrowed + $5,
+                total_repaid = borrower_reputation.total_repaid + $6,
+                liquidation_count = borrower_reputation.liquidation_count + $7
+            RETURNING user_id, total_borrowed, total_repaid, liquidation_count, created_at, updated_at
             "#,
         )
         .bind(user_id)
-        .bind(score_adjustment)
+        .bind(breakdown.score)
+        .bind(breakdown.tier.as_str())
+        .bind(serde_json::to_value(&breakdown.components).unwrap_or_default())
         .bind(add_borrow)
         .bind(add_repay)
         .bind(add_liquidation)
@@ -137,7 +543,8 @@ impl ReputationService {
 
         Ok(BorrowerReputation {
             user_id: row.user_id,
-            score: row.score,
+            score: breakdown.score,
+            tier: breakdown.tier,
             total_borrowed: row.total_borrowed,
             total_repaid: row.total_repaid,
             liquidation_count: row.liquidation_count,
@@ -145,4 +552,364 @@ impl ReputationService {
             updated_at: row.updated_at,
         })
     }
+
+    /// The wallet a borrower has linked, which attestations are issued to
+    pub async fn wallet_address(pool: &PgPool, user_id: Uuid) -> Result<String, ApiError> {
+        let wallet: Option<String> =
+            sqlx::query_scalar("SELECT wallet_address FROM users WHERE id = $1")
+                .bind(user_id)
+                .fetch_optional(pool)
+                .await?
+                .flatten();
+        wallet.filter(|w| !w.trim().is_empty()).ok_or_else(|| {
+            ApiError::BadRequest(
+                "Link a Stellar wallet before requesting a reputation attestation".to_string(),
+            )
+        })
+    }
+}
+
+// ─── On-chain attestation ────────────────────────────────────────────────────
+
+/// An attestation ready for the lending contract's `submit_reputation`
+#[derive(Debug, Clone, Serialize)]
+pub struct SignedAttestation {
+    pub borrower: String,
+    pub lending_contract: String,
+    pub score: u32,
+    pub tier: ReputationTier,
+    pub collateral_ratio_bps: u32,
+    pub expires_at: u64,
+    /// The contract only accepts an attestation issued after the borrower's
+    /// current one
+    pub issued_at: u64,
+    /// Base64 `ReputationAttestation` ScVal XDR
+    pub attestation_xdr: String,
+    /// Hex ed25519 signature over the SHA-256 of the XDR
+    pub signature: String,
+    /// Hex key the contract's reputation signer must be set to
+    pub signer_public_key: String,
+}
+
+/// Signs borrowers' tiers for the lending contract, which lowers their
+/// collateral ratio while the attestation is current
+pub struct ReputationAttester {
+    signer: Arc<dyn TransactionSigner>,
+    account: String,
+    lending_contract: [u8; 32],
+    ttl: Duration,
+}
+
+impl ReputationAttester {
+    pub fn new(
+        signer: Arc<dyn TransactionSigner>,
+        account: String,
+        lending_contract: &str,
+        ttl: Duration,
+    ) -> Result<Self, ApiError> {
+        let lending_contract = stellar_strkey::Contract::from_string(lending_contract)
+            .map_err(|e| {
+                ApiError::Internal(anyhow::anyhow!(
+                    "Invalid contract id {}: {}",
+                    lending_contract,
+                    e
+                ))
+            })?
+            .0;
+        if !signer.can_sign(&account) {
+            return Err(ApiError::Internal(anyhow::anyhow!(
+                "No signing key for reputation attestation account {}",
+                account
+            )));
+        }
+        Ok(Self {
+            signer,
+            account,
+            lending_contract,
+            ttl,
+        })
+    }
+
+    /// Attester for `LENDING_CONTRACT_ID` signing as
+    /// `REPUTATION_ATTESTATION_ACCOUNT`, or `None` when either is unset.
+    /// Attestations last `REPUTATION_ATTESTATION_TTL_SECS` (a day by default).
+    pub fn from_env() -> Result<Option<Self>, ApiError> {
+        let (Ok(contract_id), Ok(account)) = (
+            std::env::var("LENDING_CONTRACT_ID"),
+            std::env::var("REPUTATION_ATTESTATION_ACCOUNT"),
+        ) else {
+            return Ok(None);
+        };
+        let ttl_secs = std::env::var("REPUTATION_ATTESTATION_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(86_400);
+        Self::new(
+            crate::tx_submitter::signer_from_env()?,
+            account,
+            &contract_id,
+            Duration::seconds(ttl_secs),
+        )
+        .map(Some)
+    }
+
+    /// XDR of the contract's `ReputationAttestation`: a map keyed by field
+    /// name, in the sorted order the contract serializes it
+    pub fn attestation_xdr(
+        &self,
+        borrower: &str,
+        score: u32,
+        collateral_ratio_bps: u32,
+        expires_at: u64,
+        issued_at: u64,
+    ) -> Result<Vec<u8>, ApiError> {
+        let xdr_error = |e| ApiError::Internal(anyhow::anyhow!("XDR error: {:?}", e));
+        let entry = |name: &str, val: ScVal| -> Result<ScMapEntry, ApiError> {
+            Ok(ScMapEntry {
+                key: ScVal::Symbol(ScSymbol(name.try_into().map_err(xdr_error)?)),
+                val,
+            })
+        };
+        let map = ScMap(
+            vec![
+                entry("borrower", account_address(borrower)?)?,
+                entry("collateral_ratio_bps", ScVal::U32(collateral_ratio_bps))?,
+                entry("expires_at", ScVal::U64(expires_at))?,
+                entry("issued_at", ScVal::U64(issued_at))?,
+                entry(
+                    "lending_contract",
+                    ScVal::Address(ScAddress::Contract(Hash(self.lending_contract))),
+                )?,
+                entry("score", ScVal::U32(score))?,
+            ]
+            .try_into()
+            .map_err(xdr_error)?,
+        );
+        ScVal::Map(Some(map))
+            .to_xdr(Limits::none())
+            .map_err(xdr_error)
+    }
+
+    /// Sign `breakdown`'s tier for `borrower`
+    pub async fn attest(
+        &self,
+        borrower: &str,
+        breakdown: &ScoreBreakdown,
+    ) -> Result<SignedAttestation, ApiError> {
+        let score = breakdown.score.max(0) as u32;
+        let collateral_ratio_bps = breakdown.tier.terms().collateral_ratio_bps;
+        let now = Utc::now();
+        let issued_at = now.timestamp().to_u64().unwrap_or(0);
+        let expires_at = (now + self.ttl).timestamp().to_u64().unwrap_or(0);
+
+        let xdr =
+            self.attestation_xdr(borrower, score, collateral_ratio_bps, expires_at, issued_at)?;
+        let digest: [u8; 32] = Sha256::digest(&xdr).into();
+        let signature = self.signer.sign(&self.account, &digest).await?;
+        let public_key = stellar_strkey::ed25519::PublicKey::from_string(&self.account)
+            .map_err(|_| {
+                ApiError::Internal(anyhow::anyhow!("Invalid Stellar account {}", self.account))
+            })?
+            .0;
+
+        Ok(SignedAttestation {
+            borrower: borrower.to_string(),
+            lending_contract: stellar_strkey::Contract(self.lending_contract)
+                .to_string()
+                .as_str()
+                .to_owned(),
+            score,
+            tier: breakdown.tier,
+            collateral_ratio_bps,
+            expires_at,
+            issued_at,
+            attestation_xdr: {
+                use base64::Engine as _;
+                base64::engine::general_purpose::STANDARD.encode(&xdr)
+            },
+            signature: hex::encode(signature),
+            signer_public_key: hex::encode(public_key),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tx_submitter::KeypairSigner;
+    use ring::signature::{UnparsedPublicKey, ED25519};
+    use rust_decimal_macros::dec;
+
+    fn days_ago(now: DateTime<Utc>, days: i64) -> DateTime<Utc> {
+        now - Duration::days(days)
+    }
+
+    fn points(breakdown: &ScoreBreakdown, factor: &str) -> i32 {
+        breakdown
+            .components
+            .iter()
+            .find(|c| c.factor == factor)
+            .unwrap()
+            .points
+    }
+
+    #[test]
+    fn new_borrower_starts_in_standard_tier() {
+        let breakdown = score(&ReputationFacts::default(), Utc::now());
+        assert_eq!(breakdown.score, BASE_SCORE);
+        assert_eq!(breakdown.tier, ReputationTier::Standard);
+        assert!(breakdown.components.iter().all(|c| c.points == 0));
+    }
+
+    #[test]
+    fn repayment_history_and_tenure_raise_the_score() {
+        let now = Utc::now();
+        let facts = ReputationFacts {
+            on_time_repayments: 12,
+            late_repayments: 1,
+            first_loan_at: Some(days_ago(now, 400)),
+            open_health_factors: vec![dec!(2.5), dec!(3)],
+            ..Default::default()
+        };
+        let breakdown = score(&facts, now);
+        assert_eq!(points(&breakdown, "on_time_repayments"), 250);
+        assert_eq!(points(&breakdown, "late_repayments"), -60);
+        assert_eq!(points(&breakdown, "tenure"), 120);
+        assert_eq!(points(&breakdown, "utilization"), 50);
+        assert_eq!(breakdown.score, 860);
+        assert_eq!(breakdown.tier, ReputationTier::Excellent);
+    }
+
+    #[test]
+    fn overdue_loans_and_high_utilization_lower_the_score() {
+        let facts = ReputationFacts {
+            overdue_loans: 1,
+            open_health_factors: vec![dec!(1.1)],
+            ..Default::default()
+        };
+        let breakdown = score(&facts, Utc::now());
+        assert_eq!(points(&breakdown, "overdue_loans"), -100);
+        assert_eq!(points(&breakdown, "utilization"), -100);
+        assert_eq!(breakdown.score, 300);
+        assert_eq!(breakdown.tier, ReputationTier::Subprime);
+    }
+
+    #[test]
+    fn liquidation_penalty_halves_every_half_life() {
+        let now = Utc::now();
+        let recent = ReputationFacts {
+            liquidated_at: vec![now],
+            ..Default::default()
+        };
+        let old = ReputationFacts {
+            liquidated_at: vec![days_ago(now, 180), days_ago(now, 360)],
+            ..Default::default()
+        };
+        assert_eq!(points(&score(&recent, now), "liquidations"), -200);
+        assert_eq!(points(&score(&old, now), "liquidations"), -150);
+    }
+
+    #[test]
+    fn score_is_clamped() {
+        let facts = ReputationFacts {
+            late_repayments: 50,
+            ..Default::default()
+        };
+        assert_eq!(score(&facts, Utc::now()).score, 0);
+    }
+
+    #[test]
+    fn max_ltv_follows_tier_below_liquidation_threshold() {
+        assert_eq!(max_ltv("ETH", ReputationTier::Standard), dec!(0.75));
+        assert_eq!(max_ltv("ETH", ReputationTier::Subprime), dec!(0.65));
+        assert!(max_ltv("ETH", ReputationTier::Excellent) > dec!(0.75));
+        assert!(
+            max_ltv("ETH", ReputationTier::Excellent)
+                <= collateral_params("ETH").liquidation_threshold - LTV_LIQUIDATION_BUFFER
+        );
+        assert_eq!(max_ltv("UNLISTED", ReputationTier::Standard), dec!(0.50));
+    }
+
+    #[test]
+    fn explanation_points_to_next_tier() {
+        let explanation =
+            ReputationExplanation::new(score(&ReputationFacts::default(), Utc::now()));
+        let next = explanation.next_tier.unwrap();
+        assert_eq!(next.tier, ReputationTier::Good);
+        assert_eq!(next.points_needed, 150);
+        assert_eq!(explanation.terms.collateral_ratio_bps, 15000);
+    }
+
+    #[tokio::test]
+    async fn attestation_is_signed_over_sorted_field_map() {
+        let seed = stellar_strkey::ed25519::PrivateKey([5; 32])
+            .to_string()
+            .as_str()
+            .to_owned();
+        let signer = KeypairSigner::from_secret_seeds([seed.as_str()]).unwrap();
+        let account = signer.accounts().pop().unwrap();
+        let contract = stellar_strkey::Contract([2; 32]).to_string();
+        let attester = ReputationAttester::new(
+            Arc::new(signer),
+            account.clone(),
+            contract.as_str(),
+            Duration::hours(1),
+        )
+        .unwrap();
+
+        let breakdown = score(
+            &ReputationFacts {
+                on_time_repayments: 8,
+                ..Default::default()
+            },
+            Utc::now(),
+        );
+        let signed = attester.attest(&account, &breakdown).await.unwrap();
+        assert_eq!(signed.score, 700);
+        assert_eq!(signed.tier, ReputationTier::Good);
+        assert_eq!(signed.collateral_ratio_bps, 13500);
+
+        let xdr = attester
+            .attestation_xdr(&account, 700, 13500, signed.expires_at, signed.issued_at)
+            .unwrap();
+        let ScVal::Map(Some(map)) =
+            <ScVal as soroban_sdk::xdr::ReadXdr>::from_xdr(&xdr, Limits::none()).unwrap()
+        else {
+            panic!("attestation is not a map");
+        };
+        let keys: Vec<String> = map
+            .iter()
+            .map(|e| match &e.key {
+                ScVal::Symbol(s) => s.to_utf8_string_lossy(),
+                other => panic!("unexpected key {other:?}"),
+            })
+            .collect();
+        let mut sorted = keys.clone();
+        sorted.sort();
+        assert_eq!(keys, sorted);
+        assert!(keys.contains(&"issued_at".to_string()));
+        assert!(signed.issued_at < signed.expires_at);
+
+        let digest: [u8; 32] = Sha256::digest(&xdr).into();
+        UnparsedPublicKey::new(&ED25519, hex::decode(&signed.signer_public_key).unwrap())
+            .verify(&digest, &hex::decode(&signed.signature).unwrap())
+            .unwrap();
+    }
+
+    #[test]
+    fn attester_needs_a_key_for_its_account() {
+        let signer = KeypairSigner::from_secret_seeds(std::iter::empty()).unwrap();
+        let account = stellar_strkey::ed25519::PublicKey([1; 32])
+            .to_string()
+            .as_str()
+            .to_owned();
+        let contract = stellar_strkey::Contract([2; 32]).to_string();
+        assert!(ReputationAttester::new(
+            Arc::new(signer),
+            account,
+            contract.as_str(),
+            Duration::hours(1)
+        )
+        .is_err());
+    }
 }
@@ -3,31 +3,188 @@ use crate::notifications::{
     audit_action, entity_type, notif_type, AuditLogService, NotificationService,
 };
 use crate::price_feed::PriceFeedService;
+use crate::service::CollateralType;
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Liquidation threshold for collateral without listed parameters, below
+/// the most conservative listed asset
+const UNLISTED_LIQUIDATION_THRESHOLD: Decimal = Decimal::from_parts(75, 0, 0, false, 2);
+
+/// Price drop applied to a whole correlated group when stress-testing it
+const GROUP_SHOCK: Decimal = Decimal::from_parts(20, 0, 0, false, 2);
+
+/// Days of health history and portfolio snapshots kept
+const HEALTH_HISTORY_RETENTION_DAYS: i32 = 90;
+
+/// Default cap on the share of book collateral one correlated group may hold
+const DEFAULT_MAX_GROUP_SHARE: Decimal = Decimal::from_parts(6, 0, 0, false, 1);
+
+/// Risk parameters of a collateral asset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollateralParams {
+    pub liquidation_threshold: Decimal,
+    /// Correlated collateral group; unlisted assets form their own group
+    pub group: String,
+}
+
+/// Parameters for `asset`, matched on its code so `CODE:ISSUER` ids of
+/// listed assets share their parameters
+pub fn collateral_params(asset: &str) -> CollateralParams {
+    let code = asset.split(':').next().unwrap_or(asset);
+    match CollateralType::from_str(code) {
+        Ok(collateral) => CollateralParams {
+            liquidation_threshold: collateral.get_liquidation_threshold(),
+            group: collateral.correlation_group().to_string(),
+        },
+        Err(_) => CollateralParams {
+            liquidation_threshold: UNLISTED_LIQUIDATION_THRESHOLD,
+            group: asset.to_uppercase(),
+        },
+    }
+}
+
+/// Risk-adjusted collateral over debt; below 1 the position is liquidatable.
+/// `None` when there is no debt.
+pub fn health_factor(
+    collateral_value: Decimal,
+    debt_value: Decimal,
+    liquidation_threshold: Decimal,
+) -> Option<Decimal> {
+    (debt_value > Decimal::ZERO).then(|| collateral_value * liquidation_threshold / debt_value)
+}
+
+/// A position's health as of one risk cycle
+#[derive(Debug, Clone, Serialize)]
+pub struct PositionHealth {
+    /// Lifecycle loan, if the position is one
+    pub loan_id: Option<Uuid>,
+    pub plan_id: Option<Uuid>,
+    pub user_id: Uuid,
+    pub collateral_asset: String,
+    pub borrow_asset: String,
+    pub collateral_value: Decimal,
+    pub debt_value: Decimal,
+    pub liquidation_threshold: Decimal,
+    pub health_factor: Decimal,
+    pub group: String,
+}
+
+/// Book-wide exposure to one correlated collateral group
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupConcentration {
+    pub group: String,
+    pub collateral_value: Decimal,
+    /// Share of all collateral in the book
+    pub share: Decimal,
+    pub debt_value: Decimal,
+    /// Debt that would become liquidatable if the group fell by the shock
+    pub stressed_debt_value: Decimal,
+    /// Whether `share` is above the concentration limit
+    pub breached: bool,
+}
+
+/// Exposure per correlated group, largest first
+pub fn concentrations(positions: &[PositionHealth], max_share: Decimal) -> Vec<GroupConcentration> {
+    let total: Decimal = positions.iter().map(|p| p.collateral_value).sum();
+    let mut groups: BTreeMap<&str, GroupConcentration> = BTreeMap::new();
+    for position in positions {
+        let group = groups
+            .entry(&position.group)
+            .or_insert_with(|| GroupConcentration {
+                group: position.group.clone(),
+                collateral_value: Decimal::ZERO,
+                share: Decimal::ZERO,
+                debt_value: Decimal::ZERO,
+                stressed_debt_value: Decimal::ZERO,
+                breached: false,
+            });
+        group.collateral_value += position.collateral_value;
+        group.debt_value += position.debt_value;
+        if position.health_factor * (Decimal::ONE - GROUP_SHOCK) < Decimal::ONE {
+            group.stressed_debt_value += position.debt_value;
+        }
+    }
+
+    let mut groups: Vec<GroupConcentration> = groups.into_values().collect();
+    for group in &mut groups {
+        if total > Decimal::ZERO {
+            group.share = group.collateral_value / total;
+        }
+        group.breached = group.share > max_share;
+    }
+    groups.sort_by_key(|g| std::cmp::Reverse(g.collateral_value));
+    groups
+}
+
+/// One entry of a position's health history
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct HealthHistoryPoint {
+    pub collateral_value: Decimal,
+    pub debt_value: Decimal,
+    pub liquidation_threshold: Decimal,
+    pub health_factor: Decimal,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Latest book-wide snapshot
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct PortfolioRiskSnapshot {
+    pub positions: i32,
+    pub at_risk_positions: i32,
+    pub total_collateral_value: Decimal,
+    pub total_debt_value: Decimal,
+    pub concentrations: serde_json::Value,
+    pub recorded_at: DateTime<Utc>,
+}
 
 pub struct RiskEngine {
     db: PgPool,
     price_feed: Arc<dyn PriceFeedService>,
-    liquidation_threshold: Decimal,
+    /// Largest share of book collateral one correlated group may hold
+    max_group_share: Decimal,
 }
 
 impl RiskEngine {
     pub fn new(
         db: PgPool,
         price_feed: Arc<dyn PriceFeedService>,
-        liquidation_threshold: Decimal,
+        max_group_share: Decimal,
     ) -> Self {
         Self {
             db,
             price_feed,
-            liquidation_threshold,
+            max_group_share,
         }
     }
 
+    /// Reads `RISK_MAX_COLLATERAL_CONCENTRATION`, falling back to the
+    /// default when unset or outside (0, 1]
+    pub fn from_env(db: PgPool, price_feed: Arc<dyn PriceFeedService>) -> Self {
+        let max_group_share = match std::env::var("RISK_MAX_COLLATERAL_CONCENTRATION") {
+            Ok(value) => match Decimal::from_str(value.trim()) {
+                Ok(share) if share > Decimal::ZERO && share <= Decimal::ONE => share,
+                _ => {
+                    warn!(
+                        "Invalid RISK_MAX_COLLATERAL_CONCENTRATION {:?}, using {}",
+                        value, DEFAULT_MAX_GROUP_SHARE
+                    );
+                    DEFAULT_MAX_GROUP_SHARE
+                }
+            },
+            Err(_) => DEFAULT_MAX_GROUP_SHARE,
+        };
+        Self::new(db, price_feed, max_group_share)
+    }
+
     pub fn start(self: Arc<Self>) {
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(Duration::from_secs(60));
@@ -42,20 +199,22 @@ impl RiskEngine {
 
     pub async fn check_all_loans(&self) -> Result<(), ApiError> {
         #[derive(sqlx::FromRow)]
-        struct LoanHealthRow {
-            plan_id: uuid::Uuid,
-            user_id: uuid::Uuid,
+        struct PositionRow {
+            loan_id: Option<Uuid>,
+            plan_id: Option<Uuid>,
+            user_id: Uuid,
             borrow_asset: String,
-            total_debt: rust_decimal::Decimal,
-            collateral_asset: Option<String>,
-            collateral_amount: Option<rust_decimal::Decimal>,
-            is_risky: Option<bool>,
-            risk_override_enabled: Option<bool>,
+            total_debt: Decimal,
+            collateral_asset: String,
+            collateral_amount: Decimal,
+            is_risky: bool,
+            risk_override_enabled: bool,
         }
 
-        // Find plans that have borrowing activity by aggregating lending events.
-        // Exclude paused plans from risk monitoring
-        let loans_health = sqlx::query_as::<_, LoanHealthRow>(
+        // Lifecycle loans, plus positions opened on-chain whose debt is
+        // aggregated from lending events against the plan's USDC vault.
+        // Paused plans are not monitored.
+        let rows = sqlx::query_as::<_, PositionRow>(
             r#"
             WITH loan_balances AS (
                 SELECT plan_id, user_id, asset_code AS borrow_asset,
@@ -66,114 +225,370 @@ impl RiskEngine {
                 WHERE plan_id IS NOT NULL
                 GROUP BY plan_id, user_id, asset_code
             )
-            SELECT lb.plan_id, lb.user_id, lb.borrow_asset, lb.total_debt,
-                   p.asset_code as collateral_asset, CAST(p.net_amount AS numeric) as collateral_amount, 
-                   p.is_risky, p.risk_override_enabled
+            SELECT ll.id AS loan_id, ll.plan_id, ll.user_id, ll.borrow_asset,
+                   ll.principal - ll.amount_repaid AS total_debt,
+                   ll.collateral_asset, ll.collateral_amount,
+                   ll.is_risky, COALESCE(p.risk_override_enabled, false) AS risk_override_enabled
+            FROM loan_lifecycle ll
+            LEFT JOIN plans p ON p.id = ll.plan_id
+            WHERE ll.status IN ('active', 'overdue')
+              AND ll.principal > ll.amount_repaid
+              AND (p.is_paused IS NULL OR p.is_paused = false)
+            UNION ALL
+            SELECT NULL::uuid AS loan_id, lb.plan_id, lb.user_id, lb.borrow_asset, lb.total_debt,
+                   'USDC' AS collateral_asset,
+                   COALESCE(CAST(p.net_amount AS numeric), 0) AS collateral_amount,
+                   COALESCE(p.is_risky, false) AS is_risky,
+                   COALESCE(p.risk_override_enabled, false) AS risk_override_enabled
             FROM loan_balances lb
             JOIN plans p ON p.id = lb.plan_id
             WHERE lb.total_debt > 0
               AND (p.is_paused IS NULL OR p.is_paused = false)
-            "#
+            "#,
         )
         .fetch_all(&self.db)
         .await
         .map_err(|e| ApiError::Internal(anyhow::anyhow!("DB error loading loan balances: {}", e)))?;
 
-        for loan in loans_health {
-            // Get prices for evaluation
-            let borrow_price = match self.price_feed.get_price(&loan.borrow_asset).await {
-                Ok(p) => p.price,
-                Err(e) => {
-                    warn!(
-                        "Risk Engine: Could not get price for borrow asset {}: {}",
-                        loan.borrow_asset, e
-                    );
-                    continue;
-                }
+        // Value both sides at TWAP so a momentary spot spike cannot flag a
+        // loan; each asset is priced once per cycle
+        let mut prices: HashMap<String, Option<Decimal>> = HashMap::new();
+        let mut positions = Vec::with_capacity(rows.len());
+        for row in rows {
+            let (Some(borrow_price), Some(collat_price)) = (
+                self.cached_price(&mut prices, &row.borrow_asset).await,
+                self.cached_price(&mut prices, &row.collateral_asset).await,
+            ) else {
+                continue;
             };
 
-            let collat_asset = loan.collateral_asset.unwrap_or_else(|| "USDC".to_string());
-            let collat_price = match self.price_feed.get_price(&collat_asset).await {
-                Ok(p) => p.price,
-                Err(e) => {
-                    warn!(
-                        "Risk Engine: Could not get price for collateral asset {}: {}",
-                        collat_asset, e
-                    );
-                    continue;
-                }
+            let params = collateral_params(&row.collateral_asset);
+            let collateral_value = row.collateral_amount * collat_price;
+            let debt_value = row.total_debt * borrow_price;
+            let Some(health_factor) =
+                health_factor(collateral_value, debt_value, params.liquidation_threshold)
+            else {
+                continue;
             };
 
-            // Values are aggregated using numeric casts, yielding Decimal safely via sqlx mapping
-            let collat_value = loan.collateral_amount.unwrap_or(Decimal::ZERO) * collat_price;
-            let debt_value = loan.total_debt * borrow_price;
-
-            if debt_value > Decimal::ZERO {
-                let health_factor = collat_value / debt_value;
-
-                // Skip risk flagging if risk override is enabled
-                let should_skip_risk_check = loan.risk_override_enabled.unwrap_or(false);
-
-                let is_now_risky = if should_skip_risk_check {
-                    false // Override: never mark as risky
-                } else {
-                    health_factor < self.liquidation_threshold
-                };
-
-                // Update database state
-                sqlx::query(
-                    r#"
-                    UPDATE plans
-                    SET is_risky = $1, health_factor = $2, risk_flagged_at = CASE WHEN $1 AND risk_flagged_at IS NULL THEN CURRENT_TIMESTAMP ELSE risk_flagged_at END
-                    WHERE id = $3
-                    "#
-                )
-                .bind(is_now_risky)
-                .bind(health_factor)
-                .bind(loan.plan_id)
-                .execute(&self.db)
-                .await
-                .map_err(|e| ApiError::Internal(anyhow::anyhow!("DB error updating plan risk status: {}", e)))?;
-
-                // Notify if transitioned to risky (and not overridden)
-                if is_now_risky && !loan.is_risky.unwrap_or(false) && !should_skip_risk_check {
-                    info!(
-                        "Plan {} for User {} flagged as risky. HF: {}",
-                        loan.plan_id, loan.user_id, health_factor
-                    );
+            let position = PositionHealth {
+                loan_id: row.loan_id,
+                plan_id: row.plan_id,
+                user_id: row.user_id,
+                collateral_asset: row.collateral_asset,
+                borrow_asset: row.borrow_asset,
+                collateral_value,
+                debt_value,
+                liquidation_threshold: params.liquidation_threshold,
+                health_factor,
+                group: params.group,
+            };
+            // Skip risk flagging if risk override is enabled
+            let is_now_risky = !row.risk_override_enabled && health_factor < Decimal::ONE;
+            self.record_position(&position, row.is_risky, is_now_risky)
+                .await?;
+            positions.push(position);
+        }
 
-                    let mut tx = self.db.begin().await.map_err(|e| {
-                        ApiError::Internal(anyhow::anyhow!("Tx start error: {}", e))
-                    })?;
-
-                    NotificationService::create(
-                        &mut tx,
-                        loan.user_id,
-                        notif_type::LIQUIDATION_WARNING,
-                        format!("WARNING: Your loan against plan {} is at risk of liquidation. Health factor is now {:.2}. Please add collateral or repay some debt.", loan.plan_id, health_factor)
-                    ).await?;
-
-                    AuditLogService::log(
-                        &mut *tx,
-                        Some(loan.user_id),
-                        audit_action::LIQUIDATION_WARNING,
-                        Some(loan.plan_id),
-                        Some(entity_type::PLAN),
-                    )
-                    .await?;
-
-                    tx.commit().await.map_err(|e| {
-                        ApiError::Internal(anyhow::anyhow!("Tx commit error: {}", e))
-                    })?;
-                } else if !is_now_risky && loan.is_risky.unwrap_or(false) {
-                    info!(
-                        "Plan {} for User {} is no longer risky. HF: {}",
-                        loan.plan_id, loan.user_id, health_factor
-                    );
-                }
+        self.record_portfolio(&positions).await?;
+
+        for table in ["loan_health_history", "portfolio_risk_snapshots"] {
+            sqlx::query(&format!(
+                "DELETE FROM {} WHERE recorded_at < NOW() - make_interval(days => $1)",
+                table
+            ))
+            .bind(HEALTH_HISTORY_RETENTION_DAYS)
+            .execute(&self.db)
+            .await
+            .map_err(|e| {
+                ApiError::Internal(anyhow::anyhow!("DB error pruning {}: {}", table, e))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    async fn cached_price(
+        &self,
+        prices: &mut HashMap<String, Option<Decimal>>,
+        asset: &str,
+    ) -> Option<Decimal> {
+        if let Some(price) = prices.get(asset) {
+            return *price;
+        }
+        let price = match self.price_feed.get_twap_price(asset).await {
+            Ok(p) => Some(p.price),
+            Err(e) => {
+                warn!(
+                    "Risk Engine: Could not get price for asset {}: {}",
+                    asset, e
+                );
+                None
             }
+        };
+        prices.insert(asset.to_string(), price);
+        price
+    }
+
+    /// Store a position's health, append it to the history and warn the
+    /// borrower when it turns liquidatable
+    async fn record_position(
+        &self,
+        position: &PositionHealth,
+        was_risky: bool,
+        is_now_risky: bool,
+    ) -> Result<(), ApiError> {
+        let mut tx = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| ApiError::Internal(anyhow::anyhow!("Tx start error: {}", e)))?;
+
+        let (update, entity_id, entity, subject) = match (position.loan_id, position.plan_id) {
+            (Some(loan_id), _) => (
+                "UPDATE loan_lifecycle SET is_risky = $1, health_factor = $2, risk_flagged_at = CASE WHEN $1 AND risk_flagged_at IS NULL THEN CURRENT_TIMESTAMP WHEN NOT $1 THEN NULL ELSE risk_flagged_at END WHERE id = $3",
+                loan_id,
+                entity_type::LOAN,
+                format!("loan {}", loan_id),
+            ),
+            (None, Some(plan_id)) => (
+                "UPDATE plans SET is_risky = $1, health_factor = $2, risk_flagged_at = CASE WHEN $1 AND risk_flagged_at IS NULL THEN CURRENT_TIMESTAMP ELSE risk_flagged_at END WHERE id = $3",
+                plan_id,
+                entity_type::PLAN,
+                format!("loan against plan {}", plan_id),
+            ),
+            (None, None) => return Ok(()),
+        };
+        sqlx::query(update)
+            .bind(is_now_risky)
+            .bind(position.health_factor)
+            .bind(entity_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                ApiError::Internal(anyhow::anyhow!("DB error updating risk status: {}", e))
+            })?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO loan_health_history (
+                loan_id, plan_id, user_id, collateral_asset, borrow_asset,
+                collateral_value, debt_value, liquidation_threshold, health_factor
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+        )
+        .bind(position.loan_id)
+        .bind(position.plan_id)
+        .bind(position.user_id)
+        .bind(&position.collateral_asset)
+        .bind(&position.borrow_asset)
+        .bind(position.collateral_value)
+        .bind(position.debt_value)
+        .bind(position.liquidation_threshold)
+        .bind(position.health_factor)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Internal(anyhow::anyhow!("DB error recording health: {}", e)))?;
+
+        if is_now_risky && !was_risky {
+            info!(
+                "{} for User {} flagged as risky. HF: {}",
+                subject, position.user_id, position.health_factor
+            );
+
+            NotificationService::create(
+                &mut tx,
+                position.user_id,
+                notif_type::LIQUIDATION_WARNING,
+                format!("WARNING: Your {} is at risk of liquidation. Health factor is now {:.2}. Please add collateral or repay some debt.", subject, position.health_factor)
+            ).await?;
+
+            AuditLogService::log(
+                &mut *tx,
+                Some(position.user_id),
+                audit_action::LIQUIDATION_WARNING,
+                Some(entity_id),
+                Some(entity),
+            )
+            .await?;
+        } else if !is_now_risky && was_risky {
+            info!(
+                "{} for User {} is no longer risky. HF: {}",
+                subject, position.user_id, position.health_factor
+            );
         }
 
+        tx.commit()
+            .await
+            .map_err(|e| ApiError::Internal(anyhow::anyhow!("Tx commit error: {}", e)))?;
         Ok(())
     }
+
+    /// Snapshot book-wide exposure and warn about concentrated groups
+    async fn record_portfolio(&self, positions: &[PositionHealth]) -> Result<(), ApiError> {
+        let groups = concentrations(positions, self.max_group_share);
+        for group in groups.iter().filter(|g| g.breached) {
+            warn!(
+                "Risk Engine: {} collateral is {:.1}% of the book (limit {:.1}%); {} of debt liquidatable after a {}% drop",
+                group.group,
+                group.share * Decimal::ONE_HUNDRED,
+                self.max_group_share * Decimal::ONE_HUNDRED,
+                group.stressed_debt_value,
+                GROUP_SHOCK * Decimal::ONE_HUNDRED
+            );
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO portfolio_risk_snapshots (
+                positions, at_risk_positions, total_collateral_value, total_debt_value, concentrations
+            ) VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(positions.len() as i32)
+        .bind(
+            positions
+                .iter()
+                .filter(|p| p.health_factor < Decimal::ONE)
+                .count() as i32,
+        )
+        .bind(positions.iter().map(|p| p.collateral_value).sum::<Decimal>())
+        .bind(positions.iter().map(|p| p.debt_value).sum::<Decimal>())
+        .bind(serde_json::to_value(&groups).unwrap_or_default())
+        .execute(&self.db)
+        .await
+        .map_err(|e| ApiError::Internal(anyhow::anyhow!("DB error recording portfolio risk: {}", e)))?;
+        Ok(())
+    }
+
+    /// Health history of a position owned by `user_id`, newest first. `id` is
+    /// a lifecycle loan, or a plan for positions opened on-chain.
+    pub async fn get_health_history(
+        db: &PgPool,
+        id: Uuid,
+        user_id: Uuid,
+        days: i32,
+    ) -> Result<Vec<HealthHistoryPoint>, ApiError> {
+        let owned: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS (SELECT 1 FROM loan_lifecycle WHERE id = $1 AND user_id = $2)
+                OR EXISTS (SELECT 1 FROM plans WHERE id = $1 AND user_id = $2)
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_one(db)
+        .await
+        .map_err(|e| ApiError::Internal(anyhow::anyhow!("DB error fetching loan: {}", e)))?;
+        if !owned {
+            return Err(ApiError::NotFound(format!("Loan {} not found", id)));
+        }
+
+        sqlx::query_as::<_, HealthHistoryPoint>(
+            r#"
+            SELECT collateral_value, debt_value, liquidation_threshold, health_factor, recorded_at
+            FROM loan_health_history
+            WHERE (loan_id = $1 OR (loan_id IS NULL AND plan_id = $1))
+              AND recorded_at >= NOW() - make_interval(days => $2)
+            ORDER BY recorded_at DESC
+            "#,
+        )
+        .bind(id)
+        .bind(days)
+        .fetch_all(db)
+        .await
+        .map_err(|e| ApiError::Internal(anyhow::anyhow!("DB error fetching health history: {}", e)))
+    }
+
+    /// The most recent portfolio snapshot, if the engine has run
+    pub async fn latest_portfolio_snapshot(
+        db: &PgPool,
+    ) -> Result<Option<PortfolioRiskSnapshot>, ApiError> {
+        sqlx::query_as::<_, PortfolioRiskSnapshot>(
+            r#"
+            SELECT positions, at_risk_positions, total_collateral_value, total_debt_value,
+                   concentrations, recorded_at
+            FROM portfolio_risk_snapshots
+            ORDER BY recorded_at DESC
+            LIMIT 1
+            "#,
+        )
+        .fetch_optional(db)
+        .await
+        .map_err(|e| ApiError::Internal(anyhow::anyhow!("DB error fetching portfolio risk: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn position(group: &str, collateral_value: Decimal, health_factor: Decimal) -> PositionHealth {
+        PositionHealth {
+            loan_id: Some(Uuid::new_v4()),
+            plan_id: None,
+            user_id: Uuid::new_v4(),
+            collateral_asset: group.to_string(),
+            borrow_asset: "USDC".to_string(),
+            collateral_value,
+            debt_value: dec!(100),
+            liquidation_threshold: dec!(0.85),
+            health_factor,
+            group: group.to_string(),
+        }
+    }
+
+    #[test]
+    fn uses_per_collateral_parameters() {
+        assert_eq!(collateral_params("ETH").liquidation_threshold, dec!(0.85));
+        assert_eq!(collateral_params("xlm").liquidation_threshold, dec!(0.80));
+        let usdc =
+            collateral_params("USDC:GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN");
+        assert_eq!(usdc.liquidation_threshold, dec!(0.95));
+        assert_eq!(usdc.group, "stablecoin");
+        assert_eq!(
+            collateral_params("BTC").group,
+            collateral_params("ETH").group
+        );
+
+        let unlisted = collateral_params("aqua");
+        assert_eq!(
+            unlisted.liquidation_threshold,
+            UNLISTED_LIQUIDATION_THRESHOLD
+        );
+        assert_eq!(unlisted.group, "AQUA");
+    }
+
+    #[test]
+    fn health_factor_is_risk_adjusted() {
+        // 1000 of ETH collateral against 800 of debt: 850 / 800
+        assert_eq!(
+            health_factor(dec!(1000), dec!(800), dec!(0.85)),
+            Some(dec!(1.0625))
+        );
+        assert!(health_factor(dec!(900), dec!(800), dec!(0.85)).unwrap() < Decimal::ONE);
+        assert_eq!(health_factor(dec!(1000), Decimal::ZERO, dec!(0.85)), None);
+    }
+
+    #[test]
+    fn flags_concentrated_correlated_collateral() {
+        let positions = vec![
+            position("crypto_major", dec!(500), dec!(1.1)),
+            position("crypto_major", dec!(200), dec!(2.0)),
+            position("stablecoin", dec!(300), dec!(1.5)),
+        ];
+        let groups = concentrations(&positions, dec!(0.6));
+
+        assert_eq!(groups[0].group, "crypto_major");
+        assert_eq!(groups[0].share, dec!(0.7));
+        assert!(groups[0].breached);
+        // Only the 1.1 position drops below 1 after a 20% fall
+        assert_eq!(groups[0].stressed_debt_value, dec!(100));
+        assert_eq!(groups[0].debt_value, dec!(200));
+
+        assert_eq!(groups[1].share, dec!(0.3));
+        assert!(!groups[1].breached);
+        assert!(concentrations(&[], dec!(0.6)).is_empty());
+    }
 }
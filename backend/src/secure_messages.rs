@@ -1,9 +1,15 @@
+use crate::alert_provider::{AlertProvider, AlertTemplate, DeliveryReceipt};
 use crate::api_error::ApiError;
+use crate::contract_reader::InheritanceContractReader;
+use crate::message_access_audit::{MessageAccessAction, MessageAccessAuditService};
+use crate::message_e2e::E2eEnvelope;
 use chrono::{DateTime, Utc};
 use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM};
 use ring::hkdf::{Salt, HKDF_SHA256};
+use ring::hmac;
 use ring::rand::{SecureRandom, SystemRandom};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use sqlx::PgPool;
 use std::sync::Arc;
 use std::time::Duration;
@@ -12,6 +18,90 @@ use uuid::Uuid;
 
 const NONCE_LEN: usize = 12;
 const KEY_LEN: usize = 32;
+pub const MAX_DELIVERY_ATTEMPTS: i32 = 5;
+const CLAIM_LINK_TTL_DAYS: i64 = 30;
+const STALE_SENDING_MINUTES: i64 = 15;
+const DEFAULT_CLAIM_BASE_URL: &str = "http://localhost:8080";
+
+/// How a legacy message reaches its beneficiary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryChannel {
+    /// The message body is emailed to the beneficiary
+    Email,
+    /// The message body is sent by SMS
+    Sms,
+    /// Only a link is sent; the beneficiary signs in to decrypt the message
+    ClaimLink,
+}
+
+impl DeliveryChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Email => "email",
+            Self::Sms => "sms",
+            Self::ClaimLink => "claim_link",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, ApiError> {
+        match value {
+            "email" => Ok(Self::Email),
+            "sms" => Ok(Self::Sms),
+            "claim_link" => Ok(Self::ClaimLink),
+            other => Err(ApiError::Internal(anyhow::anyhow!(
+                "Unknown delivery channel: {}",
+                other
+            ))),
+        }
+    }
+
+    /// Resolve the channel for `contact`, defaulting by contact type.
+    pub fn resolve(requested: Option<Self>, contact: &str) -> Result<Self, ApiError> {
+        let channel = match requested {
+            Some(channel) => channel,
+            None if is_email(contact) => Self::Email,
+            None if is_phone(contact) => Self::Sms,
+            None => {
+                return Err(ApiError::BadRequest(
+                    "beneficiary_contact must be an email address or E.164 phone number"
+                        .to_string(),
+                ))
+            }
+        };
+
+        let valid = match channel {
+            Self::Email => is_email(contact),
+            Self::Sms => is_phone(contact),
+            Self::ClaimLink => is_email(contact) || is_phone(contact),
+        };
+        if !valid {
+            return Err(ApiError::BadRequest(format!(
+                "beneficiary_contact is not valid for {} delivery",
+                channel.as_str()
+            )));
+        }
+        Ok(channel)
+    }
+}
+
+fn is_email(contact: &str) -> bool {
+    match contact.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty() && domain.contains('.') && !contact.contains(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+fn is_phone(contact: &str) -> bool {
+    contact
+        .strip_prefix('+')
+        .map(|digits| {
+            (8..=15).contains(&digits.len()) && digits.chars().all(|c| c.is_ascii_digit())
+        })
+        .unwrap_or(false)
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateLegacyMessageRequest {
@@ -19,15 +109,26 @@ pub struct CreateLegacyMessageRequest {
     pub beneficiary_contact: String,
     pub message: String,
     pub unlock_at: DateTime<Utc>,
+    /// Defaults to email or SMS depending on `beneficiary_contact`
+    #[serde(default)]
+    pub delivery_channel: Option<DeliveryChannel>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct LegacyMessage {
     pub id: Uuid,
     pub owner_user_id: Uuid,
     pub vault_id: Option<i64>,
     pub beneficiary_contact: String,
-    pub key_version: i32,
+    pub delivery_channel: String,
+    /// `server` (key held by the platform) or `e2e` (encrypted by the owner's client)
+    pub encryption_mode: String,
+    /// Server key version; `None` for end-to-end encrypted messages
+    pub key_version: Option<i32>,
+    /// SHA-256 of the stored ciphertext, as anchored in on-chain message metadata
+    pub content_hash: Option<String>,
+    /// On-chain message anchoring `content_hash` (end-to-end messages)
+    pub on_chain_message_id: Option<i64>,
     pub unlock_at: DateTime<Utc>,
     pub status: String,
     pub delivered_at: Option<DateTime<Utc>>,
@@ -44,6 +145,13 @@ pub struct MessageEncryptionKey {
     pub rotated_at: Option<DateTime<Utc>>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyRotationResult {
+    pub key: MessageEncryptionKey,
+    pub rewrapped_messages: usize,
+    pub destroyed_key_versions: Vec<i32>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeliveryResult {
     pub processed: usize,
@@ -58,10 +166,22 @@ struct DueMessage {
     beneficiary_contact: String,
     encrypted_payload: Vec<u8>,
     payload_nonce: Vec<u8>,
-    key_version: i32,
+    key_version: Option<i32>,
+    wrapped_content_key: Option<Vec<u8>>,
+    content_key_nonce: Option<Vec<u8>>,
+    delivery_channel: String,
+    delivery_attempts: i32,
 }
 
+pub(crate) const MESSAGE_COLUMNS: &str = "id, owner_user_id, vault_id, beneficiary_contact, \
+     delivery_channel, encryption_mode, key_version, content_hash, on_chain_message_id, \
+     unlock_at, status, delivered_at, created_at";
+
 fn derive_key(secret: &[u8], context: &'static [u8]) -> Result<LessSafeKey, ApiError> {
+    aes_key(&derive_key_bytes(secret, context)?)
+}
+
+fn derive_key_bytes(secret: &[u8], context: &'static [u8]) -> Result<[u8; KEY_LEN], ApiError> {
     let salt = Salt::new(HKDF_SHA256, b"inheritx-message-encryption");
     let prk = salt.extract(secret);
     let info = [context];
@@ -71,9 +191,7 @@ fn derive_key(secret: &[u8], context: &'static [u8]) -> Result<LessSafeKey, ApiE
     let mut key_bytes = [0u8; KEY_LEN];
     okm.fill(&mut key_bytes)
         .map_err(|_| ApiError::Internal(anyhow::anyhow!("Unable to materialize key")))?;
-    let unbound = UnboundKey::new(&AES_256_GCM, &key_bytes)
-        .map_err(|_| ApiError::Internal(anyhow::anyhow!("Unable to create key")))?;
-    Ok(LessSafeKey::new(unbound))
+    Ok(key_bytes)
 }
 
 fn load_wrapping_secret() -> Result<Vec<u8>, ApiError> {
@@ -117,6 +235,42 @@ fn decrypt_with_key(
     Ok(plaintext.to_vec())
 }
 
+fn aes_key(raw: &[u8]) -> Result<LessSafeKey, ApiError> {
+    let unbound = UnboundKey::new(&AES_256_GCM, raw)
+        .map_err(|_| ApiError::Internal(anyhow::anyhow!("Unable to create key")))?;
+    Ok(LessSafeKey::new(unbound))
+}
+
+fn unwrap_data_key(encrypted_key: &[u8], wrapping_nonce: &[u8]) -> Result<Vec<u8>, ApiError> {
+    let wrapping_secret = load_wrapping_secret()?;
+    let wrapping_key = derive_key(&wrapping_secret, b"wrap-message-data-key")?;
+    decrypt_with_key(&wrapping_key, encrypted_key, wrapping_nonce)
+}
+
+/// Wrap a per-message content key under a versioned data key
+fn wrap_content_key(data_key: &[u8], content_key: &[u8]) -> Result<(Vec<u8>, Vec<u8>), ApiError> {
+    let wrap_key = derive_key(data_key, b"legacy-message-content-key-wrap")?;
+    encrypt_with_key(&wrap_key, content_key)
+}
+
+/// Recover the content key of a server-side message.
+///
+/// Messages written before per-message content keys existed were encrypted
+/// directly under a key derived from the data key; that key is returned.
+fn content_key_for(
+    data_key: &[u8],
+    wrapped: Option<&[u8]>,
+    wrap_nonce: Option<&[u8]>,
+) -> Result<LessSafeKey, ApiError> {
+    match (wrapped, wrap_nonce) {
+        (Some(wrapped), Some(nonce)) => {
+            let wrap_key = derive_key(data_key, b"legacy-message-content-key-wrap")?;
+            aes_key(&decrypt_with_key(&wrap_key, wrapped, nonce)?)
+        }
+        _ => derive_key(data_key, b"legacy-message-payload-key"),
+    }
+}
+
 pub struct MessageKeyService;
 
 impl MessageKeyService {
@@ -165,10 +319,16 @@ impl MessageKeyService {
             .collect())
     }
 
+    /// Retire the active key and re-wrap every server-side message under its
+    /// replacement, so retired key material can be destroyed.
+    ///
+    /// Only per-message content keys are re-wrapped; payload ciphertext (and
+    /// its anchored hash) is unchanged, except for messages predating content
+    /// keys, which are re-encrypted once under a fresh content key.
     pub async fn rotate_active_key(
         db: &PgPool,
         admin_id: Uuid,
-    ) -> Result<MessageEncryptionKey, ApiError> {
+    ) -> Result<KeyRotationResult, ApiError> {
         let mut tx = db.begin().await?;
 
         sqlx::query(
@@ -179,9 +339,94 @@ impl MessageKeyService {
         .execute(&mut *tx)
         .await?;
 
-        let key = Self::create_new_key_tx(&mut tx, Some(admin_id)).await?;
+        let (key, new_data_key) = Self::create_new_key_tx(&mut tx, Some(admin_id)).await?;
+
+        #[derive(sqlx::FromRow)]
+        struct MessageRow {
+            id: Uuid,
+            key_version: i32,
+            wrapped_content_key: Option<Vec<u8>>,
+            content_key_nonce: Option<Vec<u8>>,
+        }
+
+        let messages = sqlx::query_as::<_, MessageRow>(
+            "SELECT id, key_version, wrapped_content_key, content_key_nonce \
+             FROM legacy_messages \
+             WHERE encryption_mode = 'server' AND key_version <> $1 \
+             FOR UPDATE",
+        )
+        .bind(key.key_version)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut old_keys: std::collections::HashMap<i32, Vec<u8>> =
+            std::collections::HashMap::new();
+        for message in &messages {
+            if let std::collections::hash_map::Entry::Vacant(entry) =
+                old_keys.entry(message.key_version)
+            {
+                let (encrypted_key, wrapping_nonce): (Vec<u8>, Vec<u8>) = sqlx::query_as(
+                    "SELECT encrypted_key, wrapping_nonce \
+                     FROM message_encryption_keys WHERE key_version = $1",
+                )
+                .bind(message.key_version)
+                .fetch_one(&mut *tx)
+                .await?;
+                entry.insert(unwrap_data_key(&encrypted_key, &wrapping_nonce)?);
+            }
+            let old_data_key = &old_keys[&message.key_version];
+
+            // Messages from before per-message content keys keep their
+            // ciphertext, and so their anchored content hash: the key derived
+            // from the old data key becomes their wrapped content key.
+            let content_key = match (&message.wrapped_content_key, &message.content_key_nonce) {
+                (Some(wrapped), Some(nonce)) => {
+                    let unwrap_key = derive_key(old_data_key, b"legacy-message-content-key-wrap")?;
+                    decrypt_with_key(&unwrap_key, wrapped, nonce)?
+                }
+                _ => derive_key_bytes(old_data_key, b"legacy-message-payload-key")?.to_vec(),
+            };
+            let (wrapped, nonce) = wrap_content_key(&new_data_key, &content_key)?;
+            sqlx::query(
+                "UPDATE legacy_messages \
+                 SET key_version = $2, wrapped_content_key = $3, content_key_nonce = $4, \
+                     updated_at = NOW() \
+                 WHERE id = $1",
+            )
+            .bind(message.id)
+            .bind(key.key_version)
+            .bind(&wrapped)
+            .bind(&nonce)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        // Nothing references retired keys any more; drop their material.
+        let destroyed_key_versions: Vec<i32> = sqlx::query_scalar(
+            "UPDATE message_encryption_keys \
+             SET status = 'destroyed', encrypted_key = ''::bytea, wrapping_nonce = ''::bytea \
+             WHERE status = 'retired' \
+               AND NOT EXISTS (SELECT 1 FROM legacy_messages m \
+                               WHERE m.key_version = message_encryption_keys.key_version) \
+             RETURNING key_version",
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
         tx.commit().await?;
-        Ok(key)
+
+        info!(
+            "Rotated message key to version {}: re-wrapped {} messages, destroyed versions {:?}",
+            key.key_version,
+            messages.len(),
+            destroyed_key_versions
+        );
+
+        Ok(KeyRotationResult {
+            key,
+            rewrapped_messages: messages.len(),
+            destroyed_key_versions,
+        })
     }
 
     pub async fn active_data_key_material(db: &PgPool) -> Result<(i32, Vec<u8>), ApiError> {
@@ -200,9 +445,7 @@ impl MessageKeyService {
         .await?
         .ok_or_else(|| ApiError::NotFound("No active message key found".to_string()))?;
 
-        let wrapping_secret = load_wrapping_secret()?;
-        let wrapping_key = derive_key(&wrapping_secret, b"wrap-message-data-key")?;
-        let data_key = decrypt_with_key(&wrapping_key, &row.encrypted_key, &row.wrapping_nonce)?;
+        let data_key = unwrap_data_key(&row.encrypted_key, &row.wrapping_nonce)?;
         Ok((row.key_version, data_key))
     }
 
@@ -217,7 +460,7 @@ impl MessageKeyService {
         }
         let row = sqlx::query_as::<_, Row>(
             "SELECT encrypted_key, wrapping_nonce \
-             FROM message_encryption_keys WHERE key_version = $1",
+             FROM message_encryption_keys WHERE key_version = $1 AND status <> 'destroyed'",
         )
         .bind(key_version)
         .fetch_optional(db)
@@ -226,9 +469,7 @@ impl MessageKeyService {
             ApiError::NotFound(format!("Message key version {} not found", key_version))
         })?;
 
-        let wrapping_secret = load_wrapping_secret()?;
-        let wrapping_key = derive_key(&wrapping_secret, b"wrap-message-data-key")?;
-        decrypt_with_key(&wrapping_key, &row.encrypted_key, &row.wrapping_nonce)
+        unwrap_data_key(&row.encrypted_key, &row.wrapping_nonce)
     }
 
     async fn create_new_key(
@@ -236,7 +477,7 @@ impl MessageKeyService {
         created_by_admin_id: Option<Uuid>,
     ) -> Result<MessageEncryptionKey, ApiError> {
         let mut tx = db.begin().await?;
-        let key = Self::create_new_key_tx(&mut tx, created_by_admin_id).await?;
+        let (key, _) = Self::create_new_key_tx(&mut tx, created_by_admin_id).await?;
         tx.commit().await?;
         Ok(key)
     }
@@ -244,7 +485,7 @@ impl MessageKeyService {
     async fn create_new_key_tx(
         tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
         created_by_admin_id: Option<Uuid>,
-    ) -> Result<MessageEncryptionKey, ApiError> {
+    ) -> Result<(MessageEncryptionKey, Vec<u8>), ApiError> {
         let next_version: i32 = sqlx::query_scalar(
             "SELECT COALESCE(MAX(key_version), 0) + 1 FROM message_encryption_keys",
         )
@@ -283,126 +524,118 @@ impl MessageKeyService {
         .fetch_one(&mut **tx)
         .await?;
 
-        Ok(MessageEncryptionKey {
-            id: row.id,
-            key_version: row.key_version,
-            status: row.status,
-            created_by_admin_id: row.created_by_admin_id,
-            created_at: row.created_at,
-            rotated_at: row.rotated_at,
-        })
+        Ok((
+            MessageEncryptionKey {
+                id: row.id,
+                key_version: row.key_version,
+                status: row.status,
+                created_by_admin_id: row.created_by_admin_id,
+                created_at: row.created_at,
+                rotated_at: row.rotated_at,
+            },
+            raw_key.to_vec(),
+        ))
     }
 }
 
+/// Payload sealed under a fresh content key, itself wrapped by a data key
+struct SealedPayload {
+    ciphertext: Vec<u8>,
+    nonce: Vec<u8>,
+    wrapped_key: Vec<u8>,
+    wrap_nonce: Vec<u8>,
+}
+
+fn seal_server_payload(data_key: &[u8], plaintext: &[u8]) -> Result<SealedPayload, ApiError> {
+    let mut content_key = [0u8; KEY_LEN];
+    SystemRandom::new()
+        .fill(&mut content_key)
+        .map_err(|_| ApiError::Internal(anyhow::anyhow!("Failed to generate content key")))?;
+    let (ciphertext, nonce) = encrypt_with_key(&aes_key(&content_key)?, plaintext)?;
+    let (wrapped_key, wrap_nonce) = wrap_content_key(data_key, &content_key)?;
+    Ok(SealedPayload {
+        ciphertext,
+        nonce,
+        wrapped_key,
+        wrap_nonce,
+    })
+}
+
 pub struct MessageEncryptionService;
 
 impl MessageEncryptionService {
-    pub async fn create_encrypted_message(
-        db: &PgPool,
-        owner_user_id: Uuid,
-        req: &CreateLegacyMessageRequest,
-    ) -> Result<LegacyMessage, ApiError> {
-        if req.unlock_at <= Utc::now() {
+    pub(crate) fn validate_new_message(
+        unlock_at: DateTime<Utc>,
+        beneficiary_contact: &str,
+    ) -> Result<(), ApiError> {
+        if unlock_at <= Utc::now() {
             return Err(ApiError::BadRequest(
                 "unlock_at must be in the future".to_string(),
             ));
         }
-        if req.message.trim().is_empty() {
-            return Err(ApiError::BadRequest("message cannot be empty".to_string()));
-        }
-        if req.beneficiary_contact.trim().is_empty() {
+        if beneficiary_contact.trim().is_empty() {
             return Err(ApiError::BadRequest(
                 "beneficiary_contact cannot be empty".to_string(),
             ));
         }
+        Ok(())
+    }
+
+    pub async fn create_encrypted_message(
+        db: &PgPool,
+        owner_user_id: Uuid,
+        req: &CreateLegacyMessageRequest,
+    ) -> Result<LegacyMessage, ApiError> {
+        if req.message.trim().is_empty() {
+            return Err(ApiError::BadRequest("message cannot be empty".to_string()));
+        }
+        Self::validate_new_message(req.unlock_at, &req.beneficiary_contact)?;
+        let channel =
+            DeliveryChannel::resolve(req.delivery_channel, req.beneficiary_contact.trim())?;
 
         MessageKeyService::ensure_active_key(db).await?;
         let (key_version, data_key) = MessageKeyService::active_data_key_material(db).await?;
-        let payload_key = derive_key(&data_key, b"legacy-message-payload-key")?;
-        let (encrypted_payload, payload_nonce) =
-            encrypt_with_key(&payload_key, req.message.as_bytes())?;
-
-        #[derive(sqlx::FromRow)]
-        struct Row {
-            id: Uuid,
-            owner_user_id: Uuid,
-            vault_id: Option<i64>,
-            beneficiary_contact: String,
-            key_version: i32,
-            unlock_at: DateTime<Utc>,
-            status: String,
-            delivered_at: Option<DateTime<Utc>>,
-            created_at: DateTime<Utc>,
-        }
+        let sealed = seal_server_payload(&data_key, req.message.as_bytes())?;
 
-        let row = sqlx::query_as::<_, Row>(
+        let message = sqlx::query_as::<_, LegacyMessage>(&format!(
             "INSERT INTO legacy_messages \
-             (owner_user_id, vault_id, beneficiary_contact, encrypted_payload, payload_nonce, key_version, unlock_at, status) \
-             VALUES ($1, $2, $3, $4, $5, $6, $7, 'pending') \
-             RETURNING id, owner_user_id, vault_id, beneficiary_contact, key_version, unlock_at, status, delivered_at, created_at",
-        )
+             (owner_user_id, vault_id, beneficiary_contact, encrypted_payload, payload_nonce, \
+              key_version, unlock_at, status, delivery_channel, encryption_mode, \
+              wrapped_content_key, content_key_nonce, content_hash) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, 'pending', $8, 'server', $9, $10, $11) \
+             RETURNING {}",
+            MESSAGE_COLUMNS
+        ))
         .bind(owner_user_id)
         .bind(req.vault_id)
         .bind(req.beneficiary_contact.trim())
-        .bind(&encrypted_payload)
-        .bind(&payload_nonce)
+        .bind(&sealed.ciphertext)
+        .bind(&sealed.nonce)
         .bind(key_version)
         .bind(req.unlock_at)
+        .bind(channel.as_str())
+        .bind(&sealed.wrapped_key)
+        .bind(&sealed.wrap_nonce)
+        .bind(hex::encode(Sha256::digest(&sealed.ciphertext)))
         .fetch_one(db)
         .await?;
 
-        Ok(LegacyMessage {
-            id: row.id,
-            owner_user_id: row.owner_user_id,
-            vault_id: row.vault_id,
-            beneficiary_contact: row.beneficiary_contact,
-            key_version: row.key_version,
-            unlock_at: row.unlock_at,
-            status: row.status,
-            delivered_at: row.delivered_at,
-            created_at: row.created_at,
-        })
+        Ok(message)
     }
 
     pub async fn list_owner_messages(
         db: &PgPool,
         owner_user_id: Uuid,
     ) -> Result<Vec<LegacyMessage>, ApiError> {
-        #[derive(sqlx::FromRow)]
-        struct Row {
-            id: Uuid,
-            owner_user_id: Uuid,
-            vault_id: Option<i64>,
-            beneficiary_contact: String,
-            key_version: i32,
-            unlock_at: DateTime<Utc>,
-            status: String,
-            delivered_at: Option<DateTime<Utc>>,
-            created_at: DateTime<Utc>,
-        }
-
-        let rows = sqlx::query_as::<_, Row>(
-            "SELECT id, owner_user_id, vault_id, beneficiary_contact, key_version, unlock_at, status, delivered_at, created_at \
-             FROM legacy_messages WHERE owner_user_id = $1 ORDER BY created_at DESC",
-        )
+        let messages = sqlx::query_as::<_, LegacyMessage>(&format!(
+            "SELECT {} FROM legacy_messages WHERE owner_user_id = $1 ORDER BY created_at DESC",
+            MESSAGE_COLUMNS
+        ))
         .bind(owner_user_id)
         .fetch_all(db)
         .await?;
 
-        Ok(rows
-            .into_iter()
-            .map(|r| LegacyMessage {
-                id: r.id,
-                owner_user_id: r.owner_user_id,
-                vault_id: r.vault_id,
-                beneficiary_contact: r.beneficiary_contact,
-                key_version: r.key_version,
-                unlock_at: r.unlock_at,
-                status: r.status,
-                delivered_at: r.delivered_at,
-                created_at: r.created_at,
-            })
-            .collect())
+        Ok(messages)
     }
 
     pub async fn list_vault_messages(
@@ -410,52 +643,151 @@ impl MessageEncryptionService {
         owner_user_id: Uuid,
         vault_id: i64,
     ) -> Result<Vec<LegacyMessage>, ApiError> {
-        #[derive(sqlx::FromRow)]
-        struct Row {
-            id: Uuid,
-            owner_user_id: Uuid,
-            vault_id: Option<i64>,
-            beneficiary_contact: String,
-            key_version: i32,
-            unlock_at: DateTime<Utc>,
-            status: String,
-            delivered_at: Option<DateTime<Utc>>,
-            created_at: DateTime<Utc>,
-        }
-
-        let rows = sqlx::query_as::<_, Row>(
-            "SELECT id, owner_user_id, vault_id, beneficiary_contact, key_version, unlock_at, status, delivered_at, created_at \
-             FROM legacy_messages WHERE owner_user_id = $1 AND vault_id = $2 ORDER BY created_at DESC",
-        )
+        let messages = sqlx::query_as::<_, LegacyMessage>(&format!(
+            "SELECT {} FROM legacy_messages \
+             WHERE owner_user_id = $1 AND vault_id = $2 ORDER BY created_at DESC",
+            MESSAGE_COLUMNS
+        ))
         .bind(owner_user_id)
         .bind(vault_id)
         .fetch_all(db)
         .await?;
 
-        Ok(rows
-            .into_iter()
-            .map(|r| LegacyMessage {
-                id: r.id,
-                owner_user_id: r.owner_user_id,
-                vault_id: r.vault_id,
-                beneficiary_contact: r.beneficiary_contact,
-                key_version: r.key_version,
-                unlock_at: r.unlock_at,
-                status: r.status,
-                delivered_at: r.delivered_at,
-                created_at: r.created_at,
-            })
-            .collect())
+        Ok(messages)
+    }
+}
+
+/// A single delivery attempt of a legacy message. Never carries plaintext.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct LegacyMessageDelivery {
+    pub id: Uuid,
+    pub message_id: Uuid,
+    pub beneficiary_contact: String,
+    pub channel: String,
+    pub status: String,
+    pub attempt: i32,
+    pub provider_message_id: Option<String>,
+    pub error: Option<String>,
+    pub claim_expires_at: Option<DateTime<Utc>>,
+    pub claimed_at: Option<DateTime<Utc>>,
+    pub attempted_at: DateTime<Utc>,
+    pub receipt_at: Option<DateTime<Utc>>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+/// Outcome reported by a provider webhook
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiptStatus {
+    Delivered,
+    /// Permanent rejection (unknown mailbox, invalid number)
+    Bounced,
+    /// Transient failure; the message is retried
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryReceiptEvent {
+    pub provider_message_id: String,
+    pub status: ReceiptStatus,
+    pub reason: Option<String>,
+}
+
+/// A legacy message released to the beneficiary who claimed it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaimedLegacyMessage {
+    pub message_id: Uuid,
+    pub vault_id: Option<i64>,
+    pub encryption_mode: String,
+    /// Decrypted text for server-side messages
+    pub message: Option<String>,
+    /// Ciphertext and wrapped keys for end-to-end messages, opened client-side
+    pub envelope: Option<E2eEnvelope>,
+    pub unlock_at: DateTime<Utc>,
+    pub claimed_at: DateTime<Utc>,
+}
+
+/// Verify the hex HMAC-SHA256 a provider attaches to receipt webhooks.
+pub fn verify_receipt_signature(
+    secret: &[u8],
+    body: &[u8],
+    signature_hex: &str,
+) -> Result<(), ApiError> {
+    if secret.is_empty() {
+        return Err(ApiError::Unauthorized);
+    }
+    let signature = hex::decode(signature_hex.trim()).map_err(|_| ApiError::Unauthorized)?;
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret);
+    hmac::verify(&key, body, &signature).map_err(|_| ApiError::Unauthorized)
+}
+
+fn hash_claim_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Backoff before the next attempt after `attempts` failures
+fn retry_delay_minutes(attempts: i32) -> i64 {
+    let attempts = attempts.clamp(1, MAX_DELIVERY_ATTEMPTS) as i64;
+    attempts * attempts * 5
+}
+
+async fn decrypt_payload(
+    db: &PgPool,
+    key_version: Option<i32>,
+    ciphertext: &[u8],
+    nonce: &[u8],
+    wrapped_content_key: Option<&[u8]>,
+    content_key_nonce: Option<&[u8]>,
+) -> Result<String, ApiError> {
+    let key_version = key_version.ok_or_else(|| {
+        ApiError::BadRequest(
+            "End-to-end encrypted messages cannot be decrypted by the server".to_string(),
+        )
+    })?;
+    let key_material = MessageKeyService::key_material_by_version(db, key_version).await?;
+    let payload_key = content_key_for(&key_material, wrapped_content_key, content_key_nonce)?;
+    let decrypted = decrypt_with_key(&payload_key, ciphertext, nonce)?;
+    String::from_utf8(decrypted)
+        .map_err(|_| ApiError::Internal(anyhow::anyhow!("Invalid UTF-8 payload")))
+}
+
+async fn audit(
+    db: &PgPool,
+    message_id: Uuid,
+    user_id: Uuid,
+    action: MessageAccessAction,
+    metadata: serde_json::Value,
+) {
+    if let Err(e) = MessageAccessAuditService::log_access(
+        db,
+        Some(message_id),
+        user_id,
+        action,
+        None,
+        None,
+        metadata,
+    )
+    .await
+    {
+        warn!("Failed to audit legacy message {}: {}", message_id, e);
     }
 }
 
 pub struct LegacyMessageDeliveryService {
     db: PgPool,
+    provider: Arc<dyn AlertProvider>,
+    claim_base_url: String,
 }
 
 impl LegacyMessageDeliveryService {
-    pub fn new(db: PgPool) -> Self {
-        Self { db }
+    pub fn new(db: PgPool, provider: Arc<dyn AlertProvider>) -> Self {
+        let claim_base_url = std::env::var("LEGACY_MESSAGE_CLAIM_BASE_URL")
+            .unwrap_or_else(|_| DEFAULT_CLAIM_BASE_URL.to_string());
+        Self {
+            db,
+            provider,
+            claim_base_url: claim_base_url.trim_end_matches('/').to_string(),
+        }
     }
 
     pub fn start(self: Arc<Self>) {
@@ -471,12 +803,24 @@ impl LegacyMessageDeliveryService {
     }
 
     pub async fn process_due_messages(&self) -> Result<DeliveryResult, ApiError> {
+        // Claim due messages so concurrent workers never send the same one;
+        // rows left in 'sending' by a crashed worker are picked up again.
         let due_messages = sqlx::query_as::<_, DueMessage>(
-            "SELECT id, owner_user_id, beneficiary_contact, encrypted_payload, payload_nonce, key_version \
-             FROM legacy_messages \
-             WHERE status = 'pending' AND unlock_at <= NOW() \
-             ORDER BY unlock_at ASC LIMIT 100",
+            "UPDATE legacy_messages \
+             SET status = 'sending', delivery_attempts = delivery_attempts + 1, \
+                 last_attempt_at = NOW(), updated_at = NOW() \
+             WHERE id IN ( \
+                 SELECT id FROM legacy_messages \
+                 WHERE unlock_at <= NOW() \
+                   AND ((status = 'pending' AND (next_attempt_at IS NULL OR next_attempt_at <= NOW())) \
+                        OR (status = 'sending' AND last_attempt_at < NOW() - make_interval(mins => $1))) \
+                 ORDER BY unlock_at ASC LIMIT 100 \
+                 FOR UPDATE SKIP LOCKED) \
+             RETURNING id, owner_user_id, beneficiary_contact, encrypted_payload, payload_nonce, \
+                       key_version, wrapped_content_key, content_key_nonce, delivery_channel, \
+                       delivery_attempts",
         )
+        .bind(STALE_SENDING_MINUTES as i32)
         .fetch_all(&self.db)
         .await?;
 
@@ -491,10 +835,12 @@ impl LegacyMessageDeliveryService {
                 Err(e) => {
                     failed += 1;
                     warn!("Failed delivering legacy message {}: {}", row.id, e);
-                    sqlx::query("UPDATE legacy_messages SET status = 'failed', updated_at = NOW() WHERE id = $1")
-                        .bind(row.id)
-                        .execute(&self.db)
-                        .await?;
+                    if let Err(e) = self.record_failed_attempt(row, &e.to_string()).await {
+                        error!(
+                            "Failed recording delivery failure of legacy message {}: {}",
+                            row.id, e
+                        );
+                    }
                 }
             }
         }
@@ -510,40 +856,458 @@ impl LegacyMessageDeliveryService {
         })
     }
 
+    async fn send(
+        &self,
+        contact: &str,
+        template: &AlertTemplate<'_>,
+    ) -> Result<DeliveryReceipt, ApiError> {
+        let sent = if is_email(contact) {
+            self.provider.send_templated_email(contact, template).await
+        } else {
+            self.provider.send_templated_sms(contact, template).await
+        };
+        sent.map_err(ApiError::Internal)
+    }
+
+    /// Deliver one message. The attempt and the message's new status are
+    /// committed before the provider is called, so a database failure after
+    /// a successful send cannot lead to the message being sent again.
     async fn deliver_single(&self, row: &DueMessage) -> Result<(), ApiError> {
-        let key_material =
-            MessageKeyService::key_material_by_version(&self.db, row.key_version).await?;
-        let payload_key = derive_key(&key_material, b"legacy-message-payload-key")?;
-        let decrypted = decrypt_with_key(&payload_key, &row.encrypted_payload, &row.payload_nonce)?;
-        let decrypted_payload = String::from_utf8(decrypted)
-            .map_err(|_| ApiError::Internal(anyhow::anyhow!("Invalid UTF-8 payload")))?;
+        let channel = DeliveryChannel::parse(&row.delivery_channel)?;
+
+        let message: String;
+        let claim_url: String;
+        let (template, claim, message_status) = match channel {
+            DeliveryChannel::Email | DeliveryChannel::Sms => {
+                // The plaintext only lives in memory for the provider hand-off.
+                message = decrypt_payload(
+                    &self.db,
+                    row.key_version,
+                    &row.encrypted_payload,
+                    &row.payload_nonce,
+                    row.wrapped_content_key.as_deref(),
+                    row.content_key_nonce.as_deref(),
+                )
+                .await?;
+                (
+                    AlertTemplate::LegacyMessage { message: &message },
+                    None,
+                    "delivered",
+                )
+            }
+            DeliveryChannel::ClaimLink => {
+                let mut token = [0u8; 32];
+                SystemRandom::new().fill(&mut token).map_err(|_| {
+                    ApiError::Internal(anyhow::anyhow!("Failed to generate claim token"))
+                })?;
+                let token = hex::encode(token);
+                let expires_at = Utc::now() + chrono::Duration::days(CLAIM_LINK_TTL_DAYS);
+                claim_url = format!(
+                    "{}/api/messages/legacy/claim/{}",
+                    self.claim_base_url, token
+                );
+                (
+                    AlertTemplate::LegacyMessageClaimLink {
+                        claim_url: &claim_url,
+                        expires_at,
+                    },
+                    Some((hash_claim_token(&token), expires_at)),
+                    "awaiting_claim",
+                )
+            }
+        };
 
         let mut tx = self.db.begin().await?;
 
-        sqlx::query(
+        let delivery_id: Uuid = sqlx::query_scalar(
             "INSERT INTO legacy_message_deliveries \
-             (message_id, owner_user_id, beneficiary_contact, decrypted_payload, delivered_at) \
-             VALUES ($1, $2, $3, $4, NOW())",
+             (message_id, owner_user_id, beneficiary_contact, channel, status, attempt, \
+              claim_token_hash, claim_expires_at) \
+             VALUES ($1, $2, $3, $4, 'sending', $5, $6, $7) \
+             RETURNING id",
         )
         .bind(row.id)
         .bind(row.owner_user_id)
         .bind(&row.beneficiary_contact)
-        .bind(&decrypted_payload)
-        .execute(&mut *tx)
+        .bind(channel.as_str())
+        .bind(row.delivery_attempts)
+        .bind(claim.as_ref().map(|(hash, _)| hash))
+        .bind(claim.as_ref().map(|(_, expires_at)| *expires_at))
+        .fetch_one(&mut *tx)
         .await?;
 
         sqlx::query(
             "UPDATE legacy_messages \
-             SET status = 'delivered', delivered_at = NOW(), updated_at = NOW() \
+             SET status = $2, next_attempt_at = NULL, updated_at = NOW(), \
+                 delivered_at = CASE WHEN $2 = 'delivered' THEN NOW() ELSE delivered_at END \
              WHERE id = $1",
         )
         .bind(row.id)
+        .bind(message_status)
         .execute(&mut *tx)
         .await?;
 
         tx.commit().await?;
+
+        let receipt = self.send(&row.beneficiary_contact, &template).await?;
+
+        // Only receipt matching depends on this; the send itself stands
+        if let Err(e) = sqlx::query(
+            "UPDATE legacy_message_deliveries \
+             SET status = 'sent', provider_message_id = $2 WHERE id = $1",
+        )
+        .bind(delivery_id)
+        .bind(&receipt.provider_message_id)
+        .execute(&self.db)
+        .await
+        {
+            error!(
+                "Legacy message {} was sent as {} but the delivery was not updated: {}",
+                row.id, receipt.provider_message_id, e
+            );
+        }
+
+        audit(
+            &self.db,
+            row.id,
+            row.owner_user_id,
+            MessageAccessAction::Delivered,
+            serde_json::json!({
+                "delivery_id": delivery_id,
+                "channel": channel.as_str(),
+                "attempt": row.delivery_attempts,
+                "provider_message_id": receipt.provider_message_id,
+            }),
+        )
+        .await;
+        Ok(())
+    }
+
+    /// Fail the attempt, recorded before the send or not at all, and
+    /// requeue the message
+    async fn record_failed_attempt(&self, row: &DueMessage, error: &str) -> Result<(), ApiError> {
+        let updated = sqlx::query(
+            "UPDATE legacy_message_deliveries SET status = 'failed', error = $3 \
+             WHERE message_id = $1 AND attempt = $2 AND status = 'sending'",
+        )
+        .bind(row.id)
+        .bind(row.delivery_attempts)
+        .bind(error)
+        .execute(&self.db)
+        .await?
+        .rows_affected();
+        if updated == 0 {
+            sqlx::query(
+                "INSERT INTO legacy_message_deliveries \
+                 (message_id, owner_user_id, beneficiary_contact, channel, status, attempt, error) \
+                 VALUES ($1, $2, $3, $4, 'failed', $5, $6)",
+            )
+            .bind(row.id)
+            .bind(row.owner_user_id)
+            .bind(&row.beneficiary_contact)
+            .bind(&row.delivery_channel)
+            .bind(row.delivery_attempts)
+            .bind(error)
+            .execute(&self.db)
+            .await?;
+        }
+
+        let status = Self::schedule_retry(&self.db, row.id).await?;
+
+        audit(
+            &self.db,
+            row.id,
+            row.owner_user_id,
+            MessageAccessAction::DeliveryFailed,
+            serde_json::json!({
+                "channel": row.delivery_channel,
+                "attempt": row.delivery_attempts,
+                "error": error,
+                "status": status,
+            }),
+        )
+        .await;
         Ok(())
     }
+
+    /// Requeue a message with backoff, or fail it once attempts run out.
+    async fn schedule_retry(db: &PgPool, message_id: Uuid) -> Result<String, ApiError> {
+        let attempts: i32 =
+            sqlx::query_scalar("SELECT delivery_attempts FROM legacy_messages WHERE id = $1")
+                .bind(message_id)
+                .fetch_one(db)
+                .await?;
+
+        let status = if attempts >= MAX_DELIVERY_ATTEMPTS {
+            "failed"
+        } else {
+            "pending"
+        };
+        sqlx::query(
+            "UPDATE legacy_messages \
+             SET status = $2, next_attempt_at = NOW() + make_interval(mins => $3), \
+                 updated_at = NOW() \
+             WHERE id = $1",
+        )
+        .bind(message_id)
+        .bind(status)
+        .bind(retry_delay_minutes(attempts) as i32)
+        .execute(db)
+        .await?;
+
+        Ok(status.to_string())
+    }
+
+    /// Apply a provider delivery receipt or bounce to its attempt.
+    pub async fn record_receipt(
+        db: &PgPool,
+        event: &DeliveryReceiptEvent,
+    ) -> Result<LegacyMessageDelivery, ApiError> {
+        let delivery_status = match event.status {
+            ReceiptStatus::Delivered => "delivered",
+            ReceiptStatus::Bounced => "bounced",
+            ReceiptStatus::Failed => "failed",
+        };
+
+        let delivery = sqlx::query_as::<_, LegacyMessageDelivery>(
+            "UPDATE legacy_message_deliveries \
+             SET status = $2, error = $3, receipt_at = NOW(), \
+                 delivered_at = CASE WHEN $2 = 'delivered' THEN NOW() ELSE delivered_at END \
+             WHERE provider_message_id = $1 AND status IN ('sent', 'delivered') \
+             RETURNING id, message_id, beneficiary_contact, channel, status, attempt, \
+                       provider_message_id, error, claim_expires_at, claimed_at, attempted_at, \
+                       receipt_at, delivered_at",
+        )
+        .bind(&event.provider_message_id)
+        .bind(delivery_status)
+        .bind(&event.reason)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| {
+            ApiError::NotFound(format!(
+                "No open delivery for provider message {}",
+                event.provider_message_id
+            ))
+        })?;
+
+        let owner_user_id: Uuid =
+            sqlx::query_scalar("SELECT owner_user_id FROM legacy_messages WHERE id = $1")
+                .bind(delivery.message_id)
+                .fetch_one(db)
+                .await?;
+
+        let action = match event.status {
+            ReceiptStatus::Delivered => MessageAccessAction::Delivered,
+            ReceiptStatus::Bounced => {
+                sqlx::query(
+                    "UPDATE legacy_messages SET status = 'bounced', updated_at = NOW() \
+                     WHERE id = $1 AND status IN ('delivered', 'awaiting_claim')",
+                )
+                .bind(delivery.message_id)
+                .execute(db)
+                .await?;
+                MessageAccessAction::DeliveryBounced
+            }
+            ReceiptStatus::Failed => {
+                Self::schedule_retry(db, delivery.message_id).await?;
+                MessageAccessAction::DeliveryFailed
+            }
+        };
+
+        audit(
+            db,
+            delivery.message_id,
+            owner_user_id,
+            action,
+            serde_json::json!({
+                "delivery_id": delivery.id,
+                "channel": delivery.channel,
+                "provider_message_id": event.provider_message_id,
+                "receipt": delivery_status,
+                "reason": event.reason,
+            }),
+        )
+        .await;
+
+        Ok(delivery)
+    }
+
+    /// Apply a provider's batch of receipts, skipping those for messages
+    /// that are not legacy message deliveries. Returns how many applied.
+    pub async fn record_receipts(
+        db: &PgPool,
+        events: &[DeliveryReceiptEvent],
+    ) -> Result<usize, ApiError> {
+        let mut applied = 0;
+        for event in events {
+            match Self::record_receipt(db, event).await {
+                Ok(_) => applied += 1,
+                Err(ApiError::NotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(applied)
+    }
+
+    /// Delivery attempts for one of the owner's messages, newest first
+    pub async fn list_deliveries(
+        db: &PgPool,
+        owner_user_id: Uuid,
+        message_id: Uuid,
+    ) -> Result<Vec<LegacyMessageDelivery>, ApiError> {
+        let rows = sqlx::query_as::<_, LegacyMessageDelivery>(
+            "SELECT id, message_id, beneficiary_contact, channel, status, attempt, \
+                    provider_message_id, error, claim_expires_at, claimed_at, attempted_at, \
+                    receipt_at, delivered_at \
+             FROM legacy_message_deliveries \
+             WHERE message_id = $1 AND owner_user_id = $2 \
+             ORDER BY attempted_at DESC",
+        )
+        .bind(message_id)
+        .bind(owner_user_id)
+        .fetch_all(db)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Release a claim-link message to a signed-in beneficiary.
+    ///
+    /// Email contacts must match the account's email. For SMS contacts the
+    /// first account to redeem the link is bound to it. Server-side messages
+    /// are decrypted; end-to-end messages are returned as an envelope for the
+    /// beneficiary's client to open.
+    pub async fn claim_message(
+        db: &PgPool,
+        reader: &dyn InheritanceContractReader,
+        token: &str,
+        user_id: Uuid,
+        user_email: &str,
+    ) -> Result<ClaimedLegacyMessage, ApiError> {
+        #[derive(sqlx::FromRow)]
+        struct Row {
+            delivery_id: Uuid,
+            delivery_status: String,
+            claim_expires_at: Option<DateTime<Utc>>,
+            claimed_by_user_id: Option<Uuid>,
+            message_id: Uuid,
+            vault_id: Option<i64>,
+            beneficiary_contact: String,
+            encryption_mode: String,
+            encrypted_payload: Vec<u8>,
+            payload_nonce: Vec<u8>,
+            key_version: Option<i32>,
+            wrapped_content_key: Option<Vec<u8>>,
+            content_key_nonce: Option<Vec<u8>>,
+            unlock_at: DateTime<Utc>,
+        }
+
+        let row = sqlx::query_as::<_, Row>(
+            "SELECT d.id AS delivery_id, d.status AS delivery_status, d.claim_expires_at, \
+                    d.claimed_by_user_id, m.id AS message_id, m.vault_id, m.beneficiary_contact, \
+                    m.encryption_mode, m.encrypted_payload, m.payload_nonce, m.key_version, \
+                    m.wrapped_content_key, m.content_key_nonce, m.unlock_at \
+             FROM legacy_message_deliveries d \
+             JOIN legacy_messages m ON m.id = d.message_id \
+             WHERE d.claim_token_hash = $1 AND d.channel = 'claim_link'",
+        )
+        .bind(hash_claim_token(token))
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Claim link not found".to_string()))?;
+
+        if !matches!(
+            row.delivery_status.as_str(),
+            "sent" | "delivered" | "claimed"
+        ) {
+            return Err(ApiError::Forbidden(
+                "Claim link is no longer valid".to_string(),
+            ));
+        }
+        if row.claim_expires_at.is_none_or(|at| at <= Utc::now()) {
+            return Err(ApiError::Forbidden("Claim link has expired".to_string()));
+        }
+        if is_email(&row.beneficiary_contact)
+            && !row.beneficiary_contact.eq_ignore_ascii_case(user_email)
+        {
+            return Err(ApiError::Forbidden(
+                "This message was addressed to a different account".to_string(),
+            ));
+        }
+        if row.claimed_by_user_id.is_some_and(|id| id != user_id) {
+            return Err(ApiError::Forbidden(
+                "This message has already been claimed".to_string(),
+            ));
+        }
+
+        let claimed_at: Option<DateTime<Utc>> = sqlx::query_scalar(
+            "UPDATE legacy_message_deliveries \
+             SET status = 'claimed', claimed_by_user_id = $2, \
+                 claimed_at = COALESCE(claimed_at, NOW()) \
+             WHERE id = $1 AND (claimed_by_user_id IS NULL OR claimed_by_user_id = $2) \
+             RETURNING claimed_at",
+        )
+        .bind(row.delivery_id)
+        .bind(user_id)
+        .fetch_optional(db)
+        .await?;
+        let claimed_at = claimed_at.ok_or_else(|| {
+            ApiError::Forbidden("This message has already been claimed".to_string())
+        })?;
+
+        sqlx::query(
+            "UPDATE legacy_messages \
+             SET status = 'claimed', delivered_at = COALESCE(delivered_at, NOW()), updated_at = NOW() \
+             WHERE id = $1",
+        )
+        .bind(row.message_id)
+        .execute(db)
+        .await?;
+
+        let (message, envelope, action) = if row.encryption_mode == "e2e" {
+            let envelope = E2eEnvelope::load(
+                db,
+                reader,
+                row.message_id,
+                row.encrypted_payload,
+                row.payload_nonce,
+            )
+            .await?;
+            (None, Some(envelope), MessageAccessAction::Viewed)
+        } else {
+            let message = decrypt_payload(
+                db,
+                row.key_version,
+                &row.encrypted_payload,
+                &row.payload_nonce,
+                row.wrapped_content_key.as_deref(),
+                row.content_key_nonce.as_deref(),
+            )
+            .await?;
+            (Some(message), None, MessageAccessAction::Decrypted)
+        };
+
+        audit(
+            db,
+            row.message_id,
+            user_id,
+            action,
+            serde_json::json!({
+                "delivery_id": row.delivery_id,
+                "channel": DeliveryChannel::ClaimLink.as_str(),
+            }),
+        )
+        .await;
+
+        Ok(ClaimedLegacyMessage {
+            message_id: row.message_id,
+            vault_id: row.vault_id,
+            encryption_mode: row.encryption_mode,
+            message,
+            envelope,
+            unlock_at: row.unlock_at,
+            claimed_at,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -559,4 +1323,97 @@ mod tests {
         let decrypted = decrypt_with_key(&key, &ciphertext, &nonce).unwrap();
         assert_eq!(decrypted, plaintext);
     }
+
+    #[test]
+    fn delivery_channel_defaults_by_contact_type() {
+        assert_eq!(
+            DeliveryChannel::resolve(None, "heir@example.com").unwrap(),
+            DeliveryChannel::Email
+        );
+        assert_eq!(
+            DeliveryChannel::resolve(None, "+2348012345678").unwrap(),
+            DeliveryChannel::Sms
+        );
+        assert!(DeliveryChannel::resolve(None, "not a contact").is_err());
+    }
+
+    #[test]
+    fn delivery_channel_must_match_contact() {
+        assert!(DeliveryChannel::resolve(Some(DeliveryChannel::Sms), "heir@example.com").is_err());
+        assert!(DeliveryChannel::resolve(Some(DeliveryChannel::Email), "+2348012345678").is_err());
+        assert_eq!(
+            DeliveryChannel::resolve(Some(DeliveryChannel::ClaimLink), "+2348012345678").unwrap(),
+            DeliveryChannel::ClaimLink
+        );
+    }
+
+    #[test]
+    fn receipt_signature_verification() {
+        let secret = b"webhook-secret";
+        let body = br#"{"provider_message_id":"abc","status":"bounced"}"#;
+        let key = hmac::Key::new(hmac::HMAC_SHA256, secret);
+        let signature = hex::encode(hmac::sign(&key, body));
+
+        assert!(verify_receipt_signature(secret, body, &signature).is_ok());
+        assert!(verify_receipt_signature(secret, b"tampered", &signature).is_err());
+        assert!(verify_receipt_signature(b"", body, &signature).is_err());
+        assert!(verify_receipt_signature(secret, body, "not-hex").is_err());
+    }
+
+    #[test]
+    fn retry_delay_grows_with_attempts() {
+        assert_eq!(retry_delay_minutes(1), 5);
+        assert_eq!(retry_delay_minutes(2), 20);
+        assert!(retry_delay_minutes(MAX_DELIVERY_ATTEMPTS) > retry_delay_minutes(2));
+    }
+
+    #[test]
+    fn claim_tokens_are_stored_hashed() {
+        let hash = hash_claim_token("token");
+        assert_eq!(hash.len(), 64);
+        assert_ne!(hash, "token");
+    }
+
+    #[test]
+    fn content_key_survives_rewrap_to_new_data_key() {
+        let old_data_key = [1u8; KEY_LEN];
+        let new_data_key = [2u8; KEY_LEN];
+        let sealed = seal_server_payload(&old_data_key, b"for my heirs").unwrap();
+
+        let content_key = {
+            let unwrap = derive_key(&old_data_key, b"legacy-message-content-key-wrap").unwrap();
+            decrypt_with_key(&unwrap, &sealed.wrapped_key, &sealed.wrap_nonce).unwrap()
+        };
+        let (wrapped, nonce) = wrap_content_key(&new_data_key, &content_key).unwrap();
+
+        let key = content_key_for(&new_data_key, Some(&wrapped), Some(&nonce)).unwrap();
+        let plaintext = decrypt_with_key(&key, &sealed.ciphertext, &sealed.nonce).unwrap();
+        assert_eq!(plaintext, b"for my heirs");
+
+        let stale = content_key_for(&old_data_key, Some(&wrapped), Some(&nonce));
+        assert!(stale.is_err());
+    }
+
+    #[test]
+    fn messages_without_content_key_use_legacy_payload_key() {
+        let data_key = [3u8; KEY_LEN];
+        let legacy_key = derive_key(&data_key, b"legacy-message-payload-key").unwrap();
+        let (ciphertext, nonce) = encrypt_with_key(&legacy_key, b"old message").unwrap();
+
+        let key = content_key_for(&data_key, None, None).unwrap();
+        assert_eq!(
+            decrypt_with_key(&key, &ciphertext, &nonce).unwrap(),
+            b"old message"
+        );
+
+        // Rotation wraps that key rather than re-encrypting the payload
+        let new_data_key = [4u8; KEY_LEN];
+        let legacy_bytes = derive_key_bytes(&data_key, b"legacy-message-payload-key").unwrap();
+        let (wrapped, wrap_nonce) = wrap_content_key(&new_data_key, &legacy_bytes).unwrap();
+        let key = content_key_for(&new_data_key, Some(&wrapped), Some(&wrap_nonce)).unwrap();
+        assert_eq!(
+            decrypt_with_key(&key, &ciphertext, &nonce).unwrap(),
+            b"old message"
+        );
+    }
 }
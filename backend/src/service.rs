@@ -11,6 +11,11 @@ pub fn notify_plan_deactivated(_user_id: uuid::Uuid, _plan_id: uuid::Uuid) {
     // TODO: Implement email or in-app notification for plan deactivation
 }
 use crate::api_error::ApiError;
+use crate::loan_projection::{
+    amortization_schedule, debt_by_day, project, PriceHistoryStats, ProjectionInputs,
+    RepaymentSchedule, RiskProjection, ScheduledPayment, DEFAULT_INSTALLMENT_INTERVAL_DAYS,
+    DEFAULT_MONTE_CARLO_RUNS, MAX_MONTE_CARLO_RUNS,
+};
 use crate::notifications::{
     audit_action, entity_type, notif_type, AuditLogService, NotificationService,
 };
@@ -1910,6 +1915,25 @@ impl CollateralType {
             CollateralType::StellarXlm => rust_decimal::Decimal::new(80, 2), // 0.80
         }
     }
+
+    /// Price feed asset code of this collateral
+    pub fn asset_code(&self) -> &'static str {
+        match self {
+            CollateralType::Usdc => "USDC",
+            CollateralType::Eth => "ETH",
+            CollateralType::Btc => "BTC",
+            CollateralType::StellarXlm => "XLM",
+        }
+    }
+
+    /// Collateral whose prices tend to move together, for concentration limits
+    pub fn correlation_group(&self) -> &'static str {
+        match self {
+            CollateralType::Usdc => "stablecoin",
+            CollateralType::Eth | CollateralType::Btc => "crypto_major",
+            CollateralType::StellarXlm => "stellar_native",
+        }
+    }
 }
 
 impl FromStr for CollateralType {
@@ -1929,7 +1953,7 @@ impl FromStr for CollateralType {
 }
 
 /// Request to simulate a loan
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct LoanSimulationRequest {
     /// Amount the user wants to borrow in USDC
     pub loan_amount: rust_decimal::Decimal,
@@ -1939,6 +1963,14 @@ pub struct LoanSimulationRequest {
     pub collateral_type: String,
     /// Current price of the collateral in USD
     pub collateral_price_usd: rust_decimal::Decimal,
+    /// bullet (default), equal_installment or interest_only
+    pub repayment_schedule: Option<String>,
+    /// Days between installments (default 30)
+    pub installment_interval_days: Option<u32>,
+    /// Monte Carlo price paths to run (default 1000)
+    pub monte_carlo_runs: Option<u32>,
+    /// Fixes the Monte Carlo runs so a projection can be reproduced
+    pub seed: Option<u64>,
 }
 
 /// Response containing loan simulation results
@@ -1967,6 +1999,13 @@ pub struct LoanSimulationResult {
     pub annual_interest_rate: rust_decimal::Decimal,
     /// Liquidation threshold percentage
     pub liquidation_threshold: rust_decimal::Decimal,
+
+    pub repayment_schedule: RepaymentSchedule,
+    pub installment_interval_days: u32,
+    /// Every payment until the loan is repaid
+    pub schedule: Vec<ScheduledPayment>,
+    /// Collateral price scenarios, when there is enough price history
+    pub projection: Option<RiskProjection>,
 }
 
 /// Record of a simulation stored in the database
@@ -1985,6 +2024,11 @@ pub struct LoanSimulationRecord {
     pub liquidation_price: rust_decimal::Decimal,
     pub loan_to_value_ratio: rust_decimal::Decimal,
     pub interest_rate: rust_decimal::Decimal,
+    pub repayment_schedule: String,
+    pub installment_interval_days: Option<i32>,
+    pub schedule: serde_json::Value,
+    pub projection: Option<serde_json::Value>,
+    pub liquidation_probability: Option<rust_decimal::Decimal>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -2028,20 +2072,40 @@ impl LoanSimulationService {
         // collateral_quantity = required_collateral_usd / collateral_price_usd
         let collateral_quantity = required_collateral_usd / req.collateral_price_usd;
 
-        // Calculate interest
-        // interest = loan_amount * annual_rate * (days / 365)
-        let days_fraction = rust_decimal::Decimal::new(req.loan_duration_days as i64, 0)
-            / rust_decimal::Decimal::new(365, 0);
-        let estimated_interest = req.loan_amount * annual_interest_rate * days_fraction;
-
-        // Calculate total repayment
-        let total_repayment = req.loan_amount + estimated_interest;
-
         // Calculate liquidation price
         // Liquidation occurs when: collateral_quantity * price < loan_amount / liquidation_threshold
         // Solving for price: liquidation_price = (loan_amount / liquidation_threshold) / collateral_quantity
         let liquidation_price = (req.loan_amount / liquidation_threshold) / collateral_quantity;
 
+        // Build the payment schedule; interest accrues on the outstanding
+        // principal, so a bullet loan pays loan_amount * annual_rate * (days / 365)
+        let repayment_schedule = match &req.repayment_schedule {
+            Some(kind) => RepaymentSchedule::from_str(kind)?,
+            None => RepaymentSchedule::Bullet,
+        };
+        let installment_interval_days = req
+            .installment_interval_days
+            .unwrap_or(DEFAULT_INSTALLMENT_INTERVAL_DAYS);
+        if installment_interval_days == 0 {
+            return Err(ApiError::BadRequest(
+                "installment_interval_days must be greater than 0".to_string(),
+            ));
+        }
+        let mut schedule = amortization_schedule(
+            repayment_schedule,
+            req.loan_amount,
+            annual_interest_rate,
+            req.loan_duration_days,
+            installment_interval_days,
+        );
+        for payment in &mut schedule {
+            payment.liquidation_price = (!payment.remaining_balance.is_zero())
+                .then(|| (payment.remaining_balance / liquidation_threshold) / collateral_quantity);
+        }
+
+        let estimated_interest: rust_decimal::Decimal = schedule.iter().map(|p| p.interest).sum();
+        let total_repayment = req.loan_amount + estimated_interest;
+
         Ok(LoanSimulationResult {
             loan_amount: req.loan_amount,
             loan_duration_days: req.loan_duration_days,
@@ -2055,9 +2119,50 @@ impl LoanSimulationService {
             loan_to_value_ratio: ltv_ratio,
             annual_interest_rate,
             liquidation_threshold,
+            repayment_schedule,
+            installment_interval_days,
+            schedule,
+            projection: None,
         })
     }
 
+    /// Project the loan's health under collateral price scenarios built from
+    /// the collateral's recorded daily prices. `None` when the history is too
+    /// short to estimate volatility.
+    pub async fn project_risk(
+        db: &PgPool,
+        req: &LoanSimulationRequest,
+        result: &LoanSimulationResult,
+    ) -> Result<Option<RiskProjection>, ApiError> {
+        let runs = req.monte_carlo_runs.unwrap_or(DEFAULT_MONTE_CARLO_RUNS);
+        if runs == 0 || runs > MAX_MONTE_CARLO_RUNS {
+            return Err(ApiError::BadRequest(format!(
+                "monte_carlo_runs must be between 1 and {}",
+                MAX_MONTE_CARLO_RUNS
+            )));
+        }
+        let asset_code = CollateralType::from_str(&result.collateral_type)?.asset_code();
+        let closes = crate::loan_projection::load_daily_closes(db, asset_code).await?;
+        let Some(history) = PriceHistoryStats::from_closes(&closes) else {
+            return Ok(None);
+        };
+
+        let debt = debt_by_day(
+            &result.schedule,
+            result.loan_amount,
+            result.annual_interest_rate,
+            result.loan_duration_days,
+        );
+        let inputs = ProjectionInputs {
+            collateral_quantity: result.collateral_quantity,
+            start_price: result.collateral_price_usd,
+            liquidation_threshold: result.liquidation_threshold,
+            debt_by_day: &debt,
+        };
+        let seed = req.seed.unwrap_or_else(rand::random);
+        Ok(Some(project(&inputs, asset_code, &history, runs, seed)))
+    }
+
     /// Create and store a loan simulation
     pub async fn create_simulation(
         db: &PgPool,
@@ -2065,7 +2170,8 @@ impl LoanSimulationService {
         req: &LoanSimulationRequest,
     ) -> Result<LoanSimulationResult, ApiError> {
         // Calculate simulation
-        let result = Self::calculate_simulation(req)?;
+        let mut result = Self::calculate_simulation(req)?;
+        result.projection = Self::project_risk(db, req, &result).await?;
 
         // Store in database
         sqlx::query(
@@ -2073,9 +2179,11 @@ impl LoanSimulationService {
             INSERT INTO loan_simulations (
                 user_id, loan_amount, loan_duration_days, collateral_type, collateral_price_usd,
                 required_collateral, collateral_quantity, estimated_interest, total_repayment,
-                liquidation_price, loan_to_value_ratio, interest_rate
+                liquidation_price, loan_to_value_ratio, interest_rate,
+                repayment_schedule, installment_interval_days, schedule, projection,
+                liquidation_probability
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
             "#,
         )
         .bind(user_id)
@@ -2090,6 +2198,19 @@ impl LoanSimulationService {
         .bind(result.liquidation_price)
         .bind(result.loan_to_value_ratio)
         .bind(result.annual_interest_rate)
+        .bind(result.repayment_schedule.as_str())
+        .bind(
+            (result.repayment_schedule != RepaymentSchedule::Bullet)
+                .then_some(result.installment_interval_days as i32),
+        )
+        .bind(sqlx::types::Json(&result.schedule))
+        .bind(result.projection.as_ref().map(sqlx::types::Json))
+        .bind(
+            result
+                .projection
+                .as_ref()
+                .map(|p| p.monte_carlo.liquidation_probability),
+        )
         .execute(db)
         .await?;
 
@@ -2114,7 +2235,9 @@ impl LoanSimulationService {
             SELECT id, user_id, loan_amount, loan_duration_days, collateral_type,
                    collateral_price_usd, required_collateral, collateral_quantity,
                    estimated_interest, total_repayment, liquidation_price,
-                   loan_to_value_ratio, interest_rate, created_at
+                   loan_to_value_ratio, interest_rate, repayment_schedule,
+                   installment_interval_days, schedule, projection,
+                   liquidation_probability, created_at
             FROM loan_simulations
             WHERE user_id = $1
             ORDER BY created_at DESC
@@ -2140,7 +2263,9 @@ impl LoanSimulationService {
             SELECT id, user_id, loan_amount, loan_duration_days, collateral_type,
                    collateral_price_usd, required_collateral, collateral_quantity,
                    estimated_interest, total_repayment, liquidation_price,
-                   loan_to_value_ratio, interest_rate, created_at
+                   loan_to_value_ratio, interest_rate, repayment_schedule,
+                   installment_interval_days, schedule, projection,
+                   liquidation_probability, created_at
             FROM loan_simulations
             WHERE id = $1 AND user_id = $2
             "#,
@@ -2407,6 +2532,7 @@ mod tests {
             loan_duration_days: 30,
             collateral_type: "USDC".to_string(),
             collateral_price_usd: dec!(1),
+            ..Default::default()
         };
 
         let result = LoanSimulationService::calculate_simulation(&req).unwrap();
@@ -2441,6 +2567,7 @@ mod tests {
             loan_duration_days: 90,
             collateral_type: "ETH".to_string(),
             collateral_price_usd: eth_price,
+            ..Default::default()
         };
 
         let result = LoanSimulationService::calculate_simulation(&req).unwrap();
@@ -2468,6 +2595,7 @@ mod tests {
             loan_duration_days: 180,
             collateral_type: "BTC".to_string(),
             collateral_price_usd: btc_price,
+            ..Default::default()
         };
 
         let result = LoanSimulationService::calculate_simulation(&req).unwrap();
@@ -2492,6 +2620,7 @@ mod tests {
             loan_duration_days: 60,
             collateral_type: "XLM".to_string(),
             collateral_price_usd: xlm_price,
+            ..Default::default()
         };
 
         let result = LoanSimulationService::calculate_simulation(&req).unwrap();
@@ -2517,6 +2646,7 @@ mod tests {
             loan_duration_days: 30,
             collateral_type: "ETH".to_string(),
             collateral_price_usd: dec!(2000),
+            ..Default::default()
         };
 
         let result = LoanSimulationService::calculate_simulation(&req);
@@ -2530,6 +2660,7 @@ mod tests {
             loan_duration_days: 0,
             collateral_type: "ETH".to_string(),
             collateral_price_usd: dec!(2000),
+            ..Default::default()
         };
 
         let result = LoanSimulationService::calculate_simulation(&req);
@@ -2543,6 +2674,7 @@ mod tests {
             loan_duration_days: 30,
             collateral_type: "ETH".to_string(),
             collateral_price_usd: dec!(0),
+            ..Default::default()
         };
 
         let result = LoanSimulationService::calculate_simulation(&req);
@@ -2556,12 +2688,54 @@ mod tests {
             loan_duration_days: 30,
             collateral_type: "INVALID".to_string(),
             collateral_price_usd: dec!(2000),
+            ..Default::default()
         };
 
         let result = LoanSimulationService::calculate_simulation(&req);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn loan_simulation_builds_installment_schedule() {
+        let req = LoanSimulationRequest {
+            loan_amount: dec!(10000),
+            loan_duration_days: 90,
+            collateral_type: "ETH".to_string(),
+            collateral_price_usd: dec!(2000),
+            repayment_schedule: Some("equal_installment".to_string()),
+            ..Default::default()
+        };
+
+        let result = LoanSimulationService::calculate_simulation(&req).unwrap();
+
+        assert_eq!(result.schedule.len(), 3);
+        assert_eq!(result.installment_interval_days, 30);
+        // Paying principal down costs less than the ~197.26 of a bullet loan
+        assert!(result.estimated_interest < dec!(197));
+        assert_eq!(
+            result.total_repayment,
+            result.schedule.iter().map(|p| p.payment).sum::<Decimal>()
+        );
+        // The liquidation price falls as the balance is repaid
+        assert!(result.schedule[0].liquidation_price.unwrap() < result.liquidation_price);
+        assert_eq!(result.schedule[2].liquidation_price, None);
+    }
+
+    #[test]
+    fn loan_simulation_rejects_unknown_schedule() {
+        let req = LoanSimulationRequest {
+            loan_amount: dec!(1000),
+            loan_duration_days: 30,
+            collateral_type: "ETH".to_string(),
+            collateral_price_usd: dec!(2000),
+            repayment_schedule: Some("balloon".to_string()),
+            ..Default::default()
+        };
+
+        let result = LoanSimulationService::calculate_simulation(&req);
+        assert!(matches!(result, Err(ApiError::BadRequest(_))));
+    }
+
     #[test]
     fn loan_simulation_liquidation_price_logic() {
         // Test that liquidation price is calculated correctly
@@ -2572,6 +2746,7 @@ mod tests {
             loan_duration_days: 30,
             collateral_type: "ETH".to_string(),
             collateral_price_usd: eth_price,
+            ..Default::default()
         };
 
         let result = LoanSimulationService::calculate_simulation(&req).unwrap();
@@ -3151,7 +3326,7 @@ impl EmergencyAccessService {
         }
     }
 
-    async fn log_action(
+    pub(crate) async fn log_action(
         executor: impl sqlx::PgExecutor<'_>,
         grant_id: Uuid,
         user_id: Uuid,
@@ -3808,6 +3983,45 @@ impl EmergencySessionService {
         Ok(session)
     }
 
+    /// Start a session as the emergency contact named on the grant.
+    ///
+    /// The contact is matched by the signed-in account's email; the session
+    /// is what `EmergencyPrincipal` later resolves.
+    pub async fn start_contact_session(
+        pool: &PgPool,
+        contact_email: &str,
+        req: &StartSessionRequest,
+    ) -> Result<EmergencyAccessSession, ApiError> {
+        let grant = sqlx::query_as::<_, EmergencyAccessGrant>(
+            r#"
+            SELECT g.id, g.user_id, g.emergency_contact_id, g.permissions, g.expires_at,
+                   g.is_active, g.revoked_at, g.created_at, g.updated_at
+            FROM emergency_access_grants g
+            JOIN emergency_contacts c ON c.id = g.emergency_contact_id
+            WHERE g.id = $1 AND LOWER(c.email) = LOWER($2)
+            "#,
+        )
+        .bind(req.grant_id)
+        .bind(contact_email)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| {
+            ApiError::NotFound(format!("Emergency access grant {} not found", req.grant_id))
+        })?;
+
+        let session = Self::start_session(pool, grant.user_id, req).await?;
+        EmergencyAccessService::log_action(
+            pool,
+            grant.id,
+            grant.user_id,
+            grant.emergency_contact_id,
+            "session_started",
+            serde_json::json!({ "session_id": session.id }),
+        )
+        .await?;
+        Ok(session)
+    }
+
     /// Update the heartbeat timestamp for an active session.
     pub async fn heartbeat(
         pool: &PgPool,
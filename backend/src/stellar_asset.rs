@@ -0,0 +1,276 @@
+//! # Stellar Asset Identity
+//!
+//! Stellar assets are identified by code *and* issuer: any account can issue
+//! a `USDC`, so a bare code only names an asset on centralized exchanges.
+//! [`AssetId`] parses the `CODE:ISSUER` form used by price feeds (`XLM` or
+//! `native` for lumens) and converts it to the shapes Horizon and Soroban
+//! expect.
+
+use crate::api_error::ApiError;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use soroban_sdk::xdr::{
+    AccountId, AlphaNum12, AlphaNum4, Asset, AssetCode12, AssetCode4, ContractIdPreimage, Hash,
+    HashIdPreimage, HashIdPreimageContractId, Limits, PublicKey, ScAddress, ScSymbol, ScVal, ScVec,
+    Uint256, WriteXdr,
+};
+use std::fmt;
+use std::str::FromStr;
+
+/// An asset code with its issuing account, or native lumens
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct AssetId {
+    pub code: String,
+    /// `G…` issuer; `None` for native XLM and for bare exchange tickers
+    pub issuer: Option<String>,
+}
+
+impl AssetId {
+    pub fn native() -> Self {
+        Self {
+            code: "XLM".to_string(),
+            issuer: None,
+        }
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.issuer.is_none() && self.code == "XLM"
+    }
+
+    /// Whether this names a concrete asset on the Stellar network
+    pub fn is_stellar(&self) -> bool {
+        self.is_native() || self.issuer.is_some()
+    }
+
+    fn asset_type(&self) -> &'static str {
+        match (&self.issuer, self.code.len()) {
+            (None, _) => "native",
+            (Some(_), 1..=4) => "credit_alphanum4",
+            (Some(_), _) => "credit_alphanum12",
+        }
+    }
+
+    /// Horizon query parameters, e.g. `selling_asset_type`, `selling_asset_code`
+    /// and `selling_asset_issuer` for `prefix = "selling"`
+    pub fn horizon_params(&self, prefix: &str) -> Vec<(String, String)> {
+        let mut params = vec![(
+            format!("{prefix}_asset_type"),
+            self.asset_type().to_string(),
+        )];
+        if let Some(issuer) = &self.issuer {
+            params.push((format!("{prefix}_asset_code"), self.code.clone()));
+            params.push((format!("{prefix}_asset_issuer"), issuer.clone()));
+        }
+        params
+    }
+
+    /// The XDR asset, or an error for bare exchange tickers
+    pub fn to_xdr(&self) -> Result<Asset, ApiError> {
+        if self.is_native() {
+            return Ok(Asset::Native);
+        }
+        let issuer = self
+            .issuer
+            .as_deref()
+            .ok_or_else(|| ApiError::BadRequest(format!("{} is not a Stellar asset", self.code)))?;
+        let key = stellar_strkey::ed25519::PublicKey::from_string(issuer)
+            .map_err(|e| ApiError::BadRequest(format!("Invalid issuer {}: {}", issuer, e)))?
+            .0;
+        let issuer = AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(key)));
+        let code = self.code.as_bytes();
+        Ok(if code.len() <= 4 {
+            let mut padded = [0u8; 4];
+            padded[..code.len()].copy_from_slice(code);
+            Asset::CreditAlphanum4(AlphaNum4 {
+                asset_code: AssetCode4(padded),
+                issuer,
+            })
+        } else {
+            let mut padded = [0u8; 12];
+            padded[..code.len()].copy_from_slice(code);
+            Asset::CreditAlphanum12(AlphaNum12 {
+                asset_code: AssetCode12(padded),
+                issuer,
+            })
+        })
+    }
+
+    /// Address of the asset's Stellar Asset Contract on the given network
+    pub fn contract_id(&self, network_passphrase: &str) -> Result<[u8; 32], ApiError> {
+        let preimage = HashIdPreimage::ContractId(HashIdPreimageContractId {
+            network_id: Hash(Sha256::digest(network_passphrase.as_bytes()).into()),
+            contract_id_preimage: ContractIdPreimage::Asset(self.to_xdr()?),
+        });
+        let bytes = preimage
+            .to_xdr(Limits::none())
+            .map_err(|e| ApiError::Internal(anyhow::anyhow!("XDR error: {}", e)))?;
+        Ok(Sha256::digest(bytes).into())
+    }
+
+    /// The SEP-40 oracle `Asset` value: `Stellar(<asset contract>)` for
+    /// Stellar assets and `Other(<symbol>)` for bare tickers, as Reflector
+    /// and the `price-oracle` contract expect
+    pub fn oracle_asset(&self, network_passphrase: &str) -> Result<ScVal, ApiError> {
+        let variant = if self.is_stellar() {
+            vec![
+                sc_symbol("Stellar")?,
+                ScVal::Address(ScAddress::Contract(Hash(
+                    self.contract_id(network_passphrase)?,
+                ))),
+            ]
+        } else {
+            vec![sc_symbol("Other")?, sc_symbol(&self.code)?]
+        };
+        Ok(ScVal::Vec(Some(ScVec(variant.try_into().map_err(
+            |e| ApiError::Internal(anyhow::anyhow!("XDR error: {}", e)),
+        )?))))
+    }
+}
+
+fn sc_symbol(name: &str) -> Result<ScVal, ApiError> {
+    Ok(ScVal::Symbol(ScSymbol(name.try_into().map_err(|e| {
+        ApiError::Internal(anyhow::anyhow!("XDR error: {}", e))
+    })?)))
+}
+
+impl FromStr for AssetId {
+    type Err = ApiError;
+
+    /// Accepts `CODE:ISSUER`, a bare `CODE`, or `XLM`/`native`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid =
+            |reason: &str| ApiError::BadRequest(format!("Invalid asset '{}': {}", s, reason));
+        let (code, issuer) = match s.trim().split_once(':') {
+            Some((code, issuer)) => (code, Some(issuer.trim().to_uppercase())),
+            None => (s.trim(), None),
+        };
+        let code = code.trim();
+        if code.eq_ignore_ascii_case("native") || (code == "XLM" && issuer.is_none()) {
+            return Ok(Self::native());
+        }
+        if code.is_empty() || code.len() > 12 || !code.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return Err(invalid("code must be 1-12 letters or digits"));
+        }
+        if let Some(issuer) = &issuer {
+            stellar_strkey::ed25519::PublicKey::from_string(issuer)
+                .map_err(|_| invalid("issuer must be a G... account"))?;
+        }
+        Ok(Self {
+            code: code.to_string(),
+            issuer,
+        })
+    }
+}
+
+impl fmt::Display for AssetId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.issuer {
+            Some(issuer) => write!(f, "{}:{}", self.code, issuer),
+            None => f.write_str(&self.code),
+        }
+    }
+}
+
+impl TryFrom<String> for AssetId {
+    type Error = ApiError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<AssetId> for String {
+    fn from(value: AssetId) -> Self {
+        value.to_string()
+    }
+}
+
+// ─── Unit Tests ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CIRCLE: &str = "GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN";
+    const PUBNET: &str = "Public Global Stellar Network ; September 2015";
+
+    #[test]
+    fn parses_code_issuer_pairs() {
+        let usdc: AssetId = format!("USDC:{}", CIRCLE).parse().unwrap();
+        assert_eq!(usdc.code, "USDC");
+        assert_eq!(usdc.issuer.as_deref(), Some(CIRCLE));
+        assert_eq!(usdc.to_string(), format!("USDC:{}", CIRCLE));
+        assert!(usdc.is_stellar());
+
+        assert!("native".parse::<AssetId>().unwrap().is_native());
+        assert!("XLM".parse::<AssetId>().unwrap().is_native());
+
+        let eth: AssetId = "ETH".parse().unwrap();
+        assert!(!eth.is_stellar());
+        assert!(eth.to_xdr().is_err());
+
+        assert!("USDC:not-an-account".parse::<AssetId>().is_err());
+        assert!("TOOLONGASSETCODE".parse::<AssetId>().is_err());
+    }
+
+    #[test]
+    fn builds_horizon_params() {
+        let usdc: AssetId = format!("USDC:{}", CIRCLE).parse().unwrap();
+        assert_eq!(
+            usdc.horizon_params("base"),
+            vec![
+                (
+                    "base_asset_type".to_string(),
+                    "credit_alphanum4".to_string()
+                ),
+                ("base_asset_code".to_string(), "USDC".to_string()),
+                ("base_asset_issuer".to_string(), CIRCLE.to_string()),
+            ]
+        );
+        assert_eq!(
+            AssetId::native().horizon_params("counter"),
+            vec![("counter_asset_type".to_string(), "native".to_string())]
+        );
+    }
+
+    #[test]
+    fn derives_stellar_asset_contract_ids() {
+        let usdc: AssetId = format!("USDC:{}", CIRCLE).parse().unwrap();
+        let contract = stellar_strkey::Contract(usdc.contract_id(PUBNET).unwrap());
+        assert_eq!(
+            contract.to_string().as_str(),
+            "CCW67TSZV3SSS2HXMBQ5JFGCKJNXKZM7UQUWUZPUTHXSTZLEO7SJMI75"
+        );
+
+        let xlm = stellar_strkey::Contract(AssetId::native().contract_id(PUBNET).unwrap());
+        assert_eq!(
+            xlm.to_string().as_str(),
+            "CAS3J7GYLGXMF6TDJBBYYSE3HQ6BBSMLNUQ34T6TZMYMW2EVH34XOWMA"
+        );
+    }
+
+    #[test]
+    fn builds_oracle_asset_arguments() {
+        let ScVal::Vec(Some(items)) = "BTC"
+            .parse::<AssetId>()
+            .unwrap()
+            .oracle_asset(PUBNET)
+            .unwrap()
+        else {
+            panic!("expected vec");
+        };
+        assert_eq!(items[0], sc_symbol("Other").unwrap());
+        assert_eq!(items[1], sc_symbol("BTC").unwrap());
+
+        let usdc: AssetId = format!("USDC:{}", CIRCLE).parse().unwrap();
+        let ScVal::Vec(Some(items)) = usdc.oracle_asset(PUBNET).unwrap() else {
+            panic!("expected vec");
+        };
+        assert_eq!(items[0], sc_symbol("Stellar").unwrap());
+        assert_eq!(
+            items[1],
+            ScVal::Address(ScAddress::Contract(Hash(usdc.contract_id(PUBNET).unwrap())))
+        );
+    }
+}
@@ -0,0 +1,500 @@
+//! # Stellar-Native Price Sources
+//!
+//! [`ExternalPriceProvider`]s for assets issued on Stellar, which the
+//! centralized exchanges do not list: the SDEX order book and trade
+//! aggregations served by Horizon, and a SEP-40 price oracle contract read
+//! through Soroban RPC simulation. Assets are named `CODE:ISSUER` (see
+//! [`AssetId`]); SDEX prices are quoted in a configured stablecoin and taken
+//! as USD.
+
+use crate::api_error::ApiError;
+use crate::external_price_fetcher::{ExternalPrice, ExternalPriceProvider};
+use crate::stellar_asset::AssetId;
+use crate::tx_submitter::{ContractCall, SorobanInvoker, TESTNET_PASSPHRASE};
+use async_trait::async_trait;
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde_json::Value as JsonValue;
+use soroban_sdk::xdr::{ScMap, ScVal};
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::{error, info};
+
+/// Circle's USDC on the public network, the default SDEX quote asset
+pub const PUBNET_USDC: &str = "USDC:GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN";
+
+/// Trade aggregation bucket size (5 minutes)
+const TRADE_RESOLUTION_MS: i64 = 300_000;
+
+/// Widest bid/ask spread, as a fraction of the mid, accepted by default
+const DEFAULT_MAX_SPREAD: Decimal = Decimal::from_parts(5, 0, 0, false, 2);
+
+/// Providers configured from the environment.
+///
+/// The SDEX providers need `HORIZON_URL` and price against `SDEX_QUOTE_ASSET`
+/// (default Circle USDC). The oracle needs `SOROBAN_RPC_URL` and
+/// `PRICE_ORACLE_CONTRACT_ID`; `PRICE_ORACLE_DECIMALS` skips reading the
+/// contract's `decimals()`. Misconfigured providers are logged and left out.
+pub fn stellar_providers_from_env() -> Vec<Box<dyn ExternalPriceProvider>> {
+    let mut providers: Vec<Box<dyn ExternalPriceProvider>> = Vec::new();
+
+    if let Ok(horizon_url) = std::env::var("HORIZON_URL") {
+        let quote = std::env::var("SDEX_QUOTE_ASSET").unwrap_or_else(|_| PUBNET_USDC.to_string());
+        match quote.parse::<AssetId>() {
+            Ok(quote) => {
+                providers.push(Box::new(SdexOrderBookProvider::new(
+                    &horizon_url,
+                    quote.clone(),
+                )));
+                providers.push(Box::new(SdexTradeProvider::new(&horizon_url, quote)));
+            }
+            Err(e) => error!("SDEX price sources disabled: {}", e),
+        }
+    }
+
+    if let (Ok(rpc_url), Ok(contract_id)) = (
+        std::env::var("SOROBAN_RPC_URL"),
+        std::env::var("PRICE_ORACLE_CONTRACT_ID"),
+    ) {
+        let passphrase = std::env::var("STELLAR_NETWORK_PASSPHRASE")
+            .unwrap_or_else(|_| TESTNET_PASSPHRASE.to_string());
+        let decimals = std::env::var("PRICE_ORACLE_DECIMALS")
+            .ok()
+            .and_then(|v| v.parse().ok());
+        match SorobanOracleProvider::new(&rpc_url, &contract_id, &passphrase, decimals) {
+            Ok(oracle) => providers.push(Box::new(oracle)),
+            Err(e) => error!("Soroban price oracle disabled: {}", e),
+        }
+    }
+
+    if !providers.is_empty() {
+        info!("Enabled {} Stellar price sources", providers.len());
+    }
+    providers
+}
+
+/// Parse an asset that must exist on Stellar and differ from the quote asset
+fn stellar_asset(asset_code: &str, quote: &AssetId) -> Result<AssetId, ApiError> {
+    let asset: AssetId = asset_code.parse()?;
+    if !asset.is_stellar() {
+        return Err(ApiError::BadRequest(format!(
+            "{} is not a Stellar asset; use CODE:ISSUER",
+            asset_code
+        )));
+    }
+    if quote == &asset {
+        return Err(ApiError::BadRequest(format!(
+            "{} is the SDEX quote asset",
+            asset_code
+        )));
+    }
+    Ok(asset)
+}
+
+fn decimal_field(value: &JsonValue, name: &str) -> Result<Decimal, ApiError> {
+    value[name]
+        .as_str()
+        .and_then(|v| Decimal::from_str(v).ok())
+        .ok_or_else(|| ApiError::Internal(anyhow::anyhow!("Horizon response missing '{}'", name)))
+}
+
+async fn horizon_get(
+    client: &Client,
+    url: &str,
+    params: &[(String, String)],
+) -> Result<JsonValue, ApiError> {
+    let response = client
+        .get(url)
+        .query(params)
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+        .map_err(|e| {
+            error!("Horizon request failed: {}", e);
+            ApiError::Internal(anyhow::anyhow!("Horizon fetch failed"))
+        })?;
+
+    if !response.status().is_success() {
+        return Err(ApiError::Internal(anyhow::anyhow!(
+            "Horizon returned status: {}",
+            response.status()
+        )));
+    }
+
+    response.json().await.map_err(|e| {
+        error!("Failed to parse Horizon response: {}", e);
+        ApiError::Internal(anyhow::anyhow!("Invalid Horizon response"))
+    })
+}
+
+// ─── SDEX Order Book ──────────────────────────────────────────────────────────
+
+/// Mid price of the best bid and ask on the SDEX order book
+pub struct SdexOrderBookProvider {
+    client: Client,
+    base_url: String,
+    quote: AssetId,
+    max_spread: Decimal,
+}
+
+impl SdexOrderBookProvider {
+    pub fn new(horizon_url: &str, quote: AssetId) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: horizon_url.trim_end_matches('/').to_string(),
+            quote,
+            max_spread: DEFAULT_MAX_SPREAD,
+        }
+    }
+
+    /// Mid price from a Horizon `/order_book` response, rejecting books with
+    /// an empty side or a spread wider than `max_spread`
+    pub fn mid_price(book: &JsonValue, max_spread: Decimal) -> Result<Decimal, ApiError> {
+        let best = |side: &str| {
+            book[side]
+                .as_array()
+                .and_then(|offers| offers.first())
+                .ok_or_else(|| {
+                    ApiError::Internal(anyhow::anyhow!("SDEX order book has no {}", side))
+                })
+                .and_then(|offer| decimal_field(offer, "price"))
+        };
+        let (bid, ask) = (best("bids")?, best("asks")?);
+        let mid = (bid + ask) / Decimal::TWO;
+        if mid <= Decimal::ZERO {
+            return Err(ApiError::Internal(anyhow::anyhow!(
+                "SDEX order book is empty"
+            )));
+        }
+        let spread = (ask - bid) / mid;
+        if spread > max_spread {
+            return Err(ApiError::Internal(anyhow::anyhow!(
+                "SDEX spread {} exceeds {}",
+                spread.round_dp(4),
+                max_spread
+            )));
+        }
+        Ok(mid)
+    }
+}
+
+#[async_trait]
+impl ExternalPriceProvider for SdexOrderBookProvider {
+    async fn fetch_price(&self, asset_code: &str) -> Result<ExternalPrice, ApiError> {
+        let asset = stellar_asset(asset_code, &self.quote)?;
+        let mut params = asset.horizon_params("selling");
+        params.extend(self.quote.horizon_params("buying"));
+        params.push(("limit".to_string(), "1".to_string()));
+
+        let book = horizon_get(
+            &self.client,
+            &format!("{}/order_book", self.base_url),
+            &params,
+        )
+        .await?;
+
+        Ok(ExternalPrice {
+            asset_code: asset.to_string(),
+            price: Self::mid_price(&book, self.max_spread)?,
+            source: "sdex_order_book".to_string(),
+            timestamp_seconds: chrono::Utc::now().timestamp(),
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "SDEX order book"
+    }
+}
+
+// ─── SDEX Trades ──────────────────────────────────────────────────────────────
+
+/// Volume-weighted price of the most recent 5-minute SDEX trade bucket
+pub struct SdexTradeProvider {
+    client: Client,
+    base_url: String,
+    quote: AssetId,
+}
+
+impl SdexTradeProvider {
+    pub fn new(horizon_url: &str, quote: AssetId) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: horizon_url.trim_end_matches('/').to_string(),
+            quote,
+        }
+    }
+
+    /// `(vwap, bucket end in seconds)` of the newest record in a Horizon
+    /// `/trade_aggregations` response
+    pub fn latest_vwap(aggregations: &JsonValue) -> Result<(Decimal, i64), ApiError> {
+        let record = aggregations["_embedded"]["records"]
+            .as_array()
+            .and_then(|records| records.first())
+            .ok_or_else(|| ApiError::Internal(anyhow::anyhow!("No recent SDEX trades")))?;
+
+        let base_volume = decimal_field(record, "base_volume")?;
+        let price = if base_volume > Decimal::ZERO {
+            decimal_field(record, "counter_volume")? / base_volume
+        } else {
+            decimal_field(record, "avg")?
+        };
+
+        // Horizon has served the bucket start both as a number and a string
+        let start_ms = match &record["timestamp"] {
+            JsonValue::String(s) => s.parse::<i64>().ok(),
+            other => other.as_i64(),
+        }
+        .ok_or_else(|| ApiError::Internal(anyhow::anyhow!("Trade bucket without timestamp")))?;
+        let end = ((start_ms + TRADE_RESOLUTION_MS) / 1000).min(chrono::Utc::now().timestamp());
+
+        Ok((price.round_dp(8), end))
+    }
+}
+
+#[async_trait]
+impl ExternalPriceProvider for SdexTradeProvider {
+    async fn fetch_price(&self, asset_code: &str) -> Result<ExternalPrice, ApiError> {
+        let asset = stellar_asset(asset_code, &self.quote)?;
+        let mut params = asset.horizon_params("base");
+        params.extend(self.quote.horizon_params("counter"));
+        params.extend([
+            ("resolution".to_string(), TRADE_RESOLUTION_MS.to_string()),
+            ("order".to_string(), "desc".to_string()),
+            ("limit".to_string(), "1".to_string()),
+        ]);
+
+        let aggregations = horizon_get(
+            &self.client,
+            &format!("{}/trade_aggregations", self.base_url),
+            &params,
+        )
+        .await?;
+        let (price, timestamp_seconds) = Self::latest_vwap(&aggregations)?;
+
+        Ok(ExternalPrice {
+            asset_code: asset.to_string(),
+            price,
+            source: "sdex_trades".to_string(),
+            timestamp_seconds,
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "SDEX trades"
+    }
+}
+
+// ─── SEP-40 Oracle ────────────────────────────────────────────────────────────
+
+/// Reads `lastprice(asset)` from a SEP-40 oracle contract.
+///
+/// Assets are passed as built by [`AssetId::oracle_asset`].
+pub struct SorobanOracleProvider {
+    invoker: SorobanInvoker,
+    network_passphrase: String,
+    /// Any account works; simulation neither signs nor charges it
+    source_account: String,
+    decimals: tokio::sync::OnceCell<u32>,
+}
+
+fn oracle_error(e: impl std::fmt::Display) -> ApiError {
+    ApiError::Internal(anyhow::anyhow!("Price oracle read failed: {}", e))
+}
+
+impl SorobanOracleProvider {
+    pub fn new(
+        rpc_url: &str,
+        contract_id: &str,
+        network_passphrase: &str,
+        decimals: Option<u32>,
+    ) -> Result<Self, ApiError> {
+        Ok(Self {
+            invoker: SorobanInvoker::new(rpc_url, contract_id, network_passphrase)?,
+            network_passphrase: network_passphrase.to_string(),
+            source_account: stellar_strkey::ed25519::PublicKey([0; 32])
+                .to_string()
+                .as_str()
+                .to_owned(),
+            decimals: tokio::sync::OnceCell::new_with(decimals),
+        })
+    }
+
+    /// `(price, timestamp)` from a `PriceData` value scaled by `decimals`
+    pub fn parse_price_data(val: &ScVal, decimals: u32) -> Result<(Decimal, i64), ApiError> {
+        let ScVal::Map(Some(ScMap(entries))) = val else {
+            return Err(ApiError::Internal(anyhow::anyhow!(
+                "Oracle has no price for this asset"
+            )));
+        };
+        let field = |name: &str| {
+            entries
+                .iter()
+                .find(|e| matches!(&e.key, ScVal::Symbol(s) if s.0.as_slice() == name.as_bytes()))
+                .map(|e| &e.val)
+                .ok_or_else(|| oracle_error(format!("PriceData missing '{name}'")))
+        };
+
+        let raw = match field("price")? {
+            ScVal::I128(parts) => ((parts.hi as i128) << 64) | parts.lo as i128,
+            other => return Err(oracle_error(format!("expected i128 price, got {other:?}"))),
+        };
+        let timestamp = match field("timestamp")? {
+            ScVal::U64(ts) => *ts as i64,
+            other => {
+                return Err(oracle_error(format!(
+                    "expected u64 timestamp, got {other:?}"
+                )))
+            }
+        };
+        let price = Decimal::try_from_i128_with_scale(raw, decimals).map_err(oracle_error)?;
+        Ok((price.normalize(), timestamp))
+    }
+
+    async fn call(&self, function: &str, args: Vec<ScVal>) -> Result<ScVal, ApiError> {
+        self.invoker
+            .read(&ContractCall {
+                source_account: self.source_account.clone(),
+                function: function.to_string(),
+                args,
+            })
+            .await
+            .map_err(oracle_error)
+    }
+
+    async fn decimals(&self) -> Result<u32, ApiError> {
+        self.decimals
+            .get_or_try_init(|| async {
+                match self.call("decimals", Vec::new()).await? {
+                    ScVal::U32(decimals) => Ok(decimals),
+                    other => Err(oracle_error(format!(
+                        "expected u32 decimals, got {other:?}"
+                    ))),
+                }
+            })
+            .await
+            .copied()
+    }
+}
+
+#[async_trait]
+impl ExternalPriceProvider for SorobanOracleProvider {
+    async fn fetch_price(&self, asset_code: &str) -> Result<ExternalPrice, ApiError> {
+        let asset: AssetId = asset_code.parse()?;
+        let decimals = self.decimals().await?;
+        let val = self
+            .call(
+                "lastprice",
+                vec![asset.oracle_asset(&self.network_passphrase)?],
+            )
+            .await?;
+        let (price, timestamp_seconds) = Self::parse_price_data(&val, decimals)?;
+
+        Ok(ExternalPrice {
+            asset_code: asset.to_string(),
+            price,
+            source: "soroban_oracle".to_string(),
+            timestamp_seconds,
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "Soroban oracle"
+    }
+}
+
+// ─── Unit Tests ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+    use rust_decimal_macros::dec;
+
+    const ORDER_BOOK: &str = include_str!("../tests/fixtures/horizon_order_book.json");
+    const TRADE_AGGREGATIONS: &str =
+        include_str!("../tests/fixtures/horizon_trade_aggregations.json");
+    const ORACLE_LASTPRICE: &str = include_str!("../tests/fixtures/soroban_oracle_lastprice.json");
+    const CIRCLE: &str = "GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN";
+
+    fn usdc() -> AssetId {
+        PUBNET_USDC.parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn prices_from_order_book_mid() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/order_book")
+                .query_param("selling_asset_type", "native")
+                .query_param("buying_asset_code", "USDC")
+                .query_param("buying_asset_issuer", CIRCLE);
+            then.status(200).body(ORDER_BOOK);
+        });
+
+        let provider = SdexOrderBookProvider::new(&server.base_url(), usdc());
+        let price = provider.fetch_price("XLM").await.unwrap();
+        mock.assert();
+        assert_eq!(price.price, dec!(0.1184));
+        assert_eq!(price.asset_code, "XLM");
+
+        let book: JsonValue = serde_json::from_str(ORDER_BOOK).unwrap();
+        assert!(SdexOrderBookProvider::mid_price(&book, dec!(0.001)).is_err());
+    }
+
+    #[tokio::test]
+    async fn prices_from_latest_trade_bucket() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET)
+                .path("/trade_aggregations")
+                .query_param("base_asset_type", "native")
+                .query_param("counter_asset_type", "credit_alphanum4")
+                .query_param("resolution", "300000")
+                .query_param("order", "desc");
+            then.status(200).body(TRADE_AGGREGATIONS);
+        });
+
+        let provider = SdexTradeProvider::new(&server.base_url(), usdc());
+        let price = provider.fetch_price("native").await.unwrap();
+        assert_eq!(price.price, dec!(0.11845));
+        assert_eq!(price.timestamp_seconds, 1_760_832_300);
+    }
+
+    #[tokio::test]
+    async fn sdex_rejects_non_stellar_and_quote_assets() {
+        let provider = SdexOrderBookProvider::new("http://127.0.0.1:1", usdc());
+        assert!(provider.fetch_price("ETH").await.is_err());
+        assert!(provider.fetch_price(PUBNET_USDC).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn reads_oracle_lastprice() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST).body_contains("simulateTransaction");
+            then.status(200).body(ORACLE_LASTPRICE);
+        });
+
+        let oracle = SorobanOracleProvider::new(
+            &server.base_url(),
+            &stellar_strkey::Contract([9; 32]).to_string(),
+            "Public Global Stellar Network ; September 2015",
+            Some(14),
+        )
+        .unwrap();
+        let price = oracle
+            .fetch_price(&format!("AQUA:{}", CIRCLE))
+            .await
+            .unwrap();
+        mock.assert();
+        assert_eq!(price.price, dec!(0.11845));
+        assert_eq!(price.timestamp_seconds, 1_760_832_000);
+        assert_eq!(price.source, "soroban_oracle");
+    }
+
+    #[test]
+    fn rejects_missing_price_data() {
+        assert!(SorobanOracleProvider::parse_price_data(&ScVal::Void, 14).is_err());
+    }
+}
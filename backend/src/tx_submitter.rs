@@ -0,0 +1,717 @@
+//! # Soroban Transaction Submission
+//!
+//! Builds, simulates, signs and sends contract invocations through Soroban
+//! RPC, and reports their confirmation status. Keys live behind
+//! [`TransactionSigner`] so a custodial key file, an HSM or a remote wallet
+//! service can sign without the submitter knowing which. Calls a user must
+//! authorize themselves are [prepared](ContractInvoker::prepare) unsigned for
+//! their wallet and [sent](ContractInvoker::send_signed) once it has signed.
+//!
+//! Every transaction carries a `max_time` bound, so once that time has passed
+//! and the network does not know the hash, it can never land and is safe to
+//! rebuild.
+
+use crate::api_error::ApiError;
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use serde_json::{json, Value as JsonValue};
+use sha2::{Digest, Sha256};
+use soroban_sdk::xdr::{
+    AccountId, DecoratedSignature, Hash, HostFunction, Int128Parts, InvokeContractArgs,
+    InvokeHostFunctionOp, LedgerEntryData, LedgerKey, LedgerKeyAccount, Limits, Memo, MuxedAccount,
+    Operation, OperationBody, Preconditions, PublicKey, ReadXdr, ScAddress, ScSymbol, ScVal,
+    SequenceNumber, Signature, SignatureHint, SorobanAuthorizationEntry, SorobanTransactionData,
+    TimeBounds, TimePoint, Transaction, TransactionEnvelope, TransactionExt, TransactionResult,
+    TransactionResultResult, TransactionSignaturePayload,
+    TransactionSignaturePayloadTaggedTransaction, TransactionV1Envelope, Uint256, WriteXdr,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Network used when `STELLAR_NETWORK_PASSPHRASE` is not set
+pub const TESTNET_PASSPHRASE: &str = "Test SDF Network ; September 2015";
+
+/// How long a transaction signed by the backend stays valid
+pub const DEFAULT_TX_VALIDITY_SECS: i64 = 120;
+
+// ─── Types ────────────────────────────────────────────────────────────────────
+
+/// One contract function call, authorized by its source account
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractCall {
+    /// `G…` account that pays for and authorizes the call
+    pub source_account: String,
+    pub function: String,
+    pub args: Vec<ScVal>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxStatus {
+    /// Known to the node but not yet in a ledger
+    Pending,
+    /// Unknown to the RPC node: still propagating, dropped, or expired
+    NotFound,
+    Confirmed {
+        ledger: u32,
+    },
+    Failed(String),
+}
+
+/// Why a submission did not reach the network
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubmitError {
+    /// Transient: resubmitting later, possibly with a higher fee, may work
+    Retryable(String),
+    /// The call can never succeed as built (rejected in simulation, no key)
+    Fatal(String),
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitError::Retryable(msg) => write!(f, "{msg}"),
+            SubmitError::Fatal(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+/// Produces Ed25519 signatures for Stellar accounts
+#[async_trait]
+pub trait TransactionSigner: Send + Sync {
+    /// Whether this signer holds a key for `account`
+    fn can_sign(&self, account: &str) -> bool;
+
+    /// Sign a 32-byte transaction hash as `account`
+    async fn sign(&self, account: &str, payload: &[u8; 32]) -> Result<Vec<u8>, ApiError>;
+}
+
+/// A simulated transaction waiting for its source account's signature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreparedTransaction {
+    /// Base64 envelope XDR without signatures
+    pub envelope_xdr: String,
+    /// Network-bound hash the source account signs
+    pub signature_payload: [u8; 32],
+    /// Transaction hash, which is also the signature payload in hex
+    pub tx_hash: String,
+    /// Fee including the simulated resource fee
+    pub fee: u32,
+}
+
+/// Submits contract calls and tracks them until they land in a ledger
+#[async_trait]
+pub trait ContractInvoker: Send + Sync {
+    /// Build, simulate, sign and send `call`, paying `inclusion_fee` stroops
+    /// on top of the simulated resource fee and expiring after
+    /// [`DEFAULT_TX_VALIDITY_SECS`]. Returns the transaction hash.
+    async fn submit(
+        &self,
+        call: &ContractCall,
+        inclusion_fee: u32,
+        signer: &dyn TransactionSigner,
+    ) -> Result<String, SubmitError> {
+        if !signer.can_sign(&call.source_account) {
+            return Err(fatal(format!(
+                "No signing key for account {}",
+                call.source_account
+            )));
+        }
+        let max_time = (chrono::Utc::now().timestamp() + DEFAULT_TX_VALIDITY_SECS) as u64;
+        let prepared = self.prepare(call, inclusion_fee, max_time).await?;
+        let signature = signer
+            .sign(&call.source_account, &prepared.signature_payload)
+            .await
+            .map_err(fatal)?;
+        let envelope = attach_signature(&prepared, &call.source_account, signature)?;
+        self.send_signed(&envelope).await
+    }
+
+    /// Build and simulate `call` without signing it. The transaction cannot
+    /// be included in a ledger closing after `max_time` (unix seconds).
+    async fn prepare(
+        &self,
+        call: &ContractCall,
+        inclusion_fee: u32,
+        max_time: u64,
+    ) -> Result<PreparedTransaction, SubmitError>;
+
+    /// Send a signed base64 envelope. Returns the transaction hash.
+    async fn send_signed(&self, envelope_xdr: &str) -> Result<String, SubmitError>;
+
+    async fn status(&self, tx_hash: &str) -> Result<TxStatus, SubmitError>;
+}
+
+/// Signed envelope for `prepared` carrying `signature` by `account`
+pub fn attach_signature(
+    prepared: &PreparedTransaction,
+    account: &str,
+    signature: Vec<u8>,
+) -> Result<String, SubmitError> {
+    let TransactionEnvelope::Tx(mut envelope) = decode_xdr(&prepared.envelope_xdr)? else {
+        return Err(fatal("Prepared envelope is not a v1 transaction"));
+    };
+    let public = account_key(account).map_err(fatal)?;
+    let decorated = DecoratedSignature {
+        hint: SignatureHint([public[28], public[29], public[30], public[31]]),
+        signature: Signature(signature.try_into().map_err(fatal)?),
+    };
+    envelope.signatures = vec![decorated].try_into().map_err(fatal)?;
+    encode_xdr(&TransactionEnvelope::Tx(envelope))
+}
+
+/// Check that `signed_xdr` is the `prepared` transaction, unchanged, carrying
+/// a valid signature by `account`
+pub fn verify_signed_envelope(
+    prepared_xdr: &str,
+    signature_payload: &[u8; 32],
+    account: &str,
+    signed_xdr: &str,
+) -> Result<(), ApiError> {
+    let invalid = |msg: &str| ApiError::BadRequest(format!("Invalid signed transaction: {msg}"));
+    let decode = |xdr: &str| match decode_xdr::<TransactionEnvelope>(xdr) {
+        Ok(TransactionEnvelope::Tx(envelope)) => Ok(envelope),
+        _ => Err(invalid("not a v1 transaction envelope")),
+    };
+    let prepared = decode(prepared_xdr)?;
+    let signed = decode(signed_xdr)?;
+    if signed.tx != prepared.tx {
+        return Err(invalid("the transaction differs from the one prepared"));
+    }
+
+    let public = account_key(account)?;
+    let key = ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, public);
+    let signed_by_account = signed.signatures.iter().any(|s| {
+        key.verify(signature_payload, s.signature.0.as_slice())
+            .is_ok()
+    });
+    if !signed_by_account {
+        return Err(invalid(&format!("no valid signature by {account}")));
+    }
+    Ok(())
+}
+
+// ─── Addresses ────────────────────────────────────────────────────────────────
+
+fn account_key(account: &str) -> Result<[u8; 32], ApiError> {
+    stellar_strkey::ed25519::PublicKey::from_string(&account.to_uppercase())
+        .map(|key| key.0)
+        .map_err(|_| ApiError::BadRequest(format!("Invalid Stellar account {account}")))
+}
+
+/// Contract `Address` value for a `G…` account
+pub fn account_address(account: &str) -> Result<ScVal, ApiError> {
+    Ok(ScVal::Address(ScAddress::Account(AccountId(
+        PublicKey::PublicKeyTypeEd25519(Uint256(account_key(account)?)),
+    ))))
+}
+
+/// Contract `i128` value
+pub fn i128_val(value: i128) -> ScVal {
+    ScVal::I128(Int128Parts {
+        hi: (value >> 64) as i64,
+        lo: value as u64,
+    })
+}
+
+// ─── Keypair Signer ───────────────────────────────────────────────────────────
+
+/// Signs with secret seeds held in process memory
+pub struct KeypairSigner {
+    keys: HashMap<String, Ed25519KeyPair>,
+}
+
+impl KeypairSigner {
+    /// Load `S…` secret seeds
+    pub fn from_secret_seeds<'a>(
+        seeds: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Self, ApiError> {
+        let mut keys = HashMap::new();
+        for seed in seeds {
+            let secret =
+                stellar_strkey::ed25519::PrivateKey::from_string(seed.trim()).map_err(|_| {
+                    ApiError::Internal(anyhow::anyhow!("Invalid secret seed in signer config"))
+                })?;
+            let pair = Ed25519KeyPair::from_seed_unchecked(&secret.0).map_err(|_| {
+                ApiError::Internal(anyhow::anyhow!("Invalid secret seed in signer config"))
+            })?;
+            let mut public = [0u8; 32];
+            public.copy_from_slice(pair.public_key().as_ref());
+            let account = stellar_strkey::ed25519::PublicKey(public)
+                .to_string()
+                .as_str()
+                .to_owned();
+            keys.insert(account, pair);
+        }
+        Ok(Self { keys })
+    }
+
+    pub fn accounts(&self) -> Vec<String> {
+        self.keys.keys().cloned().collect()
+    }
+}
+
+#[async_trait]
+impl TransactionSigner for KeypairSigner {
+    fn can_sign(&self, account: &str) -> bool {
+        self.keys.contains_key(&account.to_uppercase())
+    }
+
+    async fn sign(&self, account: &str, payload: &[u8; 32]) -> Result<Vec<u8>, ApiError> {
+        let pair = self.keys.get(&account.to_uppercase()).ok_or_else(|| {
+            ApiError::Internal(anyhow::anyhow!("No signing key for account {}", account))
+        })?;
+        Ok(pair.sign(payload).as_ref().to_vec())
+    }
+}
+
+/// Signer built from the comma-separated seeds in `TX_SIGNING_SECRET_SEEDS`;
+/// without it nothing can be signed and submissions fail as unsignable.
+pub fn signer_from_env() -> Result<Arc<dyn TransactionSigner>, ApiError> {
+    let seeds = std::env::var("TX_SIGNING_SECRET_SEEDS").unwrap_or_default();
+    Ok(Arc::new(KeypairSigner::from_secret_seeds(
+        seeds.split(',').filter(|s| !s.trim().is_empty()),
+    )?))
+}
+
+// ─── Soroban RPC Invoker ──────────────────────────────────────────────────────
+
+pub struct SorobanInvoker {
+    client: reqwest::Client,
+    rpc_url: String,
+    contract: [u8; 32],
+    network_id: [u8; 32],
+}
+
+fn retryable(e: impl fmt::Display) -> SubmitError {
+    SubmitError::Retryable(e.to_string())
+}
+
+fn fatal(e: impl fmt::Display) -> SubmitError {
+    SubmitError::Fatal(e.to_string())
+}
+
+fn decode_xdr<T: ReadXdr>(b64: &str) -> Result<T, SubmitError> {
+    let bytes = BASE64.decode(b64).map_err(fatal)?;
+    T::from_xdr(bytes, Limits::none()).map_err(fatal)
+}
+
+fn encode_xdr(value: &impl WriteXdr) -> Result<String, SubmitError> {
+    Ok(BASE64.encode(value.to_xdr(Limits::none()).map_err(fatal)?))
+}
+
+/// Whether a rejected transaction may go through if rebuilt and resent
+fn is_retryable_result(result: &TransactionResultResult) -> bool {
+    matches!(
+        result,
+        TransactionResultResult::TxBadSeq
+            | TransactionResultResult::TxInsufficientFee
+            | TransactionResultResult::TxTooLate
+            | TransactionResultResult::TxTooEarly
+    )
+}
+
+impl SorobanInvoker {
+    pub fn new(
+        rpc_url: &str,
+        contract_id: &str,
+        network_passphrase: &str,
+    ) -> Result<Self, ApiError> {
+        let contract = stellar_strkey::Contract::from_string(contract_id)
+            .map_err(|e| {
+                ApiError::Internal(anyhow::anyhow!(
+                    "Invalid contract id {}: {}",
+                    contract_id,
+                    e
+                ))
+            })?
+            .0;
+        Ok(Self {
+            client: reqwest::Client::new(),
+            rpc_url: rpc_url.to_string(),
+            contract,
+            network_id: Sha256::digest(network_passphrase.as_bytes()).into(),
+        })
+    }
+
+    /// Invoker for the inheritance contract from `SOROBAN_RPC_URL`,
+    /// `INHERITANCE_CONTRACT_ID` and `STELLAR_NETWORK_PASSPHRASE`, or `None`
+    /// when the chain is not configured.
+    pub fn from_env() -> Result<Option<Self>, ApiError> {
+        let (Ok(rpc_url), Ok(contract_id)) = (
+            std::env::var("SOROBAN_RPC_URL"),
+            std::env::var("INHERITANCE_CONTRACT_ID"),
+        ) else {
+            return Ok(None);
+        };
+        let passphrase = std::env::var("STELLAR_NETWORK_PASSPHRASE")
+            .unwrap_or_else(|_| TESTNET_PASSPHRASE.to_string());
+        Self::new(&rpc_url, &contract_id, &passphrase).map(Some)
+    }
+
+    async fn rpc(&self, method: &str, params: JsonValue) -> Result<JsonValue, SubmitError> {
+        let response: JsonValue = self
+            .client
+            .post(&self.rpc_url)
+            .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| retryable(format!("Soroban RPC request failed: {e}")))?
+            .json()
+            .await
+            .map_err(|e| retryable(format!("Invalid Soroban RPC response: {e}")))?;
+        if let Some(error) = response.get("error") {
+            return Err(retryable(format!("Soroban RPC error: {error}")));
+        }
+        Ok(response["result"].clone())
+    }
+
+    async fn account_sequence(&self, account: &str) -> Result<i64, SubmitError> {
+        let key = LedgerKey::Account(LedgerKeyAccount {
+            account_id: AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(
+                account_key(account).map_err(fatal)?,
+            ))),
+        });
+        let result = self
+            .rpc("getLedgerEntries", json!({ "keys": [encode_xdr(&key)?] }))
+            .await?;
+        let xdr = result["entries"]
+            .as_array()
+            .and_then(|entries| entries.first())
+            .and_then(|entry| entry["xdr"].as_str())
+            .ok_or_else(|| fatal(format!("Source account {account} does not exist")))?;
+        match decode_xdr::<LedgerEntryData>(xdr)? {
+            LedgerEntryData::Account(entry) => Ok(entry.seq_num.0),
+            _ => Err(fatal("ledger entry is not an account")),
+        }
+    }
+
+    /// Unsigned transaction invoking `call` with the given sequence and fee,
+    /// valid until `max_time` (unix seconds)
+    pub fn build_transaction(
+        &self,
+        call: &ContractCall,
+        sequence: i64,
+        fee: u32,
+        max_time: u64,
+    ) -> Result<Transaction, ApiError> {
+        let xdr_error =
+            |e: soroban_sdk::xdr::Error| ApiError::Internal(anyhow::anyhow!("XDR error: {}", e));
+        let invoke = InvokeHostFunctionOp {
+            host_function: HostFunction::InvokeContract(InvokeContractArgs {
+                contract_address: ScAddress::Contract(Hash(self.contract)),
+                function_name: ScSymbol(call.function.as_str().try_into().map_err(xdr_error)?),
+                args: call.args.clone().try_into().map_err(xdr_error)?,
+            }),
+            auth: Default::default(),
+        };
+        Ok(Transaction {
+            source_account: MuxedAccount::Ed25519(Uint256(account_key(&call.source_account)?)),
+            fee,
+            seq_num: SequenceNumber(sequence),
+            cond: Preconditions::Time(TimeBounds {
+                min_time: TimePoint(0),
+                max_time: TimePoint(max_time),
+            }),
+            memo: Memo::None,
+            operations: vec![Operation {
+                source_account: None,
+                body: OperationBody::InvokeHostFunction(invoke),
+            }]
+            .try_into()
+            .map_err(xdr_error)?,
+            ext: TransactionExt::V0,
+        })
+    }
+
+    /// Hash that signers sign, binding the transaction to this network
+    pub fn signature_payload(&self, tx: &Transaction) -> Result<[u8; 32], ApiError> {
+        let payload = TransactionSignaturePayload {
+            network_id: Hash(self.network_id),
+            tagged_transaction: TransactionSignaturePayloadTaggedTransaction::Tx(tx.clone()),
+        };
+        let bytes = payload
+            .to_xdr(Limits::none())
+            .map_err(|e| ApiError::Internal(anyhow::anyhow!("XDR error: {}", e)))?;
+        Ok(Sha256::digest(bytes).into())
+    }
+
+    fn envelope(
+        tx: Transaction,
+        signatures: Vec<DecoratedSignature>,
+    ) -> Result<TransactionEnvelope, SubmitError> {
+        Ok(TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx,
+            signatures: signatures.try_into().map_err(fatal)?,
+        }))
+    }
+
+    /// Add the simulated footprint, authorization and resource fee
+    async fn simulate(&self, mut tx: Transaction) -> Result<Transaction, SubmitError> {
+        let envelope = Self::envelope(tx.clone(), Vec::new())?;
+        let result = self
+            .rpc(
+                "simulateTransaction",
+                json!({ "transaction": encode_xdr(&envelope)? }),
+            )
+            .await?;
+        if let Some(error) = result.get("error").and_then(|e| e.as_str()) {
+            return Err(fatal(format!("Simulation failed: {error}")));
+        }
+
+        let data: SorobanTransactionData = decode_xdr(
+            result["transactionData"]
+                .as_str()
+                .ok_or_else(|| fatal("Simulation returned no transaction data"))?,
+        )?;
+        let resource_fee: u32 = result["minResourceFee"]
+            .as_str()
+            .and_then(|fee| fee.parse().ok())
+            .ok_or_else(|| fatal("Simulation returned no resource fee"))?;
+        let auth = result["results"][0]["auth"]
+            .as_array()
+            .map(|entries| {
+                entries
+                    .iter()
+                    .map(|e| {
+                        decode_xdr::<SorobanAuthorizationEntry>(e.as_str().unwrap_or_default())
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        tx.fee = tx.fee.saturating_add(resource_fee);
+        tx.ext = TransactionExt::V1(data);
+        let mut operations = tx.operations.to_vec();
+        if let Some(OperationBody::InvokeHostFunction(op)) =
+            operations.first_mut().map(|op| &mut op.body)
+        {
+            op.auth = auth.try_into().map_err(fatal)?;
+        }
+        tx.operations = operations.try_into().map_err(fatal)?;
+        Ok(tx)
+    }
+
+    /// Result of a read-only call, taken from simulation without submitting.
+    ///
+    /// Simulation skips signature and sequence checks, so the source account
+    /// need not be one the backend can sign for.
+    pub async fn read(&self, call: &ContractCall) -> Result<ScVal, SubmitError> {
+        let max_time = (chrono::Utc::now().timestamp() + DEFAULT_TX_VALIDITY_SECS) as u64;
+        let tx = self
+            .build_transaction(call, 0, 100, max_time)
+            .map_err(fatal)?;
+        let envelope = Self::envelope(tx, Vec::new())?;
+        let result = self
+            .rpc(
+                "simulateTransaction",
+                json!({ "transaction": encode_xdr(&envelope)? }),
+            )
+            .await?;
+        if let Some(error) = result.get("error").and_then(|e| e.as_str()) {
+            return Err(fatal(format!("Simulation failed: {error}")));
+        }
+        decode_xdr(
+            result["results"][0]["xdr"]
+                .as_str()
+                .ok_or_else(|| fatal("Simulation returned no result"))?,
+        )
+    }
+}
+
+#[async_trait]
+impl ContractInvoker for SorobanInvoker {
+    async fn prepare(
+        &self,
+        call: &ContractCall,
+        inclusion_fee: u32,
+        max_time: u64,
+    ) -> Result<PreparedTransaction, SubmitError> {
+        let sequence = self.account_sequence(&call.source_account).await? + 1;
+        let tx = self
+            .build_transaction(call, sequence, inclusion_fee, max_time)
+            .map_err(fatal)?;
+        let tx = self.simulate(tx).await?;
+        let signature_payload = self.signature_payload(&tx).map_err(fatal)?;
+        let fee = tx.fee;
+        Ok(PreparedTransaction {
+            envelope_xdr: encode_xdr(&Self::envelope(tx, Vec::new())?)?,
+            signature_payload,
+            tx_hash: hex::encode(signature_payload),
+            fee,
+        })
+    }
+
+    async fn send_signed(&self, envelope_xdr: &str) -> Result<String, SubmitError> {
+        let result = self
+            .rpc("sendTransaction", json!({ "transaction": envelope_xdr }))
+            .await?;
+        let hash = result["hash"].as_str().unwrap_or_default().to_string();
+        match result["status"].as_str() {
+            Some("PENDING") | Some("DUPLICATE") => Ok(hash),
+            Some("TRY_AGAIN_LATER") => Err(retryable("Network asked to try again later")),
+            _ => {
+                let reason = result["errorResultXdr"]
+                    .as_str()
+                    .and_then(|xdr| decode_xdr::<TransactionResult>(xdr).ok());
+                match reason {
+                    Some(r) if is_retryable_result(&r.result) => {
+                        Err(retryable(format!("Rejected: {}", r.result.name())))
+                    }
+                    Some(r) => Err(fatal(format!("Rejected: {}", r.result.name()))),
+                    None => Err(fatal(format!("Rejected: {result}"))),
+                }
+            }
+        }
+    }
+
+    async fn status(&self, tx_hash: &str) -> Result<TxStatus, SubmitError> {
+        let result = self
+            .rpc("getTransaction", json!({ "hash": tx_hash }))
+            .await?;
+        let ledger = result["ledger"].as_u64().unwrap_or_default() as u32;
+        Ok(match result["status"].as_str() {
+            Some("SUCCESS") => TxStatus::Confirmed { ledger },
+            Some("FAILED") => TxStatus::Failed(format!("Transaction failed in ledger {ledger}")),
+            Some("NOT_FOUND") => TxStatus::NotFound,
+            _ => TxStatus::Pending,
+        })
+    }
+}
+
+// ─── Unit Tests ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+    use ring::signature::UnparsedPublicKey;
+
+    const SEED: [u8; 32] = [3; 32];
+
+    fn seed() -> String {
+        stellar_strkey::ed25519::PrivateKey(SEED)
+            .to_string()
+            .as_str()
+            .to_owned()
+    }
+
+    fn invoker(url: &str) -> SorobanInvoker {
+        let contract = stellar_strkey::Contract([1; 32]).to_string();
+        SorobanInvoker::new(url, contract.as_str(), TESTNET_PASSPHRASE).unwrap()
+    }
+
+    #[tokio::test]
+    async fn keypair_signer_signs_for_its_accounts_only() {
+        let signer = KeypairSigner::from_secret_seeds([seed().as_str()]).unwrap();
+        let account = signer.accounts().pop().unwrap();
+        assert!(signer.can_sign(&account));
+        assert!(!signer.can_sign("GOTHER"));
+
+        let call = ContractCall {
+            source_account: account.clone(),
+            function: "sign_as_witness".to_string(),
+            args: vec![account_address(&account).unwrap(), ScVal::U64(4)],
+        };
+        let invoker = invoker("http://localhost");
+        let tx = invoker.build_transaction(&call, 11, 100, 5_000).unwrap();
+        assert_eq!(tx.seq_num.0, 11);
+        assert!(matches!(
+            &tx.cond,
+            Preconditions::Time(bounds) if bounds.max_time == TimePoint(5_000)
+        ));
+        let payload = invoker.signature_payload(&tx).unwrap();
+
+        let signature = signer.sign(&account, &payload).await.unwrap();
+        let public = account_key(&account).unwrap();
+        UnparsedPublicKey::new(&ring::signature::ED25519, public)
+            .verify(&payload, &signature)
+            .unwrap();
+    }
+
+    #[test]
+    fn only_sequence_and_fee_rejections_are_retryable() {
+        assert!(is_retryable_result(&TransactionResultResult::TxBadSeq));
+        assert!(is_retryable_result(
+            &TransactionResultResult::TxInsufficientFee
+        ));
+        assert!(!is_retryable_result(&TransactionResultResult::TxBadAuth));
+        assert!(account_address("not-an-account").is_err());
+    }
+
+    #[tokio::test]
+    async fn maps_get_transaction_statuses() {
+        let server = MockServer::start();
+        let invoker = invoker(&server.url("/"));
+
+        let mut confirmed = server.mock(|when, then| {
+            when.method(POST).body_contains("\"hash\":\"aa\"");
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0", "id": 1,
+                "result": { "status": "SUCCESS", "ledger": 42 }
+            }));
+        });
+        assert_eq!(
+            invoker.status("aa").await.unwrap(),
+            TxStatus::Confirmed { ledger: 42 }
+        );
+        confirmed.delete();
+
+        server.mock(|when, then| {
+            when.method(POST).body_contains("\"hash\":\"bb\"");
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0", "id": 1,
+                "result": { "status": "NOT_FOUND" }
+            }));
+        });
+        assert_eq!(invoker.status("bb").await.unwrap(), TxStatus::NotFound);
+    }
+
+    #[tokio::test]
+    async fn wallet_signed_envelope_must_match_the_prepared_transaction() {
+        let signer = KeypairSigner::from_secret_seeds([seed().as_str()]).unwrap();
+        let account = signer.accounts().pop().unwrap();
+        let invoker = invoker("http://localhost");
+        let call = ContractCall {
+            source_account: account.clone(),
+            function: "sign_will".to_string(),
+            args: vec![account_address(&account).unwrap(), ScVal::U64(4)],
+        };
+        let tx = invoker.build_transaction(&call, 11, 100, 5_000).unwrap();
+        let payload = invoker.signature_payload(&tx).unwrap();
+        let prepared = PreparedTransaction {
+            envelope_xdr: encode_xdr(&SorobanInvoker::envelope(tx.clone(), Vec::new()).unwrap())
+                .unwrap(),
+            signature_payload: payload,
+            tx_hash: hex::encode(payload),
+            fee: 100,
+        };
+
+        let signature = signer.sign(&account, &payload).await.unwrap();
+        let signed = attach_signature(&prepared, &account, signature.clone()).unwrap();
+        verify_signed_envelope(&prepared.envelope_xdr, &payload, &account, &signed).unwrap();
+
+        // Unsigned, or signed over a different transaction
+        assert!(verify_signed_envelope(
+            &prepared.envelope_xdr,
+            &payload,
+            &account,
+            &prepared.envelope_xdr
+        )
+        .is_err());
+        let mut altered = tx;
+        altered.fee = 1;
+        let other = PreparedTransaction {
+            envelope_xdr: encode_xdr(&SorobanInvoker::envelope(altered, Vec::new()).unwrap())
+                .unwrap(),
+            ..prepared.clone()
+        };
+        let tampered = attach_signature(&other, &account, signature).unwrap();
+        assert!(
+            verify_signed_envelope(&prepared.envelope_xdr, &payload, &account, &tampered).is_err()
+        );
+    }
+}
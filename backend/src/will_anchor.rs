@@ -0,0 +1,1409 @@
+//! # Will Anchoring
+//!
+//! Mirrors every will lifecycle step onto the inheritance contract. The will
+//! services queue contract calls in the same database transaction as their
+//! writes; a worker sends them in order per plan, raises the fee on each retry
+//! and tracks them until they land in a ledger. A call that fails holds back
+//! the rest of its plan until an admin retries it. A reconciliation pass
+//! compares each settled document with contract storage and records any drift
+//! until it clears.
+//!
+//! Calls are signed by the backend only for accounts it holds a key for. Any
+//! other call is prepared unsigned and waits for the user's wallet to sign it
+//! and hand the envelope back; the backend never asks for a user's seed.
+//!
+//! Each transaction expires at `valid_until`. A call is rebuilt only once that
+//! time has passed and the network still does not know its hash, so it can
+//! never be applied twice.
+
+use crate::api_error::ApiError;
+use crate::contract_reader::InheritanceContractReader;
+use crate::tx_submitter::{
+    account_address, attach_signature, verify_signed_envelope, ContractCall, ContractInvoker,
+    PreparedTransaction, SubmitError, TransactionSigner, TxStatus, DEFAULT_TX_VALIDITY_SECS,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use soroban_sdk::xdr::{ScBytes, ScVal};
+use sqlx::{PgConnection, PgPool};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Attempts before a call is marked failed and left for an admin to retry
+pub const MAX_ANCHOR_ATTEMPTS: i32 = 8;
+
+/// How long a claimed call may go without a prepared transaction before it
+/// is requeued
+const CLAIM_TIMEOUT_SECS: i64 = 120;
+
+/// Margin past `valid_until` before an unknown transaction counts as expired,
+/// covering ledger close times running behind the wall clock
+const EXPIRY_GRACE_SECS: i64 = 30;
+
+/// How long a wallet has to sign a prepared transaction
+const DEFAULT_WALLET_SIGNING_SECS: i64 = 24 * 60 * 60;
+
+/// Inclusion fee of the first attempt, in stroops
+const DEFAULT_BASE_FEE: u32 = 100;
+
+/// Ceiling for the bumped inclusion fee, in stroops
+const DEFAULT_MAX_FEE: u32 = 100_000;
+
+/// Worker ticks between reconciliation passes
+const RECONCILE_EVERY_TICKS: u64 = 40;
+
+// ─── Types ────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnchorOperation {
+    StoreWillHash,
+    LinkWillToVault,
+    CreateWillVersion,
+    SignWill,
+    FinalizeWill,
+    AddWitness,
+    SignAsWitness,
+    RevokeWill,
+}
+
+impl AnchorOperation {
+    /// The contract function this operation invokes
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnchorOperation::StoreWillHash => "store_will_hash",
+            AnchorOperation::LinkWillToVault => "link_will_to_vault",
+            AnchorOperation::CreateWillVersion => "create_will_version",
+            AnchorOperation::SignWill => "sign_will",
+            AnchorOperation::FinalizeWill => "finalize_will",
+            AnchorOperation::AddWitness => "add_witness",
+            AnchorOperation::SignAsWitness => "sign_as_witness",
+            AnchorOperation::RevokeWill => "revoke_will",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, ApiError> {
+        match s {
+            "store_will_hash" => Ok(AnchorOperation::StoreWillHash),
+            "link_will_to_vault" => Ok(AnchorOperation::LinkWillToVault),
+            "create_will_version" => Ok(AnchorOperation::CreateWillVersion),
+            "sign_will" => Ok(AnchorOperation::SignWill),
+            "finalize_will" => Ok(AnchorOperation::FinalizeWill),
+            "add_witness" => Ok(AnchorOperation::AddWitness),
+            "sign_as_witness" => Ok(AnchorOperation::SignAsWitness),
+            "revoke_will" => Ok(AnchorOperation::RevokeWill),
+            other => Err(ApiError::BadRequest(format!(
+                "Unknown anchor operation: {other}"
+            ))),
+        }
+    }
+}
+
+/// A queued contract call and its submission state
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct AnchorTransaction {
+    pub id: Uuid,
+    pub seq: i64,
+    pub plan_id: Uuid,
+    pub document_id: Option<Uuid>,
+    pub operation: String,
+    pub contract_plan_id: Option<i64>,
+    pub source_account: Option<String>,
+    pub will_hash: Option<String>,
+    pub version: Option<i32>,
+    pub witness_account: Option<String>,
+    pub status: String,
+    pub attempts: i32,
+    pub fee: Option<i64>,
+    pub tx_hash: Option<String>,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+    pub ledger: Option<i64>,
+    pub valid_until: Option<DateTime<Utc>>,
+    pub unsigned_envelope: Option<String>,
+    pub signature_payload: Option<String>,
+    pub signed_envelope: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A prepared call waiting for the user's wallet to sign it
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct PendingWalletSignature {
+    pub id: Uuid,
+    pub plan_id: Uuid,
+    pub document_id: Option<Uuid>,
+    pub operation: String,
+    pub source_account: String,
+    pub tx_hash: String,
+    /// Base64 envelope XDR to sign and return
+    pub unsigned_envelope: String,
+    pub valid_until: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SubmitWalletSignatureRequest {
+    /// Base64 envelope XDR carrying the wallet's signature
+    pub signed_envelope: String,
+}
+
+/// A disagreement between a document and contract storage
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct AnchorDrift {
+    pub id: Uuid,
+    pub document_id: Uuid,
+    pub plan_id: Uuid,
+    pub check_name: String,
+    pub expected: String,
+    pub actual: String,
+    pub detected_at: DateTime<Utc>,
+    pub last_checked_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// Anchoring state of one will document
+#[derive(Debug, Clone, Serialize)]
+pub struct DocumentAnchorStatus {
+    pub document_id: Uuid,
+    /// `not_anchored`, `awaiting_signature`, `pending`, `blocked`,
+    /// `anchored`, `failed` or `drift`, or `off_chain` for a codicil, which
+    /// the contract does not record
+    pub status: &'static str,
+    /// Failed call of the plan that holds this document's calls back
+    pub blocked_by: Option<Uuid>,
+    pub transactions: Vec<AnchorTransaction>,
+    pub drift: Vec<AnchorDrift>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AnchorRunResult {
+    pub submitted: usize,
+    pub awaiting_signature: usize,
+    pub confirmed: usize,
+    pub retried: usize,
+    pub failed: usize,
+    /// Due calls held back by an earlier failed call of their plan
+    pub blocked: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReconciliationReport {
+    pub documents_checked: usize,
+    pub drift_detected: usize,
+    pub drift_resolved: usize,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AnchorTransactionQuery {
+    pub status: Option<String>,
+}
+
+/// A contract call to queue; the source defaults to the plan owner's wallet
+struct NewAnchor {
+    operation: AnchorOperation,
+    source_account: Option<String>,
+    will_hash: Option<String>,
+    version: Option<i32>,
+    witness_account: Option<String>,
+}
+
+impl NewAnchor {
+    fn new(operation: AnchorOperation) -> Self {
+        Self {
+            operation,
+            source_account: None,
+            will_hash: None,
+            version: None,
+            witness_account: None,
+        }
+    }
+}
+
+// ─── Helpers ──────────────────────────────────────────────────────────────────
+
+/// SQL condition on a call `e` that holds back a later call `t` of its plan
+/// until it is retried: a terminal failure other than a witness signature
+const BLOCKING_FAILURE: &str = "e.status = 'failed' AND e.operation <> 'sign_as_witness'";
+
+/// Inclusion fee for the next attempt: doubled after every failed attempt
+pub fn inclusion_fee(base_fee: u32, max_fee: u32, previous_attempts: i32) -> u32 {
+    let shift = previous_attempts.clamp(0, 16) as u32;
+    base_fee.saturating_mul(1 << shift).min(max_fee)
+}
+
+/// Backoff before the next attempt after `attempts` failures
+fn retry_delay_secs(attempts: i32) -> i64 {
+    let attempts = attempts.clamp(1, MAX_ANCHOR_ATTEMPTS) as i64;
+    attempts * attempts * 30
+}
+
+/// Whether a transaction valid until `valid_until` can no longer be included
+fn expired(valid_until: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    valid_until.is_some_and(|until| now > until + chrono::Duration::seconds(EXPIRY_GRACE_SECS))
+}
+
+/// The contract invocation a queued row stands for
+pub fn contract_call(tx: &AnchorTransaction) -> Result<ContractCall, ApiError> {
+    let operation = AnchorOperation::parse(&tx.operation)?;
+    let missing = |field: &str| ApiError::BadRequest(format!("{} needs {field}", tx.operation));
+
+    let source = tx
+        .source_account
+        .clone()
+        .ok_or_else(|| missing("a source account"))?;
+    let plan_id = tx
+        .contract_plan_id
+        .and_then(|id| u64::try_from(id).ok())
+        .ok_or_else(|| missing("an on-chain plan id"))?;
+    let will_hash = || -> Result<ScVal, ApiError> {
+        let bytes = tx
+            .will_hash
+            .as_deref()
+            .and_then(|h| hex::decode(h).ok())
+            .filter(|b| b.len() == 32)
+            .ok_or_else(|| missing("a 32-byte will hash"))?;
+        Ok(ScVal::Bytes(ScBytes(
+            bytes
+                .try_into()
+                .map_err(|_| missing("a 32-byte will hash"))?,
+        )))
+    };
+
+    let owner = account_address(&source)?;
+    let args = match operation {
+        AnchorOperation::StoreWillHash
+        | AnchorOperation::LinkWillToVault
+        | AnchorOperation::CreateWillVersion
+        | AnchorOperation::SignWill => vec![owner, ScVal::U64(plan_id), will_hash()?],
+        AnchorOperation::FinalizeWill => {
+            let version = tx
+                .version
+                .and_then(|v| u32::try_from(v).ok())
+                .ok_or_else(|| missing("a version"))?;
+            vec![owner, ScVal::U64(plan_id), ScVal::U32(version)]
+        }
+        AnchorOperation::AddWitness => {
+            let witness = tx
+                .witness_account
+                .as_deref()
+                .ok_or_else(|| missing("a witness account"))?;
+            vec![owner, ScVal::U64(plan_id), account_address(witness)?]
+        }
+        AnchorOperation::SignAsWitness | AnchorOperation::RevokeWill => {
+            vec![owner, ScVal::U64(plan_id)]
+        }
+    };
+
+    Ok(ContractCall {
+        source_account: source,
+        function: operation.as_str().to_string(),
+        args,
+    })
+}
+
+fn summarize(
+    transactions: &[AnchorTransaction],
+    drift: &[AnchorDrift],
+    blocked: bool,
+) -> &'static str {
+    if transactions.is_empty() {
+        "not_anchored"
+    } else if transactions.iter().any(|t| t.status == "failed") {
+        "failed"
+    } else if blocked {
+        "blocked"
+    } else if transactions
+        .iter()
+        .any(|t| t.status == "awaiting_signature")
+    {
+        "awaiting_signature"
+    } else if transactions.iter().any(|t| t.status != "confirmed") {
+        "pending"
+    } else if !drift.is_empty() {
+        "drift"
+    } else {
+        "anchored"
+    }
+}
+
+// ─── Service ──────────────────────────────────────────────────────────────────
+
+pub struct WillAnchorService {
+    db: PgPool,
+    invoker: Arc<dyn ContractInvoker>,
+    signer: Arc<dyn TransactionSigner>,
+    reader: Arc<dyn InheritanceContractReader>,
+    base_fee: u32,
+    max_fee: u32,
+    wallet_signing_secs: i64,
+}
+
+impl WillAnchorService {
+    pub fn new(
+        db: PgPool,
+        invoker: Arc<dyn ContractInvoker>,
+        signer: Arc<dyn TransactionSigner>,
+        reader: Arc<dyn InheritanceContractReader>,
+    ) -> Self {
+        let fee = |name: &str, default: u32| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default)
+        };
+        let wallet_signing_secs = std::env::var("WILL_ANCHOR_WALLET_SIGNING_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_WALLET_SIGNING_SECS);
+        Self {
+            db,
+            invoker,
+            signer,
+            reader,
+            base_fee: fee("WILL_ANCHOR_BASE_FEE", DEFAULT_BASE_FEE),
+            max_fee: fee("WILL_ANCHOR_MAX_FEE", DEFAULT_MAX_FEE),
+            wallet_signing_secs,
+        }
+    }
+
+    // ── Enqueueing ──────────────────────────────────────────────────────────
+
+    /// Queue the calls mirroring a newly generated document. The first
+    /// version also records the plan's original will hash and vault link.
+    pub async fn enqueue_generated(
+        conn: &mut PgConnection,
+        plan_id: Uuid,
+        document_id: Uuid,
+        version: u32,
+        will_hash: &str,
+    ) -> Result<(), ApiError> {
+        let with_hash = |operation| NewAnchor {
+            will_hash: Some(will_hash.to_string()),
+            ..NewAnchor::new(operation)
+        };
+        let mut anchors = Vec::new();
+        if version == 1 {
+            anchors.push(with_hash(AnchorOperation::StoreWillHash));
+            anchors.push(with_hash(AnchorOperation::LinkWillToVault));
+        }
+        anchors.push(NewAnchor {
+            version: Some(version as i32),
+            ..with_hash(AnchorOperation::CreateWillVersion)
+        });
+        Self::enqueue(conn, plan_id, Some(document_id), anchors).await
+    }
+
+    /// Queue the owner's signature of a document
+    pub async fn enqueue_owner_signature(
+        conn: &mut PgConnection,
+        document_id: Uuid,
+        wallet_address: &str,
+    ) -> Result<(), ApiError> {
+        let (plan_id, will_hash) = Self::document_plan(&mut *conn, document_id).await?;
+        let anchor = NewAnchor {
+            source_account: Some(wallet_address.to_string()),
+            will_hash: Some(will_hash),
+            ..NewAnchor::new(AnchorOperation::SignWill)
+        };
+        Self::enqueue(conn, plan_id, Some(document_id), vec![anchor]).await
+    }
+
+    /// Queue the finalization of a document version
+    pub async fn enqueue_finalization(
+        conn: &mut PgConnection,
+        plan_id: Uuid,
+        document_id: Uuid,
+        version: u32,
+    ) -> Result<(), ApiError> {
+        let anchor = NewAnchor {
+            version: Some(version as i32),
+            ..NewAnchor::new(AnchorOperation::FinalizeWill)
+        };
+        Self::enqueue(conn, plan_id, Some(document_id), vec![anchor]).await
+    }
+
+    /// Queue the registration of a witness and their signature.
+    ///
+    /// Only a witness whose key is the linked wallet of an account can sign
+    /// the contract call, through [`Self::pending_wallet_signatures`]. For
+    /// anyone else, such as an email invitee or a device key, only the
+    /// owner-signed registration is queued and their signature stays in
+    /// `will_witnesses`.
+    pub async fn enqueue_witness_signature(
+        conn: &mut PgConnection,
+        document_id: Uuid,
+        witness_wallet: &str,
+    ) -> Result<(), ApiError> {
+        let (plan_id, _) = Self::document_plan(&mut *conn, document_id).await?;
+        let has_account: bool =
+            sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM users WHERE wallet_address = $1)")
+                .bind(witness_wallet)
+                .fetch_one(&mut *conn)
+                .await?;
+        let mut anchors = vec![NewAnchor {
+            witness_account: Some(witness_wallet.to_string()),
+            ..NewAnchor::new(AnchorOperation::AddWitness)
+        }];
+        if has_account {
+            anchors.push(NewAnchor {
+                source_account: Some(witness_wallet.to_string()),
+                ..NewAnchor::new(AnchorOperation::SignAsWitness)
+            });
+        }
+        Self::enqueue(conn, plan_id, Some(document_id), anchors).await
+    }
+
+    /// Queue the revocation of every finalized version of a plan's will.
+    /// `document_id` and `version` record the latest will revoked.
+    pub async fn enqueue_revocation(
+        conn: &mut PgConnection,
+        plan_id: Uuid,
+        document_id: Uuid,
+        version: u32,
+    ) -> Result<(), ApiError> {
+        let anchor = NewAnchor {
+            version: Some(version as i32),
+            ..NewAnchor::new(AnchorOperation::RevokeWill)
+        };
+        Self::enqueue(conn, plan_id, Some(document_id), vec![anchor]).await
+    }
+
+    /// The contract's number for a document version. It counts only wills,
+    /// so every codicil before a version moves it down by one.
+    pub async fn contract_version(
+        conn: &mut PgConnection,
+        plan_id: Uuid,
+        version: i32,
+    ) -> Result<i32, ApiError> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM will_documents \
+             WHERE plan_id = $1 AND kind = 'will' AND version <= $2",
+        )
+        .bind(plan_id)
+        .bind(version)
+        .fetch_one(conn)
+        .await?;
+        Ok(count as i32)
+    }
+
+    async fn document_plan(
+        conn: &mut PgConnection,
+        document_id: Uuid,
+    ) -> Result<(Uuid, String), ApiError> {
+        sqlx::query_as("SELECT plan_id, will_hash FROM will_documents WHERE id = $1")
+            .bind(document_id)
+            .fetch_optional(conn)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Will document {document_id} not found")))
+    }
+
+    /// Insert calls in order on the caller's transaction, so they commit or
+    /// roll back with the write they mirror. A plan that cannot be anchored
+    /// still gets its rows, marked failed, so the gap shows up in the
+    /// document's status.
+    async fn enqueue(
+        conn: &mut PgConnection,
+        plan_id: Uuid,
+        document_id: Option<Uuid>,
+        mut anchors: Vec<NewAnchor>,
+    ) -> Result<(), ApiError> {
+        // The contract has no notion of codicils, and refuses new versions
+        // over the finalized will a codicil amends, so they stay off-chain
+        if let Some(document_id) = document_id {
+            let kind: Option<String> =
+                sqlx::query_scalar("SELECT kind FROM will_documents WHERE id = $1")
+                    .bind(document_id)
+                    .fetch_optional(&mut *conn)
+                    .await?;
+            if kind.as_deref() == Some("codicil") {
+                return Ok(());
+            }
+        }
+        for anchor in &mut anchors {
+            if let Some(version) = anchor.version {
+                anchor.version = Some(Self::contract_version(&mut *conn, plan_id, version).await?);
+            }
+        }
+
+        let (contract_plan_id, owner_wallet): (Option<i64>, Option<String>) = sqlx::query_as(
+            "SELECT p.contract_plan_id, u.wallet_address \
+             FROM plans p JOIN users u ON u.id = p.user_id WHERE p.id = $1",
+        )
+        .bind(plan_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Plan {plan_id} not found")))?;
+
+        for anchor in anchors {
+            let source_account = anchor.source_account.or_else(|| owner_wallet.clone());
+            let blocker = if contract_plan_id.is_none() {
+                Some("Plan has not been created on-chain")
+            } else if source_account.is_none() {
+                Some("Plan owner has no linked wallet")
+            } else {
+                None
+            };
+
+            sqlx::query(
+                "INSERT INTO will_anchor_transactions \
+                 (plan_id, document_id, operation, contract_plan_id, source_account, will_hash, \
+                  version, witness_account, status, last_error) \
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            )
+            .bind(plan_id)
+            .bind(document_id)
+            .bind(anchor.operation.as_str())
+            .bind(contract_plan_id)
+            .bind(&source_account)
+            .bind(&anchor.will_hash)
+            .bind(anchor.version)
+            .bind(&anchor.witness_account)
+            .bind(if blocker.is_some() {
+                "failed"
+            } else {
+                "pending"
+            })
+            .bind(blocker)
+            .execute(&mut *conn)
+            .await?;
+        }
+        Ok(())
+    }
+
+    // ── Status ──────────────────────────────────────────────────────────────
+
+    pub async fn document_status(
+        db: &PgPool,
+        document_id: Uuid,
+    ) -> Result<DocumentAnchorStatus, ApiError> {
+        let transactions = sqlx::query_as::<_, AnchorTransaction>(
+            "SELECT * FROM will_anchor_transactions WHERE document_id = $1 ORDER BY seq",
+        )
+        .bind(document_id)
+        .fetch_all(db)
+        .await?;
+        let drift = sqlx::query_as::<_, AnchorDrift>(
+            "SELECT * FROM will_anchor_drift \
+             WHERE document_id = $1 AND resolved_at IS NULL ORDER BY detected_at",
+        )
+        .bind(document_id)
+        .fetch_all(db)
+        .await?;
+        let blocked_by: Option<Uuid> = sqlx::query_scalar(&format!(
+            "SELECT e.id FROM will_anchor_transactions e \
+             JOIN will_anchor_transactions t ON t.plan_id = e.plan_id AND e.seq < t.seq \
+             WHERE t.document_id = $1 AND t.status = 'pending' AND {BLOCKING_FAILURE} \
+             ORDER BY e.seq LIMIT 1"
+        ))
+        .bind(document_id)
+        .fetch_optional(db)
+        .await?;
+
+        let kind: Option<String> =
+            sqlx::query_scalar("SELECT kind FROM will_documents WHERE id = $1")
+                .bind(document_id)
+                .fetch_optional(db)
+                .await?;
+        let status = if kind.as_deref() == Some("codicil") {
+            "off_chain"
+        } else {
+            summarize(&transactions, &drift, blocked_by.is_some())
+        };
+
+        Ok(DocumentAnchorStatus {
+            document_id,
+            status,
+            blocked_by,
+            transactions,
+            drift,
+        })
+    }
+
+    /// Queued calls, newest first, optionally filtered by status
+    pub async fn list(
+        db: &PgPool,
+        query: &AnchorTransactionQuery,
+    ) -> Result<Vec<AnchorTransaction>, ApiError> {
+        Ok(sqlx::query_as::<_, AnchorTransaction>(
+            "SELECT * FROM will_anchor_transactions \
+             WHERE ($1::text IS NULL OR status = $1) ORDER BY seq DESC LIMIT 200",
+        )
+        .bind(&query.status)
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Open drift across all documents
+    pub async fn list_drift(db: &PgPool) -> Result<Vec<AnchorDrift>, ApiError> {
+        Ok(sqlx::query_as::<_, AnchorDrift>(
+            "SELECT * FROM will_anchor_drift WHERE resolved_at IS NULL ORDER BY detected_at DESC",
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Send a failed call again from the base fee
+    pub async fn retry(db: &PgPool, id: Uuid) -> Result<AnchorTransaction, ApiError> {
+        sqlx::query_as::<_, AnchorTransaction>(
+            "UPDATE will_anchor_transactions \
+             SET status = 'pending', attempts = 0, next_attempt_at = NOW(), updated_at = NOW() \
+             WHERE id = $1 AND status = 'failed' RETURNING *",
+        )
+        .bind(id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest(format!("No failed anchor transaction {id}")))
+    }
+
+    // ── Wallet signing ──────────────────────────────────────────────────────
+
+    /// Prepared calls the user's wallet has yet to sign
+    pub async fn pending_wallet_signatures(
+        db: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<PendingWalletSignature>, ApiError> {
+        Ok(sqlx::query_as::<_, PendingWalletSignature>(
+            "SELECT a.id, a.plan_id, a.document_id, a.operation, a.source_account, a.tx_hash, \
+                    a.unsigned_envelope, a.valid_until \
+             FROM will_anchor_transactions a JOIN users u ON u.wallet_address = a.source_account \
+             WHERE u.id = $1 AND a.status = 'awaiting_signature' AND a.valid_until > NOW() \
+             ORDER BY a.seq",
+        )
+        .bind(user_id)
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Accept the wallet's signature of a prepared call. The envelope must
+    /// carry the prepared transaction unchanged, signed by its source
+    /// account; the worker sends it on its next pass.
+    pub async fn submit_wallet_signature(
+        db: &PgPool,
+        user_id: Uuid,
+        id: Uuid,
+        signed_envelope: &str,
+    ) -> Result<AnchorTransaction, ApiError> {
+        let mut tx = db.begin().await?;
+        let row = sqlx::query_as::<_, AnchorTransaction>(
+            "SELECT a.* FROM will_anchor_transactions a \
+             JOIN users u ON u.wallet_address = a.source_account \
+             WHERE a.id = $1 AND u.id = $2 FOR UPDATE OF a",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Anchor transaction {id} not found")))?;
+
+        let (Some(unsigned), Some(payload), Some(account), "awaiting_signature") = (
+            row.unsigned_envelope.as_deref(),
+            row.signature_payload.as_deref(),
+            row.source_account.as_deref(),
+            row.status.as_str(),
+        ) else {
+            return Err(ApiError::BadRequest(format!(
+                "Anchor transaction {id} is not awaiting a signature"
+            )));
+        };
+        if row.valid_until.is_none_or(|until| until <= Utc::now()) {
+            return Err(ApiError::BadRequest(
+                "The prepared transaction has expired; a new one will be prepared".to_string(),
+            ));
+        }
+        let payload: [u8; 32] = hex::decode(payload)
+            .ok()
+            .and_then(|p| p.try_into().ok())
+            .ok_or_else(|| {
+                ApiError::Internal(anyhow::anyhow!("Corrupt signature payload on {id}"))
+            })?;
+        verify_signed_envelope(unsigned, &payload, account, signed_envelope)?;
+
+        let row = sqlx::query_as::<_, AnchorTransaction>(
+            "UPDATE will_anchor_transactions \
+             SET status = 'signed', signed_envelope = $2, next_attempt_at = NOW(), \
+                 updated_at = NOW() \
+             WHERE id = $1 RETURNING *",
+        )
+        .bind(id)
+        .bind(signed_envelope)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(row)
+    }
+
+    // ── Worker ──────────────────────────────────────────────────────────────
+
+    pub fn start(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(15));
+            let mut ticks: u64 = 0;
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.poll_confirmations().await {
+                    error!("Will anchoring error (confirmations): {}", e);
+                }
+                if let Err(e) = self.process_due().await {
+                    error!("Will anchoring error (submission): {}", e);
+                }
+                if ticks.is_multiple_of(RECONCILE_EVERY_TICKS) {
+                    if let Err(e) = Self::reconcile(&self.db, self.reader.as_ref()).await {
+                        error!("Will anchoring error (reconciliation): {}", e);
+                    }
+                }
+                ticks += 1;
+            }
+        });
+    }
+
+    /// Prepare due calls and send signed ones. A call waits until every
+    /// earlier call for its plan has confirmed, so versions and signatures
+    /// land in order and a failed call holds back the ones after it.
+    /// Witness signatures wait on the witness's wallet, so they hold back
+    /// nothing. A call that cannot be handled is logged and left for the
+    /// next pass; the rest of the batch still goes out.
+    pub async fn process_due(&self) -> Result<AnchorRunResult, ApiError> {
+        let mut result = AnchorRunResult::default();
+
+        let signed = sqlx::query_as::<_, AnchorTransaction>(
+            "UPDATE will_anchor_transactions \
+             SET status = 'submitted', submitted_at = NOW(), updated_at = NOW() \
+             WHERE id IN ( \
+                 SELECT id FROM will_anchor_transactions \
+                 WHERE status = 'signed' AND next_attempt_at <= NOW() \
+                 ORDER BY seq LIMIT 20 \
+                 FOR UPDATE SKIP LOCKED) \
+             RETURNING *",
+        )
+        .fetch_all(&self.db)
+        .await?;
+        for row in &signed {
+            if let Err(e) = self.send(row, &mut result).await {
+                error!("Sending {} ({}) failed: {}", row.operation, row.id, e);
+            }
+        }
+
+        let due = sqlx::query_as::<_, AnchorTransaction>(
+            "UPDATE will_anchor_transactions \
+             SET status = 'submitted', attempts = attempts + 1, tx_hash = NULL, \
+                 valid_until = NULL, unsigned_envelope = NULL, signature_payload = NULL, \
+                 signed_envelope = NULL, submitted_at = NOW(), updated_at = NOW() \
+             WHERE id IN ( \
+                 SELECT t.id FROM will_anchor_transactions t \
+                 WHERE t.status = 'pending' AND t.next_attempt_at <= NOW() \
+                   AND NOT EXISTS ( \
+                       SELECT 1 FROM will_anchor_transactions e \
+                       WHERE e.plan_id = t.plan_id AND e.seq < t.seq \
+                         AND e.status <> 'confirmed' AND e.operation <> 'sign_as_witness') \
+                 ORDER BY t.seq LIMIT 20 \
+                 FOR UPDATE SKIP LOCKED) \
+             RETURNING *",
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        for row in &due {
+            if let Err(e) = self.prepare(row, &mut result).await {
+                error!("Preparing {} ({}) failed: {}", row.operation, row.id, e);
+            }
+        }
+
+        result.blocked = sqlx::query_scalar::<_, i64>(&format!(
+            "SELECT COUNT(DISTINCT t.id) FROM will_anchor_transactions t \
+             JOIN will_anchor_transactions e ON e.plan_id = t.plan_id AND e.seq < t.seq \
+             WHERE t.status = 'pending' AND {BLOCKING_FAILURE}"
+        ))
+        .fetch_one(&self.db)
+        .await? as usize;
+        if result.blocked > 0 {
+            warn!(
+                "{} will anchoring transactions are held back by failed calls",
+                result.blocked
+            );
+        }
+
+        if result.submitted > 0 || result.awaiting_signature > 0 {
+            info!(
+                "Submitted {} will anchoring transactions, {} awaiting wallet signatures",
+                result.submitted, result.awaiting_signature
+            );
+        }
+        Ok(result)
+    }
+
+    /// Build, sign and send one claimed call, or park it for its wallet
+    async fn prepare(
+        &self,
+        row: &AnchorTransaction,
+        result: &mut AnchorRunResult,
+    ) -> Result<(), ApiError> {
+        let fee = inclusion_fee(self.base_fee, self.max_fee, row.attempts - 1);
+        let custodial = row
+            .source_account
+            .as_deref()
+            .is_some_and(|account| self.signer.can_sign(account));
+        let validity = if custodial {
+            DEFAULT_TX_VALIDITY_SECS
+        } else {
+            self.wallet_signing_secs
+        };
+        let valid_until = Utc::now() + chrono::Duration::seconds(validity);
+
+        let prepared = match contract_call(row) {
+            Ok(call) => self
+                .invoker
+                .prepare(&call, fee, valid_until.timestamp() as u64)
+                .await
+                .map(|prepared| (call, prepared)),
+            Err(e) => Err(SubmitError::Fatal(e.to_string())),
+        };
+        let (call, prepared) = match prepared {
+            Ok(prepared) => prepared,
+            Err(SubmitError::Retryable(e)) => {
+                warn!("Retrying {} ({}): {}", row.operation, row.id, e);
+                if self.schedule_retry(row, &e).await? == "failed" {
+                    result.failed += 1;
+                } else {
+                    result.retried += 1;
+                }
+                return Ok(());
+            }
+            Err(SubmitError::Fatal(e)) => {
+                warn!("Anchoring {} ({}) failed: {}", row.operation, row.id, e);
+                self.mark_failed(row.id, &e).await?;
+                result.failed += 1;
+                return Ok(());
+            }
+        };
+
+        if !custodial {
+            self.await_wallet_signature(row.id, &prepared, valid_until)
+                .await?;
+            result.awaiting_signature += 1;
+            return Ok(());
+        }
+
+        let envelope = match self
+            .signer
+            .sign(&call.source_account, &prepared.signature_payload)
+            .await
+            .map_err(|e| SubmitError::Fatal(e.to_string()))
+            .and_then(|sig| attach_signature(&prepared, &call.source_account, sig))
+        {
+            Ok(envelope) => envelope,
+            Err(e) => {
+                warn!("Anchoring {} ({}) failed: {}", row.operation, row.id, e);
+                self.mark_failed(row.id, &e.to_string()).await?;
+                result.failed += 1;
+                return Ok(());
+            }
+        };
+        // Record the hash before anything reaches the network
+        let row = sqlx::query_as::<_, AnchorTransaction>(
+            "UPDATE will_anchor_transactions \
+             SET tx_hash = $2, fee = $3, valid_until = $4, signed_envelope = $5, \
+                 updated_at = NOW() \
+             WHERE id = $1 RETURNING *",
+        )
+        .bind(row.id)
+        .bind(&prepared.tx_hash)
+        .bind(prepared.fee as i64)
+        .bind(valid_until)
+        .bind(&envelope)
+        .fetch_one(&self.db)
+        .await?;
+        self.send(&row, result).await
+    }
+
+    /// Park a prepared call until the source account's wallet signs it
+    async fn await_wallet_signature(
+        &self,
+        id: Uuid,
+        prepared: &PreparedTransaction,
+        valid_until: DateTime<Utc>,
+    ) -> Result<(), ApiError> {
+        sqlx::query(
+            "UPDATE will_anchor_transactions \
+             SET status = 'awaiting_signature', tx_hash = $2, fee = $3, valid_until = $4, \
+                 unsigned_envelope = $5, signature_payload = $6, last_error = NULL, \
+                 updated_at = NOW() \
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(&prepared.tx_hash)
+        .bind(prepared.fee as i64)
+        .bind(valid_until)
+        .bind(&prepared.envelope_xdr)
+        .bind(hex::encode(prepared.signature_payload))
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Send a row's signed envelope. Sending the same envelope again is
+    /// harmless, so a send that may not have reached the network goes back
+    /// to `signed` and is repeated rather than rebuilt.
+    async fn send(
+        &self,
+        row: &AnchorTransaction,
+        result: &mut AnchorRunResult,
+    ) -> Result<(), ApiError> {
+        let Some(envelope) = &row.signed_envelope else {
+            self.mark_failed(row.id, "Signed row has no envelope")
+                .await?;
+            result.failed += 1;
+            return Ok(());
+        };
+        match self.invoker.send_signed(envelope).await {
+            Ok(_) => {
+                sqlx::query(
+                    "UPDATE will_anchor_transactions \
+                     SET last_error = NULL, updated_at = NOW() WHERE id = $1",
+                )
+                .bind(row.id)
+                .execute(&self.db)
+                .await?;
+                result.submitted += 1;
+            }
+            Err(SubmitError::Retryable(e)) => {
+                warn!("Resending {} ({}): {}", row.operation, row.id, e);
+                sqlx::query(
+                    "UPDATE will_anchor_transactions \
+                     SET status = 'signed', last_error = $2, \
+                         next_attempt_at = NOW() + make_interval(secs => $3), updated_at = NOW() \
+                     WHERE id = $1",
+                )
+                .bind(row.id)
+                .bind(&e)
+                .bind(retry_delay_secs(row.attempts) as f64)
+                .execute(&self.db)
+                .await?;
+                result.retried += 1;
+            }
+            Err(SubmitError::Fatal(e)) => {
+                warn!("Anchoring {} ({}) failed: {}", row.operation, row.id, e);
+                self.mark_failed(row.id, &e).await?;
+                result.failed += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Confirm sent calls and rebuild those whose transaction expired without
+    /// reaching a ledger. Calls awaiting a wallet are checked too, since the
+    /// wallet may have sent the transaction itself.
+    pub async fn poll_confirmations(&self) -> Result<AnchorRunResult, ApiError> {
+        let sent = sqlx::query_as::<_, AnchorTransaction>(
+            "SELECT * FROM will_anchor_transactions \
+             WHERE status IN ('submitted', 'signed', 'awaiting_signature') ORDER BY seq",
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut result = AnchorRunResult::default();
+        let now = Utc::now();
+        for row in &sent {
+            let status = match &row.tx_hash {
+                Some(hash) => match self.invoker.status(hash).await {
+                    Ok(status) => status,
+                    Err(e) => {
+                        warn!("Could not check transaction {}: {}", hash, e);
+                        continue;
+                    }
+                },
+                // The worker stopped between claiming and preparing, so
+                // nothing was signed or sent
+                None => {
+                    let stale = row
+                        .submitted_at
+                        .is_some_and(|at| now - at > chrono::Duration::seconds(CLAIM_TIMEOUT_SECS));
+                    if stale {
+                        self.requeue(row, "Worker stopped before sending", &mut result)
+                            .await?;
+                    }
+                    continue;
+                }
+            };
+
+            match status {
+                TxStatus::Confirmed { ledger } => {
+                    sqlx::query(
+                        "UPDATE will_anchor_transactions \
+                         SET status = 'confirmed', confirmed_at = NOW(), ledger = $2, \
+                             last_error = NULL, updated_at = NOW() \
+                         WHERE id = $1",
+                    )
+                    .bind(row.id)
+                    .bind(ledger as i64)
+                    .execute(&self.db)
+                    .await?;
+                    result.confirmed += 1;
+                }
+                TxStatus::Failed(e) => self.requeue(row, &e, &mut result).await?,
+                TxStatus::NotFound if expired(row.valid_until, now) => {
+                    let e = if row.status == "awaiting_signature" {
+                        "Not signed in time; preparing a new transaction"
+                    } else {
+                        "Expired without reaching a ledger; resending with a higher fee"
+                    };
+                    self.requeue(row, e, &mut result).await?;
+                }
+                TxStatus::NotFound | TxStatus::Pending => {}
+            }
+        }
+        Ok(result)
+    }
+
+    async fn requeue(
+        &self,
+        row: &AnchorTransaction,
+        error: &str,
+        result: &mut AnchorRunResult,
+    ) -> Result<(), ApiError> {
+        if self.schedule_retry(row, error).await? == "failed" {
+            result.failed += 1;
+        } else {
+            result.retried += 1;
+        }
+        Ok(())
+    }
+
+    /// Requeue a call with backoff, or fail it once attempts run out.
+    async fn schedule_retry(
+        &self,
+        row: &AnchorTransaction,
+        error: &str,
+    ) -> Result<String, ApiError> {
+        if row.attempts >= MAX_ANCHOR_ATTEMPTS {
+            self.mark_failed(row.id, error).await?;
+            return Ok("failed".to_string());
+        }
+        sqlx::query(
+            "UPDATE will_anchor_transactions \
+             SET status = 'pending', last_error = $2, \
+                 next_attempt_at = NOW() + make_interval(secs => $3), updated_at = NOW() \
+             WHERE id = $1",
+        )
+        .bind(row.id)
+        .bind(error)
+        .bind(retry_delay_secs(row.attempts) as f64)
+        .execute(&self.db)
+        .await?;
+        Ok("pending".to_string())
+    }
+
+    async fn mark_failed(&self, id: Uuid, error: &str) -> Result<(), ApiError> {
+        sqlx::query(
+            "UPDATE will_anchor_transactions \
+             SET status = 'failed', last_error = $2, updated_at = NOW() WHERE id = $1",
+        )
+        .bind(id)
+        .bind(error)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    // ── Reconciliation ──────────────────────────────────────────────────────
+
+    /// Compare every document whose calls have all settled with the chain,
+    /// opening drift records for mismatches and resolving those that cleared.
+    pub async fn reconcile(
+        db: &PgPool,
+        reader: &dyn InheritanceContractReader,
+    ) -> Result<ReconciliationReport, ApiError> {
+        #[derive(sqlx::FromRow)]
+        struct DocumentRow {
+            id: Uuid,
+            plan_id: Uuid,
+            contract_version: i32,
+            will_hash: String,
+            status: String,
+            contract_plan_id: i64,
+            failed_operations: Option<String>,
+            version_anchored: bool,
+            revocation_anchored: bool,
+        }
+
+        let documents = sqlx::query_as::<_, DocumentRow>(
+            "SELECT d.id, d.plan_id, d.will_hash, d.status, p.contract_plan_id, \
+                    (SELECT COUNT(*) FROM will_documents w \
+                     WHERE w.plan_id = d.plan_id AND w.kind = 'will' \
+                       AND w.version <= d.version)::int AS contract_version, \
+                    (SELECT string_agg(a.operation, ', ' ORDER BY a.seq) \
+                     FROM will_anchor_transactions a \
+                     WHERE a.document_id = d.id AND a.status = 'failed') AS failed_operations, \
+                    EXISTS (SELECT 1 FROM will_anchor_transactions a \
+                            WHERE a.document_id = d.id AND a.status = 'confirmed' \
+                              AND a.operation = 'create_will_version') AS version_anchored, \
+                    EXISTS (SELECT 1 FROM will_anchor_transactions a \
+                            WHERE a.plan_id = d.plan_id AND a.status = 'confirmed' \
+                              AND a.operation = 'revoke_will') AS revocation_anchored \
+             FROM will_documents d JOIN plans p ON p.id = d.plan_id \
+             WHERE p.contract_plan_id IS NOT NULL \
+               AND EXISTS (SELECT 1 FROM will_anchor_transactions a WHERE a.document_id = d.id) \
+               AND NOT EXISTS (SELECT 1 FROM will_anchor_transactions a \
+                               WHERE a.document_id = d.id AND a.status NOT IN ('confirmed', 'failed'))",
+        )
+        .fetch_all(db)
+        .await?;
+
+        let mut report = ReconciliationReport::default();
+        for doc in &documents {
+            let plan_id = doc.contract_plan_id as u64;
+            let version = doc.contract_version as u32;
+
+            let on_chain_hash = match reader.get_will_version_hash(plan_id, version).await {
+                Ok(hash) => hash,
+                Err(e) => {
+                    warn!("Skipping reconciliation of document {}: {}", doc.id, e);
+                    continue;
+                }
+            };
+            let finalized = match reader.is_will_finalized(plan_id, version).await {
+                Ok(finalized) => finalized,
+                Err(e) => {
+                    warn!("Skipping reconciliation of document {}: {}", doc.id, e);
+                    continue;
+                }
+            };
+            let revoked = match reader.is_will_revoked(plan_id, version).await {
+                Ok(revoked) => revoked,
+                Err(e) => {
+                    warn!("Skipping reconciliation of document {}: {}", doc.id, e);
+                    continue;
+                }
+            };
+            report.documents_checked += 1;
+
+            let failed = doc
+                .failed_operations
+                .as_ref()
+                .map(|ops| ("all calls confirmed".to_string(), format!("failed: {ops}")));
+            let hash = match &on_chain_hash {
+                Some(hash) if *hash == doc.will_hash => None,
+                _ if !doc.version_anchored => None,
+                other => Some((
+                    doc.will_hash.clone(),
+                    other.clone().unwrap_or_else(|| "missing".to_string()),
+                )),
+            };
+            // Revoked versions stay finalized on-chain
+            let db_finalized = matches!(doc.status.as_str(), "finalized" | "revoked");
+            let finalization = (db_finalized != finalized)
+                .then(|| (db_finalized.to_string(), finalized.to_string()));
+            let db_revoked = doc.status == "revoked";
+            let revocation = (db_revoked != revoked && (revoked || doc.revocation_anchored))
+                .then(|| (db_revoked.to_string(), revoked.to_string()));
+
+            for (check, mismatch) in [
+                ("anchor_failed", failed),
+                ("version_hash", hash),
+                ("finalized", finalization),
+                ("revoked", revocation),
+            ] {
+                match mismatch {
+                    Some((expected, actual)) => {
+                        warn!(
+                            "Will anchoring drift on document {} ({}): expected {}, found {}",
+                            doc.id, check, expected, actual
+                        );
+                        sqlx::query(
+                            "INSERT INTO will_anchor_drift \
+                             (document_id, plan_id, check_name, expected, actual) \
+                             VALUES ($1, $2, $3, $4, $5) \
+                             ON CONFLICT (document_id, check_name) WHERE resolved_at IS NULL \
+                             DO UPDATE SET expected = EXCLUDED.expected, actual = EXCLUDED.actual, \
+                                           last_checked_at = NOW()",
+                        )
+                        .bind(doc.id)
+                        .bind(doc.plan_id)
+                        .bind(check)
+                        .bind(&expected)
+                        .bind(&actual)
+                        .execute(db)
+                        .await?;
+                        report.drift_detected += 1;
+                    }
+                    None => {
+                        let resolved = sqlx::query(
+                            "UPDATE will_anchor_drift \
+                             SET resolved_at = NOW(), last_checked_at = NOW() \
+                             WHERE document_id = $1 AND check_name = $2 AND resolved_at IS NULL",
+                        )
+                        .bind(doc.id)
+                        .bind(check)
+                        .execute(db)
+                        .await?;
+                        report.drift_resolved += resolved.rows_affected() as usize;
+                    }
+                }
+            }
+        }
+
+        if report.drift_detected > 0 {
+            warn!(
+                "Will anchoring reconciliation found {} mismatches",
+                report.drift_detected
+            );
+        }
+        Ok(report)
+    }
+}
+
+// ─── Unit Tests ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(byte: u8) -> String {
+        stellar_strkey::ed25519::PublicKey([byte; 32])
+            .to_string()
+            .as_str()
+            .to_owned()
+    }
+
+    fn row(operation: AnchorOperation, status: &str) -> AnchorTransaction {
+        let now = Utc::now();
+        AnchorTransaction {
+            id: Uuid::new_v4(),
+            seq: 1,
+            plan_id: Uuid::new_v4(),
+            document_id: None,
+            operation: operation.as_str().to_string(),
+            contract_plan_id: Some(7),
+            source_account: Some(account(1)),
+            will_hash: Some("ab".repeat(32)),
+            version: Some(2),
+            witness_account: Some(account(2)),
+            status: status.to_string(),
+            attempts: 0,
+            fee: None,
+            tx_hash: None,
+            last_error: None,
+            next_attempt_at: now,
+            submitted_at: None,
+            confirmed_at: None,
+            ledger: None,
+            valid_until: None,
+            unsigned_envelope: None,
+            signature_payload: None,
+            signed_envelope: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn fee_doubles_per_attempt_up_to_the_cap() {
+        assert_eq!(inclusion_fee(100, 1_000, 0), 100);
+        assert_eq!(inclusion_fee(100, 1_000, 1), 200);
+        assert_eq!(inclusion_fee(100, 1_000, 3), 800);
+        assert_eq!(inclusion_fee(100, 1_000, 4), 1_000);
+        assert_eq!(inclusion_fee(100, 1_000, 40), 1_000);
+        assert!(retry_delay_secs(MAX_ANCHOR_ATTEMPTS) > retry_delay_secs(1));
+    }
+
+    #[test]
+    fn builds_contract_arguments_per_operation() {
+        let call = contract_call(&row(AnchorOperation::StoreWillHash, "pending")).unwrap();
+        assert_eq!(call.function, "store_will_hash");
+        assert_eq!(call.args.len(), 3);
+        assert_eq!(call.args[1], ScVal::U64(7));
+        assert!(matches!(&call.args[2], ScVal::Bytes(b) if b.0.len() == 32));
+
+        let call = contract_call(&row(AnchorOperation::FinalizeWill, "pending")).unwrap();
+        assert_eq!(call.args[2], ScVal::U32(2));
+
+        let call = contract_call(&row(AnchorOperation::AddWitness, "pending")).unwrap();
+        assert_eq!(call.args[2], account_address(&account(2)).unwrap());
+
+        let call = contract_call(&row(AnchorOperation::SignAsWitness, "pending")).unwrap();
+        assert_eq!(call.args.len(), 2);
+
+        let mut bad = row(AnchorOperation::SignWill, "pending");
+        bad.will_hash = Some("abcd".to_string());
+        assert!(contract_call(&bad).is_err());
+        bad.will_hash = Some("ab".repeat(32));
+        bad.contract_plan_id = None;
+        assert!(contract_call(&bad).is_err());
+    }
+
+    #[test]
+    fn document_status_reflects_worst_transaction() {
+        let confirmed = row(AnchorOperation::CreateWillVersion, "confirmed");
+        assert_eq!(summarize(&[], &[], false), "not_anchored");
+        assert_eq!(
+            summarize(std::slice::from_ref(&confirmed), &[], false),
+            "anchored"
+        );
+        assert_eq!(
+            summarize(
+                &[
+                    confirmed.clone(),
+                    row(AnchorOperation::SignWill, "submitted")
+                ],
+                &[],
+                false
+            ),
+            "pending"
+        );
+        assert_eq!(
+            summarize(
+                &[confirmed.clone(), row(AnchorOperation::SignWill, "failed")],
+                &[],
+                true
+            ),
+            "failed"
+        );
+        assert_eq!(
+            summarize(
+                &[confirmed.clone(), row(AnchorOperation::SignWill, "pending")],
+                &[],
+                true
+            ),
+            "blocked"
+        );
+        assert_eq!(
+            summarize(
+                &[
+                    confirmed.clone(),
+                    row(AnchorOperation::SignWill, "awaiting_signature"),
+                    row(AnchorOperation::FinalizeWill, "pending")
+                ],
+                &[],
+                false
+            ),
+            "awaiting_signature"
+        );
+        let drift = AnchorDrift {
+            id: Uuid::new_v4(),
+            document_id: Uuid::new_v4(),
+            plan_id: Uuid::new_v4(),
+            check_name: "version_hash".to_string(),
+            expected: "a".to_string(),
+            actual: "b".to_string(),
+            detected_at: Utc::now(),
+            last_checked_at: Utc::now(),
+            resolved_at: None,
+        };
+        assert_eq!(summarize(&[confirmed], &[drift], false), "drift");
+    }
+
+    #[test]
+    fn transactions_are_rebuilt_only_after_they_expire() {
+        let now = Utc::now();
+        assert!(!expired(None, now));
+        assert!(!expired(Some(now), now));
+        assert!(!expired(Some(now - chrono::Duration::seconds(10)), now));
+        assert!(expired(
+            Some(now - chrono::Duration::seconds(EXPIRY_GRACE_SECS + 1)),
+            now
+        ));
+    }
+}
@@ -0,0 +1,343 @@
+//! # Will Input Assembly
+//!
+//! Builds the [`WillDocumentInput`] for a plan from server-side state: the
+//! `plans` row, the owner's account, `plan_beneficiaries`, and the plan as
+//! stored by the inheritance contract. Clients only choose presentation
+//! (template, locale, jurisdiction, execution rules), so a will can never
+//! name beneficiaries or allocations the chain does not enforce.
+
+use crate::api_error::ApiError;
+use crate::beneficiary_sync::{MismatchDetail, SyncStatus};
+use crate::contract_reader::{InheritanceContractReader, OnChainPlan};
+use crate::will_pdf::{BeneficiaryEntry, WillDocumentInput};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Template used when the client does not choose one
+pub const DEFAULT_TEMPLATE: &str = "formal";
+
+// ─── Types ────────────────────────────────────────────────────────────────────
+
+/// The choices a client may make when generating a will
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WillGenerationOptions {
+    pub template: Option<String>,
+    pub locale: Option<String>,
+    pub jurisdiction: Option<String>,
+    pub execution_rules: Option<String>,
+    /// Values for template-specific fields such as `state` and `county`
+    #[serde(default)]
+    pub template_fields: BTreeMap<String, String>,
+}
+
+/// A beneficiary recorded off-chain for a plan
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PlanBeneficiaryRow {
+    pub wallet_address: String,
+    pub allocation_percent: Decimal,
+    pub name: Option<String>,
+    pub relationship: Option<String>,
+}
+
+/// Outcome of comparing on-chain and off-chain allocations
+#[derive(Debug, Clone, Serialize)]
+pub struct AllocationCheck {
+    pub plan_id: Uuid,
+    pub contract_plan_id: u64,
+    pub status: SyncStatus,
+    pub mismatches: Vec<MismatchDetail>,
+}
+
+// ─── Allocation Check ─────────────────────────────────────────────────────────
+
+fn percent_to_bp(percent: Decimal) -> Option<u32> {
+    let bp = percent * Decimal::ONE_HUNDRED;
+    if bp.fract().is_zero() {
+        bp.trunc().to_string().parse().ok()
+    } else {
+        None
+    }
+}
+
+fn mismatch(field: &str, contract: impl ToString, document: impl ToString) -> MismatchDetail {
+    MismatchDetail {
+        wallet_address: "N/A".to_string(),
+        field: field.to_string(),
+        contract_value: contract.to_string(),
+        document_value: document.to_string(),
+    }
+}
+
+/// Compare allocations in basis points. The contract stores beneficiaries
+/// with hashed identities, so allocations are compared as sorted multisets.
+pub fn check_allocations(
+    plan_id: Uuid,
+    on_chain: &OnChainPlan,
+    off_chain: &[PlanBeneficiaryRow],
+) -> AllocationCheck {
+    let mut mismatches = Vec::new();
+
+    let mut chain_bp: Vec<u32> = on_chain
+        .beneficiaries
+        .iter()
+        .map(|b| b.allocation_bp)
+        .collect();
+    chain_bp.sort_unstable();
+
+    let mut db_bp = Vec::with_capacity(off_chain.len());
+    for b in off_chain {
+        match percent_to_bp(b.allocation_percent) {
+            Some(bp) => db_bp.push(bp),
+            None => mismatches.push(MismatchDetail {
+                wallet_address: b.wallet_address.clone(),
+                field: "allocation_percent".to_string(),
+                contract_value: "whole basis points".to_string(),
+                document_value: b.allocation_percent.to_string(),
+            }),
+        }
+    }
+    db_bp.sort_unstable();
+
+    if chain_bp.len() != off_chain.len() {
+        mismatches.push(mismatch(
+            "beneficiary_count",
+            chain_bp.len(),
+            off_chain.len(),
+        ));
+    } else if mismatches.is_empty() && chain_bp != db_bp {
+        let join = |v: &[u32]| v.iter().map(u32::to_string).collect::<Vec<_>>().join(",");
+        mismatches.push(mismatch("allocation_bp", join(&chain_bp), join(&db_bp)));
+    }
+
+    let chain_total: u32 = chain_bp.iter().sum();
+    if chain_total != on_chain.total_allocation_bp {
+        mismatches.push(mismatch(
+            "total_allocation_bp",
+            on_chain.total_allocation_bp,
+            chain_total,
+        ));
+    }
+
+    AllocationCheck {
+        plan_id,
+        contract_plan_id: on_chain.plan_id,
+        status: if mismatches.is_empty() {
+            SyncStatus::Matched
+        } else {
+            SyncStatus::Mismatched
+        },
+        mismatches,
+    }
+}
+
+// ─── Assembler ────────────────────────────────────────────────────────────────
+
+pub struct WillInputAssembler;
+
+impl WillInputAssembler {
+    /// Record the legal name printed on the user's wills
+    pub async fn set_legal_name(
+        db: &PgPool,
+        user_id: Uuid,
+        legal_name: &str,
+    ) -> Result<String, ApiError> {
+        let legal_name = legal_name.trim();
+        if legal_name.is_empty() || legal_name.chars().count() > 255 {
+            return Err(ApiError::BadRequest(
+                "legal_name must be between 1 and 255 characters".to_string(),
+            ));
+        }
+        let updated = sqlx::query("UPDATE users SET legal_name = $1 WHERE id = $2")
+            .bind(legal_name)
+            .bind(user_id)
+            .execute(db)
+            .await?;
+        if updated.rows_affected() == 0 {
+            return Err(ApiError::NotFound("User not found".to_string()));
+        }
+        Ok(legal_name.to_string())
+    }
+
+    /// Assemble the will input for a plan owned by `user_id`, refusing when
+    /// the plan's on-chain and off-chain state disagree.
+    pub async fn assemble(
+        db: &PgPool,
+        reader: &dyn InheritanceContractReader,
+        user_id: Uuid,
+        plan_id: Uuid,
+        options: WillGenerationOptions,
+    ) -> Result<WillDocumentInput, ApiError> {
+        #[derive(sqlx::FromRow)]
+        struct PlanRow {
+            contract_plan_id: Option<i64>,
+            legal_name: Option<String>,
+            wallet_address: Option<String>,
+        }
+
+        let plan = sqlx::query_as::<_, PlanRow>(
+            "SELECT p.contract_plan_id, u.legal_name, u.wallet_address \
+             FROM plans p JOIN users u ON u.id = p.user_id \
+             WHERE p.id = $1 AND p.user_id = $2",
+        )
+        .bind(plan_id)
+        .bind(user_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Plan {plan_id} not found")))?;
+
+        let contract_plan_id = plan
+            .contract_plan_id
+            .and_then(|id| u64::try_from(id).ok())
+            .ok_or_else(|| {
+                ApiError::BadRequest(format!("Plan {plan_id} has not been created on-chain"))
+            })?;
+        let owner_name = plan
+            .legal_name
+            .filter(|n| !n.trim().is_empty())
+            .ok_or_else(|| {
+                ApiError::BadRequest("Set your legal name before generating a will".to_string())
+            })?;
+        let owner_wallet = plan.wallet_address.ok_or_else(|| {
+            ApiError::BadRequest("Link a wallet before generating a will".to_string())
+        })?;
+
+        let on_chain = reader.get_plan(contract_plan_id).await?.ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "On-chain plan {contract_plan_id} not found for plan {plan_id}"
+            ))
+        })?;
+        if on_chain.owner != owner_wallet {
+            return Err(ApiError::Forbidden(format!(
+                "On-chain plan {contract_plan_id} is owned by a different wallet"
+            )));
+        }
+
+        let beneficiaries = sqlx::query_as::<_, PlanBeneficiaryRow>(
+            "SELECT wallet_address, allocation_percent, name, relationship \
+             FROM plan_beneficiaries WHERE plan_id = $1 \
+             ORDER BY allocation_percent DESC, wallet_address",
+        )
+        .bind(plan_id)
+        .fetch_all(db)
+        .await?;
+
+        let check = check_allocations(plan_id, &on_chain, &beneficiaries);
+        if check.status == SyncStatus::Mismatched {
+            let details: Vec<String> = check
+                .mismatches
+                .iter()
+                .map(|m| {
+                    format!(
+                        "{}: on-chain {}, off-chain {}",
+                        m.field, m.contract_value, m.document_value
+                    )
+                })
+                .collect();
+            return Err(ApiError::BadRequest(format!(
+                "On-chain and off-chain allocations disagree for plan {plan_id}: {}",
+                details.join("; ")
+            )));
+        }
+
+        let will_hash_reference = reader.get_will_hash(contract_plan_id).await?;
+
+        Ok(WillDocumentInput {
+            plan_id,
+            owner_name,
+            owner_wallet,
+            vault_id: contract_plan_id.to_string(),
+            beneficiaries: beneficiaries
+                .into_iter()
+                .map(|b| BeneficiaryEntry {
+                    name: b.name.unwrap_or_else(|| b.wallet_address.clone()),
+                    wallet_address: b.wallet_address,
+                    allocation_percent: b.allocation_percent,
+                    relationship: b.relationship,
+                })
+                .collect(),
+            execution_rules: options.execution_rules,
+            template: options
+                .template
+                .unwrap_or_else(|| DEFAULT_TEMPLATE.to_string()),
+            locale: options.locale,
+            jurisdiction: options.jurisdiction,
+            will_hash_reference,
+            template_fields: options.template_fields,
+        })
+    }
+}
+
+// ─── Unit Tests ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract_reader::OnChainBeneficiary;
+    use rust_decimal_macros::dec;
+
+    fn on_chain(bps: &[u32]) -> OnChainPlan {
+        OnChainPlan {
+            plan_id: 4,
+            plan_name: "Family".to_string(),
+            owner: "GOWNER".to_string(),
+            total_amount: 1000,
+            total_allocation_bp: bps.iter().sum(),
+            beneficiaries: bps
+                .iter()
+                .map(|&allocation_bp| OnChainBeneficiary { allocation_bp })
+                .collect(),
+            is_active: true,
+        }
+    }
+
+    fn row(wallet: &str, percent: Decimal) -> PlanBeneficiaryRow {
+        PlanBeneficiaryRow {
+            wallet_address: wallet.to_string(),
+            allocation_percent: percent,
+            name: None,
+            relationship: None,
+        }
+    }
+
+    #[test]
+    fn matching_allocations_in_any_order_pass() {
+        let check = check_allocations(
+            Uuid::new_v4(),
+            &on_chain(&[2500, 7500]),
+            &[row("GA", dec!(75)), row("GB", dec!(25.0000))],
+        );
+        assert_eq!(check.status, SyncStatus::Matched);
+        assert!(check.mismatches.is_empty());
+    }
+
+    #[test]
+    fn differing_allocations_and_counts_are_reported() {
+        let plan_id = Uuid::new_v4();
+        let check = check_allocations(
+            plan_id,
+            &on_chain(&[5000, 5000]),
+            &[row("GA", dec!(60)), row("GB", dec!(40))],
+        );
+        assert_eq!(check.status, SyncStatus::Mismatched);
+        assert_eq!(check.mismatches[0].field, "allocation_bp");
+        assert_eq!(check.mismatches[0].contract_value, "5000,5000");
+
+        let check = check_allocations(plan_id, &on_chain(&[10000]), &[]);
+        assert_eq!(check.mismatches[0].field, "beneficiary_count");
+
+        let check = check_allocations(plan_id, &on_chain(&[10000]), &[row("GA", dec!(99.99995))]);
+        assert_eq!(check.mismatches[0].field, "allocation_percent");
+    }
+
+    #[test]
+    fn inconsistent_on_chain_total_is_reported() {
+        let mut plan = on_chain(&[10000]);
+        plan.total_allocation_bp = 9000;
+        let check = check_allocations(Uuid::new_v4(), &plan, &[row("GA", dec!(100))]);
+        assert_eq!(check.status, SyncStatus::Mismatched);
+        assert_eq!(check.mismatches[0].field, "total_allocation_bp");
+    }
+}
@@ -0,0 +1,980 @@
+//! # Offline Verification Bundle
+//!
+//! Exports everything needed to check a will without contacting InheritX:
+//! the rendered text and PDF, each testator's canonical signing message and
+//! signature, witness signatures, the version history and the on-chain
+//! anchoring reference. [`verify_bundle`] re-checks it all locally;
+//! [`verify_bundle_on_chain`] also reads the contract, since the bundle's own
+//! anchor records prove nothing by themselves. The `verify_will_bundle`
+//! binary runs the latter.
+//!
+//! A bundle is valid only when every check passes: the will must carry at
+//! least one verified signature by the plan owner's wallet, every recorded
+//! signature must verify, and the contract must hold the will hash.
+
+use crate::api_error::ApiError;
+use crate::contract_reader::InheritanceContractReader;
+use crate::will_signature::WillSignatureService;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::fmt;
+use uuid::Uuid;
+
+/// Identifies the file format so verifiers can reject anything else
+pub const BUNDLE_FORMAT: &str = "inheritx-will-bundle";
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Prefix of the canonical testator signing message
+const SIGNING_MESSAGE_PREFIX: &str = "INHERITX_WILL_SIGN";
+
+// ─── Types ────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WillBundle {
+    pub format: String,
+    pub format_version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub document: BundleDocument,
+    pub signatures: Vec<BundleSignature>,
+    pub witnesses: Vec<BundleWitness>,
+    pub versions: Vec<BundleVersion>,
+    pub on_chain: BundleOnChainReference,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleDocument {
+    pub document_id: Uuid,
+    pub plan_id: Uuid,
+    pub version: u32,
+    pub status: String,
+    pub template: String,
+    pub locale: Option<String>,
+    pub owner_name: Option<String>,
+    /// Wallet of the plan owner, whose signature makes the will binding
+    #[serde(default)]
+    pub owner_wallet: Option<String>,
+    pub generated_at: DateTime<Utc>,
+    /// Hex SHA-256 of `rendered_content`
+    pub will_hash: String,
+    pub rendered_content: Option<String>,
+    pub filename: String,
+    pub pdf_base64: String,
+    /// Hex SHA-256 of the decoded PDF
+    pub pdf_sha256: String,
+}
+
+/// A testator's signature over the canonical signing message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleSignature {
+    pub wallet_address: String,
+    /// `INHERITX_WILL_SIGN:{will_hash}:{vault_id}:{nonce}`
+    pub message: Option<String>,
+    /// Hex SHA-256 of `message`
+    pub message_hash: String,
+    pub signature_hex: String,
+    pub signed_at: DateTime<Utc>,
+}
+
+/// A witness signature over the will hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleWitness {
+    /// Key the witness signed with: their wallet or a device key
+    pub wallet_address: Option<String>,
+    pub signature_hex: String,
+    pub signed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleVersion {
+    pub version: u32,
+    pub will_hash: String,
+    pub status: String,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Where the will hash was recorded on-chain, for independent lookup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleOnChainReference {
+    pub network_passphrase: Option<String>,
+    pub contract_id: Option<String>,
+    pub contract_plan_id: Option<i64>,
+    /// The contract's number for this version, which counts wills but not
+    /// codicils. Bundles without it use the document version.
+    #[serde(default)]
+    pub contract_version: Option<u32>,
+    pub anchors: Vec<BundleAnchor>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleAnchor {
+    pub operation: String,
+    pub will_hash: Option<String>,
+    pub version: Option<i32>,
+    pub tx_hash: Option<String>,
+    pub ledger: Option<i64>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckOutcome {
+    Passed,
+    Failed,
+    /// Not checked, so the bundle cannot be reported valid
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleCheck {
+    pub name: String,
+    pub outcome: CheckOutcome,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BundleVerdict {
+    /// Every check passed, including against the chain
+    Valid,
+    /// Every offline check passed; the chain was not consulted
+    ValidOffline,
+    Invalid,
+    /// Nothing failed but checks other than the chain were skipped
+    Incomplete,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleReport {
+    pub document_id: Uuid,
+    pub version: u32,
+    pub will_hash: String,
+    /// Every check passed
+    pub valid: bool,
+    pub verdict: BundleVerdict,
+    pub checks: Vec<BundleCheck>,
+    /// Lookups a verifier can make against the chain to finish the check
+    pub on_chain_lookups: Vec<String>,
+}
+
+// ─── Verification ─────────────────────────────────────────────────────────────
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+fn check(name: impl Into<String>, passed: bool, detail: impl Into<String>) -> BundleCheck {
+    BundleCheck {
+        name: name.into(),
+        outcome: if passed {
+            CheckOutcome::Passed
+        } else {
+            CheckOutcome::Failed
+        },
+        detail: detail.into(),
+    }
+}
+
+fn skipped(name: impl Into<String>, detail: impl Into<String>) -> BundleCheck {
+    BundleCheck {
+        name: name.into(),
+        outcome: CheckOutcome::Skipped,
+        detail: detail.into(),
+    }
+}
+
+fn verdict(checks: &[BundleCheck]) -> BundleVerdict {
+    if checks.iter().any(|c| c.outcome == CheckOutcome::Failed) {
+        BundleVerdict::Invalid
+    } else if checks.iter().all(|c| c.outcome == CheckOutcome::Passed) {
+        BundleVerdict::Valid
+    } else if checks
+        .iter()
+        .all(|c| c.outcome == CheckOutcome::Passed || c.name == "on_chain_anchor")
+    {
+        BundleVerdict::ValidOffline
+    } else {
+        BundleVerdict::Incomplete
+    }
+}
+
+/// Whether two wallet addresses name the same Ed25519 key
+fn same_key(a: &str, b: &str) -> bool {
+    match (
+        WillSignatureService::decode_public_key(a),
+        WillSignatureService::decode_public_key(b),
+    ) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Re-check a bundle's hashes and signatures without any network access.
+/// The on-chain anchor is left unchecked, so the report is never valid; use
+/// [`verify_bundle_on_chain`] for a complete verification.
+pub fn verify_bundle(bundle: &WillBundle) -> BundleReport {
+    let doc = &bundle.document;
+    let mut checks = Vec::new();
+
+    checks.push(check(
+        "format",
+        bundle.format == BUNDLE_FORMAT && bundle.format_version <= BUNDLE_FORMAT_VERSION,
+        format!("{} v{}", bundle.format, bundle.format_version),
+    ));
+
+    // The will hash commits to the rendered text
+    checks.push(match &doc.rendered_content {
+        Some(content) => {
+            let computed = sha256_hex(content.as_bytes());
+            check(
+                "content_hash",
+                computed == doc.will_hash,
+                format!("SHA-256 of will text is {computed}"),
+            )
+        }
+        None => check(
+            "content_hash",
+            false,
+            "bundle has no will text, so the will hash cannot be recomputed",
+        ),
+    });
+
+    match BASE64.decode(&doc.pdf_base64) {
+        Ok(pdf) => {
+            let computed = sha256_hex(&pdf);
+            checks.push(check(
+                "pdf_hash",
+                computed == doc.pdf_sha256,
+                format!("SHA-256 of PDF is {computed}"),
+            ));
+            let marker = format!("<inheritx:WillHash>{}</inheritx:WillHash>", doc.will_hash);
+            checks.push(check(
+                "pdf_embeds_will_hash",
+                pdf.windows(marker.len()).any(|w| w == marker.as_bytes()),
+                "PDF metadata names the will hash",
+            ));
+        }
+        Err(e) => checks.push(check(
+            "pdf_hash",
+            false,
+            format!("PDF is not valid base64: {e}"),
+        )),
+    }
+
+    let signing_prefix = format!("{SIGNING_MESSAGE_PREFIX}:{}:", doc.will_hash);
+    let mut owner_signed = false;
+    for (i, sig) in bundle.signatures.iter().enumerate() {
+        let name = format!("testator_signature[{i}] {}", sig.wallet_address);
+        let Some(message) = &sig.message else {
+            checks.push(check(name, false, "signing message was not retained"));
+            continue;
+        };
+        if !message.starts_with(&signing_prefix) {
+            checks.push(check(name, false, "message does not name this will hash"));
+        } else if sha256_hex(message.as_bytes()) != sig.message_hash {
+            checks.push(check(
+                name,
+                false,
+                "message does not match its recorded hash",
+            ));
+        } else {
+            let verified = WillSignatureService::verify_signature(
+                &sig.wallet_address,
+                message,
+                &sig.signature_hex,
+            )
+            .is_ok();
+            owner_signed |= verified
+                && doc
+                    .owner_wallet
+                    .as_deref()
+                    .is_some_and(|owner| same_key(owner, &sig.wallet_address));
+            checks.push(check(
+                name,
+                verified,
+                "Ed25519 signature over the signing message",
+            ));
+        }
+    }
+    checks.push(match &doc.owner_wallet {
+        Some(owner) => check(
+            "signed_by_owner",
+            owner_signed,
+            format!("verified signature by the plan owner's wallet {owner}"),
+        ),
+        None => check(
+            "signed_by_owner",
+            false,
+            "bundle does not name the plan owner's wallet",
+        ),
+    });
+
+    for (i, witness) in bundle.witnesses.iter().enumerate() {
+        let Some(wallet) = &witness.wallet_address else {
+            checks.push(check(
+                format!("witness_signature[{i}]"),
+                false,
+                "witness signature has no recorded key",
+            ));
+            continue;
+        };
+        let verified =
+            WillSignatureService::verify_signature(wallet, &doc.will_hash, &witness.signature_hex)
+                .is_ok();
+        checks.push(check(
+            format!("witness_signature[{i}] {wallet}"),
+            verified,
+            "Ed25519 signature over the will hash",
+        ));
+    }
+
+    let listed = bundle.versions.iter().find(|v| v.version == doc.version);
+    checks.push(check(
+        "version_history",
+        listed.is_some_and(|v| v.will_hash == doc.will_hash),
+        format!(
+            "{} versions; this is version {}",
+            bundle.versions.len(),
+            doc.version
+        ),
+    ));
+
+    // The bundle's anchor records are claims; only the chain can back them
+    let mismatched = bundle
+        .on_chain
+        .anchors
+        .iter()
+        .filter(|a| a.will_hash.as_ref().is_some_and(|h| *h != doc.will_hash))
+        .count();
+    checks.push(if mismatched > 0 {
+        check(
+            "on_chain_anchor",
+            false,
+            format!("{mismatched} recorded anchors name a different hash"),
+        )
+    } else {
+        skipped("on_chain_anchor", "not checked against the chain")
+    });
+
+    let mut on_chain_lookups = Vec::new();
+    if let (Some(contract), Some(plan)) = (
+        &bundle.on_chain.contract_id,
+        bundle.on_chain.contract_plan_id,
+    ) {
+        let version = contract_version(bundle);
+        on_chain_lookups.push(format!(
+            "{contract}.get_will_version({plan}, {version}) should return will_hash {}",
+            doc.will_hash
+        ));
+        if doc.status == "finalized" {
+            on_chain_lookups.push(format!(
+                "{contract}.is_will_finalized({plan}, {version}) should return true"
+            ));
+        }
+    }
+    for anchor in &bundle.on_chain.anchors {
+        if let Some(tx) = &anchor.tx_hash {
+            on_chain_lookups.push(format!("transaction {tx} ({})", anchor.operation));
+        }
+    }
+
+    BundleReport {
+        document_id: doc.document_id,
+        version: doc.version,
+        will_hash: doc.will_hash.clone(),
+        valid: checks.iter().all(|c| c.outcome == CheckOutcome::Passed),
+        verdict: verdict(&checks),
+        checks,
+        on_chain_lookups,
+    }
+}
+
+/// The version number the contract holds the bundled document under
+fn contract_version(bundle: &WillBundle) -> u32 {
+    bundle
+        .on_chain
+        .contract_version
+        .unwrap_or(bundle.document.version)
+}
+
+/// [`verify_bundle`], then confirm through `reader` that the contract's plan
+/// belongs to the owner's wallet and holds this version's hash and status.
+/// `reader` must point at the contract the verifier trusts, not one named by
+/// the bundle.
+pub async fn verify_bundle_on_chain(
+    bundle: &WillBundle,
+    reader: &dyn InheritanceContractReader,
+) -> BundleReport {
+    let mut report = verify_bundle(bundle);
+    let doc = &bundle.document;
+    let version = contract_version(bundle);
+    report.checks.retain(|c| c.name != "on_chain_anchor");
+
+    let Some(plan_id) = bundle
+        .on_chain
+        .contract_plan_id
+        .and_then(|id| u64::try_from(id).ok())
+    else {
+        report.checks.push(check(
+            "on_chain_anchor",
+            false,
+            "bundle names no on-chain plan",
+        ));
+        report.valid = false;
+        report.verdict = BundleVerdict::Invalid;
+        return report;
+    };
+
+    let lookup_failed = |name: &str, e: ApiError| check(name, false, format!("lookup failed: {e}"));
+    report.checks.push(match reader.get_plan(plan_id).await {
+        Ok(Some(plan)) => check(
+            "on_chain_owner",
+            doc.owner_wallet
+                .as_deref()
+                .is_some_and(|owner| same_key(owner, &plan.owner)),
+            format!("plan {plan_id} is owned by {}", plan.owner),
+        ),
+        Ok(None) => check("on_chain_owner", false, format!("plan {plan_id} not found")),
+        Err(e) => lookup_failed("on_chain_owner", e),
+    });
+    report
+        .checks
+        .push(match reader.get_will_version_hash(plan_id, version).await {
+            Ok(hash) => check(
+                "on_chain_anchor",
+                hash.as_deref() == Some(doc.will_hash.as_str()),
+                format!(
+                    "version {version} is anchored as {}",
+                    hash.as_deref().unwrap_or("nothing")
+                ),
+            ),
+            Err(e) => lookup_failed("on_chain_anchor", e),
+        });
+    // Revoked versions stay finalized on-chain
+    let finalized = matches!(doc.status.as_str(), "finalized" | "revoked");
+    report
+        .checks
+        .push(match reader.is_will_finalized(plan_id, version).await {
+            Ok(on_chain) => check(
+                "on_chain_finalized",
+                on_chain == finalized,
+                format!("finalized on-chain: {on_chain}"),
+            ),
+            Err(e) => lookup_failed("on_chain_finalized", e),
+        });
+    report
+        .checks
+        .push(match reader.is_will_revoked(plan_id, version).await {
+            Ok(on_chain) => check(
+                "on_chain_revoked",
+                on_chain == (doc.status == "revoked"),
+                format!("revoked on-chain: {on_chain}"),
+            ),
+            Err(e) => lookup_failed("on_chain_revoked", e),
+        });
+
+    report.valid = report
+        .checks
+        .iter()
+        .all(|c| c.outcome == CheckOutcome::Passed);
+    report.verdict = verdict(&report.checks);
+    report
+}
+
+impl fmt::Display for BundleReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Will document {} (version {})",
+            self.document_id, self.version
+        )?;
+        writeln!(f, "Will hash: {}", self.will_hash)?;
+        writeln!(f)?;
+        for c in &self.checks {
+            let mark = match c.outcome {
+                CheckOutcome::Passed => "PASS",
+                CheckOutcome::Failed => "FAIL",
+                CheckOutcome::Skipped => "SKIP",
+            };
+            writeln!(f, "  [{mark}] {}: {}", c.name, c.detail)?;
+        }
+        if !self.on_chain_lookups.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "To confirm against the chain:")?;
+            for lookup in &self.on_chain_lookups {
+                writeln!(f, "  - {lookup}")?;
+            }
+        }
+        writeln!(f)?;
+        write!(
+            f,
+            "Result: {}",
+            match self.verdict {
+                BundleVerdict::Valid => "VALID",
+                BundleVerdict::ValidOffline => "VALID OFFLINE (not checked against the chain)",
+                BundleVerdict::Invalid => "INVALID",
+                BundleVerdict::Incomplete => "INCOMPLETE (some checks were skipped)",
+            }
+        )
+    }
+}
+
+// ─── Export ───────────────────────────────────────────────────────────────────
+
+pub struct WillBundleService;
+
+impl WillBundleService {
+    /// Assemble the bundle for a document owned by `user_id`
+    pub async fn export(
+        db: &PgPool,
+        document_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<WillBundle, ApiError> {
+        #[derive(sqlx::FromRow)]
+        struct DocumentRow {
+            plan_id: Uuid,
+            version: i32,
+            status: String,
+            template: String,
+            locale: Option<String>,
+            owner_name: Option<String>,
+            owner_wallet: Option<String>,
+            generated_at: DateTime<Utc>,
+            will_hash: String,
+            rendered_content: Option<String>,
+            filename: String,
+            pdf_base64: String,
+            contract_plan_id: Option<i64>,
+            contract_version: i32,
+        }
+
+        let row = sqlx::query_as::<_, DocumentRow>(
+            "SELECT d.plan_id, d.version, d.status, d.template, d.locale, d.owner_name, \
+                    u.wallet_address AS owner_wallet, d.generated_at, d.will_hash, \
+                    d.rendered_content, d.filename, d.pdf_base64, p.contract_plan_id, \
+                    (SELECT COUNT(*) FROM will_documents w \
+                     WHERE w.plan_id = d.plan_id AND w.kind = 'will' \
+                       AND w.version <= d.version)::int AS contract_version \
+             FROM will_documents d JOIN plans p ON p.id = d.plan_id \
+             JOIN users u ON u.id = p.user_id \
+             WHERE d.id = $1 AND d.user_id = $2",
+        )
+        .bind(document_id)
+        .bind(user_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Will document {document_id} not found")))?;
+
+        let pdf = BASE64
+            .decode(&row.pdf_base64)
+            .map_err(|e| ApiError::Internal(anyhow::anyhow!("Failed to decode PDF: {}", e)))?;
+
+        // The canonical message lives on the challenge the signature consumed
+        let signatures = sqlx::query_as::<_, (String, Option<String>, String, String, DateTime<Utc>)>(
+            "SELECT s.wallet_address, c.message, s.document_hash, s.signature_hex, s.signed_at \
+             FROM will_signatures s \
+             LEFT JOIN LATERAL ( \
+                 SELECT message FROM will_signing_challenges \
+                 WHERE document_id = s.document_id AND message_hash = s.document_hash \
+                 LIMIT 1) c ON true \
+             WHERE s.document_id = $1 ORDER BY s.signed_at",
+        )
+        .bind(document_id)
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .map(
+            |(wallet_address, message, message_hash, signature_hex, signed_at)| BundleSignature {
+                wallet_address,
+                message,
+                message_hash,
+                signature_hex,
+                signed_at,
+            },
+        )
+        .collect();
+
+        let witnesses = sqlx::query_as::<_, (Option<String>, String, Option<DateTime<Utc>>)>(
+            "SELECT COALESCE(signer_public_key, wallet_address), signature_hex, signed_at \
+             FROM will_witnesses \
+             WHERE document_id = $1 AND status = 'signed' AND signature_hex IS NOT NULL \
+             ORDER BY signed_at",
+        )
+        .bind(document_id)
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .map(|(wallet_address, signature_hex, signed_at)| BundleWitness {
+            wallet_address,
+            signature_hex,
+            signed_at,
+        })
+        .collect();
+
+        let versions = sqlx::query_as::<_, (i32, String, String, DateTime<Utc>)>(
+            "SELECT version, will_hash, status, generated_at FROM will_documents \
+             WHERE plan_id = $1 ORDER BY version",
+        )
+        .bind(row.plan_id)
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .map(|(version, will_hash, status, generated_at)| BundleVersion {
+            version: version as u32,
+            will_hash,
+            status,
+            generated_at,
+        })
+        .collect();
+
+        let anchors = sqlx::query_as::<
+            _,
+            (
+                String,
+                Option<String>,
+                Option<i32>,
+                Option<String>,
+                Option<i64>,
+                Option<DateTime<Utc>>,
+            ),
+        >(
+            "SELECT operation, will_hash, version, tx_hash, ledger, confirmed_at \
+             FROM will_anchor_transactions \
+             WHERE document_id = $1 AND status = 'confirmed' ORDER BY seq",
+        )
+        .bind(document_id)
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .map(
+            |(operation, will_hash, version, tx_hash, ledger, confirmed_at)| BundleAnchor {
+                operation,
+                will_hash,
+                version,
+                tx_hash,
+                ledger,
+                confirmed_at,
+            },
+        )
+        .collect();
+
+        Ok(WillBundle {
+            format: BUNDLE_FORMAT.to_string(),
+            format_version: BUNDLE_FORMAT_VERSION,
+            exported_at: Utc::now(),
+            document: BundleDocument {
+                document_id,
+                plan_id: row.plan_id,
+                version: row.version as u32,
+                status: row.status,
+                template: row.template,
+                locale: row.locale,
+                owner_name: row.owner_name,
+                owner_wallet: row.owner_wallet,
+                generated_at: row.generated_at,
+                will_hash: row.will_hash,
+                rendered_content: row.rendered_content,
+                filename: row.filename,
+                pdf_base64: row.pdf_base64,
+                pdf_sha256: sha256_hex(&pdf),
+            },
+            signatures,
+            witnesses,
+            versions,
+            on_chain: BundleOnChainReference {
+                network_passphrase: std::env::var("STELLAR_NETWORK_PASSPHRASE").ok(),
+                contract_id: std::env::var("INHERITANCE_CONTRACT_ID").ok(),
+                contract_plan_id: row.contract_plan_id,
+                contract_version: Some(row.contract_version as u32),
+                anchors,
+            },
+        })
+    }
+}
+
+// ─── Unit Tests ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract_reader::{OnChainInheritanceTrigger, OnChainLegacyMessage, OnChainPlan};
+    use async_trait::async_trait;
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    fn keypair() -> (Ed25519KeyPair, String) {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let wallet = hex::encode(pair.public_key().as_ref());
+        (pair, wallet)
+    }
+
+    fn signed_bundle() -> (WillBundle, Ed25519KeyPair) {
+        let content = "LAST WILL AND TESTAMENT\nI leave everything to Bob.";
+        let will_hash = sha256_hex(content.as_bytes());
+        let pdf = format!("%PDF-1.4 <inheritx:WillHash>{will_hash}</inheritx:WillHash> %%EOF");
+        let (owner, owner_wallet) = keypair();
+        let (witness, witness_wallet) = keypair();
+        let message = WillSignatureService::build_message(&will_hash, "7", "nonce-1");
+        let now = Utc::now();
+
+        let bundle = WillBundle {
+            format: BUNDLE_FORMAT.to_string(),
+            format_version: BUNDLE_FORMAT_VERSION,
+            exported_at: now,
+            document: BundleDocument {
+                document_id: Uuid::new_v4(),
+                plan_id: Uuid::new_v4(),
+                version: 2,
+                status: "finalized".to_string(),
+                template: "formal".to_string(),
+                locale: Some("en".to_string()),
+                owner_name: Some("Alice".to_string()),
+                owner_wallet: Some(owner_wallet.clone()),
+                generated_at: now,
+                will_hash: will_hash.clone(),
+                rendered_content: Some(content.to_string()),
+                filename: "will.pdf".to_string(),
+                pdf_base64: BASE64.encode(pdf.as_bytes()),
+                pdf_sha256: sha256_hex(pdf.as_bytes()),
+            },
+            signatures: vec![BundleSignature {
+                wallet_address: owner_wallet,
+                message_hash: sha256_hex(message.as_bytes()),
+                signature_hex: hex::encode(owner.sign(message.as_bytes())),
+                message: Some(message),
+                signed_at: now,
+            }],
+            witnesses: vec![BundleWitness {
+                wallet_address: Some(witness_wallet),
+                signature_hex: hex::encode(witness.sign(will_hash.as_bytes())),
+                signed_at: Some(now),
+            }],
+            versions: vec![
+                BundleVersion {
+                    version: 1,
+                    will_hash: "00".repeat(32),
+                    status: "draft".to_string(),
+                    generated_at: now,
+                },
+                BundleVersion {
+                    version: 2,
+                    will_hash: will_hash.clone(),
+                    status: "finalized".to_string(),
+                    generated_at: now,
+                },
+            ],
+            on_chain: BundleOnChainReference {
+                network_passphrase: None,
+                contract_id: Some("CCONTRACT".to_string()),
+                contract_plan_id: Some(7),
+                contract_version: Some(2),
+                anchors: vec![BundleAnchor {
+                    operation: "create_will_version".to_string(),
+                    will_hash: Some(will_hash),
+                    version: Some(2),
+                    tx_hash: Some("ff".repeat(32)),
+                    ledger: Some(100),
+                    confirmed_at: Some(now),
+                }],
+            },
+        };
+        (bundle, owner)
+    }
+
+    /// Contract storage as a verifier would read it
+    struct Chain {
+        owner: String,
+        will_hash: Option<String>,
+        finalized: bool,
+    }
+
+    impl Chain {
+        fn anchoring(bundle: &WillBundle) -> Self {
+            let key = WillSignatureService::decode_public_key(
+                bundle.document.owner_wallet.as_deref().unwrap(),
+            )
+            .unwrap();
+            Self {
+                owner: stellar_strkey::ed25519::PublicKey(key)
+                    .to_string()
+                    .as_str()
+                    .to_owned(),
+                will_hash: Some(bundle.document.will_hash.clone()),
+                finalized: true,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl InheritanceContractReader for Chain {
+        async fn get_plan(&self, plan_id: u64) -> Result<Option<OnChainPlan>, ApiError> {
+            Ok(Some(OnChainPlan {
+                plan_id,
+                plan_name: "Plan".to_string(),
+                owner: self.owner.clone(),
+                total_amount: 0,
+                total_allocation_bp: 10_000,
+                beneficiaries: Vec::new(),
+                is_active: true,
+            }))
+        }
+        async fn get_will_hash(&self, _plan_id: u64) -> Result<Option<String>, ApiError> {
+            Ok(self.will_hash.clone())
+        }
+        async fn get_will_version_hash(
+            &self,
+            _plan_id: u64,
+            _version: u32,
+        ) -> Result<Option<String>, ApiError> {
+            Ok(self.will_hash.clone())
+        }
+        async fn is_will_finalized(&self, _plan_id: u64, _version: u32) -> Result<bool, ApiError> {
+            Ok(self.finalized)
+        }
+        async fn is_will_revoked(&self, _plan_id: u64, _version: u32) -> Result<bool, ApiError> {
+            Ok(false)
+        }
+        async fn get_inheritance_trigger(
+            &self,
+            _plan_id: u64,
+        ) -> Result<Option<OnChainInheritanceTrigger>, ApiError> {
+            Ok(None)
+        }
+        async fn emergency_transfer_limit_bp(&self) -> Result<u32, ApiError> {
+            Ok(1000)
+        }
+        async fn get_legacy_message(
+            &self,
+            _message_id: u64,
+        ) -> Result<Option<OnChainLegacyMessage>, ApiError> {
+            Ok(None)
+        }
+    }
+
+    fn failed(report: &BundleReport) -> Vec<&str> {
+        report
+            .checks
+            .iter()
+            .filter(|c| c.outcome == CheckOutcome::Failed)
+            .map(|c| c.name.as_str())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn intact_bundle_verifies_against_the_chain() {
+        let (bundle, _) = signed_bundle();
+        let report = verify_bundle_on_chain(&bundle, &Chain::anchoring(&bundle)).await;
+        assert!(report.valid, "{report}");
+        assert!(report
+            .checks
+            .iter()
+            .all(|c| c.outcome == CheckOutcome::Passed));
+        assert_eq!(report.on_chain_lookups.len(), 3);
+        assert!(report.to_string().ends_with("Result: VALID"));
+
+        assert_eq!(report.verdict, BundleVerdict::Valid);
+    }
+
+    #[test]
+    fn offline_checks_pass_without_the_chain() {
+        let (bundle, _) = signed_bundle();
+        let report = verify_bundle(&bundle);
+        // The bundle's own anchor records are not enough to call it valid
+        assert!(!report.valid);
+        assert_eq!(report.verdict, BundleVerdict::ValidOffline);
+        assert!(report
+            .to_string()
+            .ends_with("VALID OFFLINE (not checked against the chain)"));
+
+        let (mut bundle, _) = signed_bundle();
+        bundle.witnesses[0].signature_hex = "00".repeat(64);
+        assert_eq!(verify_bundle(&bundle).verdict, BundleVerdict::Invalid);
+    }
+
+    #[tokio::test]
+    async fn anchors_are_checked_against_the_chain() {
+        let (bundle, _) = signed_bundle();
+        let mut chain = Chain::anchoring(&bundle);
+        chain.will_hash = Some("11".repeat(32));
+        chain.finalized = false;
+        let report = verify_bundle_on_chain(&bundle, &chain).await;
+        assert!(!report.valid);
+        assert_eq!(
+            failed(&report),
+            vec!["on_chain_anchor", "on_chain_finalized"]
+        );
+
+        // The plan must belong to the wallet that signed as testator
+        let mut chain = Chain::anchoring(&bundle);
+        chain.owner = stellar_strkey::ed25519::PublicKey([9; 32])
+            .to_string()
+            .as_str()
+            .to_owned();
+        let report = verify_bundle_on_chain(&bundle, &chain).await;
+        assert_eq!(failed(&report), vec!["on_chain_owner"]);
+    }
+
+    #[test]
+    fn tampering_is_detected() {
+        let (mut bundle, owner) = signed_bundle();
+        bundle.document.rendered_content = Some("I leave everything to Mallory.".to_string());
+        let report = verify_bundle(&bundle);
+        assert!(!report.valid);
+        assert_eq!(failed(&report), vec!["content_hash"]);
+
+        // A signature over a message for some other will does not count
+        let (mut bundle, _) = signed_bundle();
+        let other = WillSignatureService::build_message(&"11".repeat(32), "7", "nonce-2");
+        bundle.signatures[0].message_hash = sha256_hex(other.as_bytes());
+        bundle.signatures[0].signature_hex = hex::encode(owner.sign(other.as_bytes()));
+        bundle.signatures[0].message = Some(other);
+        assert!(!verify_bundle(&bundle).valid);
+
+        let (mut bundle, _) = signed_bundle();
+        bundle.witnesses[0].signature_hex = "00".repeat(64);
+        assert!(verify_bundle(&bundle)
+            .to_string()
+            .contains("[FAIL] witness_signature[0]"));
+    }
+
+    #[tokio::test]
+    async fn missing_or_unverifiable_signatures_fail() {
+        // No testator signature at all
+        let (mut bundle, _) = signed_bundle();
+        bundle.signatures.clear();
+        let report = verify_bundle_on_chain(&bundle, &Chain::anchoring(&bundle)).await;
+        assert!(!report.valid);
+        assert_eq!(failed(&report), vec!["signed_by_owner"]);
+
+        // A valid signature by someone other than the owner
+        let (mut bundle, _) = signed_bundle();
+        let (stranger, stranger_wallet) = keypair();
+        let message = bundle.signatures[0].message.clone().unwrap();
+        bundle.signatures[0].signature_hex = hex::encode(stranger.sign(message.as_bytes()));
+        bundle.signatures[0].wallet_address = stranger_wallet;
+        let report = verify_bundle_on_chain(&bundle, &Chain::anchoring(&bundle)).await;
+        assert_eq!(failed(&report), vec!["signed_by_owner"]);
+
+        // Signatures that cannot be checked are failures, not skips
+        let (mut bundle, _) = signed_bundle();
+        bundle.signatures[0].message = None;
+        bundle.witnesses[0].wallet_address = None;
+        let report = verify_bundle_on_chain(&bundle, &Chain::anchoring(&bundle)).await;
+        assert!(!report.valid);
+        assert_eq!(
+            failed(&report),
+            vec![
+                "testator_signature[0] ".to_string() + &bundle.signatures[0].wallet_address,
+                "signed_by_owner".to_string(),
+                "witness_signature[0]".to_string(),
+            ]
+        );
+        assert!(report
+            .checks
+            .iter()
+            .all(|c| c.outcome != CheckOutcome::Skipped));
+    }
+}
@@ -2,72 +2,16 @@
 //!
 //! Validates that generated wills meet basic jurisdiction-specific legal
 //! requirements including witness counts, required fields, and formatting rules.
+//! The rules themselves live in [`crate::jurisdiction_rules`]; errors cite
+//! the rule set and statute they come from.
 
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
+use crate::jurisdiction_rules::{RuleCatalog, RuleSeverity, WitnessConflictRule};
 use crate::will_pdf::{BeneficiaryEntry, WillDocumentInput};
 
-// --- Jurisdiction Rules ---
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct JurisdictionRules {
-    pub jurisdiction: String,
-    pub min_witnesses: u32,
-    pub require_notarization: bool,
-    pub min_beneficiaries: u32,
-    pub require_execution_rules: bool,
-    pub min_testator_age: Option<u32>,
-    pub require_relationship: bool,
-}
-
-fn us_rules() -> JurisdictionRules {
-    JurisdictionRules {
-        jurisdiction: "US".to_string(),
-        min_witnesses: 2,
-        require_notarization: false,
-        min_beneficiaries: 1,
-        require_execution_rules: false,
-        min_testator_age: Some(18),
-        require_relationship: false,
-    }
-}
-
-fn uk_rules() -> JurisdictionRules {
-    JurisdictionRules {
-        jurisdiction: "UK".to_string(),
-        min_witnesses: 2,
-        require_notarization: false,
-        min_beneficiaries: 1,
-        require_execution_rules: false,
-        min_testator_age: Some(18),
-        require_relationship: true,
-    }
-}
-
-fn eu_rules() -> JurisdictionRules {
-    JurisdictionRules {
-        jurisdiction: "EU".to_string(),
-        min_witnesses: 2,
-        require_notarization: true,
-        min_beneficiaries: 1,
-        require_execution_rules: false,
-        min_testator_age: Some(18),
-        require_relationship: false,
-    }
-}
-
-fn global_rules() -> JurisdictionRules {
-    JurisdictionRules {
-        jurisdiction: "GLOBAL".to_string(),
-        min_witnesses: 1,
-        require_notarization: false,
-        min_beneficiaries: 1,
-        require_execution_rules: false,
-        min_testator_age: None,
-        require_relationship: false,
-    }
-}
+pub use crate::jurisdiction_rules::JurisdictionRules;
 
 // --- Validation Types ---
 
@@ -75,6 +19,8 @@ fn global_rules() -> JurisdictionRules {
 pub struct ValidationResult {
     pub is_valid: bool,
     pub jurisdiction: String,
+    /// Rule sets applied, root first
+    pub rule_sets: Vec<String>,
     pub errors: Vec<ValidationError>,
     pub warnings: Vec<String>,
 }
@@ -84,6 +30,38 @@ pub struct ValidationError {
     pub field: String,
     pub message: String,
     pub severity: String,
+    /// Rule set and citation the error enforces, e.g. `UK v1: Wills Act 1837 s. 9`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl ValidationError {
+    fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+            severity: "error".to_string(),
+            source: None,
+        }
+    }
+
+    fn cite(mut self, rules: &JurisdictionRules, rule: &str) -> Self {
+        self.source = rules.source(rule);
+        self
+    }
+}
+
+/// Facts about how a will was executed, beyond its content
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionFacts {
+    pub witness_count: u32,
+    /// `None` when no stored document is being checked; otherwise whether a
+    /// valid notary seal exists for the current version of the document
+    pub notarized: Option<bool>,
+    /// Handwritten and signed by the testator rather than attested
+    pub holographic: bool,
+    /// Wallet addresses, emails or names of the attesting witnesses
+    pub witnesses: Vec<String>,
 }
 
 // --- Service ---
@@ -91,62 +69,80 @@ pub struct ValidationError {
 pub struct WillComplianceService;
 
 impl WillComplianceService {
+    /// Rules for a jurisdiction from the bundled rule sets. Use
+    /// [`crate::jurisdiction_rules::JurisdictionRuleService::resolve`] for
+    /// the rules admins currently have in force.
     pub fn get_jurisdiction_rules(jurisdiction: &str) -> JurisdictionRules {
-        match jurisdiction.to_uppercase().as_str() {
-            "US" => us_rules(),
-            "UK" => uk_rules(),
-            "EU" => eu_rules(),
-            _ => global_rules(),
-        }
+        RuleCatalog::bundled()
+            .resolve(jurisdiction)
+            .expect("bundled jurisdiction rules resolve")
     }
 
     pub fn list_supported_jurisdictions() -> Vec<String> {
-        vec![
-            "US".to_string(),
-            "UK".to_string(),
-            "EU".to_string(),
-            "GLOBAL".to_string(),
-        ]
+        RuleCatalog::bundled().codes()
     }
 
     pub fn validate(input: &WillDocumentInput, witness_count: u32) -> ValidationResult {
-        let jurisdiction_key = input.jurisdiction.as_deref().unwrap_or("GLOBAL");
-        let rules = Self::get_jurisdiction_rules(jurisdiction_key);
+        Self::validate_with_notarization(input, witness_count, None)
+    }
+
+    /// Validate a will, taking its notarization state into account.
+    ///
+    /// `notarized` is `None` when no stored document is being checked (the
+    /// requirement is reported as a warning), and otherwise whether a valid
+    /// notary seal exists for the current version of the document.
+    pub fn validate_with_notarization(
+        input: &WillDocumentInput,
+        witness_count: u32,
+        notarized: Option<bool>,
+    ) -> ValidationResult {
+        let rules = Self::get_jurisdiction_rules(input.jurisdiction.as_deref().unwrap_or("GLOBAL"));
+        let facts = ExecutionFacts {
+            witness_count,
+            notarized,
+            ..Default::default()
+        };
+        Self::validate_with_rules(input, &rules, &facts)
+    }
+
+    /// Validate a will against resolved jurisdiction rules
+    pub fn validate_with_rules(
+        input: &WillDocumentInput,
+        rules: &JurisdictionRules,
+        facts: &ExecutionFacts,
+    ) -> ValidationResult {
         let mut errors: Vec<ValidationError> = Vec::new();
         let mut warnings: Vec<String> = Vec::new();
 
         // Owner name required
         if input.owner_name.trim().is_empty() {
-            errors.push(ValidationError {
-                field: "owner_name".to_string(),
-                message: "Owner name is required".to_string(),
-                severity: "error".to_string(),
-            });
+            errors.push(ValidationError::new("owner_name", "Owner name is required"));
         }
 
         // Owner wallet required
         if input.owner_wallet.trim().is_empty() {
-            errors.push(ValidationError {
-                field: "owner_wallet".to_string(),
-                message: "Owner wallet address is required".to_string(),
-                severity: "error".to_string(),
-            });
+            errors.push(ValidationError::new(
+                "owner_wallet",
+                "Owner wallet address is required",
+            ));
         }
 
         // Minimum beneficiaries
         if input.beneficiaries.len() < rules.min_beneficiaries as usize {
-            errors.push(ValidationError {
-                field: "beneficiaries".to_string(),
-                message: format!(
-                    "At least {} beneficiary required for {} jurisdiction",
-                    rules.min_beneficiaries, rules.jurisdiction
-                ),
-                severity: "error".to_string(),
-            });
+            errors.push(
+                ValidationError::new(
+                    "beneficiaries",
+                    format!(
+                        "At least {} beneficiary required for {} jurisdiction",
+                        rules.min_beneficiaries, rules.jurisdiction
+                    ),
+                )
+                .cite(rules, "min_beneficiaries"),
+            );
         }
 
         // Validate each beneficiary
-        validate_beneficiaries(&input.beneficiaries, &rules, &mut errors);
+        validate_beneficiaries(&input.beneficiaries, rules, &mut errors);
 
         // Allocation sum must equal 100%
         let total: Decimal = input
@@ -155,25 +151,45 @@ impl WillComplianceService {
             .map(|b| b.allocation_percent)
             .sum();
         if total != Decimal::new(100, 0) {
-            errors.push(ValidationError {
-                field: "beneficiaries.allocation_percent".to_string(),
-                message: format!("Beneficiary allocations must sum to 100%, got {total}%"),
-                severity: "error".to_string(),
-            });
+            errors.push(ValidationError::new(
+                "beneficiaries.allocation_percent",
+                format!("Beneficiary allocations must sum to 100%, got {total}%"),
+            ));
         }
 
-        // Witness count
-        if witness_count < rules.min_witnesses {
-            errors.push(ValidationError {
-                field: "witness_count".to_string(),
-                message: format!(
-                    "{} jurisdiction requires at least {} witnesses, got {}",
-                    rules.jurisdiction, rules.min_witnesses, witness_count
-                ),
-                severity: "error".to_string(),
-            });
+        // Witness count, or a handwritten will where those are recognised
+        if facts.holographic {
+            if !rules.holographic_wills_accepted {
+                errors.push(
+                    ValidationError::new(
+                        "execution_form",
+                        format!(
+                            "{} jurisdiction does not recognise holographic wills",
+                            rules.jurisdiction
+                        ),
+                    )
+                    .cite(rules, "holographic_wills_accepted"),
+                );
+            }
+        } else if facts.witness_count < rules.min_witnesses {
+            errors.push(
+                ValidationError::new(
+                    "witness_count",
+                    format!(
+                        "{} jurisdiction requires at least {} witnesses, got {}",
+                        rules.jurisdiction, rules.min_witnesses, facts.witness_count
+                    ),
+                )
+                .cite(rules, "min_witnesses"),
+            );
         }
 
+        // Gifts to attesting witnesses
+        validate_witness_conflicts(input, rules, facts, &mut errors, &mut warnings);
+
+        // Forced heirship reserve
+        validate_forced_heirship(&input.beneficiaries, rules, &mut errors, &mut warnings);
+
         // Execution rules required
         if rules.require_execution_rules
             && input
@@ -183,29 +199,45 @@ impl WillComplianceService {
                 .trim()
                 .is_empty()
         {
-            errors.push(ValidationError {
-                field: "execution_rules".to_string(),
-                message: format!(
-                    "Execution rules are required for {} jurisdiction",
-                    rules.jurisdiction
-                ),
-                severity: "error".to_string(),
-            });
+            errors.push(
+                ValidationError::new(
+                    "execution_rules",
+                    format!(
+                        "Execution rules are required for {} jurisdiction",
+                        rules.jurisdiction
+                    ),
+                )
+                .cite(rules, "require_execution_rules"),
+            );
         }
 
-        // Notarization warning
+        // Notarization
         if rules.require_notarization {
-            warnings.push(format!(
-                "{} jurisdiction requires notarization. Ensure the document is notarized before execution.",
-                rules.jurisdiction
-            ));
+            match facts.notarized {
+                None => warnings.push(format!(
+                    "{} jurisdiction requires notarization. Ensure the document is notarized before execution.",
+                    rules.jurisdiction
+                )),
+                Some(true) => {}
+                Some(false) => errors.push(
+                    ValidationError::new(
+                        "notarization",
+                        format!(
+                            "{} jurisdiction requires a notary seal on the current will version",
+                            rules.jurisdiction
+                        ),
+                    )
+                    .cite(rules, "require_notarization"),
+                ),
+            }
         }
 
         let is_valid = errors.is_empty();
 
         ValidationResult {
             is_valid,
-            jurisdiction: rules.jurisdiction,
+            jurisdiction: rules.jurisdiction.clone(),
+            rule_sets: rules.inherits.clone(),
             errors,
             warnings,
         }
@@ -221,30 +253,117 @@ fn validate_beneficiaries(
         let idx = i + 1;
 
         if b.name.trim().is_empty() {
-            errors.push(ValidationError {
-                field: format!("beneficiaries[{idx}].name"),
-                message: format!("Beneficiary {idx} name is required"),
-                severity: "error".to_string(),
-            });
+            errors.push(ValidationError::new(
+                format!("beneficiaries[{idx}].name"),
+                format!("Beneficiary {idx} name is required"),
+            ));
         }
 
         if b.wallet_address.trim().is_empty() {
-            errors.push(ValidationError {
-                field: format!("beneficiaries[{idx}].wallet_address"),
-                message: format!("Beneficiary {idx} wallet address is required"),
-                severity: "error".to_string(),
-            });
+            errors.push(ValidationError::new(
+                format!("beneficiaries[{idx}].wallet_address"),
+                format!("Beneficiary {idx} wallet address is required"),
+            ));
         }
 
         if rules.require_relationship && b.relationship.as_deref().unwrap_or("").trim().is_empty() {
-            errors.push(ValidationError {
-                field: format!("beneficiaries[{idx}].relationship"),
-                message: format!(
-                    "Beneficiary {idx} relationship is required for {} jurisdiction",
-                    rules.jurisdiction
-                ),
-                severity: "error".to_string(),
-            });
+            errors.push(
+                ValidationError::new(
+                    format!("beneficiaries[{idx}].relationship"),
+                    format!(
+                        "Beneficiary {idx} relationship is required for {} jurisdiction",
+                        rules.jurisdiction
+                    ),
+                )
+                .cite(rules, "require_relationship"),
+            );
+        }
+    }
+}
+
+fn validate_witness_conflicts(
+    input: &WillDocumentInput,
+    rules: &JurisdictionRules,
+    facts: &ExecutionFacts,
+    errors: &mut Vec<ValidationError>,
+    warnings: &mut Vec<String>,
+) {
+    if rules.witness_beneficiary_conflict == WitnessConflictRule::Allowed {
+        return;
+    }
+    let witnesses: Vec<String> = facts
+        .witnesses
+        .iter()
+        .map(|w| w.trim().to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    for (i, b) in input.beneficiaries.iter().enumerate() {
+        let idx = i + 1;
+        let is_witness = [&b.wallet_address, &b.name]
+            .iter()
+            .map(|v| v.trim().to_lowercase())
+            .any(|v| !v.is_empty() && witnesses.contains(&v));
+        if !is_witness {
+            continue;
+        }
+        match rules.witness_beneficiary_conflict {
+            WitnessConflictRule::Void => errors.push(
+                ValidationError::new(
+                    format!("beneficiaries[{idx}]"),
+                    format!(
+                        "Beneficiary {idx} also witnessed the will; the gift is void in {} jurisdiction",
+                        rules.jurisdiction
+                    ),
+                )
+                .cite(rules, "witness_beneficiary_conflict"),
+            ),
+            WitnessConflictRule::Warning => warnings.push(format!(
+                "Beneficiary {idx} also witnessed the will; the gift may be challenged in {} jurisdiction",
+                rules.jurisdiction
+            )),
+            WitnessConflictRule::Allowed => {}
+        }
+    }
+}
+
+fn validate_forced_heirship(
+    beneficiaries: &[BeneficiaryEntry],
+    rules: &JurisdictionRules,
+    errors: &mut Vec<ValidationError>,
+    warnings: &mut Vec<String>,
+) {
+    let Some(heirship) = &rules.forced_heirship else {
+        return;
+    };
+    let to_heirs: Decimal = beneficiaries
+        .iter()
+        .filter(|b| {
+            b.relationship
+                .as_deref()
+                .is_some_and(|r| heirship.covers(r))
+        })
+        .map(|b| b.allocation_percent)
+        .sum();
+
+    if to_heirs.is_zero() {
+        warnings.push(format!(
+            "{} jurisdiction reserves {}% of the estate for forced heirs ({}); if the testator has any, the will may be reduced",
+            rules.jurisdiction,
+            heirship.reserved_share_percent,
+            heirship.heirs.join(", ")
+        ));
+    } else if to_heirs < heirship.reserved_share_percent {
+        let message = format!(
+            "{} jurisdiction reserves {}% of the estate for forced heirs, but they receive {to_heirs}%",
+            rules.jurisdiction, heirship.reserved_share_percent
+        );
+        match heirship.severity {
+            RuleSeverity::Error => errors.push(
+                ValidationError::new("beneficiaries.allocation_percent", message)
+                    .cite(rules, "forced_heirship"),
+            ),
+            RuleSeverity::Warning => warnings.push(message),
         }
     }
 }
@@ -254,7 +373,6 @@ fn validate_beneficiaries(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::will_pdf::WillTemplate;
     use rust_decimal_macros::dec;
     use uuid::Uuid;
 
@@ -271,9 +389,11 @@ mod tests {
                 relationship: Some("Son".to_string()),
             }],
             execution_rules: Some("Distribute after 90-day inactivity".to_string()),
-            template: WillTemplate::Formal,
+            template: "formal".to_string(),
+            locale: None,
             jurisdiction: Some("US".to_string()),
             will_hash_reference: None,
+            template_fields: Default::default(),
         }
     }
 
@@ -379,6 +499,20 @@ mod tests {
         assert!(result.warnings[0].contains("notarization"));
     }
 
+    #[test]
+    fn test_eu_notarization_requires_seal() {
+        let mut input = valid_input();
+        input.jurisdiction = Some("EU".to_string());
+
+        let unsealed = WillComplianceService::validate_with_notarization(&input, 2, Some(false));
+        assert!(!unsealed.is_valid);
+        assert!(unsealed.errors.iter().any(|e| e.field == "notarization"));
+
+        let sealed = WillComplianceService::validate_with_notarization(&input, 2, Some(true));
+        assert!(sealed.is_valid);
+        assert!(sealed.warnings.is_empty());
+    }
+
     #[test]
     fn test_global_fallback_one_witness() {
         let mut input = valid_input();
@@ -400,8 +534,9 @@ mod tests {
     #[test]
     fn test_list_supported_jurisdictions() {
         let jurisdictions = WillComplianceService::list_supported_jurisdictions();
-        assert_eq!(jurisdictions.len(), 4);
         assert!(jurisdictions.contains(&"US".to_string()));
+        assert!(jurisdictions.contains(&"US-CA".to_string()));
+        assert!(jurisdictions.contains(&"BR".to_string()));
         assert!(jurisdictions.contains(&"UK".to_string()));
         assert!(jurisdictions.contains(&"EU".to_string()));
         assert!(jurisdictions.contains(&"GLOBAL".to_string()));
@@ -431,4 +566,88 @@ mod tests {
         assert!(!result.is_valid);
         assert!(result.errors.len() >= 3);
     }
+
+    #[test]
+    fn test_errors_cite_rule_source() {
+        let mut input = valid_input();
+        input.jurisdiction = Some("UK".to_string());
+        let result = WillComplianceService::validate(&input, 1);
+        let error = result
+            .errors
+            .iter()
+            .find(|e| e.field == "witness_count")
+            .unwrap();
+        assert_eq!(error.source.as_deref(), Some("UK v1: Wills Act 1837 s. 9"));
+        assert_eq!(result.rule_sets, vec!["GLOBAL v1", "UK v1"]);
+    }
+
+    #[test]
+    fn test_witness_beneficiary_conflict() {
+        let mut input = valid_input();
+        input.jurisdiction = Some("UK".to_string());
+        let rules = WillComplianceService::get_jurisdiction_rules("UK");
+        let facts = ExecutionFacts {
+            witness_count: 2,
+            witnesses: vec!["gbob1234567890abcdef".to_string(), "Carol".to_string()],
+            ..Default::default()
+        };
+        let result = WillComplianceService::validate_with_rules(&input, &rules, &facts);
+        assert!(!result.is_valid);
+        assert!(result.errors.iter().any(|e| e.field == "beneficiaries[1]"));
+
+        // Scotland lets witnesses benefit
+        let rules = WillComplianceService::get_jurisdiction_rules("UK-SCT");
+        let result = WillComplianceService::validate_with_rules(&input, &rules, &facts);
+        assert!(result.is_valid);
+    }
+
+    #[test]
+    fn test_holographic_wills_by_state() {
+        let input = valid_input();
+        let facts = ExecutionFacts {
+            holographic: true,
+            ..Default::default()
+        };
+
+        let ca = WillComplianceService::get_jurisdiction_rules("US-CA");
+        assert!(WillComplianceService::validate_with_rules(&input, &ca, &facts).is_valid);
+
+        let ny = WillComplianceService::get_jurisdiction_rules("US-NY");
+        let result = WillComplianceService::validate_with_rules(&input, &ny, &facts);
+        assert!(!result.is_valid);
+        assert!(result.errors.iter().any(|e| e.field == "execution_form"));
+    }
+
+    #[test]
+    fn test_forced_heirship_reserve() {
+        let mut input = valid_input();
+        input.jurisdiction = Some("BR".to_string());
+        input.beneficiaries = vec![
+            BeneficiaryEntry {
+                name: "Filho".to_string(),
+                wallet_address: "GCHILD".to_string(),
+                allocation_percent: dec!(30),
+                relationship: Some("Son".to_string()),
+            },
+            BeneficiaryEntry {
+                name: "Amigo".to_string(),
+                wallet_address: "GFRIEND".to_string(),
+                allocation_percent: dec!(70),
+                relationship: Some("Friend".to_string()),
+            },
+        ];
+        let result = WillComplianceService::validate_with_notarization(&input, 2, Some(true));
+        assert!(!result.is_valid);
+        let error = result
+            .errors
+            .iter()
+            .find(|e| e.field == "beneficiaries.allocation_percent")
+            .unwrap();
+        assert!(error.source.as_deref().unwrap().starts_with("BR v1:"));
+
+        input.beneficiaries[0].allocation_percent = dec!(50);
+        input.beneficiaries[1].allocation_percent = dec!(50);
+        let result = WillComplianceService::validate_with_notarization(&input, 2, Some(true));
+        assert!(result.is_valid);
+    }
 }
@@ -103,6 +103,22 @@ pub enum WillEvent {
         witness_id: Uuid,
         timestamp: DateTime<Utc>,
     },
+    WitnessExpired {
+        vault_id: String,
+        document_id: Uuid,
+        plan_id: Uuid,
+        witness_id: Uuid,
+        timestamp: DateTime<Utc>,
+    },
+    WillRevoked {
+        vault_id: String,
+        document_id: Uuid,
+        plan_id: Uuid,
+        revocation_id: Uuid,
+        versions: Vec<u32>,
+        reason: Option<String>,
+        timestamp: DateTime<Utc>,
+    },
 }
 
 impl WillEvent {
@@ -119,6 +135,8 @@ impl WillEvent {
             WillEvent::WillVerified { .. } => "will_verified",
             WillEvent::WitnessInvited { .. } => "witness_invited",
             WillEvent::WitnessDeclined { .. } => "witness_declined",
+            WillEvent::WitnessExpired { .. } => "witness_expired",
+            WillEvent::WillRevoked { .. } => "will_revoked",
         }
     }
 
@@ -134,7 +152,9 @@ impl WillEvent {
             | WillEvent::WillBackupCreated { document_id, .. }
             | WillEvent::WillVerified { document_id, .. }
             | WillEvent::WitnessInvited { document_id, .. }
-            | WillEvent::WitnessDeclined { document_id, .. } => *document_id,
+            | WillEvent::WitnessDeclined { document_id, .. }
+            | WillEvent::WitnessExpired { document_id, .. }
+            | WillEvent::WillRevoked { document_id, .. } => *document_id,
         }
     }
 
@@ -150,7 +170,9 @@ impl WillEvent {
             | WillEvent::WillBackupCreated { plan_id, .. }
             | WillEvent::WillVerified { plan_id, .. }
             | WillEvent::WitnessInvited { plan_id, .. }
-            | WillEvent::WitnessDeclined { plan_id, .. } => *plan_id,
+            | WillEvent::WitnessDeclined { plan_id, .. }
+            | WillEvent::WitnessExpired { plan_id, .. }
+            | WillEvent::WillRevoked { plan_id, .. } => *plan_id,
         }
     }
 
@@ -166,7 +188,9 @@ impl WillEvent {
             | WillEvent::WillBackupCreated { vault_id, .. }
             | WillEvent::WillVerified { vault_id, .. }
             | WillEvent::WitnessInvited { vault_id, .. }
-            | WillEvent::WitnessDeclined { vault_id, .. } => vault_id,
+            | WillEvent::WitnessDeclined { vault_id, .. }
+            | WillEvent::WitnessExpired { vault_id, .. }
+            | WillEvent::WillRevoked { vault_id, .. } => vault_id,
         }
     }
 
@@ -182,7 +206,9 @@ impl WillEvent {
             | WillEvent::WillBackupCreated { timestamp, .. }
             | WillEvent::WillVerified { timestamp, .. }
             | WillEvent::WitnessInvited { timestamp, .. }
-            | WillEvent::WitnessDeclined { timestamp, .. } => *timestamp,
+            | WillEvent::WitnessDeclined { timestamp, .. }
+            | WillEvent::WitnessExpired { timestamp, .. }
+            | WillEvent::WillRevoked { timestamp, .. } => *timestamp,
         }
     }
 }
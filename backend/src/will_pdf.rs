@@ -1,64 +1,23 @@
 //! # Will PDF Generator & Template Engine (Tasks 1 & 2)
 //!
 //! Generates a structured legal will document from vault/plan data.
-//! Supports multiple templates (simple, formal, jurisdiction-specific).
+//! Will text comes from versioned, localized templates (see
+//! [`crate::will_template`]); the document records which version it used.
 
 use crate::api_error::ApiError;
+use crate::pdf_layout::{self, Block, DocumentMeta, PageLayout, PdfAssets};
+use crate::will_revision::WillTerms;
+use crate::will_signature::{WillSignatureRecord, WillSignatureService};
+use crate::will_template::{self, Context, Value, WillTemplateRecord, WillTemplateService};
+use crate::witness::{WitnessRecord, WitnessService};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use chrono::{DateTime, Utc};
 use ring::digest::{digest, SHA256};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
-// ─── Template Types ───────────────────────────────────────────────────────────
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum WillTemplate {
-    Simple,
-    Formal,
-    UsJurisdiction,
-    UkJurisdiction,
-    GlobalGeneric,
-}
-
-impl WillTemplate {
-    pub fn as_str(self) -> &'static str {
-        match self {
-            WillTemplate::Simple => "simple",
-            WillTemplate::Formal => "formal",
-            WillTemplate::UsJurisdiction => "us_jurisdiction",
-            WillTemplate::UkJurisdiction => "uk_jurisdiction",
-            WillTemplate::GlobalGeneric => "global_generic",
-        }
-    }
-
-    pub fn display_name(self) -> &'static str {
-        match self {
-            WillTemplate::Simple => "Simple Will",
-            WillTemplate::Formal => "Formal Legal Will",
-            WillTemplate::UsJurisdiction => "US Jurisdiction Will",
-            WillTemplate::UkJurisdiction => "UK Jurisdiction Will",
-            WillTemplate::GlobalGeneric => "Global Generic Will",
-        }
-    }
-}
-
-impl std::str::FromStr for WillTemplate {
-    type Err = ApiError;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "simple" => Ok(WillTemplate::Simple),
-            "formal" => Ok(WillTemplate::Formal),
-            "us_jurisdiction" => Ok(WillTemplate::UsJurisdiction),
-            "uk_jurisdiction" => Ok(WillTemplate::UkJurisdiction),
-            "global_generic" => Ok(WillTemplate::GlobalGeneric),
-            _ => Err(ApiError::BadRequest(format!("Unknown template: {s}"))),
-        }
-    }
-}
-
 // ─── Data Structures ──────────────────────────────────────────────────────────
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -77,9 +36,16 @@ pub struct WillDocumentInput {
     pub vault_id: String,
     pub beneficiaries: Vec<BeneficiaryEntry>,
     pub execution_rules: Option<String>,
-    pub template: WillTemplate,
+    /// Key of a template in `will_templates`, e.g. `formal`
+    pub template: String,
+    /// Template locale; defaults to English
+    #[serde(default)]
+    pub locale: Option<String>,
     pub jurisdiction: Option<String>,
     pub will_hash_reference: Option<String>,
+    /// Values for template-specific fields such as `state` and `county`
+    #[serde(default)]
+    pub template_fields: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -97,214 +63,229 @@ pub struct GeneratedWillDocument {
 
 // ─── Template Engine ──────────────────────────────────────────────────────────
 
+/// Builds the values a will template can refer to and renders it.
 struct TemplateEngine;
 
 impl TemplateEngine {
-    fn render(input: &WillDocumentInput, generated_at: DateTime<Utc>, version: u32) -> String {
-        match input.template {
-            WillTemplate::Simple => Self::render_simple(input, generated_at, version),
-            WillTemplate::Formal => Self::render_formal(input, generated_at, version),
-            WillTemplate::UsJurisdiction => Self::render_us(input, generated_at, version),
-            WillTemplate::UkJurisdiction => Self::render_uk(input, generated_at, version),
-            WillTemplate::GlobalGeneric => Self::render_global(input, generated_at, version),
-        }
-    }
-
-    fn header(title: &str, generated_at: DateTime<Utc>, version: u32) -> String {
-        format!(
-            "================================================================\n\
-             {title}\n\
-             ================================================================\n\
-             Generated: {generated_at}\n\
-             Document Version: {version}\n\
-             ----------------------------------------------------------------\n"
-        )
-    }
-
-    fn beneficiaries_section(beneficiaries: &[BeneficiaryEntry]) -> String {
-        let mut s = String::from("BENEFICIARIES\n-------------\n");
-        for (i, b) in beneficiaries.iter().enumerate() {
-            s.push_str(&format!(
-                "{}. Name:       {}\n   Wallet:     {}\n   Allocation: {}%\n",
-                i + 1,
-                b.name,
-                b.wallet_address,
-                b.allocation_percent
-            ));
-            if let Some(rel) = &b.relationship {
-                s.push_str(&format!("   Relation:   {rel}\n"));
-            }
-        }
-        s
-    }
+    fn context(input: &WillDocumentInput, generated_at: DateTime<Utc>, version: u32) -> Context {
+        let text = |s: &str| Value::Text(s.to_string());
+        // Caller-supplied fields first, so built-in fields always win
+        let mut ctx: Context = input
+            .template_fields
+            .iter()
+            .map(|(k, v)| (k.clone(), text(v.trim())))
+            .collect();
+
+        let optional = |v: &Option<String>| text(v.as_deref().unwrap_or_default());
+        ctx.insert("plan_id".into(), text(&input.plan_id.to_string()));
+        ctx.insert("owner_name".into(), text(&input.owner_name));
+        ctx.insert("owner_wallet".into(), text(&input.owner_wallet));
+        ctx.insert("vault_id".into(), text(&input.vault_id));
+        ctx.insert(
+            "generated_at".into(),
+            text(&generated_at.format("%Y-%m-%d %H:%M:%S UTC").to_string()),
+        );
+        ctx.insert("version".into(), text(&version.to_string()));
+        ctx.insert("jurisdiction".into(), optional(&input.jurisdiction));
+        ctx.insert("execution_rules".into(), optional(&input.execution_rules));
+        ctx.insert(
+            "will_hash_reference".into(),
+            optional(&input.will_hash_reference),
+        );
 
-    fn render_simple(input: &WillDocumentInput, ts: DateTime<Utc>, v: u32) -> String {
-        let mut doc = Self::header("LAST WILL AND TESTAMENT (SIMPLE)", ts, v);
-        doc.push_str(&format!(
-            "\nI, {}, wallet address {}, hereby declare this my last will.\n\n",
-            input.owner_name, input.owner_wallet
-        ));
-        doc.push_str(&Self::beneficiaries_section(&input.beneficiaries));
-        Self::append_footer(&mut doc, input);
-        doc
+        let beneficiaries = input
+            .beneficiaries
+            .iter()
+            .enumerate()
+            .map(|(i, b)| {
+                Context::from([
+                    ("index".to_string(), text(&(i + 1).to_string())),
+                    ("name".to_string(), text(&b.name)),
+                    ("wallet_address".to_string(), text(&b.wallet_address)),
+                    (
+                        "allocation_percent".to_string(),
+                        // Normalised so 50 and 50.00 render identically
+                        text(&b.allocation_percent.normalize().to_string()),
+                    ),
+                    ("relationship".to_string(), optional(&b.relationship)),
+                ])
+            })
+            .collect();
+        ctx.insert(
+            will_template::BENEFICIARIES_FIELD.into(),
+            Value::List(beneficiaries),
+        );
+        ctx
     }
 
-    fn render_formal(input: &WillDocumentInput, ts: DateTime<Utc>, v: u32) -> String {
-        let mut doc = Self::header("FORMAL LAST WILL AND TESTAMENT", ts, v);
-        doc.push_str(&format!(
-            "\nI, {owner}, residing at blockchain address {wallet}, being of sound mind,\n\
-             do hereby make, publish, and declare this instrument to be my Last Will\n\
-             and Testament, hereby revoking all former wills and codicils.\n\n\
-             VAULT REFERENCE: {vault}\n\n",
-            owner = input.owner_name,
-            wallet = input.owner_wallet,
-            vault = input.vault_id
-        ));
-        doc.push_str(&Self::beneficiaries_section(&input.beneficiaries));
-        if let Some(rules) = &input.execution_rules {
-            doc.push_str(&format!("\nEXECUTION RULES\n---------------\n{rules}\n"));
-        }
-        Self::append_footer(&mut doc, input);
-        doc
+    fn render(
+        template: &WillTemplateRecord,
+        input: &WillDocumentInput,
+        generated_at: DateTime<Utc>,
+        version: u32,
+    ) -> Result<String, ApiError> {
+        template.render(&Self::context(input, generated_at, version))
     }
+}
 
-    fn render_us(input: &WillDocumentInput, ts: DateTime<Utc>, v: u32) -> String {
-        let mut doc = Self::header("LAST WILL AND TESTAMENT — US JURISDICTION", ts, v);
-        doc.push_str(
-            "\nSTATE OF [STATE], COUNTY OF [COUNTY]\n\
-             This Will is executed in accordance with applicable US state law.\n\n",
-        );
-        doc.push_str(&format!(
-            "Testator: {}, Wallet: {}\nVault ID: {}\n\n",
-            input.owner_name, input.owner_wallet, input.vault_id
-        ));
-        doc.push_str(&Self::beneficiaries_section(&input.beneficiaries));
-        doc.push_str(
-            "\nWITNESS CLAUSE\nThis will requires two witnesses per applicable state law.\n",
-        );
-        Self::append_footer(&mut doc, input);
-        doc
-    }
+// ─── PDF Builder ──────────────────────────────────────────────────────────────
 
-    fn render_uk(input: &WillDocumentInput, ts: DateTime<Utc>, v: u32) -> String {
-        let mut doc = Self::header("LAST WILL AND TESTAMENT — UK JURISDICTION", ts, v);
-        doc.push_str("\nThis Will is made in accordance with the Wills Act 1837 (as amended).\n\n");
-        doc.push_str(&format!(
-            "Testator: {}, Wallet: {}\nVault ID: {}\n\n",
-            input.owner_name, input.owner_wallet, input.vault_id
-        ));
-        doc.push_str(&Self::beneficiaries_section(&input.beneficiaries));
-        doc.push_str("\nATTESTATION\nSigned by the above-named Testator in our presence.\n");
-        Self::append_footer(&mut doc, input);
-        doc
-    }
+/// Minimum number of witness signature lines on the attestation page
+const MIN_WITNESS_LINES: usize = 2;
 
-    fn render_global(input: &WillDocumentInput, ts: DateTime<Utc>, v: u32) -> String {
-        let mut doc = Self::header("LAST WILL AND TESTAMENT — GLOBAL GENERIC", ts, v);
-        let jurisdiction = input
-            .jurisdiction
-            .as_deref()
-            .unwrap_or("International / Unspecified");
-        doc.push_str(&format!("\nJurisdiction: {jurisdiction}\n\n"));
-        doc.push_str(&format!(
-            "Testator: {}, Wallet: {}\nVault ID: {}\n\n",
-            input.owner_name, input.owner_wallet, input.vault_id
-        ));
-        doc.push_str(&Self::beneficiaries_section(&input.beneficiaries));
-        Self::append_footer(&mut doc, input);
-        doc
-    }
+/// Signatures collected for a document, shown on the attestation page
+#[derive(Debug, Clone, Default)]
+pub struct Attestation {
+    pub testator_signatures: Vec<WillSignatureRecord>,
+    pub witnesses: Vec<WitnessRecord>,
+}
 
-    fn append_footer(doc: &mut String, input: &WillDocumentInput) {
-        doc.push_str("\n----------------------------------------------------------------\n");
-        if let Some(hash_ref) = &input.will_hash_reference {
-            doc.push_str(&format!("ON-CHAIN WILL HASH: {hash_ref}\n"));
-        }
-        doc.push_str(&format!("PLAN ID: {}\n", input.plan_id));
-        doc.push_str(
-            "================================================================\n\
-             This document is cryptographically bound to the vault above.\n\
-             ================================================================\n",
-        );
-    }
+/// Document facts printed in headers, footers and metadata
+pub(crate) struct PdfContext<'a> {
+    pub(crate) plan_id: Uuid,
+    pub(crate) owner_name: &'a str,
+    pub(crate) title: &'a str,
+    pub(crate) will_hash: &'a str,
+    pub(crate) generated_at: DateTime<Utc>,
 }
 
-// ─── PDF Builder ──────────────────────────────────────────────────────────────
+/// Convert rendered template text into layout blocks.
+/// Banner lines of `=` or `-` become rules; everything else wraps.
+fn content_blocks(content: &str) -> Vec<Block> {
+    content
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim();
+            if trimmed.len() >= 8 && trimmed.chars().all(|c| c == '=' || c == '-') {
+                Block::Rule
+            } else if trimmed.is_empty() {
+                Block::Spacer(6.0)
+            } else {
+                Block::Paragraph(line.to_string())
+            }
+        })
+        .collect()
+}
 
-/// Builds a minimal valid PDF containing the will text.
-/// Uses raw PDF syntax — no external crate required.
-fn build_pdf(content: &str) -> Vec<u8> {
-    // Escape special PDF string characters
-    let escaped = content
-        .replace('\\', "\\\\")
-        .replace('(', "\\(")
-        .replace(')', "\\)");
-
-    // Split into lines for multi-line text rendering
-    let lines: Vec<&str> = escaped.lines().collect();
-    let mut stream_content = String::new();
-    stream_content.push_str("BT\n/F1 10 Tf\n50 780 Td\n12 TL\n");
-    for line in &lines {
-        stream_content.push_str(&format!("({line}) Tj T*\n"));
+fn attestation_blocks(ctx: &PdfContext, attestation: &Attestation) -> Vec<Block> {
+    let mut blocks = vec![
+        Block::PageBreak,
+        Block::Heading("SIGNATURE AND ATTESTATION".to_string()),
+        Block::Paragraph(format!(
+            "IN WITNESS WHEREOF, I, {}, sign this will, consisting of this and the \
+             preceding pages, as my last will and testament, and declare that I sign it \
+             willingly as my free and voluntary act.",
+            ctx.owner_name
+        )),
+        Block::Spacer(6.0),
+    ];
+
+    let testator_details = match attestation.testator_signatures.first() {
+        Some(sig) => vec![
+            format!("Signed by wallet {}", sig.wallet_address),
+            format!(
+                "Signed at {}",
+                sig.signed_at.format("%Y-%m-%d %H:%M:%S UTC")
+            ),
+            format!("Signature: {}", sig.signature_hex),
+        ],
+        None => vec!["Date: ____________________".to_string()],
+    };
+    blocks.push(Block::SignatureLine {
+        label: format!("{} (Testator)", ctx.owner_name),
+        details: testator_details,
+    });
+
+    blocks.push(Block::Spacer(12.0));
+    blocks.push(Block::KeepTogether(vec![
+        Block::Heading("ATTESTATION OF WITNESSES".to_string()),
+        Block::Paragraph(
+            "We declare that the testator signed this will in our presence, and that we \
+             sign below as witnesses in the presence of the testator and of each other."
+                .to_string(),
+        ),
+    ]));
+
+    let witness_count = attestation.witnesses.len().max(MIN_WITNESS_LINES);
+    for i in 0..witness_count {
+        let (label, details) = match attestation.witnesses.get(i) {
+            Some(w) => {
+                let identity = w
+                    .wallet_address
+                    .as_deref()
+                    .or(w.email.as_deref())
+                    .unwrap_or("unidentified");
+                let mut details = vec![format!("Status: {}", w.status)];
+                if let Some(signed_at) = w.signed_at {
+                    details.push(format!(
+                        "Signed at {}",
+                        signed_at.format("%Y-%m-%d %H:%M:%S UTC")
+                    ));
+                }
+                if let Some(sig) = &w.signature_hex {
+                    details.push(format!("Signature: {sig}"));
+                }
+                (format!("Witness {}: {identity}", i + 1), details)
+            }
+            None => (
+                format!("Witness {}", i + 1),
+                vec![
+                    "Name: ____________________".to_string(),
+                    "Date: ____________________".to_string(),
+                ],
+            ),
+        };
+        blocks.push(Block::SignatureLine { label, details });
     }
-    stream_content.push_str("ET\n");
-
-    let stream_bytes = stream_content.as_bytes();
-    let stream_len = stream_bytes.len();
-
-    let mut pdf = Vec::new();
-    pdf.extend_from_slice(b"%PDF-1.4\n");
-
-    // Object 1: Catalog
-    let obj1_offset = pdf.len();
-    pdf.extend_from_slice(b"1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n");
-
-    // Object 2: Pages
-    let obj2_offset = pdf.len();
-    pdf.extend_from_slice(b"2 0 obj\n<< /Type /Pages /Kids [3 0 R] /Count 1 >>\nendobj\n");
-
-    // Object 3: Page
-    let obj3_offset = pdf.len();
-    pdf.extend_from_slice(
-        b"3 0 obj\n<< /Type /Page /Parent 2 0 R \
-          /MediaBox [0 0 612 792] \
-          /Contents 4 0 R \
-          /Resources << /Font << /F1 5 0 R >> >> >>\nendobj\n",
-    );
-
-    // Object 4: Content stream
-    let obj4_offset = pdf.len();
-    pdf.extend_from_slice(format!("4 0 obj\n<< /Length {stream_len} >>\nstream\n").as_bytes());
-    pdf.extend_from_slice(stream_bytes);
-    pdf.extend_from_slice(b"\nendstream\nendobj\n");
-
-    // Object 5: Font
-    let obj5_offset = pdf.len();
-    pdf.extend_from_slice(
-        b"5 0 obj\n<< /Type /Font /Subtype /Type1 /BaseFont /Courier >>\nendobj\n",
-    );
-
-    // Cross-reference table
-    let xref_offset = pdf.len();
-    pdf.extend_from_slice(
-        format!(
-            "xref\n0 6\n\
-             0000000000 65535 f \n\
-             {obj1_offset:010} 00000 n \n\
-             {obj2_offset:010} 00000 n \n\
-             {obj3_offset:010} 00000 n \n\
-             {obj4_offset:010} 00000 n \n\
-             {obj5_offset:010} 00000 n \n"
-        )
-        .as_bytes(),
-    );
+    blocks
+}
 
-    // Trailer
-    pdf.extend_from_slice(
-        format!("trailer\n<< /Size 6 /Root 1 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n").as_bytes(),
-    );
+/// Lay out the will text followed by the attestation page.
+/// Render a will as PDF/A; fails when the PDF/A assets are not configured
+pub(crate) fn build_pdf(
+    ctx: &PdfContext,
+    content: &str,
+    attestation: &Attestation,
+) -> Result<Vec<u8>, ApiError> {
+    Ok(layout_pdf(
+        ctx,
+        content,
+        attestation,
+        PdfAssets::from_env()?,
+    ))
+}
 
-    pdf
+fn layout_pdf(
+    ctx: &PdfContext,
+    content: &str,
+    attestation: &Attestation,
+    assets: &PdfAssets,
+) -> Vec<u8> {
+    let mut blocks = content_blocks(content);
+    blocks.extend(attestation_blocks(ctx, attestation));
+
+    let plan_id = ctx.plan_id.to_string();
+    let meta = DocumentMeta {
+        title: ctx.title.to_string(),
+        author: ctx.owner_name.to_string(),
+        subject: format!("Last will and testament for plan {plan_id}"),
+        keywords: vec![
+            "will".to_string(),
+            format!("plan:{plan_id}"),
+            format!("sha256:{}", ctx.will_hash),
+        ],
+        created_at: ctx.generated_at,
+        running_header: ctx.title.to_uppercase(),
+        footer_note: format!("Plan ID: {plan_id}"),
+        footer_detail: Some(format!("Will hash (SHA-256): {}", ctx.will_hash)),
+        custom_properties: vec![
+            ("WillHash".to_string(), ctx.will_hash.to_string()),
+            ("PlanId".to_string(), plan_id),
+        ],
+        document_id: hex::decode(ctx.will_hash)
+            .unwrap_or_else(|_| ctx.will_hash.as_bytes().to_vec()),
+    };
+    pdf_layout::render(&meta, &blocks, &PageLayout::default(), assets)
 }
 
 // ─── Will PDF Service ─────────────────────────────────────────────────────────
@@ -318,28 +299,45 @@ impl WillPdfService {
         user_id: Uuid,
         input: &WillDocumentInput,
     ) -> Result<GeneratedWillDocument, ApiError> {
-        // Determine next version for this plan
-        let version: i64 = sqlx::query_scalar(
+        // Determine next version for this plan, holding the plan row so
+        // concurrent wills and codicils cannot take the same number
+        let mut tx = db.begin().await?;
+        sqlx::query("SELECT id FROM plans WHERE id = $1 FOR UPDATE")
+            .bind(input.plan_id)
+            .execute(&mut *tx)
+            .await?;
+        let version: i32 = sqlx::query_scalar(
             "SELECT COALESCE(MAX(version), 0) FROM will_documents WHERE plan_id = $1",
         )
         .bind(input.plan_id)
-        .fetch_one(db)
-        .await
-        .unwrap_or(0);
+        .fetch_one(&mut *tx)
+        .await?;
         let version = (version + 1) as u32;
 
         let generated_at = Utc::now();
         let document_id = Uuid::new_v4();
 
-        // Render content via template engine
-        let content = TemplateEngine::render(input, generated_at, version);
+        // Render content from the latest active version of the template
+        let locale = input
+            .locale
+            .as_deref()
+            .unwrap_or(will_template::DEFAULT_LOCALE);
+        let template = WillTemplateService::resolve(db, &input.template, locale).await?;
+        let content = TemplateEngine::render(&template, input, generated_at, version)?;
 
         // Compute document hash (SHA-256 over rendered text)
         let hash_bytes = digest(&SHA256, content.as_bytes());
         let will_hash = hex::encode(hash_bytes.as_ref());
 
-        // Build PDF bytes
-        let pdf_bytes = build_pdf(&content);
+        // Lay out the PDF; nobody has signed a freshly generated will yet
+        let ctx = PdfContext {
+            plan_id: input.plan_id,
+            owner_name: &input.owner_name,
+            title: &template.title,
+            will_hash: &will_hash,
+            generated_at,
+        };
+        let pdf_bytes = build_pdf(&ctx, &content, &Attestation::default())?;
         let pdf_base64 = BASE64.encode(&pdf_bytes);
 
         let filename = format!(
@@ -348,25 +346,40 @@ impl WillPdfService {
             generated_at.format("%Y%m%d%H%M%S")
         );
 
-        // Persist metadata
+        // Persist metadata and queue the on-chain mirror of the new version
         sqlx::query(
             r#"
             INSERT INTO will_documents
-                (id, plan_id, user_id, template, will_hash, version, filename, pdf_base64, generated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                (id, plan_id, user_id, template, will_hash, version, filename, pdf_base64,
+                 generated_at, rendered_content, owner_name, template_id, locale, terms)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
             "#,
         )
         .bind(document_id)
         .bind(input.plan_id)
         .bind(user_id)
-        .bind(input.template.as_str())
+        .bind(&template.template_key)
         .bind(&will_hash)
         .bind(version as i32)
         .bind(&filename)
         .bind(&pdf_base64)
         .bind(generated_at)
-        .execute(db)
+        .bind(&content)
+        .bind(&input.owner_name)
+        .bind(template.id)
+        .bind(&template.locale)
+        .bind(sqlx::types::Json(WillTerms::from_input(input)))
+        .execute(&mut *tx)
+        .await?;
+        crate::will_anchor::WillAnchorService::enqueue_generated(
+            &mut tx,
+            input.plan_id,
+            document_id,
+            version,
+            &will_hash,
+        )
         .await?;
+        tx.commit().await?;
 
         // Emit WillCreated event
         let event = crate::will_events::WillEvent::WillCreated {
@@ -374,7 +387,7 @@ impl WillPdfService {
             document_id,
             plan_id: input.plan_id,
             version,
-            template: input.template.as_str().to_string(),
+            template: template.template_key.clone(),
             will_hash: will_hash.clone(),
             timestamp: generated_at,
         };
@@ -385,7 +398,7 @@ impl WillPdfService {
         Ok(GeneratedWillDocument {
             document_id,
             plan_id: input.plan_id,
-            template_used: input.template.display_name().to_string(),
+            template_used: template.title.clone(),
             will_hash,
             generated_at,
             version,
@@ -394,6 +407,15 @@ impl WillPdfService {
         })
     }
 
+    /// Resolve the owner of a will document, for access via signed URLs.
+    pub async fn get_document_owner(db: &PgPool, document_id: Uuid) -> Result<Uuid, ApiError> {
+        sqlx::query_scalar("SELECT user_id FROM will_documents WHERE id = $1")
+            .bind(document_id)
+            .fetch_optional(db)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Will document {document_id} not found")))
+    }
+
     /// Retrieve a previously generated will document by ID.
     pub async fn get_document(
         db: &PgPool,
@@ -434,6 +456,79 @@ impl WillPdfService {
         })
     }
 
+    /// Re-render a will with the testator signatures and witness records
+    /// collected since it was generated. The will hash is unchanged because
+    /// it covers the will text, not the attestation page.
+    pub async fn render_attested(
+        db: &PgPool,
+        document_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<GeneratedWillDocument, ApiError> {
+        #[derive(sqlx::FromRow)]
+        struct Row {
+            plan_id: Uuid,
+            title: String,
+            will_hash: String,
+            version: i32,
+            generated_at: DateTime<Utc>,
+            rendered_content: Option<String>,
+            owner_name: Option<String>,
+        }
+
+        let row = sqlx::query_as::<_, Row>(
+            "SELECT d.plan_id, COALESCE(t.title, d.template) AS title, d.will_hash, d.version, \
+                    d.generated_at, d.rendered_content, d.owner_name \
+             FROM will_documents d LEFT JOIN will_templates t ON t.id = d.template_id \
+             WHERE d.id = $1 AND d.user_id = $2",
+        )
+        .bind(document_id)
+        .bind(user_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Will document {document_id} not found")))?;
+
+        let content = row.rendered_content.ok_or_else(|| {
+            ApiError::BadRequest(
+                "Will document predates attested rendering; generate a new version".to_string(),
+            )
+        })?;
+        let owner_name = row.owner_name.unwrap_or_default();
+
+        let attestation = Attestation {
+            testator_signatures: WillSignatureService::get_signatures_for_document(
+                db,
+                document_id,
+                user_id,
+            )
+            .await?,
+            witnesses: WitnessService::get_witnesses(db, user_id, document_id).await?,
+        };
+
+        let ctx = PdfContext {
+            plan_id: row.plan_id,
+            owner_name: &owner_name,
+            title: &row.title,
+            will_hash: &row.will_hash,
+            generated_at: row.generated_at,
+        };
+        let pdf_bytes = build_pdf(&ctx, &content, &attestation)?;
+
+        Ok(GeneratedWillDocument {
+            document_id,
+            plan_id: row.plan_id,
+            template_used: row.title,
+            will_hash: row.will_hash,
+            generated_at: row.generated_at,
+            version: row.version as u32,
+            pdf_base64: BASE64.encode(&pdf_bytes),
+            filename: format!(
+                "will_{}_{}_attested.pdf",
+                row.plan_id,
+                row.generated_at.format("%Y%m%d%H%M%S")
+            ),
+        })
+    }
+
     /// List all will documents for a plan.
     pub async fn list_for_plan(
         db: &PgPool,
@@ -484,7 +579,7 @@ mod tests {
     use super::*;
     use rust_decimal_macros::dec;
 
-    fn sample_input(template: WillTemplate) -> WillDocumentInput {
+    fn sample_input(template: &str) -> WillDocumentInput {
         WillDocumentInput {
             plan_id: Uuid::new_v4(),
             owner_name: "Alice Testator".to_string(),
@@ -497,16 +592,45 @@ mod tests {
                 relationship: Some("Son".to_string()),
             }],
             execution_rules: Some("Distribute after 90-day inactivity".to_string()),
-            template,
+            template: template.to_string(),
+            locale: None,
             jurisdiction: Some("Global".to_string()),
             will_hash_reference: Some("0xdeadbeef".to_string()),
+            template_fields: BTreeMap::from([
+                ("state".to_string(), "Ohio".to_string()),
+                ("county".to_string(), "Franklin".to_string()),
+            ]),
         }
     }
 
+    fn builtin(key: &str, locale: &str) -> WillTemplateRecord {
+        let t = will_template::BUILTIN_TEMPLATES
+            .iter()
+            .find(|t| t.key == key && t.locale == locale)
+            .unwrap();
+        WillTemplateRecord {
+            id: Uuid::nil(),
+            template_key: t.key.to_string(),
+            locale: t.locale.to_string(),
+            version: 1,
+            title: t.title.to_string(),
+            body: t.body.to_string(),
+            required_fields: t.required_fields.iter().map(|f| f.to_string()).collect(),
+            status: "active".to_string(),
+            created_by: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn render(input: &WillDocumentInput) -> String {
+        let locale = input.locale.as_deref().unwrap_or("en");
+        TemplateEngine::render(&builtin(&input.template, locale), input, Utc::now(), 1).unwrap()
+    }
+
     #[test]
     fn test_template_rendering_simple() {
-        let input = sample_input(WillTemplate::Simple);
-        let content = TemplateEngine::render(&input, Utc::now(), 1);
+        let input = sample_input("simple");
+        let content = render(&input);
         assert!(content.contains("Alice Testator"));
         assert!(content.contains("Bob Beneficiary"));
         assert!(content.contains("100"));
@@ -514,39 +638,58 @@ mod tests {
 
     #[test]
     fn test_template_rendering_formal() {
-        let input = sample_input(WillTemplate::Formal);
-        let content = TemplateEngine::render(&input, Utc::now(), 1);
+        let input = sample_input("formal");
+        let content = render(&input);
         assert!(content.contains("FORMAL LAST WILL"));
         assert!(content.contains("EXECUTION RULES"));
     }
 
     #[test]
     fn test_template_rendering_us() {
-        let input = sample_input(WillTemplate::UsJurisdiction);
-        let content = TemplateEngine::render(&input, Utc::now(), 1);
+        let input = sample_input("us_jurisdiction");
+        let content = render(&input);
         assert!(content.contains("US JURISDICTION"));
         assert!(content.contains("WITNESS CLAUSE"));
     }
 
     #[test]
     fn test_template_rendering_uk() {
-        let input = sample_input(WillTemplate::UkJurisdiction);
-        let content = TemplateEngine::render(&input, Utc::now(), 1);
+        let input = sample_input("uk_jurisdiction");
+        let content = render(&input);
         assert!(content.contains("Wills Act 1837"));
     }
 
     #[test]
     fn test_template_rendering_global() {
-        let input = sample_input(WillTemplate::GlobalGeneric);
-        let content = TemplateEngine::render(&input, Utc::now(), 1);
+        let input = sample_input("global_generic");
+        let content = render(&input);
         assert!(content.contains("GLOBAL GENERIC"));
     }
 
+    fn sample_pdf(input: &WillDocumentInput, content: &str, attestation: &Attestation) -> Vec<u8> {
+        let will_hash = "ab".repeat(32);
+        let ctx = PdfContext {
+            plan_id: input.plan_id,
+            owner_name: &input.owner_name,
+            title: &input.template,
+            will_hash: &will_hash,
+            generated_at: DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+        };
+        layout_pdf(&ctx, content, attestation, &PdfAssets::default())
+    }
+
+    fn contains_text(pdf: &[u8], text: &str) -> bool {
+        let needle = hex::encode_upper(pdf_layout::encode_win_ansi(text));
+        pdf.windows(needle.len()).any(|w| w == needle.as_bytes())
+    }
+
     #[test]
     fn test_pdf_bytes_start_with_pdf_header() {
-        let input = sample_input(WillTemplate::Simple);
-        let content = TemplateEngine::render(&input, Utc::now(), 1);
-        let pdf = build_pdf(&content);
+        let input = sample_input("simple");
+        let content = render(&input);
+        let pdf = sample_pdf(&input, &content, &Attestation::default());
         assert!(pdf.starts_with(b"%PDF-1.4"));
         assert!(pdf.ends_with(b"%%EOF\n"));
     }
@@ -560,22 +703,128 @@ mod tests {
     }
 
     #[test]
-    fn test_template_from_str() {
-        use std::str::FromStr;
-        assert!(matches!(
-            WillTemplate::from_str("formal"),
-            Ok(WillTemplate::Formal)
-        ));
-        assert!(WillTemplate::from_str("unknown").is_err());
+    fn test_required_template_fields() {
+        let mut input = sample_input("us_jurisdiction");
+        assert!(render(&input).contains("STATE OF Ohio, COUNTY OF Franklin"));
+
+        input.template_fields.remove("county");
+        let template = builtin("us_jurisdiction", "en");
+        assert!(TemplateEngine::render(&template, &input, Utc::now(), 1).is_err());
+    }
+
+    #[test]
+    fn test_localized_templates() {
+        let mut input = sample_input("formal");
+        for (locale, heading) in [
+            ("fr", "BÉNÉFICIAIRES"),
+            ("es", "BENEFICIARIOS"),
+            ("pt", "BENEFICIÁRIOS"),
+            ("sw", "WANUFAIKA"),
+        ] {
+            input.locale = Some(locale.to_string());
+            let content = render(&input);
+            assert!(content.contains(heading), "{locale}");
+            assert!(content.contains("Bob Beneficiary"), "{locale}");
+            assert!(!content.contains("{{"), "{locale}");
+        }
+    }
+
+    #[test]
+    fn test_rendering_is_deterministic() {
+        let template = builtin("formal", "en");
+        let generated_at = DateTime::parse_from_rfc3339("2026-01-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let a = sample_input("formal");
+        let mut b = a.clone();
+        b.beneficiaries[0].allocation_percent = dec!(100.000);
+
+        let first = TemplateEngine::render(&template, &a, generated_at, 3).unwrap();
+        let second = TemplateEngine::render(&template, &b, generated_at, 3).unwrap();
+        assert_eq!(first, second);
+        assert!(first.contains("Allocation: 100%\n"));
+        assert!(first.contains("Generated: 2026-01-01 12:00:00 UTC\n"));
     }
 
     #[test]
     fn test_pdf_base64_roundtrip() {
-        let input = sample_input(WillTemplate::Formal);
-        let content = TemplateEngine::render(&input, Utc::now(), 1);
-        let pdf = build_pdf(&content);
+        let input = sample_input("formal");
+        let content = render(&input);
+        let pdf = sample_pdf(&input, &content, &Attestation::default());
         let encoded = BASE64.encode(&pdf);
         let decoded = BASE64.decode(&encoded).unwrap();
         assert_eq!(pdf, decoded);
     }
+
+    #[test]
+    fn test_long_beneficiary_list_paginates_with_footers() {
+        let mut input = sample_input("formal");
+        input.beneficiaries = (0..80)
+            .map(|i| BeneficiaryEntry {
+                name: format!("Beneficiary {i}"),
+                wallet_address: format!("GWALLET{i:04}"),
+                allocation_percent: dec!(1.25),
+                relationship: None,
+            })
+            .collect();
+        let content = render(&input);
+        let pdf = sample_pdf(&input, &content, &Attestation::default());
+
+        assert!(contains_text(&pdf, "Beneficiary 79"));
+        assert!(contains_text(&pdf, "Page 1 of "));
+        assert!(contains_text(&pdf, &format!("Plan ID: {}", input.plan_id)));
+        let pages = pdf.windows(12).filter(|w| w == b"/Type /Page ").count();
+        assert!(pages > 2, "expected several pages, got {pages}");
+        assert!(contains_text(&pdf, &format!("Page {pages} of {pages}")));
+    }
+
+    #[test]
+    fn test_attestation_page_lists_witnesses() {
+        let input = sample_input("uk_jurisdiction");
+        let content = render(&input);
+
+        let unsigned = sample_pdf(&input, &content, &Attestation::default());
+        assert!(contains_text(&unsigned, "SIGNATURE AND ATTESTATION"));
+        assert!(contains_text(&unsigned, "Witness 2"));
+        assert!(!contains_text(&unsigned, "Witness 3"));
+
+        let witness = |i: u8| WitnessRecord {
+            id: Uuid::new_v4(),
+            document_id: Uuid::new_v4(),
+            wallet_address: Some(format!("GWITNESS{i}")),
+            email: None,
+            status: "signed".to_string(),
+            signature_hex: Some("beef".to_string()),
+            signed_at: Some(Utc::now()),
+            invited_at: Utc::now(),
+            invite_expires_at: None,
+            signing_key_type: Some("stellar".to_string()),
+            signer_public_key: Some(format!("GWITNESS{i}")),
+        };
+        let attested = sample_pdf(
+            &input,
+            &content,
+            &Attestation {
+                testator_signatures: vec![],
+                witnesses: (1..=3).map(witness).collect(),
+            },
+        );
+        assert!(contains_text(&attested, "Witness 3: GWITNESS3"));
+        assert!(contains_text(&attested, "Signature: beef"));
+    }
+
+    #[test]
+    fn test_pdf_metadata_carries_hash_and_is_deterministic() {
+        let input = sample_input("simple");
+        let content = render(&input);
+        let a = sample_pdf(&input, &content, &Attestation::default());
+        let b = sample_pdf(&input, &content, &Attestation::default());
+        assert_eq!(a, b);
+
+        let xmp_hash = format!("<inheritx:WillHash>{}</inheritx:WillHash>", "ab".repeat(32));
+        let xmp_plan = format!("<inheritx:PlanId>{}</inheritx:PlanId>", input.plan_id);
+        for needle in [xmp_hash, xmp_plan] {
+            assert!(a.windows(needle.len()).any(|w| w == needle.as_bytes()));
+        }
+    }
 }
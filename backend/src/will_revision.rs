@@ -0,0 +1,979 @@
+//! # Will Revocation, Codicils and Version Diffs
+//!
+//! Revoking a plan's will invalidates every finalized version (and any
+//! codicil drafts amending them) and queues `revoke_will` on-chain. A codicil
+//! is a separate document that amends named clauses of a finalized will;
+//! its terms are the base will's terms with the amendments applied, and it
+//! is signed and witnessed like any other document. Its text is rendered
+//! from the `codicil` template in the base will's locale, and it is queued
+//! on-chain as a new will version like any generated document.
+//!
+//! Every document records its [`WillTerms`], so any two versions can be
+//! compared beneficiary by beneficiary and clause by clause.
+
+use crate::api_error::ApiError;
+use crate::will_pdf::{
+    build_pdf, Attestation, BeneficiaryEntry, GeneratedWillDocument, PdfContext, WillDocumentInput,
+};
+use crate::will_template::{self, Context, Value, WillTemplateService};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::collections::{BTreeMap, BTreeSet};
+use uuid::Uuid;
+
+/// Clauses that choose presentation rather than terms; codicils cannot amend them
+const PRESENTATION_CLAUSES: &[&str] = &["template", "locale"];
+const MAX_CLAUSE_KEY_LEN: usize = 64;
+const MAX_CLAUSE_TEXT_LEN: usize = 10_000;
+
+// ─── Types ────────────────────────────────────────────────────────────────────
+
+/// What a will document states, independent of how it is worded
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WillTerms {
+    pub beneficiaries: Vec<BeneficiaryEntry>,
+    /// Clause key to text, e.g. `execution_rules` or `field.state`
+    pub clauses: BTreeMap<String, String>,
+}
+
+impl WillTerms {
+    pub fn from_input(input: &WillDocumentInput) -> Self {
+        let mut clauses = BTreeMap::new();
+        clauses.insert("template".to_string(), input.template.clone());
+        for (key, value) in [
+            ("locale", &input.locale),
+            ("jurisdiction", &input.jurisdiction),
+            ("execution_rules", &input.execution_rules),
+        ] {
+            if let Some(value) = value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+                clauses.insert(key.to_string(), value.to_string());
+            }
+        }
+        for (key, value) in &input.template_fields {
+            clauses.insert(format!("field.{key}"), value.trim().to_string());
+        }
+        Self {
+            beneficiaries: input.beneficiaries.clone(),
+            clauses,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BeneficiaryChange {
+    pub wallet_address: String,
+    pub change: ChangeKind,
+    pub before: Option<BeneficiaryEntry>,
+    pub after: Option<BeneficiaryEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AllocationChange {
+    pub wallet_address: String,
+    pub before_percent: Decimal,
+    pub after_percent: Decimal,
+    pub delta_percent: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClauseChange {
+    pub clause: String,
+    pub change: ChangeKind,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TextChange {
+    /// `added` or `removed`
+    pub op: &'static str,
+    /// 1-based line number in the version the line belongs to
+    pub line: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionRef {
+    pub document_id: Uuid,
+    pub version: u32,
+    pub kind: String,
+    pub status: String,
+    pub will_hash: String,
+    pub base_version: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WillVersionDiff {
+    pub plan_id: Uuid,
+    pub from: VersionRef,
+    pub to: VersionRef,
+    /// False when either version predates recorded terms; only the text
+    /// diff is then available
+    pub structured: bool,
+    pub beneficiaries: Vec<BeneficiaryChange>,
+    pub allocations: Vec<AllocationChange>,
+    pub clauses: Vec<ClauseChange>,
+    pub text: Vec<TextChange>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RevokeWillRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct WillRevocation {
+    pub id: Uuid,
+    pub plan_id: Uuid,
+    pub reason: Option<String>,
+    pub revoked_versions: Vec<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateCodicilRequest {
+    pub base_version: u32,
+    /// Clause key to its new text; `null` removes the clause
+    pub changes: BTreeMap<String, Option<String>>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct VersionDiffQuery {
+    pub from: u32,
+    pub to: u32,
+}
+
+// ─── Diffing ──────────────────────────────────────────────────────────────────
+
+/// Compare beneficiaries by wallet and clauses by key
+pub fn diff_terms(
+    from: &WillTerms,
+    to: &WillTerms,
+) -> (
+    Vec<BeneficiaryChange>,
+    Vec<AllocationChange>,
+    Vec<ClauseChange>,
+) {
+    let by_wallet = |terms: &WillTerms| -> BTreeMap<String, BeneficiaryEntry> {
+        terms
+            .beneficiaries
+            .iter()
+            .map(|b| (b.wallet_address.clone(), b.clone()))
+            .collect()
+    };
+    let (before, after) = (by_wallet(from), by_wallet(to));
+    let wallets: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+
+    let mut beneficiaries = Vec::new();
+    let mut allocations = Vec::new();
+    for wallet in wallets {
+        let (b, a) = (before.get(wallet), after.get(wallet));
+        let change = match (b, a) {
+            (None, Some(_)) => ChangeKind::Added,
+            (Some(_), None) => ChangeKind::Removed,
+            (Some(b), Some(a))
+                if b.name != a.name
+                    || b.relationship != a.relationship
+                    || b.allocation_percent != a.allocation_percent =>
+            {
+                ChangeKind::Modified
+            }
+            _ => continue,
+        };
+        let percent = |e: Option<&BeneficiaryEntry>| {
+            e.map(|e| e.allocation_percent.normalize())
+                .unwrap_or_default()
+        };
+        let (before_percent, after_percent) = (percent(b), percent(a));
+        if before_percent != after_percent {
+            allocations.push(AllocationChange {
+                wallet_address: wallet.clone(),
+                before_percent,
+                after_percent,
+                delta_percent: after_percent - before_percent,
+            });
+        }
+        beneficiaries.push(BeneficiaryChange {
+            wallet_address: wallet.clone(),
+            change,
+            before: b.cloned(),
+            after: a.cloned(),
+        });
+    }
+
+    let keys: BTreeSet<&String> = from.clauses.keys().chain(to.clauses.keys()).collect();
+    let clauses = keys
+        .into_iter()
+        .filter_map(|key| {
+            let (b, a) = (from.clauses.get(key), to.clauses.get(key));
+            let change = match (b, a) {
+                (None, Some(_)) => ChangeKind::Added,
+                (Some(_), None) => ChangeKind::Removed,
+                (Some(b), Some(a)) if b != a => ChangeKind::Modified,
+                _ => return None,
+            };
+            Some(ClauseChange {
+                clause: key.clone(),
+                change,
+                before: b.cloned(),
+                after: a.cloned(),
+            })
+        })
+        .collect();
+
+    (beneficiaries, allocations, clauses)
+}
+
+/// Line diff of two rendered documents via longest common subsequence
+pub fn diff_lines(from: &str, to: &str) -> Vec<TextChange> {
+    let a: Vec<&str> = from.lines().collect();
+    let b: Vec<&str> = to.lines().collect();
+
+    // lcs[i][j] = length of the LCS of a[i..] and b[j..]
+    let mut lcs = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut changes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            i += 1;
+            j += 1;
+        } else if j < b.len() && (i == a.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            changes.push(TextChange {
+                op: "added",
+                line: j + 1,
+                text: b[j].to_string(),
+            });
+            j += 1;
+        } else {
+            changes.push(TextChange {
+                op: "removed",
+                line: i + 1,
+                text: a[i].to_string(),
+            });
+            i += 1;
+        }
+    }
+    changes
+}
+
+// ─── Codicils ─────────────────────────────────────────────────────────────────
+
+fn validate_changes(changes: &BTreeMap<String, Option<String>>) -> Result<(), ApiError> {
+    if changes.is_empty() {
+        return Err(ApiError::BadRequest(
+            "A codicil must amend at least one clause".to_string(),
+        ));
+    }
+    for (key, text) in changes {
+        let valid_key = !key.is_empty()
+            && key.len() <= MAX_CLAUSE_KEY_LEN
+            && key.starts_with(|c: char| c.is_ascii_lowercase())
+            && key
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '.');
+        if !valid_key {
+            return Err(ApiError::BadRequest(format!(
+                "Invalid clause key '{key}': use lowercase letters, digits, '_' and '.'"
+            )));
+        }
+        if PRESENTATION_CLAUSES.contains(&key.as_str()) {
+            return Err(ApiError::BadRequest(format!(
+                "Clause '{key}' is presentation, not terms; generate a new version instead"
+            )));
+        }
+        if let Some(text) = text {
+            let len = text.trim().chars().count();
+            if len == 0 || len > MAX_CLAUSE_TEXT_LEN {
+                return Err(ApiError::BadRequest(format!(
+                    "Clause '{key}' must be between 1 and {MAX_CLAUSE_TEXT_LEN} characters"
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Apply a codicil's amendments, refusing ones that change nothing
+pub fn apply_changes(
+    terms: &WillTerms,
+    changes: &BTreeMap<String, Option<String>>,
+) -> Result<(WillTerms, Vec<ClauseChange>), ApiError> {
+    validate_changes(changes)?;
+    let mut amended = terms.clone();
+    let mut applied = Vec::new();
+    for (key, text) in changes {
+        let before = terms.clauses.get(key).cloned();
+        let after = text.as_deref().map(|t| t.trim().to_string());
+        let change = match (&before, &after) {
+            (None, None) => {
+                return Err(ApiError::BadRequest(format!(
+                    "Clause '{key}' does not exist in the will"
+                )))
+            }
+            (Some(b), Some(a)) if b == a => {
+                return Err(ApiError::BadRequest(format!(
+                    "Clause '{key}' already has that text"
+                )))
+            }
+            (None, Some(_)) => ChangeKind::Added,
+            (Some(_), None) => ChangeKind::Removed,
+            (Some(_), Some(_)) => ChangeKind::Modified,
+        };
+        match &after {
+            Some(a) => amended.clauses.insert(key.clone(), a.clone()),
+            None => amended.clauses.remove(key),
+        };
+        applied.push(ClauseChange {
+            clause: key.clone(),
+            change,
+            before,
+            after,
+        });
+    }
+    Ok((amended, applied))
+}
+
+struct CodicilText<'a> {
+    plan_id: Uuid,
+    owner_name: &'a str,
+    number: u32,
+    version: u32,
+    base_version: u32,
+    base_hash: &'a str,
+    base_generated_at: DateTime<Utc>,
+    generated_at: DateTime<Utc>,
+    reason: Option<&'a str>,
+    changes: &'a [ClauseChange],
+}
+
+/// The values a codicil template can refer to
+fn codicil_context(c: &CodicilText) -> Context {
+    let text = |s: &str| Value::Text(s.to_string());
+    let flag = |set: bool| text(if set { "yes" } else { "" });
+    let amendments = c
+        .changes
+        .iter()
+        .enumerate()
+        .map(|(i, change)| {
+            let after = change.after.as_deref();
+            let revoked = change.change == ChangeKind::Removed || after.is_none();
+            Context::from([
+                ("index".to_string(), text(&(i + 1).to_string())),
+                ("clause".to_string(), text(&change.clause)),
+                ("text".to_string(), text(after.unwrap_or_default())),
+                (
+                    "added".to_string(),
+                    flag(!revoked && change.change == ChangeKind::Added),
+                ),
+                (
+                    "replaced".to_string(),
+                    flag(!revoked && change.change == ChangeKind::Modified),
+                ),
+                ("revoked".to_string(), flag(revoked)),
+            ])
+        })
+        .collect();
+    Context::from([
+        ("plan_id".to_string(), text(&c.plan_id.to_string())),
+        ("owner_name".to_string(), text(c.owner_name)),
+        (
+            "generated_at".to_string(),
+            text(&c.generated_at.format("%Y-%m-%d %H:%M:%S UTC").to_string()),
+        ),
+        ("version".to_string(), text(&c.version.to_string())),
+        ("codicil_number".to_string(), text(&c.number.to_string())),
+        (
+            "base_version".to_string(),
+            text(&c.base_version.to_string()),
+        ),
+        (
+            "base_date".to_string(),
+            text(&c.base_generated_at.format("%Y-%m-%d").to_string()),
+        ),
+        ("base_hash".to_string(), text(c.base_hash)),
+        ("reason".to_string(), text(c.reason.unwrap_or_default())),
+        (
+            will_template::AMENDMENTS_FIELD.to_string(),
+            Value::List(amendments),
+        ),
+    ])
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+// ─── Service ──────────────────────────────────────────────────────────────────
+
+pub struct WillRevisionService;
+
+impl WillRevisionService {
+    /// Revoke every finalized version of a plan's will, along with draft
+    /// codicils that amend them.
+    pub async fn revoke(
+        db: &PgPool,
+        plan_id: Uuid,
+        user_id: Uuid,
+        reason: Option<String>,
+    ) -> Result<WillRevocation, ApiError> {
+        let reason = reason
+            .map(|r| r.trim().to_string())
+            .filter(|r| !r.is_empty());
+        let mut tx = db.begin().await?;
+
+        let finalized: Vec<(Uuid, i32, String)> = sqlx::query_as(
+            "SELECT id, version, kind FROM will_documents \
+             WHERE plan_id = $1 AND user_id = $2 AND status = 'finalized' \
+             ORDER BY version FOR UPDATE",
+        )
+        .bind(plan_id)
+        .bind(user_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        if finalized.is_empty() {
+            return Err(ApiError::BadRequest(format!(
+                "Plan {plan_id} has no finalized will to revoke"
+            )));
+        }
+        let finalized_versions: Vec<i32> = finalized.iter().map(|(_, v, _)| *v).collect();
+
+        let revocation_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO will_revocations (id, plan_id, user_id, reason, revoked_versions) \
+             VALUES ($1, $2, $3, $4, '{}')",
+        )
+        .bind(revocation_id)
+        .bind(plan_id)
+        .bind(user_id)
+        .bind(&reason)
+        .execute(&mut *tx)
+        .await?;
+
+        let mut revoked_versions: Vec<i32> = sqlx::query_scalar(
+            "UPDATE will_documents \
+             SET status = 'revoked', revoked_at = NOW(), revocation_id = $3 \
+             WHERE plan_id = $1 AND user_id = $2 \
+               AND (status = 'finalized' \
+                    OR (kind = 'codicil' AND status = 'draft' AND base_version = ANY($4))) \
+             RETURNING version",
+        )
+        .bind(plan_id)
+        .bind(user_id)
+        .bind(revocation_id)
+        .bind(&finalized_versions)
+        .fetch_all(&mut *tx)
+        .await?;
+        revoked_versions.sort_unstable();
+
+        let revocation = sqlx::query_as::<_, WillRevocation>(
+            "UPDATE will_revocations SET revoked_versions = $2 WHERE id = $1 \
+             RETURNING id, plan_id, reason, revoked_versions, created_at",
+        )
+        .bind(revocation_id)
+        .bind(&revoked_versions)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // The latest finalized will stands for the revocation on-chain and in
+        // the event log
+        let (document_id, version) = finalized
+            .iter()
+            .rev()
+            .find(|(_, _, kind)| kind == "will")
+            .or(finalized.last())
+            .map(|(id, v, _)| (*id, *v as u32))
+            .unwrap_or_default();
+        crate::will_anchor::WillAnchorService::enqueue_revocation(
+            &mut tx,
+            plan_id,
+            document_id,
+            version,
+        )
+        .await?;
+        tx.commit().await?;
+
+        let vault_id: Option<String> =
+            sqlx::query_scalar("SELECT COALESCE(title, id::text) FROM plans WHERE id = $1")
+                .bind(plan_id)
+                .fetch_optional(db)
+                .await?;
+        if let Some(vault_id) = vault_id {
+            let event = crate::will_events::WillEvent::WillRevoked {
+                vault_id,
+                document_id,
+                plan_id,
+                revocation_id,
+                versions: revoked_versions.iter().map(|v| *v as u32).collect(),
+                reason: reason.clone(),
+                timestamp: revocation.created_at,
+            };
+            if let Err(e) = crate::will_events::WillEventService::emit(db, event).await {
+                tracing::warn!("Failed to emit WillRevoked event: {}", e);
+            }
+        }
+
+        Ok(revocation)
+    }
+
+    /// Revocations of a plan's wills, newest first
+    pub async fn list_revocations(
+        db: &PgPool,
+        plan_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Vec<WillRevocation>, ApiError> {
+        let revocations = sqlx::query_as::<_, WillRevocation>(
+            "SELECT id, plan_id, reason, revoked_versions, created_at FROM will_revocations \
+             WHERE plan_id = $1 AND user_id = $2 ORDER BY created_at DESC",
+        )
+        .bind(plan_id)
+        .bind(user_id)
+        .fetch_all(db)
+        .await?;
+        Ok(revocations)
+    }
+
+    /// Draft a codicil amending clauses of a finalized will. Earlier
+    /// finalized codicils to the same will are carried forward.
+    pub async fn create_codicil(
+        db: &PgPool,
+        plan_id: Uuid,
+        user_id: Uuid,
+        req: &CreateCodicilRequest,
+    ) -> Result<GeneratedWillDocument, ApiError> {
+        #[derive(sqlx::FromRow)]
+        struct BaseRow {
+            kind: String,
+            status: String,
+            will_hash: String,
+            locale: Option<String>,
+            owner_name: Option<String>,
+            generated_at: DateTime<Utc>,
+            terms: Option<sqlx::types::Json<WillTerms>>,
+        }
+
+        // The plan row lock serializes version numbers across concurrent
+        // codicils and wills
+        let mut tx = db.begin().await?;
+        sqlx::query("SELECT id FROM plans WHERE id = $1 AND user_id = $2 FOR UPDATE")
+            .bind(plan_id)
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Plan {plan_id} not found")))?;
+
+        let base = sqlx::query_as::<_, BaseRow>(
+            "SELECT kind, status, will_hash, locale, owner_name, \
+                    generated_at, terms \
+             FROM will_documents WHERE plan_id = $1 AND user_id = $2 AND version = $3",
+        )
+        .bind(plan_id)
+        .bind(user_id)
+        .bind(req.base_version as i32)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| {
+            ApiError::NotFound(format!("Will version {} not found", req.base_version))
+        })?;
+
+        if base.kind != "will" {
+            return Err(ApiError::BadRequest(
+                "A codicil must amend a will, not another codicil".to_string(),
+            ));
+        }
+        if base.status != "finalized" {
+            return Err(ApiError::BadRequest(format!(
+                "Will version {} is {}; only finalized wills can be amended",
+                req.base_version, base.status
+            )));
+        }
+        let base_terms = base.terms.map(|t| t.0).ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "Will version {} predates recorded terms; generate a new version instead",
+                req.base_version
+            ))
+        })?;
+
+        let (prior, latest_terms): (i64, Option<sqlx::types::Json<WillTerms>>) = sqlx::query_as(
+            "SELECT COUNT(*), \
+                    (SELECT terms FROM will_documents \
+                     WHERE plan_id = $1 AND kind = 'codicil' AND base_version = $2 \
+                       AND status = 'finalized' \
+                     ORDER BY version DESC LIMIT 1) \
+             FROM will_documents WHERE plan_id = $1 AND kind = 'codicil' AND base_version = $2",
+        )
+        .bind(plan_id)
+        .bind(req.base_version as i32)
+        .fetch_one(&mut *tx)
+        .await?;
+        let current = latest_terms.map(|t| t.0).unwrap_or(base_terms);
+        let (terms, applied) = apply_changes(&current, &req.changes)?;
+
+        let version: i32 = sqlx::query_scalar(
+            "SELECT COALESCE(MAX(version), 0) FROM will_documents WHERE plan_id = $1",
+        )
+        .bind(plan_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        let version = (version + 1) as u32;
+
+        let owner_name = base.owner_name.unwrap_or_default();
+        let generated_at = Utc::now();
+        let reason = req
+            .reason
+            .as_deref()
+            .map(str::trim)
+            .filter(|r| !r.is_empty());
+        let number = prior as u32 + 1;
+        let locale = base
+            .locale
+            .as_deref()
+            .unwrap_or(will_template::DEFAULT_LOCALE);
+        let template =
+            WillTemplateService::resolve(db, will_template::CODICIL_TEMPLATE, locale).await?;
+        let content = template.render(&codicil_context(&CodicilText {
+            plan_id,
+            owner_name: &owner_name,
+            number,
+            version,
+            base_version: req.base_version,
+            base_hash: &base.will_hash,
+            base_generated_at: base.generated_at,
+            generated_at,
+            reason,
+            changes: &applied,
+        }))?;
+        let will_hash = sha256_hex(content.as_bytes());
+
+        let ctx = PdfContext {
+            plan_id,
+            owner_name: &owner_name,
+            title: &template.title,
+            will_hash: &will_hash,
+            generated_at,
+        };
+        let pdf_base64 = BASE64.encode(build_pdf(&ctx, &content, &Attestation::default())?);
+        let filename = format!(
+            "codicil_{}_{}.pdf",
+            plan_id,
+            generated_at.format("%Y%m%d%H%M%S")
+        );
+        let document_id = Uuid::new_v4();
+
+        // Persist the codicil on the transaction that allocated its version.
+        // Codicils are not anchored; see `WillAnchorService::enqueue`.
+        sqlx::query(
+            r#"
+            INSERT INTO will_documents
+                (id, plan_id, user_id, template, will_hash, version, filename, pdf_base64,
+                 generated_at, rendered_content, owner_name, template_id, locale,
+                 kind, base_version, terms, codicil_changes)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13,
+                    'codicil', $14, $15, $16)
+            "#,
+        )
+        .bind(document_id)
+        .bind(plan_id)
+        .bind(user_id)
+        .bind(&template.template_key)
+        .bind(&will_hash)
+        .bind(version as i32)
+        .bind(&filename)
+        .bind(&pdf_base64)
+        .bind(generated_at)
+        .bind(&content)
+        .bind(&owner_name)
+        .bind(template.id)
+        .bind(&template.locale)
+        .bind(req.base_version as i32)
+        .bind(sqlx::types::Json(&terms))
+        .bind(sqlx::types::Json(&applied))
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        let vault_id: Option<String> =
+            sqlx::query_scalar("SELECT COALESCE(title, id::text) FROM plans WHERE id = $1")
+                .bind(plan_id)
+                .fetch_optional(db)
+                .await?;
+        if let Some(vault_id) = vault_id {
+            let event = crate::will_events::WillEvent::WillUpdated {
+                vault_id,
+                document_id,
+                plan_id,
+                version,
+                previous_version: Some(req.base_version),
+                will_hash: will_hash.clone(),
+                timestamp: generated_at,
+            };
+            if let Err(e) = crate::will_events::WillEventService::emit(db, event).await {
+                tracing::warn!("Failed to emit WillUpdated event: {}", e);
+            }
+        }
+
+        Ok(GeneratedWillDocument {
+            document_id,
+            plan_id,
+            template_used: template.title.clone(),
+            will_hash,
+            generated_at,
+            version,
+            pdf_base64,
+            filename,
+        })
+    }
+
+    /// Compare two versions of a plan's will
+    pub async fn diff(
+        db: &PgPool,
+        plan_id: Uuid,
+        user_id: Uuid,
+        from: u32,
+        to: u32,
+    ) -> Result<WillVersionDiff, ApiError> {
+        #[derive(sqlx::FromRow)]
+        struct Row {
+            id: Uuid,
+            version: i32,
+            kind: String,
+            status: String,
+            will_hash: String,
+            base_version: Option<i32>,
+            rendered_content: Option<String>,
+            terms: Option<sqlx::types::Json<WillTerms>>,
+        }
+
+        let fetch = |version: u32| async move {
+            sqlx::query_as::<_, Row>(
+                "SELECT id, version, kind, status, will_hash, base_version, rendered_content, \
+                        terms \
+                 FROM will_documents WHERE plan_id = $1 AND user_id = $2 AND version = $3",
+            )
+            .bind(plan_id)
+            .bind(user_id)
+            .bind(version as i32)
+            .fetch_optional(db)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Will version {version} not found")))
+        };
+        let (a, b) = (fetch(from).await?, fetch(to).await?);
+
+        let version_ref = |r: &Row| VersionRef {
+            document_id: r.id,
+            version: r.version as u32,
+            kind: r.kind.clone(),
+            status: r.status.clone(),
+            will_hash: r.will_hash.clone(),
+            base_version: r.base_version.map(|v| v as u32),
+        };
+        let (from_ref, to_ref) = (version_ref(&a), version_ref(&b));
+
+        let (structured, (beneficiaries, allocations, clauses)) = match (&a.terms, &b.terms) {
+            (Some(x), Some(y)) => (true, diff_terms(x, y)),
+            _ => (false, Default::default()),
+        };
+        let text = diff_lines(
+            a.rendered_content.as_deref().unwrap_or_default(),
+            b.rendered_content.as_deref().unwrap_or_default(),
+        );
+
+        Ok(WillVersionDiff {
+            plan_id,
+            from: from_ref,
+            to: to_ref,
+            structured,
+            beneficiaries,
+            allocations,
+            clauses,
+            text,
+        })
+    }
+}
+
+// ─── Unit Tests ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn beneficiary(wallet: &str, name: &str, percent: Decimal) -> BeneficiaryEntry {
+        BeneficiaryEntry {
+            name: name.to_string(),
+            wallet_address: wallet.to_string(),
+            allocation_percent: percent,
+            relationship: None,
+        }
+    }
+
+    fn terms(beneficiaries: Vec<BeneficiaryEntry>, clauses: &[(&str, &str)]) -> WillTerms {
+        WillTerms {
+            beneficiaries,
+            clauses: clauses
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn diff_reports_beneficiary_allocation_and_clause_changes() {
+        let from = terms(
+            vec![
+                beneficiary("GA", "Ann", dec!(50)),
+                beneficiary("GB", "Ben", dec!(50)),
+            ],
+            &[("template", "formal"), ("execution_rules", "After 90 days")],
+        );
+        let to = terms(
+            vec![
+                beneficiary("GA", "Ann", dec!(60.00)),
+                beneficiary("GC", "Cat", dec!(40)),
+            ],
+            &[("template", "formal"), ("jurisdiction", "UK")],
+        );
+
+        let (beneficiaries, allocations, clauses) = diff_terms(&from, &to);
+        let kinds: Vec<(&str, ChangeKind)> = beneficiaries
+            .iter()
+            .map(|c| (c.wallet_address.as_str(), c.change))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("GA", ChangeKind::Modified),
+                ("GB", ChangeKind::Removed),
+                ("GC", ChangeKind::Added)
+            ]
+        );
+        assert_eq!(allocations[0].delta_percent, dec!(10));
+        assert_eq!(allocations[1].after_percent, Decimal::ZERO);
+        assert_eq!(allocations.len(), 3);
+
+        let clause_kinds: Vec<(&str, ChangeKind)> = clauses
+            .iter()
+            .map(|c| (c.clause.as_str(), c.change))
+            .collect();
+        assert_eq!(
+            clause_kinds,
+            vec![
+                ("execution_rules", ChangeKind::Removed),
+                ("jurisdiction", ChangeKind::Added)
+            ]
+        );
+
+        // Re-normalised allocations are not a change
+        let same = terms(vec![beneficiary("GA", "Ann", dec!(50.0))], &[]);
+        let (b, a, c) = diff_terms(&same, &terms(vec![beneficiary("GA", "Ann", dec!(50))], &[]));
+        assert!(b.is_empty() && a.is_empty() && c.is_empty());
+    }
+
+    #[test]
+    fn line_diff_marks_added_and_removed_lines() {
+        let changes = diff_lines("a\nb\nc\n", "a\nc\nd\n");
+        assert_eq!(
+            changes,
+            vec![
+                TextChange {
+                    op: "removed",
+                    line: 2,
+                    text: "b".to_string()
+                },
+                TextChange {
+                    op: "added",
+                    line: 3,
+                    text: "d".to_string()
+                },
+            ]
+        );
+        assert!(diff_lines("same\n", "same\n").is_empty());
+    }
+
+    #[test]
+    fn codicil_changes_are_validated_and_applied() {
+        let base = terms(
+            vec![],
+            &[("template", "formal"), ("execution_rules", "After 90 days")],
+        );
+        let changes = BTreeMap::from([
+            (
+                "execution_rules".to_string(),
+                Some(" After 30 days ".to_string()),
+            ),
+            ("guardianship".to_string(), Some("My sister Jo".to_string())),
+        ]);
+        let (amended, applied) = apply_changes(&base, &changes).unwrap();
+        assert_eq!(amended.clauses["execution_rules"], "After 30 days");
+        assert_eq!(applied[0].change, ChangeKind::Modified);
+        assert_eq!(applied[1].change, ChangeKind::Added);
+
+        let context = codicil_context(&CodicilText {
+            plan_id: Uuid::nil(),
+            owner_name: "Alice",
+            number: 1,
+            version: 3,
+            base_version: 2,
+            base_hash: "ab",
+            base_generated_at: Utc::now(),
+            generated_at: Utc::now(),
+            reason: None,
+            changes: &applied,
+        });
+        let render = |locale: &str| {
+            let t = will_template::BUILTIN_TEMPLATES
+                .iter()
+                .find(|t| t.key == will_template::CODICIL_TEMPLATE && t.locale == locale)
+                .unwrap();
+            will_template::CompiledTemplate::parse(t.body)
+                .unwrap()
+                .render(&context)
+        };
+        let text = render("en");
+        assert!(text.contains(
+            "1. Clause 'execution_rules' is replaced in its entirety with:\n   After 30 days\n"
+        ));
+        assert!(text.contains("2. The following clause 'guardianship' is added:"));
+        assert!(text.contains("version 2"));
+        assert!(!text.contains("REASON"));
+        let text = render("fr");
+        assert!(text.contains("La clause 'execution_rules' est intégralement remplacée par:"));
+        assert!(!text.contains("{{"));
+
+        let reject = |key: &str, text: Option<&str>| {
+            apply_changes(
+                &base,
+                &BTreeMap::from([(key.to_string(), text.map(str::to_string))]),
+            )
+            .is_err()
+        };
+        assert!(reject("template", Some("simple")));
+        assert!(reject("Bad Key", Some("x")));
+        assert!(reject("missing", None));
+        assert!(reject("execution_rules", Some("After 90 days")));
+        assert!(reject("execution_rules", Some("   ")));
+        assert!(apply_changes(&base, &BTreeMap::new()).is_err());
+    }
+}
@@ -62,15 +62,20 @@ impl WillSignatureService {
         req: &SigningChallengeRequest,
     ) -> Result<SigningChallenge, ApiError> {
         // Fetch document hash from DB
-        let doc_hash: Option<String> =
-            sqlx::query_scalar("SELECT will_hash FROM will_documents WHERE id = $1")
+        let doc: Option<(String, String)> =
+            sqlx::query_as("SELECT will_hash, status FROM will_documents WHERE id = $1")
                 .bind(req.document_id)
                 .fetch_optional(db)
                 .await?;
 
-        let document_hash = doc_hash.ok_or_else(|| {
+        let (document_hash, status) = doc.ok_or_else(|| {
             ApiError::NotFound(format!("Will document {} not found", req.document_id))
         })?;
+        if status == "revoked" {
+            return Err(ApiError::BadRequest(
+                "Will document has been revoked".to_string(),
+            ));
+        }
 
         let challenge_id = Uuid::new_v4();
         let nonce = Uuid::new_v4().to_string();
@@ -200,6 +205,12 @@ impl WillSignatureService {
         .bind(signed_at)
         .execute(&mut *tx)
         .await?;
+        crate::will_anchor::WillAnchorService::enqueue_owner_signature(
+            &mut tx,
+            row.document_id,
+            &req.wallet_address,
+        )
+        .await?;
 
         tx.commit().await?;
 
@@ -293,7 +304,7 @@ impl WillSignatureService {
     }
 
     /// Decode a Stellar G-address or hex string into 32 raw public key bytes.
-    fn decode_public_key(wallet_address: &str) -> Result<[u8; 32], ApiError> {
+    pub(crate) fn decode_public_key(wallet_address: &str) -> Result<[u8; 32], ApiError> {
         if wallet_address.starts_with('G') && wallet_address.len() == 56 {
             let strkey = Strkey::from_string(wallet_address)
                 .map_err(|_| ApiError::BadRequest("Invalid Stellar address".to_string()))?;
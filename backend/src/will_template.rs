@@ -0,0 +1,867 @@
+//! # Will Templates
+//!
+//! Will text is rendered from templates stored in `will_templates`, one row
+//! per template key, locale and version. Versions are immutable: editing a
+//! template creates a new version, so every generated document can be
+//! re-rendered byte-for-byte from the version it references.
+//!
+//! Templates use a logic-less, Mustache-style syntax:
+//!
+//! - `{{field}}` inserts a value (missing values render as empty text)
+//! - `{{#field}}…{{/field}}` renders once for a non-empty value, or once per
+//!   item for a list such as `beneficiaries`
+//! - `{{^field}}…{{/field}}` renders only when the value is missing or empty
+//! - `{{! comment}}` is ignored
+//!
+//! A line holding nothing but a section or comment tag is removed entirely,
+//! so templates can put tags on their own lines; tags may not span lines.
+//! Rendering has no access to the clock or any other ambient state, which
+//! keeps `will_hash` reproducible.
+
+use crate::api_error::ApiError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::{BTreeMap, BTreeSet};
+use uuid::Uuid;
+
+/// Locale used when a request does not specify one
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Fields every will context provides
+pub const BUILTIN_FIELDS: [&str; 9] = [
+    "plan_id",
+    "owner_name",
+    "owner_wallet",
+    "vault_id",
+    "generated_at",
+    "version",
+    "jurisdiction",
+    "execution_rules",
+    "will_hash_reference",
+];
+
+/// List of beneficiaries and the fields of each item
+pub const BENEFICIARIES_FIELD: &str = "beneficiaries";
+pub const BENEFICIARY_FIELDS: [&str; 5] = [
+    "index",
+    "name",
+    "wallet_address",
+    "allocation_percent",
+    "relationship",
+];
+
+/// Template key codicils are rendered from, and the fields their context adds
+pub const CODICIL_TEMPLATE: &str = "codicil";
+pub const CODICIL_FIELDS: [&str; 5] = [
+    "codicil_number",
+    "base_version",
+    "base_date",
+    "base_hash",
+    "reason",
+];
+
+/// List of a codicil's amendments and the fields of each item. Exactly one
+/// of `added`, `replaced` and `revoked` is non-empty.
+pub const AMENDMENTS_FIELD: &str = "amendments";
+pub const AMENDMENT_FIELDS: [&str; 6] = ["index", "clause", "text", "added", "replaced", "revoked"];
+
+// ─── Template Syntax ──────────────────────────────────────────────────────────
+
+/// A value available to a template
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Text(String),
+    List(Vec<Context>),
+}
+
+impl Value {
+    fn is_empty(&self) -> bool {
+        match self {
+            Value::Text(s) => s.is_empty(),
+            Value::List(items) => items.is_empty(),
+        }
+    }
+}
+
+/// Named values; ordered so rendering never depends on hash iteration
+pub type Context = BTreeMap<String, Value>;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Text(String),
+    Var(String),
+    Section {
+        name: String,
+        inverted: bool,
+        children: Vec<Node>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Text(String),
+    Var(String),
+    Open(String),
+    Inverted(String),
+    Close(String),
+    Comment,
+}
+
+impl Token {
+    /// Tags that produce no output and may stand alone on a line
+    fn is_standalone(&self) -> bool {
+        matches!(
+            self,
+            Token::Open(_) | Token::Inverted(_) | Token::Close(_) | Token::Comment
+        )
+    }
+}
+
+fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+fn parse_tag(tag: &str) -> Result<Token, String> {
+    let tag = tag.trim();
+    match tag.chars().next() {
+        Some('!') => Ok(Token::Comment),
+        Some(sigil @ ('#' | '^' | '/')) => {
+            let name = tag[1..].trim().to_string();
+            if !valid_name(&name) {
+                return Err(format!("invalid field name in '{{{{{tag}}}}}'"));
+            }
+            Ok(match sigil {
+                '#' => Token::Open(name),
+                '^' => Token::Inverted(name),
+                _ => Token::Close(name),
+            })
+        }
+        _ if valid_name(tag) => Ok(Token::Var(tag.to_string())),
+        _ => Err(format!("invalid tag '{{{{{tag}}}}}'")),
+    }
+}
+
+/// Split a template into tokens. Tags may not span lines, and a line
+/// holding nothing but a section or comment tag yields just that tag.
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    for (number, line) in source.split_inclusive('\n').enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("{{")
+            && trimmed.ends_with("}}")
+            && trimmed.matches("{{").count() == 1
+        {
+            let token = parse_tag(&trimmed[2..trimmed.len() - 2])
+                .map_err(|e| format!("line {}: {e}", number + 1))?;
+            if token.is_standalone() {
+                if !text.is_empty() {
+                    tokens.push(Token::Text(std::mem::take(&mut text)));
+                }
+                tokens.push(token);
+                continue;
+            }
+        }
+
+        let mut rest = line;
+        while let Some(start) = rest.find("{{") {
+            text.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            let end = after
+                .find("}}")
+                .ok_or_else(|| format!("line {}: unterminated tag '{{{{'", number + 1))?;
+            let token =
+                parse_tag(&after[..end]).map_err(|e| format!("line {}: {e}", number + 1))?;
+            if !text.is_empty() {
+                tokens.push(Token::Text(std::mem::take(&mut text)));
+            }
+            tokens.push(token);
+            rest = &after[end + 2..];
+        }
+        text.push_str(rest);
+    }
+    if !text.is_empty() {
+        tokens.push(Token::Text(text));
+    }
+    Ok(tokens)
+}
+
+fn build_tree(
+    tokens: &mut std::vec::IntoIter<Token>,
+    open: Option<&str>,
+) -> Result<Vec<Node>, String> {
+    let mut nodes = Vec::new();
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Text(t) => nodes.push(Node::Text(t)),
+            Token::Var(name) => nodes.push(Node::Var(name)),
+            Token::Comment => {}
+            Token::Open(name) => {
+                let children = build_tree(tokens, Some(&name))?;
+                nodes.push(Node::Section {
+                    name,
+                    inverted: false,
+                    children,
+                });
+            }
+            Token::Inverted(name) => {
+                let children = build_tree(tokens, Some(&name))?;
+                nodes.push(Node::Section {
+                    name,
+                    inverted: true,
+                    children,
+                });
+            }
+            Token::Close(name) => {
+                return match open {
+                    Some(expected) if expected == name => Ok(nodes),
+                    Some(expected) => {
+                        Err(format!("section '{expected}' closed by '{{{{/{name}}}}}'"))
+                    }
+                    None => Err(format!("unexpected '{{{{/{name}}}}}'")),
+                };
+            }
+        }
+    }
+    match open {
+        Some(name) => Err(format!("section '{name}' is never closed")),
+        None => Ok(nodes),
+    }
+}
+
+/// A parsed template
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompiledTemplate {
+    nodes: Vec<Node>,
+}
+
+impl CompiledTemplate {
+    pub fn parse(source: &str) -> Result<Self, ApiError> {
+        let source = source.replace("\r\n", "\n");
+        let tokens =
+            tokenize(&source).map_err(|e| ApiError::BadRequest(format!("Template error: {e}")))?;
+        let nodes = build_tree(&mut tokens.into_iter(), None)
+            .map_err(|e| ApiError::BadRequest(format!("Template error: {e}")))?;
+        Ok(Self { nodes })
+    }
+
+    /// Every field name the template refers to
+    pub fn referenced_fields(&self) -> BTreeSet<String> {
+        fn walk(nodes: &[Node], out: &mut BTreeSet<String>) {
+            for node in nodes {
+                match node {
+                    Node::Text(_) => {}
+                    Node::Var(name) => {
+                        out.insert(name.clone());
+                    }
+                    Node::Section { name, children, .. } => {
+                        out.insert(name.clone());
+                        walk(children, out);
+                    }
+                }
+            }
+        }
+        let mut out = BTreeSet::new();
+        walk(&self.nodes, &mut out);
+        out
+    }
+
+    pub fn render(&self, context: &Context) -> String {
+        let mut out = String::new();
+        render_nodes(&self.nodes, &mut vec![context], &mut out);
+        out
+    }
+}
+
+fn lookup<'a>(stack: &[&'a Context], name: &str) -> Option<&'a Value> {
+    stack.iter().rev().find_map(|ctx| ctx.get(name))
+}
+
+fn render_nodes<'a>(nodes: &'a [Node], stack: &mut Vec<&'a Context>, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(t) => out.push_str(t),
+            Node::Var(name) => {
+                if let Some(Value::Text(v)) = lookup(stack, name) {
+                    out.push_str(v);
+                }
+            }
+            Node::Section {
+                name,
+                inverted,
+                children,
+            } => {
+                let value = lookup(stack, name).filter(|v| !v.is_empty());
+                match (value, inverted) {
+                    (None, true) => render_nodes(children, stack, out),
+                    (Some(Value::Text(_)), false) => render_nodes(children, stack, out),
+                    (Some(Value::List(items)), false) => {
+                        for item in items {
+                            stack.push(item);
+                            render_nodes(children, stack, out);
+                            stack.pop();
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+// ─── Bundled Templates ────────────────────────────────────────────────────────
+
+/// A template shipped with the backend and seeded as version 1
+pub struct BuiltinTemplate {
+    pub key: &'static str,
+    pub locale: &'static str,
+    pub title: &'static str,
+    pub required_fields: &'static [&'static str],
+    pub body: &'static str,
+}
+
+const COMMON_REQUIRED: &[&str] = &["owner_name", "owner_wallet", "beneficiaries"];
+const CODICIL_REQUIRED: &[&str] = &["owner_name", "amendments"];
+const US_REQUIRED: &[&str] = &[
+    "owner_name",
+    "owner_wallet",
+    "beneficiaries",
+    "state",
+    "county",
+];
+
+macro_rules! builtin {
+    ($key:literal, $locale:literal, $title:literal, $required:expr) => {
+        BuiltinTemplate {
+            key: $key,
+            locale: $locale,
+            title: $title,
+            required_fields: $required,
+            body: include_str!(concat!("../templates/wills/", $locale, "/", $key, ".txt")),
+        }
+    };
+}
+
+pub const BUILTIN_TEMPLATES: &[BuiltinTemplate] = &[
+    builtin!("simple", "en", "Simple Will", COMMON_REQUIRED),
+    builtin!("formal", "en", "Formal Legal Will", COMMON_REQUIRED),
+    builtin!("us_jurisdiction", "en", "US Jurisdiction Will", US_REQUIRED),
+    builtin!(
+        "uk_jurisdiction",
+        "en",
+        "UK Jurisdiction Will",
+        COMMON_REQUIRED
+    ),
+    builtin!(
+        "global_generic",
+        "en",
+        "Global Generic Will",
+        COMMON_REQUIRED
+    ),
+    builtin!("codicil", "en", "Codicil", CODICIL_REQUIRED),
+    builtin!("simple", "fr", "Testament simple", COMMON_REQUIRED),
+    builtin!("formal", "fr", "Testament solennel", COMMON_REQUIRED),
+    builtin!(
+        "us_jurisdiction",
+        "fr",
+        "Testament – juridiction américaine",
+        US_REQUIRED
+    ),
+    builtin!(
+        "uk_jurisdiction",
+        "fr",
+        "Testament – juridiction britannique",
+        COMMON_REQUIRED
+    ),
+    builtin!(
+        "global_generic",
+        "fr",
+        "Testament international",
+        COMMON_REQUIRED
+    ),
+    builtin!("codicil", "fr", "Codicille", CODICIL_REQUIRED),
+    builtin!("simple", "es", "Testamento simple", COMMON_REQUIRED),
+    builtin!("formal", "es", "Testamento formal", COMMON_REQUIRED),
+    builtin!(
+        "us_jurisdiction",
+        "es",
+        "Testamento – jurisdicción de EE. UU.",
+        US_REQUIRED
+    ),
+    builtin!(
+        "uk_jurisdiction",
+        "es",
+        "Testamento – jurisdicción del Reino Unido",
+        COMMON_REQUIRED
+    ),
+    builtin!(
+        "global_generic",
+        "es",
+        "Testamento internacional",
+        COMMON_REQUIRED
+    ),
+    builtin!("codicil", "es", "Codicilo", CODICIL_REQUIRED),
+    builtin!("simple", "pt", "Testamento simples", COMMON_REQUIRED),
+    builtin!("formal", "pt", "Testamento formal", COMMON_REQUIRED),
+    builtin!(
+        "us_jurisdiction",
+        "pt",
+        "Testamento – jurisdição dos EUA",
+        US_REQUIRED
+    ),
+    builtin!(
+        "uk_jurisdiction",
+        "pt",
+        "Testamento – jurisdição do Reino Unido",
+        COMMON_REQUIRED
+    ),
+    builtin!(
+        "global_generic",
+        "pt",
+        "Testamento internacional",
+        COMMON_REQUIRED
+    ),
+    builtin!("codicil", "pt", "Codicilo", CODICIL_REQUIRED),
+    builtin!("simple", "sw", "Wosia rahisi", COMMON_REQUIRED),
+    builtin!("formal", "sw", "Wosia rasmi", COMMON_REQUIRED),
+    builtin!(
+        "us_jurisdiction",
+        "sw",
+        "Wosia – mamlaka ya Marekani",
+        US_REQUIRED
+    ),
+    builtin!(
+        "uk_jurisdiction",
+        "sw",
+        "Wosia – mamlaka ya Uingereza",
+        COMMON_REQUIRED
+    ),
+    builtin!(
+        "global_generic",
+        "sw",
+        "Wosia wa kimataifa",
+        COMMON_REQUIRED
+    ),
+    builtin!("codicil", "sw", "Nyongeza ya wosia", CODICIL_REQUIRED),
+];
+
+// ─── Data Structures ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WillTemplateRecord {
+    pub id: Uuid,
+    pub template_key: String,
+    pub locale: String,
+    pub version: i32,
+    pub title: String,
+    pub body: String,
+    pub required_fields: Vec<String>,
+    pub status: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl WillTemplateRecord {
+    /// Render the template, rejecting contexts that lack a required field.
+    pub fn render(&self, context: &Context) -> Result<String, ApiError> {
+        let missing: Vec<&str> = self
+            .required_fields
+            .iter()
+            .filter(|f| context.get(f.as_str()).is_none_or(Value::is_empty))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(ApiError::BadRequest(format!(
+                "Template '{}' ({}) requires: {}",
+                self.template_key,
+                self.locale,
+                missing.join(", ")
+            )));
+        }
+        Ok(CompiledTemplate::parse(&self.body)?.render(context))
+    }
+}
+
+const TEMPLATE_COLUMNS: &str = "id, template_key, locale, version, title, body, \
+     required_fields, status, created_by, created_at";
+
+#[derive(Debug, Deserialize)]
+pub struct CreateWillTemplateRequest {
+    pub template_key: String,
+    pub locale: String,
+    pub title: String,
+    pub body: String,
+    #[serde(default)]
+    pub required_fields: Vec<String>,
+}
+
+/// Changes applied on top of an existing version to form the next one
+#[derive(Debug, Deserialize)]
+pub struct UpdateWillTemplateRequest {
+    pub title: Option<String>,
+    pub body: Option<String>,
+    pub required_fields: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct WillTemplateQuery {
+    pub template_key: Option<String>,
+    pub locale: Option<String>,
+    /// Include retired versions and superseded versions
+    #[serde(default)]
+    pub all_versions: bool,
+}
+
+fn validate_locale(locale: &str) -> Result<(), ApiError> {
+    let valid = match locale.split_once('-') {
+        Some((lang, region)) => {
+            lang.len() == 2
+                && lang.chars().all(|c| c.is_ascii_lowercase())
+                && region.len() == 2
+                && region.chars().all(|c| c.is_ascii_uppercase())
+        }
+        None => locale.len() == 2 && locale.chars().all(|c| c.is_ascii_lowercase()),
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(ApiError::BadRequest(format!(
+            "Invalid locale '{locale}'; expected e.g. 'fr' or 'pt-BR'"
+        )))
+    }
+}
+
+/// Check a template definition before it is stored
+pub fn validate_definition(
+    template_key: &str,
+    locale: &str,
+    title: &str,
+    body: &str,
+    required_fields: &[String],
+) -> Result<(), ApiError> {
+    if template_key.len() > 64 || !valid_name(template_key) {
+        return Err(ApiError::BadRequest(
+            "template_key must be 1-64 lowercase letters, digits or underscores".to_string(),
+        ));
+    }
+    validate_locale(locale)?;
+    if title.trim().is_empty() || title.len() > 255 {
+        return Err(ApiError::BadRequest(
+            "title must be 1-255 characters".to_string(),
+        ));
+    }
+    if let Some(field) = required_fields.iter().find(|f| !valid_name(f)) {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid required field name '{field}'"
+        )));
+    }
+
+    let compiled = CompiledTemplate::parse(body)?;
+    let unknown: Vec<String> = compiled
+        .referenced_fields()
+        .into_iter()
+        .filter(|f| {
+            !BUILTIN_FIELDS.contains(&f.as_str())
+                && !BENEFICIARY_FIELDS.contains(&f.as_str())
+                && f != BENEFICIARIES_FIELD
+                && !CODICIL_FIELDS.contains(&f.as_str())
+                && !AMENDMENT_FIELDS.contains(&f.as_str())
+                && f != AMENDMENTS_FIELD
+                && !required_fields.contains(f)
+        })
+        .collect();
+    if !unknown.is_empty() {
+        return Err(ApiError::BadRequest(format!(
+            "Unknown template fields: {}; custom fields must be listed in required_fields",
+            unknown.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+// ─── Service ──────────────────────────────────────────────────────────────────
+
+pub struct WillTemplateService;
+
+impl WillTemplateService {
+    /// Seed the bundled templates as version 1 where a key and locale has
+    /// no versions yet. Existing versions are never modified.
+    pub async fn ensure_builtin_templates(db: &PgPool) -> Result<(), ApiError> {
+        for t in BUILTIN_TEMPLATES {
+            let required: Vec<String> = t.required_fields.iter().map(|f| f.to_string()).collect();
+            sqlx::query(
+                r#"
+                INSERT INTO will_templates
+                    (template_key, locale, version, title, body, required_fields)
+                SELECT $1, $2, 1, $3, $4, $5
+                WHERE NOT EXISTS (
+                    SELECT 1 FROM will_templates WHERE template_key = $1 AND locale = $2
+                )
+                "#,
+            )
+            .bind(t.key)
+            .bind(t.locale)
+            .bind(t.title)
+            .bind(t.body)
+            .bind(&required)
+            .execute(db)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// List templates; by default only the latest active version of each.
+    pub async fn list(
+        db: &PgPool,
+        query: &WillTemplateQuery,
+    ) -> Result<Vec<WillTemplateRecord>, ApiError> {
+        let sql = if query.all_versions {
+            format!(
+                "SELECT {TEMPLATE_COLUMNS} FROM will_templates \
+                 WHERE ($1::TEXT IS NULL OR template_key = $1) \
+                   AND ($2::TEXT IS NULL OR locale = $2) \
+                 ORDER BY template_key, locale, version DESC"
+            )
+        } else {
+            format!(
+                "SELECT DISTINCT ON (template_key, locale) {TEMPLATE_COLUMNS} \
+                 FROM will_templates \
+                 WHERE status = 'active' \
+                   AND ($1::TEXT IS NULL OR template_key = $1) \
+                   AND ($2::TEXT IS NULL OR locale = $2) \
+                 ORDER BY template_key, locale, version DESC"
+            )
+        };
+        let rows = sqlx::query_as::<_, WillTemplateRecord>(&sql)
+            .bind(&query.template_key)
+            .bind(&query.locale)
+            .fetch_all(db)
+            .await?;
+        Ok(rows)
+    }
+
+    pub async fn get(db: &PgPool, id: Uuid) -> Result<WillTemplateRecord, ApiError> {
+        sqlx::query_as::<_, WillTemplateRecord>(&format!(
+            "SELECT {TEMPLATE_COLUMNS} FROM will_templates WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Will template {id} not found")))
+    }
+
+    /// The latest active version of a template in a locale.
+    pub async fn resolve(
+        db: &PgPool,
+        template_key: &str,
+        locale: &str,
+    ) -> Result<WillTemplateRecord, ApiError> {
+        let found = sqlx::query_as::<_, WillTemplateRecord>(&format!(
+            "SELECT {TEMPLATE_COLUMNS} FROM will_templates \
+             WHERE template_key = $1 AND locale = $2 AND status = 'active' \
+             ORDER BY version DESC LIMIT 1"
+        ))
+        .bind(template_key)
+        .bind(locale)
+        .fetch_optional(db)
+        .await?;
+
+        match found {
+            Some(template) => Ok(template),
+            None => {
+                let locales: Vec<String> = sqlx::query_scalar(
+                    "SELECT DISTINCT locale FROM will_templates \
+                     WHERE template_key = $1 AND status = 'active' ORDER BY locale",
+                )
+                .bind(template_key)
+                .fetch_all(db)
+                .await?;
+                Err(ApiError::BadRequest(if locales.is_empty() {
+                    format!("Unknown template: {template_key}")
+                } else {
+                    format!(
+                        "Template '{template_key}' is not available in '{locale}' (available: {})",
+                        locales.join(", ")
+                    )
+                }))
+            }
+        }
+    }
+
+    /// Store a template as the next version of its key and locale.
+    pub async fn create(
+        db: &PgPool,
+        admin_id: Uuid,
+        req: &CreateWillTemplateRequest,
+    ) -> Result<WillTemplateRecord, ApiError> {
+        let body = req.body.replace("\r\n", "\n");
+        validate_definition(
+            &req.template_key,
+            &req.locale,
+            &req.title,
+            &body,
+            &req.required_fields,
+        )?;
+
+        let record = sqlx::query_as::<_, WillTemplateRecord>(&format!(
+            r#"
+            INSERT INTO will_templates
+                (template_key, locale, version, title, body, required_fields, created_by)
+            SELECT $1, $2, COALESCE(MAX(version), 0) + 1, $3, $4, $5, $6
+            FROM will_templates WHERE template_key = $1 AND locale = $2
+            RETURNING {TEMPLATE_COLUMNS}
+            "#
+        ))
+        .bind(&req.template_key)
+        .bind(&req.locale)
+        .bind(req.title.trim())
+        .bind(&body)
+        .bind(&req.required_fields)
+        .bind(admin_id)
+        .fetch_one(db)
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => ApiError::BadRequest(
+                "Another version of this template was created concurrently; retry".to_string(),
+            ),
+            _ => ApiError::from(e),
+        })?;
+        Ok(record)
+    }
+
+    /// Create the next version of a template from an existing one.
+    pub async fn update(
+        db: &PgPool,
+        admin_id: Uuid,
+        id: Uuid,
+        req: &UpdateWillTemplateRequest,
+    ) -> Result<WillTemplateRecord, ApiError> {
+        let current = Self::get(db, id).await?;
+        let next = CreateWillTemplateRequest {
+            template_key: current.template_key,
+            locale: current.locale,
+            title: req.title.clone().unwrap_or(current.title),
+            body: req.body.clone().unwrap_or(current.body),
+            required_fields: req
+                .required_fields
+                .clone()
+                .unwrap_or(current.required_fields),
+        };
+        Self::create(db, admin_id, &next).await
+    }
+
+    /// Retire a version so it is no longer used for new documents. Documents
+    /// already rendered from it keep their reference.
+    pub async fn retire(db: &PgPool, id: Uuid) -> Result<WillTemplateRecord, ApiError> {
+        sqlx::query_as::<_, WillTemplateRecord>(&format!(
+            "UPDATE will_templates SET status = 'retired' WHERE id = $1 RETURNING {TEMPLATE_COLUMNS}"
+        ))
+        .bind(id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Will template {id} not found")))
+    }
+}
+
+// ─── Unit Tests ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Value {
+        Value::Text(s.to_string())
+    }
+
+    fn ctx(pairs: &[(&str, Value)]) -> Context {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn renders_variables_sections_and_inverted_sections() {
+        let t = CompiledTemplate::parse(
+            "Hi {{name}}!{{#title}} ({{title}}){{/title}}{{^title}} (none){{/title}}",
+        )
+        .unwrap();
+        assert_eq!(t.render(&ctx(&[("name", text("Ada"))])), "Hi Ada! (none)");
+        assert_eq!(
+            t.render(&ctx(&[("name", text("Ada")), ("title", text("Dr"))])),
+            "Hi Ada! (Dr)"
+        );
+    }
+
+    #[test]
+    fn list_sections_repeat_and_standalone_tags_vanish() {
+        let t = CompiledTemplate::parse(
+            "Items:\n{{#items}}\n- {{label}} of {{owner}}\n{{/items}}\n{{! note }}\nEnd\n",
+        )
+        .unwrap();
+        let items = Value::List(vec![
+            ctx(&[("label", text("a"))]),
+            ctx(&[("label", text("b"))]),
+        ]);
+        let out = t.render(&ctx(&[("items", items), ("owner", text("x"))]));
+        assert_eq!(out, "Items:\n- a of x\n- b of x\nEnd\n");
+    }
+
+    #[test]
+    fn rejects_malformed_templates() {
+        for bad in [
+            "{{#a}}unclosed",
+            "{{/a}}",
+            "{{#a}}{{/b}}",
+            "{{Bad Name}}",
+            "{{open",
+        ] {
+            assert!(CompiledTemplate::parse(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn validation_requires_custom_fields_to_be_declared() {
+        let body = "STATE OF {{state}} {{owner_name}}";
+        assert!(validate_definition("us", "en", "US", body, &[]).is_err());
+        assert!(validate_definition("us", "en", "US", body, &["state".to_string()]).is_ok());
+        assert!(validate_definition("us", "english", "US", body, &["state".to_string()]).is_err());
+        assert!(validate_definition("us", "pt-BR", "US", body, &["state".to_string()]).is_ok());
+    }
+
+    #[test]
+    fn required_fields_are_enforced_at_render_time() {
+        let record = WillTemplateRecord {
+            id: Uuid::new_v4(),
+            template_key: "us".to_string(),
+            locale: "en".to_string(),
+            version: 1,
+            title: "US".to_string(),
+            body: "{{state}}".to_string(),
+            required_fields: vec!["state".to_string()],
+            status: "active".to_string(),
+            created_by: None,
+            created_at: Utc::now(),
+        };
+        assert!(record.render(&Context::new()).is_err());
+        assert_eq!(record.render(&ctx(&[("state", text("OH"))])).unwrap(), "OH");
+    }
+
+    #[test]
+    fn builtin_templates_are_valid_in_every_locale() {
+        let locales: BTreeSet<&str> = BUILTIN_TEMPLATES.iter().map(|t| t.locale).collect();
+        assert_eq!(
+            locales.into_iter().collect::<Vec<_>>(),
+            vec!["en", "es", "fr", "pt", "sw"]
+        );
+        for t in BUILTIN_TEMPLATES {
+            let required: Vec<String> = t.required_fields.iter().map(|f| f.to_string()).collect();
+            validate_definition(t.key, t.locale, t.title, t.body, &required)
+                .unwrap_or_else(|e| panic!("{}/{}: {e:?}", t.locale, t.key));
+        }
+    }
+}
@@ -17,6 +17,9 @@ pub struct WillVersionSummary {
     pub plan_id: Uuid,
     pub version: u32,
     pub status: String,
+    /// `will`, or `codicil` for an amendment of `base_version`
+    pub kind: String,
+    pub base_version: Option<u32>,
     pub template_used: String,
     pub will_hash: String,
     pub filename: String,
@@ -67,6 +70,8 @@ impl WillVersionService {
             plan_id: Uuid,
             version: i32,
             status: String,
+            kind: String,
+            base_version: Option<i32>,
             template: String,
             will_hash: String,
             filename: String,
@@ -74,7 +79,8 @@ impl WillVersionService {
         }
 
         let rows = sqlx::query_as::<_, Row>(
-            "SELECT id, plan_id, version, status, template, will_hash, filename, generated_at \
+            "SELECT id, plan_id, version, status, kind, base_version, template, will_hash, filename, \
+                    generated_at \
              FROM will_documents \
              WHERE plan_id = $1 AND user_id = $2 \
              ORDER BY version DESC \
@@ -94,6 +100,8 @@ impl WillVersionService {
                 plan_id: r.plan_id,
                 version: r.version as u32,
                 status: r.status,
+                kind: r.kind,
+                base_version: r.base_version.map(|v| v as u32),
                 template_used: r.template,
                 will_hash: r.will_hash,
                 filename: r.filename,
@@ -105,6 +113,7 @@ impl WillVersionService {
     }
 
     /// Get the active version: latest finalized, or latest draft if none finalized.
+    /// Codicils and revoked versions are never active.
     pub async fn get_active_version(
         db: &PgPool,
         plan_id: Uuid,
@@ -116,6 +125,8 @@ impl WillVersionService {
             plan_id: Uuid,
             version: i32,
             status: String,
+            kind: String,
+            base_version: Option<i32>,
             template: String,
             will_hash: String,
             filename: String,
@@ -123,9 +134,10 @@ impl WillVersionService {
         }
 
         let row = sqlx::query_as::<_, Row>(
-            "SELECT id, plan_id, version, status, template, will_hash, filename, generated_at \
+            "SELECT id, plan_id, version, status, kind, base_version, template, will_hash, filename, \
+                    generated_at \
              FROM will_documents \
-             WHERE plan_id = $1 AND user_id = $2 \
+             WHERE plan_id = $1 AND user_id = $2 AND kind = 'will' AND status <> 'revoked' \
              ORDER BY CASE WHEN status = 'finalized' THEN 0 ELSE 1 END, version DESC \
              LIMIT 1",
         )
@@ -140,6 +152,8 @@ impl WillVersionService {
             plan_id: row.plan_id,
             version: row.version as u32,
             status: row.status,
+            kind: row.kind,
+            base_version: row.base_version.map(|v| v as u32),
             template_used: row.template,
             will_hash: row.will_hash,
             filename: row.filename,
@@ -203,6 +217,8 @@ impl WillVersionService {
             plan_id: Uuid,
             version: i32,
             status: String,
+            kind: String,
+            base_version: Option<i32>,
             template: String,
             will_hash: String,
             filename: String,
@@ -210,7 +226,8 @@ impl WillVersionService {
         }
 
         let row = sqlx::query_as::<_, Row>(
-            "SELECT id, plan_id, version, status, template, will_hash, filename, generated_at \
+            "SELECT id, plan_id, version, status, kind, base_version, template, will_hash, filename, \
+                    generated_at \
              FROM will_documents \
              WHERE plan_id = $1 AND user_id = $2 AND version = $3",
         )
@@ -226,7 +243,13 @@ impl WillVersionService {
                 "This version is already finalized".to_string(),
             ));
         }
+        if row.status == "revoked" {
+            return Err(ApiError::BadRequest(
+                "This version has been revoked".to_string(),
+            ));
+        }
 
+        let mut tx = db.begin().await?;
         sqlx::query(
             "UPDATE will_documents SET status = 'finalized' \
              WHERE plan_id = $1 AND user_id = $2 AND version = $3",
@@ -234,8 +257,16 @@ impl WillVersionService {
         .bind(plan_id)
         .bind(user_id)
         .bind(version_number as i32)
-        .execute(db)
+        .execute(&mut *tx)
+        .await?;
+        crate::will_anchor::WillAnchorService::enqueue_finalization(
+            &mut tx,
+            plan_id,
+            row.id,
+            version_number,
+        )
         .await?;
+        tx.commit().await?;
 
         // Fetch vault_id from plan
         let vault_id: Option<String> =
@@ -264,6 +295,8 @@ impl WillVersionService {
             plan_id: row.plan_id,
             version: row.version as u32,
             status: "finalized".to_string(),
+            kind: row.kind,
+            base_version: row.base_version.map(|v| v as u32),
             template_used: row.template,
             will_hash: row.will_hash,
             filename: row.filename,
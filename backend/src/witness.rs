@@ -2,19 +2,24 @@
 //!
 //! Allows witnesses to review and sign legal will documents.
 //! Witnesses are invited via wallet address or email, can sign using
-//! Ed25519 signatures, and their status is tracked (pending/signed/declined).
+//! Ed25519 signatures, and their status is tracked
+//! (pending/signed/declined/expired). Email invitations are delivered with a
+//! signed link; see [`crate::witness_invitation`].
 
 use crate::api_error::ApiError;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use ring::signature;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use stellar_strkey::Strkey;
 use uuid::Uuid;
 
+/// How long a witness has to respond to an invitation
+pub const WITNESS_INVITE_TTL_DAYS: i64 = 14;
+
 // --- Types -------------------------------------------------------------------
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct WitnessRecord {
     pub id: Uuid,
     pub document_id: Uuid,
@@ -24,6 +29,46 @@ pub struct WitnessRecord {
     pub signature_hex: Option<String>,
     pub signed_at: Option<DateTime<Utc>>,
     pub invited_at: DateTime<Utc>,
+    pub invite_expires_at: Option<DateTime<Utc>>,
+    /// `stellar` or `device`, once signed
+    pub signing_key_type: Option<String>,
+    pub signer_public_key: Option<String>,
+}
+
+/// Key a witness signs with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WitnessKeyType {
+    /// A Stellar wallet, identified by its G-address
+    Stellar,
+    /// An Ed25519 key generated on the witness's device, as 64 hex characters
+    Device,
+}
+
+impl WitnessKeyType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Stellar => "stellar",
+            Self::Device => "device",
+        }
+    }
+
+    fn check_format(&self, public_key: &str) -> Result<(), ApiError> {
+        let valid = match self {
+            Self::Stellar => public_key.starts_with('G') && public_key.len() == 56,
+            Self::Device => {
+                public_key.len() == 64 && public_key.chars().all(|c| c.is_ascii_hexdigit())
+            }
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(ApiError::BadRequest(format!(
+                "Invalid {} public key",
+                self.as_str()
+            )))
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,6 +78,7 @@ pub struct WitnessStatusSummary {
     pub signed: i64,
     pub pending: i64,
     pub declined: i64,
+    pub expired: i64,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -49,10 +95,31 @@ pub struct WitnessSignRequest {
 
 // --- Service -----------------------------------------------------------------
 
+const WITNESS_COLUMNS: &str = "id, document_id, wallet_address, email, status, signature_hex, \
+     signed_at, invited_at, invite_expires_at, signing_key_type, signer_public_key";
+
+/// Whether an invitee is one of the beneficiaries named in the will.
+/// Wallets and emails are compared case-insensitively.
+pub fn is_beneficiary(
+    wallet_address: Option<&str>,
+    email: Option<&str>,
+    beneficiary_wallets: &[String],
+    beneficiary_emails: &[String],
+) -> bool {
+    let matches = |value: Option<&str>, list: &[String]| {
+        value
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .is_some_and(|v| list.iter().any(|b| b.trim().eq_ignore_ascii_case(v)))
+    };
+    matches(wallet_address, beneficiary_wallets) || matches(email, beneficiary_emails)
+}
+
 pub struct WitnessService;
 
 impl WitnessService {
-    /// Invite a witness to a will document. The document must belong to the inviting user.
+    /// Invite a witness to a will document. The document must belong to the inviting user,
+    /// and beneficiaries named in the will cannot witness it.
     pub async fn invite_witness(
         db: &PgPool,
         user_id: Uuid,
@@ -67,28 +134,53 @@ impl WitnessService {
         }
 
         // Verify the document belongs to this user
-        let exists: bool = sqlx::query_scalar(
-            "SELECT EXISTS(SELECT 1 FROM will_documents WHERE id = $1 AND user_id = $2)",
+        let doc: Option<(String, Uuid)> = sqlx::query_as(
+            "SELECT status, plan_id FROM will_documents WHERE id = $1 AND user_id = $2",
         )
         .bind(document_id)
         .bind(user_id)
-        .fetch_one(db)
+        .fetch_optional(db)
         .await?;
 
-        if !exists {
-            return Err(ApiError::NotFound(format!(
-                "Will document {document_id} not found"
-            )));
+        let plan_id = match doc {
+            None => {
+                return Err(ApiError::NotFound(format!(
+                    "Will document {document_id} not found"
+                )))
+            }
+            Some((status, _)) if status == "revoked" => {
+                return Err(ApiError::BadRequest(
+                    "Will document has been revoked".to_string(),
+                ))
+            }
+            Some((_, plan_id)) => plan_id,
+        };
+
+        let mut conn = db.acquire().await?;
+        let (beneficiary_wallets, beneficiary_emails) =
+            Self::beneficiaries(&mut conn, document_id, plan_id).await?;
+        drop(conn);
+        if is_beneficiary(
+            wallet_address.as_deref(),
+            email.as_deref(),
+            &beneficiary_wallets,
+            &beneficiary_emails,
+        ) {
+            return Err(ApiError::BadRequest(
+                "A beneficiary named in the will cannot act as its witness".to_string(),
+            ));
         }
 
         let id = Uuid::new_v4();
         let invited_at = Utc::now();
+        let invite_expires_at = invited_at + Duration::days(WITNESS_INVITE_TTL_DAYS);
 
         sqlx::query(
             r#"
             INSERT INTO will_witnesses
-                (id, document_id, inviter_user_id, wallet_address, email, status, invited_at)
-            VALUES ($1, $2, $3, $4, $5, 'pending', $6)
+                (id, document_id, inviter_user_id, wallet_address, email, status, invited_at,
+                 invite_expires_at)
+            VALUES ($1, $2, $3, $4, $5, 'pending', $6, $7)
             "#,
         )
         .bind(id)
@@ -97,6 +189,7 @@ impl WitnessService {
         .bind(&wallet_address)
         .bind(&email)
         .bind(invited_at)
+        .bind(invite_expires_at)
         .execute(db)
         .await?;
 
@@ -139,6 +232,9 @@ impl WitnessService {
             signature_hex: None,
             signed_at: None,
             invited_at,
+            invite_expires_at: Some(invite_expires_at),
+            signing_key_type: None,
+            signer_public_key: None,
         })
     }
 
@@ -162,92 +258,86 @@ impl WitnessService {
             )));
         }
 
-        #[derive(sqlx::FromRow)]
-        struct Row {
-            id: Uuid,
-            document_id: Uuid,
-            wallet_address: Option<String>,
-            email: Option<String>,
-            status: String,
-            signature_hex: Option<String>,
-            signed_at: Option<DateTime<Utc>>,
-            invited_at: DateTime<Utc>,
-        }
-
-        let rows = sqlx::query_as::<_, Row>(
-            "SELECT id, document_id, wallet_address, email, status, signature_hex, signed_at, invited_at \
-             FROM will_witnesses WHERE document_id = $1 ORDER BY invited_at DESC",
-        )
+        let rows = sqlx::query_as::<_, WitnessRecord>(&format!(
+            "SELECT {WITNESS_COLUMNS} FROM will_witnesses \
+             WHERE document_id = $1 ORDER BY invited_at DESC"
+        ))
         .bind(document_id)
         .fetch_all(db)
         .await?;
 
-        Ok(rows
-            .into_iter()
-            .map(|r| WitnessRecord {
-                id: r.id,
-                document_id: r.document_id,
-                wallet_address: r.wallet_address,
-                email: r.email,
-                status: r.status,
-                signature_hex: r.signature_hex,
-                signed_at: r.signed_at,
-                invited_at: r.invited_at,
-            })
-            .collect())
+        Ok(rows)
     }
 
-    /// Witness signs a document. Verifies the Ed25519 signature against the document hash.
+    /// Witness invited by wallet signs a document with that wallet. Verifies the
+    /// Ed25519 signature against the document hash.
     pub async fn sign_as_witness(
         db: &PgPool,
         witness_id: Uuid,
         wallet_address: &str,
         signature_hex: &str,
     ) -> Result<WitnessRecord, ApiError> {
-        let mut tx = db.begin().await?;
-
-        #[derive(sqlx::FromRow)]
-        struct WitnessRow {
-            id: Uuid,
-            document_id: Uuid,
-            wallet_address: Option<String>,
-            email: Option<String>,
-            status: String,
-            invited_at: DateTime<Utc>,
-        }
-
-        let witness = sqlx::query_as::<_, WitnessRow>(
-            "SELECT id, document_id, wallet_address, email, status, invited_at \
-             FROM will_witnesses WHERE id = $1 FOR UPDATE",
+        Self::record_signature(
+            db,
+            witness_id,
+            None,
+            WitnessKeyType::Stellar,
+            wallet_address,
+            signature_hex,
         )
-        .bind(witness_id)
-        .fetch_optional(&mut *tx)
-        .await?
-        .ok_or_else(|| ApiError::NotFound("Witness record not found".to_string()))?;
+        .await
+    }
 
-        if witness.status != "pending" {
-            return Err(ApiError::BadRequest(format!(
-                "Witness has already {}",
-                witness.status
-            )));
-        }
+    /// Record a witness signature over the document hash.
+    ///
+    /// Witnesses invited by wallet must sign with that wallet. Witnesses
+    /// invited only by email must present their invitation token
+    /// (`token_hash`), and may sign with a wallet or a device-generated key.
+    pub(crate) async fn record_signature(
+        db: &PgPool,
+        witness_id: Uuid,
+        token_hash: Option<&str>,
+        key_type: WitnessKeyType,
+        public_key: &str,
+        signature_hex: &str,
+    ) -> Result<WitnessRecord, ApiError> {
+        let public_key = public_key.trim();
+        let mut tx = db.begin().await?;
+
+        let witness = Self::lock_pending(&mut tx, witness_id, token_hash).await?;
 
         // Verify the wallet address matches the invited witness
-        if let Some(ref invited_wallet) = witness.wallet_address {
-            if invited_wallet.to_lowercase() != wallet_address.to_lowercase() {
+        match witness.wallet_address {
+            Some(ref invited_wallet)
+                if key_type != WitnessKeyType::Stellar
+                    || !invited_wallet.eq_ignore_ascii_case(public_key) =>
+            {
                 return Err(ApiError::Unauthorized);
             }
+            Some(_) => {}
+            None if token_hash.is_none() => {
+                return Err(ApiError::Forbidden(
+                    "Witnesses invited by email sign through their invitation link".to_string(),
+                ))
+            }
+            None => {}
         }
+        key_type.check_format(public_key)?;
 
         // Fetch the document hash to verify the signature against
-        let doc_hash: String =
-            sqlx::query_scalar("SELECT will_hash FROM will_documents WHERE id = $1")
+        let (doc_hash, doc_status, plan_id): (String, String, Uuid) =
+            sqlx::query_as("SELECT will_hash, status, plan_id FROM will_documents WHERE id = $1")
                 .bind(witness.document_id)
                 .fetch_one(&mut *tx)
                 .await?;
+        if doc_status == "revoked" {
+            return Err(ApiError::BadRequest(
+                "Will document has been revoked".to_string(),
+            ));
+        }
 
         // Verify Ed25519 signature over the document hash
-        let public_key_bytes = decode_public_key(wallet_address)?;
+        let public_key_bytes = decode_public_key(public_key)?;
         let sig_bytes = hex::decode(signature_hex)
             .map_err(|_| ApiError::BadRequest("Invalid signature hex".to_string()))?;
         let peer_public_key =
@@ -256,15 +346,44 @@ impl WitnessService {
             .verify(doc_hash.as_bytes(), &sig_bytes)
             .map_err(|_| ApiError::Unauthorized)?;
 
-        let signed_at = Utc::now();
+        // Device keys are raw Ed25519 keys, so they have a Stellar address too
+        let signer_address = stellar_strkey::ed25519::PublicKey(public_key_bytes)
+            .to_string()
+            .as_str()
+            .to_owned();
+
+        // The invitation was checked, but an email invitee chooses their key
+        // only now, and beneficiaries may have been added since
+        let (beneficiary_wallets, beneficiary_emails) =
+            Self::beneficiaries(&mut tx, witness.document_id, plan_id).await?;
+        if is_beneficiary(
+            Some(&signer_address),
+            witness.email.as_deref(),
+            &beneficiary_wallets,
+            &beneficiary_emails,
+        ) {
+            return Err(ApiError::BadRequest(
+                "A beneficiary named in the will cannot act as its witness".to_string(),
+            ));
+        }
 
-        sqlx::query(
-            "UPDATE will_witnesses SET status = 'signed', signature_hex = $1, signed_at = $2 WHERE id = $3",
-        )
+        let record = sqlx::query_as::<_, WitnessRecord>(&format!(
+            "UPDATE will_witnesses \
+             SET status = 'signed', signature_hex = $1, signed_at = NOW(), \
+                 signing_key_type = $2, signer_public_key = $3, invite_token_hash = NULL \
+             WHERE id = $4 RETURNING {WITNESS_COLUMNS}"
+        ))
         .bind(signature_hex)
-        .bind(signed_at)
+        .bind(key_type.as_str())
+        .bind(public_key)
         .bind(witness_id)
-        .execute(&mut *tx)
+        .fetch_one(&mut *tx)
+        .await?;
+        crate::will_anchor::WillAnchorService::enqueue_witness_signature(
+            &mut tx,
+            witness.document_id,
+            &signer_address,
+        )
         .await?;
 
         tx.commit().await?;
@@ -286,61 +405,45 @@ impl WitnessService {
             vault_id,
             document_id: witness.document_id,
             plan_id,
-            witness: wallet_address.to_string(),
+            witness: signer_address.clone(),
             witness_id,
             signature_hash: hex::encode(sig_hash.as_ref()),
-            timestamp: signed_at,
+            timestamp: record.signed_at.unwrap_or_else(Utc::now),
         };
         if let Err(e) = crate::will_events::WillEventService::emit(db, event).await {
             tracing::warn!("Failed to emit WitnessSigned event: {}", e);
         }
 
-        Ok(WitnessRecord {
-            id: witness.id,
-            document_id: witness.document_id,
-            wallet_address: witness.wallet_address,
-            email: witness.email,
-            status: "signed".to_string(),
-            signature_hex: Some(signature_hex.to_string()),
-            signed_at: Some(signed_at),
-            invited_at: witness.invited_at,
-        })
+        Ok(record)
     }
 
     /// Witness declines to sign.
     pub async fn decline_witness(db: &PgPool, witness_id: Uuid) -> Result<WitnessRecord, ApiError> {
-        #[derive(sqlx::FromRow)]
-        struct Row {
-            id: Uuid,
-            document_id: Uuid,
-            wallet_address: Option<String>,
-            email: Option<String>,
-            status: String,
-            signature_hex: Option<String>,
-            signed_at: Option<DateTime<Utc>>,
-            invited_at: DateTime<Utc>,
-        }
-
-        let witness = sqlx::query_as::<_, Row>(
-            "SELECT id, document_id, wallet_address, email, status, signature_hex, signed_at, invited_at \
-             FROM will_witnesses WHERE id = $1",
-        )
-        .bind(witness_id)
-        .fetch_optional(db)
-        .await?
-        .ok_or_else(|| ApiError::NotFound("Witness record not found".to_string()))?;
+        Self::record_decline(db, witness_id, None).await
+    }
 
-        if witness.status != "pending" {
-            return Err(ApiError::BadRequest(format!(
-                "Witness has already {}",
-                witness.status
-            )));
+    /// Record a decline. Email-only witnesses must present their invitation token.
+    pub(crate) async fn record_decline(
+        db: &PgPool,
+        witness_id: Uuid,
+        token_hash: Option<&str>,
+    ) -> Result<WitnessRecord, ApiError> {
+        let mut tx = db.begin().await?;
+        let witness = Self::lock_pending(&mut tx, witness_id, token_hash).await?;
+        if witness.wallet_address.is_none() && token_hash.is_none() {
+            return Err(ApiError::Forbidden(
+                "Witnesses invited by email decline through their invitation link".to_string(),
+            ));
         }
 
-        sqlx::query("UPDATE will_witnesses SET status = 'declined' WHERE id = $1")
-            .bind(witness_id)
-            .execute(db)
-            .await?;
+        let record = sqlx::query_as::<_, WitnessRecord>(&format!(
+            "UPDATE will_witnesses SET status = 'declined', invite_token_hash = NULL \
+             WHERE id = $1 RETURNING {WITNESS_COLUMNS}"
+        ))
+        .bind(witness_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
 
         // Emit WitnessDeclined event
         let result: Option<(Uuid, String)> = sqlx::query_as(
@@ -366,16 +469,76 @@ impl WitnessService {
             }
         }
 
-        Ok(WitnessRecord {
-            id: witness.id,
-            document_id: witness.document_id,
-            wallet_address: witness.wallet_address,
-            email: witness.email,
-            status: "declined".to_string(),
-            signature_hex: witness.signature_hex,
-            signed_at: witness.signed_at,
-            invited_at: witness.invited_at,
-        })
+        Ok(record)
+    }
+
+    /// Wallets and emails of everyone the will or its plan benefits
+    async fn beneficiaries(
+        conn: &mut sqlx::PgConnection,
+        document_id: Uuid,
+        plan_id: Uuid,
+    ) -> Result<(Vec<String>, Vec<String>), ApiError> {
+        let wallets: Vec<String> = sqlx::query_scalar(
+            "SELECT b->>'wallet_address' FROM will_documents d, \
+                    jsonb_array_elements(COALESCE(d.terms->'beneficiaries', '[]'::jsonb)) b \
+             WHERE d.id = $1 AND b->>'wallet_address' IS NOT NULL \
+             UNION SELECT wallet_address FROM plan_beneficiaries WHERE plan_id = $2",
+        )
+        .bind(document_id)
+        .bind(plan_id)
+        .fetch_all(&mut *conn)
+        .await?;
+        let emails: Vec<String> =
+            sqlx::query_scalar("SELECT beneficiary_email FROM claims WHERE plan_id = $1")
+                .bind(plan_id)
+                .fetch_all(&mut *conn)
+                .await?;
+        Ok((wallets, emails))
+    }
+
+    /// Lock a witness that can still respond: pending, not expired and, when
+    /// a token is presented, holding that token.
+    async fn lock_pending(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        witness_id: Uuid,
+        token_hash: Option<&str>,
+    ) -> Result<WitnessRecord, ApiError> {
+        #[derive(sqlx::FromRow)]
+        struct Row {
+            #[sqlx(flatten)]
+            record: WitnessRecord,
+            invite_token_hash: Option<String>,
+        }
+
+        let row = sqlx::query_as::<_, Row>(&format!(
+            "SELECT {WITNESS_COLUMNS}, invite_token_hash \
+             FROM will_witnesses WHERE id = $1 FOR UPDATE"
+        ))
+        .bind(witness_id)
+        .fetch_optional(&mut **tx)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Witness record not found".to_string()))?;
+
+        if let Some(token_hash) = token_hash {
+            if row.invite_token_hash.as_deref() != Some(token_hash) {
+                return Err(ApiError::Forbidden(
+                    "Invitation link has already been used or replaced".to_string(),
+                ));
+            }
+        }
+        let witness = row.record;
+        if witness.status != "pending" {
+            return Err(ApiError::BadRequest(format!(
+                "Witness has already {}",
+                witness.status
+            )));
+        }
+        if witness.invite_expires_at.is_some_and(|at| at <= Utc::now()) {
+            return Err(ApiError::Forbidden(
+                "Witness invitation has expired".to_string(),
+            ));
+        }
+        Ok(witness)
     }
 
     /// Get a summary of witness statuses for a document.
@@ -389,6 +552,7 @@ impl WitnessService {
             signed: Option<i64>,
             pending: Option<i64>,
             declined: Option<i64>,
+            expired: Option<i64>,
         }
 
         let row = sqlx::query_as::<_, CountRow>(
@@ -397,7 +561,8 @@ impl WitnessService {
                 COUNT(*) AS total,
                 COUNT(*) FILTER (WHERE status = 'signed') AS signed,
                 COUNT(*) FILTER (WHERE status = 'pending') AS pending,
-                COUNT(*) FILTER (WHERE status = 'declined') AS declined
+                COUNT(*) FILTER (WHERE status = 'declined') AS declined,
+                COUNT(*) FILTER (WHERE status = 'expired') AS expired
             FROM will_witnesses
             WHERE document_id = $1
             "#,
@@ -412,6 +577,7 @@ impl WitnessService {
             signed: row.signed.unwrap_or(0),
             pending: row.pending.unwrap_or(0),
             declined: row.declined.unwrap_or(0),
+            expired: row.expired.unwrap_or(0),
         })
     }
 }
@@ -459,6 +625,7 @@ mod tests {
             signed: 0,
             pending: 0,
             declined: 0,
+            expired: 0,
         };
         assert_eq!(summary.total, 0);
         assert_eq!(summary.signed, 0);
@@ -479,6 +646,9 @@ mod tests {
             signature_hex: None,
             signed_at: None,
             invited_at: now,
+            invite_expires_at: None,
+            signing_key_type: None,
+            signer_public_key: None,
         };
         assert_eq!(record.document_id, doc_id);
         assert_eq!(record.status, "pending");
@@ -546,4 +716,41 @@ mod tests {
             .verify(message.as_bytes(), &decoded_sig)
             .is_ok());
     }
+
+    #[test]
+    fn test_beneficiaries_cannot_witness() {
+        let wallets = vec!["GBENEFICIARY".to_string()];
+        let emails = vec!["Heir@Example.com".to_string()];
+        assert!(is_beneficiary(
+            Some("gbeneficiary"),
+            None,
+            &wallets,
+            &emails
+        ));
+        assert!(is_beneficiary(
+            None,
+            Some("heir@example.com "),
+            &wallets,
+            &emails
+        ));
+        assert!(!is_beneficiary(
+            Some("GOTHER"),
+            Some("friend@example.com"),
+            &wallets,
+            &emails
+        ));
+        assert!(!is_beneficiary(Some(""), None, &[String::new()], &emails));
+    }
+
+    #[test]
+    fn test_key_type_formats() {
+        let (_, pub_key_bytes) = generate_keypair();
+        let device_key = hex::encode(&pub_key_bytes);
+        let stellar_key =
+            stellar_strkey::ed25519::PublicKey(pub_key_bytes.try_into().unwrap()).to_string();
+        assert!(WitnessKeyType::Device.check_format(&device_key).is_ok());
+        assert!(WitnessKeyType::Stellar.check_format(&stellar_key).is_ok());
+        assert!(WitnessKeyType::Device.check_format(&stellar_key).is_err());
+        assert!(WitnessKeyType::Stellar.check_format(&device_key).is_err());
+    }
 }
@@ -0,0 +1,493 @@
+//! Witness invitations delivered through the alert provider.
+//!
+//! An email invitation carries a single-use, expiring token signed with
+//! HMAC-SHA256. The token lets a witness without an INHERITX account read the
+//! will and sign it with a Stellar wallet or a device-generated key. Only a
+//! hash of the outstanding token is stored; sending a reminder issues a new
+//! token and invalidates the previous link. Invitations that are not answered
+//! before they expire are marked `expired`.
+
+use crate::alert_provider::{AlertProvider, AlertTemplate};
+use crate::api_error::ApiError;
+use crate::witness::{WitnessKeyType, WitnessRecord, WitnessService};
+use chrono::{DateTime, Utc};
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+const DEFAULT_INVITE_BASE_URL: &str = "http://localhost:8080";
+
+/// Days between reminders for an unanswered invitation
+pub const WITNESS_REMINDER_INTERVAL_DAYS: i64 = 3;
+
+/// Reminders sent after the initial invitation before it is left to expire
+pub const MAX_WITNESS_REMINDERS: i32 = 2;
+
+/// Issues and verifies invitation tokens of the form
+/// `{witness_id}.{expires_unix}.{nonce}.{mac}`.
+pub struct WitnessInviteTokens {
+    key: hmac::Key,
+}
+
+impl WitnessInviteTokens {
+    pub fn new(secret: &[u8]) -> Self {
+        Self {
+            key: hmac::Key::new(hmac::HMAC_SHA256, secret),
+        }
+    }
+
+    fn message(witness_id: Uuid, expires: i64, nonce: &str) -> String {
+        format!("WITNESS_INVITE\n{}\n{}\n{}", witness_id, expires, nonce)
+    }
+
+    pub fn issue(&self, witness_id: Uuid, expires_at: DateTime<Utc>) -> Result<String, ApiError> {
+        let mut nonce = [0u8; 16];
+        SystemRandom::new().fill(&mut nonce).map_err(|_| {
+            ApiError::Internal(anyhow::anyhow!("Failed to generate invitation token"))
+        })?;
+        let nonce = hex::encode(nonce);
+        let expires = expires_at.timestamp();
+        let mac = hmac::sign(
+            &self.key,
+            Self::message(witness_id, expires, &nonce).as_bytes(),
+        );
+        Ok(format!(
+            "{}.{}.{}.{}",
+            witness_id,
+            expires,
+            nonce,
+            hex::encode(mac)
+        ))
+    }
+
+    /// Check the signature and expiry of `token` and return the witness it
+    /// was issued for. Whether it is still the outstanding token is checked
+    /// against the stored hash.
+    pub fn verify(&self, token: &str) -> Result<Uuid, ApiError> {
+        let parts: Vec<&str> = token.split('.').collect();
+        let [witness_id, expires, nonce, mac] = parts[..] else {
+            return Err(ApiError::Unauthorized);
+        };
+        let witness_id = Uuid::parse_str(witness_id).map_err(|_| ApiError::Unauthorized)?;
+        let expires: i64 = expires.parse().map_err(|_| ApiError::Unauthorized)?;
+        let mac = hex::decode(mac).map_err(|_| ApiError::Unauthorized)?;
+        hmac::verify(
+            &self.key,
+            Self::message(witness_id, expires, nonce).as_bytes(),
+            &mac,
+        )
+        .map_err(|_| ApiError::Unauthorized)?;
+
+        if expires <= Utc::now().timestamp() {
+            return Err(ApiError::Forbidden(
+                "Witness invitation has expired".to_string(),
+            ));
+        }
+        Ok(witness_id)
+    }
+}
+
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Read-only view of the will shown to an invited witness
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvitationView {
+    pub witness_id: Uuid,
+    pub document_id: Uuid,
+    pub version: i32,
+    pub owner_name: Option<String>,
+    pub will_hash: String,
+    /// The message to sign: the document's will hash
+    pub signing_message: String,
+    pub rendered_content: Option<String>,
+    pub filename: String,
+    pub pdf_base64: String,
+    pub generated_at: DateTime<Utc>,
+    pub invite_expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InvitationSignRequest {
+    pub key_type: WitnessKeyType,
+    /// Stellar G-address or 64-hex Ed25519 public key
+    pub public_key: String,
+    pub signature_hex: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InvitationRunResult {
+    pub sent: usize,
+    pub reminded: usize,
+    pub expired: usize,
+    pub failed: usize,
+}
+
+#[derive(sqlx::FromRow)]
+struct DueInvitation {
+    id: Uuid,
+    email: String,
+    invite_expires_at: DateTime<Utc>,
+    owner_name: String,
+}
+
+pub struct WitnessInvitationService {
+    db: PgPool,
+    provider: Arc<dyn AlertProvider>,
+    tokens: WitnessInviteTokens,
+    base_url: String,
+}
+
+impl WitnessInvitationService {
+    pub fn new(db: PgPool, provider: Arc<dyn AlertProvider>, secret: &[u8]) -> Self {
+        let base_url = std::env::var("WITNESS_INVITE_BASE_URL")
+            .unwrap_or_else(|_| DEFAULT_INVITE_BASE_URL.to_string());
+        Self {
+            db,
+            provider,
+            tokens: WitnessInviteTokens::new(secret),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Build from `WITNESS_INVITE_SIGNING_KEY`, which must be set: invite
+    /// links are bearer credentials, so they get a key of their own.
+    pub fn from_env(db: PgPool, provider: Arc<dyn AlertProvider>) -> Result<Self, ApiError> {
+        let secret = std::env::var("WITNESS_INVITE_SIGNING_KEY")
+            .ok()
+            .filter(|key| !key.trim().is_empty())
+            .ok_or_else(|| {
+                ApiError::Internal(anyhow::anyhow!(
+                    "WITNESS_INVITE_SIGNING_KEY must be set to sign witness invitations"
+                ))
+            })?;
+        Ok(Self::new(db, provider, secret.as_bytes()))
+    }
+
+    pub fn start(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(300));
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.process_due().await {
+                    error!("Witness Invitation Service error: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Expire unanswered invitations, then send pending invitations and
+    /// reminders that are due.
+    pub async fn process_due(&self) -> Result<InvitationRunResult, ApiError> {
+        let expired = self.expire_overdue().await?;
+
+        let due = sqlx::query_as::<_, DueInvitation>(
+            "SELECT w.id, w.email, w.invite_expires_at, \
+                    COALESCE(d.owner_name, u.email) AS owner_name \
+             FROM will_witnesses w \
+             JOIN will_documents d ON d.id = w.document_id \
+             JOIN users u ON u.id = d.user_id \
+             WHERE w.status = 'pending' AND w.email IS NOT NULL \
+               AND w.invite_expires_at > NOW() AND d.status <> 'revoked' \
+               AND (w.invite_sent_at IS NULL \
+                    OR (w.reminder_count < $1 \
+                        AND COALESCE(w.last_reminded_at, w.invite_sent_at) \
+                            < NOW() - make_interval(days => $2))) \
+             ORDER BY w.invited_at ASC LIMIT 100",
+        )
+        .bind(MAX_WITNESS_REMINDERS)
+        .bind(WITNESS_REMINDER_INTERVAL_DAYS as i32)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut sent = 0usize;
+        let mut reminded = 0usize;
+        let mut failed = 0usize;
+        for invitation in &due {
+            match self.deliver(invitation).await {
+                Ok(Some(true)) => reminded += 1,
+                Ok(Some(false)) => sent += 1,
+                Ok(None) => {}
+                Err(e) => {
+                    failed += 1;
+                    warn!("Failed sending witness invitation {}: {}", invitation.id, e);
+                }
+            }
+        }
+
+        if sent + reminded + expired > 0 {
+            info!(
+                "Witness invitations: {} sent, {} reminders, {} expired",
+                sent, reminded, expired
+            );
+        }
+
+        Ok(InvitationRunResult {
+            sent,
+            reminded,
+            expired,
+            failed,
+        })
+    }
+
+    /// Send the initial invitation for an email witness straight away.
+    pub async fn send_invitation(&self, witness_id: Uuid) -> Result<(), ApiError> {
+        let invitation = sqlx::query_as::<_, DueInvitation>(
+            "SELECT w.id, w.email, w.invite_expires_at, \
+                    COALESCE(d.owner_name, u.email) AS owner_name \
+             FROM will_witnesses w \
+             JOIN will_documents d ON d.id = w.document_id \
+             JOIN users u ON u.id = d.user_id \
+             WHERE w.id = $1 AND w.status = 'pending' AND w.email IS NOT NULL \
+               AND w.invite_sent_at IS NULL AND w.invite_expires_at > NOW()",
+        )
+        .bind(witness_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        match invitation {
+            Some(invitation) => self.deliver(&invitation).await.map(|_| ()),
+            None => Ok(()),
+        }
+    }
+
+    /// Claim the invitation with a fresh token and send it. Returns whether a
+    /// reminder was sent, or `None` when another worker claimed it first.
+    async fn deliver(&self, invitation: &DueInvitation) -> Result<Option<bool>, ApiError> {
+        let token = self
+            .tokens
+            .issue(invitation.id, invitation.invite_expires_at)?;
+
+        let claimed: Option<(bool,)> = sqlx::query_as(
+            "UPDATE will_witnesses \
+             SET invite_token_hash = $2, \
+                 reminder_count = CASE WHEN invite_sent_at IS NULL THEN reminder_count \
+                                       ELSE reminder_count + 1 END, \
+                 last_reminded_at = CASE WHEN invite_sent_at IS NULL THEN last_reminded_at \
+                                         ELSE NOW() END, \
+                 invite_sent_at = COALESCE(invite_sent_at, NOW()) \
+             WHERE id = $1 AND status = 'pending' \
+               AND (invite_sent_at IS NULL \
+                    OR (reminder_count < $3 \
+                        AND COALESCE(last_reminded_at, invite_sent_at) \
+                            < NOW() - make_interval(days => $4))) \
+             RETURNING reminder_count > 0",
+        )
+        .bind(invitation.id)
+        .bind(hash_token(&token))
+        .bind(MAX_WITNESS_REMINDERS)
+        .bind(WITNESS_REMINDER_INTERVAL_DAYS as i32)
+        .fetch_optional(&self.db)
+        .await?;
+        let Some((reminder,)) = claimed else {
+            return Ok(None);
+        };
+
+        let invite_url = format!("{}/api/will/witness-invitations/{}", self.base_url, token);
+        let template = AlertTemplate::WitnessInvitation {
+            owner_name: &invitation.owner_name,
+            invite_url: &invite_url,
+            expires_at: invitation.invite_expires_at,
+            reminder,
+        };
+        if let Err(e) = self
+            .provider
+            .send_templated_email(&invitation.email, &template)
+            .await
+        {
+            // Leave an unsent initial invitation for the next run. A failed
+            // reminder still counts, and the new token stays valid.
+            if !reminder {
+                sqlx::query(
+                    "UPDATE will_witnesses SET invite_sent_at = NULL, invite_token_hash = NULL \
+                     WHERE id = $1 AND status = 'pending'",
+                )
+                .bind(invitation.id)
+                .execute(&self.db)
+                .await?;
+            }
+            return Err(ApiError::Internal(e));
+        }
+        Ok(Some(reminder))
+    }
+
+    async fn expire_overdue(&self) -> Result<usize, ApiError> {
+        let expired: Vec<(Uuid, Uuid, Uuid, String)> = sqlx::query_as(
+            "UPDATE will_witnesses w \
+             SET status = 'expired', invite_token_hash = NULL \
+             FROM will_documents d \
+             JOIN plans p ON p.id = d.plan_id \
+             WHERE d.id = w.document_id AND w.status = 'pending' \
+               AND w.invite_expires_at <= NOW() \
+             RETURNING w.id, w.document_id, p.id, COALESCE(p.title, p.id::text)",
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        for (witness_id, document_id, plan_id, vault_id) in &expired {
+            let event = crate::will_events::WillEvent::WitnessExpired {
+                vault_id: vault_id.clone(),
+                document_id: *document_id,
+                plan_id: *plan_id,
+                witness_id: *witness_id,
+                timestamp: Utc::now(),
+            };
+            if let Err(e) = crate::will_events::WillEventService::emit(&self.db, event).await {
+                warn!("Failed to emit WitnessExpired event: {}", e);
+            }
+        }
+        Ok(expired.len())
+    }
+
+    /// Resolve a token to its witness, requiring it to be the outstanding one.
+    async fn witness_for(&self, token: &str) -> Result<(Uuid, String), ApiError> {
+        let witness_id = self.tokens.verify(token)?;
+        let token_hash = hash_token(token);
+        let current: Option<Option<String>> =
+            sqlx::query_scalar("SELECT invite_token_hash FROM will_witnesses WHERE id = $1")
+                .bind(witness_id)
+                .fetch_optional(&self.db)
+                .await?;
+        match current {
+            None => Err(ApiError::NotFound("Witness record not found".to_string())),
+            Some(Some(hash)) if hash == token_hash => Ok((witness_id, token_hash)),
+            Some(_) => Err(ApiError::Forbidden(
+                "Invitation link has already been used or replaced".to_string(),
+            )),
+        }
+    }
+
+    /// Show the invited witness the will they are asked to sign.
+    pub async fn view(&self, token: &str) -> Result<InvitationView, ApiError> {
+        #[derive(sqlx::FromRow)]
+        struct Row {
+            document_id: Uuid,
+            status: String,
+            witness_status: String,
+            version: i32,
+            owner_name: Option<String>,
+            will_hash: String,
+            rendered_content: Option<String>,
+            filename: String,
+            pdf_base64: String,
+            generated_at: DateTime<Utc>,
+            invite_expires_at: Option<DateTime<Utc>>,
+        }
+
+        let (witness_id, _) = self.witness_for(token).await?;
+        let row = sqlx::query_as::<_, Row>(
+            "UPDATE will_witnesses w SET viewed_at = COALESCE(w.viewed_at, NOW()) \
+             FROM will_documents d \
+             WHERE w.id = $1 AND d.id = w.document_id \
+             RETURNING d.id AS document_id, d.status, w.status AS witness_status, d.version, \
+                       d.owner_name, d.will_hash, d.rendered_content, d.filename, \
+                       d.pdf_base64, d.generated_at, w.invite_expires_at",
+        )
+        .bind(witness_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Witness record not found".to_string()))?;
+
+        if row.status == "revoked" {
+            return Err(ApiError::BadRequest(
+                "Will document has been revoked".to_string(),
+            ));
+        }
+        if row.witness_status != "pending" {
+            return Err(ApiError::BadRequest(format!(
+                "Witness has already {}",
+                row.witness_status
+            )));
+        }
+
+        Ok(InvitationView {
+            witness_id,
+            document_id: row.document_id,
+            version: row.version,
+            owner_name: row.owner_name,
+            signing_message: row.will_hash.clone(),
+            will_hash: row.will_hash,
+            rendered_content: row.rendered_content,
+            filename: row.filename,
+            pdf_base64: row.pdf_base64,
+            generated_at: row.generated_at,
+            invite_expires_at: row.invite_expires_at,
+        })
+    }
+
+    /// Sign as the invited witness. The token is consumed on success.
+    pub async fn sign(
+        &self,
+        token: &str,
+        req: &InvitationSignRequest,
+    ) -> Result<WitnessRecord, ApiError> {
+        let (witness_id, token_hash) = self.witness_for(token).await?;
+        WitnessService::record_signature(
+            &self.db,
+            witness_id,
+            Some(&token_hash),
+            req.key_type,
+            &req.public_key,
+            &req.signature_hex,
+        )
+        .await
+    }
+
+    /// Decline the invitation. The token is consumed on success.
+    pub async fn decline(&self, token: &str) -> Result<WitnessRecord, ApiError> {
+        let (witness_id, token_hash) = self.witness_for(token).await?;
+        WitnessService::record_decline(&self.db, witness_id, Some(&token_hash)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_token_round_trip() {
+        let tokens = WitnessInviteTokens::new(b"test-invite-key");
+        let witness_id = Uuid::new_v4();
+        let token = tokens
+            .issue(witness_id, Utc::now() + Duration::days(1))
+            .unwrap();
+
+        assert_eq!(tokens.verify(&token).unwrap(), witness_id);
+        assert!(matches!(
+            WitnessInviteTokens::new(b"other-key").verify(&token),
+            Err(ApiError::Unauthorized)
+        ));
+        let tampered = token.replacen(&witness_id.to_string(), &Uuid::new_v4().to_string(), 1);
+        assert!(matches!(
+            tokens.verify(&tampered),
+            Err(ApiError::Unauthorized)
+        ));
+        assert!(tokens.verify("not-a-token").is_err());
+    }
+
+    #[test]
+    fn test_expired_token_rejected() {
+        let tokens = WitnessInviteTokens::new(b"test-invite-key");
+        let token = tokens
+            .issue(Uuid::new_v4(), Utc::now() - Duration::seconds(5))
+            .unwrap();
+        assert!(matches!(tokens.verify(&token), Err(ApiError::Forbidden(_))));
+    }
+
+    #[test]
+    fn test_tokens_are_unique_per_issue() {
+        let tokens = WitnessInviteTokens::new(b"test-invite-key");
+        let witness_id = Uuid::new_v4();
+        let expires_at = Utc::now() + Duration::days(1);
+        let first = tokens.issue(witness_id, expires_at).unwrap();
+        let second = tokens.issue(witness_id, expires_at).unwrap();
+        assert_ne!(hash_token(&first), hash_token(&second));
+    }
+}
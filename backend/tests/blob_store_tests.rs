@@ -0,0 +1,288 @@
+mod helpers;
+
+use async_trait::async_trait;
+use helpers::TestContext;
+use httpmock::prelude::*;
+use httpmock::Method::HEAD;
+use inheritx_backend::api_error::ApiError;
+use inheritx_backend::blob_store::{BlobService, BlobStore, LocalBlobStore, S3BlobStore, S3Config};
+use inheritx_backend::document_storage::DocumentStorageService;
+use inheritx_backend::key_manager::KmsKeyManager;
+use sqlx::PgPool;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+fn store_for(server: &MockServer) -> S3BlobStore {
+    S3BlobStore::new(S3Config {
+        endpoint: server.base_url(),
+        bucket: "inheritx".to_string(),
+        region: "us-east-1".to_string(),
+        access_key_id: "minioadmin".to_string(),
+        secret_access_key: "minioadmin".to_string(),
+    })
+}
+
+#[tokio::test]
+async fn test_s3_put_sends_signed_path_style_request() {
+    let server = MockServer::start();
+    let put = server.mock(|when, then| {
+        when.method(PUT)
+            .path("/inheritx/sha256/ab/abc")
+            .header_exists("authorization")
+            .header_exists("x-amz-date")
+            .header(
+                "x-amz-content-sha256",
+                "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824",
+            )
+            .body("hello");
+        then.status(200);
+    });
+
+    store_for(&server)
+        .put("sha256/ab/abc", b"hello".to_vec())
+        .await
+        .unwrap();
+    put.assert();
+}
+
+#[tokio::test]
+async fn test_s3_get_and_exists() {
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/inheritx/sha256/ab/present");
+        then.status(200).body("payload");
+    });
+    server.mock(|when, then| {
+        when.method(HEAD).path("/inheritx/sha256/ab/present");
+        then.status(200);
+    });
+    server.mock(|when, then| {
+        when.method(HEAD).path("/inheritx/sha256/ab/missing");
+        then.status(404);
+    });
+    server.mock(|when, then| {
+        when.method(GET).path("/inheritx/sha256/ab/missing");
+        then.status(404);
+    });
+
+    let store = store_for(&server);
+    assert_eq!(store.get("sha256/ab/present").await.unwrap(), b"payload");
+    assert!(store.exists("sha256/ab/present").await.unwrap());
+    assert!(!store.exists("sha256/ab/missing").await.unwrap());
+    assert!(matches!(
+        store.get("sha256/ab/missing").await,
+        Err(ApiError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_s3_delete_tolerates_missing_objects() {
+    let server = MockServer::start();
+    let delete = server.mock(|when, then| {
+        when.method(DELETE).path("/inheritx/sha256/ab/gone");
+        then.status(404);
+    });
+
+    store_for(&server).delete("sha256/ab/gone").await.unwrap();
+    delete.assert();
+}
+
+#[tokio::test]
+async fn test_s3_server_error_is_reported() {
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(PUT).path("/inheritx/sha256/ab/fail");
+        then.status(503);
+    });
+
+    let result = store_for(&server)
+        .put("sha256/ab/fail", b"data".to_vec())
+        .await;
+    assert!(matches!(result, Err(ApiError::Internal(_))));
+}
+
+#[tokio::test]
+async fn test_blob_is_kept_until_its_last_reference_is_released() {
+    let Some(ctx) = TestContext::from_env().await else {
+        return;
+    };
+    let root = std::env::temp_dir().join(format!("blob-refs-{}", uuid::Uuid::new_v4()));
+    let store = LocalBlobStore::new(root.clone());
+    let bytes = uuid::Uuid::new_v4().as_bytes().to_vec();
+
+    let first = BlobService::store_bytes(&ctx.pool, &store, bytes.clone())
+        .await
+        .unwrap();
+    let second = BlobService::store_bytes(&ctx.pool, &store, bytes.clone())
+        .await
+        .unwrap();
+    assert_eq!(first.hash, second.hash);
+
+    BlobService::release(&ctx.pool, &store, &first.hash)
+        .await
+        .unwrap();
+    assert!(store.exists(&first.storage_key).await.unwrap());
+
+    BlobService::release(&ctx.pool, &store, &first.hash)
+        .await
+        .unwrap();
+    assert!(!store.exists(&first.storage_key).await.unwrap());
+    let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM blob_objects WHERE hash = $1")
+        .bind(&first.hash)
+        .fetch_one(&ctx.pool)
+        .await
+        .unwrap();
+    assert_eq!(rows, 0);
+
+    // A new reference after deletion uploads the object again
+    BlobService::store_bytes(&ctx.pool, &store, bytes.clone())
+        .await
+        .unwrap();
+    assert_eq!(BlobService::load(&store, &first.hash).await.unwrap(), bytes);
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+/// Local store that deletes a will document while its new content uploads
+struct DeletingStore {
+    inner: LocalBlobStore,
+    pool: PgPool,
+    document_id: Uuid,
+    keys: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl BlobStore for DeletingStore {
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<(), ApiError> {
+        sqlx::query("DELETE FROM will_documents WHERE id = $1")
+            .bind(self.document_id)
+            .execute(&self.pool)
+            .await?;
+        self.keys.lock().unwrap().push(key.to_string());
+        self.inner.put(key, bytes).await
+    }
+    async fn get(&self, key: &str) -> Result<Vec<u8>, ApiError> {
+        self.inner.get(key).await
+    }
+    async fn exists(&self, key: &str) -> Result<bool, ApiError> {
+        self.inner.exists(key).await
+    }
+    async fn delete(&self, key: &str) -> Result<(), ApiError> {
+        self.inner.delete(key).await
+    }
+}
+
+async fn create_will_document(pool: &PgPool) -> (Uuid, Uuid) {
+    let user_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO users (email, password_hash) VALUES ($1, 'x') RETURNING id",
+    )
+    .bind(format!("blob-doc-{}@example.com", Uuid::new_v4()))
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    let plan_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO plans (user_id, title, fee, net_amount, status) \
+         VALUES ($1, 'Family plan', 2, 98, 'pending') RETURNING id",
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    let document_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO will_documents (plan_id, user_id, template, will_hash, filename, pdf_base64) \
+         VALUES ($1, $2, 'formal', $3, 'will.pdf', '') RETURNING id",
+    )
+    .bind(plan_id)
+    .bind(user_id)
+    .bind("ab".repeat(32))
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    (user_id, document_id)
+}
+
+async fn blob_rows(pool: &PgPool, hash: &str) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM blob_objects WHERE hash = $1")
+        .bind(hash)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_replacing_document_content_releases_the_previous_blob() {
+    let Some(ctx) = TestContext::from_env().await else {
+        return;
+    };
+    let root = std::env::temp_dir().join(format!("blob-docs-{}", Uuid::new_v4()));
+    let store = LocalBlobStore::new(root.clone());
+    let keys = KmsKeyManager::new(b"blob-store-tests-kms-root-key-32-bytes", 1).unwrap();
+    let (user_id, document_id) = create_will_document(&ctx.pool).await;
+    let current_hash = || async {
+        sqlx::query_scalar::<_, Option<String>>(
+            "SELECT encrypted_blob_hash FROM will_documents WHERE id = $1",
+        )
+        .bind(document_id)
+        .fetch_one(&ctx.pool)
+        .await
+        .unwrap()
+        .unwrap()
+    };
+
+    DocumentStorageService::store_encrypted(&ctx.pool, &store, &keys, user_id, document_id, b"v1")
+        .await
+        .unwrap();
+    let first = current_hash().await;
+    DocumentStorageService::store_encrypted(&ctx.pool, &store, &keys, user_id, document_id, b"v2")
+        .await
+        .unwrap();
+    let second = current_hash().await;
+
+    assert_ne!(first, second);
+    assert_eq!(blob_rows(&ctx.pool, &first).await, 0);
+    assert_eq!(blob_rows(&ctx.pool, &second).await, 1);
+    assert_eq!(
+        DocumentStorageService::retrieve_decrypted(&ctx.pool, &store, &keys, user_id, document_id)
+            .await
+            .unwrap(),
+        b"v2"
+    );
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[tokio::test]
+async fn test_failed_document_update_releases_the_new_blob() {
+    let Some(ctx) = TestContext::from_env().await else {
+        return;
+    };
+    let root = std::env::temp_dir().join(format!("blob-docs-{}", Uuid::new_v4()));
+    let keys = KmsKeyManager::new(b"blob-store-tests-kms-root-key-32-bytes", 1).unwrap();
+    let (user_id, document_id) = create_will_document(&ctx.pool).await;
+    let store = DeletingStore {
+        inner: LocalBlobStore::new(root.clone()),
+        pool: ctx.pool.clone(),
+        document_id,
+        keys: Mutex::new(Vec::new()),
+    };
+
+    let result = DocumentStorageService::store_encrypted(
+        &ctx.pool,
+        &store,
+        &keys,
+        user_id,
+        document_id,
+        b"content",
+    )
+    .await;
+    assert!(matches!(result, Err(ApiError::Forbidden(_))));
+
+    // The reference taken for the upload is dropped along with the object
+    let uploaded = store.keys.lock().unwrap().clone();
+    assert_eq!(uploaded.len(), 1);
+    let hash = uploaded[0].rsplit('/').next().unwrap();
+    assert_eq!(blob_rows(&ctx.pool, hash).await, 0);
+    assert!(!store.exists(&uploaded[0]).await.unwrap());
+
+    let _ = std::fs::remove_dir_all(root);
+}
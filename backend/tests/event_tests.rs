@@ -54,6 +54,7 @@ fn test_liquidation_metadata_serialization() {
         collateral_seized: dec!(1200.00),
         debt_covered: dec!(1000.00),
         liquidation_penalty: dec!(50.00),
+        auction_id: None,
     };
 
     let json = serde_json::to_value(&metadata).unwrap();
@@ -1,9 +1,11 @@
 // This file is a placeholder for helper functions and structs.
 use axum::{body::Body, http::Request, Router};
-use inheritx_backend::{create_app, Config};
+use inheritx_backend::contract_reader::InheritanceContractReader;
+use inheritx_backend::{create_app, create_app_with_contract_reader, Config};
 use serde_json::json;
 use sqlx::{postgres::PgPoolOptions, PgPool};
 use std::env;
+use std::sync::Arc;
 use tower::ServiceExt;
 
 pub struct TestContext {
@@ -13,11 +15,56 @@ pub struct TestContext {
 }
 
 impl TestContext {
+    #[allow(dead_code)]
     pub async fn from_env() -> Option<Self> {
+        Self::build(None).await
+    }
+
+    /// [`Self::from_env`], reading the contract through `reader`
+    #[allow(dead_code)]
+    pub async fn with_contract_reader(reader: Arc<dyn InheritanceContractReader>) -> Option<Self> {
+        Self::build(Some(reader)).await
+    }
+
+    async fn build(reader: Option<Arc<dyn InheritanceContractReader>>) -> Option<Self> {
         // Use a static to ensure tracing is only initialized once
         static INIT: std::sync::Once = std::sync::Once::new();
         INIT.call_once(|| {
             let _ = inheritx_backend::telemetry::init_tracing();
+            // Deliver alerts through the mock provider
+            if env::var("APP_ENV").is_err() {
+                env::set_var("APP_ENV", "test");
+            }
+            if env::var("WITNESS_INVITE_SIGNING_KEY").is_err() {
+                env::set_var("WITNESS_INVITE_SIGNING_KEY", "test-witness-invite-key");
+            }
+            if env::var("DOWNLOAD_URL_SIGNING_KEY").is_err() {
+                env::set_var("DOWNLOAD_URL_SIGNING_KEY", "test-download-url-key");
+            }
+            // Render wills as PDF/A with the fixture fonts and colour profile
+            if env::var("WILL_PDF_FONT_PATH").is_err() {
+                let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+                env::set_var(
+                    "WILL_PDF_FONT_PATH",
+                    format!("{fixtures}/DejaVuSansMono.ttf"),
+                );
+                env::set_var(
+                    "WILL_PDF_BOLD_FONT_PATH",
+                    format!("{fixtures}/DejaVuSansMono-Bold.ttf"),
+                );
+                env::set_var(
+                    "WILL_PDF_ICC_PROFILE_PATH",
+                    format!("{fixtures}/srgb_test.icc"),
+                );
+            }
+            // Wrap document keys with a fixed root instead of a keyring file
+            if env::var("DOCUMENT_KEY_MANAGER").is_err() {
+                env::set_var("DOCUMENT_KEY_MANAGER", "kms");
+                env::set_var(
+                    "DOCUMENT_KMS_ROOT_KEY",
+                    "test-document-kms-root-key-32-bytes",
+                );
+            }
         });
 
         let database_url = match env::var("DATABASE_URL") {
@@ -50,10 +97,15 @@ impl TestContext {
         inheritx_backend::db::run_migrations(&pool)
             .await
             .expect("failed to run migrations");
-
-        let app = create_app(pool.clone(), config)
+        inheritx_backend::WillTemplateService::ensure_builtin_templates(&pool)
             .await
-            .expect("failed to create app");
+            .expect("failed to seed will templates");
+
+        let app = match reader {
+            Some(reader) => create_app_with_contract_reader(pool.clone(), config, reader).await,
+            None => create_app(pool.clone(), config).await,
+        }
+        .expect("failed to create app");
         Some(Self { app, pool })
     }
 
@@ -160,4 +212,4 @@ pub async fn create_admin_and_get_token(ctx: &mut TestContext) -> String {
     let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
 
     json["token"].as_str().unwrap().to_string()
-}
\ No newline at end of file
+}
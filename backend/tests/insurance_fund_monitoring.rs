@@ -5,9 +5,18 @@ use axum::{
     http::{Request, StatusCode},
 };
 use helpers::TestContext;
+use inheritx_backend::api_error::ApiError;
+use inheritx_backend::contract_reader::UnconfiguredContractReader;
+use inheritx_backend::insurance_fund::{
+    CreateInsuranceClaimRequest, InsuranceFundService, ProcessInsuranceClaimRequest,
+};
+use inheritx_backend::insurance_fund_contract::{
+    InsuranceFundContract, OnChainClaim, OnChainClaimStatus, OnChainFundMetrics,
+};
 use rust_decimal::Decimal;
 use serde_json::{json, Value};
 use sqlx::Row;
+use std::sync::Arc;
 use tower::ServiceExt;
 use uuid::Uuid;
 
@@ -555,3 +564,461 @@ async fn test_full_insurance_fund_lifecycle() {
     assert!(funds["data"].is_array());
     assert!(funds["count"].is_number());
 }
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Claim Review Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn claim_service(pool: &sqlx::PgPool) -> InsuranceFundService {
+    InsuranceFundService::new(pool.clone(), None, Arc::new(UnconfiguredContractReader))
+}
+
+/// A funded, off-chain fund of its own so reserve checks are not shared
+/// with other tests
+async fn create_test_fund(pool: &sqlx::PgPool, reserves: Decimal) -> Uuid {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO insurance_fund (fund_name, asset_code, total_reserves, available_reserves)
+        VALUES ($1, 'USDC', $2, $2)
+        RETURNING id
+        "#,
+    )
+    .bind(format!("Test Fund {}", Uuid::new_v4()))
+    .bind(reserves)
+    .fetch_one(pool)
+    .await
+    .expect("failed to create fund")
+}
+
+async fn create_claimant(pool: &sqlx::PgPool, email: &str) -> sqlx::Result<Uuid> {
+    sqlx::query_scalar("INSERT INTO users (email, password_hash) VALUES ($1, 'hash') RETURNING id")
+        .bind(email)
+        .fetch_one(pool)
+        .await
+}
+
+/// An admin to review claims. The audit log's actor references users, so
+/// the admin gets a user row with the same ID.
+async fn create_reviewer(pool: &sqlx::PgPool, email: &str) -> sqlx::Result<Uuid> {
+    let admin_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO admins (email, password_hash, role) VALUES ($1, 'hash', 'admin') RETURNING id",
+    )
+    .bind(email)
+    .fetch_one(pool)
+    .await?;
+    sqlx::query("INSERT INTO users (id, email, password_hash) VALUES ($1, $2, 'hash')")
+        .bind(admin_id)
+        .bind(email)
+        .execute(pool)
+        .await?;
+    Ok(admin_id)
+}
+
+fn claim_request(claim_type: &str, amount: Decimal) -> CreateInsuranceClaimRequest {
+    CreateInsuranceClaimRequest {
+        claim_type: claim_type.to_string(),
+        claimed_amount: amount,
+        plan_id: None,
+        loan_id: None,
+        description: None,
+        metadata: None,
+    }
+}
+
+fn approval() -> ProcessInsuranceClaimRequest {
+    ProcessInsuranceClaimRequest {
+        approved: true,
+        approved_amount: None,
+        rejection_reason: None,
+    }
+}
+
+#[tokio::test]
+async fn test_large_claim_needs_a_second_admin() {
+    let Some(test_context) = TestContext::from_env().await else {
+        return;
+    };
+    let pool = &test_context.pool;
+    let service = claim_service(pool);
+
+    let fund_id = create_test_fund(pool, Decimal::new(1_000_000, 0)).await;
+    let user_id = create_claimant(pool, &format!("claimant-{}@example.com", Uuid::new_v4()))
+        .await
+        .expect("failed to create user");
+    let first_admin = create_reviewer(pool, &format!("first-{}@example.com", Uuid::new_v4()))
+        .await
+        .expect("failed to create admin");
+    let second_admin = create_reviewer(pool, &format!("second-{}@example.com", Uuid::new_v4()))
+        .await
+        .expect("failed to create admin");
+
+    // Above the default policy's dual review threshold of 10000
+    let claim = service
+        .create_claim(
+            fund_id,
+            user_id,
+            &claim_request("default", Decimal::new(20_000, 0)),
+        )
+        .await
+        .expect("failed to create claim");
+
+    let claim = service
+        .process_claim(claim.id, first_admin, &approval())
+        .await
+        .expect("first approval failed");
+    assert_eq!(claim.status, "awaiting_second_review");
+    assert_eq!(claim.first_reviewed_by, Some(first_admin));
+    assert_eq!(claim.approved_amount, Some(Decimal::new(20_000, 0)));
+    assert!(claim.payout_amount.is_none());
+
+    // The first reviewer cannot confirm their own approval
+    let result = service
+        .process_claim(claim.id, first_admin, &approval())
+        .await;
+    assert!(matches!(result, Err(ApiError::Forbidden(_))));
+    assert_eq!(
+        service.get_claim(claim.id).await.unwrap().status,
+        "awaiting_second_review"
+    );
+
+    // Nor can the second reviewer change the amount
+    let result = service
+        .process_claim(
+            claim.id,
+            second_admin,
+            &ProcessInsuranceClaimRequest {
+                approved_amount: Some(Decimal::new(15_000, 0)),
+                ..approval()
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(ApiError::BadRequest(_))));
+
+    let claim = service
+        .process_claim(claim.id, second_admin, &approval())
+        .await
+        .expect("second approval failed");
+    assert_eq!(claim.status, "approved");
+    assert_eq!(claim.reviewed_by, Some(second_admin));
+    assert_eq!(claim.payout_amount, Some(Decimal::new(20_000, 0)));
+}
+
+#[tokio::test]
+async fn test_small_claim_is_approved_by_one_admin() {
+    let Some(test_context) = TestContext::from_env().await else {
+        return;
+    };
+    let pool = &test_context.pool;
+    let service = claim_service(pool);
+
+    let fund_id = create_test_fund(pool, Decimal::new(1_000_000, 0)).await;
+    let user_id = create_claimant(pool, &format!("claimant-{}@example.com", Uuid::new_v4()))
+        .await
+        .expect("failed to create user");
+    let admin_id = create_reviewer(pool, &format!("admin-{}@example.com", Uuid::new_v4()))
+        .await
+        .expect("failed to create admin");
+
+    let claim = service
+        .create_claim(
+            fund_id,
+            user_id,
+            &claim_request("default", Decimal::new(5_000, 0)),
+        )
+        .await
+        .expect("failed to create claim");
+    let claim = service
+        .process_claim(claim.id, admin_id, &approval())
+        .await
+        .expect("approval failed");
+
+    assert_eq!(claim.status, "approved");
+    assert!(claim.first_reviewed_by.is_none());
+    assert_eq!(claim.payout_amount, Some(Decimal::new(5_000, 0)));
+}
+
+#[tokio::test]
+async fn test_approvals_cannot_exceed_fund_reserves() {
+    let Some(test_context) = TestContext::from_env().await else {
+        return;
+    };
+    let pool = &test_context.pool;
+    let service = claim_service(pool);
+
+    let fund_id = create_test_fund(pool, Decimal::new(8_000, 0)).await;
+    let user_id = create_claimant(pool, &format!("claimant-{}@example.com", Uuid::new_v4()))
+        .await
+        .expect("failed to create user");
+    let admin_id = create_reviewer(pool, &format!("admin-{}@example.com", Uuid::new_v4()))
+        .await
+        .expect("failed to create admin");
+
+    let first = service
+        .create_claim(
+            fund_id,
+            user_id,
+            &claim_request("default", Decimal::new(5_000, 0)),
+        )
+        .await
+        .expect("failed to create claim");
+    let second = service
+        .create_claim(
+            fund_id,
+            user_id,
+            &claim_request("default", Decimal::new(5_000, 0)),
+        )
+        .await
+        .expect("failed to create claim");
+
+    service
+        .process_claim(first.id, admin_id, &approval())
+        .await
+        .expect("approval failed");
+
+    // The first approval is still unpaid, so it holds 5000 of the 8000
+    let (_, adjudication) = service
+        .adjudicate_claim(&second)
+        .await
+        .expect("adjudication failed");
+    assert_eq!(adjudication.payable_amount, Decimal::new(3_000, 0));
+
+    let result = service
+        .process_claim(
+            second.id,
+            admin_id,
+            &ProcessInsuranceClaimRequest {
+                approved_amount: Some(Decimal::new(5_000, 0)),
+                ..approval()
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(ApiError::BadRequest(_))));
+}
+
+#[tokio::test]
+async fn test_claim_review_deadline_follows_policy_sla() {
+    let Some(test_context) = TestContext::from_env().await else {
+        return;
+    };
+    let pool = &test_context.pool;
+    let service = claim_service(pool);
+
+    let fund_id = create_test_fund(pool, Decimal::new(1_000_000, 0)).await;
+    let user_id = create_claimant(pool, &format!("claimant-{}@example.com", Uuid::new_v4()))
+        .await
+        .expect("failed to create user");
+
+    // Emergency payouts are due for review within 24 hours
+    let claim = service
+        .create_claim(
+            fund_id,
+            user_id,
+            &claim_request("emergency_payout", Decimal::new(100, 0)),
+        )
+        .await
+        .expect("failed to create claim");
+    let due = claim.review_due_at.expect("claim has no review deadline");
+    assert_eq!((due - claim.created_at).num_hours(), 24);
+    assert!(claim.sla_breached_at.is_none());
+
+    sqlx::query(
+        "UPDATE insurance_claims SET review_due_at = NOW() - INTERVAL '1 hour' WHERE id = $1",
+    )
+    .bind(claim.id)
+    .execute(pool)
+    .await
+    .expect("failed to backdate claim");
+
+    service
+        .flag_overdue_claims()
+        .await
+        .expect("failed to flag overdue claims");
+    let breached_at = service
+        .get_claim(claim.id)
+        .await
+        .unwrap()
+        .sla_breached_at
+        .expect("overdue claim was not flagged");
+
+    // Flagged once only
+    service
+        .flag_overdue_claims()
+        .await
+        .expect("failed to flag overdue claims");
+    assert_eq!(
+        service.get_claim(claim.id).await.unwrap().sla_breached_at,
+        Some(breached_at)
+    );
+}
+
+#[tokio::test]
+async fn test_liquidation_shortfall_is_claimed_once() {
+    let Some(test_context) = TestContext::from_env().await else {
+        return;
+    };
+    let pool = &test_context.pool;
+    let service = claim_service(pool);
+
+    let user_id = create_claimant(pool, &format!("borrower-{}@example.com", Uuid::new_v4()))
+        .await
+        .expect("failed to create user");
+    let event_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO lending_events (event_type, user_id, asset_code, amount, metadata)
+        VALUES ('liquidation', $1, 'USDC', '1500', $2)
+        RETURNING id
+        "#,
+    )
+    .bind(user_id)
+    .bind(json!({ "debt_covered": "1500", "collateral_seized": "1200" }))
+    .fetch_one(pool)
+    .await
+    .expect("failed to insert liquidation");
+
+    service
+        .generate_automatic_claims()
+        .await
+        .expect("failed to file automatic claims");
+    service
+        .generate_automatic_claims()
+        .await
+        .expect("failed to file automatic claims");
+
+    let claims: Vec<(
+        String,
+        Decimal,
+        String,
+        Option<chrono::DateTime<chrono::Utc>>,
+    )> = sqlx::query_as(
+        r#"
+            SELECT claim_type, claimed_amount, status, review_due_at
+            FROM insurance_claims
+            WHERE source_key = $1
+            "#,
+    )
+    .bind(format!("liquidation:{}", event_id))
+    .fetch_all(pool)
+    .await
+    .expect("query failed");
+
+    assert_eq!(claims.len(), 1);
+    let (claim_type, amount, status, review_due_at) = &claims[0];
+    assert_eq!(claim_type, "liquidation_shortfall");
+    assert_eq!(*amount, Decimal::new(300, 0));
+    assert_eq!(status, "pending");
+    assert!(review_due_at.is_some());
+}
+
+/// Contract whose submissions fail until `accept` is set
+#[derive(Default)]
+struct FlakyContract {
+    accept: std::sync::atomic::AtomicBool,
+    filed: std::sync::Mutex<Vec<Uuid>>,
+}
+
+#[async_trait::async_trait]
+impl InsuranceFundContract for FlakyContract {
+    async fn metrics(&self) -> Result<OnChainFundMetrics, ApiError> {
+        Err(ApiError::BadRequest("metrics unavailable".to_string()))
+    }
+
+    async fn claim(&self, claim_id: Uuid) -> Result<Option<OnChainClaim>, ApiError> {
+        let filed = self.filed.lock().unwrap().contains(&claim_id);
+        Ok(filed.then_some(OnChainClaim {
+            status: OnChainClaimStatus::Pending,
+            amount: Decimal::ZERO,
+            approvals: 1,
+        }))
+    }
+
+    async fn file_claim(
+        &self,
+        claim_id: Uuid,
+        _beneficiary: &str,
+        _amount: Decimal,
+    ) -> Result<String, ApiError> {
+        if !self.accept.load(std::sync::atomic::Ordering::SeqCst) {
+            return Err(ApiError::BadRequest("RPC unavailable".to_string()));
+        }
+        self.filed.lock().unwrap().push(claim_id);
+        Ok("filing-hash".to_string())
+    }
+
+    async fn payout_claim(&self, _claim_id: Uuid) -> Result<String, ApiError> {
+        Err(ApiError::BadRequest("not used".to_string()))
+    }
+}
+
+#[tokio::test]
+async fn test_failed_on_chain_filing_is_retried() {
+    let Some(test_context) = TestContext::from_env().await else {
+        return;
+    };
+    let pool = &test_context.pool;
+    let contract = Arc::new(FlakyContract::default());
+    let service = InsuranceFundService::new(
+        pool.clone(),
+        Some(contract.clone()),
+        Arc::new(UnconfiguredContractReader),
+    );
+
+    // Only the primary fund is on-chain
+    let fund_id: Uuid =
+        sqlx::query_scalar("SELECT id FROM insurance_fund ORDER BY created_at LIMIT 1")
+            .fetch_one(pool)
+            .await
+            .expect("fund not found");
+    sqlx::query(
+        "UPDATE insurance_fund SET total_reserves = GREATEST(total_reserves, 1000000) WHERE id = $1",
+    )
+    .bind(fund_id)
+    .execute(pool)
+    .await
+    .expect("failed to fund reserves");
+
+    let user_id = create_claimant(pool, &format!("claimant-{}@example.com", Uuid::new_v4()))
+        .await
+        .expect("failed to create user");
+    sqlx::query("UPDATE users SET wallet_address = $1 WHERE id = $2")
+        .bind(format!("G{}", Uuid::new_v4().simple()))
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .expect("failed to set wallet");
+    let admin_id = create_reviewer(pool, &format!("admin-{}@example.com", Uuid::new_v4()))
+        .await
+        .expect("failed to create admin");
+
+    let claim = service
+        .create_claim(
+            fund_id,
+            user_id,
+            &claim_request("default", Decimal::new(100, 0)),
+        )
+        .await
+        .expect("failed to create claim");
+
+    // The approval is kept even though the submission fails
+    assert!(service
+        .process_claim(claim.id, admin_id, &approval())
+        .await
+        .is_err());
+    let stuck = service.get_claim(claim.id).await.unwrap();
+    assert_eq!(stuck.status, "filing");
+    assert_eq!(stuck.payout_amount, Some(Decimal::new(100, 0)));
+    assert!(stuck.filing_tx_hash.is_none());
+
+    // Recent filings are left to the approval that started them
+    contract
+        .accept
+        .store(true, std::sync::atomic::Ordering::SeqCst);
+    service.retry_claim_filings(5).await.unwrap();
+    assert_eq!(service.get_claim(claim.id).await.unwrap().status, "filing");
+
+    service.retry_claim_filings(0).await.unwrap();
+
+    let filed = service.get_claim(claim.id).await.unwrap();
+    assert_eq!(filed.status, "approved");
+    assert_eq!(filed.filing_tx_hash.as_deref(), Some("filing-hash"));
+    assert_eq!(contract.filed.lock().unwrap().as_slice(), &[claim.id]);
+}
@@ -0,0 +1,366 @@
+mod helpers;
+
+use async_trait::async_trait;
+use base64::Engine as _;
+use chrono::{Duration, Utc};
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use curve25519_dalek::scalar::Scalar;
+use helpers::TestContext;
+use inheritx_backend::alert_provider::{AlertProvider, DeliveryReceipt};
+use inheritx_backend::api_error::ApiError;
+use inheritx_backend::contract_reader::{
+    InheritanceContractReader, OnChainInheritanceTrigger, OnChainLegacyMessage, OnChainPlan,
+};
+use inheritx_backend::message_e2e::{
+    CreateE2eMessageRequest, E2eEnvelope, E2eMessageService, RecipientKeyQuery,
+    RecipientKeyService, RegisterRecipientKeyRequest, WrappedRecipientKey, E2E_ALGORITHM,
+};
+use inheritx_backend::LegacyMessageDeliveryService;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM};
+use ring::hkdf::{Salt, HKDF_SHA256};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// Keeps every email body so the test can read the claim link
+#[derive(Default)]
+struct CapturingAlertProvider {
+    emails: Mutex<Vec<(String, String)>>,
+}
+
+#[async_trait]
+impl AlertProvider for CapturingAlertProvider {
+    async fn send_sms(&self, _to: &str, _message: &str) -> anyhow::Result<DeliveryReceipt> {
+        anyhow::bail!("SMS is not used in this test")
+    }
+
+    async fn send_email(
+        &self,
+        to: &str,
+        _subject: &str,
+        body: &str,
+    ) -> anyhow::Result<DeliveryReceipt> {
+        self.emails
+            .lock()
+            .unwrap()
+            .push((to.to_string(), body.to_string()));
+        Ok(DeliveryReceipt {
+            provider_message_id: format!("capture-{}", Uuid::new_v4()),
+        })
+    }
+}
+
+/// Contract holding the one legacy message the owner's wallet anchored
+struct AnchoredMessage(OnChainLegacyMessage);
+
+#[async_trait]
+impl InheritanceContractReader for AnchoredMessage {
+    async fn get_plan(&self, _plan_id: u64) -> Result<Option<OnChainPlan>, ApiError> {
+        Ok(None)
+    }
+    async fn get_will_hash(&self, _plan_id: u64) -> Result<Option<String>, ApiError> {
+        Ok(None)
+    }
+    async fn get_will_version_hash(
+        &self,
+        _plan_id: u64,
+        _version: u32,
+    ) -> Result<Option<String>, ApiError> {
+        Ok(None)
+    }
+    async fn is_will_finalized(&self, _plan_id: u64, _version: u32) -> Result<bool, ApiError> {
+        Ok(false)
+    }
+    async fn is_will_revoked(&self, _plan_id: u64, _version: u32) -> Result<bool, ApiError> {
+        Ok(false)
+    }
+    async fn get_inheritance_trigger(
+        &self,
+        _plan_id: u64,
+    ) -> Result<Option<OnChainInheritanceTrigger>, ApiError> {
+        Ok(None)
+    }
+    async fn emergency_transfer_limit_bp(&self) -> Result<u32, ApiError> {
+        Ok(1000)
+    }
+    async fn get_legacy_message(
+        &self,
+        message_id: u64,
+    ) -> Result<Option<OnChainLegacyMessage>, ApiError> {
+        Ok(Some(self.0.clone()).filter(|m| m.message_id == message_id))
+    }
+}
+
+fn b64() -> base64::engine::GeneralPurpose {
+    base64::engine::general_purpose::STANDARD
+}
+
+fn scalar(seed: u8) -> Scalar {
+    Scalar::from_bytes_mod_order([seed; 32])
+}
+
+fn wrap_key(shared: &[u8; 32], ephemeral: &[u8], recipient: &[u8]) -> LessSafeKey {
+    let salt_bytes = [ephemeral, recipient].concat();
+    let prk = Salt::new(HKDF_SHA256, &salt_bytes).extract(shared);
+    let info = [E2E_ALGORITHM.as_bytes()];
+    let okm = prk.expand(&info, &AES_256_GCM).unwrap();
+    let mut raw = [0u8; 32];
+    okm.fill(&mut raw).unwrap();
+    LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &raw).unwrap())
+}
+
+fn seal(key: &LessSafeKey, nonce: [u8; 12], plaintext: &[u8]) -> Vec<u8> {
+    let mut in_out = plaintext.to_vec();
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce),
+        Aad::empty(),
+        &mut in_out,
+    )
+    .unwrap();
+    in_out
+}
+
+fn open(key: &LessSafeKey, nonce: &[u8], ciphertext: &[u8]) -> Option<Vec<u8>> {
+    let nonce = Nonce::try_assume_unique_for_key(nonce).ok()?;
+    let mut in_out = ciphertext.to_vec();
+    key.open_in_place(nonce, Aad::empty(), &mut in_out)
+        .ok()
+        .map(|plaintext| plaintext.to_vec())
+}
+
+/// What the beneficiary's client does with a claimed envelope
+fn open_envelope(envelope: &E2eEnvelope, recipient_secret: Scalar) -> Option<Vec<u8>> {
+    let recipient_public = MontgomeryPoint::mul_base(&recipient_secret).to_bytes();
+    let entry = envelope
+        .recipients
+        .iter()
+        .find(|r| b64().decode(&r.recipient_public_key).unwrap() == recipient_public)?;
+    let ephemeral: [u8; 32] = b64()
+        .decode(&entry.ephemeral_public_key)
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let shared = (MontgomeryPoint(ephemeral) * recipient_secret).to_bytes();
+    let content_key = open(
+        &wrap_key(&shared, &ephemeral, &recipient_public),
+        &b64().decode(&entry.wrap_nonce).unwrap(),
+        &b64().decode(&entry.wrapped_key).unwrap(),
+    )?;
+    let key = LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &content_key).ok()?);
+    open(
+        &key,
+        &b64().decode(&envelope.nonce).unwrap(),
+        &b64().decode(&envelope.ciphertext).unwrap(),
+    )
+}
+
+async fn create_user(pool: &PgPool, email: &str, wallet: &str) -> Uuid {
+    sqlx::query_scalar(
+        "INSERT INTO users (email, password_hash, wallet_address) VALUES ($1, 'x', $2) RETURNING id",
+    )
+    .bind(email)
+    .bind(wallet)
+    .fetch_one(pool)
+    .await
+    .expect("failed to create user")
+}
+
+async fn add_beneficiary(pool: &PgPool, owner_id: Uuid, wallet: &str) {
+    let plan_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO plans (user_id, title, fee, net_amount, status) \
+         VALUES ($1, 'Family plan', 2, 98, 'pending') RETURNING id",
+    )
+    .bind(owner_id)
+    .fetch_one(pool)
+    .await
+    .expect("failed to create plan");
+    sqlx::query(
+        "INSERT INTO plan_beneficiaries (plan_id, wallet_address, allocation_percent) \
+         VALUES ($1, $2, 100)",
+    )
+    .bind(plan_id)
+    .bind(wallet)
+    .execute(pool)
+    .await
+    .expect("failed to add beneficiary");
+}
+
+fn rand_message_id() -> u64 {
+    Uuid::new_v4().as_u64_pair().0 >> 1
+}
+
+fn wallet() -> String {
+    format!("G{}", Uuid::new_v4().simple()).to_uppercase()
+}
+
+#[tokio::test]
+async fn e2e_message_opens_for_its_recipient_only() {
+    let Some(ctx) = TestContext::from_env().await else {
+        return;
+    };
+    let pool = &ctx.pool;
+
+    let owner_wallet = wallet();
+    let heir_wallet = wallet();
+    let heir_email = format!("heir-{}@example.com", Uuid::new_v4());
+    let owner_id = create_user(
+        pool,
+        &format!("owner-{}@example.com", Uuid::new_v4()),
+        &owner_wallet,
+    )
+    .await;
+    let heir_id = create_user(pool, &heir_email, &heir_wallet).await;
+    let stranger_id = create_user(
+        pool,
+        &format!("stranger-{}@example.com", Uuid::new_v4()),
+        &wallet(),
+    )
+    .await;
+    add_beneficiary(pool, owner_id, &heir_wallet).await;
+
+    // The heir publishes a key; only the owner whose plan names them finds it
+    let recipient_secret = scalar(3);
+    let recipient_public = MontgomeryPoint::mul_base(&recipient_secret).to_bytes();
+    RecipientKeyService::register(
+        pool,
+        heir_id,
+        &RegisterRecipientKeyRequest {
+            x25519_public_key: b64().encode(recipient_public),
+        },
+    )
+    .await
+    .expect("failed to register key");
+
+    let query = RecipientKeyQuery {
+        contact: Some(heir_email.clone()),
+        stellar_address: None,
+    };
+    let found = RecipientKeyService::lookup(pool, owner_id, &query)
+        .await
+        .expect("owner should find the heir's key");
+    assert_eq!(found.x25519_public_key, b64().encode(recipient_public));
+    assert!(matches!(
+        RecipientKeyService::lookup(pool, stranger_id, &query).await,
+        Err(ApiError::NotFound(_))
+    ));
+
+    // The owner's client encrypts and wraps the content key for the heir
+    let content_key = [42u8; 32];
+    let ciphertext = seal(
+        &LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &content_key).unwrap()),
+        [7u8; 12],
+        b"The key is under the oak tree",
+    );
+    let ephemeral_secret = scalar(9);
+    let ephemeral_public = MontgomeryPoint::mul_base(&ephemeral_secret).to_bytes();
+    let shared = (MontgomeryPoint(recipient_public) * ephemeral_secret).to_bytes();
+    let wrapped = seal(
+        &wrap_key(&shared, &ephemeral_public, &recipient_public),
+        [1u8; 12],
+        &content_key,
+    );
+
+    // The owner's wallet anchors the ciphertext hash before storing it
+    let on_chain_message_id = rand_message_id();
+    let chain = AnchoredMessage(OnChainLegacyMessage {
+        message_id: on_chain_message_id,
+        vault_id: 9,
+        message_hash: hex::encode(Sha256::digest(&ciphertext)),
+        creator: owner_wallet.clone(),
+    });
+    let request = CreateE2eMessageRequest {
+        vault_id: Some(9),
+        beneficiary_contact: heir_email.clone(),
+        unlock_at: Utc::now() + Duration::days(1),
+        ciphertext: b64().encode(&ciphertext),
+        nonce: b64().encode([7u8; 12]),
+        content_hash: hex::encode(Sha256::digest(&ciphertext)),
+        on_chain_message_id,
+        recipients: vec![WrappedRecipientKey {
+            recipient_public_key: b64().encode(recipient_public),
+            ephemeral_public_key: b64().encode(ephemeral_public),
+            wrapped_key: b64().encode(&wrapped),
+            wrap_nonce: b64().encode([1u8; 12]),
+        }],
+    };
+    assert!(matches!(
+        E2eMessageService::create(pool, &chain, stranger_id, &request).await,
+        Err(ApiError::Forbidden(_))
+    ));
+    let mut unanchored = request.clone();
+    unanchored.on_chain_message_id += 1;
+    assert!(matches!(
+        E2eMessageService::create(pool, &chain, owner_id, &unanchored).await,
+        Err(ApiError::BadRequest(_))
+    ));
+    let message = E2eMessageService::create(pool, &chain, owner_id, &request)
+        .await
+        .expect("failed to create message");
+    assert_eq!(message.beneficiary_contact, heir_email);
+
+    // Unlock it and let the delivery worker send the claim link
+    sqlx::query("UPDATE legacy_messages SET unlock_at = NOW() - INTERVAL '1 minute' WHERE id = $1")
+        .bind(message.id)
+        .execute(pool)
+        .await
+        .expect("failed to unlock message");
+    let provider = Arc::new(CapturingAlertProvider::default());
+    LegacyMessageDeliveryService::new(pool.clone(), provider.clone())
+        .process_due_messages()
+        .await
+        .expect("delivery failed");
+
+    let body = provider
+        .emails
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(to, _)| *to == heir_email)
+        .map(|(_, body)| body.clone())
+        .expect("no claim link sent to the heir");
+    let token = body
+        .split("/api/messages/legacy/claim/")
+        .nth(1)
+        .and_then(|rest| rest.split_whitespace().next())
+        .expect("claim link not in email");
+
+    let claimed =
+        LegacyMessageDeliveryService::claim_message(pool, &chain, token, heir_id, &heir_email)
+            .await
+            .expect("heir should claim the message");
+    assert!(claimed.message.is_none(), "the server has no plaintext");
+    let envelope = claimed.envelope.expect("claim should return the envelope");
+    assert_eq!(
+        envelope.content_hash,
+        hex::encode(Sha256::digest(&ciphertext))
+    );
+    assert_eq!(envelope.on_chain_message_id, Some(on_chain_message_id));
+
+    assert_eq!(
+        open_envelope(&envelope, recipient_secret).as_deref(),
+        Some(b"The key is under the oak tree".as_slice())
+    );
+    // A different key cannot unwrap the content key
+    let mut impostor = envelope.clone();
+    impostor.recipients[0].recipient_public_key =
+        b64().encode(MontgomeryPoint::mul_base(&scalar(5)).to_bytes());
+    assert!(open_envelope(&impostor, scalar(5)).is_none());
+
+    // Ciphertext swapped in the database no longer matches the anchor
+    sqlx::query("UPDATE legacy_messages SET encrypted_payload = $2 WHERE id = $1")
+        .bind(message.id)
+        .bind(seal(
+            &LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &content_key).unwrap()),
+            [7u8; 12],
+            b"The key is under the elm tree",
+        ))
+        .execute(pool)
+        .await
+        .expect("failed to swap ciphertext");
+    assert!(
+        LegacyMessageDeliveryService::claim_message(pool, &chain, token, heir_id, &heir_email)
+            .await
+            .is_err()
+    );
+}
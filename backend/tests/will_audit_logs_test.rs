@@ -102,20 +102,7 @@ async fn test_audit_log_created_on_document_generation() {
         .uri(format!("/api/plans/{}/will/generate", plan_id))
         .header("Authorization", format!("Bearer {}", token))
         .header("Content-Type", "application/json")
-        .body(Body::from(
-            json!({
-                "owner_name": "Alice Testator",
-                "owner_wallet": "GABC1234567890ABCDEF",
-                "vault_id": "vault-001",
-                "beneficiaries": [{
-                    "name": "Bob Beneficiary",
-                    "wallet_address": "GBOB1234567890ABCDEF",
-                    "allocation_percent": "100.0000"
-                }],
-                "template": "formal"
-            })
-            .to_string(),
-        ))
+        .body(Body::from(json!({ "template": "formal" }).to_string()))
         .unwrap();
 
     let response = ctx.app.clone().oneshot(request).await.unwrap();
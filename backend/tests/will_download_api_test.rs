@@ -70,20 +70,7 @@ async fn test_download_will_document_success() {
         .uri(format!("/api/plans/{}/will/generate", plan_id))
         .header("Authorization", format!("Bearer {}", token))
         .header("Content-Type", "application/json")
-        .body(Body::from(
-            json!({
-                "owner_name": "Alice Testator",
-                "owner_wallet": "GABC1234567890ABCDEF",
-                "vault_id": "vault-001",
-                "beneficiaries": [{
-                    "name": "Bob Beneficiary",
-                    "wallet_address": "GBOB1234567890ABCDEF",
-                    "allocation_percent": "100.0000"
-                }],
-                "template": "formal"
-            })
-            .to_string(),
-        ))
+        .body(Body::from(json!({ "template": "formal" }).to_string()))
         .unwrap();
 
     let response = ctx.app.clone().oneshot(request).await.unwrap();
@@ -159,20 +146,7 @@ async fn test_download_will_document_unauthorized() {
         .uri(format!("/api/plans/{}/will/generate", plan_id))
         .header("Authorization", format!("Bearer {}", owner_token))
         .header("Content-Type", "application/json")
-        .body(Body::from(
-            json!({
-                "owner_name": "Alice Testator",
-                "owner_wallet": "GABC1234567890ABCDEF",
-                "vault_id": "vault-001",
-                "beneficiaries": [{
-                    "name": "Bob Beneficiary",
-                    "wallet_address": "GBOB1234567890ABCDEF",
-                    "allocation_percent": "100.0000"
-                }],
-                "template": "formal"
-            })
-            .to_string(),
-        ))
+        .body(Body::from(json!({ "template": "formal" }).to_string()))
         .unwrap();
 
     let response = ctx.app.clone().oneshot(request).await.unwrap();
@@ -266,20 +240,7 @@ async fn test_download_will_document_by_version_success() {
         .uri(format!("/api/plans/{}/will/generate", plan_id))
         .header("Authorization", format!("Bearer {}", token))
         .header("Content-Type", "application/json")
-        .body(Body::from(
-            json!({
-                "owner_name": "Alice Testator",
-                "owner_wallet": "GABC1234567890ABCDEF",
-                "vault_id": "vault-001",
-                "beneficiaries": [{
-                    "name": "Bob Beneficiary",
-                    "wallet_address": "GBOB1234567890ABCDEF",
-                    "allocation_percent": "100.0000"
-                }],
-                "template": "formal"
-            })
-            .to_string(),
-        ))
+        .body(Body::from(json!({ "template": "formal" }).to_string()))
         .unwrap();
 
     let response = ctx.app.clone().oneshot(request).await.unwrap();
@@ -381,20 +342,7 @@ async fn test_download_emits_event() {
         .uri(format!("/api/plans/{}/will/generate", plan_id))
         .header("Authorization", format!("Bearer {}", token))
         .header("Content-Type", "application/json")
-        .body(Body::from(
-            json!({
-                "owner_name": "Alice Testator",
-                "owner_wallet": "GABC1234567890ABCDEF",
-                "vault_id": "vault-001",
-                "beneficiaries": [{
-                    "name": "Bob Beneficiary",
-                    "wallet_address": "GBOB1234567890ABCDEF",
-                    "allocation_percent": "100.0000"
-                }],
-                "template": "formal"
-            })
-            .to_string(),
-        ))
+        .body(Body::from(json!({ "template": "formal" }).to_string()))
         .unwrap();
 
     let response = ctx.app.clone().oneshot(request).await.unwrap();
@@ -0,0 +1,207 @@
+mod helpers;
+
+use async_trait::async_trait;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use base64::Engine as _;
+use helpers::TestContext;
+use inheritx_backend::api_error::ApiError;
+use inheritx_backend::contract_reader::{
+    InheritanceContractReader, OnChainBeneficiary, OnChainInheritanceTrigger, OnChainLegacyMessage,
+    OnChainPlan,
+};
+use inheritx_backend::Config;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Contract holding a fixed set of plans and no will hashes
+struct Plans(HashMap<u64, OnChainPlan>);
+
+#[async_trait]
+impl InheritanceContractReader for Plans {
+    async fn get_plan(&self, plan_id: u64) -> Result<Option<OnChainPlan>, ApiError> {
+        Ok(self.0.get(&plan_id).cloned())
+    }
+    async fn get_will_hash(&self, _plan_id: u64) -> Result<Option<String>, ApiError> {
+        Ok(None)
+    }
+    async fn get_will_version_hash(
+        &self,
+        _plan_id: u64,
+        _version: u32,
+    ) -> Result<Option<String>, ApiError> {
+        Ok(None)
+    }
+    async fn is_will_finalized(&self, _plan_id: u64, _version: u32) -> Result<bool, ApiError> {
+        Ok(false)
+    }
+    async fn is_will_revoked(&self, _plan_id: u64, _version: u32) -> Result<bool, ApiError> {
+        Ok(false)
+    }
+    async fn get_inheritance_trigger(
+        &self,
+        _plan_id: u64,
+    ) -> Result<Option<OnChainInheritanceTrigger>, ApiError> {
+        Ok(None)
+    }
+    async fn emergency_transfer_limit_bp(&self) -> Result<u32, ApiError> {
+        Ok(1000)
+    }
+    async fn get_legacy_message(
+        &self,
+        _message_id: u64,
+    ) -> Result<Option<OnChainLegacyMessage>, ApiError> {
+        Ok(None)
+    }
+}
+
+fn wallet() -> String {
+    format!("G{}", Uuid::new_v4().simple()).to_uppercase()
+}
+
+fn on_chain(plan_id: u64, owner: &str, bps: &[u32]) -> OnChainPlan {
+    OnChainPlan {
+        plan_id,
+        plan_name: "Family plan".to_string(),
+        owner: owner.to_string(),
+        total_amount: 1000,
+        total_allocation_bp: bps.iter().sum(),
+        beneficiaries: bps
+            .iter()
+            .map(|&allocation_bp| OnChainBeneficiary { allocation_bp })
+            .collect(),
+        is_active: true,
+    }
+}
+
+/// An owner with a legal name and wallet, holding a plan created on-chain as
+/// `contract_plan_id` with a 60/40 split. Returns the user, plan and token.
+async fn create_owner_plan(
+    pool: &PgPool,
+    wallet_address: &str,
+    contract_plan_id: u64,
+) -> (Uuid, Uuid, String) {
+    let email = format!("will-owner-{}@example.com", Uuid::new_v4());
+    let user_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO users (email, password_hash, wallet_address, legal_name) \
+         VALUES ($1, 'x', $2, 'Ada Lovelace') RETURNING id",
+    )
+    .bind(&email)
+    .bind(wallet_address)
+    .fetch_one(pool)
+    .await
+    .expect("failed to create user");
+
+    let plan_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO plans (user_id, title, fee, net_amount, status, contract_plan_id) \
+         VALUES ($1, 'Family plan', 2, 98, 'pending', $2) RETURNING id",
+    )
+    .bind(user_id)
+    .bind(contract_plan_id as i64)
+    .fetch_one(pool)
+    .await
+    .expect("failed to create plan");
+    for (name, percent) in [("Grace", 60), ("Alan", 40)] {
+        sqlx::query(
+            "INSERT INTO plan_beneficiaries (plan_id, wallet_address, allocation_percent, name) \
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(plan_id)
+        .bind(wallet())
+        .bind(rust_decimal::Decimal::from(percent))
+        .bind(name)
+        .execute(pool)
+        .await
+        .expect("failed to add beneficiary");
+    }
+
+    let claims = inheritx_backend::auth::UserClaims {
+        user_id,
+        email,
+        exp: (chrono::Utc::now() + chrono::Duration::hours(24)).timestamp() as usize,
+    };
+    let token = jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &claims,
+        &jsonwebtoken::EncodingKey::from_secret("test-jwt-secret".as_bytes()),
+    )
+    .unwrap();
+
+    (user_id, plan_id, token)
+}
+
+fn rand_plan_id() -> u64 {
+    Uuid::new_v4().as_u64_pair().0 >> 1
+}
+
+async fn generate(ctx: &TestContext, plan_id: Uuid, token: &str) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/api/plans/{plan_id}/will/generate"))
+        .header("Authorization", format!("Bearer {token}"))
+        .header("Content-Type", "application/json")
+        // The auth extractors read the JWT secret from request extensions
+        .extension(Config {
+            database_url: String::new(),
+            port: 0,
+            jwt_secret: "test-jwt-secret".to_string(),
+        })
+        .body(Body::from(json!({}).to_string()))
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn test_will_is_generated_from_the_on_chain_plan() {
+    let owner = wallet();
+    let (matching, mismatched, foreign) = (rand_plan_id(), rand_plan_id(), rand_plan_id());
+    let reader = Plans(HashMap::from([
+        (matching, on_chain(matching, &owner, &[6000, 4000])),
+        (mismatched, on_chain(mismatched, &owner, &[5000, 5000])),
+        (foreign, on_chain(foreign, &wallet(), &[6000, 4000])),
+    ]));
+    let Some(ctx) = TestContext::with_contract_reader(Arc::new(reader)).await else {
+        return;
+    };
+
+    let (user_id, plan_id, token) = create_owner_plan(&ctx.pool, &owner, matching).await;
+    let (status, body) = generate(&ctx, plan_id, &token).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let data = &body["data"];
+    assert_eq!(data["plan_id"], json!(plan_id));
+    assert_eq!(data["version"], 1);
+    let pdf = base64::engine::general_purpose::STANDARD
+        .decode(data["pdf_base64"].as_str().unwrap())
+        .unwrap();
+    assert!(pdf.starts_with(b"%PDF-"));
+
+    let (stored_user, will_hash): (Uuid, String) =
+        sqlx::query_as("SELECT user_id, will_hash FROM will_documents WHERE id = $1")
+            .bind(Uuid::parse_str(data["document_id"].as_str().unwrap()).unwrap())
+            .fetch_one(&ctx.pool)
+            .await
+            .unwrap();
+    assert_eq!(stored_user, user_id);
+    assert_eq!(data["will_hash"], json!(will_hash));
+
+    // Allocations that disagree with the contract are refused
+    let (_, plan_id, token) = create_owner_plan(&ctx.pool, &owner, mismatched).await;
+    let (status, _) = generate(&ctx, plan_id, &token).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // So is an on-chain plan owned by another wallet
+    let (_, plan_id, token) = create_owner_plan(&ctx.pool, &owner, foreign).await;
+    let (status, _) = generate(&ctx, plan_id, &token).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
@@ -1,6 +1,6 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Env,
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Env, Symbol,
 };
 
 mod test;
@@ -18,6 +18,27 @@ pub struct Loan {
     pub is_active: bool,
 }
 
+/// SEP-40 asset identifier, matching the price oracle contract
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OracleAsset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+#[soroban_sdk::contractclient(name = "PriceOracleClient")]
+pub trait PriceOracleInterface {
+    fn decimals(env: Env) -> u32;
+    fn price(env: Env, asset: OracleAsset) -> PriceData;
+}
+
 // ─────────────────────────────────────────────────
 // Events
 // ─────────────────────────────────────────────────
@@ -82,6 +103,8 @@ pub enum DataKey {
     VaultPause(Address),
     LoanCounter,
     Loan(u64),
+    PriceOracle,
+    DebtAsset,
 }
 
 #[contracterror]
@@ -96,6 +119,7 @@ pub enum BorrowingError {
     LoanNotActive = 7,
     InvalidAmount = 8,
     Paused = 9,
+    PriceUnavailable = 10,
 }
 
 #[contract]
@@ -156,8 +180,9 @@ impl BorrowingContract {
             .checked_mul(ratio as u128)
             .and_then(|v| v.checked_div(10000))
             .unwrap_or(0) as i128;
+        let collateral_value = Self::collateral_value(&env, &collateral_token, collateral_amount)?;
 
-        if collateral_amount < required_collateral {
+        if collateral_value < required_collateral {
             return Err(BorrowingError::InsufficientCollateral);
         }
 
@@ -352,15 +377,11 @@ impl BorrowingContract {
             return Err(BorrowingError::InvalidAmount);
         }
 
-        // Calculate health factor
-        let health_factor = if debt == 0 {
-            10000
-        } else {
-            (loan.collateral_amount as u128)
-                .checked_mul(10000)
-                .and_then(|v| v.checked_div(debt as u128))
-                .unwrap_or(0) as u32
-        };
+        let (collateral_price, debt_price) = Self::prices(&env, &loan.collateral_token)?;
+        let health_factor = Self::health_factor(
+            Self::convert(loan.collateral_amount, collateral_price, debt_price),
+            debt,
+        );
 
         let liquidation_threshold = Self::get_liquidation_threshold(&env);
 
@@ -375,7 +396,11 @@ impl BorrowingContract {
             .checked_mul(liquidation_bonus as u128)
             .and_then(|v| v.checked_div(10000))
             .unwrap_or(0) as i128;
-        let liquidator_reward = liquidate_amount + bonus_amount;
+        let liquidator_reward = Self::convert(
+            liquidate_amount + bonus_amount,
+            debt_price,
+            collateral_price,
+        );
 
         if liquidator_reward > loan.collateral_amount {
             return Err(BorrowingError::InvalidAmount);
@@ -426,16 +451,87 @@ impl BorrowingContract {
             .ok_or(BorrowingError::LoanNotFound)?;
 
         let debt = loan.principal - loan.amount_repaid;
-        let health_factor = if debt == 0 {
+        let (collateral_price, debt_price) = Self::prices(&env, &loan.collateral_token)?;
+        Ok(Self::health_factor(
+            Self::convert(loan.collateral_amount, collateral_price, debt_price),
+            debt,
+        ))
+    }
+
+    /// Value collateral in `debt_asset` using the price oracle. Without an
+    /// oracle, collateral is valued 1:1 with the debt.
+    pub fn set_price_oracle(
+        env: Env,
+        admin: Address,
+        oracle: Address,
+        debt_asset: Address,
+    ) -> Result<(), BorrowingError> {
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            return Err(BorrowingError::Unauthorized);
+        }
+        admin.require_auth();
+        env.storage().instance().set(&DataKey::PriceOracle, &oracle);
+        env.storage()
+            .instance()
+            .set(&DataKey::DebtAsset, &debt_asset);
+        Ok(())
+    }
+
+    pub fn get_price_oracle(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::PriceOracle)
+    }
+
+    /// `(collateral price, debt price)`; both come from the same oracle so
+    /// their scale cancels out. `(1, 1)` when no oracle is set.
+    fn prices(env: &Env, collateral_token: &Address) -> Result<(i128, i128), BorrowingError> {
+        let Some(oracle) = env
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::PriceOracle)
+        else {
+            return Ok((1, 1));
+        };
+        let debt_asset: Address = env.storage().instance().get(&DataKey::DebtAsset).unwrap();
+        let client = PriceOracleClient::new(env, &oracle);
+        let price = |token: &Address| match client.try_price(&OracleAsset::Stellar(token.clone())) {
+            Ok(Ok(data)) if data.price > 0 => Ok(data.price),
+            _ => Err(BorrowingError::PriceUnavailable),
+        };
+        Ok((price(collateral_token)?, price(&debt_asset)?))
+    }
+
+    fn collateral_value(
+        env: &Env,
+        collateral_token: &Address,
+        collateral_amount: i128,
+    ) -> Result<i128, BorrowingError> {
+        let (collateral_price, debt_price) = Self::prices(env, collateral_token)?;
+        Ok(Self::convert(
+            collateral_amount,
+            collateral_price,
+            debt_price,
+        ))
+    }
+
+    /// Convert `amount` of the asset priced at `from_price` into the asset
+    /// priced at `to_price`
+    fn convert(amount: i128, from_price: i128, to_price: i128) -> i128 {
+        amount
+            .checked_mul(from_price)
+            .and_then(|v| v.checked_div(to_price))
+            .unwrap_or(0)
+    }
+
+    fn health_factor(collateral_value: i128, debt: i128) -> u32 {
+        if debt == 0 {
             10000
         } else {
-            (loan.collateral_amount as u128)
+            (collateral_value as u128)
                 .checked_mul(10000)
                 .and_then(|v| v.checked_div(debt as u128))
                 .unwrap_or(0) as u32
-        };
-
-        Ok(health_factor)
+        }
     }
 
     fn get_liquidation_threshold(env: &Env) -> u32 {
@@ -175,3 +175,67 @@ fn test_vault_pause() {
     let new_loan_id = client.create_loan(&borrower, &1000, &5, &1000000, &collateral_addr, &1500);
     assert_eq!(new_loan_id, 1);
 }
+
+#[test]
+fn test_oracle_priced_collateral_and_liquidation() {
+    use soroban_sdk::testutils::Ledger;
+
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000_000);
+    let (client, collateral_addr, admin) = setup(&env);
+    let debt_addr = create_token_addr(&env);
+
+    let oracle_id = env.register_contract(None, price_oracle::PriceOracle);
+    let oracle = price_oracle::PriceOracleClient::new(&env, &oracle_id);
+    oracle.initialize(
+        &admin,
+        &price_oracle::OracleConfig {
+            decimals: 7,
+            heartbeat_secs: 3600,
+            max_deviation_bps: 1000,
+        },
+    );
+    client.set_price_oracle(&admin, &oracle_id, &debt_addr);
+    assert_eq!(client.get_price_oracle(), Some(oracle_id.clone()));
+
+    let borrower = Address::generate(&env);
+    let liquidator = Address::generate(&env);
+    sac_client(&env, &collateral_addr).mint(&borrower, &1500);
+
+    // No prices pushed yet
+    let result = client.try_create_loan(&borrower, &1000, &5, &1000000, &collateral_addr, &750);
+    assert_eq!(result, Err(Ok(BorrowingError::PriceUnavailable)));
+
+    // Collateral worth twice the debt asset: 750 covers 150% of 1000
+    let price_of = |token: &Address| price_oracle::Asset::Stellar(token.clone());
+    oracle.force_price(&admin, &price_of(&debt_addr), &10_000_000, &1_000_000);
+    oracle.force_price(&admin, &price_of(&collateral_addr), &20_000_000, &1_000_000);
+    let result = client.try_create_loan(&borrower, &1000, &5, &1000000, &collateral_addr, &749);
+    assert_eq!(result, Err(Ok(BorrowingError::InsufficientCollateral)));
+    let loan_id = client.create_loan(&borrower, &1000, &5, &1000000, &collateral_addr, &750);
+    assert_eq!(client.get_health_factor(&loan_id), 15000);
+    let result = client.try_liquidate(&liquidator, &loan_id, &400);
+    assert_eq!(result, Err(Ok(BorrowingError::LoanHealthy)));
+
+    // Collateral drops to 1.5x: health factor 112.5% is below the 120% threshold
+    env.ledger().set_timestamp(1_000_060);
+    oracle.force_price(&admin, &price_of(&collateral_addr), &15_000_000, &1_000_060);
+    assert_eq!(client.get_health_factor(&loan_id), 11250);
+
+    // 400 of debt plus the 5% bonus is 420 of value, paid as 280 collateral
+    client.liquidate(&liquidator, &loan_id, &400);
+    let loan = client.get_loan(&loan_id);
+    assert_eq!(loan.collateral_amount, 470);
+    assert_eq!(
+        token::Client::new(&env, &collateral_addr).balance(&liquidator),
+        280
+    );
+
+    // A stale price stops health checks rather than using old data
+    env.ledger().set_timestamp(1_000_060 + 3_601);
+    assert_eq!(
+        client.try_get_health_factor(&loan_id),
+        Err(Ok(BorrowingError::PriceUnavailable))
+    );
+}
@@ -13,6 +13,10 @@ const EMERGENCY_TRANSFER_LIMIT_BP: u32 = 1000;
 /// Emergency cooldown period in seconds (24 hours)
 const EMERGENCY_COOLDOWN_PERIOD: u64 = 86400;
 
+/// Most finalized will versions a vault can hold before revoking them, which
+/// bounds the work `revoke_will` does in one call
+const MAX_UNREVOKED_WILL_VERSIONS: u32 = 10;
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DistributionMethod {
@@ -108,7 +112,9 @@ pub enum InheritanceError {
     WillHashAlreadyStored = 44,
     WillAlreadyLinked = 45,
     VaultNotFound = 46,
-    VerificationFailed = 47,
+    /// Too many finalized will versions are unrevoked. Takes the code of the
+    /// never-raised `VerificationFailed`, as errors are capped at 50 variants.
+    WillVersionLimitReached = 47,
     WillVersionNotFound = 48,
     WillAlreadyFinalized = 49,
     WillNotVerified = 50,
@@ -147,6 +153,8 @@ pub enum DataKey {
     VaultMessages(u64),               // vault_id -> Vec<u64> (message IDs)
     WillFinalized(u64, u32),          // (plan_id, version) -> bool
     WillFinalizedAt(u64, u32),        // (plan_id, version) -> u64 timestamp
+    WillRevokedAt(u64, u32),          // (plan_id, version) -> u64 timestamp
+    UnrevokedWills(u64),              // plan_id -> Vec<u32> (finalized, not revoked)
     WillWitnesses(u64),               // plan_id -> Vec<Address>
     WitnessSignature(u64, Address),   // (plan_id, witness) -> u64 (signed_at)
 }
@@ -496,6 +504,14 @@ pub struct WillFinalizedEvent {
     pub finalized_at: u64,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WillRevokedEvent {
+    pub vault_id: u64,
+    pub versions: Vec<u32>,
+    pub revoked_at: u64,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct WitnessAddedEvent {
@@ -1993,6 +2009,12 @@ impl InheritanceContract {
         }
     }
 
+    /// Share of a plan, in basis points, that may leave it in a single
+    /// withdrawal or claim while emergency access is active.
+    pub fn get_emergency_transfer_limit_bp(_env: Env) -> u32 {
+        EMERGENCY_TRANSFER_LIMIT_BP
+    }
+
     /// Check if emergency access is active and within the cooldown period for a plan.
     ///
     /// # Arguments
@@ -3345,6 +3367,8 @@ impl InheritanceContract {
     /// - The owner must have signed the will (WillSignature must exist).
     /// - If witnesses are assigned, all must have signed.
     /// - Cannot finalize an already-finalized version.
+    /// - At most `MAX_UNREVOKED_WILL_VERSIONS` versions may be finalized and
+    ///   not yet revoked.
     pub fn finalize_will(
         env: Env,
         owner: Address,
@@ -3407,6 +3431,19 @@ impl InheritanceContract {
             }
         }
 
+        let unrevoked_key = DataKey::UnrevokedWills(vault_id);
+        let mut unrevoked: Vec<u32> = env
+            .storage()
+            .persistent()
+            .get(&unrevoked_key)
+            .unwrap_or_else(|| Vec::new(&env));
+        // The owner must revoke before finalizing more
+        if unrevoked.len() >= MAX_UNREVOKED_WILL_VERSIONS {
+            return Err(InheritanceError::WillVersionLimitReached);
+        }
+        unrevoked.push_back(version);
+        env.storage().persistent().set(&unrevoked_key, &unrevoked);
+
         let finalized_at = env.ledger().timestamp();
         env.storage().persistent().set(&fin_key, &true);
         env.storage()
@@ -3440,6 +3477,91 @@ impl InheritanceContract {
             .get(&DataKey::WillFinalizedAt(vault_id, version))
     }
 
+    /// Revoke every finalized will version of a vault.
+    ///
+    /// Revoked versions stay finalized for the record but no longer count as
+    /// the vault's will: the active version and will link are cleared so the
+    /// owner can create a new version afterwards.
+    ///
+    /// Requirements:
+    /// - Caller must be the plan owner.
+    /// - At least one finalized, unrevoked version must exist.
+    pub fn revoke_will(env: Env, owner: Address, vault_id: u64) -> Result<u32, InheritanceError> {
+        owner.require_auth();
+
+        let plan = Self::get_plan(&env, vault_id).ok_or(InheritanceError::PlanNotFound)?;
+        if plan.owner != owner {
+            return Err(InheritanceError::Unauthorized);
+        }
+
+        // Only finalized versions awaiting revocation are visited, at most
+        // MAX_UNREVOKED_WILL_VERSIONS of them
+        let unrevoked_key = DataKey::UnrevokedWills(vault_id);
+        let versions: Vec<u32> = env
+            .storage()
+            .persistent()
+            .get(&unrevoked_key)
+            .unwrap_or_else(|| Vec::new(&env));
+        if versions.is_empty() {
+            return Err(InheritanceError::WillVersionNotFound);
+        }
+        let revoked_at = env.ledger().timestamp();
+        for version in versions.iter() {
+            env.storage()
+                .persistent()
+                .set(&DataKey::WillRevokedAt(vault_id, version), &revoked_at);
+        }
+        env.storage().persistent().remove(&unrevoked_key);
+
+        if let Some(active) = env
+            .storage()
+            .persistent()
+            .get::<_, u32>(&DataKey::ActiveWillVersion(vault_id))
+        {
+            let ver_key = DataKey::WillVersion(vault_id, active);
+            if let Some(mut info) = env
+                .storage()
+                .persistent()
+                .get::<_, WillVersionInfo>(&ver_key)
+            {
+                info.is_active = false;
+                env.storage().persistent().set(&ver_key, &info);
+            }
+        }
+        env.storage()
+            .persistent()
+            .remove(&DataKey::ActiveWillVersion(vault_id));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::VaultWill(vault_id));
+
+        let revoked = versions.len();
+        env.events().publish(
+            (symbol_short!("WILL"), symbol_short!("REVOKE")),
+            WillRevokedEvent {
+                vault_id,
+                versions,
+                revoked_at,
+            },
+        );
+
+        Ok(revoked)
+    }
+
+    /// Check whether a specific will version has been revoked.
+    pub fn is_will_revoked(env: Env, vault_id: u64, version: u32) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::WillRevokedAt(vault_id, version))
+    }
+
+    /// Get the revocation timestamp for a will version (None if not revoked).
+    pub fn get_will_revoked_at(env: Env, vault_id: u64, version: u32) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::WillRevokedAt(vault_id, version))
+    }
+
     // ── Legal Witness Verification (Issue #320) ──
 
     /// Assign a witness address to a vault's will. Only the plan owner can add witnesses.
@@ -2740,6 +2740,13 @@ fn test_is_emergency_active_cooldown() {
     assert!(!client.is_emergency_active(&plan_id));
 }
 
+#[test]
+fn test_emergency_transfer_limit_is_published() {
+    let env = Env::default();
+    let (client, _token, _admin, _owner) = setup_with_token_and_admin(&env);
+    assert_eq!(client.get_emergency_transfer_limit_bp(), 1000);
+}
+
 #[test]
 fn test_withdraw_emergency_limit() {
     let env = Env::default();
@@ -4091,6 +4098,86 @@ fn test_finalized_will_blocks_new_version() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_revoke_will_allows_new_version() {
+    let env = Env::default();
+    let (client, token_id, _admin, owner) = setup_with_token_and_admin(&env);
+    let plan_id = create_plan_and_get_id(&env, &client, &token_id, &owner);
+    let will_hash = test_will_hash(&env);
+
+    let version = client.create_will_version(&owner, &plan_id, &will_hash);
+    client.sign_will(&owner, &plan_id, &will_hash);
+    client.finalize_will(&owner, &plan_id, &version);
+
+    assert_eq!(client.revoke_will(&owner, &plan_id), 1);
+    assert!(client.is_will_revoked(&plan_id, &version));
+    assert!(client.is_will_finalized(&plan_id, &version));
+    assert!(client.get_active_will_version(&plan_id).is_none());
+    assert!(client.get_vault_will(&plan_id).is_none());
+
+    let next = client.create_will_version(&owner, &plan_id, &test_will_hash_2(&env));
+    assert_eq!(next, version + 1);
+    assert!(!client.is_will_revoked(&plan_id, &next));
+}
+
+#[test]
+fn test_revoke_will_requires_finalized_version() {
+    let env = Env::default();
+    let (client, token_id, _admin, owner) = setup_with_token_and_admin(&env);
+    let plan_id = create_plan_and_get_id(&env, &client, &token_id, &owner);
+    let will_hash = test_will_hash(&env);
+
+    client.create_will_version(&owner, &plan_id, &will_hash);
+    let result = client.try_revoke_will(&owner, &plan_id);
+    assert_eq!(result, Err(Ok(InheritanceError::WillVersionNotFound)));
+}
+
+#[test]
+fn test_finalized_versions_are_capped_until_revoked() {
+    let env = Env::default();
+    let (client, token_id, _admin, owner) = setup_with_token_and_admin(&env);
+    let plan_id = create_plan_and_get_id(&env, &client, &token_id, &owner);
+    let will_hash = test_will_hash(&env);
+    client.sign_will(&owner, &plan_id, &will_hash);
+
+    // Each version is finalized after the next one is created, since a
+    // finalized active version blocks new ones
+    let mut previous = client.create_will_version(&owner, &plan_id, &will_hash);
+    for _ in 0..10 {
+        let next = client.create_will_version(&owner, &plan_id, &test_will_hash_2(&env));
+        client.finalize_will(&owner, &plan_id, &previous);
+        previous = next;
+    }
+    assert_eq!(
+        client.try_finalize_will(&owner, &plan_id, &previous),
+        Err(Ok(InheritanceError::WillVersionLimitReached))
+    );
+
+    assert_eq!(client.revoke_will(&owner, &plan_id), 10);
+    assert!(client.is_will_revoked(&plan_id, &1u32));
+    assert!(!client.is_will_revoked(&plan_id, &previous));
+
+    client.finalize_will(&owner, &plan_id, &previous);
+    assert_eq!(client.revoke_will(&owner, &plan_id), 1);
+    assert!(client.is_will_revoked(&plan_id, &previous));
+}
+
+#[test]
+fn test_revoke_will_unauthorized() {
+    let env = Env::default();
+    let (client, token_id, _admin, owner) = setup_with_token_and_admin(&env);
+    let plan_id = create_plan_and_get_id(&env, &client, &token_id, &owner);
+    let will_hash = test_will_hash(&env);
+
+    let version = client.create_will_version(&owner, &plan_id, &will_hash);
+    client.sign_will(&owner, &plan_id, &will_hash);
+    client.finalize_will(&owner, &plan_id, &version);
+
+    let attacker = Address::generate(&env);
+    assert!(client.try_revoke_will(&attacker, &plan_id).is_err());
+    assert!(!client.is_will_revoked(&plan_id, &version));
+}
+
 // --- Issue #320: Legal Witness Verification ---
 
 #[test]
@@ -0,0 +1,477 @@
+#![no_std]
+//! Protocol insurance fund.
+//!
+//! Holds the reserve token, receives a slice of lending protocol fees from
+//! registered premium sources and pays approved claims to beneficiaries. A
+//! claim is approved either by the admin or once `approvals_required`
+//! reviewers have signed off; approval locks the claimed amount until it is
+//! paid out.
+
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, BytesN, Env,
+    Vec,
+};
+
+mod test;
+
+// ─────────────────────────────────────────────────
+// Data Types
+// ─────────────────────────────────────────────────
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FundConfig {
+    /// Reserve asset, as a token contract address
+    pub token: Address,
+    /// Reviewer sign-offs needed to approve a claim without the admin
+    pub approvals_required: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ClaimStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Paid,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Claim {
+    /// Caller-chosen identifier, e.g. a hash of the off-chain claim id
+    pub claim_id: BytesN<32>,
+    pub beneficiary: Address,
+    pub amount: i128,
+    pub status: ClaimStatus,
+    pub approvals: Vec<Address>,
+    pub filed_at: u64,
+    pub settled_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FundMetrics {
+    /// Accounted balance: premiums + contributions - payouts
+    pub total_reserves: i128,
+    /// Reserved for approved, unpaid claims
+    pub locked_reserves: i128,
+    pub available_reserves: i128,
+    pub total_premiums: i128,
+    pub total_contributions: i128,
+    pub total_payouts: i128,
+    pub pending_claims: u32,
+    pub paid_claims: u32,
+}
+
+// ─────────────────────────────────────────────────
+// Events
+// ─────────────────────────────────────────────────
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FundedEvent {
+    pub from: Address,
+    pub amount: i128,
+    pub premium: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimEvent {
+    pub claim_id: BytesN<32>,
+    pub beneficiary: Address,
+    pub amount: i128,
+    pub status: ClaimStatus,
+    pub actor: Address,
+}
+
+// ─────────────────────────────────────────────────
+// Errors
+// ─────────────────────────────────────────────────
+
+#[contracterror]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FundError {
+    AlreadyInitialized = 1,
+    NotInitialized = 2,
+    Unauthorized = 3,
+    NotReviewer = 4,
+    NotPremiumSource = 5,
+    InvalidAmount = 6,
+    InvalidConfig = 7,
+    ClaimExists = 8,
+    ClaimNotFound = 9,
+    InvalidClaimStatus = 10,
+    AlreadyApproved = 11,
+    InsufficientReserves = 12,
+    PremiumNotReceived = 13,
+}
+
+// ─────────────────────────────────────────────────
+// Storage Keys
+// ─────────────────────────────────────────────────
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Admin,
+    Config,
+    Metrics,
+    Reviewer(Address),
+    PremiumSource(Address),
+    Claim(BytesN<32>),
+}
+
+// ─────────────────────────────────────────────────
+// Contract
+// ─────────────────────────────────────────────────
+
+#[contract]
+pub struct InsuranceFund;
+
+#[contractimpl]
+impl InsuranceFund {
+    // ─── Admin / Init ───────────────────────────────
+
+    pub fn initialize(env: Env, admin: Address, config: FundConfig) -> Result<(), FundError> {
+        admin.require_auth();
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(FundError::AlreadyInitialized);
+        }
+        Self::validate_config(&config)?;
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Config, &config);
+        env.storage()
+            .instance()
+            .set(&DataKey::Metrics, &FundMetrics::default());
+        Ok(())
+    }
+
+    /// Change the reviewer threshold; the reserve token is fixed at init
+    pub fn set_approvals_required(
+        env: Env,
+        admin: Address,
+        approvals_required: u32,
+    ) -> Result<(), FundError> {
+        Self::require_admin(&env, &admin)?;
+        let mut config = Self::config(env.clone())?;
+        config.approvals_required = approvals_required;
+        Self::validate_config(&config)?;
+        env.storage().instance().set(&DataKey::Config, &config);
+        Ok(())
+    }
+
+    pub fn add_reviewer(env: Env, admin: Address, reviewer: Address) -> Result<(), FundError> {
+        Self::require_admin(&env, &admin)?;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Reviewer(reviewer), &true);
+        Ok(())
+    }
+
+    pub fn remove_reviewer(env: Env, admin: Address, reviewer: Address) -> Result<(), FundError> {
+        Self::require_admin(&env, &admin)?;
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Reviewer(reviewer));
+        Ok(())
+    }
+
+    pub fn is_reviewer(env: Env, reviewer: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Reviewer(reviewer))
+            .unwrap_or(false)
+    }
+
+    /// Allow a contract (e.g. a lending pool) to report premiums
+    pub fn add_premium_source(env: Env, admin: Address, source: Address) -> Result<(), FundError> {
+        Self::require_admin(&env, &admin)?;
+        env.storage()
+            .persistent()
+            .set(&DataKey::PremiumSource(source), &true);
+        Ok(())
+    }
+
+    pub fn remove_premium_source(
+        env: Env,
+        admin: Address,
+        source: Address,
+    ) -> Result<(), FundError> {
+        Self::require_admin(&env, &admin)?;
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PremiumSource(source));
+        Ok(())
+    }
+
+    pub fn config(env: Env) -> Result<FundConfig, FundError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(FundError::NotInitialized)
+    }
+
+    // ─── Funding ────────────────────────────────────
+
+    /// Account for a premium the source has already transferred in. The
+    /// fund's token balance must cover everything accounted so far.
+    pub fn record_premium(env: Env, source: Address, amount: i128) -> Result<(), FundError> {
+        source.require_auth();
+        let is_source = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PremiumSource(source.clone()))
+            .unwrap_or(false);
+        if !is_source {
+            return Err(FundError::NotPremiumSource);
+        }
+        if amount <= 0 {
+            return Err(FundError::InvalidAmount);
+        }
+        let config = Self::config(env.clone())?;
+        let mut metrics = Self::get_metrics(env.clone());
+        let balance =
+            token::Client::new(&env, &config.token).balance(&env.current_contract_address());
+        if balance < metrics.total_reserves + amount {
+            return Err(FundError::PremiumNotReceived);
+        }
+
+        metrics.total_premiums += amount;
+        Self::set_metrics(&env, metrics);
+        env.events().publish(
+            (symbol_short!("FUND"), symbol_short!("PREMIUM")),
+            FundedEvent {
+                from: source,
+                amount,
+                premium: true,
+            },
+        );
+        Ok(())
+    }
+
+    /// Top up the reserves directly from `from`
+    pub fn contribute(env: Env, from: Address, amount: i128) -> Result<(), FundError> {
+        from.require_auth();
+        if amount <= 0 {
+            return Err(FundError::InvalidAmount);
+        }
+        let config = Self::config(env.clone())?;
+        token::Client::new(&env, &config.token).transfer(
+            &from,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        let mut metrics = Self::get_metrics(env.clone());
+        metrics.total_contributions += amount;
+        Self::set_metrics(&env, metrics);
+        env.events().publish(
+            (symbol_short!("FUND"), symbol_short!("CONTRIB")),
+            FundedEvent {
+                from,
+                amount,
+                premium: false,
+            },
+        );
+        Ok(())
+    }
+
+    // ─── Claims ─────────────────────────────────────
+
+    /// File a claim (admin or reviewer). The filer's own sign-off counts
+    /// toward approval.
+    pub fn file_claim(
+        env: Env,
+        filer: Address,
+        claim_id: BytesN<32>,
+        beneficiary: Address,
+        amount: i128,
+    ) -> Result<(), FundError> {
+        Self::require_approver(&env, &filer)?;
+        if amount <= 0 {
+            return Err(FundError::InvalidAmount);
+        }
+        if env
+            .storage()
+            .persistent()
+            .has(&DataKey::Claim(claim_id.clone()))
+        {
+            return Err(FundError::ClaimExists);
+        }
+
+        let claim = Claim {
+            claim_id: claim_id.clone(),
+            beneficiary,
+            amount,
+            status: ClaimStatus::Pending,
+            approvals: Vec::new(&env),
+            filed_at: env.ledger().timestamp(),
+            settled_at: 0,
+        };
+        let mut metrics = Self::get_metrics(env.clone());
+        metrics.pending_claims += 1;
+        Self::set_metrics(&env, metrics);
+        Self::record_approval(&env, claim, filer)
+    }
+
+    /// Sign off on a pending claim. The admin approves outright; reviewers
+    /// approve once `approvals_required` of them have signed.
+    pub fn approve_claim(
+        env: Env,
+        approver: Address,
+        claim_id: BytesN<32>,
+    ) -> Result<(), FundError> {
+        Self::require_approver(&env, &approver)?;
+        let claim = Self::pending_claim(&env, &claim_id)?;
+        if claim.approvals.contains(&approver) {
+            return Err(FundError::AlreadyApproved);
+        }
+        Self::record_approval(&env, claim, approver)
+    }
+
+    pub fn reject_claim(
+        env: Env,
+        approver: Address,
+        claim_id: BytesN<32>,
+    ) -> Result<(), FundError> {
+        Self::require_approver(&env, &approver)?;
+        let mut claim = Self::pending_claim(&env, &claim_id)?;
+        claim.status = ClaimStatus::Rejected;
+        claim.settled_at = env.ledger().timestamp();
+
+        let mut metrics = Self::get_metrics(env.clone());
+        metrics.pending_claims -= 1;
+        Self::set_metrics(&env, metrics);
+        Self::store_claim(&env, &claim, approver);
+        Ok(())
+    }
+
+    /// Pay an approved claim to its beneficiary. Anyone may trigger this;
+    /// the approval is what authorizes the payout.
+    pub fn payout_claim(env: Env, claim_id: BytesN<32>) -> Result<(), FundError> {
+        let mut claim = Self::get_claim(env.clone(), claim_id).ok_or(FundError::ClaimNotFound)?;
+        if claim.status != ClaimStatus::Approved {
+            return Err(FundError::InvalidClaimStatus);
+        }
+        let config = Self::config(env.clone())?;
+        let contract_id = env.current_contract_address();
+        token::Client::new(&env, &config.token).transfer(
+            &contract_id,
+            &claim.beneficiary,
+            &claim.amount,
+        );
+
+        let mut metrics = Self::get_metrics(env.clone());
+        metrics.locked_reserves -= claim.amount;
+        metrics.total_payouts += claim.amount;
+        metrics.paid_claims += 1;
+        Self::set_metrics(&env, metrics);
+
+        claim.status = ClaimStatus::Paid;
+        claim.settled_at = env.ledger().timestamp();
+        Self::store_claim(&env, &claim, contract_id);
+        Ok(())
+    }
+
+    // ─── Reads ──────────────────────────────────────
+
+    pub fn get_claim(env: Env, claim_id: BytesN<32>) -> Option<Claim> {
+        env.storage().persistent().get(&DataKey::Claim(claim_id))
+    }
+
+    pub fn get_metrics(env: Env) -> FundMetrics {
+        env.storage()
+            .instance()
+            .get(&DataKey::Metrics)
+            .unwrap_or_default()
+    }
+
+    // ─── Internal ───────────────────────────────────
+
+    fn validate_config(config: &FundConfig) -> Result<(), FundError> {
+        if config.approvals_required == 0 {
+            return Err(FundError::InvalidConfig);
+        }
+        Ok(())
+    }
+
+    fn get_admin(env: &Env) -> Result<Address, FundError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(FundError::NotInitialized)
+    }
+
+    fn require_admin(env: &Env, caller: &Address) -> Result<(), FundError> {
+        if *caller != Self::get_admin(env)? {
+            return Err(FundError::Unauthorized);
+        }
+        caller.require_auth();
+        Ok(())
+    }
+
+    /// The admin or a reviewer
+    fn require_approver(env: &Env, caller: &Address) -> Result<(), FundError> {
+        if *caller != Self::get_admin(env)? && !Self::is_reviewer(env.clone(), caller.clone()) {
+            return Err(FundError::NotReviewer);
+        }
+        caller.require_auth();
+        Ok(())
+    }
+
+    fn pending_claim(env: &Env, claim_id: &BytesN<32>) -> Result<Claim, FundError> {
+        let claim =
+            Self::get_claim(env.clone(), claim_id.clone()).ok_or(FundError::ClaimNotFound)?;
+        if claim.status != ClaimStatus::Pending {
+            return Err(FundError::InvalidClaimStatus);
+        }
+        Ok(claim)
+    }
+
+    /// Add `approver`'s sign-off and, once the threshold is met, approve the
+    /// claim and lock its amount
+    fn record_approval(env: &Env, mut claim: Claim, approver: Address) -> Result<(), FundError> {
+        claim.approvals.push_back(approver.clone());
+        let config = Self::config(env.clone())?;
+        let approved =
+            approver == Self::get_admin(env)? || claim.approvals.len() >= config.approvals_required;
+
+        if approved {
+            let mut metrics = Self::get_metrics(env.clone());
+            if metrics.available_reserves < claim.amount {
+                return Err(FundError::InsufficientReserves);
+            }
+            metrics.locked_reserves += claim.amount;
+            metrics.pending_claims -= 1;
+            Self::set_metrics(env, metrics);
+            claim.status = ClaimStatus::Approved;
+        }
+        Self::store_claim(env, &claim, approver);
+        Ok(())
+    }
+
+    fn set_metrics(env: &Env, mut metrics: FundMetrics) {
+        metrics.total_reserves =
+            metrics.total_premiums + metrics.total_contributions - metrics.total_payouts;
+        metrics.available_reserves = metrics.total_reserves - metrics.locked_reserves;
+        env.storage().instance().set(&DataKey::Metrics, &metrics);
+    }
+
+    fn store_claim(env: &Env, claim: &Claim, actor: Address) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Claim(claim.claim_id.clone()), claim);
+        env.events().publish(
+            (symbol_short!("FUND"), symbol_short!("CLAIM")),
+            ClaimEvent {
+                claim_id: claim.claim_id.clone(),
+                beneficiary: claim.beneficiary.clone(),
+                amount: claim.amount,
+                status: claim.status,
+                actor,
+            },
+        );
+    }
+}
@@ -0,0 +1,197 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{token, Address, BytesN, Env};
+
+struct Setup<'a> {
+    client: InsuranceFundClient<'a>,
+    token: Address,
+    admin: Address,
+    reviewers: [Address; 3],
+}
+
+fn setup(env: &Env) -> Setup<'_> {
+    env.mock_all_auths();
+    let admin = Address::generate(env);
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    let contract_id = env.register_contract(None, InsuranceFund);
+    let client = InsuranceFundClient::new(env, &contract_id);
+    client.initialize(
+        &admin,
+        &FundConfig {
+            token: token.clone(),
+            approvals_required: 2,
+        },
+    );
+    let reviewers = [
+        Address::generate(env),
+        Address::generate(env),
+        Address::generate(env),
+    ];
+    for reviewer in reviewers.iter() {
+        client.add_reviewer(&admin, reviewer);
+    }
+    Setup {
+        client,
+        token,
+        admin,
+        reviewers,
+    }
+}
+
+fn fund(env: &Env, s: &Setup, amount: i128) {
+    let donor = Address::generate(env);
+    token::StellarAssetClient::new(env, &s.token).mint(&donor, &amount);
+    s.client.contribute(&donor, &amount);
+}
+
+fn claim_id(env: &Env, n: u8) -> BytesN<32> {
+    BytesN::from_array(env, &[n; 32])
+}
+
+#[test]
+fn test_premiums_must_be_received_from_listed_source() {
+    let env = Env::default();
+    let s = setup(&env);
+    let pool = Address::generate(&env);
+
+    assert_eq!(
+        s.client.try_record_premium(&pool, &100),
+        Err(Ok(FundError::NotPremiumSource))
+    );
+
+    s.client.add_premium_source(&s.admin, &pool);
+    // Nothing transferred yet
+    assert_eq!(
+        s.client.try_record_premium(&pool, &100),
+        Err(Ok(FundError::PremiumNotReceived))
+    );
+
+    token::StellarAssetClient::new(&env, &s.token).mint(&s.client.address, &100);
+    s.client.record_premium(&pool, &100);
+    let metrics = s.client.get_metrics();
+    assert_eq!(metrics.total_premiums, 100);
+    assert_eq!(metrics.total_reserves, 100);
+    assert_eq!(metrics.available_reserves, 100);
+}
+
+#[test]
+fn test_reviewer_quorum_approves_and_pays_claim() {
+    let env = Env::default();
+    let s = setup(&env);
+    fund(&env, &s, 1_000);
+    let beneficiary = Address::generate(&env);
+    let id = claim_id(&env, 1);
+
+    s.client
+        .file_claim(&s.reviewers[0], &id, &beneficiary, &400);
+    assert_eq!(
+        s.client.try_approve_claim(&s.reviewers[0], &id),
+        Err(Ok(FundError::AlreadyApproved))
+    );
+    // One of two sign-offs: not payable yet
+    assert_eq!(
+        s.client.try_payout_claim(&id),
+        Err(Ok(FundError::InvalidClaimStatus))
+    );
+    assert_eq!(s.client.get_metrics().pending_claims, 1);
+
+    s.client.approve_claim(&s.reviewers[1], &id);
+    let metrics = s.client.get_metrics();
+    assert_eq!(metrics.locked_reserves, 400);
+    assert_eq!(metrics.available_reserves, 600);
+    assert_eq!(metrics.pending_claims, 0);
+
+    s.client.payout_claim(&id);
+    assert_eq!(
+        token::Client::new(&env, &s.token).balance(&beneficiary),
+        400
+    );
+    let claim = s.client.get_claim(&id).unwrap();
+    assert_eq!(claim.status, ClaimStatus::Paid);
+    assert_eq!(claim.approvals.len(), 2);
+
+    let metrics = s.client.get_metrics();
+    assert_eq!(metrics.total_reserves, 600);
+    assert_eq!(metrics.locked_reserves, 0);
+    assert_eq!(metrics.total_payouts, 400);
+    assert_eq!(metrics.paid_claims, 1);
+    assert_eq!(
+        s.client.try_payout_claim(&id),
+        Err(Ok(FundError::InvalidClaimStatus))
+    );
+}
+
+#[test]
+fn test_admin_approval_is_sufficient() {
+    let env = Env::default();
+    let s = setup(&env);
+    fund(&env, &s, 1_000);
+    let id = claim_id(&env, 2);
+
+    s.client
+        .file_claim(&s.admin, &id, &Address::generate(&env), &250);
+    assert_eq!(
+        s.client.get_claim(&id).unwrap().status,
+        ClaimStatus::Approved
+    );
+}
+
+#[test]
+fn test_claims_cannot_exceed_available_reserves() {
+    let env = Env::default();
+    let s = setup(&env);
+    fund(&env, &s, 500);
+    let beneficiary = Address::generate(&env);
+
+    s.client
+        .file_claim(&s.admin, &claim_id(&env, 1), &beneficiary, &400);
+    // Only 100 left unlocked
+    let id = claim_id(&env, 2);
+    s.client
+        .file_claim(&s.reviewers[0], &id, &beneficiary, &200);
+    assert_eq!(
+        s.client.try_approve_claim(&s.reviewers[1], &id),
+        Err(Ok(FundError::InsufficientReserves))
+    );
+
+    s.client.reject_claim(&s.reviewers[2], &id);
+    assert_eq!(
+        s.client.get_claim(&id).unwrap().status,
+        ClaimStatus::Rejected
+    );
+    assert_eq!(s.client.get_metrics().pending_claims, 0);
+}
+
+#[test]
+fn test_only_reviewers_act_on_claims() {
+    let env = Env::default();
+    let s = setup(&env);
+    fund(&env, &s, 1_000);
+    let stranger = Address::generate(&env);
+    let id = claim_id(&env, 1);
+
+    assert_eq!(
+        s.client.try_file_claim(&stranger, &id, &stranger, &100),
+        Err(Ok(FundError::NotReviewer))
+    );
+    s.client.file_claim(&s.reviewers[0], &id, &stranger, &100);
+    assert_eq!(
+        s.client
+            .try_file_claim(&s.reviewers[1], &id, &stranger, &100),
+        Err(Ok(FundError::ClaimExists))
+    );
+
+    s.client.remove_reviewer(&s.admin, &s.reviewers[1]);
+    assert_eq!(
+        s.client.try_approve_claim(&s.reviewers[1], &id),
+        Err(Ok(FundError::NotReviewer))
+    );
+    assert_eq!(
+        s.client.try_set_approvals_required(&s.admin, &0),
+        Err(Ok(FundError::InvalidConfig))
+    );
+}
@@ -1,7 +1,7 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, log, symbol_short, vec, Address, Env,
-    IntoVal, InvokeError, Val, Vec,
+    contract, contracterror, contractimpl, contracttype, log, symbol_short, vec, xdr::ToXdr,
+    Address, Bytes, BytesN, Env, IntoVal, InvokeError, Symbol, Val, Vec,
 };
 
 // ─────────────────────────────────────────────────
@@ -13,6 +13,8 @@ const PROTOCOL_INTEREST_BPS: u32 = 1000; // 10% of interest retained by protocol
 const BAD_DEBT_RESERVE_BPS: u32 = 5000; // 50% of protocol share routed to reserve
 const DEFAULT_GRACE_PERIOD_SECONDS: u64 = 259_200; // 3 days
 const DEFAULT_LATE_FEE_RATE_BPS: u32 = 500; // 5% per day = 0.058% per second (approx)
+const LIQUIDATION_HEALTH_BPS: u32 = 15000; // Loans below 150% collateral value are liquidatable
+const MIN_ATTESTED_COLLATERAL_BPS: u32 = 11000; // Reputation can lower collateral to 110% at most
 
 // ─────────────────────────────────────────────────
 // Data Types
@@ -68,6 +70,87 @@ pub trait LoanNFTInterface {
     fn owner_of(env: Env, loan_id: u64) -> Option<Address>;
 }
 
+/// SEP-40 asset identifier, matching the price oracle contract
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OracleAsset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+#[soroban_sdk::contractclient(name = "PriceOracleClient")]
+pub trait PriceOracleInterface {
+    fn decimals(env: Env) -> u32;
+    fn price(env: Env, asset: OracleAsset) -> PriceData;
+}
+
+/// Where protocol-fee premiums go, and what slice of the protocol share
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InsuranceRouting {
+    pub fund: Address,
+    pub premium_bps: u32,
+}
+
+#[soroban_sdk::contractclient(name = "InsuranceFundClient")]
+pub trait InsuranceFundInterface {
+    fn record_premium(env: Env, source: Address, amount: i128);
+}
+
+/// Dutch-auction liquidation settings. Collateral is offered at
+/// `start_premium_bps` of its current oracle value and the price falls
+/// linearly to `floor_bps` over `duration_seconds`, then stays at the floor.
+/// The floor must be a discount, so collateral always finds a buyer.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionConfig {
+    pub duration_seconds: u64,
+    pub start_premium_bps: u32,
+    pub floor_bps: u32,
+}
+
+/// Borrow terms granted off-chain from a borrower's repayment history,
+/// signed by the reputation signer. `collateral_ratio_bps` replaces the
+/// pool's collateral ratio for the borrower's new loans until `expires_at`.
+/// Each attestation must be issued after the one it replaces, so an older,
+/// better one cannot be replayed once the score drops.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReputationAttestation {
+    pub borrower: Address,
+    /// The lending contract the attestation was issued for
+    pub lending_contract: Address,
+    pub score: u32,
+    pub collateral_ratio_bps: u32,
+    pub expires_at: u64,
+    pub issued_at: u64,
+}
+
+/// A running liquidation auction of a borrower's collateral
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Auction {
+    pub loan_id: u64,
+    pub borrower: Address,
+    pub collateral_token: Address,
+    pub collateral_remaining: u64,
+    /// Principal, interest and late fees at the start, less bids so far
+    pub debt_remaining: u64,
+    pub principal_remaining: u64,
+    /// Oracle prices at the latest start, restart or bid
+    pub collateral_price: i128,
+    pub debt_price: i128,
+    pub started_at: u64,
+    pub config: AuctionConfig,
+}
+
 // ─────────────────────────────────────────────────
 // Events
 // ─────────────────────────────────────────────────
@@ -136,6 +219,37 @@ pub struct LiquidationEvent {
     pub health_factor: u32,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionStartedEvent {
+    pub loan_id: u64,
+    pub borrower: Address,
+    pub keeper: Address,
+    pub collateral_amount: u64,
+    pub debt: u64,
+    pub health_factor: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionBidEvent {
+    pub loan_id: u64,
+    pub borrower: Address,
+    pub liquidator: Address,
+    pub debt_repaid: u64,
+    pub collateral_sold: u64,
+    pub price_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionSettledEvent {
+    pub loan_id: u64,
+    pub borrower: Address,
+    pub collateral_returned: u64,
+    pub bad_debt: u64,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct InterestAccrualEvent {
@@ -180,6 +294,16 @@ pub enum LendingError {
     CollateralNotWhitelisted = 12,
     UtilizationCapExceeded = 13,
     ReentrantCall = 14,
+    PriceUnavailable = 15,
+    AuctionModeActive = 16,
+    AuctionsDisabled = 17,
+    AuctionExists = 18,
+    NoAuction = 19,
+    NotLiquidatable = 20,
+    InvalidAttestation = 21,
+    AttestationExpired = 22,
+    AuctionRunning = 23,
+    StaleAttestation = 24,
 }
 
 // ─────────────────────────────────────────────────
@@ -201,6 +325,12 @@ pub enum DataKey {
     NFTToken,
     ReentrancyGuard,
     LateFeesAccrued(u64), // Track late fees for a specific loan_id
+    PriceOracle,
+    InsuranceFund,
+    AuctionConfig,
+    Auction(Address), // Keyed by borrower, like Loan
+    ReputationSigner,
+    Reputation(Address),
 }
 
 // ─────────────────────────────────────────────────
@@ -258,6 +388,71 @@ impl LendingContract {
         Ok(())
     }
 
+    /// Value collateral against the pool token using the price oracle.
+    /// Without an oracle, collateral is valued 1:1 with the pool token.
+    pub fn set_price_oracle(env: Env, admin: Address, oracle: Address) -> Result<(), LendingError> {
+        Self::require_admin(&env, &admin)?;
+        env.storage().instance().set(&DataKey::PriceOracle, &oracle);
+        Ok(())
+    }
+
+    pub fn get_price_oracle(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::PriceOracle)
+    }
+
+    /// Route `premium_bps` of the protocol's interest share to the insurance
+    /// fund on every repayment. The pool must be a premium source of the fund.
+    pub fn set_insurance_fund(
+        env: Env,
+        admin: Address,
+        fund: Address,
+        premium_bps: u32,
+    ) -> Result<(), LendingError> {
+        Self::require_admin(&env, &admin)?;
+        if premium_bps > 10000 {
+            return Err(LendingError::InvalidAmount);
+        }
+        env.storage().instance().set(
+            &DataKey::InsuranceFund,
+            &InsuranceRouting { fund, premium_bps },
+        );
+        Ok(())
+    }
+
+    pub fn get_insurance_fund(env: Env) -> Option<InsuranceRouting> {
+        env.storage().instance().get(&DataKey::InsuranceFund)
+    }
+
+    /// Liquidate through Dutch auctions instead of fixed-bonus seizure, or
+    /// return to fixed-bonus `liquidate` with `None`
+    pub fn set_auction_config(
+        env: Env,
+        admin: Address,
+        config: Option<AuctionConfig>,
+    ) -> Result<(), LendingError> {
+        Self::require_admin(&env, &admin)?;
+        match config {
+            Some(config) => {
+                if config.duration_seconds == 0
+                    || config.floor_bps == 0
+                    || config.floor_bps >= 10_000
+                    || config.floor_bps > config.start_premium_bps
+                {
+                    return Err(LendingError::InvalidAmount);
+                }
+                env.storage()
+                    .instance()
+                    .set(&DataKey::AuctionConfig, &config);
+            }
+            None => env.storage().instance().remove(&DataKey::AuctionConfig),
+        }
+        Ok(())
+    }
+
+    pub fn get_auction_config(env: Env) -> Option<AuctionConfig> {
+        env.storage().instance().get(&DataKey::AuctionConfig)
+    }
+
     fn enter_reentrancy_guard(env: &Env) -> Result<(), LendingError> {
         if env.storage().instance().has(&DataKey::ReentrancyGuard) {
             return Err(LendingError::ReentrantCall);
@@ -337,6 +532,69 @@ impl LendingContract {
             .unwrap_or(false)
     }
 
+    /// Fresh oracle price of `token`, or `PriceUnavailable` if the oracle
+    /// has none or it is stale
+    fn oracle_price(env: &Env, oracle: &Address, token: &Address) -> Result<i128, LendingError> {
+        let client = PriceOracleClient::new(env, oracle);
+        match client.try_price(&OracleAsset::Stellar(token.clone())) {
+            Ok(Ok(data)) if data.price > 0 => Ok(data.price),
+            _ => Err(LendingError::PriceUnavailable),
+        }
+    }
+
+    /// `(collateral price, pool token price)`; both come from the same
+    /// oracle so their scale cancels out. `(1, 1)` when no oracle is set.
+    fn collateral_prices(
+        env: &Env,
+        collateral_token: &Address,
+    ) -> Result<(i128, i128), LendingError> {
+        match env
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::PriceOracle)
+        {
+            Some(oracle) => Ok((
+                Self::oracle_price(env, &oracle, collateral_token)?,
+                Self::oracle_price(env, &oracle, &Self::get_token(env))?,
+            )),
+            None => Ok((1, 1)),
+        }
+    }
+
+    /// Convert `amount` of the asset priced at `from_price` into the asset
+    /// priced at `to_price`
+    fn convert(amount: u64, from_price: i128, to_price: i128) -> Result<u64, LendingError> {
+        (amount as u128)
+            .checked_mul(from_price as u128)
+            .and_then(|v| v.checked_div(to_price as u128))
+            .and_then(|v| u64::try_from(v).ok())
+            .ok_or(LendingError::InvalidAmount)
+    }
+
+    /// Send the insurance fund its slice of `protocol_share`, if a fund is
+    /// configured. Returns the premium paid.
+    fn pay_insurance_premium(
+        env: &Env,
+        token: &Address,
+        protocol_share: u64,
+    ) -> Result<u64, LendingError> {
+        let Some(routing) = Self::get_insurance_fund(env.clone()) else {
+            return Ok(0);
+        };
+        let premium = ((protocol_share as u128)
+            .checked_mul(routing.premium_bps as u128)
+            .and_then(|v| v.checked_div(10000))
+            .unwrap_or(0)) as u64;
+        if premium == 0 {
+            return Ok(0);
+        }
+        let contract_id = env.current_contract_address();
+        Self::transfer(env, token, &contract_id, &routing.fund, premium)?;
+        InsuranceFundClient::new(env, &routing.fund)
+            .record_premium(&contract_id, &(premium as i128));
+        Ok(premium)
+    }
+
     fn get_admin(env: &Env) -> Option<Address> {
         env.storage().instance().get(&DataKey::Admin)
     }
@@ -581,13 +839,15 @@ impl LendingContract {
             return Err(LendingError::LoanAlreadyExists);
         }
 
-        // Check collateral ratio (collateral_amount must be >= amount * ratio / 10000)
+        // Check collateral ratio (collateral value must be >= amount * ratio / 10000)
         let required_collateral = (amount as u128)
-            .checked_mul(Self::get_collateral_ratio(&env) as u128)
+            .checked_mul(Self::borrower_collateral_ratio(&env, &borrower) as u128)
             .and_then(|v| v.checked_div(10000))
             .unwrap_or(0) as u64;
+        let (collateral_price, debt_price) = Self::collateral_prices(&env, &collateral_token)?;
+        let collateral_value = Self::convert(collateral_amount, collateral_price, debt_price)?;
 
-        if collateral_amount < required_collateral {
+        if collateral_value < required_collateral {
             return Err(LendingError::InsufficientCollateral);
         }
 
@@ -707,6 +967,14 @@ impl LendingContract {
             .persistent()
             .get(&DataKey::Loan(borrower.clone()))
             .ok_or(LendingError::NoOpenLoan)?;
+        // Collateral under auction is settled by bids, not repayment
+        if env
+            .storage()
+            .persistent()
+            .has(&DataKey::Auction(borrower.clone()))
+        {
+            return Err(LendingError::AuctionExists);
+        }
 
         let elapsed = env.ledger().timestamp().saturating_sub(loan.borrow_time);
         let interest = Self::calculate_interest(loan.principal, loan.interest_rate_bps, elapsed);
@@ -734,12 +1002,14 @@ impl LendingContract {
             .checked_mul(PROTOCOL_INTEREST_BPS as u128)
             .and_then(|v| v.checked_div(10000))
             .unwrap_or(0)) as u64;
+        let premium = Self::pay_insurance_premium(&env, &token, protocol_share)?;
+        let pool_share = interest - protocol_share;
+        let protocol_share = protocol_share - premium;
         let reserve_share = ((protocol_share as u128)
             .checked_mul(BAD_DEBT_RESERVE_BPS as u128)
             .and_then(|v| v.checked_div(10000))
             .unwrap_or(0)) as u64;
         let retained_share = protocol_share.saturating_sub(reserve_share);
-        let pool_share = interest - protocol_share;
 
         // Late fees go entirely to retained_yield (protocol reserve)
         pool.total_deposits += pool_share; // Interest increases pool value for share holders
@@ -1067,6 +1337,96 @@ impl LendingContract {
         Self::get_collateral_ratio(&env)
     }
 
+    // ─── Reputation ─────────────────────────────────
+
+    /// Set the ed25519 key that signs reputation attestations, or stop
+    /// honouring attestations with `None`
+    pub fn set_reputation_signer(
+        env: Env,
+        admin: Address,
+        signer: Option<BytesN<32>>,
+    ) -> Result<(), LendingError> {
+        Self::require_admin(&env, &admin)?;
+        match signer {
+            Some(signer) => env
+                .storage()
+                .instance()
+                .set(&DataKey::ReputationSigner, &signer),
+            None => env.storage().instance().remove(&DataKey::ReputationSigner),
+        }
+        Ok(())
+    }
+
+    pub fn get_reputation_signer(env: Env) -> Option<BytesN<32>> {
+        env.storage().instance().get(&DataKey::ReputationSigner)
+    }
+
+    /// Record a borrower's reputation attestation. `signature` is the
+    /// reputation signer's ed25519 signature over the SHA-256 of the
+    /// attestation's XDR; anyone may submit it on the borrower's behalf.
+    pub fn submit_reputation(
+        env: Env,
+        attestation: ReputationAttestation,
+        signature: BytesN<64>,
+    ) -> Result<(), LendingError> {
+        Self::require_initialized(&env)?;
+        let signer =
+            Self::get_reputation_signer(env.clone()).ok_or(LendingError::InvalidAttestation)?;
+        if attestation.lending_contract != env.current_contract_address()
+            || attestation.collateral_ratio_bps < MIN_ATTESTED_COLLATERAL_BPS
+        {
+            return Err(LendingError::InvalidAttestation);
+        }
+        if attestation.expires_at <= env.ledger().timestamp() {
+            return Err(LendingError::AttestationExpired);
+        }
+        if let Some(current) = Self::get_reputation(env.clone(), attestation.borrower.clone()) {
+            if attestation.issued_at <= current.issued_at {
+                return Err(LendingError::StaleAttestation);
+            }
+        }
+
+        // Traps if the signature does not match
+        let digest: Bytes = env
+            .crypto()
+            .sha256(&attestation.clone().to_xdr(&env))
+            .to_bytes()
+            .into();
+        env.crypto().ed25519_verify(&signer, &digest, &signature);
+
+        env.storage().persistent().set(
+            &DataKey::Reputation(attestation.borrower.clone()),
+            &attestation,
+        );
+        env.events()
+            .publish((symbol_short!("REP"), symbol_short!("ATTEST")), attestation);
+        Ok(())
+    }
+
+    pub fn get_reputation(env: Env, borrower: Address) -> Option<ReputationAttestation> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Reputation(borrower))
+    }
+
+    /// Collateral ratio a new loan for `borrower` must meet, in basis points
+    pub fn get_borrower_collateral_ratio(env: Env, borrower: Address) -> u32 {
+        Self::borrower_collateral_ratio(&env, &borrower)
+    }
+
+    /// The attested ratio while the attestation is current and its signer is
+    /// still trusted, otherwise the pool's
+    fn borrower_collateral_ratio(env: &Env, borrower: &Address) -> u32 {
+        let attested = Self::get_reputation(env.clone(), borrower.clone()).filter(|a| {
+            a.expires_at > env.ledger().timestamp()
+                && Self::get_reputation_signer(env.clone()).is_some()
+        });
+        match attested {
+            Some(attestation) => attestation.collateral_ratio_bps,
+            None => Self::get_collateral_ratio(env),
+        }
+    }
+
     /// Set the grace period for loans (admin only)
     /// Grace period is the time after due date during which no late fees accrue
     pub fn set_grace_period(
@@ -1133,6 +1493,10 @@ impl LendingContract {
         Self::enter_reentrancy_guard(&env)?;
         liquidator.require_auth();
 
+        if Self::get_auction_config(env.clone()).is_some() {
+            return Err(LendingError::AuctionModeActive);
+        }
+
         let loan: LoanRecord = env
             .storage()
             .persistent()
@@ -1143,29 +1507,15 @@ impl LendingContract {
             return Err(LendingError::InvalidAmount);
         }
 
-        // Check if grace period has expired before allowing liquidation
-        let is_in_grace = Self::is_in_grace_period(env.clone(), borrower.clone())?;
-        if is_in_grace {
-            return Err(LendingError::InvalidAmount);
-        }
-
-        // Calculate health factor (collateral / debt ratio)
-        let health_factor = (loan.collateral_amount as u128)
-            .checked_mul(10000)
-            .and_then(|v| v.checked_div(loan.principal as u128))
-            .unwrap_or(0) as u32;
-
-        // Allow liquidation if health factor is below 150% (15000 basis points)
-        let liquidation_threshold_bps = 15000u32;
-        if health_factor >= liquidation_threshold_bps {
-            return Err(LendingError::InvalidAmount);
-        }
+        let (health_factor, collateral_price, debt_price) =
+            Self::liquidation_health(&env, &borrower, &loan)?.ok_or(LendingError::InvalidAmount)?;
 
         // Calculate collateral to seize (with small penalty/bonus to liquidator)
-        let collateral_to_seize = (amount as u128)
+        let seize_value = (amount as u128)
             .checked_mul(15000) // 150% of the amount repaid
             .and_then(|v| v.checked_div(10000))
             .unwrap_or(amount as u128) as u64;
+        let collateral_to_seize = Self::convert(seize_value, debt_price, collateral_price)?;
 
         if collateral_to_seize > loan.collateral_amount {
             return Err(LendingError::InvalidAmount);
@@ -1215,6 +1565,312 @@ impl LendingContract {
         Self::exit_reentrancy_guard(&env);
         Ok(collateral_to_seize)
     }
+
+    /// `(health factor bps, collateral price, debt price)` if the loan is
+    /// past its grace period and below the liquidation health factor
+    fn liquidation_health(
+        env: &Env,
+        borrower: &Address,
+        loan: &LoanRecord,
+    ) -> Result<Option<(u32, i128, i128)>, LendingError> {
+        if Self::is_in_grace_period(env.clone(), borrower.clone())? {
+            return Ok(None);
+        }
+        // Health factor is collateral value over principal
+        let (collateral_price, debt_price) = Self::collateral_prices(env, &loan.collateral_token)?;
+        let collateral_value = Self::convert(loan.collateral_amount, collateral_price, debt_price)?;
+        let health_factor = (collateral_value as u128)
+            .checked_mul(10000)
+            .and_then(|v| v.checked_div(loan.principal as u128))
+            .unwrap_or(0) as u32;
+        Ok((health_factor < LIQUIDATION_HEALTH_BPS).then_some((
+            health_factor,
+            collateral_price,
+            debt_price,
+        )))
+    }
+
+    // ─── Liquidation Auctions ───────────────────────
+
+    /// Put a liquidatable loan's collateral up for Dutch auction. Anyone may
+    /// start one; the debt due (principal, interest and late fees) stops
+    /// accruing while it runs. Bids are priced from the oracle at the time
+    /// of the bid, not at the start.
+    pub fn start_auction(
+        env: Env,
+        keeper: Address,
+        borrower: Address,
+    ) -> Result<Auction, LendingError> {
+        Self::require_initialized(&env)?;
+        keeper.require_auth();
+
+        let config = Self::get_auction_config(env.clone()).ok_or(LendingError::AuctionsDisabled)?;
+        if env
+            .storage()
+            .persistent()
+            .has(&DataKey::Auction(borrower.clone()))
+        {
+            return Err(LendingError::AuctionExists);
+        }
+        let loan: LoanRecord = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Loan(borrower.clone()))
+            .ok_or(LendingError::NoOpenLoan)?;
+        let (health_factor, collateral_price, debt_price) =
+            Self::liquidation_health(&env, &borrower, &loan)?
+                .ok_or(LendingError::NotLiquidatable)?;
+
+        let debt = Self::get_total_due_with_late_fees(env.clone(), borrower.clone())?;
+        let auction = Auction {
+            loan_id: loan.loan_id,
+            borrower: borrower.clone(),
+            collateral_token: loan.collateral_token,
+            collateral_remaining: loan.collateral_amount,
+            debt_remaining: debt,
+            principal_remaining: loan.principal,
+            collateral_price,
+            debt_price,
+            started_at: env.ledger().timestamp(),
+            config,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Auction(borrower.clone()), &auction);
+
+        env.events().publish(
+            (symbol_short!("AUCTION"), symbol_short!("START")),
+            AuctionStartedEvent {
+                loan_id: loan.loan_id,
+                borrower,
+                keeper,
+                collateral_amount: loan.collateral_amount,
+                debt,
+                health_factor,
+            },
+        );
+        Ok(auction)
+    }
+
+    pub fn get_auction(env: Env, borrower: Address) -> Option<Auction> {
+        env.storage().persistent().get(&DataKey::Auction(borrower))
+    }
+
+    /// Restart an auction that has run its full duration without selling
+    /// out, with a fresh price curve under the current auction settings.
+    /// Anyone may call it.
+    pub fn restart_auction(
+        env: Env,
+        keeper: Address,
+        borrower: Address,
+    ) -> Result<Auction, LendingError> {
+        Self::require_initialized(&env)?;
+        keeper.require_auth();
+
+        let mut auction =
+            Self::get_auction(env.clone(), borrower.clone()).ok_or(LendingError::NoAuction)?;
+        let now = env.ledger().timestamp();
+        if now
+            < auction
+                .started_at
+                .saturating_add(auction.config.duration_seconds)
+        {
+            return Err(LendingError::AuctionRunning);
+        }
+        let (collateral_price, debt_price) =
+            Self::collateral_prices(&env, &auction.collateral_token)?;
+        auction.collateral_price = collateral_price;
+        auction.debt_price = debt_price;
+        auction.started_at = now;
+        if let Some(config) = Self::get_auction_config(env.clone()) {
+            auction.config = config;
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::Auction(borrower.clone()), &auction);
+
+        let collateral_value =
+            Self::convert(auction.collateral_remaining, collateral_price, debt_price)?;
+        let health_factor = (collateral_value as u128)
+            .checked_mul(10000)
+            .and_then(|v| v.checked_div(auction.debt_remaining as u128))
+            .unwrap_or(0) as u32;
+        env.events().publish(
+            (symbol_short!("AUCTION"), symbol_short!("RESTART")),
+            AuctionStartedEvent {
+                loan_id: auction.loan_id,
+                borrower,
+                keeper,
+                collateral_amount: auction.collateral_remaining,
+                debt: auction.debt_remaining,
+                health_factor,
+            },
+        );
+        Ok(auction)
+    }
+
+    /// Current auction price as bps of the collateral's current oracle value
+    pub fn get_auction_price_bps(env: Env, borrower: Address) -> Result<u32, LendingError> {
+        let auction = Self::get_auction(env.clone(), borrower).ok_or(LendingError::NoAuction)?;
+        Ok(Self::auction_price_bps(&env, &auction))
+    }
+
+    fn auction_price_bps(env: &Env, auction: &Auction) -> u32 {
+        let config = &auction.config;
+        let elapsed = env.ledger().timestamp().saturating_sub(auction.started_at);
+        if elapsed >= config.duration_seconds {
+            return config.floor_bps;
+        }
+        let decay = ((config.start_premium_bps - config.floor_bps) as u128)
+            .checked_mul(elapsed as u128)
+            .and_then(|v| v.checked_div(config.duration_seconds as u128))
+            .unwrap_or(0) as u32;
+        config.start_premium_bps - decay
+    }
+
+    /// Buy auctioned collateral at the current price, repaying up to
+    /// `max_debt`. The collateral is valued at the oracle's current price, so
+    /// a price move after the start neither strands the auction nor hands
+    /// bidders a windfall. Partial fills are allowed; the auction settles once
+    /// the debt is covered or the collateral is sold out, returning leftover
+    /// collateral to the borrower. Returns the collateral bought.
+    pub fn bid(
+        env: Env,
+        liquidator: Address,
+        borrower: Address,
+        max_debt: u64,
+    ) -> Result<u64, LendingError> {
+        Self::require_initialized(&env)?;
+        Self::enter_reentrancy_guard(&env)?;
+        liquidator.require_auth();
+
+        let mut auction =
+            Self::get_auction(env.clone(), borrower.clone()).ok_or(LendingError::NoAuction)?;
+        if max_debt == 0 {
+            return Err(LendingError::InvalidAmount);
+        }
+        let (collateral_price, debt_price) =
+            Self::collateral_prices(&env, &auction.collateral_token)?;
+        auction.collateral_price = collateral_price;
+        auction.debt_price = debt_price;
+        let price_bps = Self::auction_price_bps(&env, &auction);
+
+        // Collateral per unit of debt at the auction price
+        let collateral_for = |debt: u64| -> Result<u64, LendingError> {
+            (debt as u128)
+                .checked_mul(auction.debt_price as u128)
+                .and_then(|v| v.checked_mul(10000))
+                .and_then(|v| v.checked_div(auction.collateral_price as u128))
+                .and_then(|v| v.checked_div(price_bps as u128))
+                .and_then(|v| u64::try_from(v).ok())
+                .ok_or(LendingError::InvalidAmount)
+        };
+        let mut debt_repaid = max_debt.min(auction.debt_remaining);
+        let mut collateral_sold = collateral_for(debt_repaid)?;
+        if collateral_sold > auction.collateral_remaining {
+            // Not enough collateral left: sell all of it for what it fetches
+            collateral_sold = auction.collateral_remaining;
+            debt_repaid = ((collateral_sold as u128)
+                .checked_mul(auction.collateral_price as u128)
+                .and_then(|v| v.checked_mul(price_bps as u128))
+                .and_then(|v| v.checked_div(auction.debt_price as u128))
+                .and_then(|v| v.checked_div(10000))
+                .unwrap_or(0) as u64)
+                .min(auction.debt_remaining);
+        }
+        if debt_repaid == 0 || collateral_sold == 0 {
+            return Err(LendingError::InvalidAmount);
+        }
+
+        let token = Self::get_token(&env);
+        let contract_id = env.current_contract_address();
+        Self::transfer(&env, &token, &liquidator, &contract_id, debt_repaid)?;
+        Self::transfer(
+            &env,
+            &auction.collateral_token,
+            &contract_id,
+            &liquidator,
+            collateral_sold,
+        )?;
+
+        // Bids repay principal first; the rest is interest and fees for the pool
+        let principal_repaid = debt_repaid.min(auction.principal_remaining);
+        let mut pool = Self::get_pool(&env);
+        pool.total_borrowed = pool.total_borrowed.saturating_sub(principal_repaid);
+        pool.total_deposits += debt_repaid - principal_repaid;
+        Self::set_pool(&env, &pool);
+
+        auction.debt_remaining -= debt_repaid;
+        auction.principal_remaining -= principal_repaid;
+        auction.collateral_remaining -= collateral_sold;
+
+        env.events().publish(
+            (symbol_short!("AUCTION"), symbol_short!("BID")),
+            AuctionBidEvent {
+                loan_id: auction.loan_id,
+                borrower: borrower.clone(),
+                liquidator,
+                debt_repaid,
+                collateral_sold,
+                price_bps,
+            },
+        );
+
+        if auction.debt_remaining == 0 || auction.collateral_remaining == 0 {
+            Self::settle_auction(&env, &auction)?;
+        } else {
+            env.storage()
+                .persistent()
+                .set(&DataKey::Auction(borrower), &auction);
+        }
+
+        Self::exit_reentrancy_guard(&env);
+        Ok(collateral_sold)
+    }
+
+    /// Close the loan behind a finished auction: leftover collateral goes
+    /// back to the borrower, and unrecovered principal is written off
+    /// against the bad-debt reserve, then against depositors
+    fn settle_auction(env: &Env, auction: &Auction) -> Result<(), LendingError> {
+        let contract_id = env.current_contract_address();
+        if auction.collateral_remaining > 0 {
+            Self::transfer(
+                env,
+                &auction.collateral_token,
+                &contract_id,
+                &auction.borrower,
+                auction.collateral_remaining,
+            )?;
+        }
+
+        let bad_debt = auction.principal_remaining;
+        let mut pool = Self::get_pool(env);
+        pool.total_borrowed = pool.total_borrowed.saturating_sub(bad_debt);
+        let from_reserve = bad_debt.min(pool.bad_debt_reserve);
+        pool.bad_debt_reserve -= from_reserve;
+        pool.total_deposits = pool.total_deposits.saturating_sub(bad_debt - from_reserve);
+        Self::set_pool(env, &pool);
+
+        let storage = env.storage().persistent();
+        storage.remove(&DataKey::Auction(auction.borrower.clone()));
+        storage.remove(&DataKey::Loan(auction.borrower.clone()));
+        storage.remove(&DataKey::LoanById(auction.loan_id));
+        storage.remove(&DataKey::LateFeesAccrued(auction.loan_id));
+        if let Some(nft_token) = Self::get_nft_token(env) {
+            LoanNFTClient::new(env, &nft_token).burn(&auction.loan_id);
+        }
+
+        env.events().publish(
+            (symbol_short!("AUCTION"), symbol_short!("SETTLE")),
+            AuctionSettledEvent {
+                loan_id: auction.loan_id,
+                borrower: auction.borrower.clone(),
+                collateral_returned: auction.collateral_remaining,
+                bad_debt,
+            },
+        );
+        Ok(())
+    }
 }
 
 mod test;
@@ -1321,3 +1321,580 @@ fn test_multiple_loans_grace_period() {
     assert!(client.is_in_grace_period(&borrower2));
     assert_eq!(client.calculate_late_fee(&borrower2), 0u64);
 }
+
+// ─────────────────────────────────────────────────
+// Price oracle
+// ─────────────────────────────────────────────────
+
+fn setup_oracle(env: &Env, admin: &Address) -> price_oracle::PriceOracleClient<'static> {
+    let oracle_id = env.register_contract(None, price_oracle::PriceOracle);
+    let oracle = price_oracle::PriceOracleClient::new(env, &oracle_id);
+    oracle.initialize(
+        admin,
+        &price_oracle::OracleConfig {
+            decimals: 7,
+            heartbeat_secs: 3600,
+            max_deviation_bps: 1000,
+        },
+    );
+    oracle
+}
+
+fn push_price(
+    env: &Env,
+    oracle: &price_oracle::PriceOracleClient,
+    admin: &Address,
+    token: &Address,
+    price: i128,
+) {
+    oracle.force_price(
+        admin,
+        &price_oracle::Asset::Stellar(token.clone()),
+        &price,
+        &env.ledger().timestamp(),
+    );
+}
+
+#[test]
+fn test_borrow_values_collateral_with_oracle() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000_000);
+    let (client, token_addr, collateral_addr, admin) = setup(&env);
+    let oracle = setup_oracle(&env, &admin);
+    client.set_price_oracle(&admin, &oracle.address);
+    assert_eq!(client.get_price_oracle(), Some(oracle.address.clone()));
+
+    let depositor = Address::generate(&env);
+    let borrower = Address::generate(&env);
+    mint_to(&env, &token_addr, &depositor, 100_000);
+    mint_to(&env, &collateral_addr, &borrower, 100_000);
+    client.deposit(&depositor, &10_000u64);
+
+    // No collateral price yet
+    let result = client.try_borrow(&borrower, &1_000u64, &collateral_addr, &1_500u64, &86_400);
+    assert_eq!(result, Err(Ok(LendingError::PriceUnavailable)));
+
+    // Collateral worth twice the pool token: 750 covers 150% of 1000
+    push_price(&env, &oracle, &admin, &token_addr, 10_000_000);
+    push_price(&env, &oracle, &admin, &collateral_addr, 20_000_000);
+    let result = client.try_borrow(&borrower, &1_000u64, &collateral_addr, &749u64, &86_400);
+    assert_eq!(result, Err(Ok(LendingError::InsufficientCollateral)));
+    client.borrow(&borrower, &1_000u64, &collateral_addr, &750u64, &86_400);
+
+    // Stale prices block new borrows
+    let other = Address::generate(&env);
+    mint_to(&env, &collateral_addr, &other, 100_000);
+    env.ledger().set_timestamp(1_000_000 + 3_601);
+    let result = client.try_borrow(&other, &1_000u64, &collateral_addr, &750u64, &86_400);
+    assert_eq!(result, Err(Ok(LendingError::PriceUnavailable)));
+}
+
+#[test]
+fn test_liquidation_uses_oracle_prices() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000_000);
+    let (client, token_addr, collateral_addr, admin) = setup(&env);
+    let oracle = setup_oracle(&env, &admin);
+    client.set_price_oracle(&admin, &oracle.address);
+    push_price(&env, &oracle, &admin, &token_addr, 10_000_000);
+    push_price(&env, &oracle, &admin, &collateral_addr, 20_000_000);
+
+    let depositor = Address::generate(&env);
+    let borrower = Address::generate(&env);
+    let liquidator = Address::generate(&env);
+    mint_to(&env, &token_addr, &depositor, 100_000);
+    mint_to(&env, &token_addr, &liquidator, 100_000);
+    mint_to(&env, &collateral_addr, &borrower, 100_000);
+    client.deposit(&depositor, &10_000u64);
+    client.borrow(&borrower, &1_000u64, &collateral_addr, &1_000u64, &86_400);
+
+    // Past the grace period with collateral still at 200% of debt
+    env.ledger().set_timestamp(1_000_000 + 5 * 86_400);
+    push_price(&env, &oracle, &admin, &token_addr, 10_000_000);
+    push_price(&env, &oracle, &admin, &collateral_addr, 20_000_000);
+    let result = client.try_liquidate(&liquidator, &borrower, &500u64);
+    assert_eq!(result, Err(Ok(LendingError::InvalidAmount)));
+
+    // Collateral falls to 1.2x: health factor 120% is below the 150% threshold,
+    // and the 750 of debt value seized converts to 625 collateral tokens
+    env.ledger().set_timestamp(1_000_000 + 5 * 86_400 + 60);
+    push_price(&env, &oracle, &admin, &collateral_addr, 12_000_000);
+    let seized = client.liquidate(&liquidator, &borrower, &500u64);
+    assert_eq!(seized, 625);
+    assert_eq!(tok_client(&env, &collateral_addr).balance(&liquidator), 625);
+}
+
+// ─────────────────────────────────────────────────
+// Insurance fund
+// ─────────────────────────────────────────────────
+
+#[test]
+fn test_repay_routes_premium_to_insurance_fund() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, collateral_addr, admin) = setup(&env);
+
+    let fund_id = env.register_contract(None, insurance_fund::InsuranceFund);
+    let fund = insurance_fund::InsuranceFundClient::new(&env, &fund_id);
+    fund.initialize(
+        &admin,
+        &insurance_fund::FundConfig {
+            token: token_addr.clone(),
+            approvals_required: 2,
+        },
+    );
+    fund.add_premium_source(&admin, &client.address);
+    assert_eq!(
+        client.try_set_insurance_fund(&admin, &fund_id, &10_001u32),
+        Err(Ok(LendingError::InvalidAmount))
+    );
+    client.set_insurance_fund(&admin, &fund_id, &4000u32);
+
+    let depositor = Address::generate(&env);
+    let borrower = Address::generate(&env);
+    mint_to(&env, &collateral_addr, &borrower, 100_000);
+    mint_to(&env, &token_addr, &depositor, 100_000);
+    mint_to(&env, &token_addr, &borrower, 100_000);
+    client.deposit(&depositor, &10_000u64);
+    client.borrow(
+        &borrower,
+        &5_000u64,
+        &collateral_addr,
+        &7500u64,
+        &(365 * 24 * 60 * 60),
+    );
+    env.ledger()
+        .set_timestamp(env.ledger().timestamp() + 31_536_000);
+
+    // 750 interest: 75 protocol share, 40% of it (30) to the fund
+    assert_eq!(client.repay(&borrower), 5_750);
+    let pool = client.get_pool_state();
+    assert_eq!(pool.total_deposits, 10_675);
+    assert_eq!(pool.bad_debt_reserve + pool.retained_yield, 45);
+    assert_eq!(tok_client(&env, &token_addr).balance(&fund_id), 30);
+    assert_eq!(fund.get_metrics().total_premiums, 30);
+}
+
+// ─────────────────────────────────────────────────
+// Liquidation auctions
+// ─────────────────────────────────────────────────
+
+/// A 1000-token loan against 1000 collateral, past its grace period with
+/// collateral at 1.2x the pool token (120% health factor)
+fn setup_auction(
+    env: &Env,
+) -> (
+    LendingContractClient<'_>,
+    Address,
+    Address,
+    Address,
+    Address,
+    price_oracle::PriceOracleClient<'static>,
+) {
+    env.ledger().set_timestamp(1_000_000);
+    let (client, token_addr, collateral_addr, admin) = setup(env);
+    let oracle = setup_oracle(env, &admin);
+    client.set_price_oracle(&admin, &oracle.address);
+    push_price(env, &oracle, &admin, &token_addr, 10_000_000);
+    push_price(env, &oracle, &admin, &collateral_addr, 20_000_000);
+
+    let depositor = Address::generate(env);
+    let borrower = Address::generate(env);
+    mint_to(env, &token_addr, &depositor, 100_000);
+    mint_to(env, &collateral_addr, &borrower, 100_000);
+    client.deposit(&depositor, &10_000u64);
+    client.borrow(&borrower, &1_000u64, &collateral_addr, &1_000u64, &86_400);
+
+    env.ledger().set_timestamp(1_000_000 + 5 * 86_400);
+    push_price(env, &oracle, &admin, &token_addr, 10_000_000);
+    push_price(env, &oracle, &admin, &collateral_addr, 12_000_000);
+    client.set_auction_config(
+        &admin,
+        &Some(AuctionConfig {
+            duration_seconds: 1_000,
+            start_premium_bps: 10_000,
+            floor_bps: 5_000,
+        }),
+    );
+    (client, token_addr, collateral_addr, admin, borrower, oracle)
+}
+
+#[test]
+fn test_auction_config_validated_and_replaces_fixed_liquidation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, collateral_addr, admin, borrower, _) = setup_auction(&env);
+
+    let invalid = AuctionConfig {
+        duration_seconds: 1_000,
+        start_premium_bps: 8_000,
+        floor_bps: 9_000,
+    };
+    assert_eq!(
+        client.try_set_auction_config(&admin, &Some(invalid)),
+        Err(Ok(LendingError::InvalidAmount))
+    );
+
+    let liquidator = Address::generate(&env);
+    assert_eq!(
+        client.try_liquidate(&liquidator, &borrower, &500u64),
+        Err(Ok(LendingError::AuctionModeActive))
+    );
+
+    // Healthy loans cannot be auctioned
+    let healthy = Address::generate(&env);
+    mint_to(&env, &collateral_addr, &healthy, 100_000);
+    client.borrow(&healthy, &100u64, &collateral_addr, &1_000u64, &86_400);
+    assert_eq!(
+        client.try_start_auction(&liquidator, &healthy),
+        Err(Ok(LendingError::NotLiquidatable))
+    );
+
+    client.start_auction(&liquidator, &borrower);
+    assert_eq!(
+        client.try_start_auction(&liquidator, &borrower),
+        Err(Ok(LendingError::AuctionExists))
+    );
+    assert_eq!(
+        client.try_repay(&borrower),
+        Err(Ok(LendingError::AuctionExists))
+    );
+
+    client.set_auction_config(&admin, &None);
+    assert_eq!(client.get_auction_config(), None);
+}
+
+#[test]
+fn test_auction_price_decays_to_floor() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, _, borrower, _) = setup_auction(&env);
+    let keeper = Address::generate(&env);
+    client.start_auction(&keeper, &borrower);
+    let start = env.ledger().timestamp();
+
+    assert_eq!(client.get_auction_price_bps(&borrower), 10_000);
+    env.ledger().set_timestamp(start + 500);
+    assert_eq!(client.get_auction_price_bps(&borrower), 7_500);
+    env.ledger().set_timestamp(start + 5_000);
+    assert_eq!(client.get_auction_price_bps(&borrower), 5_000);
+}
+
+#[test]
+fn test_auction_partial_fills_return_leftover_collateral() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, collateral_addr, _, borrower, _) = setup_auction(&env);
+    let liquidator = Address::generate(&env);
+    mint_to(&env, &token_addr, &liquidator, 100_000);
+
+    let auction = client.start_auction(&liquidator, &borrower);
+    let debt = auction.debt_remaining;
+    assert!(debt >= 1_000);
+
+    // At full price 300 of debt buys 300 / 1.2 = 250 collateral
+    assert_eq!(client.bid(&liquidator, &borrower, &300u64), 250);
+    let auction = client.get_auction(&borrower).unwrap();
+    assert_eq!(auction.debt_remaining, debt - 300);
+    assert_eq!(auction.collateral_remaining, 750);
+
+    // The rest of the debt clears the auction with collateral to spare
+    let sold = client.bid(&liquidator, &borrower, &u64::MAX);
+    assert_eq!(sold, ((debt - 300) as u128 * 10 / 12) as u64);
+    assert_eq!(client.get_auction(&borrower), None);
+    assert_eq!(client.get_loan(&borrower), None);
+
+    let collateral = tok_client(&env, &collateral_addr);
+    assert_eq!(collateral.balance(&liquidator), (250 + sold) as i128);
+    assert_eq!(
+        collateral.balance(&borrower),
+        100_000 - (250 + sold) as i128
+    );
+    assert_eq!(client.get_pool_state().total_borrowed, 0);
+}
+
+#[test]
+fn test_auction_shortfall_written_off_as_bad_debt() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, collateral_addr, admin, borrower, oracle) = setup_auction(&env);
+    let liquidator = Address::generate(&env);
+    mint_to(&env, &token_addr, &liquidator, 100_000);
+
+    // Collateral crashes to 0.5x: all 1000 of it fetches at most 500
+    env.ledger().set_timestamp(env.ledger().timestamp() + 60);
+    push_price(&env, &oracle, &admin, &token_addr, 10_000_000);
+    push_price(&env, &oracle, &admin, &collateral_addr, 5_000_000);
+    client.start_auction(&liquidator, &borrower);
+    let deposits_before = client.get_pool_state().total_deposits;
+
+    assert_eq!(client.bid(&liquidator, &borrower, &u64::MAX), 1_000);
+    assert_eq!(client.get_auction(&borrower), None);
+    assert_eq!(tok_client(&env, &token_addr).balance(&liquidator), 99_500);
+
+    // The 500 of principal left unpaid comes out of depositors' funds
+    let pool = client.get_pool_state();
+    assert_eq!(pool.total_borrowed, 0);
+    assert_eq!(pool.total_deposits, deposits_before - 500);
+}
+
+#[test]
+fn test_auction_bids_use_the_live_oracle_price() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, collateral_addr, admin, borrower, oracle) = setup_auction(&env);
+    let liquidator = Address::generate(&env);
+    mint_to(&env, &token_addr, &liquidator, 100_000);
+    client.start_auction(&liquidator, &borrower);
+
+    // Collateral falls from 1.2 to 0.6 after the auction started. Priced at
+    // the start, 300 of debt would buy collateral now worth 150 and nobody
+    // would bid; at the live price it buys 500.
+    env.ledger().set_timestamp(env.ledger().timestamp() + 1);
+    push_price(&env, &oracle, &admin, &token_addr, 10_000_000);
+    push_price(&env, &oracle, &admin, &collateral_addr, 6_000_000);
+    assert_eq!(client.bid(&liquidator, &borrower, &300u64), 500);
+    let auction = client.get_auction(&borrower).unwrap();
+    assert_eq!(auction.collateral_price, 6_000_000);
+    assert_eq!(auction.collateral_remaining, 500);
+}
+
+#[test]
+fn test_unsold_auction_restarts_after_its_duration() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, collateral_addr, admin, borrower, oracle) = setup_auction(&env);
+    let keeper = Address::generate(&env);
+    let started = client.start_auction(&keeper, &borrower).started_at;
+
+    assert_eq!(
+        client.try_restart_auction(&keeper, &borrower),
+        Err(Ok(LendingError::AuctionRunning))
+    );
+
+    // Nobody bid before the price bottomed out; anyone can start it over
+    env.ledger().set_timestamp(started + 1_000);
+    push_price(&env, &oracle, &admin, &token_addr, 10_000_000);
+    push_price(&env, &oracle, &admin, &collateral_addr, 8_000_000);
+    assert_eq!(client.get_auction_price_bps(&borrower), 5_000);
+    let auction = client.restart_auction(&keeper, &borrower);
+    assert_eq!(auction.started_at, started + 1_000);
+    assert_eq!(auction.collateral_price, 8_000_000);
+    assert_eq!(client.get_auction_price_bps(&borrower), 10_000);
+
+    // Floors at or above the oracle value could leave collateral unsold
+    let config = AuctionConfig {
+        duration_seconds: 1_000,
+        start_premium_bps: 11_000,
+        floor_bps: 10_000,
+    };
+    assert_eq!(
+        client.try_set_auction_config(&admin, &Some(config)),
+        Err(Ok(LendingError::InvalidAmount))
+    );
+}
+
+// ─── Reputation attestations ─────────────────────────
+
+fn reputation_signer(
+    env: &Env,
+    client: &LendingContractClient,
+    admin: &Address,
+) -> ed25519_dalek::SigningKey {
+    let key = ed25519_dalek::SigningKey::from_bytes(&[7u8; 32]);
+    let public = BytesN::from_array(env, &key.verifying_key().to_bytes());
+    client.set_reputation_signer(admin, &Some(public));
+    key
+}
+
+fn sign_attestation(
+    env: &Env,
+    key: &ed25519_dalek::SigningKey,
+    attestation: &ReputationAttestation,
+) -> BytesN<64> {
+    use ed25519_dalek::Signer;
+    let digest = env.crypto().sha256(&attestation.clone().to_xdr(env));
+    BytesN::from_array(env, &key.sign(&digest.to_array()).to_bytes())
+}
+
+#[test]
+fn test_attested_borrower_needs_less_collateral() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, collateral_addr, admin) = setup(&env);
+    let key = reputation_signer(&env, &client, &admin);
+
+    let depositor = Address::generate(&env);
+    let borrower = Address::generate(&env);
+    mint_to(&env, &token_addr, &depositor, 10_000);
+    mint_to(&env, &collateral_addr, &borrower, 10_000);
+    client.deposit(&depositor, &2000u64);
+
+    // 500 of collateral falls short of the pool's 150% for 400
+    let duration = 30 * 24 * 60 * 60;
+    assert_eq!(
+        client.try_borrow(&borrower, &400u64, &collateral_addr, &500u64, &duration),
+        Err(Ok(LendingError::InsufficientCollateral))
+    );
+
+    let attestation = ReputationAttestation {
+        borrower: borrower.clone(),
+        lending_contract: client.address.clone(),
+        score: 820,
+        collateral_ratio_bps: 12500,
+        expires_at: env.ledger().timestamp() + 3600,
+        issued_at: env.ledger().timestamp(),
+    };
+    let signature = sign_attestation(&env, &key, &attestation);
+    client.submit_reputation(&attestation, &signature);
+    assert_eq!(client.get_reputation(&borrower), Some(attestation));
+    assert_eq!(client.get_borrower_collateral_ratio(&borrower), 12500);
+
+    assert!(client.borrow(&borrower, &400u64, &collateral_addr, &500u64, &duration) > 0);
+}
+
+#[test]
+fn test_expired_attestation_falls_back_to_pool_ratio() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _token_addr, _collateral_addr, admin) = setup(&env);
+    let key = reputation_signer(&env, &client, &admin);
+    let borrower = Address::generate(&env);
+
+    let attestation = ReputationAttestation {
+        borrower: borrower.clone(),
+        lending_contract: client.address.clone(),
+        score: 700,
+        collateral_ratio_bps: 13500,
+        expires_at: env.ledger().timestamp() + 3600,
+        issued_at: env.ledger().timestamp(),
+    };
+    client.submit_reputation(&attestation, &sign_attestation(&env, &key, &attestation));
+    assert_eq!(client.get_borrower_collateral_ratio(&borrower), 13500);
+
+    env.ledger().set_timestamp(env.ledger().timestamp() + 3600);
+    assert_eq!(client.get_borrower_collateral_ratio(&borrower), 15000);
+
+    // Already expired attestations are refused outright
+    assert_eq!(
+        client.try_submit_reputation(&attestation, &sign_attestation(&env, &key, &attestation)),
+        Err(Ok(LendingError::AttestationExpired))
+    );
+
+    // Revoking the signer drops every attested ratio
+    let renewed = ReputationAttestation {
+        expires_at: env.ledger().timestamp() + 3600,
+        issued_at: env.ledger().timestamp(),
+        ..attestation
+    };
+    client.submit_reputation(&renewed, &sign_attestation(&env, &key, &renewed));
+    client.set_reputation_signer(&admin, &None);
+    assert_eq!(client.get_borrower_collateral_ratio(&borrower), 15000);
+}
+
+#[test]
+fn test_older_attestation_cannot_be_replayed() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _token_addr, _collateral_addr, admin) = setup(&env);
+    let key = reputation_signer(&env, &client, &admin);
+    let borrower = Address::generate(&env);
+
+    let good = ReputationAttestation {
+        borrower: borrower.clone(),
+        lending_contract: client.address.clone(),
+        score: 900,
+        collateral_ratio_bps: 11000,
+        expires_at: env.ledger().timestamp() + 30 * 86_400,
+        issued_at: env.ledger().timestamp(),
+    };
+    let good_signature = sign_attestation(&env, &key, &good);
+    client.submit_reputation(&good, &good_signature);
+
+    // The borrower's score drops and a worse attestation replaces it
+    env.ledger()
+        .set_timestamp(env.ledger().timestamp() + 86_400);
+    let worse = ReputationAttestation {
+        score: 500,
+        collateral_ratio_bps: 14000,
+        expires_at: env.ledger().timestamp() + 30 * 86_400,
+        issued_at: env.ledger().timestamp(),
+        ..good.clone()
+    };
+    client.submit_reputation(&worse, &sign_attestation(&env, &key, &worse));
+    assert_eq!(client.get_borrower_collateral_ratio(&borrower), 14000);
+
+    // The earlier, better attestation is still unexpired and validly signed
+    assert_eq!(
+        client.try_submit_reputation(&good, &good_signature),
+        Err(Ok(LendingError::StaleAttestation))
+    );
+    assert_eq!(
+        client.try_submit_reputation(&worse, &sign_attestation(&env, &key, &worse)),
+        Err(Ok(LendingError::StaleAttestation))
+    );
+    assert_eq!(client.get_reputation(&borrower), Some(worse));
+}
+
+#[test]
+fn test_attestation_rejected_outside_bounds() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _token_addr, _collateral_addr, admin) = setup(&env);
+    let borrower = Address::generate(&env);
+    let attestation = ReputationAttestation {
+        borrower: borrower.clone(),
+        lending_contract: client.address.clone(),
+        score: 990,
+        collateral_ratio_bps: 12500,
+        expires_at: env.ledger().timestamp() + 3600,
+        issued_at: env.ledger().timestamp(),
+    };
+    let unsigned = BytesN::from_array(&env, &[0u8; 64]);
+
+    // No signer configured
+    assert_eq!(
+        client.try_submit_reputation(&attestation, &unsigned),
+        Err(Ok(LendingError::InvalidAttestation))
+    );
+
+    let key = reputation_signer(&env, &client, &admin);
+    let too_low = ReputationAttestation {
+        collateral_ratio_bps: 10000,
+        ..attestation.clone()
+    };
+    assert_eq!(
+        client.try_submit_reputation(&too_low, &sign_attestation(&env, &key, &too_low)),
+        Err(Ok(LendingError::InvalidAttestation))
+    );
+
+    let other_pool = ReputationAttestation {
+        lending_contract: Address::generate(&env),
+        ..attestation.clone()
+    };
+    assert_eq!(
+        client.try_submit_reputation(&other_pool, &sign_attestation(&env, &key, &other_pool)),
+        Err(Ok(LendingError::InvalidAttestation))
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_attestation_with_bad_signature_panics() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _token_addr, _collateral_addr, admin) = setup(&env);
+    reputation_signer(&env, &client, &admin);
+    let attestation = ReputationAttestation {
+        borrower: Address::generate(&env),
+        lending_contract: client.address.clone(),
+        score: 900,
+        collateral_ratio_bps: 11000,
+        expires_at: env.ledger().timestamp() + 3600,
+        issued_at: env.ledger().timestamp(),
+    };
+    let forger = ed25519_dalek::SigningKey::from_bytes(&[9u8; 32]);
+    client.submit_reputation(&attestation, &sign_attestation(&env, &forger, &attestation));
+}
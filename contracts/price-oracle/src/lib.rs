@@ -0,0 +1,299 @@
+#![no_std]
+//! On-chain price oracle fed by whitelisted signers.
+//!
+//! Exposes the SEP-40 read interface (`decimals`, `lastprice`) plus a checked
+//! `price` that fails once a price is older than the heartbeat. Signer
+//! updates may move a fresh price by at most `max_deviation_bps`; the admin
+//! can force a larger move after a genuine market gap.
+
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, Symbol,
+};
+
+mod test;
+
+const BPS_DENOMINATOR: i128 = 10_000;
+
+// ─────────────────────────────────────────────────
+// Data Types
+// ─────────────────────────────────────────────────
+
+/// Asset identifier as defined by SEP-40
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Asset {
+    /// A Stellar asset, by its Stellar Asset Contract address
+    Stellar(Address),
+    /// Any other asset, by ticker
+    Other(Symbol),
+}
+
+/// A price scaled by `10^decimals`, as of `timestamp` (unix seconds)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OracleConfig {
+    pub decimals: u32,
+    /// Age after which `price` refuses to serve a value
+    pub heartbeat_secs: u64,
+    /// Largest move a signer may make from a fresh price
+    pub max_deviation_bps: u32,
+}
+
+// ─────────────────────────────────────────────────
+// Events
+// ─────────────────────────────────────────────────
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceUpdatedEvent {
+    pub asset: Asset,
+    pub price: i128,
+    pub timestamp: u64,
+    pub updated_by: Address,
+    pub forced: bool,
+}
+
+// ─────────────────────────────────────────────────
+// Errors
+// ─────────────────────────────────────────────────
+
+#[contracterror]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OracleError {
+    AlreadyInitialized = 1,
+    NotInitialized = 2,
+    Unauthorized = 3,
+    NotSigner = 4,
+    InvalidPrice = 5,
+    InvalidTimestamp = 6,
+    DeviationTooLarge = 7,
+    NoPrice = 8,
+    StalePrice = 9,
+    InvalidConfig = 10,
+}
+
+// ─────────────────────────────────────────────────
+// Storage Keys
+// ─────────────────────────────────────────────────
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Admin,
+    Config,
+    Signer(Address),
+    Price(Asset),
+}
+
+// ─────────────────────────────────────────────────
+// Contract
+// ─────────────────────────────────────────────────
+
+#[contract]
+pub struct PriceOracle;
+
+#[contractimpl]
+impl PriceOracle {
+    // ─── Admin / Init ───────────────────────────────
+
+    pub fn initialize(env: Env, admin: Address, config: OracleConfig) -> Result<(), OracleError> {
+        admin.require_auth();
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(OracleError::AlreadyInitialized);
+        }
+        Self::validate_config(&config)?;
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Config, &config);
+        Ok(())
+    }
+
+    pub fn set_config(env: Env, admin: Address, config: OracleConfig) -> Result<(), OracleError> {
+        Self::require_admin(&env, &admin)?;
+        Self::validate_config(&config)?;
+        env.storage().instance().set(&DataKey::Config, &config);
+        Ok(())
+    }
+
+    pub fn add_signer(env: Env, admin: Address, signer: Address) -> Result<(), OracleError> {
+        Self::require_admin(&env, &admin)?;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Signer(signer), &true);
+        Ok(())
+    }
+
+    pub fn remove_signer(env: Env, admin: Address, signer: Address) -> Result<(), OracleError> {
+        Self::require_admin(&env, &admin)?;
+        env.storage().persistent().remove(&DataKey::Signer(signer));
+        Ok(())
+    }
+
+    pub fn is_signer(env: Env, signer: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Signer(signer))
+            .unwrap_or(false)
+    }
+
+    pub fn config(env: Env) -> Result<OracleConfig, OracleError> {
+        Self::get_config(&env)
+    }
+
+    // ─── Updates ────────────────────────────────────
+
+    /// Record a signer's price. The timestamp must be newer than the stored
+    /// one, not in the future and within the heartbeat; against a fresh
+    /// price the move must stay within `max_deviation_bps`.
+    pub fn set_price(
+        env: Env,
+        signer: Address,
+        asset: Asset,
+        price: i128,
+        timestamp: u64,
+    ) -> Result<(), OracleError> {
+        signer.require_auth();
+        if !Self::is_signer(env.clone(), signer.clone()) {
+            return Err(OracleError::NotSigner);
+        }
+        let config = Self::get_config(&env)?;
+        let previous = Self::lastprice(env.clone(), asset.clone());
+        Self::check_update(&env, &config, previous.as_ref(), price, timestamp)?;
+
+        if let Some(previous) = previous.filter(|p| Self::is_fresh(&env, &config, p)) {
+            let moved = (price - previous.price).abs();
+            let allowed = previous
+                .price
+                .checked_mul(config.max_deviation_bps as i128)
+                .ok_or(OracleError::InvalidPrice)?
+                / BPS_DENOMINATOR;
+            if moved > allowed {
+                return Err(OracleError::DeviationTooLarge);
+            }
+        }
+
+        Self::store(&env, asset, price, timestamp, signer, false);
+        Ok(())
+    }
+
+    /// Record a price without the deviation rule (admin only)
+    pub fn force_price(
+        env: Env,
+        admin: Address,
+        asset: Asset,
+        price: i128,
+        timestamp: u64,
+    ) -> Result<(), OracleError> {
+        Self::require_admin(&env, &admin)?;
+        let config = Self::get_config(&env)?;
+        let previous = Self::lastprice(env.clone(), asset.clone());
+        Self::check_update(&env, &config, previous.as_ref(), price, timestamp)?;
+        Self::store(&env, asset, price, timestamp, admin, true);
+        Ok(())
+    }
+
+    // ─── Reads ──────────────────────────────────────
+
+    /// Number of decimal places prices are scaled by (SEP-40)
+    pub fn decimals(env: Env) -> Result<u32, OracleError> {
+        Ok(Self::get_config(&env)?.decimals)
+    }
+
+    /// Most recent price regardless of age (SEP-40)
+    pub fn lastprice(env: Env, asset: Asset) -> Option<PriceData> {
+        env.storage().persistent().get(&DataKey::Price(asset))
+    }
+
+    /// Most recent price, failing if none is recorded or it has gone stale
+    pub fn price(env: Env, asset: Asset) -> Result<PriceData, OracleError> {
+        let config = Self::get_config(&env)?;
+        let data = Self::lastprice(env.clone(), asset).ok_or(OracleError::NoPrice)?;
+        if !Self::is_fresh(&env, &config, &data) {
+            return Err(OracleError::StalePrice);
+        }
+        Ok(data)
+    }
+
+    // ─── Internal ───────────────────────────────────
+
+    fn validate_config(config: &OracleConfig) -> Result<(), OracleError> {
+        if config.decimals > 18 || config.heartbeat_secs == 0 || config.max_deviation_bps == 0 {
+            return Err(OracleError::InvalidConfig);
+        }
+        Ok(())
+    }
+
+    fn get_config(env: &Env) -> Result<OracleConfig, OracleError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(OracleError::NotInitialized)
+    }
+
+    fn require_admin(env: &Env, caller: &Address) -> Result<(), OracleError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(OracleError::NotInitialized)?;
+        if *caller != admin {
+            return Err(OracleError::Unauthorized);
+        }
+        caller.require_auth();
+        Ok(())
+    }
+
+    fn is_fresh(env: &Env, config: &OracleConfig, data: &PriceData) -> bool {
+        env.ledger().timestamp().saturating_sub(data.timestamp) <= config.heartbeat_secs
+    }
+
+    fn check_update(
+        env: &Env,
+        config: &OracleConfig,
+        previous: Option<&PriceData>,
+        price: i128,
+        timestamp: u64,
+    ) -> Result<(), OracleError> {
+        if price <= 0 {
+            return Err(OracleError::InvalidPrice);
+        }
+        let now = env.ledger().timestamp();
+        if timestamp > now || now - timestamp > config.heartbeat_secs {
+            return Err(OracleError::InvalidTimestamp);
+        }
+        if previous.is_some_and(|p| timestamp <= p.timestamp) {
+            return Err(OracleError::InvalidTimestamp);
+        }
+        Ok(())
+    }
+
+    fn store(
+        env: &Env,
+        asset: Asset,
+        price: i128,
+        timestamp: u64,
+        updated_by: Address,
+        forced: bool,
+    ) {
+        env.storage().persistent().set(
+            &DataKey::Price(asset.clone()),
+            &PriceData { price, timestamp },
+        );
+        env.events().publish(
+            (symbol_short!("ORACLE"), symbol_short!("PRICE")),
+            PriceUpdatedEvent {
+                asset,
+                price,
+                timestamp,
+                updated_by,
+                forced,
+            },
+        );
+    }
+}
@@ -0,0 +1,139 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{symbol_short, Address, Env};
+
+const NOW: u64 = 1_760_000_000;
+
+fn setup(env: &Env) -> (PriceOracleClient<'_>, Address, Address) {
+    env.mock_all_auths();
+    env.ledger().with_mut(|l| l.timestamp = NOW);
+    let admin = Address::generate(env);
+    let signer = Address::generate(env);
+    let contract_id = env.register_contract(None, PriceOracle);
+    let client = PriceOracleClient::new(env, &contract_id);
+    client.initialize(
+        &admin,
+        &OracleConfig {
+            decimals: 14,
+            heartbeat_secs: 600,
+            max_deviation_bps: 1000,
+        },
+    );
+    client.add_signer(&admin, &signer);
+    (client, admin, signer)
+}
+
+fn xlm() -> Asset {
+    Asset::Other(symbol_short!("XLM"))
+}
+
+#[test]
+fn test_signer_sets_and_reads_price() {
+    let env = Env::default();
+    let (client, _, signer) = setup(&env);
+
+    assert_eq!(client.decimals(), 14);
+    assert_eq!(client.lastprice(&xlm()), None);
+
+    client.set_price(&signer, &xlm(), &12_000_000_000_000, &(NOW - 5));
+    let data = client.price(&xlm());
+    assert_eq!(data.price, 12_000_000_000_000);
+    assert_eq!(data.timestamp, NOW - 5);
+    assert_eq!(client.lastprice(&xlm()), Some(data));
+}
+
+#[test]
+fn test_rejects_unlisted_signer() {
+    let env = Env::default();
+    let (client, admin, signer) = setup(&env);
+    let stranger = Address::generate(&env);
+
+    assert_eq!(
+        client.try_set_price(&stranger, &xlm(), &100, &NOW),
+        Err(Ok(OracleError::NotSigner))
+    );
+
+    client.remove_signer(&admin, &signer);
+    assert!(!client.is_signer(&signer));
+    assert_eq!(
+        client.try_set_price(&signer, &xlm(), &100, &NOW),
+        Err(Ok(OracleError::NotSigner))
+    );
+}
+
+#[test]
+fn test_enforces_timestamps() {
+    let env = Env::default();
+    let (client, _, signer) = setup(&env);
+
+    // Future and already-stale timestamps
+    assert_eq!(
+        client.try_set_price(&signer, &xlm(), &100, &(NOW + 1)),
+        Err(Ok(OracleError::InvalidTimestamp))
+    );
+    assert_eq!(
+        client.try_set_price(&signer, &xlm(), &100, &(NOW - 601)),
+        Err(Ok(OracleError::InvalidTimestamp))
+    );
+
+    // Replays of an older observation
+    client.set_price(&signer, &xlm(), &100, &NOW);
+    assert_eq!(
+        client.try_set_price(&signer, &xlm(), &101, &NOW),
+        Err(Ok(OracleError::InvalidTimestamp))
+    );
+}
+
+#[test]
+fn test_deviation_limit_and_admin_override() {
+    let env = Env::default();
+    let (client, admin, signer) = setup(&env);
+
+    client.set_price(&signer, &xlm(), &1_000, &(NOW - 10));
+    // 10% is allowed, 20% is not
+    client.set_price(&signer, &xlm(), &1_100, &(NOW - 5));
+    assert_eq!(
+        client.try_set_price(&signer, &xlm(), &1_320, &NOW),
+        Err(Ok(OracleError::DeviationTooLarge))
+    );
+
+    client.force_price(&admin, &xlm(), &1_320, &NOW);
+    assert_eq!(client.price(&xlm()).price, 1_320);
+}
+
+#[test]
+fn test_heartbeat_expires_price() {
+    let env = Env::default();
+    let (client, _, signer) = setup(&env);
+
+    client.set_price(&signer, &xlm(), &1_000, &NOW);
+    env.ledger().with_mut(|l| l.timestamp = NOW + 601);
+    assert_eq!(client.try_price(&xlm()), Err(Ok(OracleError::StalePrice)));
+    assert!(client.lastprice(&xlm()).is_some());
+
+    // Once stale, any move is accepted to recover
+    client.set_price(&signer, &xlm(), &5_000, &(NOW + 600));
+    assert_eq!(client.price(&xlm()).price, 5_000);
+}
+
+#[test]
+fn test_rejects_invalid_config_and_reinitialization() {
+    let env = Env::default();
+    let (client, admin, _) = setup(&env);
+
+    let bad = OracleConfig {
+        decimals: 14,
+        heartbeat_secs: 0,
+        max_deviation_bps: 100,
+    };
+    assert_eq!(
+        client.try_set_config(&admin, &bad),
+        Err(Ok(OracleError::InvalidConfig))
+    );
+    assert_eq!(
+        client.try_initialize(&admin, &client.config()),
+        Err(Ok(OracleError::AlreadyInitialized))
+    );
+}